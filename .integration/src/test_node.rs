@@ -184,7 +184,7 @@ impl Reading for TestNode {
             ClientMessage::UnconfirmedTransaction(_transaction) => {}
             ClientMessage::PoolRegister(_address) => {}
             ClientMessage::PoolRequest(_share_difficulty, _block_template) => {}
-            ClientMessage::PoolResponse(_address, _nonce, _proof) => {}
+            ClientMessage::PoolResponse(_address, _nonce, _proof, _timestamp) => {}
             _ => return Err(io::ErrorKind::InvalidData.into()), // Peer is not following the protocol.
         }
 
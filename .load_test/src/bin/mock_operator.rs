@@ -0,0 +1,152 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Serves jobs to and receives shares from real provers, in place of the RPC server of an operator.
+//!
+//! Start a prover with `--pool-http http://<rpc>` and an unreachable `--pool` address, so that it falls back
+//! to fetching its jobs from this mock operator. The mock operator accepts every share without verifying it,
+//! so the rate of the prover's shares is bounded by the given share difficulty alone.
+
+use snarkos_environment::CurrentNetwork;
+use snarkos_load_test::{enable_tracing, spawn_reporter, LoadStats};
+use snarkos_network::helpers::{from_wire_hex, to_wire_hex, POOL_JOB_LONG_POLL_IN_SECS};
+use snarkos_storage::{storage::rocksdb::RocksDB, LedgerState};
+use snarkvm::dpc::{prelude::*, PoSWProof};
+
+use clap::Parser;
+use jsonrpsee::{
+    core::Error as JsonrpseeError,
+    http_server::{HttpServerBuilder, RpcModule},
+};
+use rand::thread_rng;
+use serde_json::{json, Value};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+#[derive(Debug, Parser)]
+#[clap(name = "mock-operator", about = "Serves jobs to provers in place of an operator")]
+struct Opts {
+    /// Specify the IP address and port for the RPC server.
+    #[clap(default_value = "0.0.0.0:3032", long = "rpc")]
+    rpc: SocketAddr,
+    /// Specify the path to an existing ledger, or a new ledger is initialized in a temporary directory.
+    #[clap(long = "ledger")]
+    ledger: Option<PathBuf>,
+    /// Specify the share difficulty of the jobs.
+    #[clap(default_value = "18446744073709551615", long = "share-difficulty")]
+    share_difficulty: u64,
+    /// Specify the interval between new jobs, in seconds.
+    #[clap(default_value = "10", long = "job-interval")]
+    job_interval: u64,
+    /// Specify the interval between the reports of the load test, in seconds.
+    #[clap(default_value = "5", long = "report-interval")]
+    report_interval: u64,
+}
+
+/// The current job of the mock operator := (share_difficulty, block_template).
+type Job = (u64, BlockTemplate<CurrentNetwork>);
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    enable_tracing();
+
+    let opts = Opts::parse();
+
+    // The resource of a ledger reader is held until the mock operator exits.
+    let (ledger, _ledger_resource) = match &opts.ledger {
+        Some(path) => {
+            let (ledger, resource) = LedgerState::<CurrentNetwork>::open_reader::<RocksDB, _>(path)?;
+            (ledger, Some(resource))
+        }
+        None => {
+            let path = std::env::temp_dir().join(format!("snarkos-mock-operator-{}", std::process::id()));
+            (Arc::new(LedgerState::<CurrentNetwork>::open_writer::<RocksDB, _>(path)?), None)
+        }
+    };
+    info!("Serving jobs from a ledger at block {}", ledger.latest_block_height());
+
+    // The block templates are built for a random recipient, as the mock operator never submits a block.
+    let recipient = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+    let new_job = move || -> anyhow::Result<Job> {
+        let block_template = ledger.get_block_template(recipient, true, &[], &mut thread_rng())?;
+        Ok((opts.share_difficulty, block_template))
+    };
+    let (job_sender, job_receiver) = watch::channel(new_job()?);
+
+    // Refresh the job at the given interval, releasing the long-polls of the provers.
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(opts.job_interval));
+        // The first tick completes immediately.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match new_job() {
+                Ok(job) => {
+                    let _ = job_sender.send(job);
+                }
+                Err(error) => warn!("Failed to build a new job: {}", error),
+            }
+        }
+    });
+
+    let stats = Arc::new(LoadStats::default());
+    spawn_reporter(stats.clone(), Duration::from_secs(opts.report_interval));
+
+    let mut module = RpcModule::new((job_receiver, stats));
+
+    module.register_async_method("getpooljob", |rpc_params, context| async move {
+        let (job_receiver, stats) = &*context;
+        let (_prover, known_block_height, _timestamp, _signature) =
+            rpc_params.parse::<(Address<CurrentNetwork>, Option<u32>, i64, <CurrentNetwork as Network>::AccountSignature)>()?;
+
+        // Hold the long-poll of a prover which already knows the current job, until a new job is available.
+        let mut job_receiver = job_receiver.clone();
+        if known_block_height == Some(job_receiver.borrow().1.block_height()) {
+            let timeout = Duration::from_secs(POOL_JOB_LONG_POLL_IN_SECS);
+            if tokio::time::timeout(timeout, job_receiver.changed()).await.is_err() {
+                return Ok(Value::Null);
+            }
+        }
+
+        let (share_difficulty, block_template) = job_receiver.borrow().clone();
+        stats.record_job();
+        Ok(json!({
+            "share_difficulty": share_difficulty,
+            "block_height": block_template.block_height(),
+            "block_template": to_wire_hex(&block_template).map_err(|error| JsonrpseeError::Custom(error.to_string()))?,
+        }))
+    })?;
+
+    module.register_async_method("submitshare", |rpc_params, context| async move {
+        let (_, stats) = &*context;
+        let (_prover, nonce_hex, proof_hex, _timestamp) = rpc_params.parse::<(Address<CurrentNetwork>, String, String, i64)>()?;
+
+        // Decode the share, as the operator does, but accept it without verifying it.
+        let _nonce: <CurrentNetwork as Network>::PoSWNonce =
+            from_wire_hex(&nonce_hex).map_err(|error| JsonrpseeError::Custom(error.to_string()))?;
+        let _proof: PoSWProof<CurrentNetwork> = from_wire_hex(&proof_hex).map_err(|error| JsonrpseeError::Custom(error.to_string()))?;
+        stats.record_share();
+        Ok(true)
+    })?;
+
+    let server = HttpServerBuilder::new().build(opts.rpc)?;
+    info!("Mock operator listening on {}", server.local_addr()?);
+    let _server_handle = server.start(module)?;
+
+    std::future::pending::<()>().await;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Simulates many provers fetching jobs from and submitting shares to the RPC server of a real operator.
+//!
+//! Each simulated prover submits shares with a random nonce and the proof of the genesis block,
+//! so the operator rejects every share, after verifying it in full unless its share sampling skips it.
+
+use snarkos_environment::CurrentNetwork;
+use snarkos_load_test::{enable_tracing, spawn_reporter, LoadStats};
+use snarkos_network::helpers::PoolClient;
+use snarkvm::{dpc::prelude::*, prelude::UniformRand};
+
+use clap::Parser;
+use rand::thread_rng;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{info, warn};
+
+#[derive(Debug, Parser)]
+#[clap(name = "mock-provers", about = "Simulates provers submitting shares to an operator")]
+struct Opts {
+    /// Specify the URL of the RPC server of the operator.
+    #[clap(default_value = "http://127.0.0.1:3032", long = "operator")]
+    operator: String,
+    /// Specify the number of simulated provers.
+    #[clap(default_value = "1000", long = "provers")]
+    provers: u32,
+    /// Specify the number of shares submitted per second by each simulated prover.
+    #[clap(default_value = "1.0", long = "share-rate")]
+    share_rate: f64,
+    /// Specify the duration of the load test, in seconds.
+    #[clap(default_value = "60", long = "duration")]
+    duration: u64,
+    /// Specify the interval between the reports of the load test, in seconds.
+    #[clap(default_value = "5", long = "report-interval")]
+    report_interval: u64,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    enable_tracing();
+
+    let opts = Opts::parse();
+    if opts.share_rate <= 0.0 {
+        anyhow::bail!("The share rate must be positive");
+    }
+
+    let client = PoolClient::<CurrentNetwork>::new(opts.operator.clone())?;
+    let stats = Arc::new(LoadStats::default());
+    let share_interval = Duration::from_secs_f64(1.0 / opts.share_rate);
+
+    info!(
+        "Simulating {} provers at {} shares/s each against {}",
+        opts.provers, opts.share_rate, opts.operator
+    );
+
+    let reporter = spawn_reporter(stats.clone(), Duration::from_secs(opts.report_interval));
+    let started_at = Instant::now();
+    let provers: Vec<_> = (0..opts.provers)
+        .map(|_| {
+            let account = Account::<CurrentNetwork>::new(&mut thread_rng());
+            tokio::spawn(simulate_prover(client.clone(), account, share_interval, stats.clone()))
+        })
+        .collect();
+
+    tokio::time::sleep(Duration::from_secs(opts.duration)).await;
+    for prover in provers {
+        prover.abort();
+    }
+    reporter.abort();
+    stats.report(started_at);
+
+    Ok(())
+}
+
+///
+/// Fetches the jobs of the given prover in a long-poll loop, while submitting its shares at the given interval.
+///
+async fn simulate_prover(client: PoolClient<CurrentNetwork>, account: Account<CurrentNetwork>, interval: Duration, stats: Arc<LoadStats>) {
+    let address = account.address();
+    let proof = CurrentNetwork::genesis_block().header().proof().clone();
+
+    // Spread the first submissions of the provers over the interval, so that they do not arrive in bursts.
+    tokio::time::sleep(interval.mul_f64(rand::random::<f64>())).await;
+
+    let fetch_jobs = async {
+        let mut known_block_height = None;
+        loop {
+            match client.get_job(account.private_key(), known_block_height).await {
+                Ok(Some((_, block_template))) => {
+                    stats.record_job();
+                    known_block_height = Some(block_template.block_height());
+                }
+                Ok(None) => tokio::time::sleep(Duration::from_secs(1)).await,
+                Err(error) => {
+                    warn!("Failed to fetch a job: {}", error);
+                    stats.record_error();
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    };
+
+    let submit_shares = async {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let nonce = <CurrentNetwork as Network>::PoSWNonce::rand(&mut thread_rng());
+            let submitted_at = Instant::now();
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs() as i64);
+            match client.submit_share(address, nonce, timestamp, &proof).await {
+                Ok(()) => stats.record_submission(true, submitted_at.elapsed()),
+                // The operator rejects a share with an RPC error, which is distinguished from an unreachable operator.
+                Err(error) if error.to_string().starts_with("[submitshare]") => stats.record_submission(false, submitted_at.elapsed()),
+                Err(error) => {
+                    warn!("Failed to submit a share: {}", error);
+                    stats.record_error();
+                }
+            }
+        }
+    };
+
+    // Both loops run until the task of the simulated prover is aborted.
+    tokio::join!(fetch_jobs, submit_shares);
+}
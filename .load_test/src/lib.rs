@@ -0,0 +1,118 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// The counters of a load test, shared by all of its simulated provers or jobs.
+#[derive(Debug, Default)]
+pub struct LoadStats {
+    /// The number of jobs fetched or served.
+    jobs: AtomicU64,
+    /// The number of shares submitted or received.
+    shares: AtomicU64,
+    /// The number of shares accepted by the operator.
+    accepted: AtomicU64,
+    /// The number of shares rejected by the operator.
+    rejected: AtomicU64,
+    /// The number of requests that failed to reach the operator.
+    errors: AtomicU64,
+    /// The sum of the latencies of the share submissions, in microseconds.
+    latency_in_us: AtomicU64,
+}
+
+impl LoadStats {
+    /// Records a fetched or served job.
+    pub fn record_job(&self) {
+        self.jobs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a share received by the mock operator.
+    pub fn record_share(&self) {
+        self.shares.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a submitted share, whether it was accepted, and the latency of its submission.
+    pub fn record_submission(&self, is_accepted: bool, latency: Duration) {
+        self.shares.fetch_add(1, Ordering::Relaxed);
+        match is_accepted {
+            true => self.accepted.fetch_add(1, Ordering::Relaxed),
+            false => self.rejected.fetch_add(1, Ordering::Relaxed),
+        };
+        self.latency_in_us.fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Records a request that failed to reach the operator.
+    pub fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Logs the totals and rates of the counters since the given start of the load test.
+    pub fn report(&self, started_at: Instant) {
+        let elapsed = started_at.elapsed().as_secs_f64().max(f64::EPSILON);
+        let jobs = self.jobs.load(Ordering::Relaxed);
+        let shares = self.shares.load(Ordering::Relaxed);
+        let accepted = self.accepted.load(Ordering::Relaxed);
+        let rejected = self.rejected.load(Ordering::Relaxed);
+        let errors = self.errors.load(Ordering::Relaxed);
+        let submitted = accepted + rejected;
+        let mean_latency_in_ms = match submitted {
+            0 => 0.0,
+            _ => self.latency_in_us.load(Ordering::Relaxed) as f64 / submitted as f64 / 1000.0,
+        };
+
+        info!(
+            "{:.0}s: {} jobs ({:.1}/s), {} shares ({:.1}/s), {} accepted, {} rejected, {} errors, {:.1}ms mean share latency",
+            elapsed,
+            jobs,
+            jobs as f64 / elapsed,
+            shares,
+            shares as f64 / elapsed,
+            accepted,
+            rejected,
+            errors,
+            mean_latency_in_ms,
+        );
+    }
+}
+
+/// Spawns a task logging the given stats at the given interval.
+pub fn spawn_reporter(stats: Arc<LoadStats>, interval: Duration) -> tokio::task::JoinHandle<()> {
+    let started_at = Instant::now();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        // The first tick completes immediately.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            stats.report(started_at);
+        }
+    })
+}
+
+/// Enables the logging of the load test, filtered by `RUST_LOG` if it is set.
+pub fn enable_tracing() {
+    use tracing_subscriber::{filter::LevelFilter, fmt, EnvFilter};
+
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::default().add_directive(LevelFilter::INFO.into()));
+    fmt().with_env_filter(env_filter).init();
+}
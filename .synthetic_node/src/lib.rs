@@ -20,7 +20,7 @@ use snarkos_environment::{
     CurrentNetwork,
     Environment,
 };
-use snarkos_network::{Data, Message};
+use snarkos_network::{Data, Message, ServingMode};
 use snarkvm::traits::Network;
 
 use parking_lot::RwLock;
@@ -139,6 +139,8 @@ impl Handshake for SynthNode {
             own_ip.port(),
             self.state.local_nonce,
             0,
+            ServingMode::Full,
+            None,
         );
         trace!(parent: self.node().span(), "sending a challenge request to {}", peer_addr);
         let mut msg = Vec::new();
@@ -165,6 +167,8 @@ impl Handshake for SynthNode {
             peer_listening_port,
             peer_nonce,
             cumulative_weight,
+            _peer_serving_mode,
+            _peer_timestamp,
         )) = peer_request
         {
             // Don't reject peers due to the client version in order to keep track of non-compliant peers.
@@ -0,0 +1,102 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fmt, sync::RwLock};
+
+/// Parses the given filter directives, such as `info,snarkos_network=trace`, and applies them to the log output.
+pub type LogFilterReloader = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// The filter of the log output of the node, which may be changed while the node runs.
+#[derive(Default)]
+pub struct LogFilter {
+    /// The filter directives currently applied, and the reloader applying new ones, once the logger is initialized.
+    state: RwLock<Option<(String, LogFilterReloader)>>,
+}
+
+impl LogFilter {
+    /// Registers the reloader of the log output, and the filter directives it was initialized with.
+    pub fn initialize(&self, directives: String, reloader: LogFilterReloader) {
+        *self.state.write().expect("The log filter lock is never poisoned") = Some((directives, reloader));
+    }
+
+    /// Returns the filter directives currently applied, if the logger is initialized.
+    pub fn directives(&self) -> Option<String> {
+        self.state
+            .read()
+            .expect("The log filter lock is never poisoned")
+            .as_ref()
+            .map(|(directives, _)| directives.clone())
+    }
+
+    /// Applies the given filter directives to the log output, returning the directives they replace.
+    pub fn reload(&self, directives: &str) -> Result<String, String> {
+        let directives = directives.trim();
+        if directives.is_empty() {
+            return Err("The log filter must not be empty".to_string());
+        }
+
+        let mut state = self.state.write().expect("The log filter lock is never poisoned");
+        match state.as_mut() {
+            Some((current, reloader)) => {
+                reloader(directives)?;
+                Ok(std::mem::replace(current, directives.to_string()))
+            }
+            None => Err("The log output of the node cannot be reloaded".to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LogFilter").field("directives", &self.directives()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_log_filter_reload() {
+        let log_filter = LogFilter::default();
+        assert_eq!(log_filter.directives(), None);
+        assert!(log_filter.reload("debug").is_err());
+
+        // Initialize a reloader which records the directives it applies, and rejects unknown levels.
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let recorder = applied.clone();
+        log_filter.initialize(
+            "info".to_string(),
+            Box::new(move |directives| match directives.contains("verbose") {
+                true => Err(format!("Invalid log filter '{}'", directives)),
+                false => {
+                    recorder.lock().unwrap().push(directives.to_string());
+                    Ok(())
+                }
+            }),
+        );
+        assert_eq!(log_filter.directives(), Some("info".to_string()));
+
+        // Check the directives are replaced once applied, and kept if rejected.
+        assert_eq!(log_filter.reload(" info,snarkos_network=trace ").unwrap(), "info");
+        assert_eq!(log_filter.directives(), Some("info,snarkos_network=trace".to_string()));
+        assert!(log_filter.reload("verbose").is_err());
+        assert!(log_filter.reload("  ").is_err());
+        assert_eq!(log_filter.directives(), Some("info,snarkos_network=trace".to_string()));
+        assert_eq!(*applied.lock().unwrap(), vec!["info,snarkos_network=trace".to_string()]);
+    }
+}
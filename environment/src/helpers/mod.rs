@@ -14,11 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+mod log_filter;
+pub use log_filter::{LogFilter, LogFilterReloader};
+
 mod node_type;
 pub use node_type::NodeType;
 
+mod request_id;
+pub use request_id::next_request_id;
+
 mod resources;
 pub use resources::{Resource, Resources};
 
+mod secret;
+pub use secret::SecretString;
+
+mod shutdown;
+pub use shutdown::{Shutdown, ShutdownKind, RESTART_EXIT_CODE, STOP_EXIT_CODE};
+
 mod status;
 pub use status::{State, Status};
@@ -15,7 +15,7 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 #[repr(u8)]
@@ -33,6 +33,8 @@ pub enum NodeType {
     /// A proving node is a full node, capable of producing proofs for a pool.
     Prover,
     PoolServer,
+    /// An explorer node is a read-only node, serving the ledger synced by another node over RPC.
+    Explorer,
 }
 
 impl NodeType {
@@ -45,6 +47,7 @@ impl NodeType {
             Self::Operator => "an operating node",
             Self::Prover => "a proving node",
             Self::PoolServer => "a standalone operator node",
+            Self::Explorer => "an explorer node",
         }
     }
 }
@@ -54,3 +57,21 @@ impl fmt::Display for NodeType {
         write!(f, "{:?}", self)
     }
 }
+
+impl FromStr for NodeType {
+    type Err = String;
+
+    fn from_str(node_type: &str) -> Result<Self, Self::Err> {
+        match node_type.to_lowercase().as_str() {
+            "client" => Ok(Self::Client),
+            "miner" => Ok(Self::Miner),
+            "beacon" => Ok(Self::Beacon),
+            "sync" => Ok(Self::Sync),
+            "operator" => Ok(Self::Operator),
+            "prover" => Ok(Self::Prover),
+            "poolserver" => Ok(Self::PoolServer),
+            "explorer" => Ok(Self::Explorer),
+            _ => Err(format!("Unknown node type '{}'", node_type)),
+        }
+    }
+}
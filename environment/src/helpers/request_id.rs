@@ -0,0 +1,37 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+///
+/// Returns a new request ID, unique within the node, which correlates the tracing spans of a request.
+///
+pub fn next_request_id() -> u64 {
+    static REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+    REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_ids_are_unique() {
+        let first = next_request_id();
+        let second = next_request_id();
+        assert!(second > first);
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+/// A string holding secret material (e.g. a password or private key), which is zeroized
+/// on drop, is never revealed through its `Debug` or `Display` implementations, and is compared in constant time.
+/// Only the string itself is zeroized; a private key of snarkVM parsed from it is not zeroized on drop.
+#[derive(Clone, Default)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Initializes a new instance of `SecretString`.
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// Returns a reference to the underlying secret.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl FromStr for SecretString {
+    type Err = std::convert::Infallible;
+
+    fn from_str(secret: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(secret.to_string()))
+    }
+}
+
+impl ConstantTimeEq for SecretString {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.as_bytes().ct_eq(other.0.as_bytes())
+    }
+}
+
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretString {}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::new(String::deserialize(deserializer)?))
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString(***)")
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "***")
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_string_is_redacted() {
+        let secret = SecretString::from_str("APrivateKey1zkp").unwrap();
+        assert_eq!(secret.expose_secret(), "APrivateKey1zkp");
+        assert!(!format!("{:?}", secret).contains("APrivateKey1zkp"));
+        assert!(!format!("{}", secret).contains("APrivateKey1zkp"));
+    }
+
+    #[test]
+    fn test_secret_string_equality() {
+        let secret = SecretString::from_str("password").unwrap();
+        assert_eq!(secret, SecretString::from_str("password").unwrap());
+        assert_ne!(secret, SecretString::from_str("passwore").unwrap());
+        assert_ne!(secret, SecretString::from_str("pass").unwrap());
+        assert!(bool::from(secret.ct_eq(&secret.clone())));
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use std::{sync::Mutex, time::Duration};
+use tokio::sync::Notify;
+
+/// The exit code of a node which was stopped on request.
+pub const STOP_EXIT_CODE: i32 = 0;
+/// The exit code of a node which exits to be restarted by its supervisor, as `EX_TEMPFAIL` of the BSD sysexits.
+pub const RESTART_EXIT_CODE: i32 = 75;
+
+/// The kinds of shutdown that may be requested of a node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ShutdownKind {
+    /// The node shuts down and exits for good.
+    Stop,
+    /// The node shuts down and exits, to be started again by its supervisor.
+    Restart,
+}
+
+impl ShutdownKind {
+    /// Returns the code the node exits with after this kind of shutdown.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Stop => STOP_EXIT_CODE,
+            Self::Restart => RESTART_EXIT_CODE,
+        }
+    }
+}
+
+/// The shutdown requested of the node, if any, which the node waits for to shut down cleanly.
+#[derive(Debug, Default)]
+pub struct Shutdown {
+    /// The kind of the requested shutdown, if any.
+    kind: Mutex<Option<ShutdownKind>>,
+    /// The notification of the requested shutdown, once its delay has elapsed.
+    notify: Notify,
+}
+
+impl Shutdown {
+    /// Requests a shutdown of the given kind after the given delay, such as to let the requester receive a response first.
+    /// Returns `false` if a shutdown was already requested.
+    pub fn request(&'static self, kind: ShutdownKind, delay: Duration) -> bool {
+        {
+            let mut requested = self.kind.lock().expect("The shutdown lock is never poisoned");
+            if requested.is_some() {
+                return false;
+            }
+            *requested = Some(kind);
+        }
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            self.notify.notify_one();
+        });
+        true
+    }
+
+    /// Returns the kind of the requested shutdown, if any.
+    pub fn requested_kind(&self) -> Option<ShutdownKind> {
+        *self.kind.lock().expect("The shutdown lock is never poisoned")
+    }
+
+    /// Waits until a shutdown is requested and its delay has elapsed, returning its kind.
+    pub async fn requested(&self) -> ShutdownKind {
+        loop {
+            self.notify.notified().await;
+            if let Some(kind) = self.requested_kind() {
+                return kind;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_request() {
+        let shutdown: &'static Shutdown = Box::leak(Box::new(Shutdown::default()));
+        assert_eq!(shutdown.requested_kind(), None);
+
+        // Check only the first request is accepted, and is notified once its delay has elapsed.
+        assert!(shutdown.request(ShutdownKind::Restart, Duration::from_millis(10)));
+        assert!(!shutdown.request(ShutdownKind::Stop, Duration::from_millis(10)));
+        assert_eq!(shutdown.requested_kind(), Some(ShutdownKind::Restart));
+        let kind = tokio::time::timeout(Duration::from_secs(1), shutdown.requested()).await.unwrap();
+        assert_eq!(kind, ShutdownKind::Restart);
+        assert_eq!(kind.exit_code(), RESTART_EXIT_CODE);
+    }
+}
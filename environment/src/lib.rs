@@ -21,7 +21,7 @@ extern crate tracing;
 
 pub mod helpers;
 
-use crate::helpers::{NodeType, Resources, Status};
+use crate::helpers::{LogFilter, NodeType, Resources, Shutdown, Status};
 use snarkvm::dpc::Network;
 
 use once_cell::sync::OnceCell;
@@ -85,6 +85,10 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
     const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
     /// The maximum number of blocks that may be fetched in one request.
     const MAXIMUM_BLOCK_REQUEST: u32 = 250;
+    /// The maximum number of block responses served to syncing peers at once.
+    const MAXIMUM_CONCURRENT_BLOCK_RESPONSES: usize = 4;
+    /// The duration in seconds after which the serving cost of a syncing peer decays by half.
+    const SERVING_COST_HALF_LIFE_IN_SECS: u64 = 600; // 10 minutes
     /// The maximum number of failures tolerated before disconnecting from a peer.
     const MAXIMUM_NUMBER_OF_FAILURES: usize = 1024;
 
@@ -118,6 +122,18 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
         STATUS.get_or_init(Status::new)
     }
 
+    /// Returns the shutdown requested of the node, if any.
+    fn shutdown() -> &'static Shutdown {
+        static SHUTDOWN: OnceCell<Shutdown> = OnceCell::new();
+        SHUTDOWN.get_or_init(Shutdown::default)
+    }
+
+    /// Returns the filter of the log output of the node.
+    fn log_filter() -> &'static LogFilter {
+        static LOG_FILTER: OnceCell<LogFilter> = OnceCell::new();
+        LOG_FILTER.get_or_init(LogFilter::default)
+    }
+
     /// Returns the terminator bit for the prover.
     fn terminator() -> &'static Arc<AtomicBool> {
         static TERMINATOR: OnceCell<Arc<AtomicBool>> = OnceCell::new();
@@ -202,6 +218,17 @@ impl<N: Network> Environment for SyncNode<N> {
     const HEARTBEAT_IN_SECS: u64 = 5;
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct Explorer<N: Network>(PhantomData<N>);
+
+#[rustfmt::skip]
+impl<N: Network> Environment for Explorer<N> {
+    type Network = N;
+    const NODE_TYPE: NodeType = NodeType::Explorer;
+    const MINIMUM_NUMBER_OF_PEERS: usize = 0;
+    const MAXIMUM_NUMBER_OF_PEERS: usize = 0;
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ClientTrial<N: Network>(PhantomData<N>);
 
@@ -35,10 +35,13 @@ pub use utils::*;
 use metrics_util::debugging::{DebuggingRecorder, Snapshotter};
 
 #[cfg(feature = "prometheus")]
-pub fn initialize() -> Option<tokio::task::JoinHandle<()>> {
+pub fn initialize(listen_addr: std::net::SocketAddr) -> Option<tokio::task::JoinHandle<()>> {
     use metrics_exporter_prometheus::PrometheusBuilder;
 
-    let (recorder, exporter) = PrometheusBuilder::new().build().expect("can't build the prometheus exporter");
+    let (recorder, exporter) = PrometheusBuilder::new()
+        .with_http_listener(listen_addr)
+        .build()
+        .expect("can't build the prometheus exporter");
 
     metrics::set_boxed_recorder(Box::new(recorder)).expect("can't set the prometheus exporter");
 
@@ -68,4 +71,10 @@ fn register_metrics() {
     for name in GAUGE_NAMES {
         register_gauge!(name);
     }
+    for name in COUNTER_NAMES {
+        register_counter!(name);
+    }
+    for name in HISTOGRAM_NAMES {
+        register_histogram!(name);
+    }
 }
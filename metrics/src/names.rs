@@ -14,14 +14,61 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-pub const GAUGE_NAMES: [&str; 4] = [blocks::HEIGHT, peers::CONNECTED, peers::CANDIDATE, peers::RESTRICTED];
+pub const GAUGE_NAMES: [&str; 8] = [
+    blocks::HEIGHT,
+    blocks::CUMULATIVE_WEIGHT,
+    memory_pool::TRANSACTIONS,
+    peers::CONNECTED,
+    peers::CANDIDATE,
+    peers::RESTRICTED,
+    operator::PROVERS,
+    operator::PENDING_SHARES,
+];
+
+pub const COUNTER_NAMES: [&str; 8] = [
+    blocks::ADDED,
+    memory_pool::REJECTED,
+    peers::HANDSHAKE_FAILURES,
+    operator::SHARES_ACCEPTED,
+    operator::SHARES_UNVERIFIED,
+    operator::SHARD_CONTENTION,
+    rpc::REQUESTS,
+    storage::INDEX_DIVERGENCES,
+];
+
+pub const HISTOGRAM_NAMES: [&str; 1] = [rpc::REQUEST_DURATION];
 
 pub mod blocks {
     pub const HEIGHT: &str = "snarkos_blocks_height_total";
+    pub const CUMULATIVE_WEIGHT: &str = "snarkos_blocks_cumulative_weight";
+    pub const ADDED: &str = "snarkos_blocks_added_total";
+}
+
+pub mod memory_pool {
+    pub const TRANSACTIONS: &str = "snarkos_memory_pool_transactions_total";
+    pub const REJECTED: &str = "snarkos_memory_pool_rejected_total";
 }
 
 pub mod peers {
     pub const CONNECTED: &str = "snarkos_peers_connected_total";
     pub const CANDIDATE: &str = "snarkos_peers_candidate_total";
     pub const RESTRICTED: &str = "snarkos_peers_restricted_total";
+    pub const HANDSHAKE_FAILURES: &str = "snarkos_peers_handshake_failures_total";
+}
+
+pub mod operator {
+    pub const PROVERS: &str = "snarkos_operator_provers_total";
+    pub const PENDING_SHARES: &str = "snarkos_operator_pending_shares_total";
+    pub const SHARES_ACCEPTED: &str = "snarkos_operator_shares_accepted_total";
+    pub const SHARES_UNVERIFIED: &str = "snarkos_operator_shares_unverified_total";
+    pub const SHARD_CONTENTION: &str = "snarkos_operator_shard_contention_total";
+}
+
+pub mod rpc {
+    pub const REQUESTS: &str = "snarkos_rpc_requests_total";
+    pub const REQUEST_DURATION: &str = "snarkos_rpc_request_duration_seconds";
+}
+
+pub mod storage {
+    pub const INDEX_DIVERGENCES: &str = "snarkos_storage_index_divergences_total";
 }
@@ -0,0 +1,136 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::prelude::*;
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::VecDeque;
+use time::OffsetDateTime;
+
+/// The maximum number of audit events retained by the operator.
+const MAXIMUM_AUDIT_EVENTS: usize = 1024;
+
+///
+/// The kind of an incident in the operation of the pool.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// A block built from a block template of the operator was not accepted by the ledger.
+    BlockRejected,
+    /// The chain of the node diverged from the chains of the majority of the external endpoints.
+    ChainSplit,
+    /// A derived index of the storage diverged from the canonical blocks.
+    IndexDivergence,
+}
+
+///
+/// An incident in the operation of the pool, and the recovery of the operator from it.
+///
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct AuditEvent<N: Network> {
+    /// The unique ID of the event, which increases with each event.
+    pub id: u64,
+    /// The UNIX timestamp at which the event was recorded.
+    pub timestamp: i64,
+    /// The kind of the event.
+    pub kind: AuditEventKind,
+    /// The height of the block of the event.
+    pub block_height: u32,
+    /// The hash of the block of the event.
+    pub block_hash: N::BlockHash,
+    /// The reason for the event.
+    pub reason: String,
+    /// The transactions that were quarantined from the block templates of the operator.
+    pub quarantined_transactions: Vec<N::TransactionID>,
+}
+
+///
+/// The most recent audit events of the operator, up to `MAXIMUM_AUDIT_EVENTS`.
+///
+#[derive(Debug, Default)]
+pub struct AuditEvents<N: Network> {
+    events: RwLock<VecDeque<AuditEvent<N>>>,
+}
+
+impl<N: Network> AuditEvents<N> {
+    ///
+    /// Records a new event, evicting the oldest event if the maximum is reached, and returns the event.
+    ///
+    pub fn record(
+        &self,
+        kind: AuditEventKind,
+        block_height: u32,
+        block_hash: N::BlockHash,
+        reason: String,
+        quarantined_transactions: Vec<N::TransactionID>,
+    ) -> AuditEvent<N> {
+        let mut events = self.events.write();
+        let event = AuditEvent {
+            id: events.back().map_or(0, |event| event.id + 1),
+            timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+            kind,
+            block_height,
+            block_hash,
+            reason,
+            quarantined_transactions,
+        };
+        if events.len() == MAXIMUM_AUDIT_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event.clone());
+        event
+    }
+
+    ///
+    /// Returns the retained events with an ID greater than the given ID, or all retained events if none is given.
+    ///
+    pub fn to_events(&self, after_id: Option<u64>) -> Vec<AuditEvent<N>> {
+        self.events
+            .read()
+            .iter()
+            .filter(|event| after_id.map_or(true, |after_id| event.id > after_id))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    #[test]
+    fn test_audit_events_are_bounded() {
+        let events = AuditEvents::<CurrentNetwork>::default();
+        let block_hash = CurrentNetwork::genesis_block().hash();
+
+        for block_height in 0..(MAXIMUM_AUDIT_EVENTS as u32 + 2) {
+            events.record(AuditEventKind::BlockRejected, block_height, block_hash, "rejected".to_string(), vec![]);
+        }
+
+        // The oldest events are evicted, and the IDs continue to increase.
+        let retained = events.to_events(None);
+        assert_eq!(retained.len(), MAXIMUM_AUDIT_EVENTS);
+        assert_eq!(retained.first().map(|event| event.id), Some(2));
+        assert_eq!(retained.last().map(|event| event.id), Some(MAXIMUM_AUDIT_EVENTS as u64 + 1));
+
+        // Only the events after the given ID are returned.
+        assert_eq!(events.to_events(Some(MAXIMUM_AUDIT_EVENTS as u64)).len(), 1);
+    }
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{ledger::PeersState, DisconnectReason};
+use crate::{ledger::PeersState, DisconnectReason, ServingMode};
 use snarkos_environment::Environment;
 use snarkos_storage::{BlockLocators, LedgerState};
 use snarkvm::dpc::prelude::*;
@@ -42,7 +42,8 @@ pub fn find_maximal_peer<N: Network, E: Environment>(
         if !peers_contains_sync_node || E::sync_nodes().contains(peer_ip) {
             // Update the maximal peer state if the peer is ahead and the peer knows if you are a fork or not.
             // This accounts for (Case 1 and Case 2(a))
-            if let Some((_, _, is_on_fork, block_height, block_locators)) = peer_state {
+            // Peers that only serve block headers are skipped, as they decline block requests.
+            if let Some((_, ServingMode::Full, _, is_on_fork, block_height, block_locators)) = peer_state {
                 // Retrieve the cumulative weight, defaulting to the block height if it does not exist.
                 let cumulative_weight = match block_locators.get_cumulative_weight(*block_height) {
                     Some(cumulative_weight) => cumulative_weight,
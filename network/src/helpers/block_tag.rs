@@ -0,0 +1,194 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::{
+    dpc::prelude::*,
+    utilities::{FromBytes, ToBits, ToBytes},
+};
+
+use anyhow::{anyhow, Result};
+use rand::{CryptoRng, Rng};
+
+/// The prefix of the custom event carrying the tag of an operator in a coinbase transition.
+pub const BLOCK_TAG_PREFIX: &[u8] = b"snarkos-pool:";
+/// The maximum length of a block tag, in bytes.
+pub const MAXIMUM_BLOCK_TAG_LENGTH: usize = 32;
+
+///
+/// Ensures the given block tag is non-empty, at most `MAXIMUM_BLOCK_TAG_LENGTH` bytes, and printable ASCII.
+///
+pub fn validate_block_tag(tag: &str) -> Result<()> {
+    if tag.is_empty() || tag.len() > MAXIMUM_BLOCK_TAG_LENGTH {
+        return Err(anyhow!("The block tag must be between 1 and {} bytes", MAXIMUM_BLOCK_TAG_LENGTH));
+    }
+    if !tag.bytes().all(|byte| byte.is_ascii_graphic() || byte == b' ') {
+        return Err(anyhow!("The block tag must consist of printable ASCII characters"));
+    }
+    Ok(())
+}
+
+///
+/// Returns the message signed by an operator to tag the block with the given hash.
+///
+pub fn block_tag_message<N: Network>(tag: &str, block_hash: &N::BlockHash) -> String {
+    format!("{} block tag: {} for block {}", N::NETWORK_NAME, tag, block_hash)
+}
+
+///
+/// Returns a copy of the given block, with the given tag embedded in its coinbase transaction,
+/// signed over the block hash with the given private key.
+///
+/// The tag is a custom event of the coinbase transition. Events are not committed to by the transaction ID,
+/// so the tag does not change the block hash, nor the validity of the coinbase proof. The signature commits
+/// the tag to the block instead, so the tag may be stripped by a relaying node, but not replaced.
+///
+pub fn tag_block<N: Network, R: Rng + CryptoRng>(
+    block: &Block<N>,
+    tag: &str,
+    private_key: &PrivateKey<N>,
+    rng: &mut R,
+) -> Result<Block<N>> {
+    validate_block_tag(tag)?;
+
+    let message = block_tag_message::<N>(tag, &block.hash());
+    let signature = private_key.sign(&message.as_bytes().to_bits_le(), rng)?;
+    let event = [BLOCK_TAG_PREFIX, &[tag.len() as u8], tag.as_bytes(), &signature.to_bytes_le()?].concat();
+
+    let coinbase_transaction_id = block.to_coinbase_transaction()?.transaction_id();
+    let transactions = block
+        .transactions()
+        .iter()
+        .map(|transaction| match transaction.transaction_id() == coinbase_transaction_id {
+            true => tag_transaction(transaction, event.clone()),
+            false => Ok(transaction.clone()),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let tagged_block = Block::from(block.previous_block_hash(), block.header().clone(), Transactions::from(&transactions)?)?;
+
+    // Ensure the tag did not change the identity of the block.
+    match tagged_block.hash() == block.hash() {
+        true => Ok(tagged_block),
+        false => Err(anyhow!("Tagging changed the hash of block {}", block.height())),
+    }
+}
+
+///
+/// Returns the tag embedded in the coinbase transaction of the given block, and its signature, if any.
+///
+pub fn get_block_tag<N: Network>(block: &Block<N>) -> Option<(String, N::AccountSignature)> {
+    let coinbase_transaction = block.to_coinbase_transaction().ok()?;
+    let event = coinbase_transaction
+        .transitions()
+        .iter()
+        .flat_map(|transition| transition.events())
+        .find_map(|event| match event {
+            Event::Custom(bytes) => bytes.strip_prefix(BLOCK_TAG_PREFIX).map(|event| event.to_vec()),
+            _ => None,
+        })?;
+
+    let (tag_length, event) = event.split_first()?;
+    if event.len() < *tag_length as usize {
+        return None;
+    }
+    let (tag, signature) = event.split_at(*tag_length as usize);
+    let tag = String::from_utf8(tag.to_vec()).ok()?;
+    let signature = N::AccountSignature::from_bytes_le(signature).ok()?;
+    Some((tag, signature))
+}
+
+///
+/// Returns the tag embedded in the coinbase transaction of the given block, if it is signed by the given address.
+///
+pub fn verify_block_tag<N: Network>(block: &Block<N>, address: &Address<N>) -> Option<String> {
+    let (tag, signature) = get_block_tag(block)?;
+    let message = block_tag_message::<N>(&tag, &block.hash());
+    match address.verify_signature(&message.as_bytes().to_bits_le(), &signature) {
+        Ok(true) => Some(tag),
+        _ => None,
+    }
+}
+
+///
+/// Returns a copy of the given transaction, with the given event appended to the events of its first transition.
+///
+fn tag_transaction<N: Network>(transaction: &Transaction<N>, event: Vec<u8>) -> Result<Transaction<N>> {
+    let mut transitions = transaction.transitions().clone();
+    let transition = match transitions.first_mut() {
+        Some(transition) => transition,
+        None => return Err(anyhow!("Transaction {} has no transitions", transaction.transaction_id())),
+    };
+
+    let mut events: Vec<_> = transition.events().cloned().collect();
+    events.push(Event::Custom(event));
+
+    *transition = Transition::from(
+        transition.serial_numbers().cloned().collect(),
+        transition.ciphertexts().cloned().collect(),
+        *transition.value_balance(),
+        events,
+        transition.proof().clone(),
+    )?;
+
+    let tagged_transaction = Transaction::from(transaction.inner_circuit_id(), transaction.ledger_root(), transitions)?;
+
+    // Ensure the tag did not change the identity of the transaction.
+    match tagged_transaction.transaction_id() == transaction.transaction_id() {
+        true => Ok(tagged_transaction),
+        false => Err(anyhow!("Tagging changed the ID of transaction {}", transaction.transaction_id())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_validate_block_tag() {
+        assert!(validate_block_tag("hashpool").is_ok());
+        assert!(validate_block_tag("hash pool #1").is_ok());
+        assert!(validate_block_tag("").is_err());
+        assert!(validate_block_tag(&"a".repeat(MAXIMUM_BLOCK_TAG_LENGTH + 1)).is_err());
+        assert!(validate_block_tag("pool\n").is_err());
+        assert!(validate_block_tag("pööl").is_err());
+    }
+
+    #[test]
+    fn test_untagged_block() {
+        assert!(get_block_tag(CurrentNetwork::genesis_block()).is_none());
+    }
+
+    #[test]
+    fn test_tag_block() {
+        let rng = &mut thread_rng();
+        let account = Account::<CurrentNetwork>::new(rng);
+        let block = CurrentNetwork::genesis_block();
+
+        let tagged_block = tag_block(block, "hashpool", account.private_key(), rng).unwrap();
+        assert_eq!(tagged_block.hash(), block.hash());
+        assert_eq!(verify_block_tag(&tagged_block, &account.address()), Some("hashpool".to_string()));
+
+        // Check the tag is not attributed to another address.
+        let other_address = Account::<CurrentNetwork>::new(rng).address();
+        assert_eq!(verify_block_tag(&tagged_block, &other_address), None);
+
+        // Check an invalid tag is refused.
+        assert!(tag_block(block, "", account.private_key(), rng).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use parking_lot::Mutex;
+
+/// The maximum number of seconds a `getblocktemplate` long-poll is held until the block template changes.
+pub const BLOCK_TEMPLATE_LONG_POLL_IN_SECS: u64 = 30;
+
+///
+/// The IDs of the block templates served to miners, which increase whenever the block template changes.
+///
+/// A block template is identified by the state it is built from, namely the latest block and the memory pool.
+/// The ID is incremented on the first request after either changes, so a miner may tell a new template from a repeated one.
+/// A change of state may be clean, such as a new block, which invalidates the work on all previous block templates.
+///
+#[derive(Debug)]
+pub struct BlockTemplateIds<T: Eq> {
+    /// The state of the latest block template := (state, template_id, clean_template_id)
+    latest: Mutex<(Option<T>, u64, u64)>,
+}
+
+impl<T: Eq> BlockTemplateIds<T> {
+    ///
+    /// Returns the ID of the block template built from the given state.
+    ///
+    pub fn get_or_increment(&self, state: T) -> u64 {
+        self.get_or_increment_with(state, |_, _| true).0
+    }
+
+    ///
+    /// Returns the ID of the block template built from the given state, and the ID of the latest clean block template,
+    /// where `is_clean` determines if a change from the previous state to the given state is clean.
+    ///
+    pub fn get_or_increment_with(&self, state: T, is_clean: impl FnOnce(&T, &T) -> bool) -> (u64, u64) {
+        let mut latest = self.latest.lock();
+        if latest.0.as_ref() != Some(&state) {
+            let is_clean = match &latest.0 {
+                Some(previous) => is_clean(previous, &state),
+                None => true,
+            };
+            latest.0 = Some(state);
+            latest.1 += 1;
+            if is_clean {
+                latest.2 = latest.1;
+            }
+        }
+        (latest.1, latest.2)
+    }
+}
+
+impl<T: Eq> Default for BlockTemplateIds<T> {
+    fn default() -> Self {
+        Self { latest: Mutex::new((None, 0, 0)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_template_ids() {
+        let ids = BlockTemplateIds::default();
+
+        // The ID is repeated until the state changes, and never decreases.
+        assert_eq!(ids.get_or_increment(("a", 0)), 1);
+        assert_eq!(ids.get_or_increment(("a", 0)), 1);
+        assert_eq!(ids.get_or_increment(("a", 1)), 2);
+        assert_eq!(ids.get_or_increment(("b", 1)), 3);
+        assert_eq!(ids.get_or_increment(("a", 0)), 4);
+    }
+
+    #[test]
+    fn test_clean_block_template_ids() {
+        let ids = BlockTemplateIds::default();
+        let is_clean = |previous: &(&str, u32), state: &(&str, u32)| previous.0 != state.0;
+
+        // The first block template is clean, and only a change of the first component is clean afterwards.
+        assert_eq!(ids.get_or_increment_with(("a", 0), is_clean), (1, 1));
+        assert_eq!(ids.get_or_increment_with(("a", 1), is_clean), (2, 1));
+        assert_eq!(ids.get_or_increment_with(("a", 1), is_clean), (2, 1));
+        assert_eq!(ids.get_or_increment_with(("b", 1), is_clean), (3, 3));
+        assert_eq!(ids.get_or_increment_with(("b", 2), is_clean), (4, 3));
+    }
+}
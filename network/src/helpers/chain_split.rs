@@ -0,0 +1,210 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{helpers::call_rpc, LedgerReader};
+use snarkvm::dpc::prelude::*;
+
+use anyhow::{anyhow, Result};
+use futures::future::join_all;
+use serde::Serialize;
+use serde_json::json;
+use std::{marker::PhantomData, time::Duration};
+
+/// The maximum number of blocks the chain may diverge from the external endpoints before a chain split is raised.
+/// This bounds the block hashes requested from each endpoint, which serve at most `MAXIMUM_BLOCK_REQUEST` block hashes.
+pub const MAXIMUM_CHAIN_SPLIT_THRESHOLD: u32 = 100;
+/// The maximum duration of a request to an external endpoint.
+const CHAIN_SPLIT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+///
+/// The tip of the chain of an external endpoint, as compared with the local chain.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TipStatus {
+    /// The chain of the endpoint agrees with the local chain, up to the lower of the two tips.
+    Consistent,
+    /// The chain of the endpoint diverges from the local chain, at the given number of blocks below the local tip.
+    Diverged { fork_depth: u32 },
+    /// The endpoint could not be queried.
+    Unreachable,
+}
+
+///
+/// Periodically compares the local chain with the chains of external RPC endpoints, such as other pools and explorers,
+/// to detect when the node follows a minority fork.
+///
+#[derive(Clone, Debug)]
+pub struct ChainSplitDetector<N: Network> {
+    /// The URLs of the RPC servers of the external endpoints.
+    endpoints: Vec<String>,
+    /// The number of blocks the local chain may diverge from an endpoint before it counts towards a chain split.
+    threshold: u32,
+    /// If `true`, the payouts are halted while the chain is split.
+    halt_payouts: bool,
+    /// The interval between two comparisons with the endpoints.
+    interval: Duration,
+    /// The HTTP client.
+    client: reqwest::Client,
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> ChainSplitDetector<N> {
+    ///
+    /// Initializes a new detector comparing the local chain with the RPC servers at the given URLs.
+    ///
+    pub fn new(endpoints: Vec<String>, threshold: u32, halt_payouts: bool, interval: Duration) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(anyhow!("The chain split detector requires at least one endpoint"));
+        }
+        if threshold == 0 || threshold >= MAXIMUM_CHAIN_SPLIT_THRESHOLD {
+            return Err(anyhow!(
+                "The chain split threshold must be between 1 and {} blocks",
+                MAXIMUM_CHAIN_SPLIT_THRESHOLD - 1
+            ));
+        }
+        let client = reqwest::Client::builder().timeout(CHAIN_SPLIT_REQUEST_TIMEOUT).build()?;
+        Ok(Self {
+            endpoints,
+            threshold,
+            halt_payouts,
+            interval,
+            client,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns `true` if the payouts are halted while the chain is split.
+    pub fn halt_payouts(&self) -> bool {
+        self.halt_payouts
+    }
+
+    /// Returns the interval between two comparisons with the endpoints.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    ///
+    /// Returns the tip status of each endpoint, as compared with the given ledger.
+    ///
+    pub async fn compare_tips(&self, ledger: &LedgerReader<N>) -> Vec<(String, TipStatus)> {
+        let comparisons = self.endpoints.iter().map(|endpoint| async move {
+            match self.compare_tip(endpoint, ledger).await {
+                Ok(status) => (endpoint.clone(), status),
+                Err(error) => {
+                    debug!("Failed to compare the chain tip with {}: {}", endpoint, error);
+                    (endpoint.clone(), TipStatus::Unreachable)
+                }
+            }
+        });
+        join_all(comparisons).await
+    }
+
+    ///
+    /// Returns `true` if the local chain is split from the majority of the reachable endpoints,
+    /// which is when their chains diverge from the local chain beyond the threshold.
+    ///
+    pub fn is_chain_split(&self, statuses: &[TipStatus]) -> bool {
+        let reachable = statuses.iter().filter(|status| **status != TipStatus::Unreachable).count();
+        let diverged = statuses
+            .iter()
+            .filter(|status| matches!(status, TipStatus::Diverged { fork_depth } if *fork_depth > self.threshold))
+            .count();
+        reachable > 0 && diverged * 2 > reachable
+    }
+
+    ///
+    /// Compares the block hashes of the given endpoint with those of the given ledger,
+    /// from beyond the threshold below the lower of the two tips, up to the lower of the two tips.
+    ///
+    async fn compare_tip(&self, endpoint: &str, ledger: &LedgerReader<N>) -> Result<TipStatus> {
+        let local_height = ledger.latest_block_height();
+        let remote_height: u32 = serde_json::from_value(call_rpc(&self.client, endpoint, "latestblockheight", json!([])).await?)?;
+
+        let end_height = local_height.min(remote_height);
+        let start_height = end_height.saturating_sub(self.threshold);
+        let params = json!([start_height, end_height]);
+        let remote_hashes: Vec<N::BlockHash> = serde_json::from_value(call_rpc(&self.client, endpoint, "getblockhashes", params).await?)?;
+        if remote_hashes.is_empty() {
+            return Err(anyhow!("The endpoint returned no block hashes"));
+        }
+        let local_hashes = ledger.get_block_hashes(start_height, end_height)?;
+
+        Ok(match count_diverged_blocks(&local_hashes, &remote_hashes) {
+            Some(0) => TipStatus::Consistent,
+            Some(diverged_blocks) => TipStatus::Diverged {
+                fork_depth: local_height - end_height + diverged_blocks,
+            },
+            // No block is common within the compared blocks, so the chains diverge beyond the threshold.
+            None => TipStatus::Diverged {
+                fork_depth: local_height - start_height + 1,
+            },
+        })
+    }
+}
+
+///
+/// Returns the number of blocks at the end of the given local and remote block hashes which differ,
+/// where both end at the same block height, or `None` if no block hash is common to both.
+///
+pub fn count_diverged_blocks<H: PartialEq>(local_hashes: &[H], remote_hashes: &[H]) -> Option<u32> {
+    local_hashes
+        .iter()
+        .rev()
+        .zip(remote_hashes.iter().rev())
+        .position(|(local_hash, remote_hash)| local_hash == remote_hash)
+        .map(|diverged_blocks| diverged_blocks as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    #[test]
+    fn test_count_diverged_blocks() {
+        // Check the chains which agree at the tip have not diverged.
+        assert_eq!(count_diverged_blocks(&[1, 2, 3], &[1, 2, 3]), Some(0));
+        // Check the diverged blocks are counted from the tip, down to the last common block.
+        assert_eq!(count_diverged_blocks(&[1, 2, 3, 4], &[1, 2, 5, 6]), Some(2));
+        // Check the block hashes are aligned at the tip, if fewer were returned.
+        assert_eq!(count_diverged_blocks(&[1, 2, 3, 4], &[3, 7]), Some(1));
+        // Check the chains without a common block are distinguished.
+        assert_eq!(count_diverged_blocks(&[1, 2], &[3, 4]), None);
+    }
+
+    #[test]
+    fn test_is_chain_split() {
+        let endpoints = vec!["http://127.0.0.1:3032".to_string()];
+        let detector = ChainSplitDetector::<CurrentNetwork>::new(endpoints.clone(), 6, true, Duration::from_secs(60)).unwrap();
+        let diverged = |fork_depth| TipStatus::Diverged { fork_depth };
+
+        // Check a chain split requires the majority of the reachable endpoints to diverge beyond the threshold.
+        assert!(!detector.is_chain_split(&[TipStatus::Consistent, diverged(7)]));
+        assert!(detector.is_chain_split(&[TipStatus::Consistent, diverged(7), diverged(10)]));
+        assert!(!detector.is_chain_split(&[diverged(6), diverged(6)]));
+        assert!(detector.is_chain_split(&[diverged(7), TipStatus::Unreachable, TipStatus::Unreachable]));
+        // Check the unreachable endpoints never raise a chain split.
+        assert!(!detector.is_chain_split(&[TipStatus::Unreachable, TipStatus::Unreachable]));
+        assert!(!detector.is_chain_split(&[]));
+
+        // Check the detector requires an endpoint and a threshold within bounds.
+        assert!(ChainSplitDetector::<CurrentNetwork>::new(vec![], 6, true, Duration::from_secs(60)).is_err());
+        assert!(ChainSplitDetector::<CurrentNetwork>::new(endpoints.clone(), 0, true, Duration::from_secs(60)).is_err());
+        let threshold = MAXIMUM_CHAIN_SPLIT_THRESHOLD;
+        assert!(ChainSplitDetector::<CurrentNetwork>::new(endpoints, threshold, true, Duration::from_secs(60)).is_err());
+    }
+}
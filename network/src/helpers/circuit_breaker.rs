@@ -0,0 +1,120 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The number of consecutive timed out requests after which the circuit breaker opens.
+const MAXIMUM_CONSECUTIVE_TIMEOUTS: u32 = 3;
+/// The duration for which an open circuit breaker fails requests immediately, before letting a request through again.
+const OPEN_CIRCUIT_DURATION: Duration = Duration::from_secs(30);
+
+///
+/// A circuit breaker for the requests to a subsystem of the node, which bounds each request by a timeout,
+/// and fails requests immediately while the subsystem is unresponsive, instead of waiting on it forever.
+///
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// The name of the subsystem behind the circuit breaker.
+    subsystem: &'static str,
+    /// The maximum duration of a request to the subsystem.
+    timeout: Duration,
+    /// The number of consecutive requests to the subsystem that timed out.
+    consecutive_timeouts: AtomicU32,
+    /// The instant at which the circuit breaker opened, if it is open.
+    opened_at: Mutex<Option<Instant>>,
+}
+
+impl CircuitBreaker {
+    /// Initializes a new instance of a circuit breaker for the given subsystem.
+    pub fn new(subsystem: &'static str, timeout: Duration) -> Self {
+        Self {
+            subsystem,
+            timeout,
+            consecutive_timeouts: Default::default(),
+            opened_at: Default::default(),
+        }
+    }
+
+    /// Returns `true` if the subsystem is considered unresponsive, and requests fail immediately.
+    pub fn is_open(&self) -> bool {
+        matches!(*self.opened_at.lock(), Some(opened_at) if opened_at.elapsed() < OPEN_CIRCUIT_DURATION)
+    }
+
+    ///
+    /// Performs the given request to the subsystem, returning an error if the circuit breaker is open,
+    /// or if the request does not complete within the timeout.
+    ///
+    pub async fn call<F: Future>(&self, request: F) -> Result<F::Output> {
+        if self.is_open() {
+            return Err(anyhow!("The {} subsystem is unresponsive, try again later", self.subsystem));
+        }
+
+        match tokio::time::timeout(self.timeout, request).await {
+            Ok(output) => {
+                self.consecutive_timeouts.store(0, Ordering::SeqCst);
+                *self.opened_at.lock() = None;
+                Ok(output)
+            }
+            Err(_) => {
+                // Open the circuit breaker once the subsystem failed to respond too many times in a row.
+                // After the open duration, the next request is let through, and reopens it if it times out again.
+                let consecutive_timeouts = self.consecutive_timeouts.fetch_add(1, Ordering::SeqCst) + 1;
+                if consecutive_timeouts >= MAXIMUM_CONSECUTIVE_TIMEOUTS {
+                    warn!(
+                        "The {} subsystem is unresponsive, failing its requests for {:?}",
+                        self.subsystem, OPEN_CIRCUIT_DURATION
+                    );
+                    *self.opened_at.lock() = Some(Instant::now());
+                }
+                Err(anyhow!("The {} subsystem is unresponsive, the request timed out", self.subsystem))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let circuit_breaker = CircuitBreaker::new("test", Duration::from_millis(10));
+
+        // A responsive subsystem passes the output of the request through.
+        assert_eq!(circuit_breaker.call(async { 1 }).await.unwrap(), 1);
+
+        // An unresponsive subsystem times out, and opens the circuit breaker after consecutive timeouts.
+        for _ in 0..MAXIMUM_CONSECUTIVE_TIMEOUTS {
+            assert!(!circuit_breaker.is_open());
+            assert!(circuit_breaker.call(std::future::pending::<()>()).await.is_err());
+        }
+        assert!(circuit_breaker.is_open());
+
+        // An open circuit breaker fails requests immediately, even to a responsive subsystem.
+        assert!(circuit_breaker.call(async { 1 }).await.is_err());
+
+        // A successful request closes the circuit breaker again.
+        *circuit_breaker.opened_at.lock() = None;
+        assert_eq!(circuit_breaker.call(async { 1 }).await.unwrap(), 1);
+        assert_eq!(circuit_breaker.consecutive_timeouts.load(Ordering::SeqCst), 0);
+    }
+}
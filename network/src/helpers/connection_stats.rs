@@ -0,0 +1,175 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+///
+/// The statistics of a connection to a peer, updated by the peer handler as the connection is used.
+///
+#[derive(Debug)]
+pub struct ConnectionStats {
+    /// The number of bytes written to the connection.
+    bytes_sent: AtomicU64,
+    /// The number of bytes read from the connection.
+    bytes_received: AtomicU64,
+    /// The round-trip time from the last `Ping` to its `Pong`, in microseconds; `u64::MAX` if unknown.
+    latency_in_micros: AtomicU64,
+}
+
+impl Default for ConnectionStats {
+    fn default() -> Self {
+        Self {
+            bytes_sent: Default::default(),
+            bytes_received: Default::default(),
+            latency_in_micros: AtomicU64::new(u64::MAX),
+        }
+    }
+}
+
+impl ConnectionStats {
+    ///
+    /// Returns the number of bytes written to the connection.
+    ///
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Returns the number of bytes read from the connection.
+    ///
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Returns the last measured round-trip time of the connection, if any.
+    ///
+    pub fn latency(&self) -> Option<Duration> {
+        match self.latency_in_micros.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            latency_in_micros => Some(Duration::from_micros(latency_in_micros)),
+        }
+    }
+
+    ///
+    /// Updates the round-trip time of the connection.
+    ///
+    pub fn set_latency(&self, latency: Duration) {
+        let latency_in_micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX - 1).min(u64::MAX - 1);
+        self.latency_in_micros.store(latency_in_micros, Ordering::Relaxed);
+    }
+}
+
+///
+/// A stream that counts the bytes written to and read from it in the given connection statistics.
+///
+#[derive(Debug)]
+pub struct MeteredStream<S> {
+    stream: S,
+    stats: Arc<ConnectionStats>,
+}
+
+impl<S> MeteredStream<S> {
+    ///
+    /// Initializes a new instance of `MeteredStream`, counting the traffic of the given stream in the given statistics.
+    ///
+    pub fn new(stream: S, stats: Arc<ConnectionStats>) -> Self {
+        Self { stream, stats }
+    }
+
+    ///
+    /// Returns a reference to the underlying stream.
+    ///
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for MeteredStream<S> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled_before = buf.filled().len();
+        let result = Pin::new(&mut this.stream).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            let number_of_bytes = (buf.filled().len() - filled_before) as u64;
+            this.stats.bytes_received.fetch_add(number_of_bytes, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for MeteredStream<S> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut this.stream).poll_write(cx, buf);
+        if let Poll::Ready(Ok(number_of_bytes)) = result {
+            this.stats.bytes_sent.fetch_add(number_of_bytes as u64, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_metered_stream_counts_bytes() {
+        let (local, mut remote) = duplex(64);
+        let stats = Arc::new(ConnectionStats::default());
+        let mut stream = MeteredStream::new(local, stats.clone());
+
+        stream.write_all(b"ping").await.unwrap();
+        let mut buffer = [0u8; 4];
+        remote.read_exact(&mut buffer).await.unwrap();
+
+        remote.write_all(b"pong!").await.unwrap();
+        let mut buffer = [0u8; 5];
+        stream.read_exact(&mut buffer).await.unwrap();
+
+        assert_eq!(stats.bytes_sent(), 4);
+        assert_eq!(stats.bytes_received(), 5);
+    }
+
+    #[test]
+    fn test_latency() {
+        let stats = ConnectionStats::default();
+        assert_eq!(stats.latency(), None);
+
+        stats.set_latency(Duration::from_millis(25));
+        assert_eq!(stats.latency(), Some(Duration::from_millis(25)));
+    }
+}
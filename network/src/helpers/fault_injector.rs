@@ -0,0 +1,322 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+use rand::Rng;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// The environment variable of the probability that the verification of a share is delayed.
+pub const CHAOS_SHARE_DELAY_PROBABILITY: &str = "SNARKOS_CHAOS_SHARE_DELAY_PROBABILITY";
+/// The environment variable of the maximum delay of the verification of a share, in milliseconds.
+pub const CHAOS_MAX_SHARE_DELAY_IN_MS: &str = "SNARKOS_CHAOS_MAX_SHARE_DELAY_IN_MS";
+/// The environment variable of the probability that the push of a new block template to the provers is dropped.
+pub const CHAOS_DROP_TEMPLATE_PROBABILITY: &str = "SNARKOS_CHAOS_DROP_TEMPLATE_PROBABILITY";
+/// The environment variable of the probability that the rollover to a new round is duplicated.
+pub const CHAOS_DUPLICATE_ROLLOVER_PROBABILITY: &str = "SNARKOS_CHAOS_DUPLICATE_ROLLOVER_PROBABILITY";
+
+/// The maximum delay of the verification of a share if none is given.
+const DEFAULT_MAX_SHARE_DELAY: Duration = Duration::from_millis(1_000);
+
+///
+/// The faults injected into the operator, for chaos testing of its share accounting and payout pipeline.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaultConfig {
+    /// The probability that the verification of a share is delayed, in `[0, 1]`.
+    pub share_delay_probability: f64,
+    /// The maximum delay of the verification of a share.
+    pub max_share_delay: Duration,
+    /// The probability that the push of a new block template to the provers is dropped, in `[0, 1]`.
+    pub drop_template_probability: f64,
+    /// The probability that the rollover to a new round is duplicated, in `[0, 1]`.
+    pub duplicate_rollover_probability: f64,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            share_delay_probability: 0.0,
+            max_share_delay: DEFAULT_MAX_SHARE_DELAY,
+            drop_template_probability: 0.0,
+            duplicate_rollover_probability: 0.0,
+        }
+    }
+}
+
+impl FaultConfig {
+    ///
+    /// Returns the faults given by the `SNARKOS_CHAOS_*` environment variables, where a missing variable injects no fault.
+    ///
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    ///
+    /// Returns the faults given by the variables of the given lookup, as in `from_env`.
+    ///
+    pub fn from_vars<F: Fn(&str) -> Option<String>>(get: F) -> Result<Self> {
+        let probability = |name: &str| -> Result<f64> {
+            match get(name) {
+                Some(value) => {
+                    let probability: f64 = value.parse().map_err(|_| anyhow!("{} must be a number, found '{}'", name, value))?;
+                    match (0.0..=1.0).contains(&probability) {
+                        true => Ok(probability),
+                        false => Err(anyhow!("{} must be in [0, 1], found {}", name, probability)),
+                    }
+                }
+                None => Ok(0.0),
+            }
+        };
+        let max_share_delay = match get(CHAOS_MAX_SHARE_DELAY_IN_MS) {
+            Some(value) => Duration::from_millis(
+                value
+                    .parse()
+                    .map_err(|_| anyhow!("{} must be a number of milliseconds, found '{}'", CHAOS_MAX_SHARE_DELAY_IN_MS, value))?,
+            ),
+            None => DEFAULT_MAX_SHARE_DELAY,
+        };
+
+        Ok(Self {
+            share_delay_probability: probability(CHAOS_SHARE_DELAY_PROBABILITY)?,
+            max_share_delay,
+            drop_template_probability: probability(CHAOS_DROP_TEMPLATE_PROBABILITY)?,
+            duplicate_rollover_probability: probability(CHAOS_DUPLICATE_ROLLOVER_PROBABILITY)?,
+        })
+    }
+
+    ///
+    /// Returns `true` if any fault may be injected.
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.share_delay_probability > 0.0 || self.drop_template_probability > 0.0 || self.duplicate_rollover_probability > 0.0
+    }
+}
+
+///
+/// The accounting of the shares of the operator, checking that every attributed share was accepted, and none was lost.
+///
+/// Each share moves from accepted, to pending, to either revoked or attributed to a round in storage.
+/// A share is counted as accepted before it is pending, and leaves the pending shares before it is counted as revoked or attributed,
+/// so the sum of the pending, revoked, and attributed shares never exceeds the accepted shares, and equals it once the operator is idle.
+///
+#[derive(Debug, Default)]
+pub struct ShareAccounting {
+    /// The number of shares accepted by the operator.
+    accepted: AtomicU64,
+    /// The number of pending shares revoked as penalties.
+    revoked: AtomicU64,
+    /// The number of pending shares persisted for their rounds.
+    attributed: AtomicU64,
+    /// The accepted shares and the deficit of the last check, if shares were unaccounted for.
+    last_deficit: Mutex<Option<(u64, u64)>>,
+}
+
+impl ShareAccounting {
+    /// Records an accepted share, before it is added to the pending shares.
+    pub fn record_accepted(&self) {
+        self.accepted.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Records the given number of shares revoked from the pending shares.
+    pub fn record_revoked(&self, shares: u64) {
+        self.revoked.fetch_add(shares, Ordering::SeqCst);
+    }
+
+    /// Records the given number of pending shares persisted for their rounds.
+    pub fn record_attributed(&self, shares: u64) {
+        self.attributed.fetch_add(shares, Ordering::SeqCst);
+    }
+
+    ///
+    /// Checks the invariants of the share accounting, given a function returning the number of pending shares.
+    /// Returns an error if more shares were attributed than accepted, or if shares remain unaccounted for
+    /// across two consecutive checks without any share being accepted in between.
+    ///
+    pub fn check<F: FnOnce() -> u64>(&self, pending: F) -> Result<()> {
+        // Read the counters in the reverse order of the movements of the shares, so no share is counted twice.
+        let attributed = self.attributed.load(Ordering::SeqCst);
+        let revoked = self.revoked.load(Ordering::SeqCst);
+        let pending = pending();
+        let accepted = self.accepted.load(Ordering::SeqCst);
+
+        let accounted = attributed.saturating_add(revoked).saturating_add(pending);
+        if accounted > accepted {
+            return Err(anyhow!(
+                "{} shares are accounted for ({} attributed, {} revoked, {} pending), but only {} were accepted",
+                accounted,
+                attributed,
+                revoked,
+                pending,
+                accepted
+            ));
+        }
+
+        // A deficit is expected while shares are in flight, but not if it persists while the operator is idle.
+        let deficit = accepted - accounted;
+        let mut last_deficit = self.last_deficit.lock();
+        let is_lost = deficit > 0 && *last_deficit == Some((accepted, deficit));
+        *last_deficit = (deficit > 0).then(|| (accepted, deficit));
+        match is_lost {
+            true => Err(anyhow!("{} of the {} accepted shares are unaccounted for", deficit, accepted)),
+            false => Ok(()),
+        }
+    }
+}
+
+///
+/// The injector of the faults of the operator, and the accounting of its shares for the invariant checks.
+///
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    /// The faults to inject.
+    config: FaultConfig,
+    /// The accounting of the shares of the operator.
+    accounting: ShareAccounting,
+}
+
+impl FaultInjector {
+    ///
+    /// Initializes a new fault injector with the given faults.
+    ///
+    pub fn new(config: FaultConfig) -> Self {
+        Self {
+            config,
+            accounting: Default::default(),
+        }
+    }
+
+    ///
+    /// Returns the faults injected.
+    ///
+    pub fn config(&self) -> &FaultConfig {
+        &self.config
+    }
+
+    ///
+    /// Returns the accounting of the shares of the operator.
+    ///
+    pub fn accounting(&self) -> &ShareAccounting {
+        &self.accounting
+    }
+
+    ///
+    /// Returns a random delay to apply to the verification of a share, if one is injected.
+    ///
+    pub fn share_delay(&self) -> Option<Duration> {
+        let mut rng = rand::thread_rng();
+        match self.config.share_delay_probability > 0.0 && rng.gen_bool(self.config.share_delay_probability) {
+            true => Some(self.config.max_share_delay.mul_f64(rng.gen::<f64>())),
+            false => None,
+        }
+    }
+
+    ///
+    /// Returns `true` if the push of a new block template to the provers is to be dropped.
+    ///
+    pub fn drop_template_push(&self) -> bool {
+        self.config.drop_template_probability > 0.0 && rand::thread_rng().gen_bool(self.config.drop_template_probability)
+    }
+
+    ///
+    /// Returns `true` if the rollover to a new round is to be duplicated.
+    ///
+    pub fn duplicate_rollover(&self) -> bool {
+        self.config.duplicate_rollover_probability > 0.0 && rand::thread_rng().gen_bool(self.config.duplicate_rollover_probability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_fault_config_from_vars() {
+        // No variables inject no fault.
+        let config = FaultConfig::from_vars(|_| None).unwrap();
+        assert_eq!(config, FaultConfig::default());
+        assert!(!config.is_enabled());
+
+        let vars: HashMap<&str, &str> = [
+            (CHAOS_SHARE_DELAY_PROBABILITY, "0.5"),
+            (CHAOS_MAX_SHARE_DELAY_IN_MS, "250"),
+            (CHAOS_DUPLICATE_ROLLOVER_PROBABILITY, "1"),
+        ]
+        .into_iter()
+        .collect();
+        let config = FaultConfig::from_vars(|name| vars.get(name).map(|value| value.to_string())).unwrap();
+        assert!(config.is_enabled());
+        assert_eq!(config.share_delay_probability, 0.5);
+        assert_eq!(config.max_share_delay, Duration::from_millis(250));
+        assert_eq!(config.drop_template_probability, 0.0);
+        assert_eq!(config.duplicate_rollover_probability, 1.0);
+
+        // Invalid probabilities are refused.
+        for value in ["1.5", "-0.1", "NaN", "often"] {
+            let result = FaultConfig::from_vars(|name| (name == CHAOS_DROP_TEMPLATE_PROBABILITY).then(|| value.to_string()));
+            assert!(result.is_err(), "{} should be refused", value);
+        }
+    }
+
+    #[test]
+    fn test_fault_injector() {
+        // A fault injector without faults never injects one.
+        let injector = FaultInjector::default();
+        assert!((0..100).all(|_| injector.share_delay().is_none() && !injector.drop_template_push() && !injector.duplicate_rollover()));
+
+        // A fault injector with certain faults always injects them, within their bounds.
+        let injector = FaultInjector::new(FaultConfig {
+            share_delay_probability: 1.0,
+            max_share_delay: Duration::from_millis(10),
+            drop_template_probability: 1.0,
+            duplicate_rollover_probability: 1.0,
+        });
+        for _ in 0..100 {
+            assert!(injector.share_delay().unwrap() <= Duration::from_millis(10));
+            assert!(injector.drop_template_push());
+            assert!(injector.duplicate_rollover());
+        }
+    }
+
+    #[test]
+    fn test_share_accounting() {
+        let accounting = ShareAccounting::default();
+        assert!(accounting.check(|| 0).is_ok());
+
+        // Accept 10 shares, of which 2 are revoked, 5 are attributed, and 3 remain pending.
+        (0..10).for_each(|_| accounting.record_accepted());
+        accounting.record_revoked(2);
+        accounting.record_attributed(5);
+        assert!(accounting.check(|| 3).is_ok());
+
+        // A share in flight is tolerated once, but not if it remains unaccounted for without new shares.
+        assert!(accounting.check(|| 2).is_ok());
+        assert!(accounting.check(|| 2).is_err());
+        // A new share resets the tolerance.
+        accounting.record_accepted();
+        assert!(accounting.check(|| 3).is_ok());
+        assert!(accounting.check(|| 4).is_ok());
+
+        // Attributing a share twice is a violation.
+        accounting.record_attributed(1);
+        assert!(accounting.check(|| 4).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+///
+/// The reason a peer failed the handshake with this node.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandshakeFailureReason {
+    /// The peer runs an outdated message version := (version)
+    OutdatedVersion(u32),
+    /// The peer is on another network, judging by its maximum fork depth.
+    WrongNetwork,
+    /// The peer responded to the challenge with another genesis block.
+    BadGenesis,
+    /// The IP of the peer is banned.
+    Banned,
+    /// The node type of the peer is not permitted to connect inbound.
+    NodeTypeNotPermitted,
+    /// The handshake failed for another reason, such as a timeout or a closed connection.
+    Other,
+}
+
+impl HandshakeFailureReason {
+    /// The number of distinct reasons, regardless of the version of an outdated peer.
+    const NUMBER_OF_REASONS: usize = 6;
+
+    /// Returns the name of the reason, as reported in the metrics and over RPC.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OutdatedVersion(_) => "outdated_version",
+            Self::WrongNetwork => "wrong_network",
+            Self::BadGenesis => "bad_genesis",
+            Self::Banned => "banned",
+            Self::NodeTypeNotPermitted => "node_type_not_permitted",
+            Self::Other => "other",
+        }
+    }
+
+    /// Returns the index of the reason in the counters.
+    fn index(&self) -> usize {
+        match self {
+            Self::OutdatedVersion(_) => 0,
+            Self::WrongNetwork => 1,
+            Self::BadGenesis => 2,
+            Self::Banned => 3,
+            Self::NodeTypeNotPermitted => 4,
+            Self::Other => 5,
+        }
+    }
+}
+
+impl fmt::Display for HandshakeFailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::error::Error for HandshakeFailureReason {}
+
+///
+/// The number of failed handshakes of peers with this node, by reason, since the node started.
+///
+#[derive(Debug, Default)]
+pub struct HandshakeFailures {
+    /// The number of failed handshakes, indexed by reason.
+    counts: [AtomicU64; HandshakeFailureReason::NUMBER_OF_REASONS],
+    /// The number of failed handshakes of outdated peers := (version, count)
+    outdated_versions: Mutex<BTreeMap<u32, u64>>,
+}
+
+impl HandshakeFailures {
+    /// Records a failed handshake for the given reason.
+    pub fn record(&self, reason: HandshakeFailureReason) {
+        self.counts[reason.index()].fetch_add(1, Ordering::SeqCst);
+        if let HandshakeFailureReason::OutdatedVersion(version) = reason {
+            *self.outdated_versions.lock().entry(version).or_default() += 1;
+        }
+    }
+
+    /// Returns the number of failed handshakes for each reason.
+    pub fn counts(&self) -> BTreeMap<&'static str, u64> {
+        [
+            HandshakeFailureReason::OutdatedVersion(0),
+            HandshakeFailureReason::WrongNetwork,
+            HandshakeFailureReason::BadGenesis,
+            HandshakeFailureReason::Banned,
+            HandshakeFailureReason::NodeTypeNotPermitted,
+            HandshakeFailureReason::Other,
+        ]
+        .iter()
+        .map(|reason| (reason.as_str(), self.counts[reason.index()].load(Ordering::SeqCst)))
+        .collect()
+    }
+
+    /// Returns the number of failed handshakes of outdated peers, by their message version.
+    pub fn outdated_versions(&self) -> BTreeMap<u32, u64> {
+        self.outdated_versions.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handshake_failures() {
+        let handshake_failures = HandshakeFailures::default();
+        assert!(handshake_failures.counts().values().all(|count| *count == 0));
+
+        handshake_failures.record(HandshakeFailureReason::OutdatedVersion(10));
+        handshake_failures.record(HandshakeFailureReason::OutdatedVersion(10));
+        handshake_failures.record(HandshakeFailureReason::OutdatedVersion(9));
+        handshake_failures.record(HandshakeFailureReason::Banned);
+
+        let counts = handshake_failures.counts();
+        assert_eq!(counts.len(), HandshakeFailureReason::NUMBER_OF_REASONS);
+        assert_eq!(counts["outdated_version"], 3);
+        assert_eq!(counts["banned"], 1);
+        assert_eq!(counts["wrong_network"], 0);
+
+        let outdated_versions = handshake_failures.outdated_versions();
+        assert_eq!(outdated_versions.into_iter().collect::<Vec<_>>(), vec![(9, 1), (10, 2)]);
+    }
+
+    #[test]
+    fn test_handshake_failure_reason_downcast() {
+        // The reason of a failed handshake is recovered from the error that carries it.
+        let error = anyhow::Error::new(HandshakeFailureReason::WrongNetwork).context("Dropping a peer on another network");
+        assert_eq!(error.to_string(), "Dropping a peer on another network");
+        assert_eq!(
+            error.downcast_ref::<HandshakeFailureReason>(),
+            Some(&HandshakeFailureReason::WrongNetwork)
+        );
+    }
+}
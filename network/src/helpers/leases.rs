@@ -0,0 +1,238 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::MAX_BASIS_POINTS;
+use snarkos_storage::Lease;
+use snarkvm::dpc::prelude::*;
+
+use anyhow::{anyhow, Result};
+use parking_lot::{Mutex, RwLock};
+use rand::Rng;
+use std::collections::{BTreeMap, BTreeSet};
+use time::OffsetDateTime;
+
+///
+/// The hashrate leases of the operator, including the expired leases for accounting.
+///
+/// The leases are persisted by the operator, which writes the leases updated since its last write on each heartbeat.
+///
+#[derive(Debug, Default)]
+pub struct Leases<N: Network> {
+    leases: RwLock<BTreeMap<u64, Lease<N>>>,
+    /// The IDs of the leases updated since they were last persisted.
+    updated: Mutex<BTreeSet<u64>>,
+}
+
+impl<N: Network> Leases<N> {
+    ///
+    /// Initializes the leases from the given leases, as restored from storage.
+    ///
+    pub fn new(leases: Vec<Lease<N>>) -> Self {
+        Self {
+            leases: RwLock::new(leases.into_iter().map(|lease| (lease.id, lease)).collect()),
+            updated: Default::default(),
+        }
+    }
+
+    ///
+    /// Creates a new lease of the given share of jobs for the given duration, returning the lease.
+    ///
+    pub fn create(&self, renter: Address<N>, basis_points: u16, duration_in_secs: u64) -> Result<Lease<N>> {
+        if basis_points == 0 || duration_in_secs == 0 {
+            return Err(anyhow!("A lease must have a non-zero share of jobs and duration"));
+        }
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let mut leases = self.leases.write();
+
+        // Ensure the active leases do not exceed the aggregate hashrate of the pool.
+        let leased_basis_points: u32 = leases
+            .values()
+            .filter(|lease| lease.is_active(now))
+            .map(|lease| lease.basis_points as u32)
+            .sum();
+        if leased_basis_points + basis_points as u32 > MAX_BASIS_POINTS as u32 {
+            return Err(anyhow!(
+                "Only {} basis points of the pool remain available to lease",
+                (MAX_BASIS_POINTS as u32).saturating_sub(leased_basis_points)
+            ));
+        }
+
+        let lease = Lease {
+            id: leases.keys().next_back().map_or(0, |id| id + 1),
+            renter,
+            basis_points,
+            created_at: now,
+            expires_at: now.saturating_add(i64::try_from(duration_in_secs).unwrap_or(i64::MAX)),
+            jobs: 0,
+            shares: 0,
+            blocks: Vec::new(),
+        };
+        leases.insert(lease.id, lease.clone());
+        self.updated.lock().insert(lease.id);
+        Ok(lease)
+    }
+
+    ///
+    /// Cancels the lease with the given ID, returning `false` if it does not exist or has already expired.
+    ///
+    pub fn cancel(&self, id: u64) -> bool {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        match self.leases.write().get_mut(&id) {
+            Some(lease) if lease.is_active(now) => {
+                lease.expires_at = now;
+                self.updated.lock().insert(id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    ///
+    /// Returns all leases, including the expired ones.
+    ///
+    pub fn to_leases(&self) -> Vec<Lease<N>> {
+        self.leases.read().values().cloned().collect()
+    }
+
+    ///
+    /// Returns the active leases := (lease_id, renter)
+    ///
+    pub fn active_leases(&self) -> Vec<(u64, Address<N>)> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        self.leases
+            .read()
+            .values()
+            .filter(|lease| lease.is_active(now))
+            .map(|lease| (lease.id, lease.renter))
+            .collect()
+    }
+
+    ///
+    /// Draws the lease the next job is built for, in proportion to the leased shares of jobs.
+    /// Returns `None` if the job is built for the operator.
+    ///
+    pub fn select<R: Rng>(&self, rng: &mut R) -> Option<u64> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let draw = rng.gen_range(0..MAX_BASIS_POINTS as u32);
+
+        let mut cumulative = 0u32;
+        for lease in self.leases.read().values().filter(|lease| lease.is_active(now)) {
+            cumulative += lease.basis_points as u32;
+            if draw < cumulative {
+                return Some(lease.id);
+            }
+        }
+        None
+    }
+
+    ///
+    /// Increments the job count of the given lease.
+    ///
+    pub fn record_job(&self, id: u64) {
+        if let Some(lease) = self.leases.write().get_mut(&id) {
+            lease.jobs = lease.jobs.saturating_add(1);
+            self.updated.lock().insert(id);
+        }
+    }
+
+    ///
+    /// Increments the share count of the given lease.
+    ///
+    pub fn record_share(&self, id: u64) {
+        if let Some(lease) = self.leases.write().get_mut(&id) {
+            lease.shares = lease.shares.saturating_add(1);
+            self.updated.lock().insert(id);
+        }
+    }
+
+    ///
+    /// Records a block found on a job of the given lease.
+    ///
+    pub fn record_block(&self, id: u64, block_height: u32, block_hash: N::BlockHash) {
+        if let Some(lease) = self.leases.write().get_mut(&id) {
+            lease.blocks.push((block_height, block_hash));
+            self.updated.lock().insert(id);
+        }
+    }
+
+    ///
+    /// Returns the leases updated since the last call, to be persisted.
+    ///
+    pub fn drain_updated(&self) -> Vec<Lease<N>> {
+        let updated = std::mem::take(&mut *self.updated.lock());
+        let leases = self.leases.read();
+        updated.iter().filter_map(|id| leases.get(id).cloned()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_leases_cannot_exceed_pool() {
+        let renter = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+        let leases = Leases::<CurrentNetwork>::default();
+
+        assert!(leases.create(renter, 6_000, 60).is_ok());
+        assert!(leases.create(renter, 5_000, 60).is_err());
+        assert!(leases.create(renter, 4_000, 60).is_ok());
+    }
+
+    #[test]
+    fn test_cancelled_leases_are_not_selected() {
+        let rng = &mut thread_rng();
+        let renter = Account::<CurrentNetwork>::new(rng).address();
+        let leases = Leases::<CurrentNetwork>::default();
+
+        // A lease of the entire pool receives every job.
+        let lease = leases.create(renter, MAX_BASIS_POINTS, 60).unwrap();
+        assert_eq!(leases.select(rng), Some(lease.id));
+
+        // Once cancelled, the lease receives no jobs, but remains for accounting.
+        assert!(leases.cancel(lease.id));
+        assert!(!leases.cancel(lease.id));
+        assert_eq!(leases.select(rng), None);
+        assert_eq!(leases.to_leases().len(), 1);
+        assert!(leases.active_leases().is_empty());
+    }
+
+    #[test]
+    fn test_updated_leases_are_drained() {
+        let rng = &mut thread_rng();
+        let renter = Account::<CurrentNetwork>::new(rng).address();
+        let leases = Leases::<CurrentNetwork>::default();
+
+        // Check a new lease is updated once, however often it is recorded.
+        let lease = leases.create(renter, 1_000, 60).unwrap();
+        leases.record_job(lease.id);
+        leases.record_share(lease.id);
+        let updated = leases.drain_updated();
+        assert_eq!(updated.len(), 1);
+        assert_eq!((updated[0].jobs, updated[0].shares), (1, 1));
+        assert!(leases.drain_updated().is_empty());
+
+        // Check the restored leases keep their IDs, and new leases are numbered after them.
+        let restored = Leases::<CurrentNetwork>::new(updated);
+        assert!(restored.drain_updated().is_empty());
+        assert_eq!(restored.create(renter, 1_000, 60).unwrap().id, lease.id + 1);
+        assert_eq!(restored.to_leases()[0].shares, 1);
+    }
+}
@@ -0,0 +1,405 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::{MemoryPoolPolicy, MemoryPoolRejection};
+use snarkvm::dpc::prelude::*;
+
+#[cfg(any(feature = "test", feature = "prometheus"))]
+use snarkos_metrics as metrics;
+
+use anyhow::Result;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{broadcast, Notify};
+
+/// The number of accepted transactions buffered for each subscriber, beyond which the slowest subscribers miss transactions.
+const ACCEPTED_TRANSACTIONS_CAPACITY: usize = 1024;
+/// The duration for which a local priority flag is kept while its transaction is not in the memory pool.
+const LOCAL_PRIORITY_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+///
+/// A memory pool that may be shared across tasks without serializing its readers.
+///
+/// Updates to the memory pool are serialized, and only invalidate the snapshot of its transactions, so an update
+/// never copies the memory pool. The first reader after an update rebuilds the snapshot, which later readers only clone,
+/// so a burst of updates copies the memory pool once, and a reader holding a snapshot never blocks an update.
+///
+/// Every transaction accepted into the memory pool is also published to its subscribers.
+///
+/// Transactions of this node, such as the payouts of the operator, may be flagged as local priority,
+/// which exempts them from the memory pool policy and places them first in the block templates of this node.
+/// A flag is dropped once its transaction is confirmed, or once it expires while its transaction is not in the memory pool.
+///
+pub struct SharedMemoryPool<N: Network> {
+    /// The memory pool, which guards against concurrent updates.
+    memory_pool: Mutex<MemoryPool<N>>,
+    /// The snapshot of the transactions in the memory pool := (version, transactions), which is stale if its version
+    /// is behind the version of the memory pool.
+    snapshot: RwLock<(u64, Arc<Vec<Transaction<N>>>)>,
+    /// The number of updates to the memory pool, which identifies its snapshot.
+    version: AtomicU64,
+    /// The notification of the updates to the memory pool.
+    updated: Notify,
+    /// The channel publishing the transactions accepted into the memory pool.
+    accepted_transactions: broadcast::Sender<Transaction<N>>,
+    /// The policy admitting unconfirmed transactions into the memory pool.
+    policy: RwLock<MemoryPoolPolicy>,
+    /// The IDs of the transactions flagged as local priority, with the time they were flagged at.
+    local_priority: RwLock<HashMap<N::TransactionID, Instant>>,
+}
+
+impl<N: Network> SharedMemoryPool<N> {
+    ///
+    /// Initializes a new instance of an empty `SharedMemoryPool`.
+    ///
+    pub fn new() -> Self {
+        Self {
+            memory_pool: Mutex::new(MemoryPool::new()),
+            snapshot: Default::default(),
+            version: Default::default(),
+            updated: Notify::new(),
+            accepted_transactions: broadcast::channel(ACCEPTED_TRANSACTIONS_CAPACITY).0,
+            policy: Default::default(),
+            local_priority: Default::default(),
+        }
+    }
+
+    ///
+    /// Returns the policy admitting unconfirmed transactions into the memory pool.
+    ///
+    pub fn policy(&self) -> MemoryPoolPolicy {
+        *self.policy.read()
+    }
+
+    ///
+    /// Sets the policy admitting unconfirmed transactions into the memory pool.
+    ///
+    pub fn set_policy(&self, policy: MemoryPoolPolicy) {
+        *self.policy.write() = policy;
+    }
+
+    ///
+    /// Checks the given unconfirmed transaction against the policy of the memory pool, counting any rejection.
+    ///
+    pub fn check_policy(&self, transaction: &Transaction<N>) -> Result<(), MemoryPoolRejection> {
+        let result = self.policy().check_transaction(transaction);
+        #[cfg(any(feature = "test", feature = "prometheus"))]
+        if let Err(rejection) = &result {
+            metrics::increment_counter!(metrics::memory_pool::REJECTED, "reason" => rejection.label());
+        }
+        result
+    }
+
+    ///
+    /// Flags the transaction with the given transaction ID as local priority, whether or not it is in the memory pool yet.
+    ///
+    pub fn set_local_priority(&self, transaction_id: N::TransactionID) {
+        self.local_priority.write().insert(transaction_id, Instant::now());
+    }
+
+    ///
+    /// Returns `true` if the transaction with the given transaction ID is flagged as local priority.
+    ///
+    pub fn is_local_priority(&self, transaction_id: &N::TransactionID) -> bool {
+        self.local_priority.read().contains_key(transaction_id)
+    }
+
+    ///
+    /// Returns the transactions in the memory pool, with the local priority transactions placed first.
+    ///
+    pub fn prioritized_transactions(&self) -> Vec<Transaction<N>> {
+        let mut transactions = self.transactions().to_vec();
+        let local_priority = self.local_priority.read();
+        // The sort is stable, so the transactions otherwise keep the order of the memory pool.
+        transactions.sort_by_key(|transaction| !local_priority.contains_key(&transaction.transaction_id()));
+        transactions
+    }
+
+    ///
+    /// Returns a snapshot of the transactions in the memory pool.
+    ///
+    pub fn transactions(&self) -> Arc<Vec<Transaction<N>>> {
+        {
+            let snapshot = self.snapshot.read();
+            if snapshot.0 == self.version() {
+                return snapshot.1.clone();
+            }
+        }
+        // Rebuild the stale snapshot while holding the memory pool, so the snapshot matches the version it is taken at.
+        let memory_pool = self.memory_pool.lock();
+        let mut snapshot = self.snapshot.write();
+        let version = self.version();
+        if snapshot.0 != version {
+            *snapshot = (version, Arc::new(memory_pool.transactions()));
+        }
+        snapshot.1.clone()
+    }
+
+    ///
+    /// Returns the number of transactions in the memory pool.
+    ///
+    pub fn len(&self) -> usize {
+        self.transactions().len()
+    }
+
+    ///
+    /// Returns `true` if the memory pool contains no transactions.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.transactions().is_empty()
+    }
+
+    ///
+    /// Returns the number of updates to the memory pool, which changes whenever its snapshot does.
+    ///
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    ///
+    /// Returns a summary of the transactions in the memory pool, without their contents.
+    ///
+    pub fn summary(&self) -> MemoryPoolSummary {
+        // Serializing a transaction into a buffer does not fail.
+        MemoryPoolSummary::new(self.transactions().iter().map(|transaction| {
+            let size_in_bytes = transaction.to_bytes_le().map(|bytes| bytes.len()).unwrap_or_default();
+            (size_in_bytes, transaction.value_balance().0)
+        }))
+    }
+
+    ///
+    /// Waits until the memory pool is updated past the given version, or the given timeout elapses.
+    ///
+    pub async fn wait_for_update(&self, version: u64, timeout: Duration) {
+        // Register for the notification before checking the version, so that no update is missed.
+        let updated = self.updated.notified();
+        if self.version() == version {
+            let _ = tokio::time::timeout(timeout, updated).await;
+        }
+    }
+
+    ///
+    /// Returns a receiver of the transactions accepted into the memory pool from now on.
+    ///
+    pub fn subscribe(&self) -> broadcast::Receiver<Transaction<N>> {
+        self.accepted_transactions.subscribe()
+    }
+
+    ///
+    /// Adds the given unconfirmed transaction to the memory pool, and publishes it to the subscribers.
+    ///
+    pub fn add_transaction(&self, transaction: &Transaction<N>) -> Result<()> {
+        let mut memory_pool = self.memory_pool.lock();
+        memory_pool.add_transaction(transaction)?;
+        // Publish while still holding the memory pool, so transactions are published in the order they are accepted.
+        // Sending only fails if there are no subscribers.
+        let _ = self.accepted_transactions.send(transaction.clone());
+        self.invalidate_snapshot();
+        Ok(())
+    }
+
+    ///
+    /// Removes the given transactions from the memory pool, if they exist.
+    ///
+    pub fn remove_transactions(&self, transactions: &Transactions<N>) {
+        self.update(|memory_pool| memory_pool.remove_transactions(transactions));
+        // The confirmed transactions no longer need priority.
+        {
+            let mut local_priority = self.local_priority.write();
+            for transaction_id in transactions.transaction_ids() {
+                local_priority.remove(&transaction_id);
+            }
+        }
+        self.prune_local_priority(Instant::now());
+    }
+
+    ///
+    /// Removes all transactions from the memory pool.
+    ///
+    pub fn clear(&self) {
+        self.update(|memory_pool| *memory_pool = MemoryPool::new());
+        self.prune_local_priority(Instant::now());
+    }
+
+    ///
+    /// Drops the local priority flags which expired by the given time while their transactions are not in the memory pool,
+    /// such as the flags of evicted transactions, or of transactions which never reached this node.
+    ///
+    fn prune_local_priority(&self, now: Instant) {
+        if self.local_priority.read().is_empty() {
+            return;
+        }
+        let transactions = self.transactions();
+        let transaction_ids: HashSet<_> = transactions.iter().map(|transaction| transaction.transaction_id()).collect();
+        self.local_priority.write().retain(|transaction_id, flagged_at| {
+            transaction_ids.contains(transaction_id) || now.saturating_duration_since(*flagged_at) < LOCAL_PRIORITY_TIMEOUT
+        });
+    }
+
+    ///
+    /// Applies the given update to the memory pool, and invalidates the snapshot of its transactions.
+    ///
+    fn update<T>(&self, update: impl FnOnce(&mut MemoryPool<N>) -> T) -> T {
+        let mut memory_pool = self.memory_pool.lock();
+        let output = update(&mut memory_pool);
+        self.invalidate_snapshot();
+        output
+    }
+
+    ///
+    /// Advances the version of the memory pool past its snapshot, and notifies the waiters of the update.
+    /// This must be called while holding the memory pool, so that snapshots are rebuilt in order.
+    ///
+    fn invalidate_snapshot(&self) {
+        self.version.fetch_add(1, Ordering::SeqCst);
+        self.updated.notify_waiters();
+    }
+}
+
+impl<N: Network> Default for SharedMemoryPool<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// A summary of the transactions in the memory pool, for monitoring it without downloading its transactions.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryPoolSummary {
+    /// The number of transactions in the memory pool.
+    pub number_of_transactions: usize,
+    /// The total serialized size of the transactions, in bytes.
+    pub size_in_bytes: usize,
+    /// The total fees paid by the transactions, in gates.
+    pub total_fees: i64,
+    /// The minimum value balance of a transaction, in gates, if the memory pool is not empty.
+    pub minimum_value_balance: Option<i64>,
+    /// The maximum value balance of a transaction, in gates, if the memory pool is not empty.
+    pub maximum_value_balance: Option<i64>,
+    /// The median value balance of the transactions, in gates, if the memory pool is not empty.
+    pub median_value_balance: Option<i64>,
+}
+
+impl MemoryPoolSummary {
+    ///
+    /// Summarizes the transactions of the given serialized sizes in bytes and value balances in gates.
+    ///
+    pub fn new(transactions: impl Iterator<Item = (usize, i64)>) -> Self {
+        let mut summary = Self::default();
+        let mut value_balances = Vec::new();
+        for (size_in_bytes, value_balance) in transactions {
+            summary.number_of_transactions += 1;
+            summary.size_in_bytes = summary.size_in_bytes.saturating_add(size_in_bytes);
+            // The fee of a transaction is its value balance, which is only negative for a coinbase transaction.
+            summary.total_fees = summary.total_fees.saturating_add(value_balance.max(0));
+            value_balances.push(value_balance);
+        }
+
+        value_balances.sort_unstable();
+        summary.minimum_value_balance = value_balances.first().copied();
+        summary.maximum_value_balance = value_balances.last().copied();
+        summary.median_value_balance = value_balances.get(value_balances.len() / 2).copied();
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    #[test]
+    fn test_snapshot_follows_updates() {
+        let memory_pool = SharedMemoryPool::<CurrentNetwork>::new();
+        let genesis_block = CurrentNetwork::genesis_block();
+        let transaction = genesis_block.to_coinbase_transaction().unwrap();
+        assert!(memory_pool.is_empty());
+
+        // Check the snapshot is rebuilt after an update.
+        let empty_snapshot = memory_pool.transactions();
+        memory_pool.add_transaction(&transaction).unwrap();
+        assert_eq!(memory_pool.version(), 1);
+        assert_eq!(*memory_pool.transactions(), vec![transaction.clone()]);
+        // A snapshot held across an update is left unchanged.
+        assert!(empty_snapshot.is_empty());
+
+        // Check the snapshot is shared by the readers until the next update.
+        assert!(Arc::ptr_eq(&memory_pool.transactions(), &memory_pool.transactions()));
+
+        // Check a rejected transaction does not invalidate the snapshot.
+        let snapshot = memory_pool.transactions();
+        assert!(memory_pool.add_transaction(&transaction).is_err());
+        assert_eq!(memory_pool.version(), 1);
+        assert!(Arc::ptr_eq(&snapshot, &memory_pool.transactions()));
+
+        // Check the removal of the transaction.
+        memory_pool.remove_transactions(genesis_block.transactions());
+        assert_eq!(memory_pool.version(), 2);
+        assert!(memory_pool.is_empty());
+        assert_eq!(snapshot.len(), 1);
+    }
+
+    #[test]
+    fn test_local_priority_expires() {
+        let memory_pool = SharedMemoryPool::<CurrentNetwork>::new();
+        let genesis_block = CurrentNetwork::genesis_block();
+        let transaction = genesis_block.to_coinbase_transaction().unwrap();
+        let transaction_id = transaction.transaction_id();
+
+        // Check a flag is kept while its transaction is in the memory pool, however long ago it was set.
+        memory_pool.set_local_priority(transaction_id);
+        memory_pool.add_transaction(&transaction).unwrap();
+        memory_pool.prune_local_priority(Instant::now() + LOCAL_PRIORITY_TIMEOUT);
+        assert!(memory_pool.is_local_priority(&transaction_id));
+
+        // Check the flag of an evicted transaction is kept until it expires.
+        memory_pool.clear();
+        assert!(memory_pool.is_local_priority(&transaction_id));
+        memory_pool.prune_local_priority(Instant::now() + LOCAL_PRIORITY_TIMEOUT);
+        assert!(!memory_pool.is_local_priority(&transaction_id));
+
+        // Check the flag of a confirmed transaction is dropped.
+        memory_pool.set_local_priority(transaction_id);
+        memory_pool.remove_transactions(genesis_block.transactions());
+        assert!(!memory_pool.is_local_priority(&transaction_id));
+    }
+
+    #[test]
+    fn test_memory_pool_summary() {
+        // Check an empty memory pool has no value balances.
+        let summary = MemoryPoolSummary::new(std::iter::empty());
+        assert_eq!(summary, MemoryPoolSummary::default());
+        assert_eq!(summary.median_value_balance, None);
+
+        // Check the sizes and fees are totaled, and the value balances ordered.
+        let summary = MemoryPoolSummary::new(vec![(1200, 300), (800, 0), (1000, 100), (1500, -50)].into_iter());
+        assert_eq!(summary.number_of_transactions, 4);
+        assert_eq!(summary.size_in_bytes, 4500);
+        assert_eq!(summary.total_fees, 400);
+        assert_eq!(summary.minimum_value_balance, Some(-50));
+        assert_eq!(summary.maximum_value_balance, Some(300));
+        assert_eq!(summary.median_value_balance, Some(100));
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::prelude::*;
+
+use std::fmt;
+
+///
+/// The policy admitting unconfirmed transactions into the memory pool, beyond their validity.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MemoryPoolPolicy {
+    /// The minimum fee per byte of a transaction, in gates.
+    pub minimum_fee_per_byte: u64,
+    /// The maximum number of transitions in a transaction, if limited.
+    pub maximum_transitions: Option<usize>,
+    /// If `true`, transactions that pay no fee and have no value balance in any transition are rejected.
+    pub reject_zero_value: bool,
+}
+
+impl Default for MemoryPoolPolicy {
+    fn default() -> Self {
+        Self {
+            minimum_fee_per_byte: 0,
+            maximum_transitions: None,
+            reject_zero_value: false,
+        }
+    }
+}
+
+///
+/// The reason an unconfirmed transaction is rejected by the memory pool policy.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MemoryPoolRejection {
+    /// The transaction pays less than the minimum fee for its size.
+    InsufficientFee { fee: i64, minimum_fee: u64 },
+    /// The transaction has more transitions than permitted.
+    ExcessiveTransitions { transitions: usize, maximum_transitions: usize },
+    /// The transaction pays no fee and has no value balance.
+    ZeroValue,
+}
+
+impl MemoryPoolRejection {
+    /// Returns the label of the rejection, as counted in the metrics.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::InsufficientFee { .. } => "insufficient_fee",
+            Self::ExcessiveTransitions { .. } => "excessive_transitions",
+            Self::ZeroValue => "zero_value",
+        }
+    }
+}
+
+impl fmt::Display for MemoryPoolRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientFee { fee, minimum_fee } => {
+                write!(f, "pays a fee of {} gates, below the minimum of {} gates", fee, minimum_fee)
+            }
+            Self::ExcessiveTransitions {
+                transitions,
+                maximum_transitions,
+            } => {
+                write!(f, "has {} transitions, above the maximum of {}", transitions, maximum_transitions)
+            }
+            Self::ZeroValue => write!(f, "pays no fee and transfers no value"),
+        }
+    }
+}
+
+impl MemoryPoolPolicy {
+    ///
+    /// Checks the given transaction against the policy.
+    ///
+    pub fn check_transaction<N: Network>(&self, transaction: &Transaction<N>) -> Result<(), MemoryPoolRejection> {
+        // Serializing the transaction into a buffer does not fail.
+        let size_in_bytes = transaction.to_bytes_le().map(|bytes| bytes.len()).unwrap_or_default();
+        let value_balances: Vec<i64> = transaction
+            .transitions()
+            .iter()
+            .map(|transition| transition.value_balance().0)
+            .collect();
+        self.check(transaction.value_balance().0, size_in_bytes, &value_balances)
+    }
+
+    ///
+    /// Checks a transaction with the given fee in gates, size in bytes, and value balance of each transition against the policy.
+    ///
+    pub fn check(&self, fee: i64, size_in_bytes: usize, value_balances: &[i64]) -> Result<(), MemoryPoolRejection> {
+        if let Some(maximum_transitions) = self.maximum_transitions {
+            if value_balances.len() > maximum_transitions {
+                return Err(MemoryPoolRejection::ExcessiveTransitions {
+                    transitions: value_balances.len(),
+                    maximum_transitions,
+                });
+            }
+        }
+
+        let minimum_fee = self.minimum_fee_per_byte.saturating_mul(size_in_bytes as u64);
+        if minimum_fee > 0 && (fee.max(0) as u64) < minimum_fee {
+            return Err(MemoryPoolRejection::InsufficientFee { fee, minimum_fee });
+        }
+
+        if self.reject_zero_value && fee == 0 && value_balances.iter().all(|value_balance| *value_balance == 0) {
+            return Err(MemoryPoolRejection::ZeroValue);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_admits_all() {
+        let policy = MemoryPoolPolicy::default();
+
+        assert_eq!(policy.check(0, 1000, &[0, 0]), Ok(()));
+        assert_eq!(policy.check(0, 1000, &[0; 64]), Ok(()));
+        // A coinbase transaction has a negative fee.
+        assert_eq!(policy.check(-100, 1000, &[-100]), Ok(()));
+    }
+
+    #[test]
+    fn test_minimum_fee() {
+        let policy = MemoryPoolPolicy {
+            minimum_fee_per_byte: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.check(2000, 1000, &[0]), Ok(()));
+        assert_eq!(
+            policy.check(-100, 1000, &[-100]),
+            Err(MemoryPoolRejection::InsufficientFee {
+                fee: -100,
+                minimum_fee: 2000
+            })
+        );
+        assert_eq!(
+            policy.check(1999, 1000, &[0]),
+            Err(MemoryPoolRejection::InsufficientFee {
+                fee: 1999,
+                minimum_fee: 2000
+            })
+        );
+    }
+
+    #[test]
+    fn test_maximum_transitions() {
+        let policy = MemoryPoolPolicy {
+            maximum_transitions: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(policy.check(0, 1000, &[0, 0]), Ok(()));
+        assert_eq!(
+            policy.check(0, 1000, &[0, 0, 0]),
+            Err(MemoryPoolRejection::ExcessiveTransitions {
+                transitions: 3,
+                maximum_transitions: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_zero_value() {
+        let policy = MemoryPoolPolicy {
+            reject_zero_value: true,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.check(0, 1000, &[0, 0]), Err(MemoryPoolRejection::ZeroValue));
+        assert_eq!(policy.check(1, 1000, &[0, 0]), Ok(()));
+        // A coinbase transition has a negative value balance.
+        assert_eq!(policy.check(0, 1000, &[-100, 0]), Ok(()));
+    }
+}
@@ -14,11 +14,95 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod audit_events;
+pub use audit_events::*;
+
 pub mod block_request;
 pub use block_request::*;
 
 pub mod block_requests;
 pub use block_requests::*;
 
+pub mod block_tag;
+pub use block_tag::*;
+
+pub mod block_template_ids;
+pub use block_template_ids::*;
+
+pub mod chain_split;
+pub use chain_split::*;
+
+pub mod circuit_breaker;
+pub use circuit_breaker::*;
+
 pub mod circular_map;
 pub use circular_map::*;
+
+pub mod connection_stats;
+pub use connection_stats::*;
+
+pub mod fault_injector;
+pub use fault_injector::*;
+
+pub mod handshake_failures;
+pub use handshake_failures::*;
+
+pub mod leases;
+pub use leases::*;
+
+pub mod memory_pool;
+pub use memory_pool::*;
+
+pub mod memory_pool_policy;
+pub use memory_pool_policy::*;
+
+pub mod payout_policy;
+pub use payout_policy::*;
+
+pub mod payouts;
+pub use payouts::*;
+
+pub mod pending_shares;
+pub use pending_shares::*;
+
+pub mod peer_info;
+pub use peer_info::*;
+
+pub mod peers_config;
+pub use peers_config::*;
+
+pub mod pool_client;
+pub use pool_client::*;
+
+pub mod pool_stats;
+pub use pool_stats::*;
+
+pub mod pool_tls;
+pub use pool_tls::*;
+
+pub mod propagation_policy;
+pub use propagation_policy::*;
+
+pub mod record_selection;
+pub use record_selection::*;
+
+pub mod retention_policy;
+pub use retention_policy::*;
+
+pub mod serving_scheduler;
+pub use serving_scheduler::*;
+
+pub mod session_recording;
+pub use session_recording::*;
+
+pub mod share_sampling;
+pub use share_sampling::*;
+
+pub mod share_timestamps;
+pub use share_timestamps::*;
+
+pub mod sub_pools;
+pub use sub_pools::*;
+
+pub mod sync_progress;
+pub use sync_progress::*;
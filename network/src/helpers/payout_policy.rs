@@ -0,0 +1,181 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::RecordSelection;
+use snarkos_storage::PayoutSchedule;
+
+use anyhow::{anyhow, Result};
+use std::ops::RangeInclusive;
+
+/// The default minimum payout of a prover, in gates.
+pub const DEFAULT_MINIMUM_PAYOUT: u64 = 1_000_000;
+/// The default number of blocks between two payouts to a prover.
+pub const DEFAULT_PAYOUT_INTERVAL: u32 = 180;
+
+///
+/// The payout schedules that provers may choose, as bounded by the operator,
+/// and the payout schedule of the provers which have not chosen their own.
+///
+#[derive(Clone, Debug)]
+pub struct PayoutPolicy {
+    /// The payout schedule of the provers which have not chosen their own.
+    pub default_schedule: PayoutSchedule,
+    /// The minimum payouts that provers may choose, in gates.
+    pub minimum_payouts: RangeInclusive<u64>,
+    /// The payout intervals that provers may choose, in blocks.
+    pub payout_intervals: RangeInclusive<u32>,
+    /// The strategy for selecting the records of the pool wallet that fund a payout transaction.
+    pub record_selection: RecordSelection,
+}
+
+impl Default for PayoutPolicy {
+    fn default() -> Self {
+        Self {
+            default_schedule: PayoutSchedule {
+                minimum_payout: DEFAULT_MINIMUM_PAYOUT,
+                payout_interval: DEFAULT_PAYOUT_INTERVAL,
+            },
+            minimum_payouts: 0..=u64::MAX,
+            payout_intervals: 1..=u32::MAX,
+            record_selection: RecordSelection::default(),
+        }
+    }
+}
+
+impl PayoutPolicy {
+    ///
+    /// Initializes a new payout policy, ensuring the default payout schedule is within the given bounds.
+    ///
+    pub fn new(
+        default_schedule: PayoutSchedule,
+        minimum_payouts: RangeInclusive<u64>,
+        payout_intervals: RangeInclusive<u32>,
+    ) -> Result<Self> {
+        if minimum_payouts.is_empty() || payout_intervals.is_empty() {
+            return Err(anyhow!("The bounds of the payout schedules must not be empty"));
+        }
+        if *payout_intervals.start() == 0 {
+            return Err(anyhow!("The payout interval must be at least one block"));
+        }
+        let policy = Self {
+            default_schedule,
+            minimum_payouts,
+            payout_intervals,
+            record_selection: RecordSelection::default(),
+        };
+        policy.check(&default_schedule)?;
+        Ok(policy)
+    }
+
+    ///
+    /// Ensures the given payout schedule is within the bounds of the policy.
+    ///
+    pub fn check(&self, schedule: &PayoutSchedule) -> Result<()> {
+        if !self.minimum_payouts.contains(&schedule.minimum_payout) {
+            return Err(anyhow!(
+                "The minimum payout must be between {} and {} gates",
+                self.minimum_payouts.start(),
+                self.minimum_payouts.end()
+            ));
+        }
+        if !self.payout_intervals.contains(&schedule.payout_interval) {
+            return Err(anyhow!(
+                "The payout interval must be between {} and {} blocks",
+                self.payout_intervals.start(),
+                self.payout_intervals.end()
+            ));
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the payout schedule of a prover which chose the given schedule, if any,
+    /// brought within the bounds of the policy, as the bounds may have changed since it was chosen.
+    ///
+    pub fn apply(&self, schedule: Option<PayoutSchedule>) -> PayoutSchedule {
+        match schedule {
+            Some(schedule) => PayoutSchedule {
+                minimum_payout: schedule
+                    .minimum_payout
+                    .clamp(*self.minimum_payouts.start(), *self.minimum_payouts.end()),
+                payout_interval: schedule
+                    .payout_interval
+                    .clamp(*self.payout_intervals.start(), *self.payout_intervals.end()),
+            },
+            None => self.default_schedule,
+        }
+    }
+
+    ///
+    /// Returns `true` if a prover on the given payout schedule is due to be paid its balance at the given block height,
+    /// which is once the balance reaches the minimum payout, and the payout interval has passed since its last payout, if any.
+    ///
+    pub fn is_payout_due(schedule: &PayoutSchedule, balance: u64, last_payout_height: Option<u32>, block_height: u32) -> bool {
+        let has_interval_passed = match last_payout_height {
+            Some(last_payout_height) => block_height.saturating_sub(last_payout_height) >= schedule.payout_interval,
+            None => true,
+        };
+        balance > 0 && balance >= schedule.minimum_payout && has_interval_passed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(minimum_payout: u64, payout_interval: u32) -> PayoutSchedule {
+        PayoutSchedule {
+            minimum_payout,
+            payout_interval,
+        }
+    }
+
+    #[test]
+    fn test_payout_policy_bounds() {
+        let policy = PayoutPolicy::new(schedule(1_000, 10), 100..=10_000, 5..=50).unwrap();
+
+        // Check the schedules within the bounds are accepted.
+        assert!(policy.check(&schedule(100, 5)).is_ok());
+        assert!(policy.check(&schedule(10_000, 50)).is_ok());
+
+        // Check the schedules beyond the bounds are rejected.
+        assert!(policy.check(&schedule(99, 10)).is_err());
+        assert!(policy.check(&schedule(1_000, 51)).is_err());
+
+        // Check a prover without a schedule is paid on the default schedule, and a stale schedule is clamped to the bounds.
+        assert_eq!(policy.apply(None), schedule(1_000, 10));
+        assert_eq!(policy.apply(Some(schedule(50_000, 1))), schedule(10_000, 5));
+
+        // Check the default schedule must be within the bounds, and the bounds must be valid.
+        assert!(PayoutPolicy::new(schedule(10, 10), 100..=10_000, 5..=50).is_err());
+        assert!(PayoutPolicy::new(schedule(1_000, 10), 10_000..=100, 5..=50).is_err());
+        assert!(PayoutPolicy::new(schedule(1_000, 0), 100..=10_000, 0..=50).is_err());
+    }
+
+    #[test]
+    fn test_is_payout_due() {
+        let payout_schedule = schedule(1_000, 10);
+
+        // Check a balance below the minimum payout is not paid.
+        assert!(!PayoutPolicy::is_payout_due(&payout_schedule, 999, None, 100));
+        // Check a balance at the minimum payout is paid, once the payout interval has passed.
+        assert!(PayoutPolicy::is_payout_due(&payout_schedule, 1_000, None, 100));
+        assert!(!PayoutPolicy::is_payout_due(&payout_schedule, 1_000, Some(95), 100));
+        assert!(PayoutPolicy::is_payout_due(&payout_schedule, 1_000, Some(90), 100));
+        // Check an empty balance is never paid, even without a minimum payout.
+        assert!(!PayoutPolicy::is_payout_due(&schedule(0, 1), 0, None, 100));
+    }
+}
@@ -0,0 +1,308 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::{SubPool, MAX_BASIS_POINTS};
+use snarkos_storage::{PayoutBatch, PayoutSchedule};
+use snarkvm::dpc::prelude::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+///
+/// The payout of an address for a round, as it was paid and as it is recomputed under a payout configuration.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PayoutDiff<N: Network> {
+    /// The address receiving the payout.
+    pub address: Address<N>,
+    /// The amount that was paid to the address.
+    pub paid: u64,
+    /// The amount that is owed to the address under the payout configuration.
+    pub recomputed: u64,
+}
+
+impl<N: Network> PayoutDiff<N> {
+    ///
+    /// Returns the amount the address was underpaid by, or overpaid by if negative.
+    ///
+    pub fn difference(&self) -> i64 {
+        self.recomputed as i64 - self.paid as i64
+    }
+}
+
+///
+/// The payouts of a block found by the operator, replayed under a payout configuration.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PayoutReplay<N: Network> {
+    /// The hash of the found block.
+    pub block_hash: N::BlockHash,
+    /// The reward of the block, as the value of its coinbase record.
+    pub reward: u64,
+    /// The total amount that was paid for the block, in the payout batches confirmed as paid.
+    pub paid: u64,
+    /// The total amount that is owed for the block under the payout configuration.
+    pub recomputed: u64,
+    /// The addresses whose recomputed payout differs from what they were paid.
+    pub diffs: Vec<PayoutDiff<N>>,
+}
+
+///
+/// Returns the message signed by a prover to redirect its payouts to the given payout address,
+/// from the round with the given block height.
+///
+pub fn payout_address_message<N: Network>(prover: &Address<N>, payout_address: &Address<N>, effective_from: u32) -> String {
+    format!(
+        "{} payout address update: {} to {} from block {}",
+        N::NETWORK_NAME,
+        prover,
+        payout_address,
+        effective_from
+    )
+}
+
+///
+/// Returns the message signed by a prover to be paid on the given payout schedule, from the given block height.
+///
+pub fn payout_schedule_message<N: Network>(prover: &Address<N>, schedule: &PayoutSchedule, effective_from: u32) -> String {
+    format!(
+        "{} payout schedule update: {} paid from {} gates every {} blocks from block {}",
+        N::NETWORK_NAME,
+        prover,
+        schedule.minimum_payout,
+        schedule.payout_interval,
+        effective_from
+    )
+}
+
+///
+/// Splits the reward of a round between the provers, in proportion to their shares.
+///
+/// The share of each prover, net of fees, is paid to its payout address.
+/// The fee of the sub-pool of each prover is paid to the payout address of the sub-pool, or else to the operator.
+/// A prover which is not admitted to any sub-pool is paid without a fee, as is a prover whose fee has no recipient.
+/// The remainder from rounding, or the whole reward if there are no shares, is paid to the operator if it has an address.
+///
+pub fn compute_payouts<'a, N: Network, F, G>(
+    reward: u64,
+    shares: &HashMap<Address<N>, u64>,
+    operator_address: Option<Address<N>>,
+    get_sub_pool: F,
+    get_payout_address: G,
+) -> HashMap<Address<N>, u64>
+where
+    F: Fn(&Address<N>) -> Option<&'a SubPool<N>>,
+    G: Fn(&Address<N>) -> Address<N>,
+{
+    let mut payouts = HashMap::new();
+    let mut credit = |address: Address<N>, amount: u64| {
+        if amount > 0 {
+            let entry = payouts.entry(address).or_insert(0u64);
+            *entry = entry.saturating_add(amount);
+        }
+    };
+
+    let total_shares: u128 = shares.values().map(|shares| *shares as u128).sum();
+    let mut distributed = 0u64;
+    if total_shares > 0 {
+        for (prover, prover_shares) in shares {
+            let gross_reward = (reward as u128 * *prover_shares as u128 / total_shares) as u64;
+            let payout_address = get_payout_address(prover);
+            let fee_recipient = get_sub_pool(prover).and_then(|sub_pool| {
+                let fee_address = sub_pool.payout_address.or(operator_address)?;
+                Some((sub_pool.fee_basis_points, fee_address))
+            });
+            match fee_recipient {
+                Some((fee_basis_points, fee_address)) => {
+                    let fee = (gross_reward as u128 * fee_basis_points as u128 / MAX_BASIS_POINTS as u128) as u64;
+                    credit(payout_address, gross_reward - fee);
+                    credit(fee_address, fee);
+                }
+                None => credit(payout_address, gross_reward),
+            }
+            distributed += gross_reward;
+        }
+    }
+
+    if let Some(operator_address) = operator_address {
+        credit(operator_address, reward - distributed);
+    }
+    payouts
+}
+
+///
+/// Returns the addresses whose recomputed payout differs from what they were paid, ordered by address.
+///
+pub fn diff_payouts<N: Network>(paid: &HashMap<Address<N>, u64>, recomputed: &HashMap<Address<N>, u64>) -> Vec<PayoutDiff<N>> {
+    let addresses: HashSet<&Address<N>> = paid.keys().chain(recomputed.keys()).collect();
+    let mut diffs: Vec<_> = addresses
+        .into_iter()
+        .map(|address| PayoutDiff {
+            address: *address,
+            paid: paid.get(address).copied().unwrap_or(0),
+            recomputed: recomputed.get(address).copied().unwrap_or(0),
+        })
+        .filter(|diff| diff.paid != diff.recomputed)
+        .collect();
+    diffs.sort_unstable_by_key(|diff| diff.address.to_string());
+    diffs
+}
+
+///
+/// Returns the maximum number of addresses paid in a single transaction of the network.
+///
+/// Each transition of a payout transaction pays one address, with its other output records holding the change,
+/// so the number of addresses is bounded by the number of transitions in a transaction.
+///
+pub fn maximum_payouts_per_transaction<N: Network>() -> usize {
+    (N::NUM_TRANSITIONS as usize) * (N::NUM_OUTPUT_RECORDS - 1)
+}
+
+///
+/// Returns the maximum number of records spent by a single transaction of the network.
+///
+pub fn maximum_inputs_per_transaction<N: Network>() -> usize {
+    (N::NUM_TRANSITIONS as usize) * N::NUM_INPUT_RECORDS
+}
+
+///
+/// Splits the given payouts into batches of at most the given number of addresses, each paid in a single transaction.
+/// The addresses are ordered by address, and addresses with an empty payout are skipped.
+///
+pub fn batch_payouts<N: Network>(payouts: &HashMap<Address<N>, u64>, maximum_payouts: usize) -> Vec<PayoutBatch<N>> {
+    let mut payouts: Vec<_> = payouts
+        .iter()
+        .filter(|(_, amount)| **amount > 0)
+        .map(|(address, amount)| (*address, *amount))
+        .collect();
+    payouts.sort_unstable_by_key(|(address, _)| address.to_string());
+    payouts
+        .chunks(maximum_payouts.max(1))
+        .map(|payouts| PayoutBatch {
+            payouts: payouts.to_vec(),
+            transaction_id: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    use rand::thread_rng;
+
+    fn sub_pool(fee_basis_points: u16, payout_address: Option<Address<CurrentNetwork>>) -> SubPool<CurrentNetwork> {
+        SubPool {
+            name: "public".to_string(),
+            fee_basis_points,
+            payout_address,
+            provers: None,
+            server_name: None,
+            require_client_cert: false,
+        }
+    }
+
+    #[test]
+    fn test_compute_payouts() {
+        let rng = &mut thread_rng();
+        let operator = Account::<CurrentNetwork>::new(rng).address();
+        let first_prover = Account::<CurrentNetwork>::new(rng).address();
+        let second_prover = Account::<CurrentNetwork>::new(rng).address();
+        let shares: HashMap<_, _> = [(first_prover, 3), (second_prover, 1)].into_iter().collect();
+
+        // A 1% fee is paid to the operator, along with the remainder from rounding.
+        let sub_pool = sub_pool(100, None);
+        let payouts = compute_payouts(1_001, &shares, Some(operator), |_| Some(&sub_pool), |prover| *prover);
+        assert_eq!(payouts[&first_prover], 743);
+        assert_eq!(payouts[&second_prover], 248);
+        assert_eq!(payouts[&operator], 10);
+        assert_eq!(payouts.values().sum::<u64>(), 1_001);
+
+        // Without a sub-pool, the provers are paid without a fee.
+        let payouts = compute_payouts(1_000, &shares, Some(operator), |_| None, |prover| *prover);
+        assert_eq!(payouts[&first_prover], 750);
+        assert_eq!(payouts[&second_prover], 250);
+        assert!(!payouts.contains_key(&operator));
+
+        // Without shares, the operator keeps the reward.
+        let payouts = compute_payouts(1_000, &HashMap::new(), Some(operator), |_| Some(&sub_pool), |prover| *prover);
+        assert_eq!(payouts, [(operator, 1_000)].into_iter().collect());
+
+        // A prover with an updated payout address is paid to it, net of fees.
+        let payout_address = Account::<CurrentNetwork>::new(rng).address();
+        let get_payout_address = |prover: &Address<CurrentNetwork>| if *prover == first_prover { payout_address } else { *prover };
+        let payouts = compute_payouts(1_001, &shares, Some(operator), |_| Some(&sub_pool), get_payout_address);
+        assert_eq!(payouts[&payout_address], 743);
+        assert_eq!(payouts[&second_prover], 248);
+        assert!(!payouts.contains_key(&first_prover));
+    }
+
+    #[test]
+    fn test_diff_payouts() {
+        let rng = &mut thread_rng();
+        let operator = Account::<CurrentNetwork>::new(rng).address();
+        let prover = Account::<CurrentNetwork>::new(rng).address();
+        let shares: HashMap<_, _> = [(prover, 1)].into_iter().collect();
+
+        // A fee that was charged at 10% instead of 1% is diffed per address.
+        let (charged, corrected) = (sub_pool(1_000, None), sub_pool(100, None));
+        let paid = compute_payouts(1_000, &shares, Some(operator), |_| Some(&charged), |prover| *prover);
+        let recomputed = compute_payouts(1_000, &shares, Some(operator), |_| Some(&corrected), |prover| *prover);
+        let diffs = diff_payouts(&paid, &recomputed);
+        assert_eq!(diffs.len(), 2);
+        for diff in diffs {
+            if diff.address == prover {
+                assert_eq!((diff.paid, diff.recomputed, diff.difference()), (900, 990, 90));
+            } else {
+                assert_eq!(
+                    (diff.address, diff.paid, diff.recomputed, diff.difference()),
+                    (operator, 100, 10, -90)
+                );
+            }
+        }
+
+        // Identical payouts have no diff.
+        assert!(diff_payouts(&paid, &paid).is_empty());
+    }
+
+    #[test]
+    fn test_batch_payouts() {
+        let rng = &mut thread_rng();
+        let mut payouts: HashMap<_, _> = (1..=5)
+            .map(|amount| (Account::<CurrentNetwork>::new(rng).address(), amount))
+            .collect();
+        payouts.insert(Account::<CurrentNetwork>::new(rng).address(), 0);
+
+        // The payouts are split into batches of at most the given size, skipping the empty payouts.
+        let batches = batch_payouts(&payouts, 2);
+        assert_eq!(batches.iter().map(|batch| batch.payouts.len()).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert!(batches.iter().all(|batch| batch.transaction_id.is_none()));
+        let batched: u64 = batches
+            .iter()
+            .flat_map(|batch| batch.payouts.iter().map(|(_, amount)| amount))
+            .sum();
+        assert_eq!(batched, 15);
+
+        // The batches are deterministic, and fit in a single transaction if possible.
+        assert_eq!(batch_payouts(&payouts, 2), batches);
+        let maximum_payouts = maximum_payouts_per_transaction::<CurrentNetwork>();
+        assert_eq!(batch_payouts(&payouts, maximum_payouts).len(), 1);
+        assert!(batch_payouts::<CurrentNetwork>(&HashMap::new(), 2).is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::ConnectionStats;
+use snarkos_environment::helpers::NodeType;
+
+use std::{sync::Arc, time::Instant};
+
+///
+/// The information this node maintains about a connected peer.
+///
+#[derive(Clone, Debug)]
+pub struct PeerInfo {
+    /// If `true`, this node initiated the connection to the peer.
+    pub is_outbound: bool,
+    /// The node type of the peer.
+    pub node_type: NodeType,
+    /// The message version of the peer; `0` until the peer has sent a `Ping`.
+    pub version: u32,
+    /// The latest block height reported by the peer.
+    pub block_height: u32,
+    /// If `true`, the peer is on a fork; `None` if unknown.
+    pub is_fork: Option<bool>,
+    /// The offset of the peer's clock from the local clock in seconds, as advertised in its handshake; `None` if unknown.
+    pub clock_offset: Option<i64>,
+    /// The timestamp of when the peer connected.
+    pub connected_at: Instant,
+    /// The traffic and latency of the connection to the peer.
+    pub stats: Arc<ConnectionStats>,
+}
+
+impl PeerInfo {
+    ///
+    /// Initializes a new instance of `PeerInfo`.
+    ///
+    pub fn new(is_outbound: bool, node_type: NodeType, stats: Arc<ConnectionStats>) -> Self {
+        Self {
+            is_outbound,
+            node_type,
+            version: 0,
+            block_height: 0,
+            is_fork: None,
+            clock_offset: None,
+            connected_at: Instant::now(),
+            stats,
+        }
+    }
+
+    ///
+    /// Returns the score of the peer, where a lower score indicates a less useful peer.
+    /// Peers on a fork always score lowest, followed by the peers that are furthest behind.
+    ///
+    pub fn score(&self) -> i64 {
+        match self.is_fork {
+            Some(true) => -1,
+            _ => self.block_height as i64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        let mut peer = PeerInfo::new(true, NodeType::Client, Default::default());
+        peer.block_height = 10;
+        assert_eq!(peer.score(), 10);
+
+        // A peer on a fork scores below a peer that has not reported its height.
+        peer.is_fork = Some(true);
+        assert!(peer.score() < PeerInfo::new(true, NodeType::Client, Default::default()).score());
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{helpers::PoolTlsConnector, ServingMode};
+use snarkos_environment::helpers::NodeType;
+
+use std::{collections::HashSet, net::SocketAddr, path::PathBuf, time::Duration};
+
+///
+/// The runtime configuration of the peers of a node, as specified by its operator.
+///
+#[derive(Clone, Debug, Default)]
+pub struct PeersConfig {
+    /// The node types permitted to connect inbound to this node; if empty, all node types are permitted.
+    /// Trusted and sync nodes are always permitted.
+    pub allowed_inbound_node_types: HashSet<NodeType>,
+    /// The number of connection slots reserved exclusively for outbound connections to sync and beacon nodes.
+    pub reserved_outbound_slots: usize,
+    /// The interval at which a fraction of the outbound peers is rotated; if `None`, peers are not rotated.
+    pub peer_rotation_interval: Option<Duration>,
+    /// The TLS configuration used by a prover to connect to its pool; if `None`, the pool is reached over plaintext TCP.
+    pub pool_tls: Option<PoolTlsConnector>,
+    /// The blocks this node serves to its peers; in `HeadersOnly` mode, requests for full blocks are declined.
+    pub serving_mode: ServingMode,
+    /// The directory in which the inbound message stream of each peer session is recorded; if `None`, sessions are not recorded.
+    pub session_directory: Option<PathBuf>,
+}
+
+impl PeersConfig {
+    ///
+    /// Returns `true` if an inbound peer of the given node type is permitted to connect.
+    ///
+    pub fn is_inbound_node_type_allowed(&self, node_type: NodeType) -> bool {
+        self.allowed_inbound_node_types.is_empty() || self.allowed_inbound_node_types.contains(&node_type)
+    }
+
+    ///
+    /// Returns the number of reserved outbound slots occupied, given the number of connected sync and beacon nodes.
+    ///
+    pub fn occupied_reserved_slots(&self, number_of_reserved_peers: usize) -> usize {
+        number_of_reserved_peers.min(self.reserved_outbound_slots)
+    }
+
+    ///
+    /// Returns the number of reserved outbound slots still vacant, given the number of connected sync and beacon nodes.
+    ///
+    pub fn vacant_reserved_slots(&self, number_of_reserved_peers: usize) -> usize {
+        self.reserved_outbound_slots.saturating_sub(number_of_reserved_peers)
+    }
+
+    ///
+    /// Returns `true` if the given number of connected peers, along with the vacant reserved outbound slots,
+    /// reaches the given maximum number of peers, so no further inbound peers may take up a slot.
+    ///
+    pub fn is_inbound_saturated(&self, number_of_peers: usize, number_of_reserved_peers: usize, maximum_number_of_peers: usize) -> bool {
+        number_of_peers + self.vacant_reserved_slots(number_of_reserved_peers) >= maximum_number_of_peers
+    }
+
+    ///
+    /// Returns the configuration enforced on an inbound peer of an operator whose slots are saturated, which only permits
+    /// provers to connect, or `None` if provers are not permitted to connect.
+    ///
+    pub fn saturated_inbound_config(&self) -> Option<Self> {
+        match self.is_inbound_node_type_allowed(NodeType::Prover) {
+            true => Some(Self {
+                allowed_inbound_node_types: [NodeType::Prover].into_iter().collect(),
+                ..self.clone()
+            }),
+            false => None,
+        }
+    }
+
+    ///
+    /// Returns `true` if the given inbound peer is permitted to connect, either by its node type, or as one of the given
+    /// exempt nodes. As the port of an inbound peer is ephemeral, exempt nodes are matched by their IP alone.
+    ///
+    pub fn is_inbound_peer_allowed<'a>(
+        &self,
+        peer_ip: SocketAddr,
+        node_type: NodeType,
+        exempt_nodes: impl IntoIterator<Item = &'a SocketAddr>,
+    ) -> bool {
+        self.is_inbound_node_type_allowed(node_type) || exempt_nodes.into_iter().any(|node| node.ip() == peer_ip.ip())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inbound_node_types() {
+        let config = PeersConfig::default();
+        assert!(config.is_inbound_node_type_allowed(NodeType::Client));
+        assert!(config.is_inbound_node_type_allowed(NodeType::Prover));
+
+        let config = PeersConfig {
+            allowed_inbound_node_types: [NodeType::Client].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(config.is_inbound_node_type_allowed(NodeType::Client));
+        assert!(!config.is_inbound_node_type_allowed(NodeType::Prover));
+    }
+
+    #[test]
+    fn test_reserved_slots() {
+        // By default, no slots are reserved, so the connected sync and beacon nodes count towards the peer limit.
+        let config = PeersConfig::default();
+        assert_eq!(config.occupied_reserved_slots(3), 0);
+        assert_eq!(config.vacant_reserved_slots(0), 0);
+
+        let config = PeersConfig {
+            reserved_outbound_slots: 2,
+            ..Default::default()
+        };
+        assert_eq!(config.occupied_reserved_slots(0), 0);
+        assert_eq!(config.vacant_reserved_slots(0), 2);
+        assert_eq!(config.occupied_reserved_slots(1), 1);
+        assert_eq!(config.vacant_reserved_slots(1), 1);
+        assert_eq!(config.occupied_reserved_slots(3), 2);
+        assert_eq!(config.vacant_reserved_slots(3), 0);
+    }
+
+    #[test]
+    fn test_operator_saturated_by_inbound_peers() {
+        let config = PeersConfig {
+            reserved_outbound_slots: 2,
+            ..Default::default()
+        };
+        // The vacant reserved outbound slots are withheld from inbound peers.
+        assert!(!config.is_inbound_saturated(17, 0, 20));
+        assert!(config.is_inbound_saturated(18, 0, 20));
+        assert!(!config.is_inbound_saturated(18, 1, 20));
+        assert!(config.is_inbound_saturated(19, 1, 20));
+
+        // Once saturated, an operator only accepts inbound provers.
+        let saturated_config = config.saturated_inbound_config().unwrap();
+        assert!(saturated_config.is_inbound_node_type_allowed(NodeType::Prover));
+        assert!(!saturated_config.is_inbound_node_type_allowed(NodeType::Client));
+        assert!(!saturated_config.is_inbound_node_type_allowed(NodeType::Operator));
+        assert_eq!(saturated_config.reserved_outbound_slots, 2);
+
+        // If provers are not permitted to connect, a saturated operator accepts no inbound peers.
+        let config = PeersConfig {
+            allowed_inbound_node_types: [NodeType::Client].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(config.saturated_inbound_config().is_none());
+    }
+
+    #[test]
+    fn test_exempt_nodes_are_matched_by_ip() {
+        let config = PeersConfig {
+            allowed_inbound_node_types: [NodeType::Client].into_iter().collect(),
+            ..Default::default()
+        };
+        let trusted_node: SocketAddr = "10.0.0.1:4132".parse().unwrap();
+
+        // An inbound connection from a trusted node arrives from an ephemeral port.
+        let inbound_ip: SocketAddr = "10.0.0.1:53712".parse().unwrap();
+        assert!(config.is_inbound_peer_allowed(inbound_ip, NodeType::Prover, &[trusted_node]));
+
+        // Another IP is not exempt, even on the port of the trusted node.
+        let other_ip: SocketAddr = "10.0.0.2:4132".parse().unwrap();
+        assert!(!config.is_inbound_peer_allowed(other_ip, NodeType::Prover, &[trusted_node]));
+        assert!(config.is_inbound_peer_allowed(other_ip, NodeType::Client, &[trusted_node]));
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::prelude::*;
+
+#[cfg(any(feature = "test", feature = "prometheus"))]
+use snarkos_metrics as metrics;
+
+use dashmap::{try_result::TryResult, DashMap};
+use std::collections::HashMap;
+
+///
+/// The shares accepted by the operator since they were last persisted, keyed by round and prover.
+///
+/// The shares are counted in memory between heartbeats, and drained into a single write for each round.
+///
+#[derive(Debug)]
+pub struct PendingShares<N: Network> {
+    /// The number of pending shares of each prover in each round, with the coinbase record of the round.
+    shares: DashMap<(u32, Address<N>), (Record<N>, u64)>,
+}
+
+impl<N: Network> Default for PendingShares<N> {
+    fn default() -> Self {
+        Self { shares: Default::default() }
+    }
+}
+
+impl<N: Network> PendingShares<N> {
+    ///
+    /// Increments the pending share count for the given prover in the given round.
+    ///
+    pub fn record(&self, block_height: u32, coinbase_record: &Record<N>, prover: Address<N>) {
+        // Attempt a non-blocking update first, so that contention on the prover's shard can be observed.
+        let key = (block_height, prover);
+        match self.shares.try_get_mut(&key) {
+            TryResult::Present(mut shares) => {
+                shares.1 = shares.1.saturating_add(1);
+                return;
+            }
+            TryResult::Absent => {}
+            TryResult::Locked => {
+                #[cfg(any(feature = "test", feature = "prometheus"))]
+                metrics::increment_counter!(metrics::operator::SHARD_CONTENTION);
+            }
+        }
+
+        let mut shares = self.shares.entry(key).or_insert_with(|| (coinbase_record.clone(), 0));
+        shares.1 = shares.1.saturating_add(1);
+    }
+
+    ///
+    /// Revokes up to the given number of pending shares from the given prover in the given round,
+    /// returning the number of shares revoked.
+    ///
+    pub fn revoke(&self, block_height: u32, prover: Address<N>, penalty: u64) -> u64 {
+        match self.shares.get_mut(&(block_height, prover)) {
+            Some(mut shares) => {
+                let revoked = shares.1.min(penalty);
+                shares.1 -= revoked;
+                revoked
+            }
+            None => 0,
+        }
+    }
+
+    ///
+    /// Returns the number of pending shares of the given prover, across all rounds.
+    ///
+    pub fn get_shares_for_prover(&self, prover: &Address<N>) -> u64 {
+        self.shares
+            .iter()
+            .filter(|entry| entry.key().1 == *prover)
+            .fold(0u64, |total, entry| total.saturating_add(entry.value().1))
+    }
+
+    ///
+    /// Returns the number of pending shares of each prover, across all rounds.
+    ///
+    pub fn to_shares_by_prover(&self) -> HashMap<Address<N>, u64> {
+        let mut shares_by_prover = HashMap::new();
+        for entry in self.shares.iter() {
+            let shares = shares_by_prover.entry(entry.key().1).or_insert(0u64);
+            *shares = shares.saturating_add(entry.value().1);
+        }
+        shares_by_prover
+    }
+
+    ///
+    /// Returns the total number of pending shares.
+    ///
+    pub fn total(&self) -> u64 {
+        self.shares.iter().fold(0u64, |total, entry| total.saturating_add(entry.value().1))
+    }
+
+    ///
+    /// Removes the pending shares, returning the coinbase record and the shares of each prover for each round.
+    ///
+    pub fn drain(&self) -> HashMap<u32, (Record<N>, HashMap<Address<N>, u64>)> {
+        let keys: Vec<_> = self.shares.iter().map(|entry| *entry.key()).collect();
+        let mut rounds: HashMap<u32, (Record<N>, HashMap<Address<N>, u64>)> = HashMap::new();
+        for key in keys {
+            if let Some(((block_height, prover), (coinbase_record, shares))) = self.shares.remove(&key) {
+                let (_, round) = rounds.entry(block_height).or_insert_with(|| (coinbase_record, HashMap::new()));
+                let entry = round.entry(prover).or_insert(0);
+                *entry = entry.saturating_add(shares);
+            }
+        }
+        rounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    use rand::thread_rng;
+
+    fn coinbase_record() -> Record<CurrentNetwork> {
+        let transaction = CurrentNetwork::genesis_block().to_coinbase_transaction().unwrap();
+        transaction.to_records().next().unwrap()
+    }
+
+    #[test]
+    fn test_pending_shares() {
+        let rng = &mut thread_rng();
+        let alice = Account::<CurrentNetwork>::new(rng).address();
+        let bob = Account::<CurrentNetwork>::new(rng).address();
+        let coinbase_record = coinbase_record();
+        let pending_shares = PendingShares::<CurrentNetwork>::default();
+
+        // Record shares across two rounds.
+        for _ in 0..3 {
+            pending_shares.record(1, &coinbase_record, alice);
+        }
+        pending_shares.record(2, &coinbase_record, alice);
+        pending_shares.record(2, &coinbase_record, bob);
+        assert_eq!(pending_shares.get_shares_for_prover(&alice), 4);
+        assert_eq!(pending_shares.get_shares_for_prover(&bob), 1);
+        assert_eq!(pending_shares.total(), 5);
+        assert_eq!(pending_shares.to_shares_by_prover(), HashMap::from([(alice, 4), (bob, 1)]));
+
+        // Check the revocation is capped by the pending shares of the round.
+        assert_eq!(pending_shares.revoke(1, alice, 2), 2);
+        assert_eq!(pending_shares.revoke(2, bob, 5), 1);
+        assert_eq!(pending_shares.revoke(3, bob, 1), 0);
+        assert_eq!(pending_shares.total(), 2);
+
+        // Check the shares are drained by round.
+        let rounds = pending_shares.drain();
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(rounds[&1].1, HashMap::from([(alice, 1)]));
+        assert_eq!(rounds[&2].1, HashMap::from([(alice, 1), (bob, 0)]));
+        assert_eq!(pending_shares.total(), 0);
+        assert!(pending_shares.drain().is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::{
+    dpc::{prelude::*, PoSWProof},
+    utilities::ToBits,
+};
+
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::{marker::PhantomData, time::Duration};
+use time::OffsetDateTime;
+
+/// The maximum number of seconds the operator holds a long-poll for a new job.
+pub const POOL_JOB_LONG_POLL_IN_SECS: u64 = 30;
+
+///
+/// Returns the hex encoding of the given object, in the same format as the P2P messages of the pool.
+///
+pub fn to_wire_hex<T: Serialize>(object: &T) -> Result<String> {
+    Ok(hex::encode(bincode::serialize(object)?))
+}
+
+///
+/// Returns the object decoded from the given hex, in the same format as the P2P messages of the pool.
+///
+pub fn from_wire_hex<T: DeserializeOwned>(object_hex: &str) -> Result<T> {
+    Ok(bincode::deserialize(&hex::decode(object_hex)?)?)
+}
+
+///
+/// Returns the message signed by a prover to request its job at the given UNIX timestamp, over the RPC server of the operator.
+///
+pub fn pool_job_message<N: Network>(prover: &Address<N>, timestamp: i64) -> String {
+    format!("{} pool job request: {} at {}", N::NETWORK_NAME, prover, timestamp)
+}
+
+///
+/// A client for fetching jobs from and submitting shares to the RPC server of a pool operator,
+/// for provers which cannot reach the operator over TCP.
+///
+#[derive(Clone, Debug)]
+pub struct PoolClient<N: Network> {
+    /// The URL of the RPC server of the operator.
+    url: String,
+    /// The HTTP client.
+    client: reqwest::Client,
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network> PoolClient<N> {
+    ///
+    /// Initializes a new client for the RPC server at the given URL.
+    ///
+    pub fn new(url: String) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            // Allow the long-poll to be held by the operator, with a margin for the response.
+            .timeout(Duration::from_secs(POOL_JOB_LONG_POLL_IN_SECS + 10))
+            .build()?;
+        Ok(Self {
+            url,
+            client,
+            _phantom: PhantomData,
+        })
+    }
+
+    ///
+    /// Returns the current job of the prover of the given private key := (share_difficulty, block_template).
+    /// If the job is for the given known block height, the operator holds the request until a new job is available,
+    /// or the long-poll expires. The request is signed, as the operator only assigns a job to the owner of the address.
+    ///
+    pub async fn get_job(&self, private_key: &PrivateKey<N>, known_block_height: Option<u32>) -> Result<Option<(u64, BlockTemplate<N>)>> {
+        let prover = Address::from_private_key(private_key);
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let message = pool_job_message(&prover, timestamp);
+        let signature = private_key.sign(&message.as_bytes().to_bits_le(), &mut rand::thread_rng())?;
+
+        let result = self
+            .call("getpooljob", json!([prover, known_block_height, timestamp, signature]))
+            .await?;
+        match result {
+            Value::Null => Ok(None),
+            job => {
+                let share_difficulty = job["share_difficulty"]
+                    .as_u64()
+                    .ok_or_else(|| anyhow!("The job is missing a share difficulty"))?;
+                let block_template = job["block_template"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("The job is missing a block template"))?;
+                Ok(Some((share_difficulty, from_wire_hex(block_template)?)))
+            }
+        }
+    }
+
+    ///
+    /// Submits a share of the given prover, stamped at the given UNIX timestamp, to the operator.
+    ///
+    pub async fn submit_share(&self, prover: Address<N>, nonce: N::PoSWNonce, timestamp: i64, proof: &PoSWProof<N>) -> Result<()> {
+        self.call("submitshare", json!([prover, to_wire_hex(&nonce)?, to_wire_hex(proof)?, timestamp]))
+            .await
+            .map(|_| ())
+    }
+
+    ///
+    /// Performs a JSON-RPC call of the given method, returning its result.
+    ///
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        call_rpc(&self.client, &self.url, method, params).await
+    }
+}
+
+///
+/// Performs a JSON-RPC call of the given method on the RPC server at the given URL, returning its result.
+///
+pub(crate) async fn call_rpc(client: &reqwest::Client, url: &str, method: &str, params: Value) -> Result<Value> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let mut response: Value = client.post(url).json(&request).send().await?.error_for_status()?.json().await?;
+    match response["error"].take() {
+        Value::Null => Ok(response["result"].take()),
+        error => Err(anyhow!("[{}] {}", method, error["message"].as_str().unwrap_or("Unknown error"))),
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_storage::Round;
+use snarkvm::dpc::prelude::*;
+
+use serde::Serialize;
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// The maximum number of recent pool rounds reported.
+const MAXIMUM_RECENT_ROUNDS: usize = 50;
+
+///
+/// A pool round, spanning the shares submitted since the previous block found by the pool, up to the block that ended it.
+///
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct PoolRound<N: Network> {
+    /// The height of the block that ended the round.
+    pub block_height: u32,
+    /// The hash of the block that ended the round.
+    pub block_hash: N::BlockHash,
+    /// The UNIX timestamp at which the block was found.
+    pub found_at: i64,
+    /// The number of shares submitted in the round.
+    pub shares: u64,
+    /// The number of shares expected to find a block, given the network difficulty during the round.
+    pub expected_shares: f64,
+    /// The ratio of expected shares to actual shares; above 1.0, the round was lucky.
+    pub luck: f64,
+    /// Whether the block was not included in the canonical chain.
+    pub is_orphaned: bool,
+}
+
+///
+/// The luck and variance statistics of the pool.
+///
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct PoolStats<N: Network> {
+    /// The number of shares submitted since the last block found by the pool.
+    pub current_round_shares: u64,
+    /// The ratio of actual shares to expected shares in the current round; above 1.0, the round is taking longer than expected.
+    pub current_round_effort: f64,
+    /// The ratio of blocks found to blocks expected over the last 7 days, if any shares were submitted.
+    pub luck_7d: Option<f64>,
+    /// The ratio of blocks found to blocks expected over the last 30 days, if any shares were submitted.
+    pub luck_30d: Option<f64>,
+    /// The fraction of blocks found over the last 30 days which were orphaned, if any blocks were found.
+    pub orphan_rate_30d: Option<f64>,
+    /// The most recent pool rounds, from newest to oldest.
+    pub rounds: Vec<PoolRound<N>>,
+}
+
+impl<N: Network> PoolStats<N> {
+    ///
+    /// Computes the statistics of the pool from the given per-height statistics, in ascending order of block height.
+    ///
+    pub fn new<F: Fn(u32, N::BlockHash) -> bool>(heights: &[(u32, Round<N>)], now: i64, is_orphaned: F) -> Self {
+        let mut rounds = Vec::new();
+        let (mut shares, mut expected_blocks) = (0u64, 0f64);
+        let mut windows = [Window::new(now, 7), Window::new(now, 30)];
+
+        for (block_height, round) in heights {
+            shares = shares.saturating_add(round.shares);
+            expected_blocks += round.expected_blocks;
+
+            let block = round
+                .block
+                .map(|(block_hash, found_at)| (block_hash, found_at, is_orphaned(*block_height, block_hash)));
+            for window in windows.iter_mut() {
+                window.add(round, block.map(|(_, _, is_orphaned)| is_orphaned));
+            }
+
+            // A block found by the pool ends the round.
+            if let Some((block_hash, found_at, is_orphaned)) = block {
+                rounds.push(PoolRound {
+                    block_height: *block_height,
+                    block_hash,
+                    found_at,
+                    shares,
+                    expected_shares: ratio(shares as f64, expected_blocks).unwrap_or(0.0),
+                    luck: ratio(1.0, expected_blocks).unwrap_or(0.0),
+                    is_orphaned,
+                });
+                shares = 0;
+                expected_blocks = 0.0;
+            }
+        }
+
+        let [week, month] = windows;
+        Self {
+            current_round_shares: shares,
+            current_round_effort: expected_blocks,
+            luck_7d: week.luck(),
+            luck_30d: month.luck(),
+            orphan_rate_30d: month.orphan_rate(),
+            rounds: rounds.into_iter().rev().take(MAXIMUM_RECENT_ROUNDS).collect(),
+        }
+    }
+}
+
+/// The blocks found and expected within a trailing window of time.
+struct Window {
+    start: i64,
+    found_blocks: u64,
+    orphaned_blocks: u64,
+    expected_blocks: f64,
+}
+
+impl Window {
+    fn new(now: i64, days: i64) -> Self {
+        Self {
+            start: now.saturating_sub(days * SECONDS_PER_DAY),
+            found_blocks: 0,
+            orphaned_blocks: 0,
+            expected_blocks: 0.0,
+        }
+    }
+
+    fn add<N: Network>(&mut self, round: &Round<N>, is_orphaned: Option<bool>) {
+        if round.started_at >= self.start {
+            self.expected_blocks += round.expected_blocks;
+            if let Some(is_orphaned) = is_orphaned {
+                self.found_blocks += 1;
+                self.orphaned_blocks += is_orphaned as u64;
+            }
+        }
+    }
+
+    fn luck(&self) -> Option<f64> {
+        ratio(self.found_blocks as f64, self.expected_blocks)
+    }
+
+    fn orphan_rate(&self) -> Option<f64> {
+        ratio(self.orphaned_blocks as f64, self.found_blocks as f64)
+    }
+}
+
+/// Returns the ratio of the given numerator to the given denominator, if the denominator is positive.
+fn ratio(numerator: f64, denominator: f64) -> Option<f64> {
+    match denominator > 0.0 {
+        true => Some(numerator / denominator),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    fn round(started_at: i64, shares: u64, expected_blocks: f64, block: Option<i64>) -> Round<CurrentNetwork> {
+        Round {
+            started_at,
+            shares,
+            expected_blocks,
+            block: block.map(|found_at| (CurrentNetwork::genesis_block().hash(), found_at)),
+        }
+    }
+
+    #[test]
+    fn test_pool_rounds() {
+        let now = 100 * SECONDS_PER_DAY;
+        let heights = vec![
+            (1, round(now - 10, 10, 0.25, None)),
+            (2, round(now - 9, 10, 0.25, Some(now - 8))),
+            (3, round(now - 7, 40, 1.0, Some(now - 6))),
+            (4, round(now - 5, 5, 0.5, None)),
+        ];
+        let stats = PoolStats::new(&heights, now, |block_height, _| block_height == 3);
+
+        // The rounds are reported from newest to oldest.
+        assert_eq!(stats.rounds.len(), 2);
+        assert_eq!(stats.rounds[0].block_height, 3);
+        assert_eq!(stats.rounds[0].luck, 1.0);
+        assert!(stats.rounds[0].is_orphaned);
+        // The first round found a block after half of the expected shares.
+        assert_eq!(stats.rounds[1].block_height, 2);
+        assert_eq!(stats.rounds[1].shares, 20);
+        assert_eq!(stats.rounds[1].expected_shares, 40.0);
+        assert_eq!(stats.rounds[1].luck, 2.0);
+
+        assert_eq!(stats.current_round_shares, 5);
+        assert_eq!(stats.current_round_effort, 0.5);
+        assert_eq!(stats.luck_7d, Some(1.0));
+        assert_eq!(stats.orphan_rate_30d, Some(0.5));
+    }
+
+    #[test]
+    fn test_pool_stats_windows() {
+        let now = 100 * SECONDS_PER_DAY;
+        let heights = vec![
+            (1, round(now - 20 * SECONDS_PER_DAY, 10, 1.0, Some(now - 20 * SECONDS_PER_DAY))),
+            (2, round(now - SECONDS_PER_DAY, 10, 0.5, None)),
+        ];
+        let stats = PoolStats::new(&heights, now, |_, _| false);
+
+        // The block found 20 days ago is only within the 30 day window.
+        assert_eq!(stats.luck_7d, Some(0.0));
+        assert_eq!(stats.luck_30d, Some(1.0 / 1.5));
+        assert_eq!(stats.orphan_rate_30d, Some(0.0));
+
+        // Without any shares, there are no statistics.
+        let stats = PoolStats::<CurrentNetwork>::new(&[], now, |_, _| false);
+        assert_eq!(stats.luck_30d, None);
+        assert_eq!(stats.orphan_rate_30d, None);
+    }
+}
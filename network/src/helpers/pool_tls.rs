@@ -0,0 +1,300 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader},
+    net::SocketAddr,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    client,
+    rustls::{
+        server::AllowAnyAnonymousOrAuthenticatedClient,
+        Certificate,
+        ClientConfig,
+        OwnedTrustAnchor,
+        PrivateKey,
+        RootCertStore,
+        ServerConfig,
+        ServerName,
+    },
+    server,
+    TlsAcceptor,
+    TlsConnector,
+};
+
+///
+/// A stream to a peer, which is secured by TLS for the connections between provers and operators that enable it.
+///
+#[derive(Debug)]
+pub enum PeerStream {
+    /// A plaintext TCP stream.
+    Tcp(TcpStream),
+    /// A TLS stream accepted by the operator.
+    TlsServer(Box<server::TlsStream<TcpStream>>),
+    /// A TLS stream initiated by the prover.
+    TlsClient(Box<client::TlsStream<TcpStream>>),
+}
+
+impl PeerStream {
+    ///
+    /// Returns the socket address of the peer.
+    ///
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            Self::Tcp(stream) => stream.peer_addr(),
+            Self::TlsServer(stream) => stream.get_ref().0.peer_addr(),
+            Self::TlsClient(stream) => stream.get_ref().0.peer_addr(),
+        }
+    }
+}
+
+impl From<TcpStream> for PeerStream {
+    fn from(stream: TcpStream) -> Self {
+        Self::Tcp(stream)
+    }
+}
+
+impl AsyncRead for PeerStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::TlsServer(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::TlsClient(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for PeerStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::TlsServer(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::TlsClient(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            Self::TlsServer(stream) => Pin::new(stream).poll_flush(cx),
+            Self::TlsClient(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::TlsServer(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::TlsClient(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+///
+/// The TLS configuration of the operator's prover listener.
+///
+#[derive(Clone)]
+pub struct PoolTlsAcceptor {
+    acceptor: TlsAcceptor,
+}
+
+impl PoolTlsAcceptor {
+    ///
+    /// Initializes the TLS configuration from the PEM-encoded certificate chain and private key at the given paths.
+    /// If a client CA is given, provers may authenticate with a client certificate issued by it.
+    ///
+    pub fn new<P: AsRef<Path>>(certificate_path: P, private_key_path: P, client_ca_path: Option<P>) -> Result<Self> {
+        let certificates = load_certificates(certificate_path)?;
+        let private_key = load_private_key(private_key_path)?;
+
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let config = match client_ca_path {
+            // Client certificates are optional during the handshake, and enforced by the sub-pool requiring them.
+            Some(client_ca_path) => {
+                let client_roots = load_root_certificates(client_ca_path)?;
+                builder.with_client_cert_verifier(AllowAnyAnonymousOrAuthenticatedClient::new(client_roots))
+            }
+            None => builder.with_no_client_auth(),
+        }
+        .with_single_cert(certificates, private_key)?;
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+
+    ///
+    /// Performs the TLS handshake on the given stream, returning (stream, server_name, is_authenticated), where
+    /// the server name is the SNI requested by the prover, and `is_authenticated` indicates a valid client certificate.
+    ///
+    pub async fn accept(&self, stream: TcpStream) -> Result<(PeerStream, Option<String>, bool)> {
+        let stream = self.acceptor.accept(stream).await?;
+        let (_, connection) = stream.get_ref();
+        let server_name = connection.sni_hostname().map(|server_name| server_name.to_lowercase());
+        let is_authenticated = connection
+            .peer_certificates()
+            .map_or(false, |certificates| !certificates.is_empty());
+        Ok((PeerStream::TlsServer(Box::new(stream)), server_name, is_authenticated))
+    }
+}
+
+impl fmt::Debug for PoolTlsAcceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolTlsAcceptor").finish()
+    }
+}
+
+///
+/// The TLS configuration of a prover connecting to its pool.
+///
+#[derive(Clone)]
+pub struct PoolTlsConnector {
+    pool_ip: SocketAddr,
+    server_name: ServerName,
+    connector: TlsConnector,
+}
+
+impl PoolTlsConnector {
+    ///
+    /// Initializes the TLS configuration for the pool at the given IP, which is verified against the given server name.
+    /// If a CA is given, the pool certificate is verified against it instead of the web PKI roots.
+    /// If an identity is given, its PEM-encoded certificate chain and private key authenticate the prover.
+    ///
+    pub fn new<P: AsRef<Path>>(pool_ip: SocketAddr, server_name: &str, ca_path: Option<P>, identity_path: Option<P>) -> Result<Self> {
+        let server_name = ServerName::try_from(server_name).map_err(|_| anyhow!("Invalid pool server name '{}'", server_name))?;
+
+        let roots = match ca_path {
+            Some(ca_path) => load_root_certificates(ca_path)?,
+            None => {
+                let mut roots = RootCertStore::empty();
+                roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|anchor| {
+                    OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)
+                }));
+                roots
+            }
+        };
+
+        let builder = ClientConfig::builder().with_safe_defaults().with_root_certificates(roots);
+        let config = match identity_path {
+            Some(identity_path) => builder.with_single_cert(load_certificates(&identity_path)?, load_private_key(&identity_path)?)?,
+            None => builder.with_no_client_auth(),
+        };
+
+        Ok(Self {
+            pool_ip,
+            server_name,
+            connector: TlsConnector::from(Arc::new(config)),
+        })
+    }
+
+    ///
+    /// Returns `true` if connections to the given peer IP are secured by this configuration.
+    ///
+    pub fn is_pool(&self, peer_ip: SocketAddr) -> bool {
+        self.pool_ip == peer_ip
+    }
+
+    ///
+    /// Performs the TLS handshake with the pool on the given stream.
+    ///
+    pub async fn connect(&self, stream: TcpStream) -> Result<PeerStream> {
+        let stream = self.connector.connect(self.server_name.clone(), stream).await?;
+        Ok(PeerStream::TlsClient(Box::new(stream)))
+    }
+}
+
+impl fmt::Debug for PoolTlsConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolTlsConnector")
+            .field("pool_ip", &self.pool_ip)
+            .field("server_name", &self.server_name)
+            .finish()
+    }
+}
+
+///
+/// Loads the PEM-encoded certificates from the file at the given path.
+///
+pub fn load_certificates<P: AsRef<Path>>(path: P) -> Result<Vec<Certificate>> {
+    let file = File::open(path.as_ref()).map_err(|error| anyhow!("Failed to open {}: {}", path.as_ref().display(), error))?;
+    let certificates = rustls_pemfile::certs(&mut BufReader::new(file))?;
+    if certificates.is_empty() {
+        return Err(anyhow!("No certificates found in {}", path.as_ref().display()));
+    }
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+///
+/// Loads the first PEM-encoded private key from the file at the given path.
+///
+pub fn load_private_key<P: AsRef<Path>>(path: P) -> Result<PrivateKey> {
+    let file = File::open(path.as_ref()).map_err(|error| anyhow!("Failed to open {}: {}", path.as_ref().display(), error))?;
+    for item in rustls_pemfile::read_all(&mut BufReader::new(file))? {
+        match item {
+            rustls_pemfile::Item::RSAKey(key) | rustls_pemfile::Item::PKCS8Key(key) | rustls_pemfile::Item::ECKey(key) => {
+                return Ok(PrivateKey(key));
+            }
+            _ => continue,
+        }
+    }
+    Err(anyhow!("No private key found in {}", path.as_ref().display()))
+}
+
+///
+/// Loads the PEM-encoded CA certificates from the file at the given path, as a set of trusted roots.
+///
+fn load_root_certificates<P: AsRef<Path>>(path: P) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for certificate in load_certificates(path)? {
+        roots.add(&certificate)?;
+    }
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_pool_server_name() {
+        let pool_ip = "127.0.0.1:4132".parse().unwrap();
+        assert!(PoolTlsConnector::new::<&str>(pool_ip, "not a server name", None, None).is_err());
+    }
+
+    #[test]
+    fn test_pool_tls_connector() {
+        let pool_ip = "127.0.0.1:4132".parse().unwrap();
+        let connector = PoolTlsConnector::new::<&str>(pool_ip, "pool.example.com", None, None).unwrap();
+        assert!(connector.is_pool(pool_ip));
+        assert!(!connector.is_pool("127.0.0.1:4133".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_missing_certificates() {
+        assert!(PoolTlsAcceptor::new("/nonexistent/cert.pem", "/nonexistent/key.pem", None).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use rand::Rng;
+use std::{fmt, str::FromStr, time::Duration};
+
+/// The maximum delay before a trickled transaction is propagated to the peers.
+pub const MAXIMUM_TRICKLE_DELAY: Duration = Duration::from_secs(30);
+
+///
+/// The propagation of the transactions submitted to this node (e.g. over RPC) to its peers.
+/// Transactions received from peers are always propagated immediately.
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PropagationPolicy {
+    /// Each transaction is propagated to the peers as soon as it is received.
+    Immediate,
+    /// Each transaction is propagated to the peers after a random delay, which obscures the node it originates from.
+    Trickle,
+    /// Transactions are never propagated, and are only included in the block templates of this node.
+    Private,
+}
+
+impl Default for PropagationPolicy {
+    fn default() -> Self {
+        Self::Immediate
+    }
+}
+
+impl PropagationPolicy {
+    ///
+    /// Returns the delay before a transaction is propagated under this policy, or `None` if it is not propagated.
+    ///
+    pub fn to_delay<R: Rng>(&self, rng: &mut R) -> Option<Duration> {
+        match self {
+            Self::Immediate => Some(Duration::ZERO),
+            Self::Trickle => Some(Duration::from_millis(rng.gen_range(0..=MAXIMUM_TRICKLE_DELAY.as_millis() as u64))),
+            Self::Private => None,
+        }
+    }
+}
+
+impl fmt::Display for PropagationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Immediate => write!(f, "immediate"),
+            Self::Trickle => write!(f, "trickle"),
+            Self::Private => write!(f, "private"),
+        }
+    }
+}
+
+impl FromStr for PropagationPolicy {
+    type Err = String;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.to_lowercase().as_str() {
+            "immediate" => Ok(Self::Immediate),
+            "trickle" => Ok(Self::Trickle),
+            "private" => Ok(Self::Private),
+            _ => Err(format!("Unknown propagation policy '{}'", policy)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_propagation_policy() {
+        let rng = &mut thread_rng();
+
+        // Transactions are propagated immediately by default.
+        assert_eq!(PropagationPolicy::default().to_delay(rng), Some(Duration::ZERO));
+
+        // Trickled transactions are delayed by at most the maximum delay.
+        for _ in 0..100 {
+            let delay = PropagationPolicy::Trickle.to_delay(rng).unwrap();
+            assert!(delay <= MAXIMUM_TRICKLE_DELAY);
+        }
+
+        // Private transactions are never propagated.
+        assert_eq!(PropagationPolicy::Private.to_delay(rng), None);
+
+        // Policies round trip through their string representation.
+        for policy in [PropagationPolicy::Immediate, PropagationPolicy::Trickle, PropagationPolicy::Private] {
+            assert_eq!(policy.to_string().parse::<PropagationPolicy>(), Ok(policy));
+        }
+        assert!("broadcast".parse::<PropagationPolicy>().is_err());
+    }
+}
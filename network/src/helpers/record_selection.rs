@@ -0,0 +1,269 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{cmp::Reverse, fmt, str::FromStr};
+
+/// The maximum number of branches explored by the branch-and-bound selection, before it settles for the best selection found.
+const MAXIMUM_BRANCH_AND_BOUND_TRIES: usize = 100_000;
+
+///
+/// The strategy for selecting the records of the pool wallet that fund a payout transaction.
+///
+/// The pool wallet accumulates thousands of coinbase records, so the strategy trades off the number of inputs
+/// of the payout transaction against the change it produces, and the consolidation of old records.
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RecordSelection {
+    /// The records of the highest values are selected first, which minimizes the number of inputs.
+    LargestFirst,
+    /// The records of the lowest block heights are selected first, which consolidates old records over time.
+    OldestFirst,
+    /// The records are searched for the selection with the least change, which avoids creating dust records.
+    /// If no selection is found within the search bound, the records of the highest values are selected first.
+    BranchAndBound,
+}
+
+impl Default for RecordSelection {
+    fn default() -> Self {
+        Self::LargestFirst
+    }
+}
+
+impl RecordSelection {
+    ///
+    /// Returns the indices of the candidates selected to fund the given amount with at most the given number of inputs,
+    /// where each candidate is the block height and the value of a record, or `None` if the amount cannot be funded.
+    ///
+    pub fn select(&self, candidates: &[(u32, u64)], amount: u64, maximum_inputs: usize) -> Option<Vec<usize>> {
+        let mut indices: Vec<usize> = (0..candidates.len()).filter(|index| candidates[*index].1 > 0).collect();
+        match self {
+            Self::LargestFirst => {
+                indices.sort_by_key(|index| (Reverse(candidates[*index].1), candidates[*index].0));
+                Self::select_in_order(candidates, &indices, amount, maximum_inputs)
+            }
+            Self::OldestFirst => {
+                indices.sort_by_key(|index| (candidates[*index].0, Reverse(candidates[*index].1)));
+                Self::select_in_order(candidates, &indices, amount, maximum_inputs)
+            }
+            Self::BranchAndBound => {
+                indices.sort_by_key(|index| (Reverse(candidates[*index].1), candidates[*index].0));
+                BranchAndBound::new(candidates, &indices, amount, maximum_inputs)
+                    .search()
+                    .or_else(|| Self::select_in_order(candidates, &indices, amount, maximum_inputs))
+            }
+        }
+    }
+
+    ///
+    /// Returns the shortest prefix of the given indices whose candidates fund the given amount, if any.
+    ///
+    fn select_in_order(candidates: &[(u32, u64)], indices: &[usize], amount: u64, maximum_inputs: usize) -> Option<Vec<usize>> {
+        let mut total = 0u128;
+        let mut selection = Vec::new();
+        for index in indices.iter().take(maximum_inputs) {
+            if total >= amount as u128 {
+                break;
+            }
+            total += candidates[*index].1 as u128;
+            selection.push(*index);
+        }
+        match total >= amount as u128 {
+            true => Some(selection),
+            false => None,
+        }
+    }
+}
+
+///
+/// A depth-first search for the selection of candidates with the least change,
+/// bounded by the maximum number of branches it explores.
+///
+struct BranchAndBound<'a> {
+    /// The block heights and values of the candidates.
+    candidates: &'a [(u32, u64)],
+    /// The indices of the candidates, in descending order of value.
+    indices: &'a [usize],
+    /// The total value of the candidates from each position in `indices` onwards.
+    remaining: Vec<u128>,
+    /// The amount to fund.
+    amount: u128,
+    /// The maximum number of inputs of a selection.
+    maximum_inputs: usize,
+    /// The number of branches explored so far.
+    tries: usize,
+    /// The selection of the current branch.
+    selection: Vec<usize>,
+    /// The change and the selection of the best branch found so far.
+    best: Option<(u128, Vec<usize>)>,
+}
+
+impl<'a> BranchAndBound<'a> {
+    fn new(candidates: &'a [(u32, u64)], indices: &'a [usize], amount: u64, maximum_inputs: usize) -> Self {
+        let mut remaining = vec![0u128; indices.len() + 1];
+        for position in (0..indices.len()).rev() {
+            remaining[position] = remaining[position + 1] + candidates[indices[position]].1 as u128;
+        }
+        Self {
+            candidates,
+            indices,
+            remaining,
+            amount: amount as u128,
+            maximum_inputs,
+            tries: 0,
+            selection: Vec::new(),
+            best: None,
+        }
+    }
+
+    /// Returns the selection with the least change found, if any.
+    fn search(mut self) -> Option<Vec<usize>> {
+        self.explore(0, 0);
+        self.best.map(|(_, selection)| selection)
+    }
+
+    fn explore(&mut self, position: usize, total: u128) {
+        if self.tries >= MAXIMUM_BRANCH_AND_BOUND_TRIES {
+            return;
+        }
+        self.tries += 1;
+
+        // If the amount is funded, record the selection if it has less change than the best one, and stop descending,
+        // as further inputs only add change.
+        if total >= self.amount {
+            let change = total - self.amount;
+            let is_better = match &self.best {
+                Some((best_change, _)) => change < *best_change,
+                None => true,
+            };
+            if is_better {
+                self.best = Some((change, self.selection.clone()));
+            }
+            return;
+        }
+
+        // Stop if an exact selection was found, the inputs are exhausted, or the remaining candidates cannot fund the amount.
+        if matches!(self.best, Some((0, _)))
+            || position == self.indices.len()
+            || self.selection.len() == self.maximum_inputs
+            || total + self.remaining[position] < self.amount
+        {
+            return;
+        }
+
+        // Skip the branch if its lowest possible change is no better than the best selection found.
+        let value = self.candidates[self.indices[position]].1 as u128;
+        let can_improve = match &self.best {
+            Some((best_change, _)) => total + value < self.amount + *best_change,
+            None => true,
+        };
+
+        // Explore the branch that includes the candidate, then the branch that excludes it.
+        if can_improve {
+            self.selection.push(self.indices[position]);
+            self.explore(position + 1, total + value);
+            self.selection.pop();
+        }
+        // Excluding the candidate also excludes the following candidates of the same value,
+        // as the selections including them are equivalent to those explored by including it.
+        let mut next_position = position + 1;
+        while next_position < self.indices.len() && self.candidates[self.indices[next_position]].1 as u128 == value {
+            next_position += 1;
+        }
+        self.explore(next_position, total);
+    }
+}
+
+impl fmt::Display for RecordSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LargestFirst => write!(f, "largest-first"),
+            Self::OldestFirst => write!(f, "oldest-first"),
+            Self::BranchAndBound => write!(f, "branch-and-bound"),
+        }
+    }
+}
+
+impl FromStr for RecordSelection {
+    type Err = String;
+
+    fn from_str(selection: &str) -> Result<Self, Self::Err> {
+        match selection.to_lowercase().as_str() {
+            "largest-first" => Ok(Self::LargestFirst),
+            "oldest-first" => Ok(Self::OldestFirst),
+            "branch-and-bound" => Ok(Self::BranchAndBound),
+            _ => Err(format!("Unknown record selection '{}'", selection)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(candidates: &[(u32, u64)], selection: &[usize]) -> u64 {
+        selection.iter().map(|index| candidates[*index].1).sum()
+    }
+
+    #[test]
+    fn test_record_selection() {
+        // The block heights and values of the records.
+        let candidates = [(10, 50), (20, 30), (30, 70), (40, 20), (50, 5)];
+
+        // Check the largest records are selected first.
+        assert_eq!(RecordSelection::LargestFirst.select(&candidates, 100, 10), Some(vec![2, 0]));
+        // Check the oldest records are selected first.
+        assert_eq!(RecordSelection::OldestFirst.select(&candidates, 100, 10), Some(vec![0, 1, 2]));
+        // Check the selection with the least change is found.
+        let selection = RecordSelection::BranchAndBound.select(&candidates, 100, 10).unwrap();
+        assert_eq!(total(&candidates, &selection), 100);
+        let selection = RecordSelection::BranchAndBound.select(&candidates, 96, 10).unwrap();
+        assert_eq!(total(&candidates, &selection), 100);
+
+        // Check the number of inputs is bounded.
+        assert_eq!(RecordSelection::OldestFirst.select(&candidates, 100, 2), None);
+        assert_eq!(RecordSelection::LargestFirst.select(&candidates, 120, 2), Some(vec![2, 0]));
+        let selection = RecordSelection::BranchAndBound.select(&candidates, 55, 1).unwrap();
+        assert_eq!(selection, vec![2]);
+
+        // Check an amount beyond the total value cannot be funded.
+        for strategy in [
+            RecordSelection::LargestFirst,
+            RecordSelection::OldestFirst,
+            RecordSelection::BranchAndBound,
+        ] {
+            assert_eq!(strategy.select(&candidates, 176, 10), None);
+            assert_eq!(strategy.to_string().parse::<RecordSelection>(), Ok(strategy));
+        }
+        assert!("random".parse::<RecordSelection>().is_err());
+    }
+
+    #[test]
+    fn test_branch_and_bound_avoids_dust() {
+        // Many coinbase records of equal value, and a few odd ones.
+        let mut candidates: Vec<(u32, u64)> = (0..1_000).map(|height| (height, 100)).collect();
+        candidates.extend([(1_000, 37), (1_001, 63)]);
+
+        // Check an amount funded exactly by the odd records leaves no change.
+        let selection = RecordSelection::BranchAndBound.select(&candidates, 400, 8).unwrap();
+        assert_eq!(total(&candidates, &selection), 400);
+        let selection = RecordSelection::BranchAndBound.select(&candidates, 137, 8).unwrap();
+        assert_eq!(total(&candidates, &selection), 137);
+
+        // Check the largest-first selection leaves change for the same amount.
+        let selection = RecordSelection::LargestFirst.select(&candidates, 137, 8).unwrap();
+        assert_eq!(total(&candidates, &selection), 200);
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+///
+/// The retention of the data persisted by an operator, as specified by its operator.
+///
+#[derive(Clone, Debug, Default)]
+pub struct RetentionPolicy {
+    /// The duration for which the raw shares of a round are retained; if `None`, shares are retained forever.
+    pub shares: Option<Duration>,
+    /// The duration for which the statistics of a round are retained; if `None`, rounds are retained forever.
+    pub rounds: Option<Duration>,
+    /// The duration for which the contributions of an epoch are retained; if `None`, contributions are retained forever.
+    pub metrics: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    ///
+    /// Initializes a new retention policy, ensuring the shares do not outlive the statistics of their rounds.
+    ///
+    pub fn new(shares: Option<Duration>, rounds: Option<Duration>, metrics: Option<Duration>) -> Result<Self> {
+        match (shares, rounds) {
+            (_, None) => (),
+            (Some(shares), Some(rounds)) if shares <= rounds => (),
+            _ => return Err(anyhow!("The shares must not be retained for longer than the rounds")),
+        }
+        Ok(Self { shares, rounds, metrics })
+    }
+
+    ///
+    /// Returns `true` if any data is pruned under this policy.
+    ///
+    pub fn is_pruning(&self) -> bool {
+        self.shares.is_some() || self.rounds.is_some() || self.metrics.is_some()
+    }
+
+    ///
+    /// Returns the UNIX timestamp before which the shares have expired at the given time, if shares expire.
+    ///
+    pub fn shares_cutoff(&self, now: i64) -> Option<i64> {
+        self.shares.map(|retention| now.saturating_sub(retention.as_secs() as i64))
+    }
+
+    ///
+    /// Returns the UNIX timestamp before which the rounds have expired at the given time, if rounds expire.
+    ///
+    pub fn rounds_cutoff(&self, now: i64) -> Option<i64> {
+        self.rounds.map(|retention| now.saturating_sub(retention.as_secs() as i64))
+    }
+
+    ///
+    /// Returns the UNIX timestamp before which the contributions have expired at the given time, if contributions expire.
+    ///
+    pub fn metrics_cutoff(&self, now: i64) -> Option<i64> {
+        self.metrics.map(|retention| now.saturating_sub(retention.as_secs() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn test_retention_policy() {
+        // By default, nothing expires.
+        let policy = RetentionPolicy::default();
+        assert!(!policy.is_pruning());
+        assert_eq!(policy.shares_cutoff(1_000_000), None);
+        assert_eq!(policy.rounds_cutoff(1_000_000), None);
+        assert_eq!(policy.metrics_cutoff(1_000_000), None);
+
+        // Raw shares for 30 days, and rounds forever.
+        let policy = RetentionPolicy::new(Some(30 * DAY), None, None).unwrap();
+        assert!(policy.is_pruning());
+        assert_eq!(policy.shares_cutoff(3_000_000), Some(3_000_000 - 2_592_000));
+        assert_eq!(policy.rounds_cutoff(3_000_000), None);
+
+        // Contributions for a year, independently of the shares and rounds.
+        let policy = RetentionPolicy::new(None, None, Some(365 * DAY)).unwrap();
+        assert!(policy.is_pruning());
+        assert_eq!(policy.metrics_cutoff(40_000_000), Some(40_000_000 - 31_536_000));
+        assert_eq!(policy.shares_cutoff(40_000_000), None);
+
+        // The shares may not outlive the rounds.
+        assert!(RetentionPolicy::new(Some(30 * DAY), Some(365 * DAY), None).is_ok());
+        assert!(RetentionPolicy::new(Some(30 * DAY), Some(7 * DAY), None).is_err());
+        assert!(RetentionPolicy::new(None, Some(7 * DAY), None).is_err());
+    }
+}
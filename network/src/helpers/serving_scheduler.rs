@@ -0,0 +1,267 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+
+///
+/// The cost of the blocks served to a peer, in bytes, decaying by half every half-life.
+///
+#[derive(Clone, Copy, Debug)]
+struct ServingCost {
+    /// The cost as of the last update.
+    cost: f64,
+    /// The timestamp of the last update.
+    updated_at: Instant,
+}
+
+impl ServingCost {
+    ///
+    /// Returns the cost decayed up to the given timestamp.
+    ///
+    fn decayed(&self, now: Instant, half_life: Duration) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.cost * 0.5f64.powf(elapsed / half_life.as_secs_f64())
+    }
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    /// The map of peer IPs to the cost of the blocks served to them.
+    costs: HashMap<SocketAddr, ServingCost>,
+    /// The number of block responses being served.
+    in_flight: usize,
+    /// The peers waiting to serve a block response := (peer_ip, sequence, permit_router)
+    waiting: Vec<(SocketAddr, u64, oneshot::Sender<ServingPermit>)>,
+    /// The sequence number of the next waiting peer, used to break ties in arrival order.
+    next_sequence: u64,
+}
+
+impl SchedulerState {
+    ///
+    /// Returns the decayed cost of the given peer.
+    ///
+    fn cost(&self, peer_ip: &SocketAddr, now: Instant, half_life: Duration) -> f64 {
+        self.costs.get(peer_ip).map(|cost| cost.decayed(now, half_life)).unwrap_or_default()
+    }
+
+    ///
+    /// Removes and returns the permit router of the waiting peer with the least cost, if any.
+    ///
+    fn next_waiting(&mut self, half_life: Duration) -> Option<oneshot::Sender<ServingPermit>> {
+        let now = Instant::now();
+        let index = self
+            .waiting
+            .iter()
+            .enumerate()
+            .min_by(|(_, (a, a_sequence, _)), (_, (b, b_sequence, _))| {
+                let (a_cost, b_cost) = (self.cost(a, now, half_life), self.cost(b, now, half_life));
+                a_cost.total_cmp(&b_cost).then(a_sequence.cmp(b_sequence))
+            })
+            .map(|(index, _)| index)?;
+
+        Some(self.waiting.swap_remove(index).2)
+    }
+}
+
+///
+/// A fair-share scheduler for the block responses served to syncing peers.
+///
+/// A limited number of block responses are served at once. When a slot frees up, it is granted to
+/// the waiting peer that was served the fewest bytes recently, so one aggressive syncing peer
+/// cannot monopolize the disk and upload bandwidth of the node at the expense of the others.
+///
+#[derive(Debug)]
+pub struct ServingScheduler {
+    /// The maximum number of block responses served at once.
+    capacity: usize,
+    /// The duration after which the cost of a peer decays by half.
+    half_life: Duration,
+    /// The accounting and queue of the scheduler.
+    state: Mutex<SchedulerState>,
+}
+
+impl ServingScheduler {
+    ///
+    /// Initializes a new instance of `ServingScheduler`.
+    ///
+    pub fn new(capacity: usize, half_life: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            capacity: capacity.max(1),
+            half_life,
+            state: Default::default(),
+        })
+    }
+
+    ///
+    /// Waits for a slot to serve a block response to the given peer, returning a permit that frees the slot on drop.
+    ///
+    pub async fn acquire(self: &Arc<Self>, peer_ip: SocketAddr) -> Result<ServingPermit> {
+        let permit_handler = {
+            let mut state = self.state.lock();
+            if state.in_flight < self.capacity {
+                state.in_flight += 1;
+                return Ok(ServingPermit {
+                    scheduler: Some(self.clone()),
+                });
+            }
+
+            let (permit_router, permit_handler) = oneshot::channel();
+            let sequence = state.next_sequence;
+            state.next_sequence += 1;
+            state.waiting.push((peer_ip, sequence, permit_router));
+            permit_handler
+        };
+
+        // The slot is handed over by the permit that frees it.
+        permit_handler
+            .await
+            .map_err(|_| anyhow!("The serving scheduler dropped the request of {}", peer_ip))
+    }
+
+    ///
+    /// Adds the given number of bytes to the cost of the given peer.
+    ///
+    pub fn record(&self, peer_ip: SocketAddr, bytes: usize) {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        let cost = state.cost(&peer_ip, now, self.half_life) + bytes as f64;
+        state.costs.insert(peer_ip, ServingCost { cost, updated_at: now });
+
+        // Forget the peers whose cost has decayed away.
+        let half_life = self.half_life;
+        state.costs.retain(|_, cost| cost.decayed(now, half_life) >= 1.0);
+    }
+
+    ///
+    /// Returns the recent cost of the given peer, in bytes.
+    ///
+    pub fn cost(&self, peer_ip: &SocketAddr) -> u64 {
+        self.state.lock().cost(peer_ip, Instant::now(), self.half_life) as u64
+    }
+
+    ///
+    /// Hands over a freed slot to the waiting peer with the least cost, or returns it to the pool.
+    ///
+    fn release(self: &Arc<Self>) {
+        let mut state = self.state.lock();
+        loop {
+            match state.next_waiting(self.half_life) {
+                Some(permit_router) => {
+                    let permit = ServingPermit {
+                        scheduler: Some(self.clone()),
+                    };
+                    match permit_router.send(permit) {
+                        Ok(()) => return,
+                        // The waiting peer has disconnected, so the permit is discarded without freeing the slot.
+                        Err(mut permit) => permit.scheduler = None,
+                    }
+                }
+                None => {
+                    state.in_flight = state.in_flight.saturating_sub(1);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+///
+/// A slot to serve a block response, freed when dropped.
+///
+#[derive(Debug)]
+pub struct ServingPermit {
+    scheduler: Option<Arc<ServingScheduler>>,
+}
+
+impl Drop for ServingPermit {
+    fn drop(&mut self) {
+        if let Some(scheduler) = self.scheduler.take() {
+            scheduler.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fair_share() {
+        let scheduler = ServingScheduler::new(1, Duration::from_secs(600));
+        let aggressive_peer: SocketAddr = "127.0.0.1:4130".parse().unwrap();
+        let other_peer: SocketAddr = "127.0.0.1:4131".parse().unwrap();
+
+        // Serve a block to the aggressive peer, while both peers wait for the slot.
+        let permit = scheduler.acquire(aggressive_peer).await.unwrap();
+        scheduler.record(aggressive_peer, 1024);
+        assert_eq!(1024, scheduler.cost(&aggressive_peer));
+        assert_eq!(0, scheduler.cost(&other_peer));
+
+        let (order_router, mut order_handler) = tokio::sync::mpsc::unbounded_channel();
+        let mut tasks = vec![];
+        for peer_ip in [aggressive_peer, other_peer] {
+            let scheduler = scheduler.clone();
+            let order_router = order_router.clone();
+            tasks.push(tokio::spawn(async move {
+                let _permit = scheduler.acquire(peer_ip).await.unwrap();
+                order_router.send(peer_ip).unwrap();
+            }));
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(2, scheduler.state.lock().waiting.len());
+
+        // The slot is granted to the peer that was served the fewest bytes, despite arriving last.
+        drop(permit);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(Some(other_peer), order_handler.recv().await);
+        assert_eq!(Some(aggressive_peer), order_handler.recv().await);
+
+        // The slot is returned once no peer is waiting.
+        assert_eq!(0, scheduler.state.lock().in_flight);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_waiter() {
+        let scheduler = ServingScheduler::new(1, Duration::from_secs(600));
+        let peer_ip: SocketAddr = "127.0.0.1:4130".parse().unwrap();
+
+        let permit = scheduler.acquire(peer_ip).await.unwrap();
+
+        // A peer that disconnects while waiting does not hold on to the slot.
+        let waiter = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire(peer_ip).await.map(|_| ()) }
+        });
+        tokio::task::yield_now().await;
+        waiter.abort();
+        let _ = waiter.await;
+
+        drop(permit);
+        assert_eq!(0, scheduler.state.lock().in_flight);
+        assert!(scheduler.acquire(peer_ip).await.is_ok());
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, Read, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::mpsc::{self, SyncSender, TrySendError},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    task::{self, JoinHandle},
+};
+
+/// The magic bytes at the start of a session file, followed by the recorded chunks of the session.
+pub const SESSION_FILE_MAGIC: [u8; 8] = *b"SNOSSES1";
+/// The extension of a session file.
+pub const SESSION_FILE_EXTENSION: &str = "session";
+/// The maximum size of a session file, in bytes, beyond which the session is no longer recorded.
+pub const MAXIMUM_SESSION_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// The maximum number of chunks queued for the session file, beyond which the session is no longer recorded.
+const MAXIMUM_PENDING_CHUNKS: usize = 256;
+
+///
+/// The recorder of the inbound byte stream of a peer session, to replay the session against another node.
+///
+/// Each chunk read from the peer is appended to the session file as
+/// `(milliseconds since the start of the session: u64, length: u32, bytes)`, in little-endian,
+/// and written through by a blocking task, so the session up to a crash of the node is preserved.
+/// The recording stops if the session file reaches its maximum size, or falls too far behind the peer.
+///
+#[derive(Debug)]
+pub struct SessionRecorder {
+    /// The path of the session file.
+    path: PathBuf,
+    /// The sender of the chunks to the task writing the session file.
+    chunks: SyncSender<Vec<u8>>,
+    /// The task writing the session file.
+    writer: JoinHandle<io::Result<()>>,
+    /// The size of the session file once the chunks sent so far are written, in bytes.
+    size: u64,
+    /// The start of the session.
+    started_at: Instant,
+}
+
+impl SessionRecorder {
+    ///
+    /// Creates a new session file for the given peer in the given directory.
+    ///
+    pub fn create(directory: &Path, peer_ip: SocketAddr) -> Result<Self> {
+        fs::create_dir_all(directory)?;
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000;
+        let file_name = format!("{}-{}.{}", peer_ip.to_string().replace(':', "_"), timestamp, SESSION_FILE_EXTENSION);
+        let path = directory.join(file_name);
+
+        let mut file = File::create(&path)?;
+        file.write_all(&SESSION_FILE_MAGIC)?;
+
+        let (chunks, receiver) = mpsc::sync_channel::<Vec<u8>>(MAXIMUM_PENDING_CHUNKS);
+        let writer = task::spawn_blocking(move || receiver.iter().try_for_each(|chunk| file.write_all(&chunk)));
+        Ok(Self {
+            path,
+            chunks,
+            writer,
+            size: SESSION_FILE_MAGIC.len() as u64,
+            started_at: Instant::now(),
+        })
+    }
+
+    ///
+    /// Returns the path of the session file.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///
+    /// Appends the given chunk read from the peer to the session file.
+    /// This never blocks, and fails if the session file would exceed its maximum size, or its writes fell behind.
+    ///
+    pub fn record(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let size = self.size + 12 + bytes.len() as u64;
+        if size > MAXIMUM_SESSION_FILE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::Other, "the session file reached its maximum size"));
+        }
+
+        let elapsed_in_ms = self.started_at.elapsed().as_millis() as u64;
+        let mut chunk = Vec::with_capacity(12 + bytes.len());
+        chunk.extend_from_slice(&elapsed_in_ms.to_le_bytes());
+        chunk.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(bytes);
+        match self.chunks.try_send(chunk) {
+            Ok(()) => {
+                self.size = size;
+                Ok(())
+            }
+            Err(TrySendError::Full(_)) => Err(io::Error::new(io::ErrorKind::Other, "the session file fell behind the peer")),
+            Err(TrySendError::Disconnected(_)) => Err(io::Error::new(io::ErrorKind::Other, "the session file cannot be written")),
+        }
+    }
+
+    ///
+    /// Stops recording the session, and waits until the recorded chunks are written to the session file.
+    ///
+    pub async fn close(self) -> io::Result<()> {
+        drop(self.chunks);
+        self.writer.await.map_err(|error| io::Error::new(io::ErrorKind::Other, error))?
+    }
+}
+
+///
+/// Returns the frames of the recorded session at the given path := \[(time since the start of the session, frame)\],
+/// where each frame is a length-prefixed message as sent by the peer, and its time is the time its last byte was read.
+/// Any trailing bytes of an incomplete frame, e.g. if the peer disconnected mid-message, are returned as a final frame.
+///
+pub fn read_session(path: &Path) -> Result<Vec<(Duration, Vec<u8>)>> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != SESSION_FILE_MAGIC {
+        return Err(anyhow!("{} is not a session file", path.display()));
+    }
+
+    let mut frames = Vec::new();
+    let mut pending = Vec::new();
+    let mut elapsed_in_ms = 0u64;
+    loop {
+        let mut header = [0u8; 12];
+        match reader.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error.into()),
+        }
+        elapsed_in_ms = u64::from_le_bytes(header[..8].try_into()?);
+        let length = u32::from_le_bytes(header[8..].try_into()?) as u64;
+        if length > MAXIMUM_SESSION_FILE_SIZE {
+            return Err(anyhow!("{} has a chunk of invalid length {}", path.display(), length));
+        }
+        // A chunk truncated by a crash of the node is kept up to the bytes that were written.
+        let mut chunk = Vec::with_capacity(length as usize);
+        (&mut reader).take(length).read_to_end(&mut chunk)?;
+        pending.extend_from_slice(&chunk);
+
+        // Split the complete frames off the pending bytes.
+        while pending.len() >= 4 {
+            let frame_length = 4 + u32::from_le_bytes(pending[..4].try_into()?) as usize;
+            if pending.len() < frame_length {
+                break;
+            }
+            let remaining = pending.split_off(frame_length);
+            frames.push((Duration::from_millis(elapsed_in_ms), std::mem::replace(&mut pending, remaining)));
+        }
+    }
+    if !pending.is_empty() {
+        frames.push((Duration::from_millis(elapsed_in_ms), pending));
+    }
+    Ok(frames)
+}
+
+///
+/// A stream that records the bytes read from it in the given session recorder, if any.
+///
+#[derive(Debug)]
+pub struct RecordedStream<S> {
+    stream: S,
+    recorder: Option<SessionRecorder>,
+}
+
+impl<S> RecordedStream<S> {
+    ///
+    /// Initializes a new instance of `RecordedStream`, recording the bytes read from the given stream in the given recorder.
+    ///
+    pub fn new(stream: S, recorder: Option<SessionRecorder>) -> Self {
+        Self { stream, recorder }
+    }
+
+    ///
+    /// Returns a reference to the underlying stream.
+    ///
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    ///
+    /// Returns the underlying stream and the session recorder, if it is still recording.
+    ///
+    pub fn into_inner(self) -> (S, Option<SessionRecorder>) {
+        (self.stream, self.recorder)
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for RecordedStream<S> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled_before = buf.filled().len();
+        let result = Pin::new(&mut this.stream).poll_read(cx, buf);
+        if let (Poll::Ready(Ok(())), Some(recorder)) = (&result, &mut this.recorder) {
+            let bytes = &buf.filled()[filled_before..];
+            if !bytes.is_empty() {
+                // Stop recording the session if the session file cannot be written, rather than failing the connection.
+                if let Err(error) = recorder.record(bytes) {
+                    warn!("Stopped recording the session in {}: {}", recorder.path().display(), error);
+                    this.recorder = None;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for RecordedStream<S> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    /// Returns the given payload as a length-prefixed frame.
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut frame = (payload.len() as u32).to_le_bytes().to_vec();
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[tokio::test]
+    async fn test_recorded_stream_replays_frames() {
+        let directory = tempfile::tempdir().unwrap();
+        let peer_ip: SocketAddr = "127.0.0.1:4132".parse().unwrap();
+        let recorder = SessionRecorder::create(directory.path(), peer_ip).unwrap();
+        let path = recorder.path().to_path_buf();
+
+        let (local, mut remote) = duplex(64);
+        let mut stream = RecordedStream::new(local, Some(recorder));
+
+        // Send two frames split across chunks, and a truncated frame.
+        let (first, second) = (frame(b"challenge"), frame(b"ping"));
+        let mut bytes = [first.clone(), second.clone()].concat();
+        bytes.extend_from_slice(&frame(b"truncated")[..6]);
+        for chunk in bytes.chunks(5) {
+            remote.write_all(chunk).await.unwrap();
+            let mut buffer = vec![0u8; chunk.len()];
+            stream.read_exact(&mut buffer).await.unwrap();
+        }
+
+        // Check the bytes written to the peer are not recorded.
+        stream.write_all(b"pong").await.unwrap();
+        stream.into_inner().1.unwrap().close().await.unwrap();
+
+        let frames: Vec<Vec<u8>> = read_session(&path).unwrap().into_iter().map(|(_, frame)| frame).collect();
+        assert_eq!(frames, vec![first, second, frame(b"truncated")[..6].to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_without_recorder() {
+        let (local, mut remote) = duplex(64);
+        let mut stream = RecordedStream::new(local, None);
+
+        remote.write_all(b"ping").await.unwrap();
+        let mut buffer = [0u8; 4];
+        stream.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"ping");
+    }
+
+    #[tokio::test]
+    async fn test_recorder_stops_at_maximum_size() {
+        let directory = tempfile::tempdir().unwrap();
+        let peer_ip: SocketAddr = "127.0.0.1:4132".parse().unwrap();
+        let mut recorder = SessionRecorder::create(directory.path(), peer_ip).unwrap();
+        let path = recorder.path().to_path_buf();
+
+        // Check a chunk that would exceed the maximum size is not recorded.
+        recorder.record(&frame(b"ping")).unwrap();
+        assert!(recorder.record(&vec![0u8; MAXIMUM_SESSION_FILE_SIZE as usize]).is_err());
+        recorder.close().await.unwrap();
+
+        let frames: Vec<Vec<u8>> = read_session(&path).unwrap().into_iter().map(|(_, frame)| frame).collect();
+        assert_eq!(frames, vec![frame(b"ping")]);
+    }
+
+    #[test]
+    fn test_read_session_rejects_invalid_chunks() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("invalid-chunk");
+        let mut bytes = SESSION_FILE_MAGIC.to_vec();
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+        assert!(read_session(&path).is_err());
+    }
+
+    #[test]
+    fn test_read_session_rejects_other_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("not-a-session");
+        fs::write(&path, b"0123456789").unwrap();
+        assert!(read_session(&path).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use rand::Rng;
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+/// The duration over which the share load of the operator is measured.
+const LOAD_WINDOW: Duration = Duration::from_secs(1);
+/// The duration for which every share from a peer is fully verified, after one of its sampled shares failed.
+const PROBATION_DURATION: Duration = Duration::from_secs(60 * 60);
+/// The duration after which the sampling statistics of a peer that is not on probation are forgotten.
+const PEER_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// The maximum number of peers with sampling statistics; the shares of any other peer are fully verified.
+const MAXIMUM_SAMPLED_PEERS: usize = 1 << 16;
+
+///
+/// The verification applied to a share, as decided by the sampling policy of the operator.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ShareVerification {
+    /// The share is fully verified, as the operator is not under load, or the peer is on probation or unknown.
+    Full,
+    /// The share is fully verified, as it was sampled among the shares of the peer under load.
+    Sampled,
+    /// The share is accepted without verification, as it was not sampled under load.
+    Skipped,
+}
+
+///
+/// The sampling statistics of a peer, keyed by its IP, so that provers may not escape probation by rotating their addresses.
+///
+#[derive(Clone, Debug)]
+pub struct SampledPeer {
+    /// The number of shares verified as samples under load.
+    pub sampled: u64,
+    /// The number of shares accepted without verification under load.
+    pub skipped: u64,
+    /// The number of sampled shares that failed verification.
+    pub failed: u64,
+    /// The number of shares revoked from the provers of the peer as a penalty for its failed samples.
+    pub revoked: u64,
+    /// The instant until which every share from the peer is fully verified.
+    pub probation_until: Option<Instant>,
+    /// The instant at which the peer last submitted a share under load.
+    pub last_seen: Instant,
+}
+
+impl Default for SampledPeer {
+    fn default() -> Self {
+        Self {
+            sampled: 0,
+            skipped: 0,
+            failed: 0,
+            revoked: 0,
+            probation_until: None,
+            last_seen: Instant::now(),
+        }
+    }
+}
+
+impl SampledPeer {
+    /// Returns `true` if every share from the peer is fully verified at this instant.
+    fn is_on_probation(&self) -> bool {
+        matches!(self.probation_until, Some(probation_until) if Instant::now() < probation_until)
+    }
+}
+
+///
+/// The sampling policy of share verification, bounding the verification CPU of the operator under extreme share load.
+///
+/// While the share rate exceeds the load threshold, only a random fraction of the shares from each peer is verified.
+/// Each sampled share stands for `1 / rate` shares, so when a sampled share fails, twice as many shares are revoked from
+/// its prover, and every share from the peer is fully verified for the duration of its probation. As an invalid share
+/// gains a share with probability `1 - rate`, and loses `2 / rate` shares with probability `rate`, submitting invalid
+/// shares is unprofitable in expectation, while the operator verifies only a bounded number of shares.
+///
+/// The shares of peers without an IP, such as the shares submitted over HTTP, are always fully verified.
+///
+#[derive(Debug)]
+pub struct ShareSampling {
+    /// The fraction of shares fully verified under load, in `(0, 1]`, where `1` disables sampling.
+    rate: f64,
+    /// The number of shares per second above which the operator is under load.
+    load_threshold: u64,
+    /// The share load := (start of the current window, shares in the current window, shares per second in the last window)
+    load: Mutex<(Instant, u64, u64)>,
+    /// The sampling statistics of each peer, by IP.
+    peers: DashMap<IpAddr, SampledPeer>,
+}
+
+impl Default for ShareSampling {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            load_threshold: u64::MAX,
+            load: Mutex::new((Instant::now(), 0, 0)),
+            peers: Default::default(),
+        }
+    }
+}
+
+impl ShareSampling {
+    ///
+    /// Initializes a new sampling policy, fully verifying the given fraction of shares above the given shares per second.
+    ///
+    pub fn new(rate: f64, load_threshold: u64) -> Result<Self> {
+        if !rate.is_finite() || rate <= 0.0 || rate > 1.0 {
+            return Err(anyhow!("The share sampling rate must be in (0, 1], found {}", rate));
+        }
+        Ok(Self {
+            rate,
+            load_threshold,
+            ..Default::default()
+        })
+    }
+
+    ///
+    /// Returns `true` if shares are sampled while the operator is under load.
+    ///
+    pub fn is_enabled(&self) -> bool {
+        self.rate < 1.0
+    }
+
+    ///
+    /// Returns `true` if the share rate of the last window exceeds the load threshold.
+    ///
+    pub fn is_under_load(&self) -> bool {
+        self.is_enabled() && self.load.lock().2 > self.load_threshold
+    }
+
+    ///
+    /// Records a share from the given peer against the load, and returns the verification to apply to it.
+    ///
+    pub fn select(&self, peer_ip: Option<IpAddr>) -> ShareVerification {
+        if !self.is_enabled() {
+            return ShareVerification::Full;
+        }
+
+        // Account for the share in the load of the current window.
+        let is_under_load = {
+            let mut load = self.load.lock();
+            if load.0.elapsed() >= LOAD_WINDOW {
+                let shares_per_sec = load.1 as f64 / load.0.elapsed().as_secs_f64();
+                *load = (Instant::now(), 0, shares_per_sec as u64);
+            }
+            load.1 = load.1.saturating_add(1);
+            load.2 > self.load_threshold
+        };
+        if !is_under_load {
+            return ShareVerification::Full;
+        }
+
+        // Fully verify the shares of the peers that cannot be placed on probation.
+        let peer_ip = match peer_ip {
+            Some(peer_ip) => peer_ip,
+            None => return ShareVerification::Full,
+        };
+        if self.peers.len() >= MAXIMUM_SAMPLED_PEERS && !self.peers.contains_key(&peer_ip) {
+            return ShareVerification::Full;
+        }
+
+        let mut state = self.peers.entry(peer_ip).or_default();
+        state.last_seen = Instant::now();
+        match state.is_on_probation() {
+            true => return ShareVerification::Full,
+            false => state.probation_until = None,
+        }
+
+        match rand::thread_rng().gen_bool(self.rate) {
+            true => {
+                state.sampled = state.sampled.saturating_add(1);
+                ShareVerification::Sampled
+            }
+            false => {
+                state.skipped = state.skipped.saturating_add(1);
+                ShareVerification::Skipped
+            }
+        }
+    }
+
+    ///
+    /// Records a failed sample from the given peer, placing it on probation,
+    /// and returns the number of shares to revoke from the prover of the sample.
+    ///
+    pub fn record_failure(&self, peer_ip: IpAddr) -> u64 {
+        // Each sampled share stands for `1 / rate` shares, and twice as many are revoked to make invalid shares a loss.
+        let penalty = ((1.0 / self.rate).ceil() as u64).saturating_mul(2);
+
+        let mut state = self.peers.entry(peer_ip).or_default();
+        state.failed = state.failed.saturating_add(1);
+        state.revoked = state.revoked.saturating_add(penalty);
+        state.probation_until = Some(Instant::now() + PROBATION_DURATION);
+        penalty
+    }
+
+    ///
+    /// Returns the sampling statistics of the given peer, if it submitted shares under load.
+    ///
+    pub fn get_peer(&self, peer_ip: &IpAddr) -> Option<SampledPeer> {
+        self.peers.get(peer_ip).map(|state| state.clone())
+    }
+
+    ///
+    /// Forgets the sampling statistics of the peers which are not on probation, and have been idle for `PEER_IDLE_TIMEOUT`.
+    ///
+    pub fn evict_idle_peers(&self) {
+        self.peers
+            .retain(|_, state| state.is_on_probation() || state.last_seen.elapsed() < PEER_IDLE_TIMEOUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    const PEER_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+    /// Returns a sampling policy that is under load, as if the previous window exceeded the load threshold.
+    fn under_load(rate: f64) -> ShareSampling {
+        let sampling = ShareSampling::new(rate, 10).unwrap();
+        *sampling.load.lock() = (Instant::now(), 0, 100);
+        sampling
+    }
+
+    #[test]
+    fn test_share_sampling_rate() {
+        assert!(ShareSampling::new(0.0, 10).is_err());
+        assert!(ShareSampling::new(1.5, 10).is_err());
+        assert!(ShareSampling::new(f64::NAN, 10).is_err());
+        assert!(!ShareSampling::new(1.0, 10).unwrap().is_enabled());
+        assert!(ShareSampling::new(0.1, 10).unwrap().is_enabled());
+    }
+
+    #[test]
+    fn test_share_sampling_without_load() {
+        // Sampling is disabled by default.
+        let sampling = ShareSampling::default();
+        assert!(!sampling.is_under_load());
+        assert!((0..100).all(|_| sampling.select(Some(PEER_IP)) == ShareVerification::Full));
+
+        // Below the load threshold, every share is fully verified.
+        let sampling = ShareSampling::new(0.1, 1_000_000).unwrap();
+        assert!((0..100).all(|_| sampling.select(Some(PEER_IP)) == ShareVerification::Full));
+        assert!(sampling.get_peer(&PEER_IP).is_none());
+    }
+
+    #[test]
+    fn test_share_sampling_under_load() {
+        let sampling = under_load(0.1);
+        assert!(sampling.is_under_load());
+
+        let verifications: Vec<_> = (0..1_000).map(|_| sampling.select(Some(PEER_IP))).collect();
+        let sampled = verifications
+            .iter()
+            .filter(|verification| **verification == ShareVerification::Sampled)
+            .count();
+        assert!(sampled > 0 && sampled < 1_000);
+        assert!(!verifications.contains(&ShareVerification::Full));
+
+        let state = sampling.get_peer(&PEER_IP).unwrap();
+        assert_eq!(state.sampled as usize, sampled);
+        assert_eq!(state.skipped as usize, 1_000 - sampled);
+
+        // The shares of peers without an IP are always fully verified.
+        assert!((0..100).all(|_| sampling.select(None) == ShareVerification::Full));
+    }
+
+    #[test]
+    fn test_share_sampling_failure() {
+        let sampling = under_load(0.1);
+
+        // A failed sample revokes twice the shares it stands for, and places the peer on probation.
+        assert_eq!(sampling.record_failure(PEER_IP), 20);
+        assert!((0..100).all(|_| sampling.select(Some(PEER_IP)) == ShareVerification::Full));
+
+        let state = sampling.get_peer(&PEER_IP).unwrap();
+        assert_eq!(state.failed, 1);
+        assert_eq!(state.revoked, 20);
+
+        // Other peers are still sampled.
+        let other = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+        assert!((0..100).all(|_| sampling.select(Some(other)) != ShareVerification::Full));
+
+        // The peers on probation are not evicted, while the idle peers are.
+        sampling.peers.get_mut(&PEER_IP).unwrap().last_seen -= PEER_IDLE_TIMEOUT;
+        sampling.peers.get_mut(&other).unwrap().last_seen -= PEER_IDLE_TIMEOUT;
+        sampling.evict_idle_peers();
+        assert!(sampling.get_peer(&PEER_IP).is_some());
+        assert!(sampling.get_peer(&other).is_none());
+    }
+}
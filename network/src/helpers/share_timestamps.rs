@@ -0,0 +1,224 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::prelude::*;
+
+use anyhow::{anyhow, Result};
+use dashmap::DashMap;
+use serde::Serialize;
+
+/// The default tolerance of the timestamps of the shares, in seconds.
+pub const DEFAULT_SHARE_TIMESTAMP_TOLERANCE_IN_SECS: u64 = 60;
+/// The weight of each share in the running clock offset of its prover.
+const CLOCK_OFFSET_WEIGHT: f64 = 0.1;
+/// The number of shares a prover submits before its clock offset is considered persistent.
+const MINIMUM_SKEW_SAMPLES: u64 = 10;
+
+///
+/// The clock of a prover, as measured by the timestamps of its shares.
+///
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ProverClock {
+    /// The running offset of the clock of the prover from the clock of the operator, in seconds.
+    pub clock_offset_in_secs: f64,
+    /// The number of timestamped shares submitted by the prover.
+    pub shares: u64,
+    /// The number of shares rejected for their timestamp.
+    pub rejected: u64,
+    /// `true` if the clock of the prover is persistently skewed by over half the tolerance.
+    pub is_skewed: bool,
+}
+
+///
+/// The replay window of the shares, which rejects the shares whose timestamp is off the clock of the operator
+/// by more than the tolerance, or predates the job they were computed for, and tracks the clock offset of each prover.
+///
+/// A share held back or replayed after the tolerance is rejected, as is a share stamped by a grossly skewed clock.
+/// A prover whose clock is persistently skewed by over half the tolerance is flagged, so its operator can warn it
+/// before its shares are rejected.
+///
+#[derive(Debug)]
+pub struct ShareTimestamps<N: Network> {
+    /// The maximum offset of the timestamp of a share from the clock of the operator, in seconds.
+    tolerance_in_secs: i64,
+    /// The clock of each prover.
+    provers: DashMap<Address<N>, ProverClock>,
+}
+
+impl<N: Network> Default for ShareTimestamps<N> {
+    fn default() -> Self {
+        Self {
+            tolerance_in_secs: DEFAULT_SHARE_TIMESTAMP_TOLERANCE_IN_SECS as i64,
+            provers: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> ShareTimestamps<N> {
+    ///
+    /// Initializes a new replay window of the given tolerance, in seconds.
+    ///
+    pub fn new(tolerance_in_secs: u64) -> Result<Self> {
+        if tolerance_in_secs == 0 || tolerance_in_secs > i64::MAX as u64 {
+            return Err(anyhow!("The share timestamp tolerance must be a positive number of seconds"));
+        }
+        Ok(Self {
+            tolerance_in_secs: tolerance_in_secs as i64,
+            ..Default::default()
+        })
+    }
+
+    ///
+    /// Returns the maximum offset of the timestamp of a share from the clock of the operator, in seconds.
+    ///
+    pub fn tolerance_in_secs(&self) -> i64 {
+        self.tolerance_in_secs
+    }
+
+    ///
+    /// Validates the timestamp of a share of the given prover, for a job issued at the given UNIX timestamp,
+    /// against the given time of the operator, and records it in the clock of the prover.
+    ///
+    pub fn validate(&self, prover: Address<N>, timestamp: i64, job_issued_at: i64, now: i64) -> Result<()> {
+        let offset = timestamp.saturating_sub(now);
+
+        let mut clock = self.provers.entry(prover).or_default();
+        clock.clock_offset_in_secs = match clock.shares {
+            0 => offset as f64,
+            _ => clock.clock_offset_in_secs + CLOCK_OFFSET_WEIGHT * (offset as f64 - clock.clock_offset_in_secs),
+        };
+        clock.shares = clock.shares.saturating_add(1);
+
+        // Flag the prover once its clock is persistently skewed, and clear the flag once its clock is corrected.
+        let is_skewed = clock.shares >= MINIMUM_SKEW_SAMPLES && clock.clock_offset_in_secs.abs() > (self.tolerance_in_secs / 2) as f64;
+        if is_skewed && !clock.is_skewed {
+            warn!(
+                "The clock of prover {} is skewed by {:.0} seconds, beyond half the share timestamp tolerance of {} seconds",
+                prover, clock.clock_offset_in_secs, self.tolerance_in_secs
+            );
+        }
+        clock.is_skewed = is_skewed;
+
+        let result = if offset.abs() > self.tolerance_in_secs {
+            Err(anyhow!(
+                "The share timestamp {} is {} seconds off the clock of the operator, beyond the tolerance of {} seconds",
+                timestamp,
+                offset,
+                self.tolerance_in_secs
+            ))
+        } else if timestamp < job_issued_at.saturating_sub(self.tolerance_in_secs) {
+            Err(anyhow!("The share timestamp {} predates its job, issued at {}", timestamp, job_issued_at))
+        } else {
+            Ok(())
+        };
+        if result.is_err() {
+            clock.rejected = clock.rejected.saturating_add(1);
+        }
+        result
+    }
+
+    ///
+    /// Returns the clock of the given prover, if it submitted a timestamped share.
+    ///
+    pub fn get_prover(&self, prover: &Address<N>) -> Option<ProverClock> {
+        self.provers.get(prover).map(|clock| clock.clone())
+    }
+
+    ///
+    /// Returns the clocks of the provers which submitted a timestamped share.
+    ///
+    pub fn to_provers(&self) -> Vec<(Address<N>, ProverClock)> {
+        self.provers.iter().map(|entry| (*entry.key(), entry.value().clone())).collect()
+    }
+
+    ///
+    /// Returns the median clock offset of the provers, in seconds, and the number of provers, if any submitted a timestamped share.
+    ///
+    pub fn median_clock_offset(&self) -> Option<(f64, usize)> {
+        let mut clock_offsets: Vec<f64> = self.provers.iter().map(|entry| entry.value().clock_offset_in_secs).collect();
+        clock_offsets.sort_unstable_by(|a, b| a.total_cmp(b));
+        clock_offsets.get(clock_offsets.len() / 2).map(|clock_offset| (*clock_offset, clock_offsets.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    use rand::thread_rng;
+
+    const NOW: i64 = 1_650_000_000;
+
+    #[test]
+    fn test_share_timestamp_tolerance() {
+        assert!(ShareTimestamps::<CurrentNetwork>::new(0).is_err());
+        assert_eq!(ShareTimestamps::<CurrentNetwork>::new(30).unwrap().tolerance_in_secs(), 30);
+        assert_eq!(
+            ShareTimestamps::<CurrentNetwork>::default().tolerance_in_secs(),
+            DEFAULT_SHARE_TIMESTAMP_TOLERANCE_IN_SECS as i64
+        );
+    }
+
+    #[test]
+    fn test_share_timestamp_window() {
+        let prover = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+        let timestamps = ShareTimestamps::<CurrentNetwork>::new(60).unwrap();
+
+        // Shares within the tolerance of the clock of the operator are accepted.
+        assert!(timestamps.validate(prover, NOW, NOW - 10, NOW).is_ok());
+        assert!(timestamps.validate(prover, NOW - 60, NOW - 90, NOW).is_ok());
+        assert!(timestamps.validate(prover, NOW + 60, NOW, NOW).is_ok());
+
+        // Shares replayed after the tolerance, or stamped in the future, are rejected.
+        assert!(timestamps.validate(prover, NOW - 61, NOW - 90, NOW).is_err());
+        assert!(timestamps.validate(prover, NOW + 61, NOW, NOW).is_err());
+
+        // Shares stamped before their job was issued, beyond the tolerance, are rejected.
+        assert!(timestamps.validate(prover, NOW - 30, NOW + 40, NOW).is_err());
+        assert!(timestamps.validate(prover, NOW - 30, NOW + 20, NOW).is_ok());
+
+        let clock = timestamps.get_prover(&prover).unwrap();
+        assert_eq!(clock.shares, 7);
+        assert_eq!(clock.rejected, 3);
+    }
+
+    #[test]
+    fn test_share_timestamp_skew() {
+        let prover = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+        let other = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+        let timestamps = ShareTimestamps::<CurrentNetwork>::new(60).unwrap();
+        assert!(timestamps.median_clock_offset().is_none());
+
+        // A prover whose clock is persistently skewed by over half the tolerance is flagged, though its shares are accepted.
+        for _ in 0..MINIMUM_SKEW_SAMPLES {
+            assert!(timestamps.validate(prover, NOW + 45, NOW, NOW).is_ok());
+            assert!(timestamps.validate(other, NOW - 1, NOW - 10, NOW).is_ok());
+        }
+        let clock = timestamps.get_prover(&prover).unwrap();
+        assert!(clock.is_skewed);
+        assert!((clock.clock_offset_in_secs - 45.0).abs() < 1e-9);
+        assert!(!timestamps.get_prover(&other).unwrap().is_skewed);
+        assert_eq!(timestamps.to_provers().len(), 2);
+        assert_eq!(timestamps.median_clock_offset().map(|(_, number_of_provers)| number_of_provers), Some(2));
+
+        // The flag is cleared once the clock of the prover is corrected.
+        for _ in 0..50 {
+            assert!(timestamps.validate(prover, NOW, NOW, NOW).is_ok());
+        }
+        assert!(!timestamps.get_prover(&prover).unwrap().is_skewed);
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::prelude::*;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs::File, path::Path};
+
+/// The name of the sub-pool used when no sub-pools are configured.
+pub const DEFAULT_SUB_POOL: &str = "default";
+/// The number of basis points in a whole, used to express fees and shares of jobs.
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+///
+/// A logical pool served by the operator, with its own fee, payout address, and set of provers.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SubPool<N: Network> {
+    /// The unique name of the sub-pool.
+    pub name: String,
+    /// The fee charged by the sub-pool, in basis points.
+    #[serde(default)]
+    pub fee_basis_points: u16,
+    /// The address receiving the fees of the sub-pool; if `None`, the operator address is used.
+    #[serde(default)]
+    pub payout_address: Option<Address<N>>,
+    /// The provers admitted to the sub-pool; if `None`, the sub-pool is open to any prover.
+    #[serde(default)]
+    pub provers: Option<HashSet<Address<N>>>,
+    /// The TLS server name (SNI) routing provers on the TLS listener to the sub-pool, if any.
+    #[serde(default)]
+    pub server_name: Option<String>,
+    /// If `true`, provers must connect over TLS with a client certificate issued by the operator's client CA.
+    #[serde(default)]
+    pub require_client_cert: bool,
+}
+
+impl<N: Network> SubPool<N> {
+    ///
+    /// Returns `true` if the sub-pool is open to any prover.
+    ///
+    pub fn is_public(&self) -> bool {
+        self.provers.is_none() && !self.require_client_cert
+    }
+
+    ///
+    /// Returns `true` if the given prover is admitted to the sub-pool, given whether its connection is authenticated.
+    ///
+    pub fn admits(&self, prover: &Address<N>, is_authenticated: bool) -> bool {
+        (!self.require_client_cert || is_authenticated) && self.provers.as_ref().map_or(true, |provers| provers.contains(prover))
+    }
+}
+
+///
+/// The sub-pools served by the operator, which share the sync and block templates of the node.
+///
+/// A prover is assigned to the first public sub-pool, unless it connects over TLS and requests the server name (SNI)
+/// of another sub-pool. As the address a prover registers with is not authenticated, a private sub-pool admits
+/// its provers by their client certificate, and its list of provers only restricts the addresses they may register.
+///
+#[derive(Clone, Debug)]
+pub struct SubPools<N: Network> {
+    sub_pools: Vec<SubPool<N>>,
+}
+
+impl<N: Network> Default for SubPools<N> {
+    fn default() -> Self {
+        Self {
+            sub_pools: vec![SubPool {
+                name: DEFAULT_SUB_POOL.to_string(),
+                fee_basis_points: 0,
+                payout_address: None,
+                provers: None,
+                server_name: None,
+                require_client_cert: false,
+            }],
+        }
+    }
+}
+
+impl<N: Network> SubPools<N> {
+    ///
+    /// Initializes the sub-pools from the given list, ensuring they are well-formed.
+    ///
+    pub fn new(sub_pools: Vec<SubPool<N>>) -> Result<Self> {
+        if sub_pools.is_empty() {
+            return Err(anyhow!("At least one sub-pool must be configured"));
+        }
+
+        let mut names = HashSet::new();
+        let mut server_names = HashSet::new();
+        let mut provers = HashSet::new();
+        for sub_pool in &sub_pools {
+            if !names.insert(sub_pool.name.as_str()) {
+                return Err(anyhow!("Sub-pool '{}' is configured more than once", sub_pool.name));
+            }
+            if sub_pool.fee_basis_points > MAX_BASIS_POINTS {
+                return Err(anyhow!(
+                    "Sub-pool '{}' has a fee of {} basis points, which exceeds {}",
+                    sub_pool.name,
+                    sub_pool.fee_basis_points,
+                    MAX_BASIS_POINTS
+                ));
+            }
+            if let Some(server_name) = &sub_pool.server_name {
+                if !server_names.insert(server_name.to_lowercase()) {
+                    return Err(anyhow!("Server name '{}' is routed to more than one sub-pool", server_name));
+                }
+            }
+            // Ensure the provers of a private sub-pool are admitted by their client certificate, not their declared address.
+            if sub_pool.provers.is_some() && (sub_pool.server_name.is_none() || !sub_pool.require_client_cert) {
+                return Err(anyhow!(
+                    "Sub-pool '{}' lists its provers, so it requires a server name and client certificates",
+                    sub_pool.name
+                ));
+            }
+            // Ensure each prover is admitted to at most one private sub-pool, so its shares are attributed unambiguously.
+            for prover in sub_pool.provers.iter().flatten() {
+                if !provers.insert(*prover) {
+                    return Err(anyhow!("Prover {} is admitted to more than one sub-pool", prover));
+                }
+            }
+        }
+
+        Ok(Self { sub_pools })
+    }
+
+    ///
+    /// Opens the sub-pools from the JSON file at the given path.
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path.as_ref()).map_err(|error| anyhow!("Failed to open {}: {}", path.as_ref().display(), error))?;
+        let sub_pools: Vec<SubPool<N>> =
+            serde_json::from_reader(file).map_err(|error| anyhow!("Failed to parse {}: {}", path.as_ref().display(), error))?;
+        Self::new(sub_pools)
+    }
+
+    ///
+    /// Returns the sub-pool with the given name, if it exists.
+    ///
+    pub fn get_by_name(&self, name: &str) -> Option<&SubPool<N>> {
+        self.sub_pools.iter().find(|sub_pool| sub_pool.name == name)
+    }
+
+    ///
+    /// Returns the sub-pool serving the given TLS server name, if any.
+    ///
+    pub fn get_by_server_name(&self, server_name: &str) -> Option<&SubPool<N>> {
+        self.sub_pools
+            .iter()
+            .find(|sub_pool| matches!(&sub_pool.server_name, Some(name) if name.eq_ignore_ascii_case(server_name)))
+    }
+
+    ///
+    /// Returns the sub-pool that provers are assigned to by default, if any sub-pool is public.
+    ///
+    pub fn public(&self) -> Option<&SubPool<N>> {
+        self.sub_pools.iter().find(|sub_pool| sub_pool.is_public())
+    }
+
+    ///
+    /// Returns an iterator over the sub-pools.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &SubPool<N>> {
+        self.sub_pools.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_environment::CurrentNetwork;
+
+    use rand::thread_rng;
+
+    fn sub_pool(name: &str, provers: Option<HashSet<Address<CurrentNetwork>>>) -> SubPool<CurrentNetwork> {
+        // A private sub-pool admits its provers by their client certificate.
+        let is_private = provers.is_some();
+        SubPool {
+            name: name.to_string(),
+            fee_basis_points: 100,
+            payout_address: None,
+            provers,
+            server_name: is_private.then(|| format!("{}.pool.example.com", name)),
+            require_client_cert: is_private,
+        }
+    }
+
+    #[test]
+    fn test_prover_assignment() {
+        let rng = &mut thread_rng();
+        let private_prover = Account::<CurrentNetwork>::new(rng).address();
+        let public_prover = Account::<CurrentNetwork>::new(rng).address();
+
+        let sub_pools = SubPools::new(vec![
+            sub_pool("private", Some([private_prover].into_iter().collect())),
+            sub_pool("public", None),
+        ])
+        .unwrap();
+
+        // A prover is never assigned to a private sub-pool by its declared address.
+        assert_eq!(sub_pools.public().unwrap().name, "public");
+        let private = sub_pools.get_by_server_name("private.pool.example.com").unwrap();
+        assert!(private.admits(&private_prover, true));
+        assert!(!private.admits(&private_prover, false));
+        assert!(!private.admits(&public_prover, true));
+    }
+
+    #[test]
+    fn test_private_sub_pools_reject_unknown_provers() {
+        let private_prover = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+
+        let sub_pools = SubPools::new(vec![sub_pool("private", Some([private_prover].into_iter().collect()))]).unwrap();
+        assert!(sub_pools.public().is_none());
+    }
+
+    #[test]
+    fn test_invalid_sub_pools() {
+        let prover = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+
+        // Duplicate names.
+        assert!(SubPools::new(vec![sub_pool("a", None), sub_pool("a", None)]).is_err());
+        // A prover in two sub-pools.
+        let provers: HashSet<_> = [prover].into_iter().collect();
+        assert!(SubPools::new(vec![sub_pool("a", Some(provers.clone())), sub_pool("b", Some(provers.clone()))]).is_err());
+        // An excessive fee.
+        let mut excessive = sub_pool("a", None);
+        excessive.fee_basis_points = MAX_BASIS_POINTS + 1;
+        assert!(SubPools::new(vec![excessive]).is_err());
+        // No sub-pools.
+        assert!(SubPools::<CurrentNetwork>::new(vec![]).is_err());
+        // A private sub-pool admitting its provers by their declared address.
+        let mut unauthenticated = sub_pool("a", Some(provers.clone()));
+        unauthenticated.require_client_cert = false;
+        assert!(SubPools::new(vec![unauthenticated]).is_err());
+        let mut unrouted = sub_pool("a", Some(provers.clone()));
+        unrouted.server_name = None;
+        assert!(SubPools::new(vec![unrouted]).is_err());
+        // A server name routed to two sub-pools.
+        let mut first = sub_pool("a", None);
+        first.server_name = Some("pool.example.com".to_string());
+        let mut second = sub_pool("b", None);
+        second.server_name = Some("POOL.example.com".to_string());
+        assert!(SubPools::new(vec![first, second]).is_err());
+    }
+
+    #[test]
+    fn test_server_name_routing() {
+        let rng = &mut thread_rng();
+        let prover = Account::<CurrentNetwork>::new(rng).address();
+
+        let mut farm = sub_pool("farm", None);
+        farm.server_name = Some("farm.pool.example.com".to_string());
+        farm.require_client_cert = true;
+        let sub_pools = SubPools::new(vec![farm, sub_pool("public", None)]).unwrap();
+
+        // The server name is matched case-insensitively.
+        let farm = sub_pools.get_by_server_name("Farm.Pool.Example.com").unwrap();
+        assert_eq!(farm.name, "farm");
+        assert!(sub_pools.get_by_server_name("other.example.com").is_none());
+
+        // A sub-pool requiring client certificates only admits authenticated provers, and is never a default.
+        assert!(farm.admits(&prover, true));
+        assert!(!farm.admits(&prover, false));
+        assert!(!farm.is_public());
+        assert_eq!(sub_pools.public().unwrap().name, "public");
+    }
+}
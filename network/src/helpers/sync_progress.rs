@@ -0,0 +1,89 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The window over which the sync throughput is measured.
+const SYNC_THROUGHPUT_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// The maximum number of block height samples retained in the window.
+const MAXIMUM_SYNC_SAMPLES: usize = 1024;
+
+///
+/// The block heights of the ledger sampled over time, to measure the throughput of syncing.
+///
+#[derive(Debug, Default)]
+pub struct SyncProgress {
+    /// The sampled block heights := (sampled_at, block_height)
+    samples: Mutex<VecDeque<(Instant, u32)>>,
+}
+
+impl SyncProgress {
+    ///
+    /// Records the given block height at the given instant, and returns the throughput in blocks per second
+    /// since the oldest sample in the window, or `None` if no earlier sample exists.
+    ///
+    pub fn record(&self, now: Instant, block_height: u32) -> Option<f64> {
+        let mut samples = self.samples.lock();
+
+        // Drop the samples outside of the window, and all samples if the ledger has reverted.
+        while let Some((sampled_at, _)) = samples.front() {
+            if now.saturating_duration_since(*sampled_at) <= SYNC_THROUGHPUT_WINDOW && samples.len() < MAXIMUM_SYNC_SAMPLES {
+                break;
+            }
+            samples.pop_front();
+        }
+        if matches!(samples.back(), Some((_, latest_block_height)) if *latest_block_height > block_height) {
+            samples.clear();
+        }
+        samples.push_back((now, block_height));
+
+        let (oldest_sampled_at, oldest_block_height) = *samples.front()?;
+        let elapsed = now.saturating_duration_since(oldest_sampled_at).as_secs_f64();
+        match elapsed > 0.0 {
+            true => Some(block_height.saturating_sub(oldest_block_height) as f64 / elapsed),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_throughput() {
+        let progress = SyncProgress::default();
+        let start = Instant::now();
+
+        // The first sample has no throughput.
+        assert_eq!(progress.record(start, 100), None);
+        // The throughput is measured since the oldest sample in the window.
+        assert_eq!(progress.record(start + Duration::from_secs(10), 150), Some(5.0));
+        assert_eq!(progress.record(start + Duration::from_secs(20), 300), Some(10.0));
+
+        // Samples outside of the window are dropped, so the oldest sample is now the one at 20 seconds.
+        let later = start + Duration::from_secs(20) + SYNC_THROUGHPUT_WINDOW;
+        let block_height = 300 + 10 * SYNC_THROUGHPUT_WINDOW.as_secs() as u32;
+        assert_eq!(progress.record(later, block_height), Some(10.0));
+
+        // A revert of the ledger restarts the measurement.
+        assert_eq!(progress.record(later + Duration::from_secs(1), block_height - 10), None);
+    }
+}
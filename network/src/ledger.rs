@@ -23,9 +23,10 @@ use crate::{
     PeersRouter,
     ProverRequest,
     ProverRouter,
+    ServingMode,
 };
 use snarkos_environment::{
-    helpers::{NodeType, State},
+    helpers::{next_request_id, NodeType, State},
     Environment,
 };
 use snarkos_storage::{storage::Storage, BlockLocators, LedgerState, MAXIMUM_LINEAR_BLOCK_LOCATORS};
@@ -47,6 +48,7 @@ use tokio::{
     sync::{mpsc, oneshot, Mutex, RwLock},
     task,
 };
+use tracing::Instrument;
 
 /// The maximum number of unconfirmed blocks that can be held by the ledger.
 const MAXIMUM_UNCONFIRMED_BLOCKS: u32 = 250;
@@ -72,13 +74,13 @@ pub enum LedgerRequest<N: Network> {
     Failure(SocketAddr, String),
     /// Heartbeat := (prover_router)
     Heartbeat(ProverRouter<N>),
-    /// Pong := (peer_ip, node_type, status, is_fork, block_locators)
-    Pong(SocketAddr, NodeType, State, Option<bool>, BlockLocators<N>),
+    /// Pong := (peer_ip, node_type, serving_mode, status, is_fork, block_locators)
+    Pong(SocketAddr, NodeType, ServingMode, State, Option<bool>, BlockLocators<N>),
     /// UnconfirmedBlock := (peer_ip, block, prover_router)
     UnconfirmedBlock(SocketAddr, Block<N>, ProverRouter<N>),
 }
 
-pub type PeersState<N> = HashMap<SocketAddr, Option<(NodeType, State, Option<bool>, u32, BlockLocators<N>)>>;
+pub type PeersState<N> = HashMap<SocketAddr, Option<(NodeType, ServingMode, State, Option<bool>, u32, BlockLocators<N>)>>;
 
 ///
 /// A ledger for a specific network on the node server.
@@ -113,16 +115,41 @@ pub struct Ledger<N: Network, E: Environment> {
 }
 
 impl<N: Network, E: Environment> Ledger<N, E> {
-    /// Initializes a new instance of the ledger.
-    pub async fn open<S: Storage, P: AsRef<Path> + Copy>(path: P, peers_router: PeersRouter<N, E>) -> Result<Arc<Self>> {
-        // Initialize an mpsc channel for sending requests to the `Ledger` struct.
-        let (ledger_router, mut ledger_handler) = mpsc::channel(1024);
-
+    /// Initializes a new instance of the ledger, with the address index enabled or disabled.
+    pub async fn open<S: Storage, P: AsRef<Path> + Copy>(
+        path: P,
+        address_index: bool,
+        peers_router: PeersRouter<N, E>,
+    ) -> Result<Arc<Self>> {
         let canon = Arc::new(LedgerState::open_writer::<S, P>(path)?);
+        canon.set_address_index(address_index)?;
         let (canon_reader, reader_resource) = LedgerState::open_reader::<S, P>(path)?;
         // Register the thread; no need to provide an id, as it will run indefinitely.
         E::resources().register(reader_resource, None);
 
+        Self::initialize(canon, canon_reader, peers_router).await
+    }
+
+    /// Initializes a new read-only instance of the ledger, which follows the ledger at the given `path`
+    /// as it is synced by another node, keeping the state of its secondary instance at the given `secondary_path`.
+    pub async fn open_explorer<S: Storage, P: AsRef<Path>>(
+        path: P,
+        secondary_path: P,
+        peers_router: PeersRouter<N, E>,
+    ) -> Result<Arc<Self>> {
+        let (canon_reader, reader_resource) = LedgerState::open_secondary_reader::<S, P>(path, secondary_path)?;
+        // Register the thread; no need to provide an id, as it will run indefinitely.
+        E::resources().register(reader_resource, None);
+
+        // Note: The explorer never writes to the ledger, so the canon is the reader itself.
+        Self::initialize(canon_reader.clone(), canon_reader, peers_router).await
+    }
+
+    /// Initializes the ledger from the given canon and reader, and starts its handler.
+    async fn initialize(canon: Arc<LedgerState<N>>, canon_reader: LedgerReader<N>, peers_router: PeersRouter<N, E>) -> Result<Arc<Self>> {
+        // Initialize an mpsc channel for sending requests to the `Ledger` struct.
+        let (ledger_router, mut ledger_handler) = mpsc::channel(1024);
+
         // Initialize the ledger.
         let ledger = Arc::new(Self {
             ledger_router,
@@ -152,7 +179,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                         // Update the state of the ledger.
                         // Note: Do not wrap this call in a `task::spawn` as `BlockResponse` messages
                         // will end up being processed out of order.
-                        ledger.update(request).await;
+                        ledger.update(request).instrument(debug_span!("ledger", request_id = next_request_id())).await;
                     }
                 }),
             );
@@ -255,11 +282,11 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                     connected_peers,
                 );
             }
-            LedgerRequest::Pong(peer_ip, node_type, status, is_fork, block_locators) => {
+            LedgerRequest::Pong(peer_ip, node_type, serving_mode, status, is_fork, block_locators) => {
                 // Ensure the peer has been initialized in the ledger.
                 self.initialize_peer(peer_ip).await;
                 // Process the pong.
-                self.update_peer(peer_ip, node_type, status, is_fork, block_locators).await;
+                self.update_peer(peer_ip, node_type, serving_mode, status, is_fork, block_locators).await;
             }
             LedgerRequest::UnconfirmedBlock(peer_ip, block, prover_router) => {
                 // Ensure the node is not peering.
@@ -339,7 +366,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
 
             // Check if any of the peers are ahead and have a larger block height.
             for (peer_ip, peer_state) in peers_state.iter() {
-                if let Some((node_type, status, Some(_), block_height, block_locators)) = peer_state {
+                if let Some((node_type, _, status, Some(_), block_height, block_locators)) = peer_state {
                     // Retrieve the cumulative weight, defaulting to the block height if it does not exist.
                     let cumulative_weight = match block_locators.get_cumulative_weight(*block_height) {
                         Some(cumulative_weight) => cumulative_weight,
@@ -439,7 +466,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 let latest_cumulative_weight = self.canon.latest_cumulative_weight();
                 // Iterate through the connected peers, to determine if the ledger state is out of date.
                 for (_, peer_state) in self.peers_state.read().await.iter() {
-                    if let Some((_, _, Some(_), block_height, block_locators)) = peer_state {
+                    if let Some((_, _, _, Some(_), block_height, block_locators)) = peer_state {
                         // Retrieve the cumulative weight, defaulting to the block height if it does not exist.
                         let cumulative_weight = match block_locators.get_cumulative_weight(*block_height) {
                             Some(cumulative_weight) => cumulative_weight,
@@ -534,7 +561,11 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                         );
 
                         #[cfg(any(feature = "test", feature = "prometheus"))]
-                        metrics::gauge!(metrics::blocks::HEIGHT, latest_block_height as f64);
+                        {
+                            metrics::gauge!(metrics::blocks::HEIGHT, latest_block_height as f64);
+                            metrics::gauge!(metrics::blocks::CUMULATIVE_WEIGHT, self.canon.latest_cumulative_weight() as f64);
+                            metrics::increment_counter!(metrics::blocks::ADDED);
+                        }
 
                         // Update the timestamp of the last block increment.
                         *self.last_block_update_timestamp.write().await = Instant::now();
@@ -586,7 +617,10 @@ impl<N: Network, E: Environment> Ledger<N, E> {
                 info!("Ledger successfully reverted to block {}", latest_block_height);
 
                 #[cfg(any(feature = "test", feature = "prometheus"))]
-                metrics::gauge!(metrics::blocks::HEIGHT, latest_block_height as f64);
+                {
+                    metrics::gauge!(metrics::blocks::HEIGHT, latest_block_height as f64);
+                    metrics::gauge!(metrics::blocks::CUMULATIVE_WEIGHT, self.canon.latest_cumulative_weight() as f64);
+                }
 
                 // Update the last block update timestamp.
                 *self.last_block_update_timestamp.write().await = Instant::now();
@@ -646,6 +680,7 @@ impl<N: Network, E: Environment> Ledger<N, E> {
         &self,
         peer_ip: SocketAddr,
         node_type: NodeType,
+        serving_mode: ServingMode,
         status: State,
         is_fork: Option<bool>,
         block_locators: BlockLocators<N>,
@@ -721,7 +756,9 @@ impl<N: Network, E: Environment> Ledger<N, E> {
             );
 
             match self.peers_state.write().await.get_mut(&peer_ip) {
-                Some(peer_state) => *peer_state = Some((node_type, status, is_fork, latest_block_height_of_peer, block_locators)),
+                Some(peer_state) => {
+                    *peer_state = Some((node_type, serving_mode, status, is_fork, latest_block_height_of_peer, block_locators))
+                }
                 None => self.add_failure(peer_ip, format!("Missing ledger state for {}", peer_ip)).await,
             };
         }
@@ -61,6 +61,23 @@ impl<T: 'static + Serialize + DeserializeOwned + Send> Data<T> {
         }
     }
 
+    ///
+    /// Deserializes the object, along with the field that trails it in the buffer, if any.
+    /// The trailing field of an object that is not buffered is `None`.
+    ///
+    pub async fn deserialize_with_trailing<U: 'static + DeserializeOwned + Send>(self) -> bincode::Result<(T, Option<U>)> {
+        match self {
+            Self::Object(x) => Ok((x, None)),
+            Self::Buffer(bytes) => match task::spawn_blocking(move || deserialize_with_trailing(&bytes)).await {
+                Ok(x) => x,
+                Err(error) => Err(Box::new(bincode::ErrorKind::Custom(format!(
+                    "Dedicated deserialization failed: {}",
+                    error
+                )))),
+            },
+        }
+    }
+
     pub fn serialize_blocking_into<W: Write>(&self, writer: &mut W) -> Result<()> {
         match self {
             Self::Object(x) => Ok(bincode::serialize_into(writer, x)?),
@@ -82,6 +99,16 @@ impl<T: 'static + Serialize + DeserializeOwned + Send> Data<T> {
     }
 }
 
+/// Deserializes an object from the given bytes, followed by a trailing field, if any bytes remain.
+fn deserialize_with_trailing<T: DeserializeOwned, U: DeserializeOwned>(mut bytes: &[u8]) -> bincode::Result<(T, Option<U>)> {
+    let object = bincode::deserialize_from(&mut bytes)?;
+    let trailing = match bytes.is_empty() {
+        true => None,
+        false => Some(bincode::deserialize(bytes)?),
+    };
+    Ok((object, trailing))
+}
+
 /// The reason behind the node disconnecting from a peer.
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum DisconnectReason {
@@ -109,6 +136,28 @@ pub enum DisconnectReason {
     YouNeedToSyncFirst,
     /// The peer's listening port is closed.
     YourPortIsClosed(u16),
+    // The reasons below are appended after the existing ones, as the reasons are encoded by their position.
+    /// The peer's node type is not permitted to connect inbound to the node.
+    NodeTypeNotPermitted,
+    /// The node only serves its block locators, and declines requests for full blocks.
+    BlocksNotServed,
+    /// The pool is going into maintenance, and its provers may reconnect to the given backup pool.
+    PoolMaintenance(Option<SocketAddr>),
+}
+
+/// The blocks a node is willing to serve to its peers, as advertised in its handshake.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ServingMode {
+    /// The node serves full blocks.
+    Full,
+    /// The node only serves the block headers in its block locators, and declines to serve full blocks.
+    HeadersOnly,
+}
+
+impl Default for ServingMode {
+    fn default() -> Self {
+        Self::Full
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -117,8 +166,8 @@ pub enum Message<N: Network, E: Environment> {
     BlockRequest(u32, u32),
     /// BlockResponse := (block)
     BlockResponse(Data<Block<N>>),
-    /// ChallengeRequest := (version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight)
-    ChallengeRequest(u32, u32, NodeType, State, u16, u64, u128),
+    /// ChallengeRequest := (version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight, serving_mode, timestamp)
+    ChallengeRequest(u32, u32, NodeType, State, u16, u64, u128, ServingMode, Option<i64>),
     /// ChallengeResponse := (block_header)
     ChallengeResponse(Data<BlockHeader<N>>),
     /// Disconnect := ()
@@ -139,8 +188,9 @@ pub enum Message<N: Network, E: Environment> {
     PoolRegister(Address<N>),
     /// PoolRequest := (share_difficulty, block_template)
     PoolRequest(u64, Data<BlockTemplate<N>>),
-    /// PoolResponse := (address, nonce, proof)
-    PoolResponse(Address<N>, N::PoSWNonce, Data<PoSWProof<N>>),
+    /// PoolResponse := (address, nonce, proof, timestamp)
+    /// The timestamp of a received response is held in the buffer of the proof, after the proof.
+    PoolResponse(Address<N>, N::PoSWNonce, Data<PoSWProof<N>>, Option<i64>),
     NewBlockTemplate(Data<BlockTemplate<N>>),
     PoolBlock(N::PoSWNonce, Data<PoSWProof<N>>),
     /// Unused
@@ -206,11 +256,26 @@ impl<N: Network, E: Environment> Message<N, E> {
                 Ok(writer.write_all(&bytes)?)
             }
             Self::BlockResponse(block) => block.serialize_blocking_into(writer),
-            Self::ChallengeRequest(version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight) => {
-                Ok(bincode::serialize_into(
-                    writer,
+            Self::ChallengeRequest(
+                version,
+                fork_depth,
+                node_type,
+                status,
+                listener_port,
+                nonce,
+                cumulative_weight,
+                serving_mode,
+                timestamp,
+            ) => {
+                bincode::serialize_into(
+                    &mut *writer,
                     &(version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight),
-                )?)
+                )?;
+                bincode::serialize_into(&mut *writer, serving_mode)?;
+                match timestamp {
+                    Some(timestamp) => Ok(bincode::serialize_into(writer, timestamp)?),
+                    None => Ok(()),
+                }
             }
             Self::ChallengeResponse(block_header) => Ok(block_header.serialize_blocking_into(writer)?),
             Self::Disconnect(reason) => Ok(bincode::serialize_into(writer, reason)?),
@@ -243,10 +308,14 @@ impl<N: Network, E: Environment> Message<N, E> {
                 bincode::serialize_into(&mut *writer, share_difficulty)?;
                 block_template.serialize_blocking_into(writer)
             }
-            Self::PoolResponse(address, nonce, proof) => {
+            Self::PoolResponse(address, nonce, proof, timestamp) => {
                 bincode::serialize_into(&mut *writer, address)?;
                 bincode::serialize_into(&mut *writer, nonce)?;
-                proof.serialize_blocking_into(writer)
+                proof.serialize_blocking_into(writer)?;
+                match timestamp {
+                    Some(timestamp) => Ok(bincode::serialize_into(writer, timestamp)?),
+                    None => Ok(()),
+                }
             }
             Self::NewBlockTemplate(block_template) => block_template.serialize_blocking_into(writer),
             Self::PoolBlock(nonce, proof) => {
@@ -286,8 +355,30 @@ impl<N: Network, E: Environment> Message<N, E> {
             2 => {
                 let (version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight) =
                     bincode::deserialize_from(&mut *reader)?;
+                // Peers that predate serving modes do not advertise one, and serve full blocks.
+                let data = read_to_end(&mut *reader)?;
+                let mut data = Cursor::new(&data[..]);
+                let serving_mode = match data.has_remaining() {
+                    true => bincode::deserialize_from(&mut data)?,
+                    false => ServingMode::Full,
+                };
+                // Peers that predate timestamped handshakes do not advertise their time.
+                let timestamp = match data.has_remaining() {
+                    true => Some(bincode::deserialize_from(&mut data)?),
+                    false => None,
+                };
 
-                Self::ChallengeRequest(version, fork_depth, node_type, status, listener_port, nonce, cumulative_weight)
+                Self::ChallengeRequest(
+                    version,
+                    fork_depth,
+                    node_type,
+                    status,
+                    listener_port,
+                    nonce,
+                    cumulative_weight,
+                    serving_mode,
+                    timestamp,
+                )
             }
             3 => Self::ChallengeResponse(Data::Buffer(read_to_end(&mut *reader)?)),
             4 => {
@@ -337,10 +428,12 @@ impl<N: Network, E: Environment> Message<N, E> {
             10 => Self::UnconfirmedTransaction(Data::Buffer(read_to_end(&mut *reader)?)),
             11 => Self::PoolRegister(bincode::deserialize_from(&mut *reader)?),
             12 => Self::PoolRequest(bincode::deserialize_from(&mut *reader)?, Data::Buffer(read_to_end(&mut *reader)?)),
+            // The timestamp follows the proof, so it is left in the buffer of the proof, to be split from it upon deserialization.
             13 => Self::PoolResponse(
                 bincode::deserialize_from(&mut *reader)?,
                 bincode::deserialize_from(&mut *reader)?,
                 Data::Buffer(read_to_end(&mut *reader)?),
+                None,
             ),
             100 => Self::NewBlockTemplate(Data::Buffer(read_to_end(&mut *reader)?)),
             101 => Self::PoolBlock(bincode::deserialize_from(&mut *reader)?, Data::Buffer(read_to_end(&mut *reader)?)),
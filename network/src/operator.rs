@@ -14,24 +14,96 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Data, LedgerReader, LedgerRequest, LedgerRouter, Message, PeersRequest, PeersRouter, ProverRouter};
-use snarkos_environment::{helpers::NodeType, Environment};
-use snarkos_storage::{storage::Storage, OperatorState};
-use snarkvm::dpc::{prelude::*, PoSWProof};
+#[cfg(feature = "chaos")]
+use crate::helpers::{FaultConfig, FaultInjector};
+use crate::{
+    helpers::{
+        batch_payouts,
+        compute_payouts,
+        diff_payouts,
+        maximum_inputs_per_transaction,
+        maximum_payouts_per_transaction,
+        payout_address_message,
+        payout_schedule_message,
+        pool_job_message,
+        tag_block,
+        validate_block_tag,
+        verify_block_tag,
+        AuditEventKind,
+        AuditEvents,
+        ChainSplitDetector,
+        CircuitBreaker,
+        CircularMap,
+        Leases,
+        PayoutPolicy,
+        PayoutReplay,
+        PendingShares,
+        PoolStats,
+        RetentionPolicy,
+        ShareSampling,
+        ShareTimestamps,
+        ShareVerification,
+        SharedMemoryPool,
+        SubPool,
+        SubPools,
+        TipStatus,
+    },
+    Data,
+    DisconnectReason,
+    LedgerReader,
+    LedgerRequest,
+    LedgerRouter,
+    Message,
+    PeersRequest,
+    PeersRouter,
+    ProverRouter,
+};
+use snarkos_environment::{
+    helpers::{next_request_id, NodeType, ShutdownKind},
+    Environment,
+};
+use snarkos_storage::{
+    sample_block_heights,
+    storage::Storage,
+    ContributionEpoch,
+    ContributionPeriod,
+    FoundBlock,
+    IndexDivergence,
+    OperatorState,
+    PayoutAddressUpdate,
+    PayoutBatch,
+    PayoutSchedule,
+    PayoutScheduleUpdate,
+    ShareRecord,
+};
+use snarkvm::{
+    dpc::{prelude::*, PoSW, PoSWProof, PoSWScheme},
+    utilities::ToBits,
+};
 
-use anyhow::Result;
+#[cfg(any(feature = "test", feature = "prometheus"))]
+use snarkos_metrics as metrics;
+
+use anyhow::{anyhow, Result};
+use dashmap::{try_result::TryResult, DashMap, DashSet};
+use parking_lot::Mutex;
 use rand::thread_rng;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     net::SocketAddr,
     path::Path,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
+use time::OffsetDateTime;
 use tokio::{
-    sync::{mpsc, oneshot, RwLock},
+    sync::{mpsc, oneshot, Notify, RwLock, Semaphore},
     task,
 };
+use tracing::Instrument;
 
 /// Shorthand for the parent half of the `Operator` message channel.
 pub type OperatorRouter<N> = mpsc::Sender<OperatorRequest<N>>;
@@ -46,16 +118,40 @@ type OperatorHandler<N> = mpsc::Receiver<OperatorRequest<N>>;
 pub enum OperatorRequest<N: Network> {
     /// PoolRegister := (peer_ip, prover_address)
     PoolRegister(SocketAddr, Address<N>),
-    /// PoolResponse := (peer_ip, prover_address, nonce, proof)
-    PoolResponse(SocketAddr, Address<N>, N::PoSWNonce, PoSWProof<N>),
-    /// PoolBlock := (nonce, proof)
-    PoolBlock(N::PoSWNonce, PoSWProof<N>),
+    /// PoolResponse := (peer_ip, prover_address, nonce, timestamp, proof)
+    PoolResponse(SocketAddr, Address<N>, N::PoSWNonce, Option<i64>, PoSWProof<N>),
+    /// PoolBlock := (peer_ip, nonce, proof)
+    PoolBlock(SocketAddr, N::PoSWNonce, PoSWProof<N>),
+    /// PoolShare := (prover_address, nonce, timestamp, proof, router)
+    PoolShare(Address<N>, N::PoSWNonce, i64, PoSWProof<N>, oneshot::Sender<Result<()>>),
 }
 
 /// The predefined base share difficulty.
 const BASE_SHARE_DIFFICULTY: u64 = u64::MAX / 5;
 /// The operator heartbeat in seconds.
 const HEARTBEAT_IN_SECONDS: Duration = Duration::from_millis(100);
+/// The interval at which the data expired under the retention policy is pruned.
+const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The time between rollups of the share history and found blocks into the contributions of the provers.
+const ROLLUP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// The age of the shares and found blocks before they are rolled up, so those still being persisted are not skipped.
+const ROLLUP_DELAY: Duration = Duration::from_secs(60);
+/// The time between the provers being notified of the maintenance and the shutdown, for their in-flight shares to arrive.
+const MAINTENANCE_GRACE_PERIOD: Duration = Duration::from_secs(10);
+/// The maximum number of TLS connections, and of provers registered over them, whose sub-pool route is retained.
+const MAXIMUM_CONNECTION_ROUTES: u32 = 1 << 14;
+/// The duration after which a submitted block that the ledger has not accepted is considered rejected.
+const BLOCK_ACCEPTANCE_TIMEOUT: Duration = Duration::from_secs(30);
+/// The duration for which the suspect transactions of a rejected block are left out of the block templates.
+const QUARANTINE_DURATION: Duration = Duration::from_secs(60 * 60);
+/// The maximum duration for which the operator waits on the ledger to receive a block.
+const LEDGER_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// The maximum number of provers whose job and share difficulty are retained.
+const MAXIMUM_NUMBER_OF_PROVERS: usize = 1 << 16;
+/// The duration after which a prover that neither requested a job nor submitted a share is forgotten.
+const PROVER_IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// The maximum number of provers holding a long-poll for a new job at once.
+const MAXIMUM_JOB_POLLERS: usize = 1 << 12;
 
 ///
 /// An operator for a program on a specific network in the node server.
@@ -64,26 +160,83 @@ const HEARTBEAT_IN_SECONDS: Duration = Duration::from_millis(100);
 pub struct Operator<N: Network, E: Environment> {
     /// The address of the operator.
     address: Option<Address<N>>,
+    /// The sub-pools served by the operator.
+    sub_pools: SubPools<N>,
+    /// The tag embedded in the coinbase of the blocks found by the operator, and the private key signing it, if any.
+    block_tag: Option<(String, PrivateKey<N>)>,
+    /// The retention of the shares and rounds persisted by the operator.
+    retention_policy: RetentionPolicy,
+    /// The sampling policy of share verification under extreme share load.
+    share_sampling: ShareSampling,
+    /// The replay window of the share timestamps, and the clock of each prover.
+    share_timestamps: ShareTimestamps<N>,
+    /// The payout schedules that provers may choose, and the default payout schedule.
+    payout_policy: PayoutPolicy,
+    /// The sub-pools that TLS connections are routed to by their server name := (peer_ip, sub_pool_name)
+    connection_routes: RwLock<CircularMap<SocketAddr, String, MAXIMUM_CONNECTION_ROUTES>>,
+    /// The sub-pools that provers registered to over a routed TLS connection := (prover, (peer_ip, sub_pool_name))
+    prover_routes: Mutex<CircularMap<Address<N>, (SocketAddr, String), MAXIMUM_CONNECTION_ROUTES>>,
     /// The local address of this node.
     local_ip: SocketAddr,
+    /// The address of the TLS listener for the provers, if it is enabled.
+    tls_ip: Option<SocketAddr>,
     /// The state storage of the operator.
     state: Arc<OperatorState<N>>,
     /// The current block template that is being mined on by the operator.
     block_template: RwLock<Option<BlockTemplate<N>>>,
-    /// A list of provers and their associated state := (last_submitted, share_difficulty)
-    provers: RwLock<HashMap<Address<N>, (Instant, u64)>>,
+    /// The notification of a new block template, for provers waiting on a new job.
+    new_block_template: Notify,
+    /// The hashrate leases of the operator.
+    leases: Leases<N>,
+    /// The current block templates built on behalf of the renters := (lease_id, block_template)
+    lease_templates: RwLock<HashMap<u64, BlockTemplate<N>>>,
+    /// `true` if the block templates of the renters are being updated.
+    is_updating_lease_templates: AtomicBool,
+    /// The blocks submitted to the ledger that are not yet accepted := (block_hash, (block, submitted_at))
+    submitted_blocks: DashMap<N::BlockHash, (Block<N>, Instant)>,
+    /// The transactions left out of the block templates, as suspects of a rejected block := (transaction_id, quarantined_at)
+    quarantined_transactions: DashMap<N::TransactionID, Instant>,
+    /// The incidents in the operation of the pool, and the recovery of the operator from them.
+    audit_events: AuditEvents<N>,
+    /// If `true`, the payouts are halted, as the chain of the node is split from the external endpoints.
+    payouts_halted: AtomicBool,
+    /// If `true`, the operator is in maintenance, and refuses new provers until it shuts down.
+    is_in_maintenance: AtomicBool,
+    /// The backup pool that the provers are told to reconnect to, once the operator is in maintenance.
+    backup_pool: Mutex<Option<SocketAddr>>,
+    /// The lease each prover was last given a job for := (prover, lease_id); `None` for the jobs of the operator.
+    assignments: DashMap<Address<N>, Option<u64>>,
+    /// A list of provers and their associated state := (last_seen, share_difficulty)
+    provers: DashMap<Address<N>, (Instant, u64)>,
+    /// The permits of the provers holding a long-poll for a new job.
+    job_pollers: Semaphore,
     /// A list of the known nonces for the current round.
-    known_nonces: RwLock<HashSet<N::PoSWNonce>>,
+    known_nonces: DashSet<N::PoSWNonce>,
+    /// The shares accepted since the last flush to storage := ((block_height, prover), (coinbase_record, shares))
+    pending_shares: PendingShares<N>,
+    /// The round statistics accumulated since the last flush to storage := (block_height, (shares, expected_blocks))
+    pending_rounds: DashMap<u32, (u64, f64)>,
+    /// The share history not yet persisted to storage := \[(prover, share_record)\]
+    pending_share_history: Mutex<Vec<(Address<N>, ShareRecord)>>,
+    /// The lock held while rolling up the contributions of the provers, so no share is rolled up twice.
+    rollup_lock: Mutex<()>,
+    /// The injector of faults and checker of the share accounting, for chaos testing.
+    #[cfg(feature = "chaos")]
+    fault_injector: FaultInjector,
     /// The operator router of the node.
     operator_router: OperatorRouter<N>,
     /// The pool of unconfirmed transactions.
-    memory_pool: Arc<RwLock<MemoryPool<N>>>,
+    memory_pool: Arc<SharedMemoryPool<N>>,
+    /// The PoSW of the operator, with only the verifying key loaded, to verify the shares of provers.
+    posw: Arc<PoSW<N>>,
     /// The peers router of the node.
     peers_router: PeersRouter<N, E>,
     /// The ledger state of the node.
     ledger_reader: LedgerReader<N>,
     /// The ledger router of the node.
     ledger_router: LedgerRouter<N>,
+    /// The circuit breaker for the requests to the ledger, failing submissions while the ledger is unresponsive.
+    ledger_circuit_breaker: CircuitBreaker,
     /// The prover router of the node.
     prover_router: ProverRouter<N>,
 }
@@ -94,28 +247,91 @@ impl<N: Network, E: Environment> Operator<N, E> {
     pub async fn open<S: Storage, P: AsRef<Path> + Copy>(
         path: P,
         address: Option<Address<N>>,
+        sub_pools: SubPools<N>,
+        block_tag: Option<(String, PrivateKey<N>)>,
+        retention_policy: RetentionPolicy,
+        share_sampling: ShareSampling,
+        share_timestamps: ShareTimestamps<N>,
+        payout_policy: PayoutPolicy,
         local_ip: SocketAddr,
-        memory_pool: Arc<RwLock<MemoryPool<N>>>,
+        tls_ip: Option<SocketAddr>,
+        memory_pool: Arc<SharedMemoryPool<N>>,
         peers_router: PeersRouter<N, E>,
         ledger_reader: LedgerReader<N>,
         ledger_router: LedgerRouter<N>,
         prover_router: ProverRouter<N>,
     ) -> Result<Arc<Self>> {
+        // Ensure the block tag is well-formed, and signed by the operator, so it is attributed to the coinbase recipient.
+        if let Some((block_tag, private_key)) = &block_tag {
+            validate_block_tag(block_tag)?;
+            if address != Some(Address::from_private_key(private_key)) {
+                return Err(anyhow!("The block tag must be signed with the private key of the operator address"));
+            }
+        }
+
+        // Load the PoSW without its proving key, as the operator verifies shares, but never produces PoSW proofs.
+        let posw = task::spawn_blocking(|| PoSW::<N>::load(false))
+            .await?
+            .map_err(|error| anyhow!("Failed to load the PoSW verifying key: {}", error))?;
+
+        // Load the faults to inject for chaos testing, if any.
+        #[cfg(feature = "chaos")]
+        let fault_injector = {
+            let config = FaultConfig::from_env()?;
+            if config.is_enabled() {
+                warn!("Operator is injecting faults for chaos testing: {:?}", config);
+            }
+            FaultInjector::new(config)
+        };
+
+        // Open the operator state, restoring the hashrate leases.
+        let state = Arc::new(OperatorState::open_writer::<S, P>(path)?);
+        let leases = Leases::new(state.to_leases());
+
         // Initialize an mpsc channel for sending requests to the `Operator` struct.
         let (operator_router, mut operator_handler) = mpsc::channel(1024);
         // Initialize the operator.
         let operator = Arc::new(Self {
             address,
+            sub_pools,
+            block_tag,
+            retention_policy,
+            share_sampling,
+            share_timestamps,
+            payout_policy,
+            connection_routes: RwLock::new(CircularMap::new()),
+            prover_routes: Mutex::new(CircularMap::new()),
             local_ip,
-            state: Arc::new(OperatorState::open_writer::<S, P>(path)?),
+            tls_ip,
+            state,
             block_template: RwLock::new(None),
+            new_block_template: Notify::new(),
+            leases,
+            lease_templates: Default::default(),
+            is_updating_lease_templates: Default::default(),
+            submitted_blocks: Default::default(),
+            quarantined_transactions: Default::default(),
+            audit_events: Default::default(),
+            payouts_halted: Default::default(),
+            is_in_maintenance: Default::default(),
+            backup_pool: Default::default(),
+            assignments: Default::default(),
             provers: Default::default(),
+            job_pollers: Semaphore::new(MAXIMUM_JOB_POLLERS),
             known_nonces: Default::default(),
+            pending_shares: Default::default(),
+            pending_rounds: Default::default(),
+            pending_share_history: Default::default(),
+            rollup_lock: Default::default(),
+            #[cfg(feature = "chaos")]
+            fault_injector,
             operator_router,
             memory_pool,
+            posw: Arc::new(posw),
             peers_router: peers_router.clone(),
             ledger_reader,
             ledger_router,
+            ledger_circuit_breaker: CircuitBreaker::new("ledger", LEDGER_REQUEST_TIMEOUT),
             prover_router,
         });
 
@@ -130,7 +346,10 @@ impl<N: Network, E: Environment> Operator<N, E> {
                     let _ = router.send(());
                     // Asynchronously wait for a operator request.
                     while let Some(request) = operator_handler.recv().await {
-                        operator_clone.update(request).await;
+                        // Hand off the request to the operator.
+                        // The requests are processed in order, so that the operator maintains a unified view of its state,
+                        // while the proofs of the shares are verified on the blocking thread pool.
+                        operator_clone.update(request).instrument(debug_span!("operator", request_id = next_request_id())).await;
                     }
                 }),
             );
@@ -152,6 +371,13 @@ impl<N: Network, E: Environment> Operator<N, E> {
                         let _ = router.send(());
                         // TODO (julesdesmit): Add logic to the loop to retarget share difficulty.
                         loop {
+                            // Persist the shares accepted, and the leases updated, since the last heartbeat.
+                            operator.flush_pending_shares();
+                            operator.flush_leases();
+
+                            // Recover from the submitted blocks that the ledger did not accept, if any.
+                            operator.check_submitted_blocks().await;
+
                             // Determine if the current block template is stale.
                             let is_block_template_stale = match &*operator.block_template.read().await {
                                 Some(template) => operator.ledger_reader.latest_block_height().saturating_add(1) != template.block_height(),
@@ -161,22 +387,7 @@ impl<N: Network, E: Environment> Operator<N, E> {
                             // Update the block template if it is stale.
                             if is_block_template_stale {
                                 // Construct a new block template.
-                                let transactions = operator.memory_pool.read().await.transactions();
-                                let ledger_reader = operator.ledger_reader.clone();
-                                let result = task::spawn_blocking(move || {
-                                    E::thread_pool().install(move || {
-                                        match ledger_reader.get_block_template(
-                                            recipient,
-                                            E::COINBASE_IS_PUBLIC,
-                                            &transactions,
-                                            &mut thread_rng(),
-                                        ) {
-                                            Ok(block_template) => Ok(block_template),
-                                            Err(error) => Err(format!("Failed to produce a new block template: {}", error)),
-                                        }
-                                    })
-                                })
-                                .await;
+                                let result = operator.construct_block_template(recipient).await;
 
                                 // Update the block template.
                                 match result {
@@ -184,11 +395,33 @@ impl<N: Network, E: Environment> Operator<N, E> {
                                         // Acquire the write lock to update the block template.
                                         *operator.block_template.write().await = Some(block_template.clone());
                                         // Clear the set of known nonces.
-                                        operator.known_nonces.write().await.clear();
+                                        operator.known_nonces.clear();
+                                        // Notify the provers waiting on a new job.
+                                        operator.new_block_template.notify_waiters();
+                                        // Duplicate the rollover to the new round, as if the ledger reported the new block twice.
+                                        #[cfg(feature = "chaos")]
+                                        if operator.fault_injector.duplicate_rollover() {
+                                            warn!("[Chaos] Duplicating the rollover to block {}", block_template.block_height());
+                                            operator.flush_pending_shares();
+                                            operator.known_nonces.clear();
+                                            operator.new_block_template.notify_waiters();
+                                        }
                                         todo!("(猜测)这里可能有一些问题：当同步的模版不是最新的区块时，如果传播出去，会导致矿机浪费算力");
-                                        let pool_message = Message::NewBlockTemplate(Data::Object(block_template));
-                                        if let Err(error) = peers_router.send(PeersRequest::MessagePropagatePoolServer(pool_message)).await {
-                                            warn!("Failed to propagate PoolRequest: {}", error);
+                                        // Drop the push of the new block template, so the provers are left on a stale job.
+                                        #[cfg(feature = "chaos")]
+                                        let is_push_dropped = operator.fault_injector.drop_template_push();
+                                        #[cfg(not(feature = "chaos"))]
+                                        let is_push_dropped = false;
+                                        if is_push_dropped {
+                                            warn!("[Chaos] Dropping the push of block template {}", block_template.block_height());
+                                        } else {
+                                            let pool_message = Message::NewBlockTemplate(Data::Object(block_template));
+                                            if let Err(error) = peers_router
+                                                .send(PeersRequest::MessagePropagatePoolServer(pool_message))
+                                                .await
+                                            {
+                                                warn!("Failed to propagate PoolRequest: {}", error);
+                                            }
                                         }
                                     }
                                     Ok(Err(error_message)) => error!("{}", error_message),
@@ -196,6 +429,15 @@ impl<N: Network, E: Environment> Operator<N, E> {
                                 };
                             }
 
+                            // Update the block templates of the renters, if any are stale or missing, off the heartbeat.
+                            if !operator.is_updating_lease_templates.swap(true, Ordering::SeqCst) {
+                                let operator = operator.clone();
+                                task::spawn(async move {
+                                    operator.update_lease_templates().await;
+                                    operator.is_updating_lease_templates.store(false, Ordering::SeqCst);
+                                });
+                            }
+
                             // Proceed to sleep for a preset amount of time.
                             tokio::time::sleep(HEARTBEAT_IN_SECONDS).await;
                         }
@@ -209,6 +451,53 @@ impl<N: Network, E: Environment> Operator<N, E> {
             }
         }
 
+        if E::NODE_TYPE == NodeType::Operator {
+            // Initialize a rollup loop for the contributions of the provers, which also forgets the idle provers.
+            let operator = operator.clone();
+            let (router, handler) = oneshot::channel();
+            E::resources().register_task(
+                None, // No need to provide an id, as the task will run indefinitely.
+                task::spawn(async move {
+                    // Notify the outer function that the task is ready.
+                    let _ = router.send(());
+                    loop {
+                        let operator = operator.clone();
+                        let _ = task::spawn_blocking(move || {
+                            operator.roll_up_contributions();
+                            operator.evict_idle_provers();
+                            operator.share_sampling.evict_idle_peers();
+                        })
+                        .await;
+                        tokio::time::sleep(ROLLUP_INTERVAL).await;
+                    }
+                }),
+            );
+
+            // Wait until the rollup loop is ready.
+            let _ = handler.await;
+        }
+
+        if E::NODE_TYPE == NodeType::Operator && operator.retention_policy.is_pruning() {
+            // Initialize a pruning loop for the data expired under the retention policy.
+            let operator = operator.clone();
+            let (router, handler) = oneshot::channel();
+            E::resources().register_task(
+                None, // No need to provide an id, as the task will run indefinitely.
+                task::spawn(async move {
+                    // Notify the outer function that the task is ready.
+                    let _ = router.send(());
+                    loop {
+                        let operator = operator.clone();
+                        let _ = task::spawn_blocking(move || operator.prune_expired_data()).await;
+                        tokio::time::sleep(PRUNING_INTERVAL).await;
+                    }
+                }),
+            );
+
+            // Wait until the pruning loop is ready.
+            let _ = handler.await;
+        }
+
         Ok(operator)
     }
 
@@ -229,7 +518,8 @@ impl<N: Network, E: Environment> Operator<N, E> {
 
     /// Returns the shares for a specific prover, given the prover address.
     pub fn get_shares_for_prover(&self, prover: &Address<N>) -> u64 {
-        self.state.get_shares_for_prover(prover)
+        let pending_shares = self.pending_shares.get_shares_for_prover(prover);
+        self.state.get_shares_for_prover(prover).saturating_add(pending_shares)
     }
 
     ///
@@ -240,109 +530,1481 @@ impl<N: Network, E: Environment> Operator<N, E> {
     }
 
     ///
-    /// Performs the given `request` to the operator.
-    /// All requests must go through this `update`, so that a unified view is preserved.
+    /// Returns each sub-pool, with the number of provers and the shares attributed to it.
     ///
-    pub(super) async fn update(&self, request: OperatorRequest<N>) {
-        match request {
-            OperatorRequest::PoolRegister(peer_ip, address) => {
-                if let Some(block_template) = self.block_template.read().await.clone() {
-                    // Ensure this prover exists in the list first, and retrieve their share difficulty.
-                    let share_difficulty = self
-                        .provers
-                        .write()
-                        .await
-                        .entry(address)
-                        .or_insert((Instant::now(), BASE_SHARE_DIFFICULTY))
-                        .1;
-
-                    // Route a `PoolRequest` to the peer.
-                    let message = Message::PoolRequest(share_difficulty, Data::Object(block_template));
-                    if let Err(error) = self.peers_router.send(PeersRequest::MessageSend(peer_ip, message)).await {
-                        warn!("[PoolRequest] {}", error);
-                    }
-                } else {
-                    warn!("[PoolRegister] No current block template exists");
+    pub fn get_sub_pools(&self) -> Vec<(SubPool<N>, usize, u64)> {
+        // Sum the stored and pending shares of each prover.
+        let mut shares_by_prover: HashMap<Address<N>, u64> = HashMap::new();
+        for (_, shares) in self.state.to_shares() {
+            for (prover, shares) in shares {
+                let entry = shares_by_prover.entry(prover).or_insert(0);
+                *entry = entry.saturating_add(shares);
+            }
+        }
+        for (prover, pending_shares) in self.pending_shares.to_shares_by_prover() {
+            let shares = shares_by_prover.entry(prover).or_insert(0);
+            *shares = shares.saturating_add(pending_shares);
+        }
+
+        // Attribute the shares of each prover to its sub-pool.
+        let mut sub_pools: Vec<_> = self.sub_pools.iter().map(|sub_pool| (sub_pool.clone(), 0usize, 0u64)).collect();
+        for (prover, shares) in shares_by_prover {
+            if let Some(sub_pool) = self.get_sub_pool(&prover) {
+                if let Some((_, number_of_provers, total_shares)) = sub_pools.iter_mut().find(|(s, _, _)| s.name == sub_pool.name) {
+                    *number_of_provers += 1;
+                    *total_shares = total_shares.saturating_add(shares);
                 }
             }
-            OperatorRequest::PoolResponse(peer_ip, prover, nonce, proof) => {
-                if let Some(block_template) = self.block_template.read().await.clone() {
-                    // Ensure the given nonce from the prover is new.
-                    if self.known_nonces.read().await.contains(&nonce) {
-                        warn!("[PoolResponse] Peer {} sent a duplicate share", peer_ip);
-                        // TODO (julesdesmit): punish?
-                        return;
-                    }
+        }
+        sub_pools
+    }
 
-                    // Update known nonces.
-                    self.known_nonces.write().await.insert(nonce);
-
-                    // Retrieve the share difficulty for the given prover.
-                    let share_difficulty = {
-                        let provers = self.provers.read().await.clone();
-                        match provers.get(&prover) {
-                            Some((_, share_difficulty)) => *share_difficulty,
-                            None => {
-                                self.provers.write().await.insert(prover, (Instant::now(), BASE_SHARE_DIFFICULTY));
-                                BASE_SHARE_DIFFICULTY
-                            }
-                        }
-                    };
+    ///
+    /// Returns the sub-pools served by the operator.
+    ///
+    pub fn sub_pools(&self) -> &SubPools<N> {
+        &self.sub_pools
+    }
 
-                    // Ensure the share difficulty target is met, and the PoSW proof is valid.
-                    let block_height = block_template.block_height();
-                    if !N::posw().verify(
-                        block_height,
-                        share_difficulty,
-                        &[*block_template.to_header_root().unwrap(), *nonce],
-                        &proof,
-                    ) {
-                        warn!("[PoolResponse] PoSW proof verification failed");
-                        return;
-                    }
+    ///
+    /// Returns the sub-pool the given prover is assigned to, following its TLS route if any, if the prover is admitted to any.
+    ///
+    pub fn get_sub_pool(&self, prover: &Address<N>) -> Option<&SubPool<N>> {
+        let route = self.prover_routes.lock().get(prover).map(|(_, sub_pool_name)| sub_pool_name.clone());
+        match route {
+            Some(sub_pool_name) => self.sub_pools.get_by_name(&sub_pool_name),
+            None => self.sub_pools.public(),
+        }
+    }
 
-                    // Update the internal state for this prover.
-                    if let Some(ref mut prover) = self.provers.write().await.get_mut(&prover) {
-                        prover.0 = Instant::now();
-                    } else {
-                        error!("Prover should have existing info");
-                        return;
-                    }
+    ///
+    /// Routes the TLS connection from the given peer IP to the sub-pool serving the given server name, if any.
+    /// Returns an error if the sub-pool requires a client certificate, and the connection is not authenticated.
+    ///
+    pub async fn route_connection(&self, peer_ip: SocketAddr, server_name: Option<&str>, is_authenticated: bool) -> Result<()> {
+        let mut connection_routes = self.connection_routes.write().await;
+        connection_routes.remove(&peer_ip);
+
+        if let Some(sub_pool) = server_name.and_then(|server_name| self.sub_pools.get_by_server_name(server_name)) {
+            if sub_pool.require_client_cert && !is_authenticated {
+                return Err(anyhow!("Sub-pool '{}' requires a client certificate", sub_pool.name));
+            }
+            connection_routes.insert(peer_ip, sub_pool.name.clone());
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns `true` if the given sub-pool is only routed to over connections authenticated by a client certificate.
+    ///
+    fn is_authenticated_route(&self, sub_pool_name: &str) -> bool {
+        self.sub_pools.get_by_name(sub_pool_name).map_or(false, |sub_pool| sub_pool.require_client_cert)
+    }
+
+    ///
+    /// Assigns the given prover to the sub-pool that its connection is routed to, if the connection was routed,
+    /// binding the prover to the connection. A prover bound to an authenticated connection may only be rerouted
+    /// by another authenticated connection.
+    ///
+    async fn route_prover(&self, peer_ip: SocketAddr, prover: Address<N>) -> Result<()> {
+        let route = self.connection_routes.read().await.get(&peer_ip).cloned();
+        let is_authenticated = route.as_deref().map_or(false, |sub_pool_name| self.is_authenticated_route(sub_pool_name));
+
+        let mut prover_routes = self.prover_routes.lock();
+        if let Some((route_ip, sub_pool_name)) = prover_routes.get(&prover) {
+            if *route_ip != peer_ip && !is_authenticated && self.is_authenticated_route(sub_pool_name) {
+                return Err(anyhow!(
+                    "Prover {} is registered to sub-pool '{}' over an authenticated connection",
+                    prover,
+                    sub_pool_name
+                ));
+            }
+        }
+
+        match route {
+            Some(sub_pool_name) => {
+                let sub_pool = match self.sub_pools.get_by_name(&sub_pool_name) {
+                    Some(sub_pool) => sub_pool,
+                    None => return Err(anyhow!("Sub-pool '{}' does not exist", sub_pool_name)),
+                };
+                // The client certificate of the connection was verified when it was routed.
+                if !sub_pool.admits(&prover, true) {
+                    return Err(anyhow!("Prover {} is not admitted to sub-pool '{}'", prover, sub_pool_name));
+                }
+                prover_routes.remove(&prover);
+                prover_routes.insert(prover, (peer_ip, sub_pool_name));
+            }
+            None => prover_routes.remove(&prover),
+        }
+        Ok(())
+    }
+
+    ///
+    /// Ensures a share of the given prover arrives over the connection it is bound to, if it is bound to an authenticated
+    /// connection, where `None` stands for the RPC server of the operator.
+    ///
+    fn check_prover_route(&self, prover: &Address<N>, peer_ip: Option<SocketAddr>) -> Result<()> {
+        match self.prover_routes.lock().get(prover) {
+            Some((route_ip, sub_pool_name)) if Some(*route_ip) != peer_ip && self.is_authenticated_route(sub_pool_name) => Err(anyhow!(
+                "Prover {} submits its shares to sub-pool '{}' over its authenticated connection",
+                prover,
+                sub_pool_name
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    ///
+    /// Returns the address receiving the fees of the given sub-pool.
+    ///
+    pub fn get_payout_address(&self, sub_pool: &SubPool<N>) -> Option<Address<N>> {
+        sub_pool.payout_address.or(self.address)
+    }
+
+    ///
+    /// Returns the luck and variance statistics of the pool.
+    ///
+    pub fn get_pool_stats(&self) -> PoolStats<N> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        // A found block is orphaned once the canonical chain holds a different block at its height.
+        PoolStats::new(&self.state.to_rounds(), now, |block_height, block_hash| {
+            matches!(self.ledger_reader.get_block_hash(block_height), Ok(canonical_hash) if canonical_hash != block_hash)
+        })
+    }
+
+    ///
+    /// Returns the addresses at which provers connect to the operator: the listener of the node,
+    /// and the TLS listener for the provers, if it is enabled.
+    ///
+    pub fn listeners(&self) -> (SocketAddr, Option<SocketAddr>) {
+        (self.local_ip, self.tls_ip)
+    }
+
+    ///
+    /// Returns the tag embedded in the blocks found by the operator, if any.
+    ///
+    pub fn block_tag(&self) -> Option<&str> {
+        self.block_tag.as_ref().map(|(block_tag, _)| block_tag.as_str())
+    }
+
+    ///
+    /// Returns the given block found by the operator, with the tag of the operator signed over its hash, if any.
+    /// The untagged block is returned if tagging fails, as the block is valid either way.
+    ///
+    fn tag_found_block(&self, block: Block<N>) -> Block<N> {
+        match &self.block_tag {
+            Some((block_tag, private_key)) => match tag_block(&block, block_tag, private_key, &mut thread_rng()) {
+                Ok(tagged_block) => tagged_block,
+                Err(error) => {
+                    warn!("Failed to tag block {}: {}", block.height(), error);
+                    block
+                }
+            },
+            None => block,
+        }
+    }
+
+    ///
+    /// Returns the blocks found by the operator with the given tag := \[(block_height, block_hash)\], in ascending order.
+    ///
+    pub fn get_tagged_blocks(&self, block_tag: &str) -> Vec<(u32, N::BlockHash)> {
+        self.state.get_tagged_blocks(block_tag)
+    }
 
-                    // Increment the share count for the prover.
-                    let coinbase_record = block_template.coinbase_record().clone();
-                    match self.state.increment_share(block_height, coinbase_record.clone(), &prover) {
-                        Ok(..) => info!(
-                            "Operator has received a valid share from {} ({}) for block {}",
-                            prover, peer_ip, block_height,
-                        ),
-                        Err(error) => error!("{}", error),
+    ///
+    /// Returns the provenance of the blocks found by the operator at the given block height.
+    ///
+    pub fn get_found_blocks(&self, block_height: u32) -> Result<Vec<FoundBlock<N>>> {
+        self.state.get_found_blocks(block_height)
+    }
+
+    ///
+    /// Returns the shares submitted by the given prover between the given UNIX timestamps, inclusive,
+    /// as retained under the retention policy of the operator.
+    ///
+    pub fn get_share_history(&self, prover: &Address<N>, from: i64, to: i64) -> Vec<ShareRecord> {
+        self.state.get_share_history(prover, from, to)
+    }
+
+    ///
+    /// Returns the contributions of the provers over the epoch of the given period containing the given UNIX timestamp,
+    /// as of the last rollup, or `None` if the epoch was not rolled up yet.
+    ///
+    pub fn get_leaderboard(&self, period: ContributionPeriod, timestamp: i64) -> Result<Option<ContributionEpoch<N>>> {
+        self.state.get_contribution_epoch(period, period.epoch_start(timestamp))
+    }
+
+    ///
+    /// Returns the updates of the payout address of the given prover, in ascending order of the rounds they are effective from.
+    ///
+    pub fn get_payout_address_updates(&self, prover: &Address<N>) -> Result<Vec<PayoutAddressUpdate<N>>> {
+        self.state.get_payout_address_updates(prover)
+    }
+
+    ///
+    /// Redirects the payouts of the given prover to the given payout address, from the round with the given block height.
+    /// The update must be signed by the prover, and may not be effective from a round which was already paid.
+    ///
+    pub fn update_payout_address(
+        &self,
+        prover: Address<N>,
+        payout_address: Address<N>,
+        effective_from: u32,
+        signature: N::AccountSignature,
+    ) -> Result<PayoutAddressUpdate<N>> {
+        // Ensure the update is not effective from a past round.
+        let current_block_height = self.ledger_reader.latest_block_height().saturating_add(1);
+        if effective_from < current_block_height {
+            return Err(anyhow!(
+                "The payout address update must be effective from block {} or later",
+                current_block_height
+            ));
+        }
+
+        // Ensure the update is signed by the prover.
+        let message = payout_address_message(&prover, &payout_address, effective_from);
+        if !prover.verify_signature(&message.as_bytes().to_bits_le(), &signature)? {
+            return Err(anyhow!("Invalid signature for the payout address update of {}", prover));
+        }
+
+        let update = PayoutAddressUpdate {
+            payout_address,
+            effective_from,
+            signature,
+            updated_at: OffsetDateTime::now_utc().unix_timestamp(),
+        };
+        self.state.add_payout_address_update(&prover, update.clone())?;
+        info!(
+            "Redirected the payouts of {} to {} from block {}",
+            prover, payout_address, effective_from
+        );
+        Ok(update)
+    }
+
+    ///
+    /// Returns the payout schedules that provers may choose, and the default payout schedule.
+    ///
+    pub fn payout_policy(&self) -> &PayoutPolicy {
+        &self.payout_policy
+    }
+
+    ///
+    /// Returns the payout schedule of the given prover at the next block, within the bounds of the payout policy,
+    /// and `true` if the prover chose it, or `false` if it is the default payout schedule.
+    ///
+    pub fn get_payout_schedule(&self, prover: &Address<N>) -> Result<(PayoutSchedule, bool)> {
+        let block_height = self.ledger_reader.latest_block_height().saturating_add(1);
+        let schedule = self.state.get_payout_schedule(prover, block_height)?;
+        Ok((self.payout_policy.apply(schedule), schedule.is_some()))
+    }
+
+    ///
+    /// Returns the updates of the payout schedule of the given prover, in ascending order of the block heights they are effective from.
+    ///
+    pub fn get_payout_schedule_updates(&self, prover: &Address<N>) -> Result<Vec<PayoutScheduleUpdate<N>>> {
+        self.state.get_payout_schedule_updates(prover)
+    }
+
+    ///
+    /// Sets the payout schedule of the given prover, from the given block height.
+    /// The update must be signed by the prover, be within the bounds of the payout policy, and may not be effective from a past block.
+    ///
+    pub fn update_payout_schedule(
+        &self,
+        prover: Address<N>,
+        schedule: PayoutSchedule,
+        effective_from: u32,
+        signature: N::AccountSignature,
+    ) -> Result<PayoutScheduleUpdate<N>> {
+        // Ensure the payout schedule is within the bounds set by the operator.
+        self.payout_policy.check(&schedule)?;
+
+        // Ensure the update is not effective from a past block.
+        let current_block_height = self.ledger_reader.latest_block_height().saturating_add(1);
+        if effective_from < current_block_height {
+            return Err(anyhow!(
+                "The payout schedule update must be effective from block {} or later",
+                current_block_height
+            ));
+        }
+
+        // Ensure the update is signed by the prover.
+        let message = payout_schedule_message(&prover, &schedule, effective_from);
+        if !prover.verify_signature(&message.as_bytes().to_bits_le(), &signature)? {
+            return Err(anyhow!("Invalid signature for the payout schedule update of {}", prover));
+        }
+
+        let update = PayoutScheduleUpdate {
+            schedule,
+            effective_from,
+            signature,
+            updated_at: OffsetDateTime::now_utc().unix_timestamp(),
+        };
+        self.state.add_payout_schedule_update(&prover, update.clone())?;
+        info!(
+            "Set the payout schedule of {} to {} gates every {} blocks from block {}",
+            prover, schedule.minimum_payout, schedule.payout_interval, effective_from
+        );
+        Ok(update)
+    }
+
+    ///
+    /// Selects the records of the pool wallet that fund a payout transaction of the given amount, with at most the inputs
+    /// of a transaction, under the record selection strategy of the payout policy. Each record is given with the block height
+    /// it was created at.
+    ///
+    pub fn select_payout_records(&self, records: &[(u32, Record<N>)], amount: u64) -> Result<Vec<Record<N>>> {
+        let maximum_inputs = maximum_inputs_per_transaction::<N>();
+        let candidates: Vec<(u32, u64)> = records
+            .iter()
+            .map(|(block_height, record)| (*block_height, record.value().0.max(0) as u64))
+            .collect();
+        match self.payout_policy.record_selection.select(&candidates, amount, maximum_inputs) {
+            Some(selection) => Ok(selection.into_iter().map(|index| records[index].1.clone()).collect()),
+            None => Err(anyhow!(
+                "Insufficient records to fund a payout of {} gates with at most {} inputs",
+                amount,
+                maximum_inputs
+            )),
+        }
+    }
+
+    ///
+    /// Returns the payout batches for the block found by the operator with the given block height and block hash,
+    /// each of which is paid in a single transaction.
+    ///
+    pub fn get_payout_batches(&self, block_height: u32, block_hash: N::BlockHash) -> Result<Vec<PayoutBatch<N>>> {
+        self.state.get_payout_batches(block_height, block_hash)
+    }
+
+    ///
+    /// Returns the payout batches which are yet to be paid, with the block height, block hash, and index of each batch,
+    /// so a payout run which was interrupted resumes from the first batch it did not pay.
+    ///
+    pub fn get_pending_payout_batches(&self) -> Vec<(u32, N::BlockHash, usize, PayoutBatch<N>)> {
+        // No batch is paid while the payouts are halted, as the blocks they pay for may be on a minority fork.
+        match self.are_payouts_halted() {
+            true => vec![],
+            false => self.state.get_pending_payout_batches(),
+        }
+    }
+
+    ///
+    /// Records the given payout batch as paid by the transaction with the given transaction ID,
+    /// and flags the transaction as local priority in the memory pool.
+    /// A batch is recorded even while the payouts are halted, as its transaction may already have been sent.
+    ///
+    pub fn complete_payout_batch(
+        &self,
+        block_height: u32,
+        block_hash: N::BlockHash,
+        index: usize,
+        transaction_id: N::TransactionID,
+    ) -> Result<()> {
+        self.state.complete_payout_batch(block_height, block_hash, index, transaction_id)?;
+        // Place the payout transaction first in the block templates of the operator, so the payout confirms promptly.
+        self.memory_pool.set_local_priority(transaction_id);
+        info!("Paid payout batch {} for block {} in transaction {}", index, block_height, transaction_id);
+        Ok(())
+    }
+
+    ///
+    /// Recomputes the payouts of the blocks found at the given block height from the persisted shares of the round,
+    /// under the given sub-pools, and returns the differences from what was paid for each block.
+    ///
+    pub fn replay_payouts(&self, block_height: u32, sub_pools: &SubPools<N>) -> Result<Vec<PayoutReplay<N>>> {
+        let mut replays = Vec::new();
+        for found_block in self.state.get_found_blocks(block_height)? {
+            // The coinbase of a leased block is paid to the renter, and not to the provers.
+            if found_block.lease_id.is_some() {
+                continue;
+            }
+            let coinbase_record = found_block.block_template.coinbase_record();
+            // Only the batches confirmed as paid count towards what was paid.
+            let mut paid: HashMap<Address<N>, u64> = HashMap::new();
+            for batch in self.state.get_payout_batches(block_height, found_block.block_hash)? {
+                if batch.transaction_id.is_some() {
+                    for (address, amount) in batch.payouts {
+                        let entry = paid.entry(address).or_insert(0);
+                        *entry = entry.saturating_add(amount);
                     }
+                }
+            }
+            let recomputed = self.compute_round_payouts(block_height, coinbase_record, sub_pools);
+            replays.push(PayoutReplay {
+                block_hash: found_block.block_hash,
+                reward: coinbase_record.value().0 as u64,
+                paid: paid.values().sum(),
+                recomputed: recomputed.values().sum(),
+                diffs: diff_payouts(&paid, &recomputed),
+            });
+        }
+        Ok(replays)
+    }
 
-                    // If the block has satisfactory difficulty and is valid, proceed to broadcast it.
-                    let previous_block_hash = block_template.previous_block_hash();
-                    let transactions = block_template.transactions().clone();
-                    if let Ok(block_header) = BlockHeader::<N>::from(
-                        block_template.previous_ledger_root(),
-                        block_template.transactions().transactions_root(),
-                        BlockHeaderMetadata::new(&block_template),
-                        nonce,
-                        proof,
-                    ) {
-                        if let Ok(block) = Block::from(previous_block_hash, block_header, transactions) {
-                            info!("Operator has found unconfirmed block {} ({})", block.height(), block.hash());
-                            let request = LedgerRequest::UnconfirmedBlock(self.local_ip, block, self.prover_router.clone());
-                            self.ledger_reader.invalidate_coinbase_cache();
-                            if let Err(error) = self.ledger_router.send(request).await {
-                                warn!("Failed to broadcast mined block - {}", error);
+    ///
+    /// Returns the replay window of the share timestamps, with the clock of each prover.
+    ///
+    pub fn share_timestamps(&self) -> &ShareTimestamps<N> {
+        &self.share_timestamps
+    }
+
+    ///
+    /// Returns the hashrate leases of the operator.
+    ///
+    pub fn leases(&self) -> &Leases<N> {
+        &self.leases
+    }
+
+    ///
+    /// Returns the incidents in the operation of the pool.
+    ///
+    pub fn audit_events(&self) -> &AuditEvents<N> {
+        &self.audit_events
+    }
+
+    ///
+    /// Returns `true` if the payouts are halted, as the chain of the node is split from the external endpoints.
+    ///
+    pub fn are_payouts_halted(&self) -> bool {
+        self.payouts_halted.load(Ordering::SeqCst)
+    }
+
+    ///
+    /// Returns `true` if the operator is in maintenance, with the backup pool that its provers are told to reconnect to.
+    ///
+    pub fn maintenance(&self) -> (bool, Option<SocketAddr>) {
+        (self.is_in_maintenance.load(Ordering::SeqCst), *self.backup_pool.lock())
+    }
+
+    ///
+    /// Puts the operator into maintenance: it refuses new provers, tells its connected provers to reconnect
+    /// to the given backup pool, if any, and once the grace period for their in-flight shares has elapsed,
+    /// persists the accounting of the current round and shuts the node down cleanly.
+    ///
+    pub async fn enter_maintenance(self: &Arc<Self>, backup_ip: Option<SocketAddr>) -> Result<()> {
+        if E::NODE_TYPE != NodeType::Operator {
+            return Err(anyhow!("Only an operating node may enter maintenance"));
+        }
+        if backup_ip == Some(self.local_ip) || (backup_ip.is_some() && backup_ip == self.tls_ip) {
+            return Err(anyhow!("The backup pool must not be this operator"));
+        }
+        {
+            // Set the backup pool before the flag, so any prover refused from here on is told of it.
+            let mut backup_pool = self.backup_pool.lock();
+            if self.is_in_maintenance.load(Ordering::SeqCst) {
+                return Err(anyhow!("The operator is already in maintenance"));
+            }
+            *backup_pool = backup_ip;
+            self.is_in_maintenance.store(true, Ordering::SeqCst);
+        }
+        match backup_ip {
+            Some(backup_ip) => warn!("Operator is entering maintenance, migrating its provers to the backup pool {}", backup_ip),
+            None => warn!("Operator is entering maintenance, without a backup pool for its provers"),
+        }
+
+        // Tell the connected provers to reconnect to the backup pool, which drops their connections.
+        let message = Message::Disconnect(DisconnectReason::PoolMaintenance(backup_ip));
+        if let Err(error) = self.peers_router.send(PeersRequest::MessagePropagateProver(message)).await {
+            warn!("[PoolMaintenance] {}", error);
+        }
+
+        let operator = self.clone();
+        task::spawn(async move {
+            // Let the in-flight shares arrive, then persist the accounting of the current round.
+            tokio::time::sleep(MAINTENANCE_GRACE_PERIOD).await;
+            let flushing_operator = operator.clone();
+            let _ = task::spawn_blocking(move || flushing_operator.flush_pending_shares()).await;
+            info!("Operator has finished the accounting of the current round, shutting down for maintenance...");
+            if !E::shutdown().request(ShutdownKind::Stop, Duration::ZERO) {
+                debug!("The node is already shutting down");
+            }
+        });
+
+        Ok(())
+    }
+
+    ///
+    /// Persists the shares, share history, and round statistics accepted since the last heartbeat,
+    /// so that no accepted share is lost when the node shuts down.
+    ///
+    pub fn shut_down(&self) {
+        debug!("Operator is shutting down...");
+        self.flush_pending_shares();
+        self.flush_leases();
+        trace!("[ShuttingDown] Pending shares and leases have been persisted");
+    }
+
+    ///
+    /// Periodically compares the chain of the node with the external endpoints of the given detector,
+    /// raising an audit event once the chain is split from them, and halting the payouts until it is not, if configured.
+    ///
+    pub fn start_chain_split_detector(self: &Arc<Self>, detector: ChainSplitDetector<N>) {
+        let operator = self.clone();
+        E::resources().register_task(
+            None, // No need to provide an id, as the task will run indefinitely.
+            task::spawn(async move {
+                let mut is_split = false;
+                loop {
+                    tokio::time::sleep(detector.interval()).await;
+
+                    let comparisons = detector.compare_tips(&operator.ledger_reader).await;
+                    let statuses: Vec<TipStatus> = comparisons.iter().map(|(_, status)| status.clone()).collect();
+                    let is_chain_split = detector.is_chain_split(&statuses);
+                    match (is_split, is_chain_split) {
+                        (false, true) => {
+                            let diverged: Vec<String> = comparisons
+                                .iter()
+                                .filter_map(|(endpoint, status)| match status {
+                                    TipStatus::Diverged { fork_depth } => Some(format!("{} by {} blocks", endpoint, fork_depth)),
+                                    _ => None,
+                                })
+                                .collect();
+                            let reason = format!("The chain diverges from {}", diverged.join(", "));
+                            operator.audit_events.record(
+                                AuditEventKind::ChainSplit,
+                                operator.ledger_reader.latest_block_height(),
+                                operator.ledger_reader.latest_block_hash(),
+                                reason.clone(),
+                                vec![],
+                            );
+                            error!("Chain split detected: {}", reason);
+                            if detector.halt_payouts() {
+                                operator.payouts_halted.store(true, Ordering::SeqCst);
+                                warn!("Halting the payouts until the chain agrees with the external endpoints");
                             }
                         }
+                        (true, false) => {
+                            info!("The chain agrees with the external endpoints again");
+                            if operator.payouts_halted.swap(false, Ordering::SeqCst) {
+                                info!("Resuming the payouts");
+                            }
+                        }
+                        _ => (),
                     }
-                } else {
-                    warn!("[PoolResponse] No current block template exists");
+                    is_split = is_chain_split;
                 }
-            }
-            OperatorRequest::PoolBlock(nonce, proof) => {
+            }),
+        );
+    }
+
+    ///
+    /// Periodically cross-checks the derived indexes of the storage against the canonical blocks, on a sample of the blocks,
+    /// and the shares of the rounds won by an operating node against the canonical chain,
+    /// raising an audit event for each block with a newly diverged index.
+    ///
+    pub fn start_index_divergence_detector(self: &Arc<Self>, interval: Duration, sample_size: u32) {
+        let operator = self.clone();
+        E::resources().register_task(
+            None, // No need to provide an id, as the task will run indefinitely.
+            task::spawn(async move {
+                // The divergences already reported, which persist until the indexes are repaired.
+                let mut reported = HashSet::new();
+                loop {
+                    tokio::time::sleep(interval).await;
+
+                    let checker = operator.clone();
+                    let divergences = match task::spawn_blocking(move || checker.check_indexes(sample_size)).await {
+                        Ok(divergences) => divergences,
+                        Err(error) => {
+                            warn!("Failed to check the storage indexes: {}", error);
+                            continue;
+                        }
+                    };
+                    for (block_height, divergences) in divergences {
+                        let reasons: Vec<String> = divergences
+                            .iter()
+                            .map(ToString::to_string)
+                            .filter(|reason| reported.insert(reason.clone()))
+                            .collect();
+                        if reasons.is_empty() {
+                            continue;
+                        }
+                        #[cfg(any(feature = "test", feature = "prometheus"))]
+                        for divergence in &divergences {
+                            metrics::increment_counter!(metrics::storage::INDEX_DIVERGENCES, "index" => divergence.index());
+                        }
+                        let reason = reasons.join("; ");
+                        let block_hash = match operator.ledger_reader.get_block_hash(block_height) {
+                            Ok(block_hash) => block_hash,
+                            Err(_) => operator.ledger_reader.latest_block_hash(),
+                        };
+                        operator
+                            .audit_events
+                            .record(AuditEventKind::IndexDivergence, block_height, block_hash, reason.clone(), vec![]);
+                        error!("Index divergence detected in block {}: {}", block_height, reason);
+                    }
+                }
+            }),
+        );
+    }
+
+    ///
+    /// Returns the divergences of the storage indexes of the given number of sampled blocks,
+    /// and of the shares of the rounds won by an operating node, by block height.
+    ///
+    fn check_indexes(&self, sample_size: u32) -> BTreeMap<u32, Vec<IndexDivergence<N>>> {
+        let mut divergences: BTreeMap<u32, Vec<_>> = BTreeMap::new();
+        let latest_block_height = self.ledger_reader.latest_block_height();
+        for block_height in sample_block_heights(latest_block_height, sample_size, &mut thread_rng()) {
+            // A block reverted since the sample was taken cannot be checked, and is skipped.
+            match self.ledger_reader.check_indexes(block_height) {
+                Ok(block_divergences) if block_divergences.is_empty() => (),
+                Ok(block_divergences) => {
+                    divergences.insert(block_height, block_divergences);
+                }
+                Err(error) => debug!("Failed to check the indexes of block {}: {}", block_height, error),
+            }
+        }
+        if E::NODE_TYPE == NodeType::Operator {
+            match self.state.check_share_rounds(&self.ledger_reader) {
+                Ok(share_divergences) => {
+                    for divergence in share_divergences {
+                        if let IndexDivergence::ShareRound { block_height, .. } = divergence {
+                            divergences.entry(block_height).or_default().push(divergence);
+                        }
+                    }
+                }
+                Err(error) => warn!("Failed to check the shares of the rounds: {}", error),
+            }
+        }
+        divergences
+    }
+
+    ///
+    /// Constructs a new block template paying the coinbase to the given recipient.
+    ///
+    async fn construct_block_template(&self, recipient: Address<N>) -> Result<Result<BlockTemplate<N>, String>, task::JoinError> {
+        // Leave out the quarantined transactions of the memory pool, placing its local priority transactions first.
+        let transactions: Vec<_> = self
+            .memory_pool
+            .prioritized_transactions()
+            .into_iter()
+            .filter(|transaction| !self.quarantined_transactions.contains_key(&transaction.transaction_id()))
+            .collect();
+        let ledger_reader = self.ledger_reader.clone();
+        task::spawn_blocking(move || {
+            E::thread_pool().install(move || {
+                match ledger_reader.get_block_template(recipient, E::COINBASE_IS_PUBLIC, &transactions, &mut thread_rng()) {
+                    Ok(block_template) => Ok(block_template),
+                    Err(error) => Err(format!("Failed to produce a new block template: {}", error)),
+                }
+            })
+        })
+        .await
+    }
+
+    ///
+    /// Submits the given block to the ledger, which propagates it to the network, and tracks it until it is accepted.
+    /// Returns an error if the ledger is unresponsive, in which case the block is not tracked.
+    ///
+    async fn submit_block(&self, block: Block<N>) -> Result<()> {
+        let block_hash = block.hash();
+        self.submitted_blocks.insert(block_hash, (block.clone(), Instant::now()));
+        let request = LedgerRequest::UnconfirmedBlock(self.local_ip, block, self.prover_router.clone());
+        self.ledger_reader.invalidate_coinbase_cache();
+        match self.ledger_circuit_breaker.call(self.ledger_router.send(request)).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(error)) => {
+                warn!("Failed to broadcast mined block - {}", error);
+                Ok(())
+            }
+            Err(error) => {
+                // The block never reached the ledger, so it must not be mistaken for a rejected block.
+                self.submitted_blocks.remove(&block_hash);
+                Err(error)
+            }
+        }
+    }
+
+    ///
+    /// Checks the submitted blocks, and recovers from each block that the ledger has not accepted in time.
+    /// A block that lost to another block at its height is dropped, as it was not rejected.
+    ///
+    async fn check_submitted_blocks(&self) {
+        // Release the transactions whose quarantine has ended.
+        self.quarantined_transactions
+            .retain(|_, quarantined_at| quarantined_at.elapsed() < QUARANTINE_DURATION);
+
+        let latest_block_height = self.ledger_reader.latest_block_height();
+        let block_hashes: Vec<_> = self.submitted_blocks.iter().map(|entry| *entry.key()).collect();
+        for block_hash in block_hashes {
+            let (block_height, is_expired) = match self.submitted_blocks.get(&block_hash) {
+                Some(entry) => (entry.0.height(), entry.1.elapsed() > BLOCK_ACCEPTANCE_TIMEOUT),
+                None => continue,
+            };
+            if let Ok(true) = self.ledger_reader.contains_block_hash(&block_hash) {
+                self.submitted_blocks.remove(&block_hash);
+            } else if block_height <= latest_block_height {
+                debug!("Operator block {} ({}) was orphaned", block_height, block_hash);
+                self.submitted_blocks.remove(&block_hash);
+            } else if is_expired {
+                if let Some((_, (block, _))) = self.submitted_blocks.remove(&block_hash) {
+                    self.recover_from_rejected_block(block).await;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Quarantines the suspect transactions of the given rejected block, rebuilds the block templates,
+    /// and records the incident in the audit events.
+    ///
+    async fn recover_from_rejected_block(&self, block: Block<N>) {
+        let (block_height, block_hash) = (block.height(), block.hash());
+
+        // Determine the transactions of the block that the ledger would reject.
+        let ledger_reader = self.ledger_reader.clone();
+        let suspects = task::spawn_blocking(move || Self::find_suspect_transactions(&ledger_reader, &block))
+            .await
+            .unwrap_or_default();
+
+        let reason = match suspects.is_empty() {
+            true => "The block was not accepted by the ledger, and none of its transactions are suspect".to_string(),
+            false => suspects.iter().map(|(_, reason)| reason.as_str()).collect::<Vec<_>>().join("; "),
+        };
+        let quarantined_transactions: Vec<_> = suspects.into_iter().map(|(transaction_id, _)| transaction_id).collect();
+        for transaction_id in &quarantined_transactions {
+            self.quarantined_transactions.insert(*transaction_id, Instant::now());
+        }
+
+        // Drop the block templates, so they are rebuilt without the quarantined transactions on the next heartbeat.
+        *self.block_template.write().await = None;
+        self.lease_templates.write().await.clear();
+
+        let event = self.audit_events.record(
+            AuditEventKind::BlockRejected,
+            block_height,
+            block_hash,
+            reason,
+            quarantined_transactions,
+        );
+        error!(
+            "Operator block {} ({}) was rejected, quarantining {} transactions: {}",
+            block_height,
+            block_hash,
+            event.quarantined_transactions.len(),
+            event.reason
+        );
+    }
+
+    ///
+    /// Returns the transactions of the given block that the ledger would reject := (transaction_id, reason)
+    ///
+    fn find_suspect_transactions(ledger_reader: &LedgerReader<N>, block: &Block<N>) -> Vec<(N::TransactionID, String)> {
+        let mut serial_numbers = HashSet::new();
+        let mut suspects = Vec::new();
+        for transaction in block.transactions().iter() {
+            let transaction_id = transaction.transaction_id();
+            let reason = if !transaction.is_valid() {
+                Some("is invalid")
+            } else if !transaction.serial_numbers().all(|serial_number| serial_numbers.insert(*serial_number)) {
+                Some("spends a serial number of another transaction in the block")
+            } else if transaction
+                .serial_numbers()
+                .any(|serial_number| ledger_reader.contains_serial_number(serial_number).unwrap_or(true))
+            {
+                Some("spends a serial number that already exists in the ledger")
+            } else if transaction
+                .commitments()
+                .any(|commitment| ledger_reader.contains_commitment(commitment).unwrap_or(true))
+            {
+                Some("contains a commitment that already exists in the ledger")
+            } else if !ledger_reader.contains_ledger_root(&transaction.ledger_root()).unwrap_or(false) {
+                Some("references an unknown ledger root")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                suspects.push((transaction_id, format!("Transaction {} {}", transaction_id, reason)));
+            }
+        }
+        suspects
+    }
+
+    ///
+    /// Constructs a block template for each active lease without a current one, and drops those of expired leases.
+    ///
+    async fn update_lease_templates(&self) {
+        let active_leases = self.leases.active_leases();
+        let next_block_height = self.ledger_reader.latest_block_height().saturating_add(1);
+
+        // Drop the block templates of the expired leases.
+        self.lease_templates
+            .write()
+            .await
+            .retain(|id, _| active_leases.iter().any(|(lease_id, _)| lease_id == id));
+
+        // Construct the stale block templates concurrently.
+        let mut stale_leases = Vec::new();
+        for (id, renter) in active_leases {
+            let is_stale = match self.lease_templates.read().await.get(&id) {
+                Some(template) => template.block_height() != next_block_height,
+                None => true,
+            };
+            if is_stale {
+                stale_leases.push((id, renter));
+            }
+        }
+        let block_templates =
+            futures::future::join_all(stale_leases.iter().map(|(_, renter)| self.construct_block_template(*renter))).await;
+
+        for ((id, _), result) in stale_leases.into_iter().zip(block_templates) {
+            match result {
+                Ok(Ok(block_template)) => {
+                    self.lease_templates.write().await.insert(id, block_template);
+                }
+                Ok(Err(error_message)) => error!("[Lease {}] {}", id, error_message),
+                Err(error) => error!("[Lease {}] {}", id, error),
+            }
+        }
+    }
+
+    ///
+    /// Returns the block template of the job to send to the given prover, assigning the job to a lease
+    /// in proportion to the leased shares of jobs. A prover already assigned a job for the current block height keeps it,
+    /// so a prover may not re-roll its assignment by requesting its job again.
+    ///
+    async fn assign_job(&self, prover: Address<N>) -> Option<BlockTemplate<N>> {
+        if self.assignments.contains_key(&prover) {
+            let current_block_height = self.block_template.read().await.as_ref().map(|template| template.block_height());
+            if let (_, Some(block_template)) = self.assigned_job(&prover).await {
+                if Some(block_template.block_height()) == current_block_height {
+                    return Some(block_template);
+                }
+            }
+        }
+
+        let lease_id = self.leases.select(&mut thread_rng());
+        let lease_template = match lease_id {
+            Some(id) => self.lease_templates.read().await.get(&id).cloned(),
+            None => None,
+        };
+
+        match (lease_id, lease_template) {
+            (Some(id), Some(block_template)) => {
+                self.leases.record_job(id);
+                self.assignments.insert(prover, Some(id));
+                Some(block_template)
+            }
+            // Fall back to the block template of the operator, if the lease has no block template yet.
+            _ => {
+                self.assignments.insert(prover, None);
+                self.block_template.read().await.clone()
+            }
+        }
+    }
+
+    ///
+    /// Returns the block template of the job last given to the given prover, and the lease it was assigned to.
+    ///
+    async fn assigned_job(&self, prover: &Address<N>) -> (Option<u64>, Option<BlockTemplate<N>>) {
+        match self.assignments.get(prover).and_then(|assignment| *assignment) {
+            Some(id) => (Some(id), self.lease_templates.read().await.get(&id).cloned()),
+            None => (None, self.block_template.read().await.clone()),
+        }
+    }
+
+    ///
+    /// Returns the share difficulty for the given prover, registering the prover if it is new.
+    ///
+    fn get_share_difficulty(&self, prover: Address<N>) -> u64 {
+        // Attempt a non-blocking read first, so that contention on the prover's shard can be observed.
+        match self.provers.try_get(&prover) {
+            TryResult::Present(state) => return state.1,
+            TryResult::Absent => {}
+            TryResult::Locked => {
+                #[cfg(any(feature = "test", feature = "prometheus"))]
+                metrics::increment_counter!(metrics::operator::SHARD_CONTENTION);
+            }
+        }
+
+        let share_difficulty = self.provers.entry(prover).or_insert((Instant::now(), BASE_SHARE_DIFFICULTY)).1;
+
+        #[cfg(any(feature = "test", feature = "prometheus"))]
+        metrics::gauge!(metrics::operator::PROVERS, self.provers.len() as f64);
+
+        share_difficulty
+    }
+
+    ///
+    /// Increments the pending share count for the given prover in the given round.
+    ///
+    fn record_share(&self, block_height: u32, coinbase_record: &Record<N>, prover: Address<N>) {
+        #[cfg(any(feature = "test", feature = "prometheus"))]
+        metrics::increment_counter!(metrics::operator::SHARES_ACCEPTED);
+        #[cfg(feature = "chaos")]
+        self.fault_injector.accounting().record_accepted();
+
+        self.pending_shares.record(block_height, coinbase_record, prover);
+    }
+
+    ///
+    /// Revokes up to the given number of shares from the given prover in the given round,
+    /// from its pending shares first, and then from its persisted shares.
+    ///
+    fn revoke_shares(&self, block_height: u32, coinbase_record: &Record<N>, prover: Address<N>, penalty: u64) {
+        let mut revoked = self.pending_shares.revoke(block_height, prover, penalty);
+        if revoked < penalty {
+            match self.state.revoke_shares(block_height, coinbase_record.clone(), &prover, penalty - revoked) {
+                Ok(persisted) => revoked = revoked.saturating_add(persisted),
+                Err(error) => error!("Failed to revoke the shares of {} in block {}: {}", prover, block_height, error),
+            }
+        }
+        #[cfg(feature = "chaos")]
+        self.fault_injector.accounting().record_revoked(revoked);
+        warn!(
+            "Revoked {} of {} shares from {} for a failed sample in block {}",
+            revoked, penalty, prover, block_height
+        );
+    }
+
+    ///
+    /// Adds a valid share to the pending round statistics, given the probability that it was a block.
+    ///
+    fn record_round_share(&self, block_height: u32, expected_blocks: f64) {
+        let mut round = self.pending_rounds.entry(block_height).or_insert((0, 0.0));
+        round.0 = round.0.saturating_add(1);
+        round.1 += expected_blocks;
+    }
+
+    ///
+    /// Records the given block as found by the operator, for the round statistics and the audit trail.
+    ///
+    fn record_found_block(&self, block: &Block<N>, found_block: FoundBlock<N>) {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if let Err(error) = self.state.set_round_block(block.height(), block.hash(), now) {
+            error!("Failed to store the found block {}: {}", block.height(), error);
+        }
+        // Store the payouts of the block, unless its coinbase is paid to the renter of a lease.
+        if found_block.lease_id.is_none() {
+            self.record_payouts(block, found_block.block_template.coinbase_record());
+        }
+        // Store the provenance of the block, linking it to the share that found it.
+        if let Err(error) = self.state.add_found_block(block.height(), found_block) {
+            error!("Failed to store the provenance of the found block {}: {}", block.height(), error);
+        }
+        // Index the block by its tag, so the authorship of the operator can be proven.
+        if let Some(block_tag) = self.address.and_then(|address| verify_block_tag(block, &address)) {
+            if let Err(error) = self.state.add_tagged_block(&block_tag, block.height(), block.hash()) {
+                error!("Failed to index the found block {} by its tag: {}", block.height(), error);
+            }
+        }
+    }
+
+    ///
+    /// Records the payouts of the given block, splitting its coinbase between the provers of the round.
+    ///
+    fn record_payouts(&self, block: &Block<N>, coinbase_record: &Record<N>) {
+        // Persist the pending shares first, so the payouts account for every share of the round.
+        self.flush_pending_shares();
+        let payouts = self.compute_round_payouts(block.height(), coinbase_record, &self.sub_pools);
+        if let Err(error) = self.state.set_payouts(block.height(), block.hash(), &payouts) {
+            error!("Failed to store the payouts for block {}: {}", block.height(), error);
+        }
+        // Split the payouts into batches which each fit in a single transaction of the network.
+        let batches = batch_payouts(&payouts, maximum_payouts_per_transaction::<N>());
+        if let Err(error) = self.state.set_payout_batches(block.height(), block.hash(), &batches) {
+            error!("Failed to store the payout batches for block {}: {}", block.height(), error);
+        }
+    }
+
+    ///
+    /// Computes the payouts of the round with the given block height and coinbase record, under the given sub-pools.
+    /// Each prover is assigned to the sub-pool it was routed to in the round, as persisted with the shares of the round.
+    ///
+    fn compute_round_payouts(&self, block_height: u32, coinbase_record: &Record<N>, sub_pools: &SubPools<N>) -> HashMap<Address<N>, u64> {
+        // A round without persisted shares pays the whole coinbase to the operator.
+        let shares = self
+            .state
+            .get_shares_for_block(block_height, coinbase_record.clone())
+            .unwrap_or_default();
+        let routes = self.state.get_round_routes(block_height).unwrap_or_else(|error| {
+            error!("Failed to read the routes of the provers for block {}: {}", block_height, error);
+            Default::default()
+        });
+        let reward = coinbase_record.value().0 as u64;
+        // Each prover is paid to the payout address effective for the round, if it was updated.
+        let get_payout_address = |prover: &Address<N>| match self.state.get_payout_address(prover, block_height) {
+            Ok(payout_address) => payout_address.unwrap_or(*prover),
+            Err(error) => {
+                error!("Failed to read the payout address of {}: {}", prover, error);
+                *prover
+            }
+        };
+        compute_payouts(
+            reward,
+            &shares,
+            self.address,
+            |prover| match routes.get(prover) {
+                Some(sub_pool_name) => sub_pools.get_by_name(sub_pool_name),
+                None => sub_pools.public(),
+            },
+            get_payout_address,
+        )
+    }
+
+    ///
+    /// Writes the pending shares to storage, batching the increments for each round into a single write.
+    ///
+    fn flush_pending_shares(&self) {
+        // Drain the pending shares, grouping them by round.
+        let rounds = self.pending_shares.drain();
+
+        #[cfg(any(feature = "test", feature = "prometheus"))]
+        {
+            let number_of_pending_shares: u64 = rounds.values().flat_map(|(_, round)| round.values()).sum();
+            metrics::gauge!(metrics::operator::PENDING_SHARES, number_of_pending_shares as f64);
+        }
+
+        // Persist the shares for each round, with the sub-pool route of each prover, so the round is paid as it was routed.
+        for (block_height, (coinbase_record, shares)) in rounds {
+            let routes: HashMap<_, _> = {
+                let prover_routes = self.prover_routes.lock();
+                shares
+                    .keys()
+                    .map(|prover| (*prover, prover_routes.get(prover).map(|(_, sub_pool_name)| sub_pool_name.clone())))
+                    .collect()
+            };
+            if let Err(error) = self.state.set_round_routes(block_height, &routes) {
+                error!("Failed to store the routes of the provers for block {}: {}", block_height, error);
+            }
+
+            let result = self.state.increment_shares(block_height, coinbase_record, &shares);
+            #[cfg(feature = "chaos")]
+            if result.is_ok() {
+                self.fault_injector.accounting().record_attributed(shares.values().sum());
+            }
+            if let Err(error) = result {
+                error!("Failed to store the shares for block {}: {}", block_height, error);
+            }
+        }
+
+        // Check that every share attributed to a round was accepted, and that no accepted share was lost.
+        #[cfg(feature = "chaos")]
+        if let Err(error) = self.fault_injector.accounting().check(|| self.pending_shares.total()) {
+            error!("[Chaos] The share accounting is violated: {}", error);
+        }
+
+        // Persist the share history.
+        let share_history = std::mem::take(&mut *self.pending_share_history.lock());
+        if !share_history.is_empty() {
+            if let Err(error) = self.state.add_share_records(share_history) {
+                error!("Failed to store the share history: {}", error);
+            }
+        }
+
+        // Persist the round statistics.
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let keys: Vec<_> = self.pending_rounds.iter().map(|entry| *entry.key()).collect();
+        for key in keys {
+            if let Some((block_height, (shares, expected_blocks))) = self.pending_rounds.remove(&key) {
+                if let Err(error) = self.state.increment_round(block_height, shares, expected_blocks, now) {
+                    error!("Failed to store the round statistics for block {}: {}", block_height, error);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Writes the leases updated since the last write to storage.
+    ///
+    fn flush_leases(&self) {
+        let leases = self.leases.drain_updated();
+        if !leases.is_empty() {
+            if let Err(error) = self.state.set_leases(&leases) {
+                error!("Failed to store the leases: {}", error);
+            }
+        }
+    }
+
+    ///
+    /// Rolls up the shares and found blocks persisted since the last rollup into the contributions of the provers,
+    /// for every period of the leaderboard.
+    ///
+    fn roll_up_contributions(&self) {
+        let _rollup_lock = self.rollup_lock.lock();
+        let to = OffsetDateTime::now_utc().unix_timestamp().saturating_sub(ROLLUP_DELAY.as_secs() as i64);
+        for period in ContributionPeriod::ALL {
+            match self.state.roll_up_contributions(period, to) {
+                Ok(number_of_epochs) => trace!("Rolled up {} {} epochs of contributions", number_of_epochs, period),
+                Err(error) => error!("Failed to roll up the {} contributions: {}", period, error),
+            }
+        }
+    }
+
+    ///
+    /// Forgets the share difficulty and the job assignment of the provers which have been idle for `PROVER_IDLE_TIMEOUT`.
+    ///
+    fn evict_idle_provers(&self) {
+        self.provers.retain(|_, (last_seen, _)| last_seen.elapsed() < PROVER_IDLE_TIMEOUT);
+        self.assignments.retain(|prover, _| self.provers.contains_key(prover));
+
+        #[cfg(any(feature = "test", feature = "prometheus"))]
+        metrics::gauge!(metrics::operator::PROVERS, self.provers.len() as f64);
+    }
+
+    ///
+    /// Removes the shares, share history, rounds, and contributions which have expired under the retention policy of the operator.
+    ///
+    fn prune_expired_data(&self) {
+        // Roll up the share history first, so the expired shares are counted in the contributions before they are removed.
+        self.roll_up_contributions();
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        if let Some(cutoff) = self.retention_policy.shares_cutoff(now) {
+            // The shares are dated by the blocks of the canonical chain, so the shares of orphaned rounds expire too.
+            if let Some(block_height) = self.get_block_height_before(cutoff) {
+                match self.state.prune_shares(block_height) {
+                    Ok(0) => (),
+                    Ok(number_of_entries) => info!("Pruned {} expired share entries", number_of_entries),
+                    Err(error) => error!("Failed to prune the expired shares: {}", error),
+                }
+            }
+            match self.state.prune_share_history(cutoff) {
+                Ok(0) => (),
+                Ok(number_of_entries) => info!("Pruned {} expired share history entries", number_of_entries),
+                Err(error) => error!("Failed to prune the expired share history: {}", error),
+            }
+        }
+        if let Some(cutoff) = self.retention_policy.rounds_cutoff(now) {
+            match self.state.prune_rounds(cutoff) {
+                Ok(0) => (),
+                Ok(number_of_rounds) => info!("Pruned {} expired rounds", number_of_rounds),
+                Err(error) => error!("Failed to prune the expired rounds: {}", error),
+            }
+        }
+        if let Some(cutoff) = self.retention_policy.metrics_cutoff(now) {
+            match self.state.prune_contributions(cutoff) {
+                Ok(0) => (),
+                Ok(number_of_epochs) => info!("Pruned {} expired contribution epochs", number_of_epochs),
+                Err(error) => error!("Failed to prune the expired contributions: {}", error),
+            }
+        }
+    }
+
+    ///
+    /// Returns the height of the latest canonical block with a timestamp before the given UNIX timestamp,
+    /// or `None` if there is no such block.
+    ///
+    fn get_block_height_before(&self, timestamp: i64) -> Option<u32> {
+        let block_timestamp = |block_height: u32| self.ledger_reader.get_block_header(block_height).map(|header| header.timestamp());
+
+        // The block timestamps increase along the canonical chain, so the block height is found by a binary search.
+        if block_timestamp(0).ok()? >= timestamp {
+            return None;
+        }
+        let (mut low, mut high) = (0, self.ledger_reader.latest_block_height());
+        while low < high {
+            let middle = low + (high - low + 1) / 2;
+            match block_timestamp(middle) {
+                Ok(block_timestamp) if block_timestamp < timestamp => low = middle,
+                Ok(_) => high = middle - 1,
+                Err(_) => return None,
+            }
+        }
+        Some(low)
+    }
+
+    ///
+    /// Returns the current job of the given prover := (share_difficulty, block_template),
+    /// or `None` if no block template exists yet.
+    ///
+    async fn get_job(&self, prover: Address<N>) -> Result<Option<(u64, BlockTemplate<N>)>> {
+        // Ensure the operator is not in maintenance.
+        if let (true, backup_ip) = self.maintenance() {
+            return match backup_ip {
+                Some(backup_ip) => Err(anyhow!("The pool is in maintenance, reconnect to the backup pool {}", backup_ip)),
+                None => Err(anyhow!("The pool is in maintenance")),
+            };
+        }
+
+        // Ensure the prover is admitted to a sub-pool.
+        if self.get_sub_pool(&prover).is_none() {
+            return Err(anyhow!("Prover {} is not admitted to any sub-pool", prover));
+        }
+
+        // Ensure the state of the provers remains bounded, and mark the prover as seen.
+        match self.provers.get_mut(&prover) {
+            Some(mut state) => state.0 = Instant::now(),
+            None if self.provers.len() >= MAXIMUM_NUMBER_OF_PROVERS => {
+                return Err(anyhow!("The pool has reached its maximum of {} provers", MAXIMUM_NUMBER_OF_PROVERS));
+            }
+            None => (),
+        }
+
+        match self.assign_job(prover).await {
+            // Ensure this prover exists in the list first, and retrieve their share difficulty.
+            Some(block_template) => Ok(Some((self.get_share_difficulty(prover), block_template))),
+            None => Ok(None),
+        }
+    }
+
+    ///
+    /// Returns the current job of the given prover, as in `get_job`, for a request signed by the prover
+    /// at the given UNIX timestamp. If the current block template is for the given known block height,
+    /// waits until a new block template is available, or the given timeout elapses.
+    ///
+    pub async fn wait_for_job(
+        &self,
+        prover: Address<N>,
+        known_block_height: Option<u32>,
+        timestamp: i64,
+        signature: N::AccountSignature,
+        timeout: Duration,
+    ) -> Result<Option<(u64, BlockTemplate<N>)>> {
+        // Ensure the request was signed recently by the prover, so no one else may take or reassign its job.
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if timestamp.saturating_sub(now).abs() > self.share_timestamps.tolerance_in_secs() {
+            return Err(anyhow!("The job request timestamp {} is off the clock of the operator", timestamp));
+        }
+        let message = pool_job_message(&prover, timestamp);
+        if !prover.verify_signature(&message.as_bytes().to_bits_le(), &signature)? {
+            return Err(anyhow!("Invalid signature for the job request of {}", prover));
+        }
+
+        if let Some(known_block_height) = known_block_height {
+            // Register for the notification before checking the block template, so that no update is missed.
+            let new_block_template = self.new_block_template.notified();
+            let is_current = match &*self.block_template.read().await {
+                Some(template) => template.block_height() == known_block_height,
+                None => false,
+            };
+            if is_current {
+                // Ensure the long-polls held open remain bounded.
+                let _permit = self
+                    .job_pollers
+                    .try_acquire()
+                    .map_err(|_| anyhow!("Too many provers are waiting for a new job, retry later"))?;
+                let _ = tokio::time::timeout(timeout, new_block_template).await;
+            }
+        }
+        self.get_job(prover).await
+    }
+
+    ///
+    /// Submits the given share of the given prover, stamped at the given UNIX timestamp by the prover, through the operator router,
+    /// and returns once it is processed, as in `process_share`.
+    ///
+    pub async fn submit_share(&self, prover: Address<N>, nonce: N::PoSWNonce, timestamp: i64, proof: PoSWProof<N>) -> Result<()> {
+        let (router, handler) = oneshot::channel();
+        let request = OperatorRequest::PoolShare(prover, nonce, timestamp, proof, router);
+        self.operator_router.send(request).await?;
+        handler.await?
+    }
+
+    ///
+    /// Verifies the given share of the given prover, stamped at the given UNIX timestamp by the prover, against its current job,
+    /// and accounts for it if it is valid. A share that is not stamped is rejected. If the share satisfies the block difficulty,
+    /// the block is broadcast.
+    /// The share arrived over the given connection, or over the RPC server of the operator if `None`.
+    /// The share is recorded in the share history of the prover, whether it is accepted or rejected.
+    ///
+    async fn process_share(
+        &self,
+        peer_ip: Option<SocketAddr>,
+        prover: Address<N>,
+        nonce: N::PoSWNonce,
+        timestamp: Option<i64>,
+        proof: PoSWProof<N>,
+    ) -> Result<()> {
+        // Ensure the prover is admitted to a sub-pool, so that only the history of admitted provers is recorded.
+        if self.get_sub_pool(&prover).is_none() {
+            return Err(anyhow!("Prover {} is not admitted to any sub-pool", prover));
+        }
+        // Ensure the share arrives over the connection the prover is bound to, so no one else may submit to its sub-pool.
+        self.check_prover_route(&prover, peer_ip)?;
+
+        let mut share_record = ShareRecord {
+            submitted_at: OffsetDateTime::now_utc().unix_timestamp(),
+            worker: peer_ip.map_or_else(|| "http".to_string(), |peer_ip| peer_ip.to_string()),
+            block_height: None,
+            share_difficulty: None,
+            is_accepted: false,
+            rejection_reason: None,
+        };
+        let result = self.verify_share(peer_ip, prover, nonce, timestamp, proof, &mut share_record).await;
+
+        // A share that was accepted remains accepted, even if the block it satisfied failed to be submitted.
+        if let Err(error) = &result {
+            if !share_record.is_accepted {
+                share_record.rejection_reason = Some(error.to_string());
+            }
+        }
+        self.pending_share_history.lock().push((prover, share_record));
+
+        result
+    }
+
+    ///
+    /// Verifies the given share of the given prover, as in `process_share`, filling in the given share record.
+    ///
+    async fn verify_share(
+        &self,
+        peer_ip: Option<SocketAddr>,
+        prover: Address<N>,
+        nonce: N::PoSWNonce,
+        timestamp: Option<i64>,
+        proof: PoSWProof<N>,
+        share_record: &mut ShareRecord,
+    ) -> Result<()> {
+        let (lease_id, block_template) = self.assigned_job(&prover).await;
+        let block_template = match block_template {
+            Some(block_template) => block_template,
+            None => return Err(anyhow!("No current block template exists")),
+        };
+
+        // Ensure the share was stamped within the replay window, before its nonce is marked as known.
+        // Provers that predate timestamped shares do not stamp them.
+        let timestamp = timestamp.ok_or_else(|| anyhow!("The share is not timestamped"))?;
+        self.share_timestamps
+            .validate(prover, timestamp, block_template.block_timestamp(), share_record.submitted_at)?;
+
+        // Delay the verification of the share, so the round may roll over while the share is in flight.
+        #[cfg(feature = "chaos")]
+        if let Some(delay) = self.fault_injector.share_delay() {
+            tokio::time::sleep(delay).await;
+        }
+
+        // Ensure the given nonce from the prover is new, and mark it as known.
+        if !self.known_nonces.insert(nonce) {
+            // TODO (julesdesmit): punish?
+            return Err(anyhow!("Duplicate share"));
+        }
+
+        // Retrieve the share difficulty for the given prover.
+        let share_difficulty = self.get_share_difficulty(prover);
+        let block_height = block_template.block_height();
+        share_record.block_height = Some(block_height);
+        share_record.share_difficulty = Some(share_difficulty);
+
+        // Ensure the share difficulty target is met, and the PoSW proof is valid, unless the share is not sampled under load.
+        // A share that is not verified here is still verified in full by the block header if it meets the block difficulty.
+        let job_id = block_template.to_header_root()?;
+        match self.share_sampling.select(peer_ip.map(|peer_ip| peer_ip.ip())) {
+            ShareVerification::Skipped => {
+                #[cfg(any(feature = "test", feature = "prometheus"))]
+                metrics::increment_counter!(metrics::operator::SHARES_UNVERIFIED);
+            }
+            verification => {
+                let inputs = [*job_id, *nonce];
+                let proof_clone = proof.clone();
+                let posw = self.posw.clone();
+                let is_valid = task::spawn_blocking(move || posw.verify(block_height, share_difficulty, &inputs, &proof_clone))
+                    .await
+                    .unwrap_or(false);
+                if !is_valid {
+                    // A failed sample stands for the unverified shares of the peer, which are revoked from its prover in its place.
+                    if let (ShareVerification::Sampled, Some(peer_ip)) = (verification, peer_ip) {
+                        let penalty = self.share_sampling.record_failure(peer_ip.ip());
+                        self.revoke_shares(block_height, block_template.coinbase_record(), prover, penalty);
+                    }
+                    return Err(anyhow!("PoSW proof verification failed"));
+                }
+            }
+        }
+
+        // Update the internal state for this prover.
+        match self.provers.get_mut(&prover) {
+            Some(mut state) => state.0 = Instant::now(),
+            None => return Err(anyhow!("Prover should have existing info")),
+        }
+
+        // Account the share in the round statistics, as the probability that a share at this difficulty is a block.
+        let expected_blocks = (block_template.difficulty_target() as f64 / share_difficulty as f64).min(1.0);
+        self.record_round_share(block_height, expected_blocks);
+
+        // Increment the share count for the prover, and for the lease if the job was built for a renter.
+        // The shares of a leased job are attributed to the coinbase record of the renter, so the prover is credited
+        // for its work, but is not paid from the blocks of the operator for it.
+        self.record_share(block_height, block_template.coinbase_record(), prover);
+        if let Some(id) = lease_id {
+            self.leases.record_share(id);
+        }
+        share_record.is_accepted = true;
+        info!(
+            "Operator has received a valid share from {} ({}) for block {}",
+            prover, share_record.worker, block_height
+        );
+
+        // If the block has satisfactory difficulty and is valid, proceed to broadcast it.
+        let previous_block_hash = block_template.previous_block_hash();
+        let transactions = block_template.transactions().clone();
+        if let Ok(block_header) = BlockHeader::<N>::from(
+            block_template.previous_ledger_root(),
+            block_template.transactions().transactions_root(),
+            BlockHeaderMetadata::new(&block_template),
+            nonce,
+            proof,
+        ) {
+            if let Ok(block) = Block::from(previous_block_hash, block_header, transactions) {
+                let block = self.tag_found_block(block);
+                info!("Operator has found unconfirmed block {} ({})", block.height(), block.hash());
+                let found_block = FoundBlock {
+                    block_hash: block.hash(),
+                    prover: Some(prover),
+                    worker: share_record.worker.clone(),
+                    job_id,
+                    lease_id,
+                    share_difficulty: Some(share_difficulty),
+                    nonce,
+                    block_template: block_template.clone(),
+                    submitted_at: share_record.submitted_at,
+                };
+                self.record_found_block(&block, found_block);
+                if let Some(id) = lease_id {
+                    self.leases.record_block(id, block.height(), block.hash());
+                }
+                self.submit_block(block).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Performs the given `request` to the operator.
+    /// All requests must go through this `update`, so that a unified view is preserved.
+    ///
+    pub(super) async fn update(&self, request: OperatorRequest<N>) {
+        match request {
+            OperatorRequest::PoolRegister(peer_ip, address) => {
+                // Refuse new provers while in maintenance, telling them of the backup pool.
+                if let (true, backup_ip) = self.maintenance() {
+                    debug!("Refusing prover {} ({}) while in maintenance", address, peer_ip);
+                    let message = Message::Disconnect(DisconnectReason::PoolMaintenance(backup_ip));
+                    if let Err(error) = self.peers_router.send(PeersRequest::MessageSend(peer_ip, message)).await {
+                        warn!("[PoolMaintenance] {}", error);
+                    }
+                    return;
+                }
+                if let Err(error) = self.route_prover(peer_ip, address).await {
+                    warn!("[PoolRegister] {} ({})", error, peer_ip);
+                    return;
+                }
+                match self.get_job(address).await {
+                    Ok(Some((share_difficulty, block_template))) => {
+                        // Route a `PoolRequest` to the peer.
+                        let message = Message::PoolRequest(share_difficulty, Data::Object(block_template));
+                        if let Err(error) = self.peers_router.send(PeersRequest::MessageSend(peer_ip, message)).await {
+                            warn!("[PoolRequest] {}", error);
+                        }
+                    }
+                    Ok(None) => warn!("[PoolRegister] No current block template exists"),
+                    Err(error) => warn!("[PoolRegister] {} ({})", error, peer_ip),
+                }
+            }
+            OperatorRequest::PoolResponse(peer_ip, prover, nonce, timestamp, proof) => {
+                if let Err(error) = self.process_share(Some(peer_ip), prover, nonce, timestamp, proof).await {
+                    warn!("[PoolResponse] Rejected a share from {} ({}): {}", prover, peer_ip, error);
+                }
+            }
+            OperatorRequest::PoolShare(prover, nonce, timestamp, proof, router) => {
+                let _ = router.send(self.process_share(None, prover, nonce, Some(timestamp), proof).await);
+            }
+            OperatorRequest::PoolBlock(peer_ip, nonce, proof) => {
+                let submitted_at = OffsetDateTime::now_utc().unix_timestamp();
                 if let Some(block_template) = self.block_template.read().await.clone() {
                     let previous_block_hash = block_template.previous_block_hash();
                     let transactions = block_template.transactions().clone();
@@ -354,11 +2016,24 @@ impl<N: Network, E: Environment> Operator<N, E> {
                         proof,
                     ) {
                         if let Ok(block) = Block::from(previous_block_hash, block_header, transactions) {
+                            let block = self.tag_found_block(block);
                             info!("Operator has found unconfirmed block {} ({})", block.height(), block.hash());
-                            let request = LedgerRequest::UnconfirmedBlock(self.local_ip, block, self.prover_router.clone());
-                            self.ledger_reader.invalidate_coinbase_cache();
-                            if let Err(error) = self.ledger_router.send(request).await {
-                                warn!("Failed to broadcast mined block - {}", error);
+                            if let Ok(job_id) = block_template.to_header_root() {
+                                let found_block = FoundBlock {
+                                    block_hash: block.hash(),
+                                    prover: None,
+                                    worker: peer_ip.to_string(),
+                                    job_id,
+                                    lease_id: None,
+                                    share_difficulty: None,
+                                    nonce,
+                                    block_template: block_template.clone(),
+                                    submitted_at,
+                                };
+                                self.record_found_block(&block, found_block);
+                            }
+                            if let Err(error) = self.submit_block(block).await {
+                                warn!("[PoolBlock] {}", error);
                             }
                         }
                     }
@@ -15,6 +15,16 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+    helpers::{
+        ConnectionStats,
+        HandshakeFailureReason,
+        MeteredStream,
+        PeerStream,
+        PeersConfig,
+        RecordedStream,
+        ServingScheduler,
+        SessionRecorder,
+    },
     ConnectionResult,
     Data,
     DisconnectReason,
@@ -28,6 +38,7 @@ use crate::{
     PeersRouter,
     ProverRequest,
     ProverRouter,
+    ServingMode,
 };
 use snarkos_environment::{
     helpers::{NodeType, State, Status},
@@ -40,8 +51,10 @@ use futures::SinkExt;
 use std::{
     collections::HashMap,
     net::SocketAddr,
+    sync::Arc,
     time::{Duration, Instant, SystemTime},
 };
+use time::OffsetDateTime;
 use tokio::{net::TcpStream, sync::mpsc, task, time::timeout};
 use tokio_stream::StreamExt;
 use tokio_util::codec::Framed;
@@ -63,12 +76,18 @@ pub(crate) struct Peer<N: Network, E: Environment> {
     node_type: NodeType,
     /// The node type of the peer.
     status: Status,
+    /// The blocks the peer serves, as advertised in its handshake.
+    serving_mode: ServingMode,
     /// The block header of the peer.
     block_header: BlockHeader<N>,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
-    /// The TCP socket that handles sending and receiving data with this peer.
-    outbound_socket: Framed<TcpStream, Message<N, E>>,
+    /// The timestamp of the last `Ping` sent to this peer, until its `Pong` is received.
+    last_ping_sent: Option<Instant>,
+    /// The traffic and latency of the connection to this peer.
+    stats: Arc<ConnectionStats>,
+    /// The socket that handles sending and receiving data with this peer.
+    outbound_socket: Framed<MeteredStream<RecordedStream<PeerStream>>, Message<N, E>>,
     /// The `outbound_handler` half of the MPSC message channel, used to receive messages from peers.
     /// When a message is received on this `OutboundHandler`, it will be written to the socket.
     outbound_handler: OutboundHandler<N, E>,
@@ -84,24 +103,32 @@ pub(crate) struct Peer<N: Network, E: Environment> {
 
 impl<N: Network, E: Environment> Peer<N, E> {
     /// Create a new instance of `Peer`.
+    #[allow(clippy::too_many_arguments)]
     async fn new(
-        stream: TcpStream,
+        stream: PeerStream,
         local_ip: SocketAddr,
         local_nonce: u64,
         peers_router: &PeersRouter<N, E>,
         ledger_reader: &LedgerReader<N>,
         connected_nonces: &[u64],
+        serving_mode: ServingMode,
+        inbound_config: Option<&PeersConfig>,
+        session_recorder: Option<SessionRecorder>,
     ) -> Result<Self> {
-        // Construct the socket.
-        let mut outbound_socket = Framed::new(stream, Message::<N, E>::PeerRequest);
+        // Construct the socket, counting its traffic and recording its inbound stream, if enabled, from the handshake onwards.
+        let stats = Arc::new(ConnectionStats::default());
+        let stream = RecordedStream::new(stream, session_recorder);
+        let mut outbound_socket = Framed::new(MeteredStream::new(stream, stats.clone()), Message::<N, E>::PeerRequest);
 
         // Perform the handshake before proceeding.
-        let (peer_ip, peer_nonce, node_type, status) = Peer::handshake(
+        let (peer_ip, peer_nonce, node_type, status, peer_serving_mode, clock_offset) = Peer::handshake(
             &mut outbound_socket,
             local_ip,
             local_nonce,
             ledger_reader.latest_cumulative_weight(),
             connected_nonces,
+            serving_mode,
+            inbound_config,
         )
         .await?;
 
@@ -116,13 +143,22 @@ impl<N: Network, E: Environment> Peer<N, E> {
         );
         trace!("Sending '{}' to {}", message.name(), peer_ip);
         outbound_socket.send(message).await?;
+        let last_ping_sent = Some(Instant::now());
 
         // Create a channel for this peer.
         let (outbound_router, outbound_handler) = mpsc::channel(1024);
 
         // Add an entry for this `Peer` in the connected peers.
         peers_router
-            .send(PeersRequest::PeerConnected(peer_ip, peer_nonce, outbound_router))
+            .send(PeersRequest::PeerConnected(
+                peer_ip,
+                peer_nonce,
+                node_type,
+                inbound_config.is_none(),
+                clock_offset,
+                stats.clone(),
+                outbound_router,
+            ))
             .await?;
 
         Ok(Peer {
@@ -130,8 +166,11 @@ impl<N: Network, E: Environment> Peer<N, E> {
             version: 0,
             node_type,
             status,
+            serving_mode: peer_serving_mode,
             block_header: N::genesis_block().header().clone(),
             last_seen: Instant::now(),
+            last_ping_sent,
+            stats,
             outbound_socket,
             outbound_handler,
             seen_inbound_blocks: Default::default(),
@@ -154,15 +193,20 @@ impl<N: Network, E: Environment> Peer<N, E> {
     }
 
     /// Performs the handshake protocol, returning the listener IP and nonce of the peer upon success.
+    /// The given `local_serving_mode` is advertised to the peer, and the peer's serving mode is returned,
+    /// along with the offset of the peer's clock from the local clock in seconds, if the peer advertised its time.
+    /// If the peer is connecting inbound, the given `inbound_config` is enforced on the peer.
     async fn handshake(
-        outbound_socket: &mut Framed<TcpStream, Message<N, E>>,
+        outbound_socket: &mut Framed<MeteredStream<RecordedStream<PeerStream>>, Message<N, E>>,
         local_ip: SocketAddr,
         local_nonce: u64,
         local_cumulative_weight: u128,
         connected_nonces: &[u64],
-    ) -> Result<(SocketAddr, u64, NodeType, Status)> {
+        local_serving_mode: ServingMode,
+        inbound_config: Option<&PeersConfig>,
+    ) -> Result<(SocketAddr, u64, NodeType, Status, ServingMode, Option<i64>)> {
         // Get the IP address of the peer.
-        let mut peer_ip = outbound_socket.get_ref().peer_addr()?;
+        let mut peer_ip = outbound_socket.get_ref().get_ref().get_ref().peer_addr()?;
 
         // Retrieve the genesis block header.
         let genesis_header = N::genesis_block().header();
@@ -176,12 +220,14 @@ impl<N: Network, E: Environment> Peer<N, E> {
             local_ip.port(),
             local_nonce,
             local_cumulative_weight,
+            local_serving_mode,
+            Some(OffsetDateTime::now_utc().unix_timestamp()),
         );
         trace!("Sending '{}-A' to {}", message.name(), peer_ip);
         outbound_socket.send(message).await?;
 
         // Wait for the counterparty challenge request to come in.
-        let (peer_nonce, node_type, status) = match outbound_socket.next().await {
+        let (peer_nonce, node_type, status, serving_mode, clock_offset) = match outbound_socket.next().await {
             Some(Ok(message)) => {
                 // Process the message.
                 trace!("Received '{}-B' from {}", message.name(), peer_ip);
@@ -194,7 +240,13 @@ impl<N: Network, E: Environment> Peer<N, E> {
                         listener_port,
                         peer_nonce,
                         peer_cumulative_weight,
+                        serving_mode,
+                        peer_timestamp,
                     ) => {
+                        // Measure the offset of the peer's clock, before any further round trips to the peer.
+                        let clock_offset =
+                            peer_timestamp.map(|timestamp| timestamp.saturating_sub(OffsetDateTime::now_utc().unix_timestamp()));
+
                         // Ensure the message protocol version is not outdated.
                         if version < E::MESSAGE_VERSION {
                             warn!("Dropping {} on version {} (outdated)", peer_ip, version);
@@ -203,7 +255,8 @@ impl<N: Network, E: Environment> Peer<N, E> {
                             let message = Message::Disconnect(DisconnectReason::OutdatedClientVersion);
                             outbound_socket.send(message).await?;
 
-                            bail!("Dropping {} on version {} (outdated)", peer_ip, version);
+                            let message = format!("Dropping {} on version {} (outdated)", peer_ip, version);
+                            return Err(handshake_failure(HandshakeFailureReason::OutdatedVersion(version), message));
                         }
                         // Ensure the maximum fork depth is correct.
                         if fork_depth != N::ALEO_MAXIMUM_FORK_DEPTH {
@@ -211,7 +264,21 @@ impl<N: Network, E: Environment> Peer<N, E> {
                             let message = Message::Disconnect(DisconnectReason::InvalidForkDepth);
                             outbound_socket.send(message).await?;
 
-                            bail!("Dropping {} for an incorrect maximum fork depth of {}", peer_ip, fork_depth);
+                            let message = format!("Dropping {} for an incorrect maximum fork depth of {}", peer_ip, fork_depth);
+                            return Err(handshake_failure(HandshakeFailureReason::WrongNetwork, message));
+                        }
+                        // If the peer is connecting inbound, ensure its node type is permitted to connect.
+                        if let Some(config) = inbound_config {
+                            // The port of an inbound peer is ephemeral, so trusted and sync nodes are matched by their IP alone.
+                            let exempt_nodes = E::trusted_nodes().iter().chain(E::sync_nodes().iter());
+                            if !config.is_inbound_peer_allowed(peer_ip, node_type, exempt_nodes) {
+                                // Send the disconnect message.
+                                let message = Message::Disconnect(DisconnectReason::NodeTypeNotPermitted);
+                                outbound_socket.send(message).await?;
+
+                                let message = format!("Dropping {} as inbound {} peers are not permitted", peer_ip, node_type);
+                                return Err(handshake_failure(HandshakeFailureReason::NodeTypeNotPermitted, message));
+                            }
                         }
                         // If this node is not a sync node and is syncing, the peer is a sync node, and this node is ahead, proceed to disconnect.
                         if E::NODE_TYPE != NodeType::Sync
@@ -270,10 +337,24 @@ impl<N: Network, E: Environment> Peer<N, E> {
                         let status = Status::new();
                         status.update(peer_status);
 
-                        (peer_nonce, node_type, status)
+                        (peer_nonce, node_type, status, serving_mode, clock_offset)
                     }
                     Message::Disconnect(reason) => {
-                        bail!("Peer {} disconnected for the following reason: {:?}", peer_ip, reason);
+                        let message = format!("Peer {} disconnected for the following reason: {:?}", peer_ip, reason);
+                        // Count the rejections of this node for being outdated or on another network, from the peer's perspective.
+                        match reason {
+                            DisconnectReason::OutdatedClientVersion => {
+                                let reason = HandshakeFailureReason::OutdatedVersion(E::MESSAGE_VERSION);
+                                return Err(handshake_failure(reason, message));
+                            }
+                            DisconnectReason::InvalidForkDepth => {
+                                return Err(handshake_failure(HandshakeFailureReason::WrongNetwork, message));
+                            }
+                            DisconnectReason::NodeTypeNotPermitted => {
+                                return Err(handshake_failure(HandshakeFailureReason::NodeTypeNotPermitted, message));
+                            }
+                            _ => bail!(message),
+                        }
                     }
                     message => {
                         bail!("Expected challenge request, received '{}' from {}", message.name(), peer_ip);
@@ -296,8 +377,11 @@ impl<N: Network, E: Environment> Peer<N, E> {
                         // Perform the deferred non-blocking deserialization of the block header.
                         let block_header = block_header.deserialize().await?;
                         match &block_header == genesis_header {
-                            true => Ok((peer_ip, peer_nonce, node_type, status)),
-                            false => Err(anyhow!("Challenge response from {} failed, received '{}'", peer_ip, block_header)),
+                            true => Ok((peer_ip, peer_nonce, node_type, status, serving_mode, clock_offset)),
+                            false => Err(handshake_failure(
+                                HandshakeFailureReason::BadGenesis,
+                                format!("Challenge response from {} failed, received '{}'", peer_ip, block_header),
+                            )),
                         }
                     }
                     Message::Disconnect(reason) => {
@@ -320,7 +404,7 @@ impl<N: Network, E: Environment> Peer<N, E> {
     /// A handler to process an individual peer.
     #[allow(clippy::too_many_arguments)]
     pub(super) async fn handler(
-        stream: TcpStream,
+        stream: PeerStream,
         local_ip: SocketAddr,
         local_nonce: u64,
         peers_router: &PeersRouter<N, E>,
@@ -329,6 +413,10 @@ impl<N: Network, E: Environment> Peer<N, E> {
         prover_router: ProverRouter<N>,
         operator_router: OperatorRouter<N>,
         connected_nonces: Vec<u64>,
+        serving_mode: ServingMode,
+        serving_scheduler: Arc<ServingScheduler>,
+        inbound_config: Option<PeersConfig>,
+        session_recorder: Option<SessionRecorder>,
         connection_result: Option<ConnectionResult>,
     ) {
         let peers_router = peers_router.clone();
@@ -337,7 +425,7 @@ impl<N: Network, E: Environment> Peer<N, E> {
         let peer_resource_id = E::resources().procure_id();
         E::resources().register_task(Some(peer_resource_id), task::spawn(async move {
             // Register our peer with state which internally sets up some channels.
-            let mut peer = match Peer::new(stream, local_ip, local_nonce, &peers_router, &ledger_reader, &connected_nonces).await {
+            let mut peer = match Peer::new(stream, local_ip, local_nonce, &peers_router, &ledger_reader, &connected_nonces, serving_mode, inbound_config.as_ref(), session_recorder).await {
                 Ok(peer) => {
                     // If the optional connection result router is given, report a successful connection result.
                     if let Some(router) = connection_result {
@@ -349,6 +437,11 @@ impl<N: Network, E: Environment> Peer<N, E> {
                 }
                 Err(error) => {
                     trace!("{}", error);
+                    // Report the reason of the failed handshake, to count the failures by reason.
+                    let reason = error.downcast_ref::<HandshakeFailureReason>().copied().unwrap_or(HandshakeFailureReason::Other);
+                    if let Err(error) = peers_router.send(PeersRequest::PeerHandshakeFailed(reason)).await {
+                        warn!("[PeerHandshakeFailed] {}", error);
+                    }
                     // If the optional connection result router is given, report a failed connection result.
                     if let Some(router) = connection_result {
                         if router.send(Err(error)).is_err() {
@@ -381,6 +474,8 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                     // Perform non-blocking serialisation of the block header.
                                     let serialized_header = Data::serialize(data.clone()).await.expect("Block header serialization is bugged");
                                     let _ = std::mem::replace(data, Data::Buffer(serialized_header));
+                                    // Measure the latency of the peer from this `Ping` to its `Pong`.
+                                    peer.last_ping_sent = Some(Instant::now());
 
                                     true
                                 }
@@ -437,10 +532,15 @@ impl<N: Network, E: Environment> Peer<N, E> {
                             };
                             // Send the message if it is ready.
                             if is_ready_to_send {
+                                // Drop the connection once a `Disconnect` is sent, instead of relying on the peer to close it.
+                                let is_disconnect = matches!(message, Message::Disconnect(_));
                                 // Route a message to the peer.
                                 if let Err(error) = peer.send(message).await {
                                     warn!("[OutboundRouter] {}", error);
                                 }
+                                if is_disconnect {
+                                    break;
+                                }
                             }
                         }
                     }
@@ -463,6 +563,15 @@ impl<N: Network, E: Environment> Peer<N, E> {
                             trace!("Received '{}' from {}", message.name(), peer_ip);
                             match message {
                                 Message::BlockRequest(start_block_height, end_block_height) => {
+                                    // Decline to serve full blocks if this node only serves block headers.
+                                    if serving_mode == ServingMode::HeadersOnly {
+                                        debug!("Declining 'BlockRequest' from {} as this node only serves block headers", peer_ip);
+                                        // The peer was told in the handshake, so disconnect it instead of leaving its request to time out.
+                                        if let Err(error) = peer.send(Message::Disconnect(DisconnectReason::BlocksNotServed)).await {
+                                            warn!("[Disconnect] {}", error);
+                                        }
+                                        break;
+                                    }
                                     // Ensure the request is within the accepted limits.
                                     let number_of_blocks = end_block_height.saturating_sub(start_block_height);
                                     if number_of_blocks > E::MAXIMUM_BLOCK_REQUEST {
@@ -473,21 +582,54 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                         }
                                         continue;
                                     }
-                                    // Retrieve the requested blocks.
-                                    let blocks = match ledger_reader.get_blocks(start_block_height, end_block_height) {
-                                        Ok(blocks) => blocks,
-                                        Err(error) => {
-                                            // Route a `Failure` to the ledger.
-                                            if let Err(error) = ledger_router.send(LedgerRequest::Failure(peer_ip, format!("{}", error))).await {
-                                                warn!("[Failure] {}", error);
-                                            }
-                                            continue;
+                                    // Ensure the requested blocks exist.
+                                    let latest_block_height = ledger_reader.latest_block_height();
+                                    if start_block_height > end_block_height || end_block_height > latest_block_height {
+                                        // Route a `Failure` to the ledger.
+                                        let failure = format!(
+                                            "Attempted to request blocks {} to {}, which do not exist",
+                                            start_block_height, end_block_height
+                                        );
+                                        if let Err(error) = ledger_router.send(LedgerRequest::Failure(peer_ip, failure)).await {
+                                            warn!("[Failure] {}", error);
                                         }
-                                    };
-                                    // Send a `BlockResponse` message for each block to the peer.
-                                    for block in blocks {
+                                        continue;
+                                    }
+                                    // Send a `BlockResponse` message for each block to the peer, retrieving each block as it is sent.
+                                    let mut blocks = ledger_reader.iter_blocks(start_block_height..=end_block_height);
+                                    loop {
+                                        // Wait for a fair share of the serving slots, before reading the next block.
+                                        let _permit = match serving_scheduler.acquire(peer_ip).await {
+                                            Ok(permit) => permit,
+                                            Err(error) => {
+                                                warn!("[BlockResponse] {}", error);
+                                                break;
+                                            }
+                                        };
+                                        let block = match blocks.next() {
+                                            Some(Ok(block)) => block,
+                                            Some(Err(error)) => {
+                                                // Route a `Failure` to the ledger.
+                                                let failure = format!("{}", error);
+                                                if let Err(error) = ledger_router.send(LedgerRequest::Failure(peer_ip, failure)).await {
+                                                    warn!("[Failure] {}", error);
+                                                }
+                                                break;
+                                            }
+                                            None => break,
+                                        };
                                         debug!("Sending 'BlockResponse {}' to {}", block.height(), peer_ip);
-                                        if let Err(error) = peer.outbound_socket.send(Message::BlockResponse(Data::Object(block))).await {
+                                        // Perform non-blocking serialization of the block, and account for its size.
+                                        let serialized_block = match Data::serialize(Data::Object(block)).await {
+                                            Ok(serialized_block) => serialized_block,
+                                            Err(error) => {
+                                                warn!("[BlockResponse] {}", error);
+                                                break;
+                                            }
+                                        };
+                                        serving_scheduler.record(peer_ip, serialized_block.len());
+                                        let message = Message::BlockResponse(Data::Buffer(serialized_block));
+                                        if let Err(error) = peer.outbound_socket.send(message).await {
                                             warn!("[BlockResponse] {}", error);
                                             break;
                                         }
@@ -525,6 +667,15 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                 },
                                 Message::Disconnect(reason) => {
                                     debug!("Peer {} disconnected for the following reason: {:?}", peer_ip, reason);
+                                    // Migrate to the backup pool given by a pool going into maintenance.
+                                    if let DisconnectReason::PoolMaintenance(backup_ip) = reason {
+                                        if E::NODE_TYPE == NodeType::Prover {
+                                            let request = ProverRequest::PoolMaintenance(peer_ip, backup_ip);
+                                            if let Err(error) = prover_router.send(request).await {
+                                                warn!("[PoolMaintenance] {}", error);
+                                            }
+                                        }
+                                    }
                                     break;
                                 },
                                 Message::PeerRequest => {
@@ -591,6 +742,11 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                         Ok(expected_block_hash) => Some(expected_block_hash != block_hash),
                                         Err(_) => None,
                                     };
+                                    // Update the peer information with the latest block height and fork status of the peer.
+                                    let request = PeersRequest::PeerUpdated(peer_ip, peer.version, peer.block_header.height(), is_fork);
+                                    if let Err(error) = peers_router.send(request).await {
+                                        warn!("[PeerUpdated] {}", error);
+                                    }
                                     // Send a `Pong` message to the peer.
                                     if let Err(error) = peer.send(Message::Pong(is_fork, Data::Object(ledger_reader.latest_block_locators()))).await {
                                         warn!("[Pong] {}", error);
@@ -611,10 +767,14 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                     }
                                 },
                                 Message::Pong(is_fork, block_locators) => {
+                                    // Update the latency of the peer, if this `Pong` answers a `Ping`.
+                                    if let Some(last_ping_sent) = peer.last_ping_sent.take() {
+                                        peer.stats.set_latency(last_ping_sent.elapsed());
+                                    }
                                     // Perform the deferred non-blocking deserialization of block locators.
                                     let request = match block_locators.deserialize().await {
                                         // Route the `Pong` to the ledger.
-                                        Ok(block_locators) => LedgerRequest::Pong(peer_ip, peer.node_type, peer.status.get(), is_fork, block_locators),
+                                        Ok(block_locators) => LedgerRequest::Pong(peer_ip, peer.node_type, peer.serving_mode, peer.status.get(), is_fork, block_locators),
                                         // Route the `Failure` to the ledger.
                                         Err(error) => LedgerRequest::Failure(peer_ip, format!("{}", error)),
                                     };
@@ -758,11 +918,14 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                         warn!("[PoolRequest] could not deserialize block template");
                                     }
                                 }
-                                Message::PoolResponse(address, nonce, proof) => {
+                                Message::PoolResponse(address, nonce, proof, timestamp) => {
                                     if E::NODE_TYPE != NodeType::Operator {
                                         trace!("Skipping 'PoolResponse' from {}", peer_ip);
-                                    } else if let Ok(proof) = proof.deserialize().await {
-                                        if let Err(error) = operator_router.send(OperatorRequest::PoolResponse(peer_ip, address, nonce, proof)).await {
+                                    } else if let Ok((proof, trailing_timestamp)) = proof.deserialize_with_trailing().await {
+                                        // Provers that predate timestamped shares do not stamp them.
+                                        let timestamp = timestamp.or(trailing_timestamp);
+                                        let request = OperatorRequest::PoolResponse(peer_ip, address, nonce, timestamp, proof);
+                                        if let Err(error) = operator_router.send(request).await {
                                             warn!("[PoolResponse] {}", error);
                                         }
                                     } else {
@@ -773,7 +936,7 @@ impl<N: Network, E: Environment> Peer<N, E> {
                                     if E::NODE_TYPE != NodeType::Operator {
                                         trace!("Skipping 'PoolBlock' from {}", peer_ip);
                                     } else if let Ok(proof) = proof.deserialize().await {
-                                        if let Err(error) = operator_router.send(OperatorRequest::PoolBlock(nonce, proof)).await {
+                                        if let Err(error) = operator_router.send(OperatorRequest::PoolBlock(peer_ip, nonce, proof)).await {
                                             warn!("[PoolBlock] {}", error);
                                         }
                                     } else {
@@ -804,3 +967,8 @@ impl<N: Network, E: Environment> Peer<N, E> {
         }));
     }
 }
+
+/// Returns the error of a handshake that failed for the given reason, with the given message.
+fn handshake_failure(reason: HandshakeFailureReason, message: String) -> anyhow::Error {
+    anyhow::Error::new(reason).context(message)
+}
@@ -14,8 +14,28 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Data, DisconnectReason, LedgerReader, LedgerRouter, Message, OperatorRouter, OutboundRouter, Peer, ProverRouter};
-use snarkos_environment::Environment;
+use crate::{
+    helpers::{
+        ConnectionStats,
+        HandshakeFailureReason,
+        HandshakeFailures,
+        PeerInfo,
+        PeerStream,
+        PeersConfig,
+        ServingScheduler,
+        SessionRecorder,
+    },
+    Data,
+    DisconnectReason,
+    LedgerReader,
+    LedgerRouter,
+    Message,
+    OperatorRouter,
+    OutboundRouter,
+    Peer,
+    ProverRouter,
+};
+use snarkos_environment::{helpers::next_request_id, Environment};
 use snarkvm::dpc::prelude::*;
 
 #[cfg(any(feature = "test", feature = "prometheus"))]
@@ -25,7 +45,7 @@ use anyhow::Result;
 use rand::{prelude::IteratorRandom, rngs::OsRng, thread_rng, Rng};
 use std::{
     collections::{HashMap, HashSet},
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
     time::{Duration, Instant, SystemTime},
 };
@@ -35,8 +55,12 @@ use tokio::{
     task,
     time::timeout,
 };
+use tracing::Instrument;
 use snarkos_environment::helpers::NodeType;
 
+/// The fraction of the eligible outbound peers rotated at each peer rotation, as a divisor.
+const PEER_ROTATION_DIVISOR: usize = 4;
+
 /// Shorthand for the parent half of the `Peers` message channel.
 pub type PeersRouter<N, E> = mpsc::Sender<PeersRequest<N, E>>;
 #[allow(unused)]
@@ -60,6 +84,8 @@ pub enum PeersRequest<N: Network, E: Environment> {
         ProverRouter<N>,
         ConnectionResult,
     ),
+    /// Disconnect := (peer_ip, is_restricted)
+    Disconnect(SocketAddr, bool),
     /// Heartbeat := (ledger_reader, ledger_router, operator_router, prover_router)
     Heartbeat(LedgerReader<N>, LedgerRouter<N>, OperatorRouter<N>, ProverRouter<N>),
     /// MessagePropagate := (peer_ip, message)
@@ -70,17 +96,29 @@ pub enum PeersRequest<N: Network, E: Environment> {
     MessageSend(SocketAddr, Message<N, E>),
     /// PeerConnecting := (stream, peer_ip, ledger_reader, ledger_router, operator_router, prover_router)
     PeerConnecting(
-        TcpStream,
+        PeerStream,
         SocketAddr,
         LedgerReader<N>,
         LedgerRouter<N>,
         OperatorRouter<N>,
         ProverRouter<N>,
     ),
-    /// PeerConnected := (peer_ip, peer_nonce, outbound_router)
-    PeerConnected(SocketAddr, u64, OutboundRouter<N, E>),
+    /// PeerConnected := (peer_ip, peer_nonce, node_type, is_outbound, clock_offset, connection_stats, outbound_router)
+    PeerConnected(
+        SocketAddr,
+        u64,
+        NodeType,
+        bool,
+        Option<i64>,
+        Arc<ConnectionStats>,
+        OutboundRouter<N, E>,
+    ),
+    /// PeerUpdated := (peer_ip, version, block_height, is_fork)
+    PeerUpdated(SocketAddr, u32, u32, Option<bool>),
     PeerIsProver(SocketAddr),
     PeerIsPoolServer(SocketAddr),
+    /// PeerHandshakeFailed := (reason)
+    PeerHandshakeFailed(HandshakeFailureReason),
     /// PeerDisconnected := (peer_ip)
     PeerDisconnected(SocketAddr),
     /// PeerRestricted := (peer_ip)
@@ -101,12 +139,24 @@ pub struct Peers<N: Network, E: Environment> {
     local_ip: SocketAddr,
     /// The local nonce for this node session.
     local_nonce: u64,
+    /// The runtime configuration of the peers.
+    config: PeersConfig,
     /// The map connected peer IPs to their nonce and outbound message router.
     connected_peers: RwLock<HashMap<SocketAddr, (u64, OutboundRouter<N, E>)>>,
     /// The set of candidate peer IPs.
     candidate_peers: RwLock<HashSet<SocketAddr>>,
     /// The set of restricted peer IPs.
     restricted_peers: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The map of banned IPs to the expiry of their ban, if the ban expires.
+    banned_ips: RwLock<HashMap<IpAddr, Option<Instant>>>,
+    /// The number of failed handshakes with peers, by reason.
+    handshake_failures: HandshakeFailures,
+    /// The map of connected peer IPs to their peer information.
+    peer_info: RwLock<HashMap<SocketAddr, PeerInfo>>,
+    /// The scheduler of the block responses served to syncing peers.
+    serving_scheduler: Arc<ServingScheduler>,
+    /// The timestamp of the last rotation of outbound peers.
+    last_peer_rotation: RwLock<Instant>,
     prover_peers: RwLock<HashSet<SocketAddr>>,
     poolserver_peers: RwLock<HashSet<SocketAddr>>,
     /// The map of peers to their first-seen port number, number of attempts, and timestamp of the last inbound connection request.
@@ -119,7 +169,7 @@ impl<N: Network, E: Environment> Peers<N, E> {
     ///
     /// Initializes a new instance of `Peers`.
     ///
-    pub async fn new(local_ip: SocketAddr, local_nonce: Option<u64>) -> Arc<Self> {
+    pub async fn new(local_ip: SocketAddr, local_nonce: Option<u64>, config: PeersConfig) -> Arc<Self> {
         // Initialize an mpsc channel for sending requests to the `Peers` struct.
         let (peers_router, mut peers_handler) = mpsc::channel(1024);
 
@@ -134,9 +184,18 @@ impl<N: Network, E: Environment> Peers<N, E> {
             peers_router,
             local_ip,
             local_nonce,
+            config,
             connected_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
+            banned_ips: Default::default(),
+            handshake_failures: Default::default(),
+            peer_info: Default::default(),
+            serving_scheduler: ServingScheduler::new(
+                E::MAXIMUM_CONCURRENT_BLOCK_RESPONSES,
+                Duration::from_secs(E::SERVING_COST_HALF_LIFE_IN_SECS),
+            ),
+            last_peer_rotation: RwLock::new(Instant::now()),
             prover_peers: Default::default(),
             poolserver_peers: Default::default(),
             seen_inbound_connections: Default::default(),
@@ -162,7 +221,7 @@ impl<N: Network, E: Environment> Peers<N, E> {
                             Some(resource_id),
                             task::spawn(async move {
                                 // Update the state of the peers.
-                                peers.update(request).await;
+                                peers.update(request).instrument(debug_span!("peers", request_id = next_request_id())).await;
 
                                 E::resources().deregister(resource_id);
                             }),
@@ -183,6 +242,11 @@ impl<N: Network, E: Environment> Peers<N, E> {
         self.peers_router.clone()
     }
 
+    /// Returns the IP address of this node.
+    pub fn local_ip(&self) -> SocketAddr {
+        self.local_ip
+    }
+
     ///
     /// Returns `true` if the node is connected to the given IP.
     ///
@@ -200,6 +264,55 @@ impl<N: Network, E: Environment> Peers<N, E> {
         }
     }
 
+    ///
+    /// Returns `true` if the given IP is banned.
+    ///
+    pub async fn is_banned(&self, ip: IpAddr) -> bool {
+        match self.banned_ips.read().await.get(&ip) {
+            Some(Some(expires_at)) => Instant::now() < *expires_at,
+            Some(None) => true,
+            None => false,
+        }
+    }
+
+    ///
+    /// Bans the given IP for the given duration, or indefinitely if `None`, dropping the connections
+    /// to the peers at the IP, and refusing any further connections to it until the ban is lifted.
+    ///
+    pub async fn ban(&self, ip: IpAddr, duration: Option<Duration>) {
+        {
+            let mut banned_ips = self.banned_ips.write().await;
+            // Remove the expired bans.
+            let now = Instant::now();
+            banned_ips.retain(|_, expires_at| !matches!(expires_at, Some(expires_at) if *expires_at <= now));
+            // A duration beyond the range of an `Instant` is as good as an indefinite ban.
+            banned_ips.insert(ip, duration.and_then(|duration| now.checked_add(duration)));
+        }
+
+        for peer_ip in self.connected_peers().await.into_iter().filter(|peer_ip| peer_ip.ip() == ip) {
+            debug!("Disconnecting from {} (banned)", peer_ip);
+            self.send(peer_ip, Message::Disconnect(DisconnectReason::NoReasonGiven)).await;
+        }
+    }
+
+    ///
+    /// Lifts the ban of the given IP, returning `true` if the IP was banned.
+    ///
+    pub async fn unban(&self, ip: IpAddr) -> bool {
+        match self.banned_ips.write().await.remove(&ip) {
+            Some(Some(expires_at)) => Instant::now() < expires_at,
+            Some(None) => true,
+            None => false,
+        }
+    }
+
+    ///
+    /// Returns the number of failed handshakes with peers, by reason.
+    ///
+    pub fn handshake_failures(&self) -> &HandshakeFailures {
+        &self.handshake_failures
+    }
+
     ///
     /// Returns the list of connected peers.
     ///
@@ -214,6 +327,29 @@ impl<N: Network, E: Environment> Peers<N, E> {
         self.candidate_peers.read().await.clone()
     }
 
+    ///
+    /// Returns the information of the connected peers.
+    ///
+    pub async fn peer_info(&self) -> HashMap<SocketAddr, PeerInfo> {
+        self.peer_info.read().await.clone()
+    }
+
+    ///
+    /// Returns the median offset of the clocks of the connected peers from the local clock in seconds,
+    /// and the number of peers it is measured over, or `None` if no connected peer advertised its time.
+    ///
+    pub async fn median_clock_offset(&self) -> Option<(i64, usize)> {
+        let mut clock_offsets: Vec<_> = self
+            .peer_info
+            .read()
+            .await
+            .values()
+            .filter_map(|peer_info| peer_info.clock_offset)
+            .collect();
+        clock_offsets.sort_unstable();
+        clock_offsets.get(clock_offsets.len() / 2).map(|clock_offset| (*clock_offset, clock_offsets.len()))
+    }
+
     ///
     /// Returns the set of connected sync nodes.
     ///
@@ -241,6 +377,60 @@ impl<N: Network, E: Environment> Peers<N, E> {
             .count()
     }
 
+    ///
+    /// Returns the number of connected sync and beacon nodes, which may occupy the reserved outbound slots.
+    ///
+    async fn number_of_connected_reserved_peers(&self) -> usize {
+        self.connected_peers
+            .read()
+            .await
+            .keys()
+            .filter(|addr| E::sync_nodes().contains(addr) || E::beacon_nodes().contains(addr))
+            .count()
+    }
+
+    ///
+    /// Returns the number of reserved outbound slots that are not yet occupied by sync or beacon nodes.
+    ///
+    async fn number_of_vacant_reserved_slots(&self) -> usize {
+        self.config.vacant_reserved_slots(self.number_of_connected_reserved_peers().await)
+    }
+
+    ///
+    /// Returns the number of connected peers, excluding the provers and pool servers.
+    ///
+    async fn number_of_connected_non_prover_peers(&self) -> usize {
+        let prover_peers = self.prover_peers.read().await;
+        let poolserver_peers = self.poolserver_peers.read().await;
+        self.connected_peers
+            .read()
+            .await
+            .keys()
+            .filter(|addr| !prover_peers.contains(addr) && !poolserver_peers.contains(addr))
+            .count()
+    }
+
+    ///
+    /// Returns the configuration enforced on the next inbound peer, or `None` if the maximum number of peers is reached.
+    /// The vacant reserved outbound slots are withheld from inbound peers. As the provers of an operator do not count
+    /// towards its maximum number of peers, an operator only accepts inbound provers once the remaining slots are taken.
+    ///
+    async fn inbound_config(&self) -> Option<PeersConfig> {
+        let number_of_connected_peers = match E::NODE_TYPE {
+            NodeType::Operator => self.number_of_connected_non_prover_peers().await,
+            _ => self.number_of_connected_peers().await,
+        };
+        let number_of_reserved_peers = self.number_of_connected_reserved_peers().await;
+        match self
+            .config
+            .is_inbound_saturated(number_of_connected_peers, number_of_reserved_peers, E::MAXIMUM_NUMBER_OF_PEERS)
+        {
+            false => Some(self.config.clone()),
+            true if E::NODE_TYPE == NodeType::Operator => self.config.saturated_inbound_config(),
+            true => None,
+        }
+    }
+
     ///
     /// Returns the number of connected peers.
     ///
@@ -287,12 +477,15 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 {
                     debug!("Skipping connection request to {} (attempted to self-connect)", peer_ip);
                 }
-                // Ensure the node does not surpass the maximum number of peer connections.
-                else if self
-                    .number_of_connected_peers()
-                    .await
-                    .saturating_sub(self.poolserver_peers.read().await.len())
-                    >= E::MAXIMUM_NUMBER_OF_PEERS
+                // Ensure the node does not surpass the maximum number of peer connections,
+                // unless the peer is a sync or beacon node and a reserved outbound slot is vacant.
+                else if !((E::sync_nodes().contains(&peer_ip) || E::beacon_nodes().contains(&peer_ip))
+                    && self.number_of_vacant_reserved_slots().await > 0)
+                    && self
+                        .number_of_connected_peers()
+                        .await
+                        .saturating_sub(self.poolserver_peers.read().await.len())
+                        >= E::MAXIMUM_NUMBER_OF_PEERS
                 {
                     debug!("Skipping connection request to {} (maximum peers reached)", peer_ip);
                 }
@@ -304,6 +497,10 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 else if self.is_restricted(peer_ip).await {
                     debug!("Skipping connection request to {} (restricted)", peer_ip);
                 }
+                // Ensure the peer is not banned.
+                else if self.is_banned(peer_ip.ip()).await {
+                    debug!("Skipping connection request to {} (banned)", peer_ip);
+                }
                 // Attempt to open a TCP stream.
                 else {
                     // Lock seen_outbound_connections for further processing.
@@ -323,7 +520,7 @@ impl<N: Network, E: Environment> Peers<N, E> {
                         drop(seen_outbound_connections);
 
                         // Initialize the peer handler.
-                        match timeout(Duration::from_millis(E::CONNECTION_TIMEOUT_IN_MILLIS), TcpStream::connect(peer_ip)).await {
+                        match timeout(Duration::from_millis(E::CONNECTION_TIMEOUT_IN_MILLIS), self.connect(peer_ip)).await {
                             Ok(stream) => match stream {
                                 Ok(stream) => {
                                     Peer::handler(
@@ -336,6 +533,10 @@ impl<N: Network, E: Environment> Peers<N, E> {
                                         prover_router,
                                         operator_router,
                                         self.connected_nonces().await,
+                                        self.config.serving_mode,
+                                        self.serving_scheduler.clone(),
+                                        None,
+                                        self.session_recorder(peer_ip),
                                         Some(connection_result),
                                     )
                                     .await
@@ -354,8 +555,11 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 }
             }
             PeersRequest::Heartbeat(ledger_reader, ledger_router, operator_router, prover_router) => {
-                // Obtain the number of connected peers.
-                let number_of_connected_peers = self.number_of_connected_peers().await;
+                // Obtain the number of connected peers, excluding the sync and beacon nodes in the reserved outbound slots.
+                let number_of_connected_peers = self
+                    .number_of_connected_peers()
+                    .await
+                    .saturating_sub(self.config.occupied_reserved_slots(self.number_of_connected_reserved_peers().await));
                 // Ensure the number of connected peers is below the maximum threshold.
                 if number_of_connected_peers.saturating_sub(self.poolserver_peers.read().await.len()) > E::MAXIMUM_NUMBER_OF_PEERS {
                     debug!("Exceeded maximum number of connected peers");
@@ -415,6 +619,10 @@ impl<N: Network, E: Environment> Peers<N, E> {
                     }
                 }
 
+                // Rotate a fraction of the outbound peers, if the rotation interval has elapsed.
+                self.rotate_outbound_peers(&ledger_reader, &ledger_router, &operator_router, &prover_router)
+                    .await;
+
                 // Ensure that the trusted nodes are connected.
                 if !E::trusted_nodes().is_empty() {
                     let connected_peers = self.connected_peers().await.into_iter().collect::<HashSet<_>>();
@@ -514,6 +722,15 @@ impl<N: Network, E: Environment> Peers<N, E> {
                     }
                 }
             }
+            PeersRequest::Disconnect(peer_ip, is_restricted) => {
+                debug!("Disconnecting from {} (requested)", peer_ip);
+                // Drop the connection, upon which the peer is removed from the ledger and the connected peers.
+                self.send(peer_ip, Message::Disconnect(DisconnectReason::NoReasonGiven)).await;
+                // Add an entry for this `Peer` in the restricted peers, to avoid reconnecting immediately.
+                if is_restricted {
+                    self.restricted_peers.write().await.insert(peer_ip, Instant::now());
+                }
+            }
             PeersRequest::MessagePropagate(sender, message) => {
                 self.propagate(sender, message).await;
             }
@@ -527,14 +744,16 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 self.send(sender, message).await;
             }
             PeersRequest::PeerConnecting(stream, peer_ip, ledger_reader, ledger_router, operator_router, prover_router) => {
+                // Retrieve the configuration enforced on the peer, if the node has room for it.
+                let inbound_config = self.inbound_config().await;
                 // Ensure the peer IP is not this node.
                 if peer_ip == self.local_ip
                     || (peer_ip.ip().is_unspecified() || peer_ip.ip().is_loopback()) && peer_ip.port() == self.local_ip.port()
                 {
                     debug!("Skipping connection request to {} (attempted to self-connect)", peer_ip);
                 }
-                // Ensure the node does not surpass the maximum number of peer connections.
-                else if E::NODE_TYPE != NodeType::Operator && self.number_of_connected_peers().await >= E::MAXIMUM_NUMBER_OF_PEERS {
+                // Ensure the node does not surpass the maximum number of peer connections, excluding the vacant reserved outbound slots.
+                else if inbound_config.is_none() {
                     debug!("Dropping connection request from {} (maximum peers reached)", peer_ip);
                 }
                 // Ensure the node is not already connected to this peer.
@@ -545,6 +764,11 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 else if self.is_restricted(peer_ip).await {
                     debug!("Dropping connection request from {} (restricted)", peer_ip);
                 }
+                // Ensure the peer is not banned.
+                else if self.is_banned(peer_ip.ip()).await {
+                    debug!("Dropping connection request from {} (banned)", peer_ip);
+                    self.record_handshake_failure(HandshakeFailureReason::Banned);
+                }
                 // Spawn a handler to be run asynchronously.
                 else {
                     // Sanitize the port from the peer, if it is a remote IP address.
@@ -598,17 +822,30 @@ impl<N: Network, E: Environment> Peers<N, E> {
                             prover_router,
                             operator_router,
                             self.connected_nonces().await,
+                            self.config.serving_mode,
+                            self.serving_scheduler.clone(),
+                            inbound_config,
+                            self.session_recorder(peer_ip),
                             None,
                         )
                         .await;
                     }
                 }
             }
-            PeersRequest::PeerConnected(peer_ip, peer_nonce, outbound) => {
+            PeersRequest::PeerConnected(peer_ip, peer_nonce, node_type, is_outbound, clock_offset, stats, outbound) => {
                 // Add an entry for this `Peer` in the connected peers.
                 self.connected_peers.write().await.insert(peer_ip, (peer_nonce, outbound));
+                // Add an entry for this `Peer` in the peer information.
+                let mut peer_info = PeerInfo::new(is_outbound, node_type, stats);
+                peer_info.clock_offset = clock_offset;
+                self.peer_info.write().await.insert(peer_ip, peer_info);
                 // Remove an entry for this `Peer` in the candidate peers, if it exists.
                 self.candidate_peers.write().await.remove(&peer_ip);
+                // Drop the connection if the peer was banned during its handshake.
+                if self.is_banned(peer_ip.ip()).await {
+                    debug!("Disconnecting from {} (banned)", peer_ip);
+                    self.send(peer_ip, Message::Disconnect(DisconnectReason::NoReasonGiven)).await;
+                }
 
                 #[cfg(any(feature = "test", feature = "prometheus"))]
                 {
@@ -618,6 +855,14 @@ impl<N: Network, E: Environment> Peers<N, E> {
                     metrics::gauge!(metrics::peers::CANDIDATE, number_of_candidate_peers as f64);
                 }
             }
+            PeersRequest::PeerUpdated(peer_ip, version, block_height, is_fork) => {
+                // Update the entry for this `Peer` in the peer information, if it exists.
+                if let Some(peer_info) = self.peer_info.write().await.get_mut(&peer_ip) {
+                    peer_info.version = version;
+                    peer_info.block_height = block_height;
+                    peer_info.is_fork = is_fork;
+                }
+            }
             PeersRequest::PeerIsProver(peer_ip) => {
                 // Add an entry for this `Peer` in the prover peers.
                 self.prover_peers.write().await.insert(peer_ip);
@@ -626,9 +871,14 @@ impl<N: Network, E: Environment> Peers<N, E> {
                 // Add an entry for this `Peer` in the pool server peers.
                 self.poolserver_peers.write().await.insert(peer_ip);
             }
+            PeersRequest::PeerHandshakeFailed(reason) => {
+                self.record_handshake_failure(reason);
+            }
             PeersRequest::PeerDisconnected(peer_ip) => {
                 // Remove an entry for this `Peer` in the connected peers, if it exists.
                 self.connected_peers.write().await.remove(&peer_ip);
+                // Remove an entry for this `Peer` in the peer information, if it exists.
+                self.peer_info.write().await.remove(&peer_ip);
                 // Add an entry for this `Peer` in the candidate peers.
                 self.candidate_peers.write().await.insert(peer_ip);
 
@@ -643,6 +893,8 @@ impl<N: Network, E: Environment> Peers<N, E> {
             PeersRequest::PeerRestricted(peer_ip) => {
                 // Remove an entry for this `Peer` in the connected peers, if it exists.
                 self.connected_peers.write().await.remove(&peer_ip);
+                // Remove an entry for this `Peer` in the peer information, if it exists.
+                self.peer_info.write().await.remove(&peer_ip);
                 // Add an entry for this `Peer` in the restricted peers.
                 self.restricted_peers.write().await.insert(peer_ip, Instant::now());
 
@@ -671,6 +923,96 @@ impl<N: Network, E: Environment> Peers<N, E> {
         }
     }
 
+    ///
+    /// Disconnects from the worst-scoring fraction of the outbound peers, and dials as many fresh candidate peers,
+    /// if the peer rotation interval has elapsed. Sync, beacon, and trusted nodes are never rotated.
+    ///
+    async fn rotate_outbound_peers(
+        &self,
+        ledger_reader: &LedgerReader<N>,
+        ledger_router: &LedgerRouter<N>,
+        operator_router: &OperatorRouter<N>,
+        prover_router: &ProverRouter<N>,
+    ) {
+        // Ensure peer rotation is enabled, and the rotation interval has elapsed.
+        let interval = match self.config.peer_rotation_interval {
+            Some(interval) => interval,
+            None => return,
+        };
+        {
+            let mut last_peer_rotation = self.last_peer_rotation.write().await;
+            if last_peer_rotation.elapsed() < interval {
+                return;
+            }
+            *last_peer_rotation = Instant::now();
+        }
+
+        // Determine the outbound peers that are eligible for rotation, ordered from the worst score.
+        let mut eligible_peers = self
+            .peer_info
+            .read()
+            .await
+            .iter()
+            .filter(|(peer_ip, peer_info)| {
+                peer_info.is_outbound
+                    && !E::sync_nodes().contains(peer_ip)
+                    && !E::beacon_nodes().contains(peer_ip)
+                    && !E::trusted_nodes().contains(peer_ip)
+            })
+            .map(|(peer_ip, peer_info)| (*peer_ip, peer_info.score()))
+            .collect::<Vec<_>>();
+        eligible_peers.sort_by_key(|(_, score)| *score);
+
+        // Determine the fresh candidate peers to dial in their place.
+        let num_rotated_peers = eligible_peers.len() / PEER_ROTATION_DIVISOR;
+        let mut fresh_peers = Vec::with_capacity(num_rotated_peers);
+        for peer_ip in self.candidate_peers().await {
+            if fresh_peers.len() >= num_rotated_peers {
+                break;
+            }
+            if !self.is_connected_to(peer_ip).await && !self.is_restricted(peer_ip).await {
+                fresh_peers.push(peer_ip);
+            }
+        }
+
+        // Only rotate as many peers as there are fresh candidates to replace them with.
+        for (peer_ip, score) in eligible_peers.into_iter().take(fresh_peers.len()) {
+            debug!("Disconnecting from {} (rotating outbound peers, score = {})", peer_ip, score);
+            self.send(peer_ip, Message::Disconnect(DisconnectReason::TooManyPeers)).await;
+            // Add an entry for this `Peer` in the restricted peers, to avoid reconnecting immediately.
+            self.restricted_peers.write().await.insert(peer_ip, Instant::now());
+        }
+
+        for peer_ip in fresh_peers {
+            trace!("Attempting connection to {} (rotating outbound peers)...", peer_ip);
+
+            // Initialize the connection process.
+            let (router, handler) = oneshot::channel();
+            let request = PeersRequest::Connect(
+                peer_ip,
+                ledger_reader.clone(),
+                ledger_router.clone(),
+                operator_router.clone(),
+                prover_router.clone(),
+                router,
+            );
+            if let Err(error) = self.peers_router.send(request).await {
+                warn!("Failed to transmit the request: '{}'", error);
+            }
+            // Do not wait for the result of each connection.
+            // Procure a resource id to register the task with, as it might be terminated at any point in time.
+            let resource_id = E::resources().procure_id();
+            E::resources().register_task(
+                Some(resource_id),
+                task::spawn(async move {
+                    let _ = handler.await;
+
+                    E::resources().deregister(resource_id);
+                }),
+            );
+        }
+    }
+
     ///
     /// Adds the given peer IPs to the set of candidate peers.
     ///
@@ -692,6 +1034,44 @@ impl<N: Network, E: Environment> Peers<N, E> {
         }
     }
 
+    ///
+    /// Opens a stream to the given peer, which is secured by TLS if the peer is the pool of this prover.
+    ///
+    async fn connect(&self, peer_ip: SocketAddr) -> Result<PeerStream> {
+        let stream = TcpStream::connect(peer_ip).await?;
+        match &self.config.pool_tls {
+            Some(pool_tls) if pool_tls.is_pool(peer_ip) => pool_tls.connect(stream).await,
+            _ => Ok(PeerStream::from(stream)),
+        }
+    }
+
+    ///
+    /// Returns a recorder of the inbound message stream of a new session with the given peer, if sessions are recorded.
+    ///
+    fn session_recorder(&self, peer_ip: SocketAddr) -> Option<SessionRecorder> {
+        let directory = self.config.session_directory.as_ref()?;
+        match SessionRecorder::create(directory, peer_ip) {
+            Ok(recorder) => {
+                debug!("Recording the session with {} in {}", peer_ip, recorder.path().display());
+                Some(recorder)
+            }
+            Err(error) => {
+                warn!("Failed to record the session with {}: {}", peer_ip, error);
+                None
+            }
+        }
+    }
+
+    ///
+    /// Records a failed handshake with a peer for the given reason.
+    ///
+    fn record_handshake_failure(&self, reason: HandshakeFailureReason) {
+        self.handshake_failures.record(reason);
+
+        #[cfg(any(feature = "test", feature = "prometheus"))]
+        metrics::increment_counter!(metrics::peers::HANDSHAKE_FAILURES, "reason" => reason.as_str());
+    }
+
     ///
     /// Sends the given message to specified peer.
     ///
@@ -14,27 +14,48 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Data, LedgerReader, LedgerRequest, LedgerRouter, Message, PeersRequest, PeersRouter};
+use crate::{
+    helpers::{PoolClient, PropagationPolicy, SharedMemoryPool},
+    Data,
+    LedgerReader,
+    LedgerRequest,
+    LedgerRouter,
+    Message,
+    Peers,
+    PeersRequest,
+    PeersRouter,
+};
 use snarkos_environment::{
-    helpers::{NodeType, State},
-    Environment
+    helpers::{next_request_id, NodeType},
+    Environment,
 };
 use snarkos_storage::{storage::Storage, ProverState};
-use snarkvm::dpc::{posw::PoSWProof, prelude::*};
+use snarkvm::dpc::prelude::*;
+
+#[cfg(feature = "prover")]
+use snarkos_environment::helpers::State;
+#[cfg(feature = "prover")]
+use snarkvm::dpc::posw::PoSWProof;
+
+#[cfg(any(feature = "test", feature = "prometheus"))]
+use snarkos_metrics as metrics;
 
 use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
 use rand::thread_rng;
+#[cfg(feature = "prover")]
 use rayon::{ThreadPool, ThreadPoolBuilder};
-use std::{
-    net::SocketAddr,
-    path::Path,
-    sync::{atomic::Ordering, Arc},
-    time::Duration,
-};
+use std::{net::SocketAddr, path::Path, sync::Arc, time::Duration};
+#[cfg(feature = "prover")]
+use std::sync::atomic::Ordering;
+use time::OffsetDateTime;
 use tokio::{
-    sync::{mpsc, oneshot, RwLock},
+    sync::{mpsc, oneshot, Notify},
     task,
 };
+#[cfg(feature = "prover")]
+use tokio::sync::RwLock;
+use tracing::Instrument;
 
 /// Shorthand for the parent half of the `Prover` message channel.
 pub type ProverRouter<N> = mpsc::Sender<ProverRequest<N>>;
@@ -53,7 +74,26 @@ pub enum ProverRequest<N: Network> {
     MemoryPoolClear(Option<Block<N>>),
     /// UnconfirmedTransaction := (peer_ip, transaction)
     UnconfirmedTransaction(SocketAddr, Transaction<N>),
+    /// LocalTransaction := (transaction), for a transaction submitted to this node, e.g. over RPC.
+    LocalTransaction(Transaction<N>),
     OperatorConnected(SocketAddr),
+    /// PoolMaintenance := (peer_ip, backup_ip)
+    PoolMaintenance(SocketAddr, Option<SocketAddr>),
+}
+
+/// The interval in seconds at which the prover checks whether the pool is reachable over TCP.
+const POOL_CONNECTION_CHECK_IN_SECS: u64 = 5;
+
+///
+/// The route over which the prover submits its shares to the pool operator.
+///
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "prover"), allow(dead_code))]
+enum PoolRoute<N: Network> {
+    /// Shares are sent as `PoolResponse` messages over the TCP connection to the operator.
+    Peer(SocketAddr),
+    /// Shares are submitted to the RPC server of the operator over HTTP(S).
+    Http(PoolClient<N>),
 }
 
 ///
@@ -65,20 +105,28 @@ pub struct Prover<N: Network, E: Environment> {
     state: Arc<ProverState<N>>,
     /// The Aleo address of the prover.
     address: Option<Address<N>>,
-    /// The IP address of the connected pool.
-    pool: Option<SocketAddr>,
+    /// The local address of this node.
+    local_ip: SocketAddr,
+    /// The IP address of the connected pool, which is replaced by its backup pool once it goes into maintenance.
+    pool: Mutex<Option<SocketAddr>>,
+    /// The notification of the pool being replaced by its backup pool.
+    pool_changed: Notify,
+    /// The propagation of the transactions submitted to this node.
+    propagation_policy: PropagationPolicy,
     /// The thread pool for the prover.
+    #[cfg(feature = "prover")]
     thread_pool: Arc<ThreadPool>,
     /// The prover router of the node.
     prover_router: ProverRouter<N>,
     /// The pool of unconfirmed transactions.
-    memory_pool: Arc<RwLock<MemoryPool<N>>>,
+    memory_pool: Arc<SharedMemoryPool<N>>,
     /// The peers router of the node.
     peers_router: PeersRouter<N, E>,
     /// The ledger state of the node.
     ledger_reader: LedgerReader<N>,
     /// The ledger router of the node.
     _ledger_router: LedgerRouter<N>,
+    #[cfg(feature = "prover")]
     current_block: Arc<RwLock<u32>>,
 }
 
@@ -87,8 +135,9 @@ impl<N: Network, E: Environment> Prover<N, E> {
     pub async fn open<S: Storage, P: AsRef<Path> + Copy>(
         path: P,
         address: Option<Address<N>>,
-        _local_ip: SocketAddr,
+        local_ip: SocketAddr,
         pool_ip: Option<SocketAddr>,
+        propagation_policy: PropagationPolicy,
         peers_router: PeersRouter<N, E>,
         ledger_reader: LedgerReader<N>,
         ledger_router: LedgerRouter<N>,
@@ -96,6 +145,7 @@ impl<N: Network, E: Environment> Prover<N, E> {
         // Initialize an mpsc channel for sending requests to the `Prover` struct.
         let (prover_router, mut prover_handler) = mpsc::channel(1024);
         // Initialize the prover thread pool.
+        #[cfg(feature = "prover")]
         let thread_pool = ThreadPoolBuilder::new()
             .stack_size(8 * 1024 * 1024)
             .num_threads(num_cpus::get())
@@ -105,13 +155,18 @@ impl<N: Network, E: Environment> Prover<N, E> {
         let prover = Arc::new(Self {
             state: Arc::new(ProverState::open::<S, P>(path, false)?),
             address,
-            pool: pool_ip,
+            local_ip,
+            pool: Mutex::new(pool_ip),
+            pool_changed: Notify::new(),
+            propagation_policy,
+            #[cfg(feature = "prover")]
             thread_pool: Arc::new(thread_pool),
             prover_router,
-            memory_pool: Arc::new(RwLock::new(MemoryPool::new())),
+            memory_pool: Arc::new(SharedMemoryPool::new()),
             peers_router,
             ledger_reader,
             _ledger_router: ledger_router,
+            #[cfg(feature = "prover")]
             current_block: Arc::new(RwLock::new(0)),
         });
 
@@ -127,7 +182,7 @@ impl<N: Network, E: Environment> Prover<N, E> {
                     // Asynchronously wait for a prover request.
                     while let Some(request) = prover_handler.recv().await {
                         // Update the state of the prover.
-                        prover.update(request).await;
+                        prover.update(request).instrument(debug_span!("prover", request_id = next_request_id())).await;
                     }
                 }),
             );
@@ -137,6 +192,7 @@ impl<N: Network, E: Environment> Prover<N, E> {
         }
 
         // terminator init
+        #[cfg(feature = "prover")]
         task::spawn(async move {
             let mut counter = false;
             loop {
@@ -162,8 +218,18 @@ impl<N: Network, E: Environment> Prover<N, E> {
         self.prover_router.clone()
     }
 
+    /// Returns the IP address of the pool of the prover, if any.
+    pub fn pool(&self) -> Option<SocketAddr> {
+        *self.pool.lock()
+    }
+
+    /// Waits until the pool of the prover is replaced by its backup pool.
+    pub async fn pool_changed(&self) {
+        self.pool_changed.notified().await
+    }
+
     /// Returns an instance of the memory pool.
-    pub fn memory_pool(&self) -> Arc<RwLock<MemoryPool<N>>> {
+    pub fn memory_pool(&self) -> Arc<SharedMemoryPool<N>> {
         self.memory_pool.clone()
     }
 
@@ -182,24 +248,39 @@ impl<N: Network, E: Environment> Prover<N, E> {
                 // Process the pool request message.
                 self.process_pool_request(operator_ip, share_difficulty, block_template).await;
             }
-            ProverRequest::MemoryPoolClear(block) => match block {
-                Some(block) => self.memory_pool.write().await.remove_transactions(block.transactions()),
-                None => *self.memory_pool.write().await = MemoryPool::new(),
-            },
+            ProverRequest::MemoryPoolClear(block) => {
+                match block {
+                    Some(block) => self.memory_pool.remove_transactions(block.transactions()),
+                    None => self.memory_pool.clear(),
+                }
+
+                #[cfg(any(feature = "test", feature = "prometheus"))]
+                metrics::gauge!(metrics::memory_pool::TRANSACTIONS, self.memory_pool.len() as f64);
+            }
             ProverRequest::UnconfirmedTransaction(peer_ip, transaction) => {
                 // Ensure the node is not peering.
                 if !E::status().is_peering() {
                     // Process the unconfirmed transaction.
-                    self.add_unconfirmed_transaction(peer_ip, transaction).await
+                    self.add_unconfirmed_transaction(peer_ip, transaction, PropagationPolicy::Immediate).await
+                }
+            }
+            ProverRequest::LocalTransaction(transaction) => {
+                // Ensure the node is not peering.
+                if !E::status().is_peering() {
+                    // Process the local transaction, as specified by the propagation policy of the node.
+                    self.add_unconfirmed_transaction(self.local_ip, transaction, self.propagation_policy).await
                 }
             }
             ProverRequest::OperatorConnected(peer_ip) => {
-                if let Some(pool_ip) = self.pool {
+                if let Some(pool_ip) = self.pool() {
                     if pool_ip == peer_ip {
                         self.send_pool_register().await;
                     }
                 }
             }
+            ProverRequest::PoolMaintenance(peer_ip, backup_ip) => {
+                self.migrate_pool(peer_ip, backup_ip);
+            }
         }
     }
 
@@ -209,7 +290,7 @@ impl<N: Network, E: Environment> Prover<N, E> {
     async fn send_pool_register(&self) {
         if E::NODE_TYPE == NodeType::Prover {
             if let Some(recipient) = self.address {
-                if let Some(pool_ip) = self.pool {
+                if let Some(pool_ip) = self.pool() {
                     // Proceed to register the prover to receive a block template.
                     let request = PeersRequest::MessageSend(pool_ip, Message::PoolRegister(recipient));
                     if let Err(error) = self.peers_router.send(request).await {
@@ -224,93 +305,126 @@ impl<N: Network, E: Environment> Prover<N, E> {
         }
     }
 
+    ///
+    /// Replaces the pool of the prover with the backup pool given by the pool, as it goes into maintenance.
+    /// Without a backup pool, the prover keeps reconnecting to its pool until it is back.
+    ///
+    fn migrate_pool(&self, peer_ip: SocketAddr, backup_ip: Option<SocketAddr>) {
+        if E::NODE_TYPE != NodeType::Prover {
+            return;
+        }
+        {
+            let mut pool = self.pool.lock();
+            // Ignore the maintenance of any pool other than the registered one.
+            if *pool != Some(peer_ip) {
+                return;
+            }
+            match backup_ip {
+                Some(backup_ip) if backup_ip != peer_ip => {
+                    warn!("Pool {} is going into maintenance, migrating to the backup pool {}", peer_ip, backup_ip);
+                    *pool = Some(backup_ip);
+                }
+                _ => {
+                    warn!("Pool {} is going into maintenance, without a backup pool to migrate to", peer_ip);
+                    return;
+                }
+            }
+        }
+        self.pool_changed.notify_one();
+    }
+
+    ///
+    /// Fetches jobs from and submits shares to the RPC server of the pool at the given URL,
+    /// whenever the pool cannot be reached over TCP, e.g. as the network blocks nonstandard ports.
+    /// The job requests are signed with the given private key, which must be the private key of the prover address.
+    ///
+    pub fn start_http_fallback(self: &Arc<Self>, pool_url: String, private_key: PrivateKey<N>, peers: Arc<Peers<N, E>>) -> Result<()> {
+        let recipient = match (self.address, self.pool()) {
+            (Some(recipient), Some(_)) => recipient,
+            _ => return Err(anyhow!("The HTTP fallback requires a prover address and a pool IP address")),
+        };
+        if Address::from_private_key(&private_key) != recipient {
+            return Err(anyhow!("The private key of the HTTP fallback is not the private key of prover {}", recipient));
+        }
+        let client = PoolClient::new(pool_url)?;
+
+        let prover = self.clone();
+        E::resources().register_task(
+            None, // No need to provide an id, as the task will run indefinitely.
+            task::spawn(async move {
+                let mut known_block_height = None;
+                loop {
+                    // Prefer the TCP connection to the pool, whenever it is established.
+                    let pool_ip = prover.pool().expect("The pool of a prover is never removed");
+                    if peers.is_connected_to(pool_ip).await {
+                        if known_block_height.take().is_some() {
+                            info!("Pool {} is reachable over TCP again, stopping the HTTP fallback", pool_ip);
+                        }
+                        tokio::time::sleep(Duration::from_secs(POOL_CONNECTION_CHECK_IN_SECS)).await;
+                        continue;
+                    }
+
+                    // Long-poll the operator for a new job.
+                    match client.get_job(&private_key, known_block_height).await {
+                        Ok(Some((share_difficulty, block_template))) => {
+                            // Once the long-poll expires, the operator returns the known job, which is already being proven.
+                            let block_height = block_template.block_height();
+                            if known_block_height == Some(block_height) {
+                                continue;
+                            }
+                            if known_block_height.is_none() {
+                                info!("Pool {} is unreachable over TCP, fetching jobs over HTTP", pool_ip);
+                            }
+                            known_block_height = Some(block_height);
+
+                            // Refuse work that is stale or bogus, as any share for it would be wasted.
+                            match prover.validate_block_template(share_difficulty, &block_template) {
+                                Ok(()) => {
+                                    prover
+                                        .start_proving(PoolRoute::Http(client.clone()), recipient, share_difficulty, block_template)
+                                        .await
+                                }
+                                Err(error) => warn!(
+                                    "[PoolRequest] Refusing the block template for block {} over HTTP: {}",
+                                    block_height, error
+                                ),
+                            }
+                        }
+                        Ok(None) => tokio::time::sleep(Duration::from_secs(POOL_CONNECTION_CHECK_IN_SECS)).await,
+                        Err(error) => {
+                            warn!("[PoolRequest] {}", error);
+                            tokio::time::sleep(Duration::from_secs(POOL_CONNECTION_CHECK_IN_SECS)).await;
+                        }
+                    }
+                }
+            }),
+        );
+
+        Ok(())
+    }
+
     ///
     /// Processes a `PoolRequest` message from a pool operator.
     ///
     async fn process_pool_request(&self, operator_ip: SocketAddr, share_difficulty: u64, block_template: BlockTemplate<N>) {
         if E::NODE_TYPE == NodeType::Prover {
             if let Some(recipient) = self.address {
-                if let Some(pool_ip) = self.pool {
+                if let Some(pool_ip) = self.pool() {
                     // Refuse work from any pool other than the registered one.
                     if pool_ip == operator_ip {
-                        let thread_pool = self.thread_pool.clone();
-                        let peers_router = self.peers_router.clone();
-                        let block_height = block_template.block_height();
-                        let current_block = self.current_block.clone();
-                        *(current_block.write().await) = block_height;
-                        task::spawn(async move {
-                            info!("[PoolRequest] Received a block template {} from the pool operator", block_height);
-                            E::prover_terminator().store(true, Ordering::SeqCst);
-                            while E::prover_terminator().load(Ordering::SeqCst) {
-                                // Wait until the prover terminator is set to false.
-                                tokio::time::sleep(Duration::from_millis(50)).await;
+                        // Refuse work that is stale or bogus, as any share for it would be wasted.
+                        match self.validate_block_template(share_difficulty, &block_template) {
+                            Ok(()) => {
+                                self.start_proving(PoolRoute::Peer(operator_ip), recipient, share_difficulty, block_template)
+                                    .await
                             }
-                            trace!("[PoolRequest] Starting to process the block template for block {}", block_height);
-
-                            // Set the status to `Mining`.
-                            E::status().update(State::Mining);
-
-                            while !E::prover_terminator().load(Ordering::SeqCst) {
-                                let block_template = block_template.clone();
-                                let block_height = block_template.block_height();
-                                let thread_pool = thread_pool.clone();
-                                if block_height != *(current_block.try_read().unwrap()) {
-                                    info!(
-                                        "Terminating stale work: current {} latest {}",
-                                        block_height,
-                                        *(current_block.try_read().unwrap())
-                                    );
-                                    break;
-                                }
-
-                                let result = task::spawn_blocking(move || {
-                                    thread_pool.install(move || {
-                                        loop {
-                                            let block_header = BlockHeader::mine_once_unchecked(
-                                                &block_template,
-                                                E::prover_terminator(),
-                                                &mut thread_rng(),
-                                            )?;
-
-                                            // Ensure the share difficulty target is met.
-                                            if N::posw().verify(
-                                                block_header.height(),
-                                                share_difficulty,
-                                                &[*block_header.to_header_root().unwrap(), *block_header.nonce()],
-                                                block_header.proof(),
-                                            ) {
-                                                return Ok::<(N::PoSWNonce, PoSWProof<N>, u64), anyhow::Error>((
-                                                    block_header.nonce(),
-                                                    block_header.proof().clone(),
-                                                    block_header.proof().to_proof_difficulty()?,
-                                                ));
-                                            }
-                                        }
-                                    })
-                                })
-                                .await;
-
-                                match result {
-                                    Ok(Ok((nonce, proof, proof_difficulty))) => {
-                                        info!(
-                                            "Prover successfully mined a share for unconfirmed block {} with proof difficulty of {}",
-                                            block_height, proof_difficulty
-                                        );
-
-                                        // Send a `PoolResponse` to the operator.
-                                        let message = Message::PoolResponse(recipient, nonce, Data::Object(proof));
-                                        if let Err(error) = peers_router.send(PeersRequest::MessageSend(operator_ip, message)).await {
-                                            warn!("[PoolResponse] {}", error);
-                                        }
-                                    }
-                                    Ok(Err(error)) => trace!("{}", error),
-                                    Err(error) => trace!("{}", anyhow!("Failed to mine the next block {}", error)),
-                                }
-                            }
-
-                            E::status().update(State::Ready);
-                            E::prover_terminator().store(false, Ordering::SeqCst);
-                        });
+                            Err(error) => warn!(
+                                "[PoolRequest] Refusing the block template for block {} from {}: {}",
+                                block_template.block_height(),
+                                operator_ip,
+                                error
+                            ),
+                        }
                     }
                 } else {
                     error!("Missing pool IP address. Please specify a pool IP address in order to run the prover");
@@ -322,21 +436,221 @@ impl<N: Network, E: Environment> Prover<N, E> {
     }
 
     ///
-    /// Adds the given unconfirmed transaction to the memory pool.
+    /// Ensures the given block template is sane, and extends the ledger of this node if it is synced up to the template.
+    ///
+    fn validate_block_template(&self, share_difficulty: u64, block_template: &BlockTemplate<N>) -> Result<()> {
+        // Ensure the targets are satisfiable.
+        if block_template.difficulty_target() == 0 || share_difficulty == 0 {
+            return Err(anyhow!("The block template has an unsatisfiable difficulty target"));
+        }
+
+        // Ensure the timestamp is within the declared time limit.
+        let timestamp_policy = self.ledger_reader.timestamp_policy();
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if block_template.block_timestamp() > now.saturating_add(timestamp_policy.future_time_limit_in_secs) {
+            return Err(anyhow!("The block template timestamp {} is in the future", block_template.block_timestamp()));
+        }
+
+        // Ensure the block template is not for a block the ledger already contains.
+        let block_height = block_template.block_height();
+        let latest_block = self.ledger_reader.latest_block();
+        if block_height <= latest_block.height() {
+            return Err(anyhow!("The block template is stale, as the ledger is at block {}", latest_block.height()));
+        }
+
+        // If the ledger is synced up to the block template, ensure the block template extends it.
+        // Otherwise, this node is behind the operator, and the block template cannot be checked further.
+        if block_height == latest_block.height() + 1 {
+            if block_template.previous_block_hash() != latest_block.hash() {
+                return Err(anyhow!(
+                    "The previous block hash {} does not match the latest block {}",
+                    block_template.previous_block_hash(),
+                    latest_block.hash()
+                ));
+            }
+            let median_time_past = self.ledger_reader.latest_median_time_past()?;
+            if block_template.block_timestamp() <= median_time_past {
+                return Err(anyhow!("The block template timestamp is not after the median time past {}", median_time_past));
+            }
+            let expected_difficulty_target = self.ledger_reader.get_difficulty_target(block_template.block_timestamp(), block_height)?;
+            if block_template.difficulty_target() != expected_difficulty_target {
+                return Err(anyhow!(
+                    "The difficulty target {} does not match the expected difficulty target {}",
+                    block_template.difficulty_target(),
+                    expected_difficulty_target
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Starts proving on the given block template, sending each share that meets the share difficulty to the operator.
+    ///
+    #[cfg(feature = "prover")]
+    async fn start_proving(&self, route: PoolRoute<N>, recipient: Address<N>, share_difficulty: u64, block_template: BlockTemplate<N>) {
+        let thread_pool = self.thread_pool.clone();
+        let peers_router = self.peers_router.clone();
+        let block_height = block_template.block_height();
+        let current_block = self.current_block.clone();
+        *(current_block.write().await) = block_height;
+        let ledger_reader = self.ledger_reader.clone();
+        task::spawn(async move {
+            info!("[PoolRequest] Received a block template {} from the pool operator", block_height);
+            E::prover_terminator().store(true, Ordering::SeqCst);
+            while E::prover_terminator().load(Ordering::SeqCst) {
+                // Wait until the prover terminator is set to false.
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            trace!("[PoolRequest] Starting to process the block template for block {}", block_height);
+
+            // Set the status to `Mining`.
+            E::status().update(State::Mining);
+
+            // Stop proving once the ledger advances to the block of the template, as any further share would be stale.
+            let stale_work_watcher = {
+                let current_block = current_block.clone();
+                task::spawn(async move {
+                    loop {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        if ledger_reader.latest_block_height() >= block_height && *current_block.read().await == block_height {
+                            info!("Terminating stale work for block {}, as the ledger has advanced", block_height);
+                            E::prover_terminator().store(true, Ordering::SeqCst);
+                            break;
+                        }
+                    }
+                })
+            };
+
+            while !E::prover_terminator().load(Ordering::SeqCst) {
+                let block_template = block_template.clone();
+                let block_height = block_template.block_height();
+                let thread_pool = thread_pool.clone();
+                if block_height != *(current_block.try_read().unwrap()) {
+                    info!(
+                        "Terminating stale work: current {} latest {}",
+                        block_height,
+                        *(current_block.try_read().unwrap())
+                    );
+                    break;
+                }
+
+                let result = task::spawn_blocking(move || {
+                    thread_pool.install(move || {
+                        loop {
+                            let block_header = BlockHeader::mine_once_unchecked(
+                                &block_template,
+                                E::prover_terminator(),
+                                &mut thread_rng(),
+                            )?;
+
+                            // Ensure the share difficulty target is met.
+                            if N::posw().verify(
+                                block_header.height(),
+                                share_difficulty,
+                                &[*block_header.to_header_root().unwrap(), *block_header.nonce()],
+                                block_header.proof(),
+                            ) {
+                                return Ok::<(N::PoSWNonce, PoSWProof<N>, u64), anyhow::Error>((
+                                    block_header.nonce(),
+                                    block_header.proof().clone(),
+                                    block_header.proof().to_proof_difficulty()?,
+                                ));
+                            }
+                        }
+                    })
+                })
+                .await;
+
+                match result {
+                    Ok(Ok((nonce, proof, proof_difficulty))) => {
+                        info!(
+                            "Prover successfully mined a share for unconfirmed block {} with proof difficulty of {}",
+                            block_height, proof_difficulty
+                        );
+
+                        // Stamp the share, so the operator may reject it if it is held back or replayed.
+                        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+                        match &route {
+                            PoolRoute::Peer(operator_ip) => {
+                                // Send a `PoolResponse` to the operator.
+                                let message = Message::PoolResponse(recipient, nonce, Data::Object(proof), Some(timestamp));
+                                if let Err(error) = peers_router.send(PeersRequest::MessageSend(*operator_ip, message)).await {
+                                    warn!("[PoolResponse] {}", error);
+                                }
+                            }
+                            PoolRoute::Http(client) => {
+                                // Submit the share to the RPC server of the operator.
+                                if let Err(error) = client.submit_share(recipient, nonce, timestamp, &proof).await {
+                                    warn!("[SubmitShare] {}", error);
+                                }
+                            }
+                        }
+                    }
+                    Ok(Err(error)) => trace!("{}", error),
+                    Err(error) => trace!("{}", anyhow!("Failed to mine the next block {}", error)),
+                }
+            }
+
+            stale_work_watcher.abort();
+            E::status().update(State::Ready);
+            E::prover_terminator().store(false, Ordering::SeqCst);
+        });
+    }
+
+    ///
+    /// Refuses to prove, as this node was built without prover support.
+    ///
+    #[cfg(not(feature = "prover"))]
+    async fn start_proving(&self, _route: PoolRoute<N>, _recipient: Address<N>, _share_difficulty: u64, _block_template: BlockTemplate<N>) {
+        error!("This node was built without prover support. Please rebuild it with '--features prover'");
+    }
+
     ///
-    async fn add_unconfirmed_transaction(&self, peer_ip: SocketAddr, transaction: Transaction<N>) {
+    /// Adds the given unconfirmed transaction to the memory pool, and propagates it as specified by the given policy.
+    ///
+    async fn add_unconfirmed_transaction(&self, peer_ip: SocketAddr, transaction: Transaction<N>, propagation_policy: PropagationPolicy) {
         // Process the unconfirmed transaction.
         trace!("Received unconfirmed transaction {} from {}", transaction.transaction_id(), peer_ip);
         // Ensure the unconfirmed transaction is new.
         if let Ok(false) = self.ledger_reader.contains_transaction(&transaction.transaction_id()) {
+            // Ensure the unconfirmed transaction is admitted by the memory pool policy, unless it is local priority.
+            if !self.memory_pool.is_local_priority(&transaction.transaction_id()) {
+                if let Err(rejection) = self.memory_pool.check_policy(&transaction) {
+                    debug!("Rejecting unconfirmed transaction {} from {}, as it {}", transaction.transaction_id(), peer_ip, rejection);
+                    return;
+                }
+            }
             debug!("Adding unconfirmed transaction {} to memory pool", transaction.transaction_id());
             // Attempt to add the unconfirmed transaction to the memory pool.
-            match self.memory_pool.write().await.add_transaction(&transaction) {
+            match self.memory_pool.add_transaction(&transaction) {
                 Ok(()) => {
-                    // Upon success, propagate the unconfirmed transaction to the connected peers.
+                    #[cfg(any(feature = "test", feature = "prometheus"))]
+                    metrics::gauge!(metrics::memory_pool::TRANSACTIONS, self.memory_pool.len() as f64);
+
+                    // Upon success, propagate the unconfirmed transaction to the connected peers, unless it is private.
+                    let delay = match propagation_policy.to_delay(&mut thread_rng()) {
+                        Some(delay) => delay,
+                        None => {
+                            debug!("Keeping unconfirmed transaction {} private", transaction.transaction_id());
+                            return;
+                        }
+                    };
                     let request = PeersRequest::MessagePropagate(peer_ip, Message::UnconfirmedTransaction(Data::Object(transaction)));
-                    if let Err(error) = self.peers_router.send(request).await {
-                        warn!("[UnconfirmedTransaction] {}", error);
+                    if delay.is_zero() {
+                        if let Err(error) = self.peers_router.send(request).await {
+                            warn!("[UnconfirmedTransaction] {}", error);
+                        }
+                    } else {
+                        // Trickle the unconfirmed transaction to the connected peers.
+                        let peers_router = self.peers_router.clone();
+                        task::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            if let Err(error) = peers_router.send(request).await {
+                                warn!("[UnconfirmedTransaction] {}", error);
+                            }
+                        });
                     }
                 }
                 Err(error) => error!("{}", error),
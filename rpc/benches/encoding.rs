@@ -0,0 +1,51 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_environment::CurrentNetwork;
+use snarkvm::dpc::prelude::*;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use jsonrpsee::types::{Id, Response};
+use serde::Serialize;
+
+/// Encodes the given result as a JSON-RPC response, as the RPC server does for each request.
+fn encode_response<T: Serialize>(result: T) -> String {
+    serde_json::to_string(&Response::new(result, Id::Number(1))).expect("Failed to encode an RPC response")
+}
+
+fn encoding(c: &mut Criterion) {
+    let block = CurrentNetwork::genesis_block();
+    let block_hashes = vec![block.hash(); 100];
+
+    c.bench_function("encode_get_block", |b| {
+        b.iter(|| encode_response(block));
+    });
+
+    c.bench_function("encode_get_block_header", |b| {
+        b.iter(|| encode_response(block.header()));
+    });
+
+    c.bench_function("encode_get_block_transactions", |b| {
+        b.iter(|| encode_response(block.transactions()));
+    });
+
+    c.bench_function("encode_get_block_hashes", |b| {
+        b.iter(|| encode_response(&block_hashes));
+    });
+}
+
+criterion_group!(benches, encoding);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversions between amounts of gates, the integer unit of `AleoAmount`, and decimal strings of credits.
+//!
+//! RPC responses return each amount both as an integer number of gates and, in a sibling field
+//! suffixed with `_credits`, as a decimal string of credits with exactly `CREDIT_DECIMALS` decimal places.
+
+use snarkvm::dpc::AleoAmount;
+
+use anyhow::{anyhow, Result};
+
+/// The number of decimal places of an amount of credits.
+pub const CREDIT_DECIMALS: usize = 6;
+/// The number of gates in one credit.
+pub const GATES_PER_CREDIT: i64 = 1_000_000;
+
+/// Returns the given amount as a decimal string of credits with exactly `CREDIT_DECIMALS` decimal places, e.g. `-1.500000`.
+pub fn format_credits(amount: AleoAmount) -> String {
+    let sign = if amount.is_negative() { "-" } else { "" };
+    let gates = amount.0.unsigned_abs();
+    let gates_per_credit = GATES_PER_CREDIT as u64;
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        gates / gates_per_credit,
+        gates % gates_per_credit,
+        width = CREDIT_DECIMALS
+    )
+}
+
+/// Returns the amount of the given decimal string of credits, which may have at most `CREDIT_DECIMALS` decimal places.
+pub fn parse_credits(credits: &str) -> Result<AleoAmount> {
+    let (is_negative, digits) = match credits.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, credits),
+    };
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) if !fraction.is_empty() => (whole, fraction),
+        Some(_) => return Err(anyhow!("Invalid amount of credits '{}'", credits)),
+        None => (digits, ""),
+    };
+
+    let is_digits = |string: &str| string.bytes().all(|byte| byte.is_ascii_digit());
+    if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) || fraction.len() > CREDIT_DECIMALS {
+        return Err(anyhow!("Invalid amount of credits '{}'", credits));
+    }
+
+    // Pad the fraction to `CREDIT_DECIMALS` digits, so that it is a number of gates.
+    let fraction = format!("{:0<width$}", fraction, width = CREDIT_DECIMALS);
+    let gates = whole
+        .parse::<i64>()
+        .ok()
+        .and_then(|whole| whole.checked_mul(GATES_PER_CREDIT))
+        .and_then(|gates| gates.checked_add(fraction.parse::<i64>().ok()?))
+        .ok_or_else(|| anyhow!("The amount of credits '{}' is out of range", credits))?;
+
+    Ok(AleoAmount(if is_negative { -gates } else { gates }))
+}
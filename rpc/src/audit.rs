@@ -0,0 +1,223 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The audit log of the calls to the guarded RPC methods, so operators may reconstruct who called each of them.
+
+use crate::{RpcAuth, RpcCredentials};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    net::IpAddr,
+    os::unix::net::UnixDatagram,
+    path::Path,
+    sync::Mutex,
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// The RPC methods which require credentials, whose calls are audited.
+/// The calls to other methods are also audited if they present credentials, as for the hints of `getblocktemplate`.
+pub const GUARDED_METHODS: [&str; 19] = [
+    "banpeer",
+    "cancellease",
+    "completepayout",
+    "connect",
+    "createlease",
+    "decryptrecord",
+    "disconnect",
+    "getapiusage",
+    "getpendingpayouts",
+    "getunspentrecords",
+    "maintenance",
+    "profilecpu",
+    "profileheap",
+    "replaypayouts",
+    "restart",
+    "selectpayoutrecords",
+    "setloglevel",
+    "stop",
+    "unbanpeer",
+];
+
+/// The name of the sink which writes the audit records to the local syslog daemon.
+const SYSLOG_SINK: &str = "syslog";
+/// The path of the socket of the local syslog daemon.
+const SYSLOG_SOCKET_PATH: &str = "/dev/log";
+/// The priority of the audit records in the syslog, with the `auth` facility and the `notice` severity.
+const SYSLOG_PRIORITY: u8 = 4 * 8 + 5;
+
+///
+/// The destination of the audit records, each of which is written as a line of JSON.
+///
+#[derive(Debug)]
+enum RpcAuditSink {
+    /// Appends the audit records to a file.
+    File(Mutex<File>),
+    /// Sends the audit records to the local syslog daemon.
+    Syslog(UnixDatagram),
+}
+
+///
+/// The audit record of a call to a guarded RPC method.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RpcAuditRecord {
+    /// The UNIX timestamp at which the call was answered.
+    pub timestamp: i64,
+    /// The method of the call.
+    pub method: String,
+    /// The IP of the caller.
+    pub ip: IpAddr,
+    /// The username of the credentials of the call, if they are valid, which is the name of an API key or of the node.
+    pub identity: Option<String>,
+    /// The SHA-256 digest of the parameters of the call, without the credentials, in hex.
+    pub params_digest: String,
+    /// The number of milliseconds the call took to answer.
+    pub latency_in_ms: u64,
+    /// The HTTP status of the response to the call.
+    pub status: u16,
+    /// `0` if the call succeeded, its JSON-RPC error code if it failed, or `None` if it was answered without a JSON-RPC response.
+    pub result_code: Option<i64>,
+}
+
+///
+/// The audit log of the calls to the guarded RPC methods, written to a file or to the syslog.
+///
+#[derive(Debug)]
+pub struct RpcAuditLog {
+    /// The credentials of the RPC server, to identify the caller of each call.
+    auth: RpcAuth,
+    /// The destination of the audit records.
+    sink: RpcAuditSink,
+}
+
+impl RpcAuditLog {
+    ///
+    /// Opens the audit log with the given sink, which is `syslog`, or the path of a file which the records are appended to.
+    /// The callers presenting valid credentials of the given authorization are identified by their username.
+    ///
+    pub fn open(sink: &str, auth: RpcAuth) -> Result<Self> {
+        let sink = match sink {
+            SYSLOG_SINK => {
+                let socket = UnixDatagram::unbound()?;
+                socket
+                    .connect(SYSLOG_SOCKET_PATH)
+                    .map_err(|error| anyhow!("Failed to connect to the syslog at {}: {}", SYSLOG_SOCKET_PATH, error))?;
+                RpcAuditSink::Syslog(socket)
+            }
+            path => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(Path::new(path))
+                    .map_err(|error| anyhow!("Failed to open the RPC audit log at {}: {}", path, error))?;
+                RpcAuditSink::File(Mutex::new(file))
+            }
+        };
+        Ok(Self { auth, sink })
+    }
+
+    ///
+    /// Records the calls to the guarded methods in the given request body of the given client IP,
+    /// which was answered with the given HTTP status and body after the given latency.
+    ///
+    pub fn record_request(&self, ip: IpAddr, request: &[u8], status: u16, response: &[u8], latency: Duration) {
+        let calls = match serde_json::from_slice::<Value>(request) {
+            Ok(Value::Array(batch)) => batch,
+            Ok(call) => vec![call],
+            Err(_) => return,
+        };
+        let responses = match serde_json::from_slice::<Value>(response) {
+            Ok(Value::Array(batch)) => batch,
+            Ok(response) => vec![response],
+            Err(_) => vec![],
+        };
+
+        for call in &calls {
+            let method = match call.get("method").and_then(Value::as_str) {
+                Some(method) => method,
+                None => continue,
+            };
+            let params = call.get("params").cloned().unwrap_or(Value::Null);
+            let credentials = params.get(0).and_then(|credentials| serde_json::from_value::<RpcCredentials>(credentials.clone()).ok());
+            if !GUARDED_METHODS.contains(&method) && credentials.is_none() {
+                continue;
+            }
+
+            let record = RpcAuditRecord {
+                timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+                method: method.to_string(),
+                ip,
+                identity: credentials.filter(|credentials| self.auth.role(credentials).is_some()).map(|credentials| credentials.username),
+                params_digest: params_digest(params),
+                latency_in_ms: latency.as_millis() as u64,
+                status,
+                result_code: result_code(call.get("id"), &responses),
+            };
+            self.write(&record);
+        }
+    }
+
+    /// Writes the given record to the sink of the audit log.
+    fn write(&self, record: &RpcAuditRecord) {
+        let line = serde_json::json!(record).to_string();
+        let result = match &self.sink {
+            RpcAuditSink::File(file) => {
+                let mut file = file.lock().expect("The RPC audit log lock is never poisoned");
+                writeln!(file, "{}", line)
+            }
+            RpcAuditSink::Syslog(socket) => {
+                let message = format!("<{}>snarkos[{}]: {}", SYSLOG_PRIORITY, std::process::id(), line);
+                socket.send(message.as_bytes()).map(|_| ())
+            }
+        };
+        if let Err(error) = result {
+            error!("Failed to write the RPC audit record {}: {}", line, error);
+        }
+    }
+}
+
+/// Returns the SHA-256 digest of the given parameters, without the credentials, in hex.
+fn params_digest(params: Value) -> String {
+    let params = match params {
+        Value::Array(mut params) => {
+            if params.first().map_or(false, |credentials| serde_json::from_value::<RpcCredentials>(credentials.clone()).is_ok()) {
+                params.remove(0);
+            }
+            Value::Array(params)
+        }
+        params => params,
+    };
+    hex::encode(Sha256::digest(params.to_string().as_bytes()))
+}
+
+/// Returns `0` if the response to the call with the given ID succeeded, or its error code if it failed.
+/// A request rejected as a whole is answered with a single error, which is the result of each of its calls.
+fn result_code(id: Option<&Value>, responses: &[Value]) -> Option<i64> {
+    let response = responses
+        .iter()
+        .find(|response| response.get("id") == id && id.is_some())
+        .or_else(|| responses.iter().find(|response| response.get("id") == Some(&Value::Null)))?;
+    match response.get("error") {
+        Some(error) => error.get("code").and_then(Value::as_i64),
+        None => Some(0),
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Role-based authorization of the private RPC endpoints.
+
+use crate::{RpcCredentials, RpcError};
+
+use snarkos_environment::helpers::SecretString;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs::File, path::Path};
+use subtle::ConstantTimeEq;
+
+///
+/// The role of an RPC credential, which bounds the private endpoints it may call.
+/// Each role may also call the endpoints of the roles below it.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RpcRole {
+    /// May call the private endpoints which only read the state of the node, of which there are none at present.
+    ReadOnly,
+    /// May call the private endpoints which act on transactions, such as the transaction hints of `getblocktemplate`.
+    Wallet,
+    /// May call every private endpoint, including those which manage the peers, leases, and profiler of the node.
+    Admin,
+}
+
+///
+/// An API key for the private RPC endpoints, presented as the username and password of the RPC credentials.
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcApiKey {
+    /// The name of the API key, presented as the username.
+    pub name: String,
+    /// The secret of the API key, presented as the password.
+    pub key: SecretString,
+    /// The role of the API key.
+    pub role: RpcRole,
+}
+
+///
+/// The credentials accepted by the RPC server, which are the admin credentials of the node, and its API keys.
+///
+#[derive(Clone, Debug)]
+pub struct RpcAuth {
+    /// The credentials of the node, which hold the admin role.
+    credentials: RpcCredentials,
+    /// The API keys of the node, each with its own role.
+    api_keys: Vec<RpcApiKey>,
+}
+
+impl RpcAuth {
+    ///
+    /// Initializes the authorization of the RPC server, ensuring each API key has a unique name and a non-empty secret.
+    ///
+    pub fn new(credentials: RpcCredentials, api_keys: Vec<RpcApiKey>) -> Result<Self> {
+        let mut names = HashSet::with_capacity(api_keys.len() + 1);
+        names.insert(credentials.username.as_str());
+        for api_key in &api_keys {
+            if !names.insert(api_key.name.as_str()) {
+                return Err(anyhow!("The RPC API key '{}' is not uniquely named", api_key.name));
+            }
+            if api_key.key.expose_secret().is_empty() {
+                return Err(anyhow!("The RPC API key '{}' has an empty secret", api_key.name));
+            }
+        }
+        Ok(Self { credentials, api_keys })
+    }
+
+    ///
+    /// Opens the API keys from the JSON file at the given path, alongside the given admin credentials.
+    ///
+    pub fn open<P: AsRef<Path>>(credentials: RpcCredentials, path: P) -> Result<Self> {
+        let file = File::open(path.as_ref()).map_err(|error| anyhow!("Failed to open {}: {}", path.as_ref().display(), error))?;
+        let api_keys: Vec<RpcApiKey> =
+            serde_json::from_reader(file).map_err(|error| anyhow!("Failed to parse {}: {}", path.as_ref().display(), error))?;
+        Self::new(credentials, api_keys)
+    }
+
+    ///
+    /// Returns the role of the given credentials, or `None` if they are not valid.
+    ///
+    pub fn role(&self, credentials: &RpcCredentials) -> Option<RpcRole> {
+        // Compare the username and secret in constant time, so the timing of a call reveals no part of a valid secret.
+        let matches = |username: &str, password: &SecretString| -> bool {
+            let is_username = username.as_bytes().ct_eq(credentials.username.as_bytes());
+            let is_password = password.ct_eq(&credentials.password);
+            (is_username & is_password).into()
+        };
+        if matches(&self.credentials.username, &self.credentials.password) {
+            return Some(RpcRole::Admin);
+        }
+        self.api_keys
+            .iter()
+            .find(|api_key| matches(&api_key.name, &api_key.key))
+            .map(|api_key| api_key.role)
+    }
+
+    ///
+    /// Ensures the given credentials are valid, and hold at least the given role.
+    ///
+    pub fn authorize(&self, credentials: &RpcCredentials, role: RpcRole) -> Result<(), RpcError> {
+        match self.role(credentials) {
+            Some(granted) if granted >= role => Ok(()),
+            Some(granted) => Err(RpcError::Message(format!(
+                "The RPC credentials hold the {:?} role, but the {:?} role is required",
+                granted, role
+            ))),
+            None => Err(RpcError::Message("Invalid RPC credentials".to_string())),
+        }
+    }
+}
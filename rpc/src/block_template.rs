@@ -0,0 +1,81 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The schemas of the block templates served by `getblocktemplate`.
+
+use serde_json::Value;
+use std::{fmt, str::FromStr};
+
+///
+/// The schema of the block templates served by `getblocktemplate`.
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BlockTemplateSchema {
+    /// The block template of snarkOS, as documented in `getblocktemplate.md`.
+    Native,
+    /// A block template in the field names of the generic pool software, with a job ID, a hex target, and a clean jobs flag.
+    Generic,
+}
+
+impl Default for BlockTemplateSchema {
+    fn default() -> Self {
+        Self::Native
+    }
+}
+
+impl fmt::Display for BlockTemplateSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Native => write!(f, "native"),
+            Self::Generic => write!(f, "generic"),
+        }
+    }
+}
+
+impl FromStr for BlockTemplateSchema {
+    type Err = String;
+
+    fn from_str(schema: &str) -> Result<Self, Self::Err> {
+        match schema.to_lowercase().as_str() {
+            "native" => Ok(Self::Native),
+            "generic" => Ok(Self::Generic),
+            _ => Err(format!("Unknown block template schema '{}'", schema)),
+        }
+    }
+}
+
+///
+/// Returns the given native block template in the generic schema, where `clean_jobs` is `true`
+/// if the work on the previous block templates of the caller is stale.
+///
+pub(crate) fn to_generic_block_template(template: &Value, clean_jobs: bool) -> Value {
+    let template_id = template["template_id"].as_u64().unwrap_or_default();
+    let difficulty_target = template["difficulty_target"].as_u64().unwrap_or_default();
+    serde_json::json!({
+        "job_id": format!("{:x}", template_id),
+        "longpollid": template_id,
+        "clean_jobs": clean_jobs,
+        "previousblockhash": template["previous_block_hash"],
+        "height": template["block_height"],
+        "curtime": template["time"],
+        "target": format!("{:016x}", difficulty_target),
+        "cumulativeweight": template["cumulative_weight"],
+        "ledgerroot": template["ledger_root"],
+        "coinbasevalue": template["coinbase_reward"],
+        "transactions": template["transactions"],
+    })
+}
+
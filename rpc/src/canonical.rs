@@ -0,0 +1,182 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Canonical JSON serialization of blocks, block headers, and transactions.
+//!
+//! Unlike the serde derives of the underlying types, the shape of each schema version is fixed:
+//! a change in shape is only ever introduced as a new schema version. Integers that may exceed 2^53
+//! are encoded as decimal strings, so they survive JSON parsers that use doubles. The schema is
+//! documented in `documentation/concepts/canonical_json.md`.
+
+use crate::RpcError;
+use snarkvm::{
+    dpc::{Block, BlockHeader, Network, Transaction, Transition},
+    utilities::ToBytes,
+};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// The canonical JSON schema versions supported by this node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SchemaVersion {
+    V1,
+}
+
+impl SchemaVersion {
+    /// Returns the schema version of the given number, if it is supported.
+    pub(crate) fn new(version: u32) -> Result<Self, RpcError> {
+        match version {
+            1 => Ok(Self::V1),
+            _ => Err(RpcError::Message(format!("Unsupported canonical JSON schema version {}", version))),
+        }
+    }
+
+    /// Returns the number of this schema version.
+    fn number(&self) -> u32 {
+        match self {
+            Self::V1 => 1,
+        }
+    }
+}
+
+/// Returns the canonical JSON of the given block.
+pub(crate) fn block_to_json<N: Network>(block: &Block<N>, version: SchemaVersion) -> Result<Value, RpcError> {
+    match version {
+        SchemaVersion::V1 => versioned(version, BlockV1::new(block)?),
+    }
+}
+
+/// Returns the canonical JSON of the given block header.
+pub(crate) fn block_header_to_json<N: Network>(block_header: &BlockHeader<N>, version: SchemaVersion) -> Result<Value, RpcError> {
+    match version {
+        SchemaVersion::V1 => versioned(version, BlockHeaderV1::new(block_header)?),
+    }
+}
+
+/// Returns the canonical JSON of the given transaction.
+pub(crate) fn transaction_to_json<N: Network>(transaction: &Transaction<N>, version: SchemaVersion) -> Result<Value, RpcError> {
+    match version {
+        SchemaVersion::V1 => versioned(version, TransactionV1::new(transaction)?),
+    }
+}
+
+/// Returns the given object as JSON, tagged with the given schema version.
+/// The fields of every JSON object are ordered lexicographically by name.
+fn versioned<T: Serialize>(version: SchemaVersion, object: T) -> Result<Value, RpcError> {
+    let mut json = serde_json::to_value(object)?;
+    if let Value::Object(fields) = &mut json {
+        fields.insert("schema_version".to_string(), version.number().into());
+    }
+    Ok(json)
+}
+
+/// Returns the given bytes as a lowercase hex string.
+fn to_hex<T: ToBytes>(object: &T) -> Result<String, RpcError> {
+    Ok(hex::encode(object.to_bytes_le()?))
+}
+
+#[derive(Serialize)]
+struct BlockV1 {
+    block_hash: String,
+    previous_block_hash: String,
+    header: BlockHeaderV1,
+    transactions: Vec<TransactionV1>,
+}
+
+impl BlockV1 {
+    fn new<N: Network>(block: &Block<N>) -> Result<Self, RpcError> {
+        Ok(Self {
+            block_hash: block.hash().to_string(),
+            previous_block_hash: block.previous_block_hash().to_string(),
+            header: BlockHeaderV1::new(block.header())?,
+            transactions: block.transactions().iter().map(TransactionV1::new).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct BlockHeaderV1 {
+    height: u32,
+    timestamp: i64,
+    previous_ledger_root: String,
+    transactions_root: String,
+    difficulty_target: String,
+    cumulative_weight: String,
+    nonce: String,
+    proof: String,
+}
+
+impl BlockHeaderV1 {
+    fn new<N: Network>(block_header: &BlockHeader<N>) -> Result<Self, RpcError> {
+        Ok(Self {
+            height: block_header.height(),
+            timestamp: block_header.timestamp(),
+            previous_ledger_root: block_header.previous_ledger_root().to_string(),
+            transactions_root: block_header.transactions_root().to_string(),
+            difficulty_target: block_header.difficulty_target().to_string(),
+            cumulative_weight: block_header.cumulative_weight().to_string(),
+            nonce: block_header.nonce().to_string(),
+            proof: to_hex(block_header.proof())?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct TransactionV1 {
+    transaction_id: String,
+    inner_circuit_id: String,
+    ledger_root: String,
+    value_balance: String,
+    transitions: Vec<TransitionV1>,
+}
+
+impl TransactionV1 {
+    fn new<N: Network>(transaction: &Transaction<N>) -> Result<Self, RpcError> {
+        Ok(Self {
+            transaction_id: transaction.transaction_id().to_string(),
+            inner_circuit_id: transaction.inner_circuit_id().to_string(),
+            ledger_root: transaction.ledger_root().to_string(),
+            value_balance: transaction.value_balance().0.to_string(),
+            transitions: transaction.transitions().iter().map(TransitionV1::new).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct TransitionV1 {
+    transition_id: String,
+    serial_numbers: Vec<String>,
+    commitments: Vec<String>,
+    ciphertexts: Vec<String>,
+    value_balance: String,
+    events: Vec<String>,
+    proof: String,
+}
+
+impl TransitionV1 {
+    fn new<N: Network>(transition: &Transition<N>) -> Result<Self, RpcError> {
+        Ok(Self {
+            transition_id: transition.transition_id().to_string(),
+            serial_numbers: transition.serial_numbers().map(ToString::to_string).collect(),
+            commitments: transition.commitments().map(ToString::to_string).collect(),
+            ciphertexts: transition.ciphertexts().map(to_hex).collect::<Result<_, _>>()?,
+            value_balance: transition.value_balance().0.to_string(),
+            events: transition.events().map(to_hex).collect::<Result<_, _>>()?,
+            proof: to_hex(transition.proof())?,
+        })
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Compression of the RPC HTTP responses, for the clients which accept gzip or deflate.
+
+use anyhow::{anyhow, Result};
+use flate2::{
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use hyper::{
+    body::{self, HttpBody},
+    header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, VARY},
+    Body,
+    HeaderMap,
+    Response,
+};
+use std::io::Write;
+
+/// The size in bytes below which a response is not worth compressing.
+const MINIMUM_COMPRESSED_SIZE: usize = 1024;
+
+///
+/// The content encodings the RPC HTTP server may compress its responses with.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RpcEncoding {
+    Gzip,
+    Deflate,
+}
+
+impl RpcEncoding {
+    /// Returns the name of the encoding in the `Content-Encoding` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+}
+
+///
+/// The compression policy of the RPC HTTP server, whose level bounds the CPU spent on each response.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct RpcCompression {
+    level: u32,
+}
+
+impl RpcCompression {
+    ///
+    /// Initializes a new compression policy with the given level, from 1 (fastest) to 9 (smallest).
+    ///
+    pub fn new(level: u32) -> Result<Self> {
+        match (1..=9).contains(&level) {
+            true => Ok(Self { level }),
+            false => Err(anyhow!("The compression level of the RPC server must be between 1 and 9")),
+        }
+    }
+
+    ///
+    /// Returns the encoding preferred by a client with the given request headers, if it accepts gzip or deflate.
+    /// Gzip is preferred over deflate, unless the client gives deflate a higher quality.
+    ///
+    pub fn negotiate(headers: &HeaderMap) -> Option<RpcEncoding> {
+        let mut preferred: Option<(RpcEncoding, f32)> = None;
+        let values = headers.get_all(ACCEPT_ENCODING).iter().filter_map(|value| value.to_str().ok());
+        for coding in values.flat_map(|value| value.split(',')) {
+            let mut parameters = coding.split(';').map(str::trim);
+            let encoding = match parameters.next().map(str::to_ascii_lowercase).as_deref() {
+                Some("gzip") | Some("x-gzip") => RpcEncoding::Gzip,
+                Some("deflate") => RpcEncoding::Deflate,
+                _ => continue,
+            };
+            let quality = parameters
+                .find_map(|parameter| parameter.strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.parse::<f32>().ok())
+                .unwrap_or(0.0);
+            // A quality of zero means the encoding is not acceptable.
+            if quality <= 0.0 {
+                continue;
+            }
+            let is_preferred = match preferred {
+                Some((_, preferred_quality)) if quality == preferred_quality => encoding == RpcEncoding::Gzip,
+                Some((_, preferred_quality)) => quality > preferred_quality,
+                None => true,
+            };
+            if is_preferred {
+                preferred = Some((encoding, quality));
+            }
+        }
+        preferred.map(|(encoding, _)| encoding)
+    }
+
+    ///
+    /// Compresses the body of the given response with the given encoding, unless it is too small or already encoded.
+    /// The compression runs on the blocking thread pool, as large responses take a while to compress.
+    /// A streamed response, whose length is not known upfront, is forwarded as it streams, without compression.
+    ///
+    pub async fn compress(&self, encoding: RpcEncoding, response: Response<Body>) -> Response<Body> {
+        if response.headers().contains_key(CONTENT_ENCODING) || response.body().size_hint().exact().is_none() {
+            return response;
+        }
+        let (mut parts, body) = response.into_parts();
+        // The response varies with the accepted encodings, whether or not it is compressed.
+        parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+
+        let bytes = match body::to_bytes(body).await {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                warn!("Failed to read an RPC response for compression: {}", error);
+                return Response::from_parts(parts, Body::empty());
+            }
+        };
+        if bytes.len() < MINIMUM_COMPRESSED_SIZE {
+            return Response::from_parts(parts, Body::from(bytes));
+        }
+
+        let level = Compression::new(self.level);
+        let compressed = tokio::task::spawn_blocking(move || match encoding {
+            RpcEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::with_capacity(bytes.len() / 4), level);
+                encoder.write_all(&bytes).and_then(|_| encoder.finish()).map_err(|error| (error, bytes))
+            }
+            RpcEncoding::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::with_capacity(bytes.len() / 4), level);
+                encoder.write_all(&bytes).and_then(|_| encoder.finish()).map_err(|error| (error, bytes))
+            }
+        })
+        .await;
+
+        match compressed {
+            Ok(Ok(compressed)) => {
+                parts.headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding.as_str()));
+                // The length of the body has changed, and is set again from the compressed body.
+                parts.headers.remove(CONTENT_LENGTH);
+                Response::from_parts(parts, Body::from(compressed))
+            }
+            Ok(Err((error, bytes))) => {
+                warn!("Failed to compress an RPC response: {}", error);
+                Response::from_parts(parts, Body::from(bytes))
+            }
+            Err(error) => {
+                warn!("Failed to compress an RPC response: {}", error);
+                Response::from_parts(parts, Body::empty())
+            }
+        }
+    }
+}
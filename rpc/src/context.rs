@@ -16,19 +16,57 @@
 
 //! Logic for instantiating the RPC server.
 
-use snarkos_environment::Environment;
-use snarkos_network::{LedgerReader, Operator, Peers, ProverRouter, LedgerRouter, OperatorRouter};
-use snarkvm::dpc::{Address, MemoryPool, Network};
+use snarkos_environment::{
+    helpers::{next_request_id, SecretString},
+    Environment,
+};
+use snarkos_network::{
+    helpers::{BlockTemplateIds, CircuitBreaker, SharedMemoryPool, SubPool, SyncProgress},
+    LedgerReader,
+    LedgerRouter,
+    Operator,
+    OperatorRouter,
+    Peers,
+    ProverRouter,
+};
+use snarkos_storage::ContributionPeriod;
+use snarkvm::dpc::{Address, Network, Record};
 
 use futures::TryFutureExt;
 use jsonrpsee::{
     core::{middleware::Middleware, Error as JsonrpseeError},
     http_server::{AccessControlBuilder, HttpServerBuilder, RpcModule},
+    types::Params,
+    ws_server::{SubscriptionSink, WsServerBuilder},
+};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use std::{
+    future::Future,
+    net::{Ipv4Addr, SocketAddr},
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{oneshot, Semaphore, SemaphorePermit};
+use tracing::{Instrument, Span};
+use crate::{
+    BlockTemplateSchema,
+    RpcAuditLog,
+    RpcAuth,
+    RpcCompression,
+    RpcCors,
+    RpcError,
+    RpcFront,
+    RpcFunctions,
+    RpcHttpConfig,
+    RpcMethodPolicy,
+    RpcRateLimiter,
+    RpcRole,
+    RpcServerKind,
+    RpcTlsAcceptor,
+    RpcUsageTracker,
+    GUARDED_METHODS,
 };
-use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, ops::Deref, sync::Arc, time::Instant};
-use tokio::sync::{oneshot, RwLock};
-use crate::RpcFunctions;
 
 // The details on resource-limiting can be found at https://github.com/paritytech/jsonrpsee/blob/master/core/src/server/resource_limiting.rs
 // note: jsonrpsee expects string literals as resource names; we'll be distinguishing
@@ -36,8 +74,17 @@ use crate::RpcFunctions;
 // the underlying strings short, as long as they are unique.
 /// The resource label corresponding to the number of all active RPC calls.
 const ALL_CONCURRENT_REQUESTS: &str = "0";
-/// The maximum number of RPC requests that can be handled at once at any given time.
-const ALL_CONCURRENT_REQUESTS_LIMIT: u16 = 10;
+/// The maximum number of RPC calls that can be accepted at once at any given time, including
+/// the calls of batch requests that are waiting for their turn to be executed.
+const ALL_CONCURRENT_REQUESTS_LIMIT: u16 = 256;
+/// The maximum number of RPC calls that can be executed at once at any given time.
+const ALL_EXECUTING_REQUESTS_LIMIT: usize = 10;
+/// The maximum duration for which an RPC call waits on a request to another subsystem of the node.
+const SUBSYSTEM_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// The maximum number of block exports of the REST gateway which stream at once.
+const MAX_CONCURRENT_BLOCK_EXPORTS: usize = 2;
+/// The maximum size of the body of an RPC request, in bytes.
+pub(crate) const MAX_REQUEST_BODY_SIZE: u32 = 10 * 1024 * 1024;
 
 #[doc(hidden)]
 pub struct RpcInner<N: Network, E: Environment> {
@@ -48,10 +95,29 @@ pub struct RpcInner<N: Network, E: Environment> {
     pub(crate) operator: Arc<Operator<N, E>>,
     pub(crate) operator_router: OperatorRouter<N>,
     pub(crate) prover_router: ProverRouter<N>,
-    pub(crate) memory_pool: Arc<RwLock<MemoryPool<N>>>,
-    /// RPC credentials for accessing guarded endpoints
-    #[allow(unused)]
-    pub(crate) credentials: RpcCredentials,
+    pub(crate) memory_pool: Arc<SharedMemoryPool<N>>,
+    /// The RPC credentials and API keys for accessing guarded endpoints, and their roles.
+    pub(crate) auth: RpcAuth,
+    /// The usage statistics of the clients of the RPC servers, if they are recorded.
+    pub(crate) usage: Option<Arc<RpcUsageTracker>>,
+    /// The audit log of the calls to the guarded methods, if they are audited.
+    pub(crate) audit_log: Option<Arc<RpcAuditLog>>,
+    /// The policy which enables and disables the RPC methods of the node.
+    pub(crate) method_policy: RpcMethodPolicy,
+    /// The permits for executing RPC calls, bounding the calls of batch requests that execute at once.
+    pub(crate) execution_permits: Semaphore,
+    /// The permits for streaming the block exports of the REST gateway, bounding the exports which stream at once.
+    pub(crate) export_permits: Arc<Semaphore>,
+    /// The block heights of the ledger sampled by the calls of `getsyncstatus`, to measure the sync throughput.
+    pub(crate) sync_progress: SyncProgress,
+    /// The IDs of the block templates served by `getblocktemplate`, by the latest block hash and memory pool version.
+    pub(crate) block_template_ids: BlockTemplateIds<(N::BlockHash, u64)>,
+    /// The schema of the block templates served by `getblocktemplate`.
+    pub(crate) block_template_schema: BlockTemplateSchema,
+    /// The circuit breaker for the requests to the prover, failing RPC calls while the prover is unresponsive.
+    pub(crate) prover_circuit_breaker: CircuitBreaker,
+    /// The circuit breaker for the requests to the peers, failing RPC calls while the peers are unresponsive.
+    pub(crate) peers_circuit_breaker: CircuitBreaker,
     pub(crate) launched: Instant,
 }
 
@@ -71,8 +137,10 @@ impl<N: Network, E: Environment> RpcContext<N, E> {
     /// Creates a new struct for calling public and private RPC endpoints.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        username: String,
-        password: String,
+        auth: RpcAuth,
+        usage: Option<Arc<RpcUsageTracker>>,
+        audit_log: Option<Arc<RpcAuditLog>>,
+        method_policy: RpcMethodPolicy,
         address: Option<Address<N>>,
         peers: Arc<Peers<N, E>>,
         ledger: LedgerReader<N>,
@@ -80,7 +148,8 @@ impl<N: Network, E: Environment> RpcContext<N, E> {
         operator: Arc<Operator<N, E>>,
         operator_router: OperatorRouter<N>,
         prover_router: ProverRouter<N>,
-        memory_pool: Arc<RwLock<MemoryPool<N>>>,
+        memory_pool: Arc<SharedMemoryPool<N>>,
+        block_template_schema: BlockTemplateSchema,
     ) -> Self {
         Self(Arc::new(RpcInner {
             address,
@@ -91,10 +160,31 @@ impl<N: Network, E: Environment> RpcContext<N, E> {
             operator_router,
             prover_router,
             memory_pool,
-            credentials: RpcCredentials { username, password },
+            auth,
+            usage,
+            audit_log,
+            method_policy,
+            execution_permits: Semaphore::new(ALL_EXECUTING_REQUESTS_LIMIT),
+            export_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_BLOCK_EXPORTS)),
+            sync_progress: Default::default(),
+            block_template_ids: Default::default(),
+            block_template_schema,
+            prover_circuit_breaker: CircuitBreaker::new("prover", SUBSYSTEM_REQUEST_TIMEOUT),
+            peers_circuit_breaker: CircuitBreaker::new("peers", SUBSYSTEM_REQUEST_TIMEOUT),
             launched: Instant::now(),
         }))
     }
+
+    /// Ensures the given credentials are valid for this RPC server, and hold at least the given role.
+    pub(crate) fn authorize(&self, credentials: &RpcCredentials, role: RpcRole) -> Result<(), RpcError> {
+        self.auth.authorize(credentials, role)
+    }
+
+    /// Waits until fewer than `ALL_EXECUTING_REQUESTS_LIMIT` RPC calls are executing,
+    /// and returns a permit which allows the caller to execute until it is dropped.
+    pub(crate) async fn acquire_execution_permit(&self) -> SemaphorePermit<'_> {
+        self.execution_permits.acquire().await.expect("The RPC execution permits are never closed")
+    }
 }
 
 /// Defines the authentication format for accessing private endpoints on the RPC server.
@@ -103,7 +193,7 @@ pub struct RpcCredentials {
     /// The username in the credential
     pub username: String,
     /// The password in the credential
-    pub password: String,
+    pub password: SecretString,
 }
 
 /// RPC metadata for encoding authentication.
@@ -131,28 +221,60 @@ impl Middleware for RpcMiddleware {
     fn on_result(&self, name: &str, success: bool, started_at: Instant) {
         let result = if success { "succeeded" } else { "failed" };
         trace!("Call to '{}' {} in {:?}", name, result, started_at.elapsed());
+
+        #[cfg(feature = "prometheus")]
+        {
+            let method = name.to_string();
+            snarkos_metrics::increment_counter!(snarkos_metrics::rpc::REQUESTS, "method" => method.clone(), "result" => result);
+            let duration = started_at.elapsed().as_secs_f64();
+            snarkos_metrics::histogram!(snarkos_metrics::rpc::REQUEST_DURATION, duration, "method" => method);
+        }
     }
 }
 
 /// Starts a local RPC HTTP server at `rpc_port` in a dedicated `tokio` task.
+/// If a TLS acceptor or a rate limiter is given, TLS is terminated and the calls of each client IP are limited at `rpc_port`,
+/// in front of a plaintext server on the loopback interface. If a CORS policy, a compression policy, or HTTP settings
+/// are given, or the context records usage statistics or an audit log, they are applied there as well.
 /// RPC failures do not affect the rest of the node.
 pub async fn initialize_rpc_server<N: Network, E: Environment>(
     rpc_server_addr: SocketAddr,
     rpc_server_context: RpcContext<N, E>,
+    tls_acceptor: Option<RpcTlsAcceptor>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+    cors: Option<Arc<RpcCors>>,
+    compression: Option<RpcCompression>,
+    http_config: Option<RpcHttpConfig>,
 ) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let (usage, audit_log) = (rpc_server_context.usage.clone(), rpc_server_context.audit_log.clone());
+    let (front, rpc_server_addr) = bind_front(
+        rpc_server_addr,
+        tls_acceptor,
+        rate_limiter,
+        usage,
+        audit_log,
+        cors,
+        compression,
+        http_config,
+        RpcServerKind::Http,
+    )
+    .await;
+
     let access_control = AccessControlBuilder::default().build(); // TODO(ljedrz): adjust to only accept the desired hosts?
 
     let server = HttpServerBuilder::new()
         .set_access_control(access_control)
         // Limit the number of requests handled at a time to `ALL_CONCURRENT_REQUESTS_LIMIT`; the `1` argument means that all RPC requests
         // will count towards that limit by 1, meaning they all have the same weight wrt. the resource labeled `ALL_CONCURRENT_REQUESTS`.
+        // Each call of a batch request counts separately; accepted calls are then executed `ALL_EXECUTING_REQUESTS_LIMIT` at a time.
         .register_resource(ALL_CONCURRENT_REQUESTS, ALL_CONCURRENT_REQUESTS_LIMIT, 1)
         .expect("Invalid JSON-RPC server resource")
-        .max_request_body_size(10 * 1024 * 1024) // Explicitly select the body size limit (jsonrpsee's default, 10MiB) for greater visibility.
+        .max_request_body_size(MAX_REQUEST_BODY_SIZE) // Explicitly select the body size limit (jsonrpsee's default, 10MiB) for greater visibility.
         .set_middleware(RpcMiddleware)
         .build(rpc_server_addr).expect("Failed to create the RPC server");
 
     let server_addr = server.local_addr().expect("Can't obtain RPC server's local address");
+    let listener_addr = front.as_ref().map_or(server_addr, RpcFront::local_addr);
 
     let module = create_rpc_module(rpc_server_context).expect("Failed to start the RPC server");
 
@@ -161,133 +283,495 @@ pub async fn initialize_rpc_server<N: Network, E: Environment>(
         // Notify the outer function that the task is ready.
         let _ = router.send(());
         let server_handle = server.start(module).expect("Failed to start the RPC server");
-        server_handle.await
+        serve_with_front(server_handle, front, server_addr).await
     });
     // Wait until the spawned task is ready.
     let _ = handler.await;
 
-    (server_addr, task)
+    (listener_addr, task)
+}
+
+/// Starts a local RPC WebSocket server at `rpc_ws_server_addr` in a dedicated `tokio` task.
+/// It serves the same methods as the RPC HTTP server, as well as subscriptions, and terminates TLS in the same way.
+/// A rate limiter limits the connections of each client IP, rather than the calls over a connection.
+/// RPC failures do not affect the rest of the node.
+pub async fn initialize_rpc_ws_server<N: Network, E: Environment>(
+    rpc_ws_server_addr: SocketAddr,
+    rpc_server_context: RpcContext<N, E>,
+    tls_acceptor: Option<RpcTlsAcceptor>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let (front, rpc_ws_server_addr) =
+        bind_front(rpc_ws_server_addr, tls_acceptor, rate_limiter, None, None, None, None, None, RpcServerKind::WebSocket).await;
+
+    let server = WsServerBuilder::new()
+        // Share the request limits of the RPC HTTP server; see `initialize_rpc_server`.
+        .register_resource(ALL_CONCURRENT_REQUESTS, ALL_CONCURRENT_REQUESTS_LIMIT, 1)
+        .expect("Invalid JSON-RPC server resource")
+        .max_request_body_size(MAX_REQUEST_BODY_SIZE)
+        .set_middleware(RpcMiddleware)
+        .build(rpc_ws_server_addr)
+        .await
+        .expect("Failed to create the RPC WebSocket server");
+
+    let server_addr = server.local_addr().expect("Can't obtain RPC WebSocket server's local address");
+    let listener_addr = front.as_ref().map_or(server_addr, RpcFront::local_addr);
+
+    let module = create_rpc_module(rpc_server_context).expect("Failed to start the RPC WebSocket server");
+
+    let (router, handler) = oneshot::channel();
+    let task = tokio::spawn(async move {
+        // Notify the outer function that the task is ready.
+        let _ = router.send(());
+        let server_handle = server.start(module).expect("Failed to start the RPC WebSocket server");
+        serve_with_front(server_handle, front, server_addr).await
+    });
+    // Wait until the spawned task is ready.
+    let _ = handler.await;
+
+    (listener_addr, task)
+}
+
+/// Binds the listener in front of the RPC server at the given address if TLS, rate limiting, usage statistics, the audit
+/// log, CORS, compression, or the HTTP settings are enabled, returning it with the address to bind the RPC server to,
+/// which is then an ephemeral port on the loopback interface, so that it is only reachable through the listener.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn bind_front(
+    rpc_server_addr: SocketAddr,
+    tls_acceptor: Option<RpcTlsAcceptor>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+    usage: Option<Arc<RpcUsageTracker>>,
+    audit_log: Option<Arc<RpcAuditLog>>,
+    cors: Option<Arc<RpcCors>>,
+    compression: Option<RpcCompression>,
+    http_config: Option<RpcHttpConfig>,
+    kind: RpcServerKind,
+) -> (Option<RpcFront>, SocketAddr) {
+    let front = RpcFront::bind(rpc_server_addr, tls_acceptor, rate_limiter, usage, audit_log, cors, compression, http_config, kind);
+    match front.await {
+        Some(front) => (Some(front), SocketAddr::from((Ipv4Addr::LOCALHOST, 0))),
+        None => (None, rpc_server_addr),
+    }
+}
+
+/// Runs the given RPC server, along with the listener in front of it, if any.
+pub(crate) async fn serve_with_front<F: Future<Output = ()>>(server_handle: F, front: Option<RpcFront>, server_addr: SocketAddr) {
+    match front {
+        Some(front) => {
+            tokio::join!(server_handle, front.serve(server_addr));
+        }
+        None => server_handle.await,
+    }
+}
+
+/// Returns a new span for a call to the given RPC method, carrying a unique request ID.
+fn rpc_span(method: &'static str) -> Span {
+    debug_span!("rpc", method, request_id = next_request_id())
+}
+
+///
+/// The RPC module under construction, which only registers the methods enabled by the method policy of the node,
+/// so that the calls to the other methods are answered as calls to unknown methods.
+///
+struct RpcModuleBuilder<N: Network, E: Environment> {
+    module: RpcModule<RpcContext<N, E>>,
+    method_policy: RpcMethodPolicy,
+}
+
+impl<N: Network, E: Environment> RpcModuleBuilder<N, E> {
+    fn new(rpc_context: RpcContext<N, E>) -> Self {
+        let method_policy = rpc_context.method_policy.clone();
+        Self {
+            module: RpcModule::new(rpc_context),
+            method_policy,
+        }
+    }
+
+    ///
+    /// Registers the given asynchronous method, if it is enabled, tracing each call in a span of the method.
+    /// Each call holds an execution permit for as long as it executes.
+    ///
+    fn register_async_method<R, Fun, Fut>(&mut self, method: &'static str, callback: Fun) -> Result<(), JsonrpseeError>
+    where
+        R: Serialize + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, JsonrpseeError>> + Send,
+        Fun: (Fn(Params<'static>, Arc<RpcContext<N, E>>) -> Fut) + Copy + Send + Sync + 'static,
+    {
+        self.register_long_poll_method(method, move |rpc_params, rpc_context| async move {
+            let permit_context = rpc_context.clone();
+            let _permit = permit_context.acquire_execution_permit().await;
+            callback(rpc_params, rpc_context).await
+        })
+    }
+
+    ///
+    /// Registers the given asynchronous method, if it is enabled, which is only served to callers presenting RPC credentials
+    /// with at least the given role as its first parameter. The credentials are checked before the call acquires an
+    /// execution permit, so the callback may ignore them.
+    ///
+    fn register_guarded_method<R, Fun, Fut>(&mut self, method: &'static str, role: RpcRole, callback: Fun) -> Result<(), JsonrpseeError>
+    where
+        R: Serialize + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, JsonrpseeError>> + Send,
+        Fun: (Fn(Params<'static>, Arc<RpcContext<N, E>>) -> Fut) + Copy + Send + Sync + 'static,
+    {
+        debug_assert!(GUARDED_METHODS.contains(&method), "The guarded RPC method '{}' is not audited", method);
+        self.register_long_poll_method(method, move |rpc_params, rpc_context| async move {
+            let credentials = rpc_params.sequence().next::<RpcCredentials>()?;
+            rpc_context.authorize(&credentials, role).map_err(JsonrpseeError::to_call_error)?;
+            let permit_context = rpc_context.clone();
+            let _permit = permit_context.acquire_execution_permit().await;
+            callback(rpc_params, rpc_context).await
+        })
+    }
+
+    ///
+    /// Registers the given asynchronous method, if it is enabled, tracing each call in a span of the method.
+    /// The calls may long-poll, so they are not given an execution permit, and must acquire one themselves once they stop waiting.
+    ///
+    fn register_long_poll_method<R, Fun, Fut>(&mut self, method: &'static str, callback: Fun) -> Result<(), JsonrpseeError>
+    where
+        R: Serialize + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, JsonrpseeError>> + Send,
+        Fun: (Fn(Params<'static>, Arc<RpcContext<N, E>>) -> Fut) + Copy + Send + Sync + 'static,
+    {
+        match self.method_policy.is_enabled(method) {
+            true => self
+                .module
+                .register_async_method(method, move |rpc_params, rpc_context| {
+                    callback(rpc_params, rpc_context).instrument(rpc_span(method))
+                })
+                .map(|_| ()),
+            false => {
+                debug!("The RPC method '{}' is disabled", method);
+                Ok(())
+            }
+        }
+    }
+
+    /// Registers the given subscription, if its subscribe method is enabled, tracing each subscription in a span of the method.
+    fn register_subscription<F>(
+        &mut self,
+        subscribe_method: &'static str,
+        notification_method: &'static str,
+        unsubscribe_method: &'static str,
+        callback: F,
+    ) -> Result<(), JsonrpseeError>
+    where
+        F: Fn(Params, SubscriptionSink, Arc<RpcContext<N, E>>) -> Result<(), JsonrpseeError> + Send + Sync + 'static,
+    {
+        match self.method_policy.is_enabled(subscribe_method) {
+            true => self
+                .module
+                .register_subscription(subscribe_method, notification_method, unsubscribe_method, move |rpc_params, sink, rpc_context| {
+                    let _span = rpc_span(subscribe_method).entered();
+                    callback(rpc_params, sink, rpc_context)
+                })
+                .map(|_| ()),
+            false => {
+                debug!("The RPC method '{}' is disabled", subscribe_method);
+                Ok(())
+            }
+        }
+    }
 }
 
 fn create_rpc_module<N: Network, E: Environment>(rpc_context: RpcContext<N, E>) -> Result<RpcModule<RpcContext<N, E>>, JsonrpseeError> {
-    let mut module = RpcModule::new(rpc_context);
+    let mut module = RpcModuleBuilder::new(rpc_context);
 
     // Public methods.
 
-    module.register_async_method("latestblock", |_rpc_params, rpc_context| async move {
-        rpc_context.latest_block().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("latestblock", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.latest_block().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("latestblockheight", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.latest_block_height().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("latestcumulativeweight", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.latest_cumulative_weight().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("latestblockhash", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.latest_block_hash().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("latestblockheader", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.latest_block_header().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("latestblocktransactions", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.latest_block_transactions().map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("latestblockheight", |_rpc_params, rpc_context| async move {
-        rpc_context.latest_block_height().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("latestledgerroot", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.latest_ledger_root().map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("latestcumulativeweight", |_rpc_params, rpc_context| async move {
-        rpc_context.latest_cumulative_weight().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getblock", |rpc_params, rpc_context| {
+        async move {
+            let mut rpc_params = rpc_params.sequence();
+            let height = rpc_params.next::<u32>()?;
+            let verbosity = rpc_params.optional_next::<u8>()?;
+            rpc_context.get_block(height, verbosity).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("latestblockhash", |_rpc_params, rpc_context| async move {
-        rpc_context.latest_block_hash().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getblockbyhash", |rpc_params, rpc_context| {
+        async move {
+            let hash = rpc_params.parse::<[N::BlockHash; 1]>()?[0];
+            rpc_context.get_block_by_hash(hash).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("latestblockheader", |_rpc_params, rpc_context| async move {
-        rpc_context.latest_block_header().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getblocks", |rpc_params, rpc_context| {
+        async move {
+            let mut rpc_params = rpc_params.sequence();
+            let start_height = rpc_params.next::<u32>()?;
+            let end_height = rpc_params.next::<u32>()?;
+            let verbosity = rpc_params.optional_next::<u8>()?;
+            rpc_context
+                .get_blocks(start_height, end_height, verbosity)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("latestblocktransactions", |_rpc_params, rpc_context| async move {
-        rpc_context.latest_block_transactions().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getblockheight", |rpc_params, rpc_context| {
+        async move {
+            let hash = rpc_params.parse::<[N::BlockHash; 1]>()?[0];
+            rpc_context.get_block_height(hash).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("latestledgerroot", |_rpc_params, rpc_context| async move {
-        rpc_context.latest_ledger_root().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getblockhash", |rpc_params, rpc_context| {
+        async move {
+            let height = rpc_params.parse::<[u32; 1]>()?[0];
+            rpc_context.get_block_hash(height).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getblock", |rpc_params, rpc_context| async move {
-        let height = rpc_params.parse::<[u32; 1]>()?[0];
-        rpc_context.get_block(height).map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getblockhashes", |rpc_params, rpc_context| {
+        async move {
+            let [start_height, end_height]: [u32; 2] = rpc_params.parse()?;
+            rpc_context
+                .get_block_hashes(start_height, end_height)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getblocks", |rpc_params, rpc_context| async move {
-        let [start_height, end_height]: [u32; 2] = rpc_params.parse()?;
-        rpc_context
-            .get_blocks(start_height, end_height)
-            .map_err(JsonrpseeError::to_call_error)
-            .await
+    module.register_async_method("getblockheader", |rpc_params, rpc_context| {
+        async move {
+            let height = rpc_params.parse::<[u32; 1]>()?[0];
+            rpc_context.get_block_header(height).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getblockheight", |rpc_params, rpc_context| async move {
-        let hash = rpc_params.parse::<[N::BlockHash; 1]>()?[0];
-        rpc_context.get_block_height(hash).map_err(JsonrpseeError::to_call_error).await
+    module.register_long_poll_method("getblocktemplate", |rpc_params, rpc_context| {
+        async move {
+            // The transaction hints and template ID are optional, so the endpoint may still be called without parameters.
+            let mut rpc_params = rpc_params.sequence();
+            let credentials = rpc_params.optional_next::<RpcCredentials>()?;
+            let exclude_tx = rpc_params.optional_next::<Vec<N::TransactionID>>()?.unwrap_or_default();
+            let include_tx = rpc_params.optional_next::<Vec<N::TransactionID>>()?.unwrap_or_default();
+            let template_id = rpc_params.optional_next::<u64>()?;
+            // Ensure the transaction hints are given by an authorized caller.
+            if !exclude_tx.is_empty() || !include_tx.is_empty() {
+                match credentials {
+                    Some(credentials) => rpc_context.authorize(&credentials, RpcRole::Wallet).map_err(JsonrpseeError::to_call_error)?,
+                    None => {
+                        let error = RpcError::Message("Transaction hints require RPC credentials with the wallet role".to_string());
+                        return Err(JsonrpseeError::to_call_error(error));
+                    }
+                }
+            }
+            // Hold the long-poll before acquiring an execution permit, so that waiting miners do not block other calls.
+            if let Some(template_id) = template_id {
+                rpc_context.wait_for_block_template(template_id).await;
+            }
+            let _permit = rpc_context.acquire_execution_permit().await;
+            rpc_context
+                .get_block_template(exclude_tx, include_tx, template_id)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getblockhash", |rpc_params, rpc_context| async move {
-        let height = rpc_params.parse::<[u32; 1]>()?[0];
-        rpc_context.get_block_hash(height).map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getblocktransactions", |rpc_params, rpc_context| {
+        async move {
+            let height = rpc_params.parse::<[u32; 1]>()?[0];
+            rpc_context
+                .get_block_transactions(height)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getblockhashes", |rpc_params, rpc_context| async move {
-        let [start_height, end_height]: [u32; 2] = rpc_params.parse()?;
-        rpc_context
-            .get_block_hashes(start_height, end_height)
-            .map_err(JsonrpseeError::to_call_error)
-            .await
+    module.register_async_method("getciphertext", |rpc_params, rpc_context| {
+        async move {
+            let commitment = rpc_params.parse::<[N::Commitment; 1]>()?[0];
+            rpc_context.get_ciphertext(commitment).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getblockheader", |rpc_params, rpc_context| async move {
-        let height = rpc_params.parse::<[u32; 1]>()?[0];
-        rpc_context.get_block_header(height).map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getciphertexts", |rpc_params, rpc_context| {
+        async move {
+            let commitments = std::mem::take(&mut rpc_params.parse::<[Vec<N::Commitment>; 1]>()?[0]);
+            rpc_context
+                .get_ciphertexts(commitments)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getblocktemplate", |_rpc_params, rpc_context| async move {
-        rpc_context.get_block_template().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getledgerproof", |rpc_params, rpc_context| {
+        async move {
+            let commitment = rpc_params.parse::<[N::Commitment; 1]>()?[0];
+            rpc_context
+                .get_ledger_proof(commitment)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getblocktransactions", |rpc_params, rpc_context| async move {
-        let height = rpc_params.parse::<[u32; 1]>()?[0];
-        rpc_context
-            .get_block_transactions(height)
-            .map_err(JsonrpseeError::to_call_error)
-            .await
+    module.register_async_method("getledgerproofs", |rpc_params, rpc_context| {
+        async move {
+            let commitments = std::mem::take(&mut rpc_params.parse::<[Vec<N::Commitment>; 1]>()?[0]);
+            rpc_context
+                .get_ledger_proofs(commitments)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getciphertext", |rpc_params, rpc_context| async move {
-        let commitment = rpc_params.parse::<[N::Commitment; 1]>()?[0];
-        rpc_context.get_ciphertext(commitment).map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getmemorypool", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_memory_pool().map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getledgerproof", |rpc_params, rpc_context| async move {
-        let commitment = rpc_params.parse::<[N::Commitment; 1]>()?[0];
-        rpc_context
-            .get_ledger_proof(commitment)
-            .map_err(JsonrpseeError::to_call_error)
-            .await
+    module.register_async_method("getmempoolinfo", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_mempool_info().map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getmemorypool", |_rpc_params, rpc_context| async move {
-        rpc_context.get_memory_pool().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("estimatefee", |rpc_params, rpc_context| {
+        async move {
+            let confirmation_target = rpc_params.parse::<[u32; 1]>()?[0];
+            rpc_context.estimate_fee(confirmation_target).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("gettransaction", |rpc_params, rpc_context| async move {
-        let id = rpc_params.parse::<[N::TransactionID; 1]>()?[0];
-        rpc_context.get_transaction(id).map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("gettransaction", |rpc_params, rpc_context| {
+        async move {
+            let id = rpc_params.parse::<[N::TransactionID; 1]>()?[0];
+            rpc_context.get_transaction(id).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("gettransition", |rpc_params, rpc_context| async move {
-        let id = rpc_params.parse::<[N::TransitionID; 1]>()?[0];
-        rpc_context.get_transition(id).map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("gettransactionsforaddress", |rpc_params, rpc_context| {
+        async move {
+            let (address, start_height, end_height) = rpc_params.parse::<(Address<N>, u32, u32)>()?;
+            rpc_context
+                .get_transactions_for_address(address, start_height, end_height)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getconnectedpeers", |_rpc_params, rpc_context| async move {
-        rpc_context.get_connected_peers().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("gettransition", |rpc_params, rpc_context| {
+        async move {
+            let id = rpc_params.parse::<[N::TransitionID; 1]>()?[0];
+            rpc_context.get_transition(id).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getnodestate", |_rpc_params, rpc_context| async move {
-        rpc_context.get_node_state().map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getconnectedpeers", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_connected_peers().map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("sendtransaction", |rpc_params, rpc_context| async move {
-        let string = std::mem::take(&mut rpc_params.parse::<[String; 1]>()?[0]);
-        rpc_context.send_transaction(string).map_err(JsonrpseeError::to_call_error).await
+    module.register_async_method("getpeerinfo", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_peer_info().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("getnetworktime", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_network_time().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("getnodestate", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_node_state().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("getsyncstatus", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_sync_status().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("sendtransaction", |rpc_params, rpc_context| {
+        async move {
+            let string = std::mem::take(&mut rpc_params.parse::<[String; 1]>()?[0]);
+            rpc_context.send_transaction(string).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("sendtransactions", |rpc_params, rpc_context| {
+        async move {
+            let transactions = std::mem::take(&mut rpc_params.parse::<[Vec<String>; 1]>()?[0]);
+            rpc_context.send_transactions(transactions).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("validatetransaction", |rpc_params, rpc_context| {
+        async move {
+            let transaction_hex = std::mem::take(&mut rpc_params.parse::<[String; 1]>()?[0]);
+            rpc_context
+                .validate_transaction(transaction_hex)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("decodetransaction", |rpc_params, rpc_context| {
+        async move {
+            let transaction_hex = std::mem::take(&mut rpc_params.parse::<[String; 1]>()?[0]);
+            rpc_context
+                .decode_transaction(transaction_hex)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("submitblock", |rpc_params, rpc_context| {
+        async move {
+            let block_hex = std::mem::take(&mut rpc_params.parse::<[String; 1]>()?[0]);
+            rpc_context.submit_block(block_hex).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
     // Private methods.
@@ -328,44 +812,440 @@ fn create_rpc_module<N: Network, E: Environment>(rpc_context: RpcContext<N, E>)
     //     result_to_response(&req, result)
     // }
 
-    module.register_async_method("connect", |_rpc_params, rpc_context| async move {
-        let addresses = _rpc_params.parse::<Vec<String>>()?;
-        rpc_context.connect(addresses).map_err(JsonrpseeError::to_call_error).await
+    module.register_guarded_method("profilecpu", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, seconds) = rpc_params.parse::<(IgnoredAny, u64)>()?;
+            rpc_context.profile_cpu(seconds).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("profileheap", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            rpc_params.one::<IgnoredAny>()?;
+            rpc_context.profile_heap().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("createlease", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, renter, basis_points, duration_in_secs) = rpc_params.parse::<(IgnoredAny, Address<N>, u16, u64)>()?;
+            rpc_context
+                .create_lease(renter, basis_points, duration_in_secs)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getsharesforprover", |_rpc_params, rpc_context| async move {
-        let prover = _rpc_params.parse::<[Address<N>; 1]>()?[0];
-        rpc_context
-            .get_shares_for_prover(prover)
-            .map_err(JsonrpseeError::to_call_error)
-            .await
+    module.register_guarded_method("cancellease", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, lease_id) = rpc_params.parse::<(IgnoredAny, u64)>()?;
+            rpc_context.cancel_lease(lease_id).map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getshares", |_rpc_params, rpc_context| async move {
-        let shares = rpc_context.get_shares().await;
-        Ok(shares)
+    module.register_guarded_method("replaypayouts", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, block_height, sub_pools) = rpc_params.parse::<(IgnoredAny, u32, Option<Vec<SubPool<N>>>)>()?;
+            rpc_context
+                .replay_payouts(block_height, sub_pools)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getprovers", |_rpc_params, rpc_context| async move {
-        let provers = rpc_context.get_provers().await;
-        Ok(provers)
+    module.register_guarded_method("selectpayoutrecords", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, amount, records) = rpc_params.parse::<(IgnoredAny, u64, Vec<(u32, Record<N>)>)>()?;
+            rpc_context
+                .select_payout_records(amount, records)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
     })?;
 
-    module.register_async_method("getminedblockinfo", |_rpc_params, rpc_context| async move {
-        let (height, block_hash) = _rpc_params.parse::<(u32, N::BlockHash)>()?;
-        rpc_context
-            .get_mined_block_info(height, block_hash)
-            .map_err(JsonrpseeError::to_call_error)
-            .await
+    module.register_guarded_method("getpendingpayouts", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            rpc_params.one::<IgnoredAny>()?;
+            rpc_context.get_pending_payouts().map_err(JsonrpseeError::to_call_error).await
+        }
     })?;
 
-    module.register_async_method("getblockheaderroot", |_rpc_params, rpc_context| async move {
-        let height = _rpc_params.parse::<[u32; 1]>()?[0];
-        rpc_context
-            .get_block_header_root(height)
-            .map_err(JsonrpseeError::to_call_error)
-            .await
+    module.register_guarded_method("completepayout", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, block_height, block_hash, index, transaction_id) =
+                rpc_params.parse::<(IgnoredAny, u32, N::BlockHash, usize, N::TransactionID)>()?;
+            rpc_context
+                .complete_payout(block_height, block_hash, index, transaction_id)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_guarded_method("decryptrecord", RpcRole::Wallet, |rpc_params, rpc_context| {
+        async move {
+            let (_, record, view_key) = rpc_params.parse::<(IgnoredAny, String, String)>()?;
+            rpc_context
+                .decrypt_record(record, view_key)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_guarded_method("getunspentrecords", RpcRole::Wallet, |rpc_params, rpc_context| {
+        async move {
+            let (_, view_key, compute_key, start_block_height, end_block_height) =
+                rpc_params.parse::<(IgnoredAny, String, String, u32, u32)>()?;
+            rpc_context
+                .get_unspent_records(view_key, compute_key, start_block_height, end_block_height)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_guarded_method("getapiusage", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            // The limit is optional, and defaults to 100 clients.
+            let mut rpc_params = rpc_params.sequence();
+            let _credentials = rpc_params.next::<IgnoredAny>()?;
+            let limit = rpc_params.optional_next::<usize>()?;
+            rpc_context.get_api_usage(limit).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("banpeer", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            // The duration is optional, and the ban is indefinite without it.
+            let mut rpc_params = rpc_params.sequence();
+            let _credentials = rpc_params.next::<IgnoredAny>()?;
+            let ip = rpc_params.next::<String>()?;
+            let duration_in_secs = rpc_params.optional_next::<u64>()?;
+            rpc_context
+                .ban_peer(ip, duration_in_secs)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_guarded_method("unbanpeer", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, ip) = rpc_params.parse::<(IgnoredAny, String)>()?;
+            rpc_context.unban_peer(ip).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("disconnect", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            // The peer is not restricted unless requested.
+            let mut rpc_params = rpc_params.sequence();
+            let _credentials = rpc_params.next::<IgnoredAny>()?;
+            let peer_ip = rpc_params.next::<SocketAddr>()?;
+            let is_restricted = rpc_params.optional_next::<bool>()?.unwrap_or_default();
+            rpc_context
+                .disconnect(peer_ip, is_restricted)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_guarded_method("connect", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, addresses) = rpc_params.parse::<(IgnoredAny, Vec<String>)>()?;
+            rpc_context.connect(addresses).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("stop", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            rpc_params.one::<IgnoredAny>()?;
+            rpc_context.stop().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("restart", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            rpc_params.one::<IgnoredAny>()?;
+            rpc_context.restart().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("maintenance", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            // The provers are not migrated unless a backup pool is given.
+            let mut rpc_params = rpc_params.sequence();
+            let _credentials = rpc_params.next::<IgnoredAny>()?;
+            let backup_ip = rpc_params.optional_next::<SocketAddr>()?;
+            rpc_context.maintenance(backup_ip).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_guarded_method("setloglevel", RpcRole::Admin, |rpc_params, rpc_context| {
+        async move {
+            let (_, filter) = rpc_params.parse::<(IgnoredAny, String)>()?;
+            rpc_context.set_log_level(filter).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("getsharesforprover", |_rpc_params, rpc_context| {
+        async move {
+            let prover = _rpc_params.parse::<[Address<N>; 1]>()?[0];
+            rpc_context
+                .get_shares_for_prover(prover)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getmyshares", |rpc_params, rpc_context| {
+        async move {
+            let (prover, from_ts, to_ts) = rpc_params.parse::<(Address<N>, i64, i64)>()?;
+            rpc_context
+                .get_my_shares(prover, from_ts, to_ts)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("updatepayoutaddress", |rpc_params, rpc_context| {
+        async move {
+            let (prover, payout_address, effective_from, signature) =
+                rpc_params.parse::<(Address<N>, Address<N>, u32, N::AccountSignature)>()?;
+            rpc_context
+                .update_payout_address(prover, payout_address, effective_from, signature)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getpayoutschedule", |rpc_params, rpc_context| {
+        async move {
+            let prover = rpc_params.one::<Address<N>>()?;
+            rpc_context.get_payout_schedule(prover).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("updatepayoutschedule", |rpc_params, rpc_context| {
+        async move {
+            let (prover, minimum_payout, payout_interval, effective_from, signature) =
+                rpc_params.parse::<(Address<N>, u64, u32, u32, N::AccountSignature)>()?;
+            rpc_context
+                .update_payout_schedule(prover, minimum_payout, payout_interval, effective_from, signature)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getshares", |_rpc_params, rpc_context| {
+        async move {
+            let shares = rpc_context.get_shares().await;
+            Ok(shares)
+        }
+    })?;
+
+    module.register_async_method("getprovers", |_rpc_params, rpc_context| {
+        async move {
+            let provers = rpc_context.get_provers().await;
+            Ok(provers)
+        }
+    })?;
+
+    module.register_async_method("getsubpools", |_rpc_params, rpc_context| {
+        async move {
+            let sub_pools = rpc_context.get_sub_pools().await;
+            Ok(sub_pools)
+        }
+    })?;
+
+    module.register_async_method("getpoolstats", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context.get_pool_stats().map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("getpoolinfo", |_rpc_params, rpc_context| {
+        async move {
+            let pool_info = rpc_context.get_pool_info().await;
+            Ok(pool_info)
+        }
+    })?;
+
+    module.register_async_method("getleaderboard", |rpc_params, rpc_context| {
+        async move {
+            // The timestamp is optional, and defaults to the current epoch of the period.
+            let mut rpc_params = rpc_params.sequence();
+            let period = rpc_params.next::<ContributionPeriod>()?;
+            let timestamp = rpc_params.optional_next::<i64>()?;
+            rpc_context
+                .get_leaderboard(period, timestamp)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("estimateearnings", |rpc_params, rpc_context| {
+        async move {
+            let (proof_rate, window_in_secs) = rpc_params.parse::<(f64, u64)>()?;
+            rpc_context
+                .estimate_earnings(proof_rate, window_in_secs)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getnetworkdifficultyestimate", |_rpc_params, rpc_context| {
+        async move {
+            rpc_context
+                .get_network_difficulty_estimate()
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getnetworkstats", |rpc_params, rpc_context| {
+        async move {
+            // The window is optional, so the endpoint may still be called without parameters.
+            let window = rpc_params.sequence().optional_next::<u32>()?;
+            rpc_context.get_network_stats(window).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("getcoinbaserewardschedule", |rpc_params, rpc_context| {
+        async move {
+            // The block height is optional, and defaults to the latest block height.
+            let block_height = rpc_params.sequence().optional_next::<u32>()?;
+            rpc_context
+                .get_coinbase_reward_schedule(block_height)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_long_poll_method("getpooljob", |rpc_params, rpc_context| {
+        async move {
+            let (prover, known_block_height, timestamp, signature) =
+                rpc_params.parse::<(Address<N>, Option<u32>, i64, N::AccountSignature)>()?;
+            rpc_context
+                .get_pool_job(prover, known_block_height, timestamp, signature)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("submitshare", |rpc_params, rpc_context| {
+        async move {
+            let (prover, nonce, proof, timestamp) = rpc_params.parse::<(Address<N>, String, String, i64)>()?;
+            rpc_context
+                .submit_share(prover, nonce, proof, timestamp)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getleases", |_rpc_params, rpc_context| {
+        async move {
+            let leases = rpc_context.get_leases().await;
+            Ok(leases)
+        }
+    })?;
+
+    module.register_async_method("getauditevents", |rpc_params, rpc_context| {
+        async move {
+            let after_id = rpc_params.sequence().optional_next::<u64>()?;
+            let events = rpc_context.get_audit_events(after_id).await;
+            Ok(events)
+        }
+    })?;
+
+    module.register_async_method("getblocksbytag", |rpc_params, rpc_context| {
+        async move {
+            let tag = rpc_params.one::<String>()?;
+            let blocks = rpc_context.get_blocks_by_tag(tag).await;
+            Ok(blocks)
+        }
+    })?;
+
+    module.register_async_method("getfoundblock", |rpc_params, rpc_context| {
+        async move {
+            let block_height = rpc_params.one::<u32>()?;
+            rpc_context.get_found_block(block_height).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("getminedblockinfo", |_rpc_params, rpc_context| {
+        async move {
+            let (height, block_hash) = _rpc_params.parse::<(u32, N::BlockHash)>()?;
+            rpc_context
+                .get_mined_block_info(height, block_hash)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getblockheaderroot", |_rpc_params, rpc_context| {
+        async move {
+            let height = _rpc_params.parse::<[u32; 1]>()?[0];
+            rpc_context
+                .get_block_header_root(height)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getcanonicalblock", |rpc_params, rpc_context| {
+        async move {
+            let [block_height, schema_version]: [u32; 2] = rpc_params.parse()?;
+            rpc_context
+                .get_canonical_block(block_height, schema_version)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getcanonicalblockheader", |rpc_params, rpc_context| {
+        async move {
+            let [block_height, schema_version]: [u32; 2] = rpc_params.parse()?;
+            rpc_context
+                .get_canonical_block_header(block_height, schema_version)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("getcanonicaltransaction", |rpc_params, rpc_context| {
+        async move {
+            let (transaction_id, schema_version) = rpc_params.parse::<(N::TransactionID, u32)>()?;
+            rpc_context
+                .get_canonical_transaction(transaction_id, schema_version)
+                .map_err(JsonrpseeError::to_call_error)
+                .await
+        }
+    })?;
+
+    module.register_async_method("search", |rpc_params, rpc_context| {
+        async move {
+            let query = std::mem::take(&mut rpc_params.parse::<[String; 1]>()?[0]);
+            rpc_context.search(query).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    module.register_async_method("validateaddress", |rpc_params, rpc_context| {
+        async move {
+            let address = std::mem::take(&mut rpc_params.parse::<[String; 1]>()?[0]);
+            rpc_context.validate_address(address).map_err(JsonrpseeError::to_call_error).await
+        }
+    })?;
+
+    // Subscriptions, which are only served by the RPC WebSocket server.
+
+    module.register_subscription("subscribe", "subscription", "unsubscribe", |rpc_params, sink, rpc_context| {
+        let (kind, full) = rpc_params.parse::<(String, Option<bool>)>()?;
+        match kind.as_str() {
+            "pendingTransactions" => {
+                rpc_context.subscribe_pending_transactions(sink, full.unwrap_or(false));
+                Ok(())
+            }
+            _ => Err(JsonrpseeError::Custom(format!("Unknown subscription '{}'", kind))),
+        }
     })?;
 
-    Ok(module)
+    Ok(module.module)
 }
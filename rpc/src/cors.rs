@@ -0,0 +1,174 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Cross-origin resource sharing (CORS) for the RPC HTTP server, so browser-based dashboards may call the node.
+
+use anyhow::{anyhow, Result};
+use hyper::{
+    header::{
+        HeaderName,
+        HeaderValue,
+        ACCESS_CONTROL_ALLOW_HEADERS,
+        ACCESS_CONTROL_ALLOW_METHODS,
+        ACCESS_CONTROL_ALLOW_ORIGIN,
+        ACCESS_CONTROL_MAX_AGE,
+        ACCESS_CONTROL_REQUEST_HEADERS,
+        ACCESS_CONTROL_REQUEST_METHOD,
+        ORIGIN,
+        VARY,
+    },
+    Body,
+    HeaderMap,
+    Method,
+    Request,
+    Response,
+    StatusCode,
+};
+use std::str::FromStr;
+
+/// The number of seconds for which a browser may cache the result of a preflight request.
+const PREFLIGHT_MAX_AGE: u32 = 3600;
+
+///
+/// The CORS policy of the RPC HTTP server, which lists the origins, methods, and headers that browsers may use to call it.
+///
+#[derive(Clone, Debug)]
+pub struct RpcCors {
+    /// The origins allowed to call the RPC server, or `None` if any origin is allowed.
+    allowed_origins: Option<Vec<HeaderValue>>,
+    /// The HTTP methods allowed in cross-origin requests.
+    allowed_methods: Vec<Method>,
+    /// The request headers allowed in cross-origin requests.
+    allowed_headers: Vec<HeaderName>,
+}
+
+impl RpcCors {
+    ///
+    /// Initializes a new CORS policy from the given origins, HTTP methods, and request headers.
+    /// The origin `*` allows any origin.
+    ///
+    pub fn new(allowed_origins: &[String], allowed_methods: &[String], allowed_headers: &[String]) -> Result<Self> {
+        if allowed_origins.is_empty() {
+            return Err(anyhow!("The CORS policy of the RPC server requires at least one allowed origin"));
+        }
+        let allowed_origins = match allowed_origins.iter().any(|origin| origin == "*") {
+            true => None,
+            false => Some(
+                allowed_origins
+                    .iter()
+                    .map(|origin| {
+                        HeaderValue::from_str(origin.trim_end_matches('/')).map_err(|_| anyhow!("Invalid CORS origin '{}'", origin))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+        };
+        let allowed_methods = allowed_methods
+            .iter()
+            .map(|method| Method::from_str(&method.to_uppercase()).map_err(|_| anyhow!("Invalid CORS method '{}'", method)))
+            .collect::<Result<_>>()?;
+        let allowed_headers = allowed_headers
+            .iter()
+            .map(|header| HeaderName::from_str(header).map_err(|_| anyhow!("Invalid CORS header '{}'", header)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            allowed_origins,
+            allowed_methods,
+            allowed_headers,
+        })
+    }
+
+    ///
+    /// Returns `true` if the given origin may call the RPC server.
+    ///
+    pub fn is_allowed_origin(&self, origin: &HeaderValue) -> bool {
+        match &self.allowed_origins {
+            Some(allowed_origins) => allowed_origins.contains(origin),
+            None => true,
+        }
+    }
+
+    ///
+    /// Returns `true` if the given request is a CORS preflight request, which asks whether a cross-origin request is allowed.
+    ///
+    pub fn is_preflight(request: &Request<Body>) -> bool {
+        request.method() == Method::OPTIONS
+            && request.headers().contains_key(ORIGIN)
+            && request.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+    }
+
+    ///
+    /// Returns the response to the given preflight request from an allowed origin,
+    /// which is `403 Forbidden` if the requested method or headers are not allowed.
+    ///
+    pub fn preflight_response(&self, origin: &HeaderValue, request: &Request<Body>) -> Response<Body> {
+        let headers = request.headers();
+        let is_allowed_method = headers
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok())
+            .map_or(false, |method| self.allowed_methods.contains(&method));
+        let is_allowed_headers = headers
+            .get_all(ACCESS_CONTROL_REQUEST_HEADERS)
+            .iter()
+            .filter_map(|names| names.to_str().ok())
+            .flat_map(|names| names.split(','))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .all(|name| {
+                self.allowed_headers
+                    .iter()
+                    .any(|allowed| allowed.as_str().eq_ignore_ascii_case(name))
+            });
+
+        let mut response = Response::new(Body::empty());
+        if !is_allowed_method || !is_allowed_headers {
+            *response.status_mut() = StatusCode::FORBIDDEN;
+            return response;
+        }
+        *response.status_mut() = StatusCode::NO_CONTENT;
+
+        let headers = response.headers_mut();
+        self.add_headers(origin, headers);
+        let allowed_methods = join_header_values(self.allowed_methods.iter().map(Method::as_str));
+        let allowed_headers = join_header_values(self.allowed_headers.iter().map(HeaderName::as_str));
+        headers.insert(ACCESS_CONTROL_ALLOW_METHODS, allowed_methods);
+        headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allowed_headers);
+        headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(PREFLIGHT_MAX_AGE));
+        response
+    }
+
+    ///
+    /// Adds the headers which allow the given origin to read a response.
+    ///
+    pub fn add_headers(&self, origin: &HeaderValue, headers: &mut HeaderMap) {
+        match self.allowed_origins {
+            Some(_) => {
+                headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+                // The response depends on the origin, so it may not be cached for other origins.
+                headers.insert(VARY, HeaderValue::from_static("origin"));
+            }
+            None => {
+                headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+            }
+        }
+    }
+}
+
+/// Returns the given values as a comma-separated header value.
+fn join_header_values<'a>(values: impl Iterator<Item = &'a str>) -> HeaderValue {
+    let values = values.collect::<Vec<_>>().join(", ");
+    HeaderValue::from_str(&values).expect("The methods and header names are valid header values")
+}
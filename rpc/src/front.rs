@@ -0,0 +1,678 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The listener in front of the RPC servers, which terminates TLS, rate limits the clients, records their usage,
+//! audits their calls to the guarded methods, applies the CORS policy, compresses the responses, and serves HTTP/2.
+
+use crate::{
+    rest::rest_method,
+    RpcAuditLog,
+    RpcCompression,
+    RpcCors,
+    RpcHttpConfig,
+    RpcRateLimiter,
+    RpcTlsAcceptor,
+    RpcUsageTracker,
+    HTTP2_KEEP_ALIVE_INTERVAL,
+    MAX_REQUEST_BODY_SIZE,
+};
+
+use hyper::{
+    body::{Bytes, HttpBody},
+    client::HttpConnector,
+    header::{HeaderValue, CONTENT_TYPE, ORIGIN},
+    http::request::Parts,
+    server::conn::Http,
+    service::service_fn,
+    Body,
+    Client,
+    Request,
+    Response,
+    StatusCode,
+    Version,
+};
+use std::{
+    convert::Infallible,
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{copy_bidirectional, AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, ReadBuf},
+    net::{TcpListener, TcpStream},
+};
+
+/// The JSON-RPC error code of a request rejected by the rate limiter.
+const RATE_LIMITED_CODE: i32 = -32005;
+/// The maximum size of the opening handshake of a WebSocket client.
+const MAX_WEBSOCKET_HANDSHAKE_SIZE: usize = 16 * 1024;
+
+///
+/// The kind of the server behind an RPC listener, which determines how the requests of its clients are inspected.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RpcServerKind {
+    /// The RPC HTTP server, whose requests are limited by the methods of their JSON-RPC calls.
+    Http,
+    /// The RPC WebSocket server, whose messages are limited as they are relayed.
+    WebSocket,
+    /// The REST gateway, whose requests are limited by the RPC methods of their routes.
+    Rest,
+}
+
+///
+/// The listener of an RPC server, which accepts the connections of RPC clients in place of the server,
+/// terminates TLS, rate limits each client IP, records the usage and audits the guarded calls of each client, and applies
+/// the CORS policy, then forwards the requests to the server on the loopback interface, compressing its responses for
+/// the clients which accept it.
+///
+pub(crate) struct RpcFront {
+    listener: TcpListener,
+    tls_acceptor: Option<RpcTlsAcceptor>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+    usage: Option<Arc<RpcUsageTracker>>,
+    audit_log: Option<Arc<RpcAuditLog>>,
+    cors: Option<Arc<RpcCors>>,
+    compression: Option<RpcCompression>,
+    http_config: Option<RpcHttpConfig>,
+    /// The kind of the server behind the listener.
+    kind: RpcServerKind,
+}
+
+impl RpcFront {
+    ///
+    /// Binds the listener at the given address, if TLS, rate limiting, usage statistics, the audit log, CORS, compression,
+    /// or the HTTP settings are enabled.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn bind(
+        rpc_server_addr: SocketAddr,
+        tls_acceptor: Option<RpcTlsAcceptor>,
+        rate_limiter: Option<Arc<RpcRateLimiter>>,
+        usage: Option<Arc<RpcUsageTracker>>,
+        audit_log: Option<Arc<RpcAuditLog>>,
+        cors: Option<Arc<RpcCors>>,
+        compression: Option<RpcCompression>,
+        http_config: Option<RpcHttpConfig>,
+        kind: RpcServerKind,
+    ) -> Option<Self> {
+        if tls_acceptor.is_none()
+            && rate_limiter.is_none()
+            && usage.is_none()
+            && audit_log.is_none()
+            && cors.is_none()
+            && compression.is_none()
+            && http_config.is_none()
+        {
+            return None;
+        }
+        // Negotiate HTTP/2 with the clients of an HTTP server over TLS, if it is served.
+        let tls_acceptor = match http_config {
+            Some(http_config) if http_config.http2 && kind != RpcServerKind::WebSocket => {
+                tls_acceptor.as_ref().map(RpcTlsAcceptor::with_http2)
+            }
+            _ => tls_acceptor,
+        };
+        let listener = TcpListener::bind(rpc_server_addr).await.expect("Failed to bind the RPC listener");
+        Some(Self {
+            listener,
+            tls_acceptor,
+            rate_limiter,
+            usage,
+            audit_log,
+            cors,
+            compression,
+            http_config,
+            kind,
+        })
+    }
+
+    ///
+    /// Returns the address that RPC clients connect to.
+    ///
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.listener.local_addr().expect("Can't obtain the RPC listener's local address")
+    }
+
+    ///
+    /// Accepts the connections of RPC clients, and forwards each to the RPC server at the given local address.
+    ///
+    pub(crate) async fn serve(self, server_addr: SocketAddr) {
+        let front = Arc::new(self);
+        let client = Client::new();
+        loop {
+            let (stream, peer_addr) = match front.listener.accept().await {
+                Ok(connection) => connection,
+                Err(error) => {
+                    warn!("Failed to accept an RPC connection: {}", error);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
+
+            // Serve the connection in its own task, so a slow client does not stall the others.
+            let (front, client) = (front.clone(), client.clone());
+            tokio::spawn(async move {
+                match &front.tls_acceptor {
+                    Some(tls_acceptor) => {
+                        if let Some(stream) = tls_acceptor.accept(stream, peer_addr).await {
+                            RpcFront::forward_connection(front, stream, peer_addr, server_addr, client).await;
+                        }
+                    }
+                    None => RpcFront::forward_connection(front, stream, peer_addr, server_addr, client).await,
+                }
+            });
+        }
+    }
+
+    ///
+    /// Forwards the connection of the given RPC client to the RPC server, rate limiting its requests if enabled.
+    ///
+    async fn forward_connection<S: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
+        front: Arc<Self>,
+        mut stream: S,
+        peer_addr: SocketAddr,
+        server_addr: SocketAddr,
+        client: Client<HttpConnector>,
+    ) {
+        let is_forwarded_as_is = front.rate_limiter.is_none()
+            && front.usage.is_none()
+            && front.audit_log.is_none()
+            && front.cors.is_none()
+            && front.compression.is_none()
+            && front.http_config.is_none();
+        match front.kind == RpcServerKind::WebSocket || is_forwarded_as_is {
+            // The requests of an HTTP client are inspected, so each call is limited by its method and checked against the CORS policy.
+            false => {
+                let http_config = front.http_config.unwrap_or_default();
+                let mut http = Http::new();
+                match http_config.http2 {
+                    // Serve HTTP/2 to clients with prior knowledge or via ALPN, and HTTP/1.1 to the others.
+                    true => http.http2_keep_alive_interval(HTTP2_KEEP_ALIVE_INTERVAL),
+                    false => http.http1_only(true),
+                };
+
+                let service = service_fn(move |request| forward_request(front.clone(), request, peer_addr, server_addr, client.clone()));
+                let result = match http_config.idle_timeout {
+                    Some(idle_timeout) => {
+                        let stream = ActivityStream::new(stream);
+                        let activity = stream.activity.clone();
+                        let connection = http.serve_connection(stream, service);
+                        tokio::pin!(connection);
+                        loop {
+                            let idle_deadline = activity.last() + idle_timeout;
+                            tokio::select! {
+                                result = &mut connection => break result,
+                                _ = tokio::time::sleep_until(idle_deadline.into()) => {
+                                    // Close the connection once it has been idle, after its current response if any.
+                                    if activity.last() + idle_timeout <= Instant::now() {
+                                        trace!("Closing the idle RPC connection of {}", peer_addr);
+                                        connection.as_mut().graceful_shutdown();
+                                        break connection.await;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    None => http.serve_connection(stream, service).await,
+                };
+                if let Err(error) = result {
+                    debug!("Failed to serve the RPC connection of {}: {}", peer_addr, error);
+                }
+            }
+            true => {
+                // Opening a WebSocket connection counts as a single call, so clients can't flood the server with connections.
+                if let Some(rate_limiter) = &front.rate_limiter {
+                    if !rate_limiter.check_connection(peer_addr.ip()) {
+                        debug!("Rate limited the RPC WebSocket connection of {}", peer_addr);
+                        return;
+                    }
+                }
+                let mut server_stream = match TcpStream::connect(server_addr).await {
+                    Ok(server_stream) => server_stream,
+                    Err(error) => {
+                        warn!("Failed to forward the RPC connection of {}: {}", peer_addr, error);
+                        return;
+                    }
+                };
+                let result = match &front.rate_limiter {
+                    // The messages of a WebSocket client are inspected, so each call is limited by its method.
+                    Some(rate_limiter) if front.kind == RpcServerKind::WebSocket => {
+                        relay_websocket(rate_limiter, stream, server_stream, peer_addr).await
+                    }
+                    _ => copy_bidirectional(&mut stream, &mut server_stream).await.map(|_| ()),
+                };
+                if let Err(error) = result {
+                    debug!("Failed to forward the RPC connection of {}: {}", peer_addr, error);
+                }
+            }
+        }
+    }
+}
+
+///
+/// Relays the WebSocket connection of the given RPC client to the RPC server, checking each message of the client
+/// against its rate limit. The connection is closed once a message exceeds the rate limit of the client.
+///
+async fn relay_websocket<S: AsyncRead + AsyncWrite + Unpin>(
+    rate_limiter: &RpcRateLimiter,
+    stream: S,
+    server_stream: TcpStream,
+    peer_addr: SocketAddr,
+) -> io::Result<()> {
+    let (client_reader, mut client_writer) = tokio::io::split(stream);
+    let (mut server_reader, mut server_writer) = server_stream.into_split();
+
+    let upstream = async {
+        let mut client_reader = BufReader::new(client_reader);
+        // Forward the opening handshake of the client as is.
+        let handshake = read_handshake(&mut client_reader).await?;
+        server_writer.write_all(&handshake).await?;
+
+        // The raw frames of the current message, and its unmasked payload.
+        let (mut frames, mut message) = (Vec::new(), Vec::new());
+        while let Some(frame) = read_frame(&mut client_reader).await? {
+            // Forward the control frames at once, as they may be interleaved with the frames of a message.
+            if frame.opcode >= 8 {
+                server_writer.write_all(&frame.raw).await?;
+                continue;
+            }
+            if message.len() + frame.payload.len() > MAX_REQUEST_BODY_SIZE as usize {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "The WebSocket message is too large"));
+            }
+            frames.extend_from_slice(&frame.raw);
+            message.extend_from_slice(&frame.payload);
+            if frame.is_final {
+                if !rate_limiter.check_request(peer_addr.ip(), &message) {
+                    debug!("Rate limited an RPC WebSocket message of {}", peer_addr);
+                    return Ok(());
+                }
+                server_writer.write_all(&frames).await?;
+                frames.clear();
+                message.clear();
+            }
+        }
+        Ok(())
+    };
+    let downstream = tokio::io::copy(&mut server_reader, &mut client_writer);
+
+    tokio::select! {
+        result = upstream => result,
+        result = downstream => result.map(|_| ()),
+    }
+}
+
+///
+/// Reads the opening handshake of a WebSocket client, up to and including the empty line which ends its headers.
+///
+async fn read_handshake<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut handshake = Vec::new();
+    loop {
+        let start = handshake.len();
+        if reader.read_until(b'\n', &mut handshake).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if handshake.len() > MAX_WEBSOCKET_HANDSHAKE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "The WebSocket handshake is too large"));
+        }
+        if handshake[start..] == b"\r\n"[..] {
+            return Ok(handshake);
+        }
+    }
+}
+
+///
+/// A frame of a WebSocket message, as read from a client.
+///
+struct WebSocketFrame {
+    /// `true` if the frame is the final frame of its message.
+    is_final: bool,
+    /// The opcode of the frame, which is 8 or above for a control frame.
+    opcode: u8,
+    /// The frame as read, to be forwarded as is.
+    raw: Vec<u8>,
+    /// The unmasked payload of the frame.
+    payload: Vec<u8>,
+}
+
+///
+/// Reads the next WebSocket frame of a client, returning `None` if the client closed the connection.
+///
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Option<WebSocketFrame>> {
+    let mut header = [0u8; 2];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => (),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let mut raw = header.to_vec();
+    let length = match header[1] & 0x7f {
+        126 => {
+            let mut length = [0u8; 2];
+            reader.read_exact(&mut length).await?;
+            raw.extend_from_slice(&length);
+            u16::from_be_bytes(length) as u64
+        }
+        127 => {
+            let mut length = [0u8; 8];
+            reader.read_exact(&mut length).await?;
+            raw.extend_from_slice(&length);
+            u64::from_be_bytes(length)
+        }
+        length => length as u64,
+    };
+    if length > MAX_REQUEST_BODY_SIZE as u64 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "The WebSocket frame is too large"));
+    }
+
+    // The frames of a client are masked; an unmasked frame is forwarded as is, and rejected by the server.
+    let mut mask = [0u8; 4];
+    if header[1] & 0x80 != 0 {
+        reader.read_exact(&mut mask).await?;
+        raw.extend_from_slice(&mask);
+    }
+    let start = raw.len();
+    raw.resize(start + length as usize, 0);
+    reader.read_exact(&mut raw[start..]).await?;
+    let payload = raw[start..].iter().enumerate().map(|(index, byte)| byte ^ mask[index % 4]).collect();
+
+    Ok(Some(WebSocketFrame {
+        is_final: header[0] & 0x80 != 0,
+        opcode: header[0] & 0x0f,
+        raw,
+        payload,
+    }))
+}
+
+///
+/// The time of the latest read or write of a connection, so it may be closed once idle.
+///
+struct Activity {
+    start: Instant,
+    elapsed_in_ms: AtomicU64,
+}
+
+impl Activity {
+    /// Records a read or write at the current time.
+    fn update(&self) {
+        self.elapsed_in_ms.store(self.start.elapsed().as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the time of the latest read or write.
+    fn last(&self) -> Instant {
+        self.start + Duration::from_millis(self.elapsed_in_ms.load(Ordering::Relaxed))
+    }
+}
+
+///
+/// A stream which records the time of its latest read or write.
+///
+struct ActivityStream<S> {
+    stream: S,
+    activity: Arc<Activity>,
+}
+
+impl<S> ActivityStream<S> {
+    fn new(stream: S) -> Self {
+        let activity = Activity {
+            start: Instant::now(),
+            elapsed_in_ms: AtomicU64::new(0),
+        };
+        Self {
+            stream,
+            activity: Arc::new(activity),
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for ActivityStream<S> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let result = Pin::new(&mut self.stream).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            self.activity.update();
+        }
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for ActivityStream<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let result = Pin::new(&mut self.stream).poll_write(cx, buf);
+        if let Poll::Ready(Ok(_)) = result {
+            self.activity.update();
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+///
+/// Answers the given HTTP request of an RPC client under the CORS policy, if enabled.
+/// Requests from disallowed origins are rejected, and preflight requests are answered by the listener itself.
+/// The response is compressed if compression is enabled, and the client accepts it.
+///
+async fn forward_request(
+    front: Arc<RpcFront>,
+    request: Request<Body>,
+    peer_addr: SocketAddr,
+    server_addr: SocketAddr,
+    client: Client<HttpConnector>,
+) -> Result<Response<Body>, Infallible> {
+    let origin = request.headers().get(ORIGIN).cloned();
+    let encoding = front.compression.and_then(|compression| Some((compression, RpcCompression::negotiate(request.headers())?)));
+    let cors = match (&front.cors, &origin) {
+        (Some(cors), Some(origin)) => Some((cors, origin)),
+        // Requests without an origin are not cross-origin requests from a browser.
+        _ => None,
+    };
+
+    let mut response = match cors {
+        Some((cors, origin)) if !cors.is_allowed_origin(origin) => {
+            debug!("Rejected an RPC request of {} from a disallowed origin", peer_addr);
+            return Ok(error_response(StatusCode::FORBIDDEN));
+        }
+        Some((cors, origin)) if RpcCors::is_preflight(&request) => return Ok(cors.preflight_response(origin, &request)),
+        _ => forward_call(&front, request, peer_addr, server_addr, client).await,
+    };
+    if let Some((cors, origin)) = cors {
+        cors.add_headers(origin, response.headers_mut());
+    }
+    if let Some((compression, encoding)) = encoding {
+        response = compression.compress(encoding, response).await;
+    }
+    Ok(response)
+}
+
+///
+/// Forwards the given HTTP request of an RPC client to the RPC server, unless its calls exceed the rate limit of the client,
+/// and records the usage of the client and audits its guarded calls, if enabled.
+///
+async fn forward_call(
+    front: &RpcFront,
+    request: Request<Body>,
+    peer_addr: SocketAddr,
+    server_addr: SocketAddr,
+    client: Client<HttpConnector>,
+) -> Response<Body> {
+    let started_at = Instant::now();
+    let (parts, body) = request.into_parts();
+    let body = match read_body(body).await {
+        Ok(Some(body)) => body,
+        Ok(None) => return error_response(StatusCode::PAYLOAD_TOO_LARGE),
+        Err(error) => {
+            debug!("Failed to read the RPC request of {}: {}", peer_addr, error);
+            return error_response(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let is_admitted = match (&front.rate_limiter, front.kind) {
+        (None, _) => true,
+        // A request to the REST gateway holds no JSON-RPC call, so it is limited by the RPC method of its route.
+        (Some(rate_limiter), RpcServerKind::Rest) => rate_limiter.check_method(peer_addr.ip(), rest_method(parts.uri.path())),
+        (Some(rate_limiter), _) => rate_limiter.check_request(peer_addr.ip(), &body),
+    };
+    let response = match is_admitted {
+        false => {
+            debug!("Rate limited an RPC request of {}", peer_addr);
+            rate_limited_response()
+        }
+        true => forward_body(parts, body.clone(), peer_addr, server_addr, client).await,
+    };
+    match front.usage.is_some() || front.audit_log.is_some() {
+        true => record_response(front, &body, response, peer_addr, started_at).await,
+        false => response,
+    }
+}
+
+///
+/// Forwards the given HTTP request of an RPC client, with the given body, to the RPC server.
+///
+async fn forward_body(
+    mut parts: Parts,
+    body: Bytes,
+    peer_addr: SocketAddr,
+    server_addr: SocketAddr,
+    client: Client<HttpConnector>,
+) -> Response<Body> {
+    let path = parts.uri.path_and_query().map(|path| path.as_str()).unwrap_or("/");
+    parts.uri = match format!("http://{}{}", server_addr, path).parse() {
+        Ok(uri) => uri,
+        Err(_) => return error_response(StatusCode::BAD_REQUEST),
+    };
+    // The RPC server is called over HTTP/1.1, whichever version the client called the listener with.
+    parts.version = Version::HTTP_11;
+    match client.request(Request::from_parts(parts, Body::from(body))).await {
+        Ok(response) => response,
+        Err(error) => {
+            warn!("Failed to forward the RPC request of {}: {}", peer_addr, error);
+            error_response(StatusCode::BAD_GATEWAY)
+        }
+    }
+}
+
+///
+/// Records the usage of the RPC client of the given request body, which was answered with the given response,
+/// and audits its calls to the guarded methods, if enabled.
+/// A streamed response, such as a block export, is counted as it streams, rather than read in full upfront.
+///
+async fn record_response(
+    front: &RpcFront,
+    request: &[u8],
+    response: Response<Body>,
+    peer_addr: SocketAddr,
+    started_at: Instant,
+) -> Response<Body> {
+    let (parts, mut body) = response.into_parts();
+    let audit = |response: &[u8]| {
+        if let Some(audit_log) = &front.audit_log {
+            audit_log.record_request(peer_addr.ip(), request, parts.status.as_u16(), response, started_at.elapsed());
+        }
+    };
+
+    if body.size_hint().exact().is_none() {
+        audit(&[]);
+        let usage = match &front.usage {
+            Some(usage) => usage.clone(),
+            None => return Response::from_parts(parts, body),
+        };
+        let client = usage.record_request(peer_addr.ip(), request, parts.status.is_success(), &[]);
+        let (mut sender, streamed_body) = Body::channel();
+        tokio::spawn(async move {
+            while let Some(chunk) = body.data().await {
+                match chunk {
+                    Ok(chunk) => {
+                        usage.record_bytes_sent(&client, chunk.len());
+                        if sender.send_data(chunk).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(error) => {
+                        debug!("Failed to stream the RPC response to {}: {}", peer_addr, error);
+                        sender.abort();
+                        return;
+                    }
+                }
+            }
+        });
+        return Response::from_parts(parts, streamed_body);
+    }
+
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(error) => {
+            warn!("Failed to read the RPC response to {}: {}", peer_addr, error);
+            return error_response(StatusCode::BAD_GATEWAY);
+        }
+    };
+    if let Some(usage) = &front.usage {
+        usage.record_request(peer_addr.ip(), request, parts.status.is_success(), &body);
+    }
+    audit(&body);
+    Response::from_parts(parts, Body::from(body))
+}
+
+///
+/// Reads the given request body, returning `None` if it exceeds the body size limit of the RPC servers.
+///
+async fn read_body(mut body: Body) -> Result<Option<Bytes>, hyper::Error> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > MAX_REQUEST_BODY_SIZE as usize {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(Some(bytes.into()))
+}
+
+///
+/// Returns an empty response with the given status.
+///
+fn error_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+///
+/// Returns the response to a request rejected by the rate limiter, with a JSON-RPC error.
+///
+fn rate_limited_response() -> Response<Body> {
+    let error = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": { "code": RATE_LIMITED_CODE, "message": "Rate limit exceeded" },
+        "id": null,
+    });
+    let mut response = error_response(StatusCode::TOO_MANY_REQUESTS);
+    *response.body_mut() = Body::from(error.to_string());
+    let content_type = HeaderValue::from_static("application/json");
+    response.headers_mut().insert(CONTENT_TYPE, content_type);
+    response
+}
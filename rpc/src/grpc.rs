@@ -0,0 +1,386 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! A gRPC service mirroring the public RPC endpoints, defined in `proto/node.proto`,
+//! for clients which are generated from protocol buffers rather than hand-written against JSON-RPC.
+
+use crate::{RpcContext, RpcError, RpcFunctions, RpcRateLimiter, RpcTlsAcceptor};
+use snarkos_environment::Environment;
+use snarkvm::dpc::prelude::*;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::server::TlsStream;
+use tonic::{
+    transport::{server::Connected, Server},
+    Request,
+    Response,
+    Status,
+};
+
+/// The types and the service generated from `proto/node.proto`.
+pub mod proto {
+    tonic::include_proto!("snarkos.v1");
+}
+
+use proto::node_server::{Node, NodeServer};
+
+/// The maximum number of accepted gRPC connections which are yet to be served.
+const MAXIMUM_PENDING_CONNECTIONS: usize = 64;
+
+/// Starts a local gRPC server at `grpc_server_addr` in a dedicated `tokio` task, which serves the public RPC endpoints
+/// of the given RPC context. As for the RPC HTTP server, TLS and rate limiting are applied if enabled, and the methods
+/// disabled by the method policy are refused. gRPC failures do not affect the rest of the node.
+pub async fn initialize_grpc_server<N: Network, E: Environment>(
+    grpc_server_addr: SocketAddr,
+    rpc_context: RpcContext<N, E>,
+    tls_acceptor: Option<RpcTlsAcceptor>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let listener = TcpListener::bind(grpc_server_addr).await.expect("Failed to bind the gRPC server");
+    let grpc_server_addr = listener.local_addr().expect("Failed to read the address of the gRPC server");
+
+    // Accept the connections in their own task, so a slow TLS handshake does not stall the others.
+    // gRPC is served over HTTP/2, which its clients negotiate via ALPN.
+    let tls_acceptor = tls_acceptor.as_ref().map(RpcTlsAcceptor::with_http2);
+    let (sender, receiver) = mpsc::channel(MAXIMUM_PENDING_CONNECTIONS);
+    let accept_task = accept_connections(listener, tls_acceptor, sender);
+    let incoming = stream::unfold(receiver, |mut receiver| async move {
+        let connection = receiver.recv().await?;
+        Some((Ok::<_, io::Error>(connection), receiver))
+    });
+
+    let service = NodeServer::new(GrpcService { rpc_context, rate_limiter });
+    let task = tokio::spawn(async move {
+        let server = Server::builder().add_service(service);
+        tokio::select! {
+            _ = accept_task => (),
+            result = server.serve_with_incoming(incoming) => {
+                if let Err(error) = result {
+                    error!("The gRPC server failed: {}", error);
+                }
+            }
+        }
+    });
+
+    (grpc_server_addr, task)
+}
+
+///
+/// Accepts the connections of gRPC clients, performing their TLS handshakes if enabled, and sends them to the server.
+///
+async fn accept_connections(listener: TcpListener, tls_acceptor: Option<RpcTlsAcceptor>, sender: mpsc::Sender<GrpcConnection>) {
+    loop {
+        let (stream, peer_addr) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!("Failed to accept a gRPC connection: {}", error);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let (tls_acceptor, sender) = (tls_acceptor.clone(), sender.clone());
+        tokio::spawn(async move {
+            let stream = match tls_acceptor {
+                Some(tls_acceptor) => match tls_acceptor.accept(stream, peer_addr).await {
+                    Some(stream) => GrpcStream::Tls(Box::new(stream)),
+                    None => return,
+                },
+                None => GrpcStream::Tcp(stream),
+            };
+            let _ = sender.send(GrpcConnection { stream, peer_addr }).await;
+        });
+    }
+}
+
+///
+/// A stream to a gRPC client, which is secured by TLS if it is enabled.
+///
+enum GrpcStream {
+    /// A plaintext TCP stream.
+    Tcp(TcpStream),
+    /// A TLS stream.
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+///
+/// A connection of a gRPC client, whose address is attached to each of its requests.
+///
+struct GrpcConnection {
+    stream: GrpcStream,
+    peer_addr: SocketAddr,
+}
+
+/// The address of the gRPC client of a request.
+#[derive(Clone, Copy, Debug)]
+struct GrpcConnectInfo {
+    peer_addr: SocketAddr,
+}
+
+impl Connected for GrpcConnection {
+    type ConnectInfo = GrpcConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        GrpcConnectInfo { peer_addr: self.peer_addr }
+    }
+}
+
+impl AsyncRead for GrpcConnection {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().stream {
+            GrpcStream::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            GrpcStream::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for GrpcConnection {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match &mut self.get_mut().stream {
+            GrpcStream::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            GrpcStream::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().stream {
+            GrpcStream::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            GrpcStream::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match &mut self.get_mut().stream {
+            GrpcStream::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+            GrpcStream::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// The gRPC service of the node, which calls the public RPC endpoints of its RPC context.
+struct GrpcService<N: Network, E: Environment> {
+    rpc_context: RpcContext<N, E>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+}
+
+impl<N: Network, E: Environment> GrpcService<N, E> {
+    ///
+    /// Returns an error if the given RPC method is disabled by the method policy,
+    /// or if the client of the given request exceeds its rate limit.
+    ///
+    fn check<T>(&self, request: &Request<T>, method: &str) -> Result<(), Status> {
+        if !self.rpc_context.method_policy.is_enabled(method) {
+            return Err(Status::unimplemented(format!("The RPC method '{}' is disabled", method)));
+        }
+        if let (Some(rate_limiter), Some(connect_info)) = (&self.rate_limiter, request.extensions().get::<GrpcConnectInfo>()) {
+            if !rate_limiter.check_method(connect_info.peer_addr.ip(), Some(method)) {
+                debug!("Rate limited a gRPC request of {}", connect_info.peer_addr);
+                return Err(Status::resource_exhausted("Rate limit exceeded"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl<N: Network, E: Environment> Node for GrpcService<N, E> {
+    type GetBlocksStream = BoxStream<'static, Result<proto::Block, Status>>;
+    type GetMemoryPoolStream = BoxStream<'static, Result<proto::Transaction, Status>>;
+
+    async fn get_latest_block(&self, request: Request<proto::GetLatestBlockRequest>) -> Result<Response<proto::Block>, Status> {
+        self.check(&request, "latestblock")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let block = self.rpc_context.latest_block().await.map_err(to_status)?;
+        Ok(Response::new(to_block(&block)?))
+    }
+
+    async fn get_block(&self, request: Request<proto::GetBlockRequest>) -> Result<Response<proto::Block>, Status> {
+        self.check(&request, "getblock")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let block_height = request.into_inner().block_height;
+        let block = self.rpc_context.get_block(block_height, Some(2)).await.map_err(to_status)?;
+        Ok(Response::new(to_block(&from_json::<Block<N>>(block)?)?))
+    }
+
+    async fn get_block_by_hash(&self, request: Request<proto::GetBlockByHashRequest>) -> Result<Response<proto::Block>, Status> {
+        self.check(&request, "getblockbyhash")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let block_hash = parse(&request.into_inner().block_hash, "block hash")?;
+        let block = self.rpc_context.get_block_by_hash(block_hash).await.map_err(to_status)?;
+        Ok(Response::new(to_block(&block)?))
+    }
+
+    async fn get_blocks(&self, request: Request<proto::GetBlocksRequest>) -> Result<Response<Self::GetBlocksStream>, Status> {
+        self.check(&request, "getblocks")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let request = request.into_inner();
+        let blocks = self
+            .rpc_context
+            .get_blocks(request.start_block_height, request.end_block_height, Some(2))
+            .await
+            .map_err(to_status)?;
+        let blocks = blocks.into_iter().map(|block| to_block(&from_json::<Block<N>>(block)?));
+        Ok(Response::new(stream::iter(blocks).boxed()))
+    }
+
+    async fn get_block_header(&self, request: Request<proto::GetBlockRequest>) -> Result<Response<proto::BlockHeader>, Status> {
+        self.check(&request, "getblockheader")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let block_header = self
+            .rpc_context
+            .get_block_header(request.into_inner().block_height)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(proto::BlockHeader {
+            height: block_header.height(),
+            timestamp: block_header.timestamp(),
+            json: to_json(&block_header)?,
+        }))
+    }
+
+    async fn get_transaction(&self, request: Request<proto::GetTransactionRequest>) -> Result<Response<proto::Transaction>, Status> {
+        self.check(&request, "gettransaction")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let transaction_id = request.into_inner().transaction_id;
+        let transaction = self
+            .rpc_context
+            .get_transaction(parse(&transaction_id, "transaction ID")?)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(proto::Transaction {
+            transaction_id,
+            json: to_json(&transaction)?,
+        }))
+    }
+
+    async fn get_transition(&self, request: Request<proto::GetTransitionRequest>) -> Result<Response<proto::Transition>, Status> {
+        self.check(&request, "gettransition")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let transition_id = request.into_inner().transition_id;
+        let transition = self
+            .rpc_context
+            .get_transition(parse(&transition_id, "transition ID")?)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(proto::Transition {
+            transition_id,
+            json: to_json(&transition)?,
+        }))
+    }
+
+    async fn get_memory_pool(&self, request: Request<proto::GetMemoryPoolRequest>) -> Result<Response<Self::GetMemoryPoolStream>, Status> {
+        self.check(&request, "getmemorypool")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let transactions = self.rpc_context.get_memory_pool().await.map_err(to_status)?;
+        let transactions = transactions.into_iter().map(|transaction| {
+            Ok(proto::Transaction {
+                transaction_id: transaction.transaction_id().to_string(),
+                json: to_json(&transaction)?,
+            })
+        });
+        Ok(Response::new(stream::iter(transactions).boxed()))
+    }
+
+    async fn send_transaction(
+        &self,
+        request: Request<proto::SendTransactionRequest>,
+    ) -> Result<Response<proto::SendTransactionResponse>, Status> {
+        self.check(&request, "sendtransaction")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let transaction_hex = request.into_inner().transaction_hex;
+        let transaction_id = self.rpc_context.send_transaction(transaction_hex).await.map_err(to_status)?;
+        Ok(Response::new(proto::SendTransactionResponse {
+            transaction_id: transaction_id.to_string(),
+        }))
+    }
+
+    async fn get_node_state(&self, request: Request<proto::GetNodeStateRequest>) -> Result<Response<proto::NodeState>, Status> {
+        self.check(&request, "getnodestate")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let node_state = self.rpc_context.get_node_state().await.map_err(to_status)?;
+        Ok(Response::new(proto::NodeState {
+            json: to_json(&node_state)?,
+        }))
+    }
+
+    async fn get_shares(&self, request: Request<proto::GetSharesRequest>) -> Result<Response<proto::Shares>, Status> {
+        self.check(&request, "getshares")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let shares = self.rpc_context.get_shares().await;
+        Ok(Response::new(proto::Shares { shares }))
+    }
+
+    async fn get_shares_for_prover(&self, request: Request<proto::GetSharesForProverRequest>) -> Result<Response<proto::Shares>, Status> {
+        self.check(&request, "getsharesforprover")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let prover = parse(&request.into_inner().prover, "prover address")?;
+        let shares = self.rpc_context.get_shares_for_prover(prover).await.map_err(to_status)?;
+        Ok(Response::new(proto::Shares { shares }))
+    }
+
+    async fn get_provers(&self, request: Request<proto::GetProversRequest>) -> Result<Response<proto::Provers>, Status> {
+        self.check(&request, "getprovers")?;
+        let _permit = self.rpc_context.acquire_execution_permit().await;
+        let provers = from_json(self.rpc_context.get_provers().await)?;
+        Ok(Response::new(proto::Provers { provers }))
+    }
+}
+
+/// Returns the given block as its gRPC message.
+fn to_block<N: Network>(block: &Block<N>) -> Result<proto::Block, Status> {
+    Ok(proto::Block {
+        height: block.height(),
+        hash: block.hash().to_string(),
+        previous_block_hash: block.previous_block_hash().to_string(),
+        timestamp: block.timestamp(),
+        transaction_ids: block.transactions().transaction_ids().map(|id| id.to_string()).collect(),
+        json: to_json(block)?,
+    })
+}
+
+/// Parses the given field of a request as it would be parsed from the parameters of a JSON-RPC request.
+fn parse<T: DeserializeOwned>(value: &str, name: &str) -> Result<T, Status> {
+    serde_json::from_value(Value::String(value.to_string())).map_err(|_| Status::invalid_argument(format!("Invalid {} '{}'", name, value)))
+}
+
+/// Returns the given result of an RPC endpoint as a JSON string.
+fn to_json<T: Serialize>(result: &T) -> Result<String, Status> {
+    serde_json::to_string(result).map_err(|error| Status::internal(error.to_string()))
+}
+
+/// Returns the given JSON result of an RPC endpoint as the given type.
+fn from_json<T: DeserializeOwned>(result: Value) -> Result<T, Status> {
+    serde_json::from_value(result).map_err(|error| Status::internal(error.to_string()))
+}
+
+/// Returns the gRPC status of the given failure of an RPC endpoint.
+fn to_status(error: RpcError) -> Status {
+    Status::invalid_argument(error.to_string())
+}
@@ -0,0 +1,34 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The HTTP settings of the connections to the RPC servers.
+
+use std::time::Duration;
+
+/// The interval at which the RPC listener pings its HTTP/2 clients, to detect those which are gone.
+pub(crate) const HTTP2_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(20);
+
+///
+/// The HTTP settings of the RPC listener, which lets clients reuse their connections
+/// for many requests, rather than opening a connection for each.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RpcHttpConfig {
+    /// If `true`, HTTP/2 is served to clients with prior knowledge, and negotiated over TLS via ALPN.
+    pub http2: bool,
+    /// The duration after which a connection without any reads or writes is closed, or `None` to keep it open.
+    pub idle_timeout: Option<Duration>,
+}
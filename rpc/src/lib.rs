@@ -24,16 +24,64 @@ extern crate thiserror;
 #[macro_use]
 extern crate tracing;
 
+pub mod amount;
+
+pub mod audit;
+pub use audit::*;
+
+pub mod auth;
+pub use auth::*;
+
+pub mod block_template;
+pub use block_template::*;
+
+pub(crate) mod canonical;
+
+pub mod compression;
+pub use compression::*;
+
 pub mod context;
 pub use context::*;
 
+pub mod cors;
+pub use cors::*;
+
 pub(crate) mod error;
 pub(crate) use error::*;
 
+pub(crate) mod front;
+pub(crate) use front::*;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "grpc")]
+pub use grpc::*;
+
+pub mod http_config;
+pub use http_config::*;
+
+#[cfg(feature = "profiling")]
+pub(crate) mod profiler;
+
+pub mod method_policy;
+pub use method_policy::*;
+
+pub mod rate_limit;
+pub use rate_limit::*;
+
+pub mod rest;
+pub use rest::*;
+
 pub(crate) mod rpc_impl;
 
 pub(crate) mod rpc_trait;
 pub(crate) use rpc_trait::*;
 
+pub mod tls;
+pub use tls::*;
+
+pub mod usage;
+pub use usage::*;
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,153 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The policy which enables and disables the RPC methods of a node, so public-facing nodes may only serve chain queries.
+
+use anyhow::{anyhow, Result};
+use std::{collections::HashSet, str::FromStr};
+
+/// The RPC methods which manage the node, such as its peers, leases, and profiler.
+pub const ADMIN_METHODS: [&str; 13] = [
+    "banpeer",
+    "cancellease",
+    "connect",
+    "createlease",
+    "disconnect",
+    "getapiusage",
+    "maintenance",
+    "profilecpu",
+    "profileheap",
+    "restart",
+    "setloglevel",
+    "stop",
+    "unbanpeer",
+];
+
+/// The RPC methods which serve the pool of an operating node, its provers, shares, and payouts.
+pub const OPERATOR_METHODS: [&str; 23] = [
+    "completepayout",
+    "estimateearnings",
+    "getauditevents",
+    "getblocksbytag",
+    "getfoundblock",
+    "getleaderboard",
+    "getleases",
+    "getminedblockinfo",
+    "getmyshares",
+    "getpayoutschedule",
+    "getpendingpayouts",
+    "getpoolinfo",
+    "getpooljob",
+    "getpoolstats",
+    "getprovers",
+    "getshares",
+    "getsharesforprover",
+    "getsubpools",
+    "replaypayouts",
+    "selectpayoutrecords",
+    "submitshare",
+    "updatepayoutaddress",
+    "updatepayoutschedule",
+];
+
+/// The RPC methods which act on records and transactions.
+pub const WALLET_METHODS: [&str; 4] = ["decryptrecord", "getunspentrecords", "sendtransaction", "sendtransactions"];
+
+///
+/// A group of RPC methods, which may be enabled or disabled at once.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RpcMethodGroup {
+    /// The methods which manage the node.
+    Admin,
+    /// The methods which serve the pool of an operating node.
+    Operator,
+    /// The methods which act on records and transactions.
+    Wallet,
+}
+
+impl RpcMethodGroup {
+    /// Returns the RPC methods of the group.
+    pub fn methods(&self) -> &'static [&'static str] {
+        match self {
+            Self::Admin => &ADMIN_METHODS,
+            Self::Operator => &OPERATOR_METHODS,
+            Self::Wallet => &WALLET_METHODS,
+        }
+    }
+}
+
+impl FromStr for RpcMethodGroup {
+    type Err = anyhow::Error;
+
+    fn from_str(group: &str) -> Result<Self> {
+        match group {
+            "admin" => Ok(Self::Admin),
+            "operator" => Ok(Self::Operator),
+            "wallet" => Ok(Self::Wallet),
+            _ => Err(anyhow!("Invalid RPC method group '{}'", group)),
+        }
+    }
+}
+
+///
+/// The policy which enables and disables the RPC methods of a node, which is set at startup.
+/// The methods which are disabled are not registered, so their calls are answered as calls to unknown methods.
+///
+#[derive(Clone, Debug, Default)]
+pub struct RpcMethodPolicy {
+    /// The methods which are enabled, or `None` if every method is enabled, unless it is denied.
+    allowed: Option<HashSet<String>>,
+    /// The methods which are disabled.
+    denied: HashSet<String>,
+}
+
+impl RpcMethodPolicy {
+    ///
+    /// Initializes a new method policy from the given allowed and denied entries, each of which is a method
+    /// or a group of methods: `admin`, `operator`, or `wallet`. If any entries are allowed, every other method
+    /// is disabled; the denied entries are disabled in any case.
+    ///
+    pub fn new(allowed: &[String], denied: &[String]) -> Result<Self> {
+        let allowed = match allowed.is_empty() {
+            true => None,
+            false => Some(expand(allowed)?),
+        };
+        Ok(Self { allowed, denied: expand(denied)? })
+    }
+
+    /// Returns `true` if the given method is enabled.
+    pub fn is_enabled(&self, method: &str) -> bool {
+        self.allowed.as_ref().map_or(true, |allowed| allowed.contains(method)) && !self.denied.contains(method)
+    }
+}
+
+/// Returns the methods of the given entries, expanding the groups of methods.
+fn expand(entries: &[String]) -> Result<HashSet<String>> {
+    let mut methods = HashSet::new();
+    for entry in entries.iter().map(|entry| entry.trim().to_lowercase()) {
+        if entry.is_empty() {
+            return Err(anyhow!("Invalid empty RPC method"));
+        }
+        match RpcMethodGroup::from_str(&entry) {
+            Ok(group) => methods.extend(group.methods().iter().map(|method| method.to_string())),
+            Err(_) => {
+                methods.insert(entry);
+            }
+        }
+    }
+    Ok(methods)
+}
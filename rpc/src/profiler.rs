@@ -0,0 +1,93 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-demand CPU and heap profiling of the running node.
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::OnceCell;
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// The sampling frequency of the CPU profiler, in Hz.
+const CPU_PROFILE_FREQUENCY: i32 = 100;
+/// The name of the file that heap profiles are dumped into, before they are moved to their timestamped path.
+const HEAP_STAGING_FILE: &str = "snarkos-heap.prof.tmp";
+
+/// Samples the CPU usage of the node for the given duration, and writes a pprof-encoded profile into the given directory.
+/// Note: this function blocks the calling thread for the entire duration.
+pub(crate) fn capture_cpu_profile(duration: Duration, directory: &Path) -> Result<PathBuf> {
+    use pprof::protos::Message;
+
+    let guard = pprof::ProfilerGuard::new(CPU_PROFILE_FREQUENCY)?;
+    thread::sleep(duration);
+    let profile = guard.report().build()?.pprof()?;
+
+    let mut bytes = Vec::new();
+    profile.encode(&mut bytes)?;
+
+    let path = profile_path(directory, "cpu", "pb")?;
+    fs::write(&path, bytes)?;
+    Ok(path)
+}
+
+/// Writes a jemalloc heap profile of the node into the given directory.
+/// Note: heap profiling must be enabled at startup, i.e. `_RJEM_MALLOC_CONF=prof:true`.
+pub(crate) fn capture_heap_profile(directory: &Path) -> Result<PathBuf> {
+    if !tikv_jemalloc_ctl::profiling::prof::read()? {
+        return Err(anyhow!("Heap profiling is disabled, restart the node with '_RJEM_MALLOC_CONF=prof:true'"));
+    }
+
+    let path = profile_path(directory, "heap", "prof")?;
+
+    // jemalloc requires a static, nul-terminated path, so the heap is dumped into a staging file whose path
+    // is allocated once for each directory, and the dump is then moved to its timestamped path.
+    // The lock also serializes the dumps, so that concurrent dumps do not overwrite the staging file.
+    static STAGING_PATHS: OnceCell<Mutex<HashMap<PathBuf, &'static [u8]>>> = OnceCell::new();
+    let mut staging_paths = STAGING_PATHS
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|_| anyhow!("The heap profiler is poisoned"))?;
+    let staging_path = directory.join(HEAP_STAGING_FILE);
+    let staging_path_bytes = match staging_paths.get(directory) {
+        Some(staging_path_bytes) => *staging_path_bytes,
+        None => {
+            let staging_path_bytes: &'static [u8] =
+                Box::leak(CString::new(staging_path.to_string_lossy().as_bytes())?.into_bytes_with_nul().into_boxed_slice());
+            staging_paths.insert(directory.to_path_buf(), staging_path_bytes);
+            staging_path_bytes
+        }
+    };
+
+    tikv_jemalloc_ctl::raw::write_str(b"prof.dump\0", staging_path_bytes)
+        .map_err(|error| anyhow!("Failed to dump the heap profile: {}", error))?;
+    fs::rename(&staging_path, &path)?;
+    Ok(path)
+}
+
+/// Returns a new, timestamped path for a profile of the given kind, creating the directory if necessary.
+fn profile_path(directory: &Path, kind: &str, extension: &str) -> Result<PathBuf> {
+    fs::create_dir_all(directory)?;
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+    Ok(directory.join(format!("snarkos-{}-{}.{}", kind, timestamp, extension)))
+}
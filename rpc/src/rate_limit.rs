@@ -0,0 +1,227 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Per-IP rate limiting of the RPC servers.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The RPC methods which are costly to serve, and are limited separately from the other methods.
+pub const EXPENSIVE_METHODS: [&str; 16] = [
+    "estimatefee",
+    "getblockhashes",
+    "getblocks",
+    "getblocksbytag",
+    "getciphertexts",
+    "getledgerproof",
+    "getledgerproofs",
+    "getmyshares",
+    "getnetworkstats",
+    "getprovers",
+    "getshares",
+    "gettransactionsforaddress",
+    "getunspentrecords",
+    "search",
+    "submitblock",
+    "validatetransaction",
+];
+
+/// The number of clients above which the idle clients are no longer tracked.
+const MAXIMUM_TRACKED_CLIENTS: usize = 65_536;
+/// The minimum interval between the sweeps of the idle clients, so a flood of new clients does not sweep on every call.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+///
+/// The rate at which a client may call a class of RPC methods, as the parameters of a token bucket.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// The number of calls per second a client may sustain.
+    pub calls_per_second: f64,
+    /// The number of calls a client may make at once, after being idle.
+    pub burst: u32,
+}
+
+///
+/// The tokens of a client for a class of RPC methods, each of which allows a single call.
+///
+#[derive(Clone, Copy, Debug)]
+struct TokenBucket {
+    /// The number of tokens remaining.
+    tokens: f64,
+    /// The time at which the tokens were last refilled.
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Initializes a full bucket for the given rate limit.
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            refilled_at: now,
+        }
+    }
+
+    /// Adds the tokens accrued since the bucket was last refilled, up to the burst of the given rate limit.
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.calls_per_second).min(limit.burst as f64);
+        self.refilled_at = now;
+    }
+
+    /// Returns `true` if the bucket holds the burst of the given rate limit, as it does for an idle client.
+    fn is_full(&self, limit: &RateLimit) -> bool {
+        self.tokens >= limit.burst as f64
+    }
+}
+
+///
+/// The token buckets of the tracked clients, and the time at which the idle clients were last swept.
+///
+#[derive(Debug)]
+struct TrackedClients {
+    /// The token buckets of each client := (calls, expensive_calls)
+    buckets: HashMap<IpAddr, (TokenBucket, TokenBucket)>,
+    /// The time at which the idle clients were last swept.
+    swept_at: Instant,
+}
+
+///
+/// The rate limits of the RPC servers, applied to the calls of each client IP.
+/// An IPv6 client is identified by its /64 prefix, as a single host is commonly assigned the whole prefix.
+///
+#[derive(Debug)]
+pub struct RpcRateLimiter {
+    /// The rate limit of the calls to the methods which are not expensive.
+    limit: RateLimit,
+    /// The rate limit of the calls to the expensive methods.
+    expensive_limit: RateLimit,
+    /// The token buckets of the tracked clients.
+    clients: Mutex<TrackedClients>,
+}
+
+impl RpcRateLimiter {
+    ///
+    /// Initializes a new rate limiter, with the given limits for the other and the expensive methods.
+    ///
+    pub fn new(limit: RateLimit, expensive_limit: RateLimit) -> Result<Self> {
+        for limit in [&limit, &expensive_limit] {
+            if !limit.calls_per_second.is_finite() || limit.calls_per_second <= 0.0 || limit.burst == 0 {
+                return Err(anyhow!("The rate and the burst of the RPC rate limits must be positive"));
+            }
+        }
+        Ok(Self {
+            limit,
+            expensive_limit,
+            clients: Mutex::new(TrackedClients {
+                buckets: Default::default(),
+                swept_at: Instant::now(),
+            }),
+        })
+    }
+
+    ///
+    /// Returns `true` if the given client may make the calls of the given JSON-RPC request body, consuming their tokens.
+    /// Each call of a batch request counts separately, and a batch is only admitted if all of its calls are.
+    ///
+    pub fn check_request(&self, ip: IpAddr, body: &[u8]) -> bool {
+        let (calls, expensive_calls) = match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(batch)) => {
+                let expensive_calls = batch.iter().filter(|call| is_expensive(call)).count();
+                (batch.len() - expensive_calls, expensive_calls)
+            }
+            Ok(call) if is_expensive(&call) => (0, 1),
+            // A request which is not a valid call is still answered with an error, so it is counted as a call.
+            _ => (1, 0),
+        };
+        self.check(ip, calls, expensive_calls, Instant::now())
+    }
+
+    ///
+    /// Returns `true` if the given client may call the given RPC method, consuming the token of a single call.
+    /// A request which calls no RPC method, such as a request for a page, counts as a call to a method which is not expensive.
+    ///
+    pub fn check_method(&self, ip: IpAddr, method: Option<&str>) -> bool {
+        match method {
+            Some(method) if EXPENSIVE_METHODS.contains(&method) => self.check(ip, 0, 1, Instant::now()),
+            _ => self.check(ip, 1, 0, Instant::now()),
+        }
+    }
+
+    ///
+    /// Returns `true` if the given client may open a WebSocket connection, consuming the token of a single call.
+    ///
+    pub fn check_connection(&self, ip: IpAddr) -> bool {
+        self.check(ip, 1, 0, Instant::now())
+    }
+
+    /// Returns `true` if the given client holds the tokens for the given calls at the given time, consuming them if so.
+    fn check(&self, ip: IpAddr, calls: usize, expensive_calls: usize, now: Instant) -> bool {
+        let (limit, expensive_limit) = (&self.limit, &self.expensive_limit);
+        let mut clients = self.clients.lock().expect("The RPC rate limiter lock is never poisoned");
+
+        // Stop tracking the idle clients once too many clients are tracked, sweeping them at most once per interval.
+        if clients.buckets.len() >= MAXIMUM_TRACKED_CLIENTS && now.saturating_duration_since(clients.swept_at) >= SWEEP_INTERVAL {
+            clients.buckets.retain(|_, (bucket, expensive_bucket)| {
+                bucket.refill(limit, now);
+                expensive_bucket.refill(expensive_limit, now);
+                !bucket.is_full(limit) || !expensive_bucket.is_full(expensive_limit)
+            });
+            clients.swept_at = now;
+        }
+
+        let key = client_key(ip);
+        // If no tracked client is idle, evict an arbitrary client, so the new client is still limited.
+        if clients.buckets.len() >= MAXIMUM_TRACKED_CLIENTS && !clients.buckets.contains_key(&key) {
+            if let Some(evicted) = clients.buckets.keys().next().copied() {
+                clients.buckets.remove(&evicted);
+            }
+        }
+
+        let (bucket, expensive_bucket) = clients
+            .buckets
+            .entry(key)
+            .or_insert_with(|| (TokenBucket::new(limit, now), TokenBucket::new(expensive_limit, now)));
+        bucket.refill(limit, now);
+        expensive_bucket.refill(expensive_limit, now);
+
+        if bucket.tokens < calls as f64 || expensive_bucket.tokens < expensive_calls as f64 {
+            return false;
+        }
+        bucket.tokens -= calls as f64;
+        expensive_bucket.tokens -= expensive_calls as f64;
+        true
+    }
+}
+
+/// Returns `true` if the given JSON-RPC call is to an expensive method.
+fn is_expensive(call: &Value) -> bool {
+    matches!(call.get("method").and_then(Value::as_str), Some(method) if EXPENSIVE_METHODS.contains(&method))
+}
+
+/// Returns the key of the token buckets of the given client IP, which is the /64 prefix of an IPv6 address.
+pub(crate) fn client_key(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => ip,
+        IpAddr::V6(ip) => IpAddr::V6((u128::from(ip) & !u128::from(u64::MAX)).into()),
+    }
+}
@@ -0,0 +1,412 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! A REST gateway over the public RPC endpoints, so explorers and curl users may query the node
+//! with plain `GET` requests, such as `GET /api/v1/blocks/{height}`, rather than JSON-RPC requests.
+//! It may also serve a built-in explorer over these routes, for deployments without an explorer of their own,
+//! and the landing page of the pool of an operating node.
+
+use crate::{
+    bind_front,
+    serve_with_front,
+    RpcCompression,
+    RpcContext,
+    RpcCors,
+    RpcError,
+    RpcFront,
+    RpcFunctions,
+    RpcHttpConfig,
+    RpcRateLimiter,
+    RpcServerKind,
+    RpcTlsAcceptor,
+};
+use snarkos_environment::Environment;
+use snarkvm::dpc::Network;
+
+use hyper::{
+    header::{HeaderValue, CONTENT_SECURITY_POLICY, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr, sync::Arc};
+
+/// The path under which the REST gateway serves its routes.
+pub const REST_API_PREFIX: &str = "/api/v1";
+/// The single-page explorer served by the REST gateway, if enabled, which only calls the routes of the gateway.
+const EXPLORER_PAGE: &str = include_str!("../explorer/index.html");
+/// The landing page of the pool served by the REST gateway, if enabled, which only calls the pool info route of the gateway.
+const POOL_PAGE: &str = include_str!("../explorer/pool.html");
+
+///
+/// The pages served by the REST gateway outside of its routes, each at the path given to the node, if it is served.
+///
+#[derive(Clone, Debug, Default)]
+pub struct RestPages {
+    /// The path of the built-in explorer.
+    pub explorer: Option<String>,
+    /// The path of the landing page of the pool.
+    pub pool: Option<String>,
+}
+
+impl RestPages {
+    /// Returns the page served at the given path, if any.
+    fn page(&self, path: &str) -> Option<&'static str> {
+        let is_served_at = |page_path: &Option<String>| {
+            matches!(page_path, Some(page_path) if page_path.trim_end_matches('/') == path.trim_end_matches('/'))
+        };
+        if is_served_at(&self.explorer) {
+            Some(EXPLORER_PAGE)
+        } else if is_served_at(&self.pool) {
+            Some(POOL_PAGE)
+        } else {
+            None
+        }
+    }
+}
+
+/// The reasons a request to the REST gateway is not served.
+enum RestError {
+    /// The path of the request does not match any route.
+    NotFound,
+    /// A path segment or query parameter of the request is missing or malformed.
+    BadRequest(String),
+    /// The RPC endpoint of the route failed.
+    Rpc(RpcError),
+    /// The route is busy serving other requests.
+    Unavailable(String),
+}
+
+impl RestError {
+    /// Returns the response to a request which is not served for this reason.
+    fn to_response(&self) -> Response<Body> {
+        match self {
+            Self::NotFound => json_response(StatusCode::NOT_FOUND, &error_body("Not found")),
+            Self::BadRequest(message) => json_response(StatusCode::BAD_REQUEST, &error_body(message)),
+            Self::Rpc(error) => json_response(StatusCode::BAD_REQUEST, &error_body(&error.to_string())),
+            Self::Unavailable(message) => json_response(StatusCode::SERVICE_UNAVAILABLE, &error_body(message)),
+        }
+    }
+}
+
+impl From<RpcError> for RestError {
+    fn from(error: RpcError) -> Self {
+        Self::Rpc(error)
+    }
+}
+
+/// Starts a local REST gateway at `rest_server_addr` in a dedicated `tokio` task, which serves the public RPC endpoints
+/// of the given RPC context, and the given pages at their paths. TLS, rate limiting, usage statistics, the audit log,
+/// the CORS policy, compression, and the HTTP settings are applied as for the RPC HTTP server.
+/// REST failures do not affect the rest of the node.
+#[allow(clippy::too_many_arguments)]
+pub async fn initialize_rest_server<N: Network, E: Environment>(
+    rest_server_addr: SocketAddr,
+    rpc_context: RpcContext<N, E>,
+    pages: RestPages,
+    tls_acceptor: Option<RpcTlsAcceptor>,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+    cors: Option<Arc<RpcCors>>,
+    compression: Option<RpcCompression>,
+    http_config: Option<RpcHttpConfig>,
+) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let (usage, audit_log) = (rpc_context.usage.clone(), rpc_context.audit_log.clone());
+    let (front, rest_server_addr) = bind_front(
+        rest_server_addr,
+        tls_acceptor,
+        rate_limiter,
+        usage,
+        audit_log,
+        cors,
+        compression,
+        http_config,
+        RpcServerKind::Rest,
+    )
+    .await;
+
+    let pages = Arc::new(pages);
+    let service = make_service_fn(move |_| {
+        let (rpc_context, pages) = (rpc_context.clone(), pages.clone());
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| handle_request(rpc_context.clone(), pages.clone(), request)))
+        }
+    });
+    let server = Server::try_bind(&rest_server_addr)
+        .expect("Failed to bind the REST server")
+        .serve(service);
+
+    let server_addr = server.local_addr();
+    let listener_addr = front.as_ref().map_or(server_addr, RpcFront::local_addr);
+
+    let task = tokio::spawn(async move {
+        let server_handle = async move {
+            if let Err(error) = server.await {
+                error!("The REST server failed: {}", error);
+            }
+        };
+        serve_with_front(server_handle, front, server_addr).await
+    });
+
+    (listener_addr, task)
+}
+
+///
+/// Serves the given request to the REST gateway, with the JSON result of the RPC endpoint of its route,
+/// or with the page served at the path of the request, if any.
+///
+async fn handle_request<N: Network, E: Environment>(
+    rpc_context: RpcContext<N, E>,
+    pages: Arc<RestPages>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    if request.method() != Method::GET {
+        let body = error_body("The REST gateway only serves GET requests");
+        return Ok(json_response(StatusCode::METHOD_NOT_ALLOWED, &body));
+    }
+
+    let path = request.uri().path();
+    if let Some(page) = pages.page(path) {
+        return Ok(page_response(page));
+    }
+    let segments: Vec<&str> = match path.strip_prefix(REST_API_PREFIX) {
+        Some(path) => path.split('/').filter(|segment| !segment.is_empty()).collect(),
+        None => return Ok(json_response(StatusCode::NOT_FOUND, &error_body("Not found"))),
+    };
+    let query = parse_query(request.uri().query().unwrap_or_default());
+
+    // The routes of the RPC methods which are disabled are not served.
+    if !rpc_method(&segments).map_or(false, |method| rpc_context.method_policy.is_enabled(method)) {
+        return Ok(RestError::NotFound.to_response());
+    }
+
+    // A block export streams its blocks for as long as the client reads them, so it does not hold an execution permit.
+    if segments == ["export", "blocks"] {
+        debug!("Received a REST request for '{}'", path);
+        return Ok(export_blocks(rpc_context.clone(), &query).unwrap_or_else(|error| error.to_response()));
+    }
+
+    let _permit = rpc_context.acquire_execution_permit().await;
+    debug!("Received a REST request for '{}'", path);
+    Ok(match route(&rpc_context, &segments, &query).await {
+        Ok(result) => json_response(StatusCode::OK, &result),
+        Err(error) => error.to_response(),
+    })
+}
+
+///
+/// Streams the blocks in the range of the given query as newline-delimited JSON, without a bound on the number of blocks.
+/// Each block is read from the ledger once the client has received the previous ones, so an export of the full chain
+/// only holds a block in memory at a time. The response is truncated if a block can no longer be read, such as after
+/// the ledger reverts below the range.
+///
+fn export_blocks<N: Network, E: Environment>(
+    rpc_context: RpcContext<N, E>,
+    query: &HashMap<&str, &str>,
+) -> Result<Response<Body>, RestError> {
+    let latest_block_height = rpc_context.ledger.latest_block_height();
+    let start_block_height = parse_optional_query_param(query, "start")?.unwrap_or(0);
+    let end_block_height = parse_optional_query_param(query, "end")?.unwrap_or(latest_block_height);
+    let verbosity: Option<u8> = parse_optional_query_param(query, "verbosity")?;
+    if start_block_height > end_block_height || end_block_height > latest_block_height {
+        return Err(RestError::BadRequest(format!(
+            "The range must be ordered and end at or below the latest block height {}",
+            latest_block_height
+        )));
+    }
+    if matches!(verbosity, Some(verbosity) if verbosity > 2) {
+        return Err(RestError::BadRequest("The verbosity must be 0, 1, or 2".to_string()));
+    }
+    let permit = rpc_context
+        .export_permits
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| RestError::Unavailable("Too many block exports are in progress".to_string()))?;
+
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        let _permit = permit;
+        for block_height in start_block_height..=end_block_height {
+            let block = rpc_context.ledger.get_block(block_height).map_err(RpcError::from);
+            match block.and_then(|block| RpcContext::<N, E>::block_to_json(&block, verbosity)) {
+                Ok(block) => {
+                    // Wait until the client has received the previous blocks, and stop once the client is gone.
+                    if sender.send_data(format!("{}\n", block).into()).await.is_err() {
+                        return;
+                    }
+                }
+                Err(error) => {
+                    warn!("Failed to export block {} over REST: {}", block_height, error);
+                    sender.abort();
+                    return;
+                }
+            }
+        }
+    });
+
+    let mut response = Response::new(body);
+    let content_type = HeaderValue::from_static("application/x-ndjson");
+    response.headers_mut().insert(CONTENT_TYPE, content_type);
+    Ok(response)
+}
+
+///
+/// Calls the RPC endpoint of the route matching the given path segments, returning its JSON result.
+///
+async fn route<N: Network, E: Environment>(
+    rpc_context: &RpcContext<N, E>,
+    segments: &[&str],
+    query: &HashMap<&str, &str>,
+) -> Result<Value, RestError> {
+    match segments {
+        ["blocks"] => {
+            let start_block_height = parse_query_param(query, "start")?;
+            let end_block_height = parse_query_param(query, "end")?;
+            let verbosity = parse_optional_query_param(query, "verbosity")?;
+            to_json(rpc_context.get_blocks(start_block_height, end_block_height, verbosity).await?)
+        }
+        ["blocks", "latest"] => to_json(rpc_context.latest_block().await?),
+        ["blocks", "latest", "height"] => to_json(rpc_context.latest_block_height().await?),
+        ["blocks", "latest", "hash"] => to_json(rpc_context.latest_block_hash().await?),
+        ["blocks", "hash", block_hash] => to_json(rpc_context.get_block_by_hash(parse(block_hash, "block hash")?).await?),
+        ["blocks", block_height] => to_json(rpc_context.get_block(parse(block_height, "block height")?, None).await?),
+        ["blocks", block_height, "hash"] => to_json(rpc_context.get_block_hash(parse(block_height, "block height")?).await?),
+        ["blocks", block_height, "header"] => to_json(rpc_context.get_block_header(parse(block_height, "block height")?).await?),
+        ["blocks", block_height, "transactions"] => {
+            to_json(rpc_context.get_block_transactions(parse(block_height, "block height")?).await?)
+        }
+        ["transactions", transaction_id] => to_json(rpc_context.get_transaction(parse(transaction_id, "transaction ID")?).await?),
+        ["transitions", transition_id] => to_json(rpc_context.get_transition(parse(transition_id, "transition ID")?).await?),
+        ["ciphertexts", commitment] => to_json(rpc_context.get_ciphertext(parse(commitment, "commitment")?).await?),
+        ["search", query] => to_json(rpc_context.search(query.to_string()).await?),
+        ["mempool"] => to_json(rpc_context.get_memory_pool().await?),
+        ["mempool", "info"] => to_json(rpc_context.get_mempool_info().await?),
+        ["mempool", "fee", confirmation_target] => {
+            to_json(rpc_context.estimate_fee(parse(confirmation_target, "confirmation target")?).await?)
+        }
+        ["pool"] => to_json(rpc_context.get_pool_stats().await?),
+        ["pool", "info"] => Ok(rpc_context.get_pool_info().await),
+        ["pool", "leaderboard", period] => {
+            let timestamp = parse_optional_query_param(query, "timestamp")?;
+            to_json(rpc_context.get_leaderboard(parse(period, "period")?, timestamp).await?)
+        }
+        ["node"] => to_json(rpc_context.get_node_state().await?),
+        ["peers"] => to_json(rpc_context.get_connected_peers().await?),
+        ["sync"] => to_json(rpc_context.get_sync_status().await?),
+        _ => Err(RestError::NotFound),
+    }
+}
+
+///
+/// Returns the RPC method called by the route of the given request path, if any.
+///
+pub(crate) fn rest_method(path: &str) -> Option<&'static str> {
+    let segments: Vec<&str> = path.strip_prefix(REST_API_PREFIX)?.split('/').filter(|segment| !segment.is_empty()).collect();
+    rpc_method(&segments)
+}
+
+///
+/// Returns the RPC method called by the route matching the given path segments, if any.
+///
+fn rpc_method(segments: &[&str]) -> Option<&'static str> {
+    match segments {
+        ["export", "blocks"] | ["blocks"] => Some("getblocks"),
+        ["blocks", "latest"] => Some("latestblock"),
+        ["blocks", "latest", "height"] => Some("latestblockheight"),
+        ["blocks", "latest", "hash"] => Some("latestblockhash"),
+        ["blocks", "hash", _] => Some("getblockbyhash"),
+        ["blocks", _] => Some("getblock"),
+        ["blocks", _, "hash"] => Some("getblockhash"),
+        ["blocks", _, "header"] => Some("getblockheader"),
+        ["blocks", _, "transactions"] => Some("getblocktransactions"),
+        ["transactions", _] => Some("gettransaction"),
+        ["transitions", _] => Some("gettransition"),
+        ["ciphertexts", _] => Some("getciphertext"),
+        ["search", _] => Some("search"),
+        ["mempool"] => Some("getmemorypool"),
+        ["mempool", "info"] => Some("getmempoolinfo"),
+        ["mempool", "fee", _] => Some("estimatefee"),
+        ["pool"] => Some("getpoolstats"),
+        ["pool", "info"] => Some("getpoolinfo"),
+        ["pool", "leaderboard", _] => Some("getleaderboard"),
+        ["node"] => Some("getnodestate"),
+        ["peers"] => Some("getconnectedpeers"),
+        ["sync"] => Some("getsyncstatus"),
+        _ => None,
+    }
+}
+
+///
+/// Parses the given path segment or query parameter as it would be parsed from the parameters of a JSON-RPC request,
+/// either as a JSON value, such as a number, or else as a JSON string, such as a hash.
+///
+fn parse<T: DeserializeOwned>(value: &str, name: &str) -> Result<T, RestError> {
+    serde_json::from_str(value)
+        .or_else(|_| serde_json::from_value(Value::String(value.to_string())))
+        .map_err(|_| RestError::BadRequest(format!("Invalid {} '{}'", name, value)))
+}
+
+/// Parses the query parameter of the given name.
+fn parse_query_param<T: DeserializeOwned>(query: &HashMap<&str, &str>, name: &str) -> Result<T, RestError> {
+    match query.get(name) {
+        Some(value) => parse(value, name),
+        None => Err(RestError::BadRequest(format!("Missing query parameter '{}'", name))),
+    }
+}
+
+/// Parses the query parameter of the given name, if it is given.
+fn parse_optional_query_param<T: DeserializeOwned>(query: &HashMap<&str, &str>, name: &str) -> Result<Option<T>, RestError> {
+    query.get(name).map(|value| parse(value, name)).transpose()
+}
+
+/// Returns the parameters of the given query string.
+fn parse_query(query: &str) -> HashMap<&str, &str> {
+    query.split('&').filter_map(|param| param.split_once('=')).collect()
+}
+
+/// Returns the given result of an RPC endpoint as JSON.
+fn to_json<T: Serialize>(result: T) -> Result<Value, RestError> {
+    serde_json::to_value(result).map_err(|error| RestError::Rpc(error.into()))
+}
+
+/// Returns the JSON body of an error with the given message.
+fn error_body(message: &str) -> Value {
+    serde_json::json!({ "error": message })
+}
+
+/// Returns the given page, which may only run its own scripts and call the gateway.
+fn page_response(page: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(page));
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+    let content_security_policy = "default-src 'none'; script-src 'unsafe-inline'; style-src 'unsafe-inline'; connect-src 'self'";
+    headers.insert(CONTENT_SECURITY_POLICY, HeaderValue::from_static(content_security_policy));
+    response
+}
+
+/// Returns a response with the given status and JSON body.
+fn json_response(status: StatusCode, body: &Value) -> Response<Body> {
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    let content_type = HeaderValue::from_static("application/json");
+    response.headers_mut().insert(CONTENT_TYPE, content_type);
+    response
+}
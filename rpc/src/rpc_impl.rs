@@ -18,20 +18,112 @@
 //!
 //! See [RpcFunctions](../trait.RpcFunctions.html) for documentation of public endpoints.
 
-use crate::{RpcContext, RpcError, RpcFunctions};
-use snarkos_environment::Environment;
-use snarkos_network::{ProverRequest, PeersRequest};
-use snarkos_storage::Metadata;
+use crate::{
+    amount::format_credits,
+    block_template::to_generic_block_template,
+    canonical,
+    canonical::SchemaVersion,
+    BlockTemplateSchema,
+    RpcContext,
+    RpcError,
+    RpcFunctions,
+};
+use snarkos_environment::{helpers::ShutdownKind, Environment};
+use snarkos_network::{
+    helpers::{
+        from_wire_hex,
+        to_wire_hex,
+        SubPool,
+        SubPools,
+        BLOCK_TEMPLATE_LONG_POLL_IN_SECS,
+        MAX_BASIS_POINTS,
+        POOL_JOB_LONG_POLL_IN_SECS,
+    },
+    LedgerRequest,
+    PeersRequest,
+    ProverRequest,
+};
+use snarkos_storage::{
+    ContributionPeriod,
+    DifficultyEstimate,
+    FeeEstimate,
+    Metadata,
+    NetworkStats,
+    PayoutAddressUpdate,
+    PayoutSchedule,
+    PayoutScheduleUpdate,
+    RewardSchedule,
+    ShareRecord,
+    DEFAULT_NETWORK_STATS_WINDOW,
+    MAXIMUM_CONFIRMATION_TARGET,
+    MAXIMUM_NETWORK_STATS_WINDOW,
+};
 use snarkvm::{
-    dpc::{Address, AleoAmount, Block, BlockHeader, Blocks, Network, Record, Transaction, Transactions, Transition},
+    dpc::{
+        Address,
+        AleoAmount,
+        Block,
+        BlockHeader,
+        Blocks,
+        ComputeKey,
+        Network,
+        PoSWProof,
+        Record,
+        Transaction,
+        Transactions,
+        Transition,
+        ViewKey,
+    },
     utilities::{FromBytes, ToBytes},
 };
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast::error::RecvError, oneshot};
 
+use jsonrpsee::ws_server::SubscriptionSink;
 use serde_json::Value;
 use time::OffsetDateTime;
 
-use std::{cmp::max, net::SocketAddr};
+use std::{
+    cmp::max,
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// The human-readable prefix of an Aleo address.
+const ADDRESS_PREFIX: &str = "aleo1";
+/// The maximum duration of a CPU profile, in seconds.
+const MAX_CPU_PROFILE_DURATION_IN_SECS: u64 = 300;
+/// The payout scheme of the pool, which splits the reward of each block found between the shares of its round.
+const POOL_PAYOUT_SCHEME: &str = "proportional";
+/// The maximum window of an earnings estimate, in seconds.
+const MAX_EARNINGS_WINDOW_IN_SECS: u64 = 365 * 24 * 60 * 60;
+/// The maximum number of blocks in a range of the address index.
+const MAX_ADDRESS_INDEX_RANGE: u32 = 10_000;
+/// The number of latest blocks whose transactions for an address are returned by a search.
+const SEARCH_ADDRESS_INDEX_RANGE: u32 = 100;
+/// The maximum number of blocks scanned for the unspent records of a view key in a single call.
+const MAX_UNSPENT_RECORDS_RANGE: u32 = 1_000;
+/// The default number of clients returned by `getapiusage`.
+const DEFAULT_API_USAGE_CLIENTS: usize = 100;
+/// The maximum number of clients returned by `getapiusage`.
+const MAX_API_USAGE_CLIENTS: usize = 1_000;
+/// The verbosity of blocks if none is given, which returns the fully expanded block.
+const DEFAULT_BLOCK_VERBOSITY: u8 = 2;
+/// The maximum number of transactions sent in a single call.
+const MAX_TRANSACTIONS_PER_SEND: usize = 100;
+/// The maximum number of ciphertexts requested in a single call.
+const MAX_CIPHERTEXTS_PER_CALL: usize = 500;
+/// The maximum number of ledger proofs requested in a single call.
+const MAX_LEDGER_PROOFS_PER_CALL: usize = 32;
+/// The maximum number of share records returned in a single call.
+const MAX_SHARE_RECORDS: usize = 10_000;
+/// The maximum number of provers ranked on a leaderboard.
+const MAX_LEADERBOARD_PROVERS: usize = 100;
+/// The delay before a shutdown requested by `stop` or `restart` begins, so that its response is sent first, in milliseconds.
+const SHUTDOWN_DELAY_IN_MS: u64 = 500;
+/// The maximum duration to wait for the ledger to accept a submitted block, in milliseconds.
+const SUBMIT_BLOCK_TIMEOUT_IN_MS: u64 = 5_000;
 
 #[async_trait::async_trait]
 impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
@@ -70,15 +162,26 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(self.ledger.latest_ledger_root())
     }
 
-    /// Returns the block given the block height.
-    async fn get_block(&self, block_height: u32) -> Result<Block<N>, RpcError> {
+    /// Returns the block given the block height, at the given verbosity.
+    async fn get_block(&self, block_height: u32, verbosity: Option<u8>) -> Result<Value, RpcError> {
+        Self::block_to_json(&self.ledger.get_block(block_height)?, verbosity)
+    }
+
+    /// Returns the block given the block hash.
+    async fn get_block_by_hash(&self, block_hash: N::BlockHash) -> Result<Block<N>, RpcError> {
+        let block_height = self.ledger.get_block_height(&block_hash)?;
         Ok(self.ledger.get_block(block_height)?)
     }
 
-    /// Returns up to `MAXIMUM_BLOCK_REQUEST` blocks from the given `start_block_height` to `end_block_height` (inclusive).
-    async fn get_blocks(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<Block<N>>, RpcError> {
+    /// Returns up to `MAXIMUM_BLOCK_REQUEST` blocks from the given `start_block_height` to `end_block_height` (inclusive),
+    /// at the given verbosity.
+    async fn get_blocks(&self, start_block_height: u32, end_block_height: u32, verbosity: Option<u8>) -> Result<Vec<Value>, RpcError> {
         let safe_start_height = max(start_block_height, end_block_height.saturating_sub(E::MAXIMUM_BLOCK_REQUEST - 1));
-        Ok(self.ledger.get_blocks(safe_start_height, end_block_height)?)
+        self.ledger
+            .get_blocks(safe_start_height, end_block_height)?
+            .iter()
+            .map(|block| Self::block_to_json(block, verbosity))
+            .collect()
     }
 
     /// Returns the block height for the given the block hash.
@@ -102,8 +205,18 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(self.ledger.get_block_header(block_height)?)
     }
 
-    /// Returns the block template for the next mined block
-    async fn get_block_template(&self) -> Result<Value, RpcError> {
+    /// Returns the block template for the next mined block.
+    /// Transactions in `exclude_tx` are left out of the template, and transactions in `include_tx` are placed first;
+    /// both require RPC credentials with the wallet role, which are checked by the dispatcher.
+    async fn get_block_template(
+        &self,
+        exclude_tx: Vec<N::TransactionID>,
+        include_tx: Vec<N::TransactionID>,
+        previous_template_id: Option<u64>,
+    ) -> Result<Value, RpcError> {
+        // Identify the block template before reading the state it is built from, so that a concurrent update yields a new ID.
+        let (template_id, clean_template_id, _) = self.current_block_template_id();
+
         // Fetch the latest state from the ledger.
         let latest_block = self.ledger.latest_block();
         let ledger_root = self.ledger.latest_ledger_root();
@@ -111,7 +224,12 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         // Prepare the new block.
         let previous_block_hash = latest_block.hash();
         let block_height = self.ledger.latest_block_height() + 1;
-        let block_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        // Ensure that the new timestamp is ahead of the median time past.
+        let median_time_past = self.ledger.latest_median_time_past()?;
+        let block_timestamp = self
+            .ledger
+            .timestamp_policy()
+            .next_timestamp(median_time_past, OffsetDateTime::now_utc().unix_timestamp());
 
         // Compute the block difficulty target.
         let difficulty_target = if N::NETWORK_ID == 2 && block_height <= snarkvm::dpc::testnet2::V12_UPGRADE_BLOCK_HEIGHT {
@@ -132,13 +250,32 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         let mut coinbase_reward = Block::<N>::block_reward(block_height);
         let mut transaction_fees = AleoAmount::ZERO;
 
-        // Get and filter the transactions from the mempool.
-        let transactions: Vec<String> = self
-            .memory_pool
-            .read()
-            .await
-            .transactions()
-            .iter()
+        // Order the included transactions first, followed by the remaining transactions from the mempool that are not excluded,
+        // of which the local priority transactions come first.
+        let memory_pool = self.memory_pool.prioritized_transactions();
+        let exclude_tx: HashSet<_> = exclude_tx.into_iter().collect();
+        let mut candidates: Vec<&Transaction<N>> = Vec::with_capacity(memory_pool.len());
+        for transaction_id in &include_tx {
+            if exclude_tx.contains(transaction_id) {
+                return Err(RpcError::Message(format!("Transaction {} is both included and excluded", transaction_id)));
+            }
+            match memory_pool.iter().find(|transaction| transaction.transaction_id() == *transaction_id) {
+                Some(transaction) => {
+                    if !candidates.iter().any(|candidate| candidate.transaction_id() == *transaction_id) {
+                        candidates.push(transaction);
+                    }
+                }
+                None => return Err(RpcError::Message(format!("Transaction {} is not in the memory pool", transaction_id))),
+            }
+        }
+        candidates.extend(memory_pool.iter().filter(|transaction| {
+            let transaction_id = transaction.transaction_id();
+            !include_tx.contains(&transaction_id) && !exclude_tx.contains(&transaction_id)
+        }));
+
+        // Filter the candidate transactions that conflict with the ledger.
+        let transactions: Vec<String> = candidates
+            .into_iter()
             .filter(|transaction| {
                 for serial_number in transaction.serial_numbers() {
                     if let Ok(true) = self.ledger.contains_serial_number(serial_number) {
@@ -166,7 +303,8 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         // Calculate the final coinbase reward (including the transaction fees).
         coinbase_reward = coinbase_reward.add(transaction_fees);
 
-        Ok(serde_json::json!({
+        let template = serde_json::json!({
+            "template_id": template_id,
             "previous_block_hash": previous_block_hash,
             "block_height": block_height,
             "time": block_timestamp,
@@ -175,7 +313,16 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
             "ledger_root": ledger_root,
             "transactions": transactions,
             "coinbase_reward": coinbase_reward,
-        }))
+            "coinbase_reward_credits": format_credits(coinbase_reward),
+        });
+        match self.block_template_schema {
+            BlockTemplateSchema::Native => Ok(template),
+            // The work of the caller is stale if its previous block template precedes the latest clean block template.
+            BlockTemplateSchema::Generic => {
+                let clean_jobs = previous_template_id.map_or(true, |previous_template_id| previous_template_id < clean_template_id);
+                Ok(to_generic_block_template(&template, clean_jobs))
+            }
+        }
     }
 
     /// Returns the transactions from the block of the given block height.
@@ -188,15 +335,77 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(self.ledger.get_ciphertext(&commitment)?)
     }
 
+    /// Returns the record ciphertexts for the given commitments, with `None` for each commitment that does not exist.
+    async fn get_ciphertexts(&self, commitments: Vec<N::Commitment>) -> Result<Vec<Option<N::RecordCiphertext>>, RpcError> {
+        if commitments.len() > MAX_CIPHERTEXTS_PER_CALL {
+            return Err(RpcError::Message(format!(
+                "At most {} ciphertexts may be requested at once",
+                MAX_CIPHERTEXTS_PER_CALL
+            )));
+        }
+
+        let mut ciphertexts = Vec::with_capacity(commitments.len());
+        for commitment in commitments {
+            match self.ledger.contains_commitment(&commitment)? {
+                true => ciphertexts.push(Some(self.ledger.get_ciphertext(&commitment)?)),
+                false => ciphertexts.push(None),
+            }
+        }
+        Ok(ciphertexts)
+    }
+
     /// Returns the ledger proof for a given record commitment.
     async fn get_ledger_proof(&self, record_commitment: N::Commitment) -> Result<String, RpcError> {
         let ledger_proof = self.ledger.get_ledger_inclusion_proof(record_commitment)?;
         Ok(hex::encode(ledger_proof.to_bytes_le().expect("Failed to serialize ledger proof")))
     }
 
+    /// Returns the ledger proofs for the given record commitments, generating them in parallel on the blocking thread pool.
+    async fn get_ledger_proofs(&self, record_commitments: Vec<N::Commitment>) -> Result<Vec<String>, RpcError> {
+        if record_commitments.len() > MAX_LEDGER_PROOFS_PER_CALL {
+            return Err(RpcError::Message(format!(
+                "At most {} ledger proofs may be requested at once",
+                MAX_LEDGER_PROOFS_PER_CALL
+            )));
+        }
+
+        let tasks = record_commitments.into_iter().map(|commitment| {
+            let ledger = self.ledger.clone();
+            tokio::task::spawn_blocking(move || -> Result<String, RpcError> {
+                let ledger_proof = ledger
+                    .get_ledger_inclusion_proof(commitment)
+                    .map_err(|error| RpcError::Message(format!("Failed to prove commitment {}: {}", commitment, error)))?;
+                Ok(hex::encode(ledger_proof.to_bytes_le().expect("Failed to serialize ledger proof")))
+            })
+        });
+        futures::future::join_all(tasks)
+            .await
+            .into_iter()
+            .map(|result| result.map_err(|error| RpcError::Message(error.to_string()))?)
+            .collect()
+    }
+
     /// Returns transactions in the node's memory pool.
     async fn get_memory_pool(&self) -> Result<Vec<Transaction<N>>, RpcError> {
-        Ok(self.memory_pool.read().await.transactions())
+        Ok(self.memory_pool.transactions().to_vec())
+    }
+
+    /// Returns a summary of the transactions in the memory pool, without their contents.
+    async fn get_mempool_info(&self) -> Result<Value, RpcError> {
+        Ok(serde_json::to_value(self.memory_pool.summary())?)
+    }
+
+    /// Returns the estimated fee for a transaction to be included within the given number of blocks,
+    /// from the fullness and fee levels of the latest blocks, and no less than the minimum fee of the memory pool.
+    async fn estimate_fee(&self, confirmation_target: u32) -> Result<FeeEstimate, RpcError> {
+        if confirmation_target == 0 || confirmation_target > MAXIMUM_CONFIRMATION_TARGET {
+            return Err(RpcError::Message(format!(
+                "The confirmation target must be between 1 and {} blocks",
+                MAXIMUM_CONFIRMATION_TARGET
+            )));
+        }
+        let minimum_fee_per_byte = self.memory_pool.policy().minimum_fee_per_byte;
+        Ok(self.ledger.get_fee_estimate(confirmation_target, minimum_fee_per_byte)?)
     }
 
     /// Returns a transaction with metadata and decrypted records given the transaction ID.
@@ -207,6 +416,34 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(serde_json::json!({ "transaction": transaction, "metadata": metadata, "decrypted_records": decrypted_records }))
     }
 
+    /// Returns the IDs of the transactions with public records owned by the given address, in the given range of block heights.
+    async fn get_transactions_for_address(
+        &self,
+        address: Address<N>,
+        start_block_height: u32,
+        end_block_height: u32,
+    ) -> Result<Value, RpcError> {
+        if start_block_height > end_block_height || end_block_height - start_block_height >= MAX_ADDRESS_INDEX_RANGE {
+            return Err(RpcError::Message(format!(
+                "The range must be ordered and span at most {} blocks",
+                MAX_ADDRESS_INDEX_RANGE
+            )));
+        }
+
+        let transactions: Vec<Value> = self
+            .ledger
+            .get_transactions_for_address(&address, start_block_height, end_block_height)?
+            .into_iter()
+            .map(|(block_height, transaction_id)| {
+                serde_json::json!({
+                    "block_height": block_height,
+                    "transaction_id": transaction_id,
+                })
+            })
+            .collect();
+        Ok(serde_json::json!(transactions))
+    }
+
     /// Returns a transition given the transition ID.
     async fn get_transition(&self, transition_id: N::TransitionID) -> Result<Transition<N>, RpcError> {
         Ok(self.ledger.get_transition(&transition_id)?)
@@ -217,6 +454,74 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(self.peers.connected_peers().await)
     }
 
+    /// Returns the information of the peers currently connected to this node, including the traffic and latency of each connection.
+    async fn get_peer_info(&self) -> Result<Value, RpcError> {
+        let mut peers: Vec<_> = self.peers.peer_info().await.into_iter().collect();
+        peers.sort_by_key(|(peer_ip, _)| *peer_ip);
+
+        let peers: Vec<Value> = peers
+            .into_iter()
+            .map(|(peer_ip, peer_info)| {
+                serde_json::json!({
+                    "ip": peer_ip,
+                    "node_type": peer_info.node_type,
+                    "version": peer_info.version,
+                    "direction": if peer_info.is_outbound { "outbound" } else { "inbound" },
+                    "block_height": peer_info.block_height,
+                    "is_fork": peer_info.is_fork,
+                    "clock_offset_in_secs": peer_info.clock_offset,
+                    "latency_in_ms": peer_info.stats.latency().map(|latency| latency.as_secs_f64() * 1000.0),
+                    "bytes_sent": peer_info.stats.bytes_sent(),
+                    "bytes_received": peer_info.stats.bytes_received(),
+                    "connected_for_in_secs": peer_info.connected_at.elapsed().as_secs(),
+                })
+            })
+            .collect();
+        Ok(serde_json::json!(peers))
+    }
+
+    /// Returns the local time of the node, the median time of its peers, and the median time past of its ledger.
+    async fn get_network_time(&self) -> Result<Value, RpcError> {
+        let local_time = OffsetDateTime::now_utc().unix_timestamp();
+        let (median_peer_time, median_clock_offset, number_of_peers) = match self.peers.median_clock_offset().await {
+            Some((clock_offset, number_of_peers)) => (Some(local_time.saturating_add(clock_offset)), Some(clock_offset), number_of_peers),
+            None => (None, None, 0),
+        };
+
+        // The clocks of the provers are measured by the timestamps of their shares, if this node is an operator.
+        let share_timestamps = self.operator.share_timestamps();
+        let (median_prover_clock_offset, number_of_provers) = match share_timestamps.median_clock_offset() {
+            Some((clock_offset, number_of_provers)) => (Some(clock_offset), number_of_provers),
+            None => (None, 0),
+        };
+        let skewed_provers: Vec<Value> = share_timestamps
+            .to_provers()
+            .into_iter()
+            .filter(|(_, clock)| clock.is_skewed)
+            .map(|(prover, clock)| {
+                serde_json::json!({
+                    "prover": prover,
+                    "clock_offset_in_secs": clock.clock_offset_in_secs,
+                    "shares": clock.shares,
+                    "rejected": clock.rejected,
+                })
+            })
+            .collect();
+
+        Ok(serde_json::json!({
+            "local_time": local_time,
+            "median_peer_time": median_peer_time,
+            "median_clock_offset_in_secs": median_clock_offset,
+            "number_of_peers": number_of_peers,
+            "median_time_past": self.ledger.latest_median_time_past()?,
+            "timestamp_policy": self.ledger.timestamp_policy(),
+            "share_timestamp_tolerance_in_secs": share_timestamps.tolerance_in_secs(),
+            "median_prover_clock_offset_in_secs": median_prover_clock_offset,
+            "number_of_provers": number_of_provers,
+            "skewed_provers": skewed_provers,
+        }))
+    }
+
     /// Returns the current state of this node.
     async fn get_node_state(&self) -> Result<Value, RpcError> {
         let candidate_peers = self.peers.candidate_peers().await;
@@ -233,6 +538,8 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
             "address": self.address,
             "candidate_peers": candidate_peers,
             "connected_peers": connected_peers,
+            "handshake_failures": self.peers.handshake_failures().counts(),
+            "outdated_peer_versions": self.peers.handshake_failures().outdated_versions(),
             "latest_block_hash": latest_block_hash,
             "latest_block_height": latest_block_height,
             "latest_cumulative_weight": latest_cumulative_weight,
@@ -247,17 +554,239 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         }))
     }
 
+    /// Returns the sync progress of this node: the latest block height against the best block height reported by a peer,
+    /// with the throughput since the earliest call in the last 5 minutes, and the estimated time to catch up.
+    async fn get_sync_status(&self) -> Result<Value, RpcError> {
+        let latest_block_height = self.ledger.latest_block_height();
+        let blocks_per_second = self.sync_progress.record(Instant::now(), latest_block_height);
+
+        // The best known block height is reported by the peers which are not on a fork.
+        let best_known_block_height = self
+            .peers
+            .peer_info()
+            .await
+            .values()
+            .filter(|peer_info| peer_info.is_fork != Some(true))
+            .map(|peer_info| peer_info.block_height)
+            .fold(latest_block_height, max);
+        let blocks_remaining = best_known_block_height - latest_block_height;
+        let eta_in_secs = match blocks_per_second {
+            Some(blocks_per_second) if blocks_per_second > 0.0 => Some((blocks_remaining as f64 / blocks_per_second).ceil() as u64),
+            _ => None,
+        };
+
+        Ok(serde_json::json!({
+            "status": E::status().to_string(),
+            "is_syncing": E::status().is_syncing(),
+            "latest_block_height": latest_block_height,
+            "best_known_block_height": best_known_block_height,
+            "blocks_remaining": blocks_remaining,
+            "blocks_per_second": blocks_per_second,
+            "eta_in_secs": eta_in_secs,
+        }))
+    }
+
     /// Returns the transaction ID. If the given transaction is valid, it is added to the memory pool and propagated to all peers.
     async fn send_transaction(&self, transaction_hex: String) -> Result<N::TransactionID, RpcError> {
         let transaction: Transaction<N> = FromBytes::from_bytes_le(&hex::decode(transaction_hex)?)?;
-        // Route an `UnconfirmedTransaction` to the prover.
-        let request = ProverRequest::UnconfirmedTransaction("0.0.0.0:3032".parse().unwrap(), transaction.clone());
-        if let Err(error) = self.prover_router.send(request).await {
+        // Route a `LocalTransaction` to the prover, which propagates it as specified by the propagation policy of the node.
+        let request = ProverRequest::LocalTransaction(transaction.clone());
+        if let Err(error) = self.prover_circuit_breaker.call(self.prover_router.send(request)).await? {
             warn!("[UnconfirmedTransaction] {}", error);
         }
         Ok(transaction.transaction_id())
     }
 
+    /// Validates the given transactions, and sends each valid transaction to the memory pool, returning the result of each transaction.
+    async fn send_transactions(&self, transactions_hex: Vec<String>) -> Result<Value, RpcError> {
+        if transactions_hex.len() > MAX_TRANSACTIONS_PER_SEND {
+            return Err(RpcError::Message(format!(
+                "At most {} transactions may be sent at once",
+                MAX_TRANSACTIONS_PER_SEND
+            )));
+        }
+
+        // Track the transactions and serial numbers in the memory pool, so conflicting transactions are rejected.
+        let memory_pool = self.memory_pool.transactions();
+        let mut transaction_ids: HashSet<_> = memory_pool.iter().map(|transaction| transaction.transaction_id()).collect();
+        let mut serial_numbers: HashSet<_> = memory_pool
+            .iter()
+            .flat_map(|transaction| transaction.serial_numbers().cloned())
+            .collect();
+
+        // Decode the transactions and verify their proofs in a blocking task, as verifying the proofs is the most expensive check.
+        let verified_transactions = tokio::task::spawn_blocking(move || {
+            transactions_hex
+                .iter()
+                .map(|transaction_hex| Self::verify_sent_transaction(transaction_hex))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|error| RpcError::Message(error.to_string()))?;
+
+        let mut results = Vec::with_capacity(verified_transactions.len());
+        for verified_transaction in verified_transactions {
+            let validated_transaction = verified_transaction
+                .and_then(|transaction| self.validate_sent_transaction(transaction, &mut transaction_ids, &mut serial_numbers));
+            match validated_transaction {
+                Ok(transaction) => {
+                    let transaction_id = transaction.transaction_id();
+                    // Route a `LocalTransaction` to the prover, which propagates it as specified by the propagation policy of the node.
+                    let request = ProverRequest::LocalTransaction(transaction);
+                    match self.prover_circuit_breaker.call(self.prover_router.send(request)).await {
+                        Ok(result) => {
+                            if let Err(error) = result {
+                                warn!("[LocalTransaction] {}", error);
+                            }
+                            results.push(serde_json::json!({ "accepted": true, "transaction_id": transaction_id }));
+                        }
+                        // Report the unresponsive prover, as the transaction did not reach the memory pool.
+                        Err(error) => results.push(serde_json::json!({ "accepted": false, "error": error.to_string() })),
+                    }
+                }
+                Err(error) => results.push(serde_json::json!({ "accepted": false, "error": error.to_string() })),
+            }
+        }
+        Ok(serde_json::json!(results))
+    }
+
+    /// Runs the checks of the given transaction as if it were sent, without adding it to the memory pool or propagating it,
+    /// and returns the verdict of each check.
+    async fn validate_transaction(&self, transaction_hex: String) -> Result<Value, RpcError> {
+        let transaction_bytes = hex::decode(transaction_hex)?;
+        let transaction: Transaction<N> = FromBytes::from_bytes_le(&transaction_bytes)?;
+        let transaction_id = transaction.transaction_id();
+        let fee = transaction.value_balance().0;
+
+        let mut checks = Vec::new();
+        let mut check = |name: &str, failure: Option<String>| {
+            checks.push(serde_json::json!({ "check": name, "passed": failure.is_none(), "reason": failure }));
+        };
+
+        // Verify the proofs of the transaction in a blocking task, as it is the most expensive check.
+        let verified_transaction = transaction.clone();
+        let is_valid = tokio::task::spawn_blocking(move || verified_transaction.is_valid())
+            .await
+            .map_err(|error| RpcError::Message(error.to_string()))?;
+        check("proof", (!is_valid).then(|| "The transaction or one of its transition proofs is invalid".to_string()));
+
+        // Ensure the transaction references a ledger root of this ledger.
+        let ledger_root = transaction.ledger_root();
+        let failure = (!self.ledger.contains_ledger_root(&ledger_root)?).then(|| format!("Ledger root {} does not exist", ledger_root));
+        check("ledger_root", failure);
+
+        // Ensure the transaction is new, and spends no record that is already spent in the ledger or by the memory pool.
+        let failure = self
+            .ledger
+            .contains_transaction(&transaction_id)?
+            .then(|| "The transaction already exists in the ledger".to_string());
+        check("new", failure);
+        let mut spent_serial_numbers = Vec::new();
+        for serial_number in transaction.serial_numbers() {
+            if self.ledger.contains_serial_number(serial_number)? {
+                spent_serial_numbers.push(serial_number.to_string());
+            }
+        }
+        let failure = (!spent_serial_numbers.is_empty())
+            .then(|| format!("The serial numbers {} are already spent in the ledger", spent_serial_numbers.join(", ")));
+        check("double_spend", failure);
+        let mut existing_commitments = Vec::new();
+        for commitment in transaction.commitments() {
+            if self.ledger.contains_commitment(commitment)? {
+                existing_commitments.push(commitment.to_string());
+            }
+        }
+        let failure = (!existing_commitments.is_empty())
+            .then(|| format!("The commitments {} already exist in the ledger", existing_commitments.join(", ")));
+        check("commitments", failure);
+        let memory_pool = self.memory_pool.transactions();
+        let conflicting_transaction = memory_pool.iter().find(|unconfirmed| {
+            unconfirmed.transaction_id() != transaction_id
+                && unconfirmed
+                    .serial_numbers()
+                    .any(|serial_number| transaction.serial_numbers().any(|spent| spent == serial_number))
+        });
+        let failure = conflicting_transaction
+            .map(|unconfirmed| format!("The transaction spends a record spent by {} in the memory pool", unconfirmed.transaction_id()));
+        check("memory_pool", failure);
+
+        // Ensure the fee is not negative, as only the coinbase transaction of a block may mint credits,
+        // and the transaction meets the admission policy of the memory pool.
+        check("fee", (fee < 0).then(|| format!("The transaction mints {} gates, which only a coinbase transaction may", -fee)));
+        let rejection = match self.memory_pool.is_local_priority(&transaction_id) {
+            true => None,
+            false => self.memory_pool.policy().check_transaction(&transaction).err(),
+        };
+        check("policy", rejection.map(|rejection| format!("The transaction {}", rejection)));
+
+        let valid = checks.iter().all(|check| check["passed"] == true);
+        Ok(serde_json::json!({
+            "transaction_id": transaction_id,
+            "valid": valid,
+            "fee": fee,
+            "size_in_bytes": transaction_bytes.len(),
+            "in_memory_pool": memory_pool.iter().any(|unconfirmed| unconfirmed.transaction_id() == transaction_id),
+            "checks": checks,
+        }))
+    }
+
+    /// Returns the given transaction hex decoded as by `get_transaction`, without checking it or touching the memory pool.
+    async fn decode_transaction(&self, transaction_hex: String) -> Result<Value, RpcError> {
+        let transaction: Transaction<N> = FromBytes::from_bytes_le(&hex::decode(transaction_hex)?)?;
+        let value_balance = transaction.value_balance().0;
+        let decrypted_records: Vec<Record<N>> = transaction.to_records().collect();
+        Ok(serde_json::json!({ "transaction": transaction, "value_balance": value_balance, "decrypted_records": decrypted_records }))
+    }
+
+    /// Validates the given mined block as the next block of the ledger, and routes it to the ledger, returning its status.
+    async fn submit_block(&self, block_hex: String) -> Result<Value, RpcError> {
+        let block: Block<N> = FromBytes::from_bytes_le(&hex::decode(block_hex)?)?;
+        let (block_height, block_hash) = (block.height(), block.hash());
+        let status = |status: &str, reason: Option<String>| {
+            serde_json::json!({ "block_height": block_height, "block_hash": block_hash, "status": status, "reason": reason })
+        };
+
+        // Ensure the block is new, and extends the latest block of the ledger.
+        if self.ledger.contains_block_hash(&block_hash)? {
+            return Ok(status("duplicate", None));
+        }
+        let latest_block_height = self.ledger.latest_block_height();
+        if block_height <= latest_block_height {
+            return Ok(status("stale", Some(format!("The ledger is already at block {}", latest_block_height))));
+        }
+
+        // Validate the block in a blocking task, as it verifies the proofs of the block.
+        let ledger = self.ledger.clone();
+        let check_block = block.clone();
+        if let Err(error) = tokio::task::spawn_blocking(move || ledger.check_next_block(&check_block))
+            .await
+            .map_err(|error| RpcError::Message(error.to_string()))?
+        {
+            return Ok(status("rejected", Some(error.to_string())));
+        }
+
+        // Route an `UnconfirmedBlock` to the ledger, which adds the block and propagates it to the connected peers.
+        let request = LedgerRequest::UnconfirmedBlock(self.peers.local_ip(), block, self.prover_router.clone());
+        self.ledger_router
+            .send(request)
+            .await
+            .map_err(|error| RpcError::Message(format!("Failed to route the block to the ledger: {}", error)))?;
+
+        // Wait for the ledger to add the block, which it may not if it is syncing, or another block was added first.
+        let deadline = Instant::now() + Duration::from_millis(SUBMIT_BLOCK_TIMEOUT_IN_MS);
+        while Instant::now() < deadline {
+            if self.ledger.contains_block_hash(&block_hash)? {
+                return Ok(status("accepted", None));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        match self.ledger.latest_block_height() >= block_height {
+            true => Ok(status("stale", Some(format!("Another block was added at block {}", block_height)))),
+            false => Ok(status("pending", None)),
+        }
+    }
+
+    /// Connects to the given peers.
     async fn connect(&self, peers: Vec<String>) -> Result<bool, RpcError> {
         for peer_ip in &peers {
             let (router, _handler) = oneshot::channel();
@@ -268,19 +797,15 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
                     return Err(RpcError::Message(error.to_string()));
                 }
             };
-            if let Err(error) = self
-                .peers
-                .router()
-                .send(PeersRequest::Connect(
-                    res,
-                    self.ledger.clone(),
-                    self.ledger_router.clone(),
-                    self.operator_router.clone(),
-                    self.prover_router.clone(),
-                    router,
-                ))
-                .await
-            {
+            let request = PeersRequest::Connect(
+                res,
+                self.ledger.clone(),
+                self.ledger_router.clone(),
+                self.operator_router.clone(),
+                self.prover_router.clone(),
+                router,
+            );
+            if let Err(error) = self.peers_circuit_breaker.call(self.peers.router().send(request)).await? {
                 warn!("Connect {}", error);
             }
         }
@@ -292,6 +817,61 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(self.operator.get_shares_for_prover(&prover))
     }
 
+    /// Returns the shares submitted by the given prover between the given UNIX timestamps, inclusive.
+    async fn get_my_shares(&self, prover: Address<N>, from_ts: i64, to_ts: i64) -> Result<Vec<ShareRecord>, RpcError> {
+        if from_ts > to_ts {
+            return Err(RpcError::Message(format!(
+                "The start timestamp {} is after the end timestamp {}",
+                from_ts, to_ts
+            )));
+        }
+        let mut share_records = self.operator.get_share_history(&prover, from_ts, to_ts);
+        share_records.truncate(MAX_SHARE_RECORDS);
+        Ok(share_records)
+    }
+
+    /// Redirects the payouts of a prover to a new payout address, given the signature of the prover over the update.
+    async fn update_payout_address(
+        &self,
+        prover: Address<N>,
+        payout_address: Address<N>,
+        effective_from: u32,
+        signature: N::AccountSignature,
+    ) -> Result<PayoutAddressUpdate<N>, RpcError> {
+        Ok(self
+            .operator
+            .update_payout_address(prover, payout_address, effective_from, signature)?)
+    }
+
+    /// Returns the payout schedule of a prover, and the bounds of the payout schedules that provers may choose.
+    async fn get_payout_schedule(&self, prover: Address<N>) -> Result<Value, RpcError> {
+        let (schedule, is_chosen) = self.operator.get_payout_schedule(&prover)?;
+        let payout_policy = self.operator.payout_policy();
+        Ok(serde_json::json!({
+            "minimum_payout": schedule.minimum_payout,
+            "payout_interval": schedule.payout_interval,
+            "is_default": !is_chosen,
+            "minimum_payout_bounds": [payout_policy.minimum_payouts.start(), payout_policy.minimum_payouts.end()],
+            "payout_interval_bounds": [payout_policy.payout_intervals.start(), payout_policy.payout_intervals.end()],
+        }))
+    }
+
+    /// Sets the payout schedule of a prover, given the signature of the prover over the update.
+    async fn update_payout_schedule(
+        &self,
+        prover: Address<N>,
+        minimum_payout: u64,
+        payout_interval: u32,
+        effective_from: u32,
+        signature: N::AccountSignature,
+    ) -> Result<PayoutScheduleUpdate<N>, RpcError> {
+        let schedule = PayoutSchedule {
+            minimum_payout,
+            payout_interval,
+        };
+        Ok(self.operator.update_payout_schedule(prover, schedule, effective_from, signature)?)
+    }
+
     /// Returns the amount of shares submitted to the operator in total.
     async fn get_shares(&self) -> u64 {
         let shares = self.operator.to_shares();
@@ -304,6 +884,316 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         serde_json::json!(provers)
     }
 
+    /// Returns the sub-pools served by the operator, with the number of provers and shares of each.
+    async fn get_sub_pools(&self) -> Value {
+        let sub_pools: Vec<Value> = self
+            .operator
+            .get_sub_pools()
+            .into_iter()
+            .map(|(sub_pool, number_of_provers, shares)| {
+                serde_json::json!({
+                    "name": sub_pool.name,
+                    "fee_basis_points": sub_pool.fee_basis_points,
+                    "payout_address": self.operator.get_payout_address(&sub_pool),
+                    "public": sub_pool.is_public(),
+                    "server_name": sub_pool.server_name,
+                    "require_client_cert": sub_pool.require_client_cert,
+                    "provers": number_of_provers,
+                    "shares": shares,
+                })
+            })
+            .collect();
+        serde_json::json!(sub_pools)
+    }
+
+    /// Returns the luck and variance statistics of the pool.
+    async fn get_pool_stats(&self) -> Result<Value, RpcError> {
+        Ok(serde_json::to_value(self.operator.get_pool_stats())?)
+    }
+
+    /// Returns the terms of the pool and the instructions to connect to it, with its current statistics,
+    /// as configured on the operator.
+    async fn get_pool_info(&self) -> Value {
+        let payout_policy = self.operator.payout_policy();
+        let minimum_payout = payout_policy.default_schedule.minimum_payout;
+        let public_sub_pools: Vec<_> = self.operator.sub_pools().iter().filter(|sub_pool| sub_pool.is_public()).collect();
+
+        // Provers connect to the listener of the node, or to the TLS listener with the server name of a sub-pool, if any.
+        let (local_ip, tls_ip) = self.operator.listeners();
+        let mut endpoints = vec![serde_json::json!({ "protocol": "tcp", "address": local_ip })];
+        if let Some(tls_ip) = tls_ip {
+            let server_names: Vec<&String> = public_sub_pools.iter().filter_map(|sub_pool| sub_pool.server_name.as_ref()).collect();
+            endpoints.push(serde_json::json!({ "protocol": "tls", "address": tls_ip, "server_names": server_names }));
+        }
+        let (is_in_maintenance, backup_pool) = self.operator.maintenance();
+
+        let sub_pools: Vec<Value> = public_sub_pools
+            .iter()
+            .map(|sub_pool| {
+                serde_json::json!({
+                    "name": sub_pool.name,
+                    "fee_basis_points": sub_pool.fee_basis_points,
+                    "server_name": sub_pool.server_name,
+                })
+            })
+            .collect();
+        let stats = self.operator.get_pool_stats();
+        serde_json::json!({
+            "operator": self.address,
+            "payout_scheme": POOL_PAYOUT_SCHEME,
+            "fee_basis_points": public_sub_pools.first().map_or(0, |sub_pool| sub_pool.fee_basis_points),
+            "sub_pools": sub_pools,
+            "minimum_payout": minimum_payout,
+            "minimum_payout_credits": format_credits(AleoAmount(minimum_payout as i64)),
+            "minimum_payout_bounds": [payout_policy.minimum_payouts.start(), payout_policy.minimum_payouts.end()],
+            "payout_interval": payout_policy.default_schedule.payout_interval,
+            "payout_interval_bounds": [payout_policy.payout_intervals.start(), payout_policy.payout_intervals.end()],
+            "block_tag": self.operator.block_tag(),
+            "endpoints": endpoints,
+            "maintenance": {
+                "is_in_maintenance": is_in_maintenance,
+                "backup_pool": backup_pool,
+            },
+            "stats": {
+                "provers": self.operator.get_provers().len(),
+                "current_round_shares": stats.current_round_shares,
+                "current_round_effort": stats.current_round_effort,
+                "luck_7d": stats.luck_7d,
+                "luck_30d": stats.luck_30d,
+                "orphan_rate_30d": stats.orphan_rate_30d,
+                "payouts_halted": self.operator.are_payouts_halted(),
+            },
+        })
+    }
+
+    /// Returns the contributions of the provers over the epoch of the given period containing the given UNIX timestamp,
+    /// or the current epoch if `None`, ranked by their accepted shares.
+    async fn get_leaderboard(&self, period: ContributionPeriod, timestamp: Option<i64>) -> Result<Value, RpcError> {
+        let timestamp = timestamp.unwrap_or_else(|| OffsetDateTime::now_utc().unix_timestamp());
+        let started_at = period.epoch_start(timestamp);
+        let (rolled_up_to, contributions) = match self.operator.get_leaderboard(period, timestamp)? {
+            Some(epoch) => (Some(epoch.rolled_up_to), epoch.contributions),
+            None => (None, Default::default()),
+        };
+
+        // Rank the provers by their shares, then by the blocks they found, breaking ties by address.
+        let mut contributions: Vec<_> = contributions
+            .into_iter()
+            .map(|(prover, contribution)| (prover.to_string(), contribution))
+            .collect();
+        contributions.sort_unstable_by(|(prover_a, a), (prover_b, b)| {
+            (b.shares, b.blocks_found).cmp(&(a.shares, a.blocks_found)).then_with(|| prover_a.cmp(prover_b))
+        });
+        let number_of_provers = contributions.len();
+        let provers: Vec<Value> = contributions
+            .into_iter()
+            .take(MAX_LEADERBOARD_PROVERS)
+            .enumerate()
+            .map(|(index, (prover, contribution))| {
+                serde_json::json!({
+                    "rank": index + 1,
+                    "prover": prover,
+                    "shares": contribution.shares,
+                    "blocks_found": contribution.blocks_found,
+                })
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "period": period,
+            "started_at": started_at,
+            "ended_at": started_at.saturating_add(period.duration_in_secs()),
+            "rolled_up_to": rolled_up_to,
+            "number_of_provers": number_of_provers,
+            "provers": provers,
+        }))
+    }
+
+    /// Returns a smoothed estimate of the network difficulty and proof rate over the latest blocks.
+    async fn get_network_difficulty_estimate(&self) -> Result<DifficultyEstimate, RpcError> {
+        Ok(self.ledger.get_network_difficulty_estimate()?)
+    }
+
+    /// Returns the network statistics over the given number of latest blocks, or the default window if `None`.
+    async fn get_network_stats(&self, window: Option<u32>) -> Result<NetworkStats, RpcError> {
+        let window = window.unwrap_or(DEFAULT_NETWORK_STATS_WINDOW);
+        if window == 0 || window > MAXIMUM_NETWORK_STATS_WINDOW {
+            return Err(RpcError::Message(format!(
+                "The window must be between 1 and {} blocks",
+                MAXIMUM_NETWORK_STATS_WINDOW
+            )));
+        }
+        Ok(self.ledger.get_network_stats(window)?)
+    }
+
+    /// Returns the coinbase reward of the block at the given height, or the latest block if `None`,
+    /// with the total issued up to it and the full emission schedule.
+    async fn get_coinbase_reward_schedule(&self, block_height: Option<u32>) -> Result<Value, RpcError> {
+        let block_height = block_height.unwrap_or_else(|| self.ledger.latest_block_height());
+        let schedule = RewardSchedule::for_network::<N>();
+
+        let block_reward = schedule.block_reward(block_height);
+        let total_issued = schedule.total_issued(block_height);
+        let next_change_block_height = schedule.epoch(block_height).end_block_height.map(|end| end + 1);
+        let epochs: Vec<Value> = schedule
+            .epochs()
+            .iter()
+            .map(|epoch| {
+                serde_json::json!({
+                    "start_block_height": epoch.start_block_height,
+                    "end_block_height": epoch.end_block_height,
+                    "block_reward": epoch.block_reward,
+                    "block_reward_credits": format_credits(AleoAmount(epoch.block_reward)),
+                })
+            })
+            .collect();
+
+        Ok(serde_json::json!({
+            "block_height": block_height,
+            "block_reward": block_reward,
+            "block_reward_credits": format_credits(AleoAmount(block_reward)),
+            "total_issued": total_issued,
+            "total_issued_credits": format_credits(AleoAmount(total_issued)),
+            "next_change_block_height": next_change_block_height,
+            "epochs": epochs,
+        }))
+    }
+
+    /// Returns the projected rewards of a prover with the given proof rate over the given window,
+    /// given the current network difficulty, the fee of the public sub-pool, and the recent luck of the pool.
+    async fn estimate_earnings(&self, proof_rate: f64, window_in_secs: u64) -> Result<Value, RpcError> {
+        if !proof_rate.is_finite() || proof_rate < 0.0 {
+            return Err(RpcError::Message("The proof rate must be a non-negative number of proofs per second".to_string()));
+        }
+        if window_in_secs == 0 || window_in_secs > MAX_EARNINGS_WINDOW_IN_SECS {
+            return Err(RpcError::Message(format!(
+                "The window must be between 1 and {} seconds",
+                MAX_EARNINGS_WINDOW_IN_SECS
+            )));
+        }
+
+        // Each proof is a block with a probability of one over the smoothed network difficulty.
+        let difficulty_estimate = self.ledger.get_network_difficulty_estimate()?;
+        let expected_blocks = proof_rate * window_in_secs as f64 / difficulty_estimate.difficulty;
+
+        // Scale the expected blocks by the recent luck of the pool, if the pool has any history.
+        let luck = self.operator.get_pool_stats().luck_30d.unwrap_or(1.0);
+        let block_reward = Block::<N>::block_reward(self.ledger.latest_block_height().saturating_add(1)).0;
+        let gross_reward = (expected_blocks * luck * block_reward as f64) as i64;
+
+        let fee_basis_points = self.operator.sub_pools().public().map_or(0, |sub_pool| sub_pool.fee_basis_points);
+        let fee = (gross_reward as i128 * fee_basis_points as i128 / MAX_BASIS_POINTS as i128) as i64;
+
+        Ok(serde_json::json!({
+            "proof_rate": proof_rate,
+            "window_in_secs": window_in_secs,
+            "difficulty_target": difficulty_estimate.difficulty_target,
+            "block_reward": block_reward,
+            "block_reward_credits": format_credits(AleoAmount(block_reward)),
+            "expected_blocks": expected_blocks,
+            "luck": luck,
+            "fee_basis_points": fee_basis_points,
+            "gross_reward": gross_reward,
+            "gross_reward_credits": format_credits(AleoAmount(gross_reward)),
+            "fee": fee,
+            "fee_credits": format_credits(AleoAmount(fee)),
+            "estimated_reward": gross_reward - fee,
+            "estimated_reward_credits": format_credits(AleoAmount(gross_reward - fee)),
+        }))
+    }
+
+    ///
+    /// Returns the current job of the given prover, for a request signed by the prover at the given UNIX timestamp,
+    /// waiting for a new job if the prover already knows the current one. The waiting pollers are bounded by the operator,
+    /// and only acquire an execution permit to encode the job.
+    ///
+    async fn get_pool_job(
+        &self,
+        prover: Address<N>,
+        known_block_height: Option<u32>,
+        timestamp: i64,
+        signature: N::AccountSignature,
+    ) -> Result<Value, RpcError> {
+        let timeout = Duration::from_secs(POOL_JOB_LONG_POLL_IN_SECS);
+        match self
+            .operator
+            .wait_for_job(prover, known_block_height, timestamp, signature, timeout)
+            .await?
+        {
+            Some((share_difficulty, block_template)) => {
+                let _permit = self.acquire_execution_permit().await;
+                Ok(serde_json::json!({
+                    "share_difficulty": share_difficulty,
+                    "block_height": block_template.block_height(),
+                    "block_template": to_wire_hex(&block_template)?,
+                }))
+            }
+            None => Ok(Value::Null),
+        }
+    }
+
+    /// Submits a share of the given prover, stamped at the given UNIX timestamp, to the operator,
+    /// returning an error if the share is rejected.
+    async fn submit_share(&self, prover: Address<N>, nonce_hex: String, proof_hex: String, timestamp: i64) -> Result<bool, RpcError> {
+        let nonce: N::PoSWNonce = from_wire_hex(&nonce_hex)?;
+        let proof: PoSWProof<N> = from_wire_hex(&proof_hex)?;
+        self.operator.submit_share(prover, nonce, timestamp, proof).await?;
+        Ok(true)
+    }
+
+    /// Returns the hashrate leases of the operator, including the expired ones.
+    async fn get_leases(&self) -> Value {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let leases: Vec<Value> = self
+            .operator
+            .leases()
+            .to_leases()
+            .into_iter()
+            .map(|lease| {
+                let is_active = lease.is_active(now);
+                let mut value = serde_json::json!(lease);
+                value["active"] = serde_json::json!(is_active);
+                value
+            })
+            .collect();
+        serde_json::json!(leases)
+    }
+
+    /// Returns the incidents in the operation of the pool after the given event ID, such as rejected blocks.
+    async fn get_audit_events(&self, after_id: Option<u64>) -> Value {
+        serde_json::json!(self.operator.audit_events().to_events(after_id))
+    }
+
+    /// Returns the blocks found by the operator with the given tag, and whether each is in the canonical chain.
+    async fn get_blocks_by_tag(&self, tag: String) -> Value {
+        let blocks: Vec<Value> = self
+            .operator
+            .get_tagged_blocks(&tag)
+            .into_iter()
+            .map(|(block_height, block_hash)| {
+                let is_canonical = matches!(self.ledger.get_block_hash(block_height), Ok(canonical_hash) if canonical_hash == block_hash);
+                serde_json::json!({
+                    "block_height": block_height,
+                    "block_hash": block_hash,
+                    "canonical": is_canonical,
+                })
+            })
+            .collect();
+        serde_json::json!(blocks)
+    }
+
+    /// Returns the provenance of the blocks found by the operator at the given height, and whether each is in the canonical chain.
+    async fn get_found_block(&self, block_height: u32) -> Result<Value, RpcError> {
+        let canonical_hash = self.ledger.get_block_hash(block_height).ok();
+        let mut found_blocks = Vec::new();
+        for found_block in self.operator.get_found_blocks(block_height)? {
+            let is_canonical = canonical_hash == Some(found_block.block_hash);
+            let mut value = serde_json::to_value(found_block)?;
+            value["canonical"] = serde_json::json!(is_canonical);
+            found_blocks.push(value);
+        }
+        Ok(serde_json::json!(found_blocks))
+    }
+
     async fn get_mined_block_info(&self, height: u32, block_hash: N::BlockHash) -> Result<Value, RpcError> {
         let block = self.ledger.get_block(height)?;
         let canonical = block.hash() == block_hash;
@@ -311,6 +1201,7 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(serde_json::json!({
             "canonical": canonical,
             "value": -value.0,
+            "value_credits": format_credits(AleoAmount(-value.0)),
         }))
     }
 
@@ -319,6 +1210,334 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
         Ok(block_header_root)
     }
 
+    /// Returns the block given the block height, in the canonical JSON of the given schema version.
+    async fn get_canonical_block(&self, block_height: u32, schema_version: u32) -> Result<Value, RpcError> {
+        let schema_version = SchemaVersion::new(schema_version)?;
+        canonical::block_to_json(&self.ledger.get_block(block_height)?, schema_version)
+    }
+
+    /// Returns the block header given the block height, in the canonical JSON of the given schema version.
+    async fn get_canonical_block_header(&self, block_height: u32, schema_version: u32) -> Result<Value, RpcError> {
+        let schema_version = SchemaVersion::new(schema_version)?;
+        canonical::block_header_to_json(&self.ledger.get_block_header(block_height)?, schema_version)
+    }
+
+    /// Returns the transaction given the transaction ID, in the canonical JSON of the given schema version.
+    async fn get_canonical_transaction(&self, transaction_id: N::TransactionID, schema_version: u32) -> Result<Value, RpcError> {
+        let schema_version = SchemaVersion::new(schema_version)?;
+        canonical::transaction_to_json(&self.ledger.get_transaction(&transaction_id)?, schema_version)
+    }
+
+    /// Returns the block, transaction, transition, record commitment, or address matching the given query, with its type,
+    /// or `null` if nothing matches.
+    async fn search(&self, query: String) -> Result<Value, RpcError> {
+        let query = query.trim();
+        let found = |kind: &str, result: Value| Ok(serde_json::json!({ "type": kind, "result": result }));
+
+        // The identifiers each carry a distinct prefix, so at most one of them parses.
+        if let Ok(block_height) = query.parse::<u32>() {
+            if self.ledger.contains_block_height(block_height)? {
+                return found("block", Self::block_to_json(&self.ledger.get_block(block_height)?, None)?);
+            }
+        } else if let Ok(block_hash) = N::BlockHash::from_str(query) {
+            if self.ledger.contains_block_hash(&block_hash)? {
+                let block_height = self.ledger.get_block_height(&block_hash)?;
+                return found("block", Self::block_to_json(&self.ledger.get_block(block_height)?, None)?);
+            }
+        } else if let Ok(transaction_id) = N::TransactionID::from_str(query) {
+            if self.ledger.contains_transaction(&transaction_id)? {
+                return found("transaction", self.get_transaction(transaction_id).await?);
+            }
+        } else if let Ok(transition_id) = N::TransitionID::from_str(query) {
+            if let Ok(transition) = self.ledger.get_transition(&transition_id) {
+                return found("transition", serde_json::json!(transition));
+            }
+        } else if let Ok(commitment) = N::Commitment::from_str(query) {
+            if self.ledger.contains_commitment(&commitment)? {
+                let ciphertext = self.ledger.get_ciphertext(&commitment)?;
+                return found("commitment", serde_json::json!({ "commitment": commitment, "ciphertext": ciphertext }));
+            }
+        } else if let Ok(address) = Address::<N>::from_str(query) {
+            // Include the recent transactions of the address, if the address index is enabled.
+            let transactions = match self.ledger.is_address_index_enabled()? {
+                true => {
+                    let end_block_height = self.ledger.latest_block_height();
+                    let start_block_height = end_block_height.saturating_sub(SEARCH_ADDRESS_INDEX_RANGE - 1);
+                    self.get_transactions_for_address(address, start_block_height, end_block_height).await?
+                }
+                false => Value::Null,
+            };
+            return found("address", serde_json::json!({ "address": address, "transactions": transactions }));
+        }
+        Ok(Value::Null)
+    }
+
+    /// Returns the validity, network, and canonical form of the given address, as enforced by this node.
+    async fn validate_address(&self, address: String) -> Result<Value, RpcError> {
+        let address = address.trim();
+
+        // Ensure the address carries the expected network prefix, before attempting to decode it.
+        let result = match address.to_lowercase().starts_with(ADDRESS_PREFIX) {
+            true => Address::<N>::from_str(address).map_err(|error| error.to_string()),
+            false => Err(format!("Address must begin with '{}'", ADDRESS_PREFIX)),
+        };
+
+        match result {
+            Ok(address) => Ok(serde_json::json!({
+                "valid": true,
+                "address": address.to_string(),
+                "network": N::NETWORK_NAME,
+                "network_id": N::NETWORK_ID,
+            })),
+            Err(error) => Ok(serde_json::json!({
+                "valid": false,
+                "error": error,
+                "network": N::NETWORK_NAME,
+                "network_id": N::NETWORK_ID,
+            })),
+        }
+    }
+
+    /// Samples the CPU usage of the node for the given number of seconds, and writes the profile to disk.
+    async fn profile_cpu(&self, seconds: u64) -> Result<Value, RpcError> {
+        if seconds == 0 || seconds > MAX_CPU_PROFILE_DURATION_IN_SECS {
+            return Err(RpcError::Message(format!(
+                "The profile duration must be between 1 and {} seconds",
+                MAX_CPU_PROFILE_DURATION_IN_SECS
+            )));
+        }
+
+        #[cfg(feature = "profiling")]
+        {
+            let duration = std::time::Duration::from_secs(seconds);
+            let path = tokio::task::spawn_blocking(move || crate::profiler::capture_cpu_profile(duration, &profiles_directory()))
+                .await
+                .map_err(|error| RpcError::Message(error.to_string()))??;
+            profile_response(path)
+        }
+        #[cfg(not(feature = "profiling"))]
+        Err(profiling_disabled())
+    }
+
+    /// Writes a heap profile of the node to disk.
+    async fn profile_heap(&self) -> Result<Value, RpcError> {
+        #[cfg(feature = "profiling")]
+        {
+            let path = tokio::task::spawn_blocking(move || crate::profiler::capture_heap_profile(&profiles_directory()))
+                .await
+                .map_err(|error| RpcError::Message(error.to_string()))??;
+            profile_response(path)
+        }
+        #[cfg(not(feature = "profiling"))]
+        Err(profiling_disabled())
+    }
+
+    /// Leases the given share of jobs of the pool to the given renter, for the given duration.
+    async fn create_lease(
+        &self,
+        renter: Address<N>,
+        basis_points: u16,
+        duration_in_secs: u64,
+    ) -> Result<Value, RpcError> {
+        let lease = self.operator.leases().create(renter, basis_points, duration_in_secs)?;
+        info!("Leased {} basis points of the pool to {} until {}", lease.basis_points, lease.renter, lease.expires_at);
+        Ok(serde_json::json!(lease))
+    }
+
+    /// Cancels the given lease, returning `false` if it does not exist or has already expired.
+    async fn cancel_lease(&self, lease_id: u64) -> Result<bool, RpcError> {
+        Ok(self.operator.leases().cancel(lease_id))
+    }
+
+    /// Recomputes the payouts of the blocks found at the given height under the given sub-pools, or the current ones if `None`.
+    async fn replay_payouts(
+        &self,
+        block_height: u32,
+        sub_pools: Option<Vec<SubPool<N>>>,
+    ) -> Result<Value, RpcError> {
+        let sub_pools = match sub_pools {
+            Some(sub_pools) => SubPools::new(sub_pools)?,
+            None => self.operator.sub_pools().clone(),
+        };
+        let replays = self.operator.replay_payouts(block_height, &sub_pools)?;
+        Ok(serde_json::json!(replays))
+    }
+
+    /// Selects the given records of the pool wallet that fund a payout transaction of the given amount,
+    /// under the record selection strategy of the operator.
+    async fn select_payout_records(&self, amount: u64, records: Vec<(u32, Record<N>)>) -> Result<Vec<Record<N>>, RpcError> {
+        Ok(self.operator.select_payout_records(&records, amount)?)
+    }
+
+    /// Returns the payout batches which are yet to be paid, in the order they are to be paid.
+    async fn get_pending_payouts(&self) -> Result<Value, RpcError> {
+        let batches: Vec<Value> = self
+            .operator
+            .get_pending_payout_batches()
+            .into_iter()
+            .map(|(block_height, block_hash, index, batch)| {
+                serde_json::json!({
+                    "block_height": block_height,
+                    "block_hash": block_hash,
+                    "index": index,
+                    "payouts": batch.payouts,
+                })
+            })
+            .collect();
+        Ok(serde_json::json!(batches))
+    }
+
+    /// Records the given payout batch as paid by the transaction with the given transaction ID.
+    async fn complete_payout(
+        &self,
+        block_height: u32,
+        block_hash: N::BlockHash,
+        index: usize,
+        transaction_id: N::TransactionID,
+    ) -> Result<bool, RpcError> {
+        self.operator.complete_payout_batch(block_height, block_hash, index, transaction_id)?;
+        Ok(true)
+    }
+
+    /// Returns the record of the given ciphertext, or of the ciphertext of the given commitment in the ledger,
+    /// decrypted with the given account view key, or `None` if the record is not owned by the account.
+    async fn decrypt_record(&self, record: String, view_key: String) -> Result<Option<Record<N>>, RpcError> {
+        let view_key = ViewKey::<N>::from_str(view_key.trim()).map_err(|error| RpcError::Message(format!("Invalid view key: {}", error)))?;
+        let ciphertext = match N::Commitment::from_str(record.trim()) {
+            Ok(commitment) => self.ledger.get_ciphertext(&commitment)?,
+            Err(_) => N::RecordCiphertext::from_str(record.trim())
+                .map_err(|_| RpcError::Message("Expected a record ciphertext or commitment".to_string()))?,
+        };
+
+        // A view key of another account fails to decrypt the record, or decrypts it for another owner.
+        match Record::<N>::decrypt(&view_key.clone().into(), &ciphertext) {
+            Ok(record) if record.owner() == Address::from_view_key(&view_key) => Ok(Some(record)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Scans the given range of block heights for the records decryptable by the given view key,
+    /// and returns those whose serial numbers, derived with the given compute key, are not in the ledger.
+    async fn get_unspent_records(
+        &self,
+        view_key: String,
+        compute_key: String,
+        start_block_height: u32,
+        end_block_height: u32,
+    ) -> Result<Value, RpcError> {
+        if start_block_height > end_block_height || end_block_height - start_block_height >= MAX_UNSPENT_RECORDS_RANGE {
+            return Err(RpcError::Message(format!(
+                "The range must be ordered and span at most {} blocks",
+                MAX_UNSPENT_RECORDS_RANGE
+            )));
+        }
+        let latest_block_height = self.ledger.latest_block_height();
+        if end_block_height > latest_block_height {
+            return Err(RpcError::Message(format!("The ledger is at block {}", latest_block_height)));
+        }
+
+        let view_key = ViewKey::<N>::from_str(view_key.trim()).map_err(|error| RpcError::Message(format!("Invalid view key: {}", error)))?;
+        let compute_key: ComputeKey<N> = FromBytes::from_bytes_le(&hex::decode(compute_key.trim())?)?;
+        let address = Address::from_view_key(&view_key);
+        if Address::from_compute_key(&compute_key) != address {
+            return Err(RpcError::Message("The compute key does not belong to the account of the view key".to_string()));
+        }
+
+        // Scan the blocks in a blocking task, as it attempts to decrypt every ciphertext in the range.
+        let ledger = self.ledger.clone();
+        tokio::task::spawn_blocking(move || -> Result<Value, RpcError> {
+            let decryption_key = view_key.into();
+            let mut unspent_records = Vec::new();
+            for block_height in start_block_height..=end_block_height {
+                for transaction in ledger.get_block_transactions(block_height)?.iter() {
+                    for record in transaction.to_decrypted_records(&decryption_key) {
+                        // A view key of another account may decrypt the record for another owner.
+                        if record.owner() != address || record.is_dummy() {
+                            continue;
+                        }
+                        if !ledger.contains_serial_number(&record.to_serial_number(&compute_key)?)? {
+                            unspent_records.push(serde_json::json!({
+                                "block_height": block_height,
+                                "commitment": record.commitment(),
+                                "record": record,
+                            }));
+                        }
+                    }
+                }
+            }
+            Ok(serde_json::json!(unspent_records))
+        })
+        .await
+        .map_err(|error| RpcError::Message(error.to_string()))?
+    }
+
+    /// Returns the usage of the RPC servers by the clients with the most calls in the latest hour.
+    async fn get_api_usage(&self, limit: Option<usize>) -> Result<Value, RpcError> {
+        let usage = self
+            .usage
+            .as_ref()
+            .ok_or_else(|| RpcError::Message("The usage statistics of the RPC servers are not recorded".to_string()))?;
+        let limit = limit.unwrap_or(DEFAULT_API_USAGE_CLIENTS);
+        if limit > MAX_API_USAGE_CLIENTS {
+            return Err(RpcError::Message(format!("The limit must be at most {}", MAX_API_USAGE_CLIENTS)));
+        }
+        Ok(serde_json::json!(usage.usage(limit)))
+    }
+
+    /// Bans the given IP for the given number of seconds, or indefinitely, dropping its connections and refusing new ones.
+    async fn ban_peer(&self, ip: String, duration_in_secs: Option<u64>) -> Result<bool, RpcError> {
+        let ip = Self::parse_peer_ip(&ip)?;
+        self.peers.ban(ip, duration_in_secs.map(Duration::from_secs)).await;
+        Ok(true)
+    }
+
+    /// Lifts the ban of the given IP, returning `false` if the IP is not banned.
+    async fn unban_peer(&self, ip: String) -> Result<bool, RpcError> {
+        let ip = Self::parse_peer_ip(&ip)?;
+        Ok(self.peers.unban(ip).await)
+    }
+
+    /// Disconnects from the given peer, and refrains from reconnecting to it for a while if `is_restricted` is set.
+    /// Returns `false` if the node is not connected to the peer.
+    async fn disconnect(&self, peer_ip: SocketAddr, is_restricted: bool) -> Result<bool, RpcError> {
+        if !self.peers.is_connected_to(peer_ip).await {
+            return Ok(false);
+        }
+        let request = PeersRequest::Disconnect(peer_ip, is_restricted);
+        if let Err(error) = self.peers_circuit_breaker.call(self.peers.router().send(request)).await? {
+            warn!("[Disconnect] {}", error);
+        }
+        Ok(true)
+    }
+
+    /// Shuts the node down cleanly, and exits with the code of a stop.
+    async fn stop(&self) -> Result<bool, RpcError> {
+        self.request_shutdown(ShutdownKind::Stop)
+    }
+
+    /// Shuts the node down cleanly, and exits with the code of a restart, for its supervisor to start it again.
+    async fn restart(&self) -> Result<bool, RpcError> {
+        self.request_shutdown(ShutdownKind::Restart)
+    }
+
+    /// Puts the operator into maintenance, migrating its provers to the given backup pool, and shutting the node down cleanly.
+    async fn maintenance(&self, backup_ip: Option<SocketAddr>) -> Result<bool, RpcError> {
+        self.operator.enter_maintenance(backup_ip).await?;
+        info!("Received a maintenance request over RPC, shutting down once the current round is accounted for...");
+        Ok(true)
+    }
+
+    /// Changes the filter of the log output of the node, returning the filter it replaced.
+    async fn set_log_level(&self, filter: String) -> Result<Value, RpcError> {
+        let previous = E::log_filter().reload(&filter).map_err(RpcError::Message)?;
+        info!("Changed the log filter from '{}' to '{}' over RPC", previous, filter.trim());
+
+        Ok(serde_json::json!({
+            "previous": previous,
+            "filter": filter.trim(),
+        }))
+    }
+
     // /// Returns the current mempool and sync information known by this node.
     // async fn get_block_template(&self) -> Result<BlockTemplate, RpcError> {
     //     let canon = self.storage.canon().await?;
@@ -349,3 +1568,152 @@ impl<N: Network, E: Environment> RpcFunctions<N> for RpcContext<N, E> {
     //     })
     // }
 }
+
+impl<N: Network, E: Environment> RpcContext<N, E> {
+    /// Returns the ID of the block template for the current state of the ledger and memory pool,
+    /// the ID of the first block template since the latest block, and the memory pool version.
+    fn current_block_template_id(&self) -> (u64, u64, u64) {
+        let version = self.memory_pool.version();
+        let (template_id, clean_template_id) = self
+            .block_template_ids
+            .get_or_increment_with((self.ledger.latest_block_hash(), version), |previous, state| previous.0 != state.0);
+        (template_id, clean_template_id, version)
+    }
+
+    /// Waits until the block template changes from the one of the given ID, or the long-poll expires.
+    pub(crate) async fn wait_for_block_template(&self, template_id: u64) {
+        let deadline = Instant::now() + Duration::from_secs(BLOCK_TEMPLATE_LONG_POLL_IN_SECS);
+        loop {
+            let (current_template_id, _, version) = self.current_block_template_id();
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if current_template_id != template_id || remaining.is_zero() {
+                return;
+            }
+            // New blocks are not notified, so the latest block is rechecked at least every second.
+            self.memory_pool.wait_for_update(version, remaining.min(Duration::from_secs(1))).await;
+        }
+    }
+
+    /// Requests a clean shutdown of the given kind, which begins once the response has been sent.
+    fn request_shutdown(&self, kind: ShutdownKind) -> Result<bool, RpcError> {
+        match E::shutdown().request(kind, Duration::from_millis(SHUTDOWN_DELAY_IN_MS)) {
+            true => {
+                info!("Received a {:?} request over RPC, shutting down...", kind);
+                Ok(true)
+            }
+            false => Err(RpcError::Message("The node is already shutting down".to_string())),
+        }
+    }
+
+    /// Parses the IP of a peer, given either as an IP or as the socket address of the peer.
+    fn parse_peer_ip(ip: &str) -> Result<IpAddr, RpcError> {
+        ip.parse::<IpAddr>()
+            .or_else(|_| ip.parse::<SocketAddr>().map(|peer_ip| peer_ip.ip()))
+            .map_err(|_| RpcError::Message(format!("Invalid IP address '{}'", ip)))
+    }
+
+    /// Returns the given block at the given verbosity: 0 returns the block hex, 1 returns the block header
+    /// and its transaction IDs, and 2 returns the fully expanded block, including the transitions of its transactions.
+    pub(crate) fn block_to_json(block: &Block<N>, verbosity: Option<u8>) -> Result<Value, RpcError> {
+        match verbosity.unwrap_or(DEFAULT_BLOCK_VERBOSITY) {
+            0 => Ok(Value::String(hex::encode(block.to_bytes_le()?))),
+            1 => Ok(serde_json::json!({
+                "block_hash": block.hash(),
+                "previous_block_hash": block.previous_block_hash(),
+                "header": block.header(),
+                "transaction_ids": block.transactions().transaction_ids().collect::<Vec<_>>(),
+            })),
+            2 => Ok(serde_json::json!(block)),
+            verbosity => Err(RpcError::Message(format!("Invalid block verbosity {}, expected 0, 1, or 2", verbosity))),
+        }
+    }
+
+    /// Returns the transaction decoded from the given hex, if the transaction and its transition proofs are valid.
+    /// As verifying the proofs is expensive, this is called from a blocking task.
+    fn verify_sent_transaction(transaction_hex: &str) -> Result<Transaction<N>, RpcError> {
+        let transaction: Transaction<N> = FromBytes::from_bytes_le(&hex::decode(transaction_hex)?)?;
+        match transaction.is_valid() {
+            true => Ok(transaction),
+            false => Err(RpcError::Message(format!("Transaction {} is invalid", transaction.transaction_id()))),
+        }
+    }
+
+    /// Returns the given verified transaction if it meets the memory pool policy, does not exist in the ledger, and does not
+    /// conflict with the given transactions and serial numbers, which are then updated to include it.
+    fn validate_sent_transaction(
+        &self,
+        transaction: Transaction<N>,
+        transaction_ids: &mut HashSet<N::TransactionID>,
+        serial_numbers: &mut HashSet<N::SerialNumber>,
+    ) -> Result<Transaction<N>, RpcError> {
+        let transaction_id = transaction.transaction_id();
+
+        if !self.memory_pool.is_local_priority(&transaction_id) {
+            if let Err(rejection) = self.memory_pool.check_policy(&transaction) {
+                return Err(RpcError::Message(format!("Transaction {} {}", transaction_id, rejection)));
+            }
+        }
+        if transaction_ids.contains(&transaction_id) || self.ledger.contains_transaction(&transaction_id)? {
+            return Err(RpcError::Message(format!("Transaction {} already exists", transaction_id)));
+        }
+        for serial_number in transaction.serial_numbers() {
+            if serial_numbers.contains(serial_number) || self.ledger.contains_serial_number(serial_number)? {
+                return Err(RpcError::Message(format!(
+                    "Transaction {} spends serial number {}, which is already spent",
+                    transaction_id, serial_number
+                )));
+            }
+        }
+
+        transaction_ids.insert(transaction_id);
+        serial_numbers.extend(transaction.serial_numbers().cloned());
+        Ok(transaction)
+    }
+
+    /// Pushes the transactions accepted into the memory pool to the given subscriber, until it unsubscribes.
+    /// If `full` is `true`, the full transactions are pushed, otherwise only their transaction IDs.
+    pub(crate) fn subscribe_pending_transactions(&self, mut sink: SubscriptionSink, full: bool) {
+        let mut transactions = self.memory_pool.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let result = match transactions.recv().await {
+                    Ok(transaction) => match full {
+                        true => sink.send(&transaction),
+                        false => sink.send(&transaction.transaction_id()),
+                    },
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("A 'pendingTransactions' subscriber fell behind and missed {} transactions", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                // Stop once the subscriber has unsubscribed or disconnected.
+                if result.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Returns the directory that profiles are written into.
+#[cfg(feature = "profiling")]
+fn profiles_directory() -> std::path::PathBuf {
+    std::env::temp_dir().join("snarkos-profiles")
+}
+
+/// Returns the response for a profile that was written to the given path.
+#[cfg(feature = "profiling")]
+fn profile_response(path: std::path::PathBuf) -> Result<Value, RpcError> {
+    let bytes = std::fs::metadata(&path)?.len();
+    Ok(serde_json::json!({
+        "path": path,
+        "bytes": bytes,
+    }))
+}
+
+/// Returns the error for a profiling request on a node that was built without profiling support.
+#[cfg(not(feature = "profiling"))]
+fn profiling_disabled() -> RpcError {
+    RpcError::Message("Profiling is not supported by this node, rebuild it with '--features profiling'".to_string())
+}
@@ -17,7 +17,17 @@
 //! Definition of the public and private RPC endpoints.
 
 use crate::RpcError;
-use snarkvm::dpc::{Address, Block, BlockHeader, Network, Transaction, Transactions, Transition};
+use snarkos_network::helpers::SubPool;
+use snarkos_storage::{
+    ContributionPeriod,
+    DifficultyEstimate,
+    FeeEstimate,
+    NetworkStats,
+    PayoutAddressUpdate,
+    PayoutScheduleUpdate,
+    ShareRecord,
+};
+use snarkvm::dpc::{Address, Block, BlockHeader, Network, Record, Transaction, Transactions, Transition};
 
 use std::net::SocketAddr;
 
@@ -46,10 +56,18 @@ pub trait RpcFunctions<N: Network> {
     async fn latest_ledger_root(&self) -> Result<N::LedgerRoot, RpcError>;
 
     #[doc = include_str!("../documentation/public_endpoints/getblock.md")]
-    async fn get_block(&self, block_height: u32) -> Result<Block<N>, RpcError>;
+    async fn get_block(&self, block_height: u32, verbosity: Option<u8>) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getblockbyhash.md")]
+    async fn get_block_by_hash(&self, block_hash: N::BlockHash) -> Result<Block<N>, RpcError>;
 
     #[doc = include_str!("../documentation/public_endpoints/getblocks.md")]
-    async fn get_blocks(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<Block<N>>, RpcError>;
+    async fn get_blocks(
+        &self,
+        start_block_height: u32,
+        end_block_height: u32,
+        verbosity: Option<u8>,
+    ) -> Result<Vec<serde_json::Value>, RpcError>;
 
     #[doc = include_str!("../documentation/public_endpoints/getblockheight.md")]
     async fn get_block_height(&self, block_hash: N::BlockHash) -> Result<u32, RpcError>;
@@ -64,7 +82,12 @@ pub trait RpcFunctions<N: Network> {
     async fn get_block_header(&self, block_height: u32) -> Result<BlockHeader<N>, RpcError>;
 
     #[doc = include_str!("../documentation/public_endpoints/getblocktemplate.md")]
-    async fn get_block_template(&self) -> Result<serde_json::Value, RpcError>;
+    async fn get_block_template(
+        &self,
+        exclude_tx: Vec<N::TransactionID>,
+        include_tx: Vec<N::TransactionID>,
+        template_id: Option<u64>,
+    ) -> Result<serde_json::Value, RpcError>;
 
     #[doc = include_str!("../documentation/public_endpoints/getblocktransactions.md")]
     async fn get_block_transactions(&self, block_height: u32) -> Result<Transactions<N>, RpcError>;
@@ -72,40 +95,249 @@ pub trait RpcFunctions<N: Network> {
     #[doc = include_str!("../documentation/public_endpoints/getciphertext.md")]
     async fn get_ciphertext(&self, commitment: N::Commitment) -> Result<N::RecordCiphertext, RpcError>;
 
+    #[doc = include_str!("../documentation/public_endpoints/getciphertexts.md")]
+    async fn get_ciphertexts(&self, commitments: Vec<N::Commitment>) -> Result<Vec<Option<N::RecordCiphertext>>, RpcError>;
+
     #[doc = include_str!("../documentation/public_endpoints/getledgerproof.md")]
     async fn get_ledger_proof(&self, record_commitment: N::Commitment) -> Result<String, RpcError>;
 
+    #[doc = include_str!("../documentation/public_endpoints/getledgerproofs.md")]
+    async fn get_ledger_proofs(&self, record_commitments: Vec<N::Commitment>) -> Result<Vec<String>, RpcError>;
+
     #[doc = include_str!("../documentation/public_endpoints/getmemorypool.md")]
     async fn get_memory_pool(&self) -> Result<Vec<Transaction<N>>, RpcError>;
 
+    #[doc = include_str!("../documentation/public_endpoints/getmempoolinfo.md")]
+    async fn get_mempool_info(&self) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/estimatefee.md")]
+    async fn estimate_fee(&self, confirmation_target: u32) -> Result<FeeEstimate, RpcError>;
+
     #[doc = include_str!("../documentation/public_endpoints/gettransaction.md")]
     async fn get_transaction(&self, transaction_id: N::TransactionID) -> Result<serde_json::Value, RpcError>;
 
+    #[doc = include_str!("../documentation/public_endpoints/gettransactionsforaddress.md")]
+    async fn get_transactions_for_address(
+        &self,
+        address: Address<N>,
+        start_block_height: u32,
+        end_block_height: u32,
+    ) -> Result<serde_json::Value, RpcError>;
+
     #[doc = include_str!("../documentation/public_endpoints/gettransition.md")]
     async fn get_transition(&self, transition_id: N::TransitionID) -> Result<Transition<N>, RpcError>;
 
     #[doc = include_str!("../documentation/public_endpoints/getconnectedpeers.md")]
     async fn get_connected_peers(&self) -> Result<Vec<SocketAddr>, RpcError>;
 
+    #[doc = include_str!("../documentation/public_endpoints/getpeerinfo.md")]
+    async fn get_peer_info(&self) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getnetworktime.md")]
+    async fn get_network_time(&self) -> Result<serde_json::Value, RpcError>;
+
     #[doc = include_str!("../documentation/public_endpoints/getnodestate.md")]
     async fn get_node_state(&self) -> Result<serde_json::Value, RpcError>;
 
+    #[doc = include_str!("../documentation/public_endpoints/getsyncstatus.md")]
+    async fn get_sync_status(&self) -> Result<serde_json::Value, RpcError>;
+
     #[doc = include_str!("../documentation/public_endpoints/sendtransaction.md")]
     async fn send_transaction(&self, transaction_bytes: String) -> Result<N::TransactionID, RpcError>;
 
-    async fn connect(&self, peers: Vec<String>) -> Result<bool, RpcError>;
+    #[doc = include_str!("../documentation/public_endpoints/sendtransactions.md")]
+    async fn send_transactions(&self, transactions_hex: Vec<String>) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/validatetransaction.md")]
+    async fn validate_transaction(&self, transaction_hex: String) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/decodetransaction.md")]
+    async fn decode_transaction(&self, transaction_hex: String) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/submitblock.md")]
+    async fn submit_block(&self, block_hex: String) -> Result<serde_json::Value, RpcError>;
 
     #[doc = include_str!("../documentation/public_endpoints/getsharesforprover.md")]
     async fn get_shares_for_prover(&self, prover: Address<N>) -> Result<u64, RpcError>;
 
+    #[doc = include_str!("../documentation/public_endpoints/getmyshares.md")]
+    async fn get_my_shares(&self, prover: Address<N>, from_ts: i64, to_ts: i64) -> Result<Vec<ShareRecord>, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/updatepayoutaddress.md")]
+    async fn update_payout_address(
+        &self,
+        prover: Address<N>,
+        payout_address: Address<N>,
+        effective_from: u32,
+        signature: N::AccountSignature,
+    ) -> Result<PayoutAddressUpdate<N>, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getpayoutschedule.md")]
+    async fn get_payout_schedule(&self, prover: Address<N>) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/updatepayoutschedule.md")]
+    async fn update_payout_schedule(
+        &self,
+        prover: Address<N>,
+        minimum_payout: u64,
+        payout_interval: u32,
+        effective_from: u32,
+        signature: N::AccountSignature,
+    ) -> Result<PayoutScheduleUpdate<N>, RpcError>;
+
     async fn get_shares(&self) -> u64;
 
     #[doc = include_str!("../documentation/public_endpoints/getprovers.md")]
     async fn get_provers(&self) -> serde_json::Value;
 
+    #[doc = include_str!("../documentation/public_endpoints/getsubpools.md")]
+    async fn get_sub_pools(&self) -> serde_json::Value;
+
+    #[doc = include_str!("../documentation/public_endpoints/getpoolstats.md")]
+    async fn get_pool_stats(&self) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getpoolinfo.md")]
+    async fn get_pool_info(&self) -> serde_json::Value;
+
+    #[doc = include_str!("../documentation/public_endpoints/getleaderboard.md")]
+    async fn get_leaderboard(&self, period: ContributionPeriod, timestamp: Option<i64>) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/estimateearnings.md")]
+    async fn estimate_earnings(&self, proof_rate: f64, window_in_secs: u64) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getnetworkdifficultyestimate.md")]
+    async fn get_network_difficulty_estimate(&self) -> Result<DifficultyEstimate, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getnetworkstats.md")]
+    async fn get_network_stats(&self, window: Option<u32>) -> Result<NetworkStats, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getcoinbaserewardschedule.md")]
+    async fn get_coinbase_reward_schedule(&self, block_height: Option<u32>) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getpooljob.md")]
+    async fn get_pool_job(
+        &self,
+        prover: Address<N>,
+        known_block_height: Option<u32>,
+        timestamp: i64,
+        signature: N::AccountSignature,
+    ) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/submitshare.md")]
+    async fn submit_share(&self, prover: Address<N>, nonce_hex: String, proof_hex: String, timestamp: i64) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getleases.md")]
+    async fn get_leases(&self) -> serde_json::Value;
+
+    #[doc = include_str!("../documentation/public_endpoints/getauditevents.md")]
+    async fn get_audit_events(&self, after_id: Option<u64>) -> serde_json::Value;
+
+    #[doc = include_str!("../documentation/public_endpoints/getblocksbytag.md")]
+    async fn get_blocks_by_tag(&self, tag: String) -> serde_json::Value;
+
+    #[doc = include_str!("../documentation/public_endpoints/getfoundblock.md")]
+    async fn get_found_block(&self, block_height: u32) -> Result<serde_json::Value, RpcError>;
+
     async fn get_mined_block_info(&self, height: u32, block_hash: N::BlockHash) -> Result<serde_json::Value, RpcError>;
 
     async fn get_block_header_root(&self, block_height: u32) -> Result<N::BlockHeaderRoot, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getcanonicalblock.md")]
+    async fn get_canonical_block(&self, block_height: u32, schema_version: u32) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getcanonicalblockheader.md")]
+    async fn get_canonical_block_header(&self, block_height: u32, schema_version: u32) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/getcanonicaltransaction.md")]
+    async fn get_canonical_transaction(
+        &self,
+        transaction_id: N::TransactionID,
+        schema_version: u32,
+    ) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/search.md")]
+    async fn search(&self, query: String) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/public_endpoints/validateaddress.md")]
+    async fn validate_address(&self, address: String) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/profilecpu.md")]
+    async fn profile_cpu(&self, seconds: u64) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/profileheap.md")]
+    async fn profile_heap(&self) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/createlease.md")]
+    async fn create_lease(
+        &self,
+        renter: Address<N>,
+        basis_points: u16,
+        duration_in_secs: u64,
+    ) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/cancellease.md")]
+    async fn cancel_lease(&self, lease_id: u64) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/replaypayouts.md")]
+    async fn replay_payouts(
+        &self,
+        block_height: u32,
+        sub_pools: Option<Vec<SubPool<N>>>,
+    ) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/selectpayoutrecords.md")]
+    async fn select_payout_records(&self, amount: u64, records: Vec<(u32, Record<N>)>) -> Result<Vec<Record<N>>, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/getpendingpayouts.md")]
+    async fn get_pending_payouts(&self) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/completepayout.md")]
+    async fn complete_payout(
+        &self,
+        block_height: u32,
+        block_hash: N::BlockHash,
+        index: usize,
+        transaction_id: N::TransactionID,
+    ) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/decryptrecord.md")]
+    async fn decrypt_record(&self, record: String, view_key: String) -> Result<Option<Record<N>>, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/getunspentrecords.md")]
+    async fn get_unspent_records(
+        &self,
+        view_key: String,
+        compute_key: String,
+        start_block_height: u32,
+        end_block_height: u32,
+    ) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/getapiusage.md")]
+    async fn get_api_usage(&self, limit: Option<usize>) -> Result<serde_json::Value, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/banpeer.md")]
+    async fn ban_peer(&self, ip: String, duration_in_secs: Option<u64>) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/unbanpeer.md")]
+    async fn unban_peer(&self, ip: String) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/connect.md")]
+    async fn connect(&self, peers: Vec<String>) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/disconnect.md")]
+    async fn disconnect(&self, peer_ip: SocketAddr, is_restricted: bool) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/stop.md")]
+    async fn stop(&self) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/restart.md")]
+    async fn restart(&self) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/maintenance.md")]
+    async fn maintenance(&self, backup_ip: Option<SocketAddr>) -> Result<bool, RpcError>;
+
+    #[doc = include_str!("../documentation/private_endpoints/setloglevel.md")]
+    async fn set_log_level(&self, filter: String) -> Result<serde_json::Value, RpcError>;
 }
 
 // /// Definition of private RPC endpoints that require authentication.
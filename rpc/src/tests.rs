@@ -14,24 +14,70 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{initialize_rpc_server, rpc_trait::RpcFunctions, RpcContext};
+use crate::{
+    amount::{format_credits, parse_credits},
+    block_template::to_generic_block_template,
+    initialize_rest_server,
+    initialize_rpc_server,
+    initialize_rpc_ws_server,
+    rest::rest_method,
+    rpc_trait::RpcFunctions,
+    BlockTemplateSchema,
+    RateLimit,
+    RestPages,
+    RpcApiKey,
+    RpcAuditLog,
+    RpcAuth,
+    RpcClient,
+    RpcCompression,
+    RpcContext,
+    RpcCors,
+    RpcCredentials,
+    RpcHttpConfig,
+    RpcMethodPolicy,
+    RpcRateLimiter,
+    RpcRole,
+    RpcUsageTracker,
+};
 use snarkos_environment::{helpers::State, Client, CurrentNetwork, Environment};
-use snarkos_network::{ledger::Ledger, Operator, Peers, Prover};
+use snarkos_network::{
+    helpers::{
+        payout_address_message,
+        payout_schedule_message,
+        pool_job_message,
+        BLOCK_TEMPLATE_LONG_POLL_IN_SECS,
+        DEFAULT_MINIMUM_PAYOUT,
+        DEFAULT_PAYOUT_INTERVAL,
+    },
+    ledger::Ledger,
+    Operator,
+    Peers,
+    Prover,
+};
 use snarkos_storage::{
     storage::{rocksdb::RocksDB, Storage},
+    DifficultyEstimate,
+    FeeEstimate,
     LedgerState,
+    NetworkStats,
+    PayoutSchedule,
+    MAXIMUM_CONFIRMATION_TARGET,
 };
 use snarkvm::{
     dpc::{Address, AleoAmount, Network, Transaction, Transactions, Transition},
     prelude::{Account, Block, BlockHeader},
-    utilities::ToBytes,
+    utilities::{ToBits, ToBytes},
 };
 
 use jsonrpsee::{
-    core::{client::ClientT, Error as JsonrpseeError},
+    core::{
+        client::{ClientT, SubscriptionClientT},
+        Error as JsonrpseeError,
+    },
     http_client::{HttpClient, HttpClientBuilder},
     rpc_params,
     types::error::METHOD_NOT_FOUND_CODE,
+    ws_client::WsClientBuilder,
 };
 use rand::{thread_rng, Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
@@ -42,6 +88,8 @@ use std::{
     fs,
     net::SocketAddr,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
 fn temp_dir() -> std::path::PathBuf {
@@ -56,6 +104,22 @@ fn new_ledger_state<N: Network, S: Storage, P: AsRef<Path>>(path: Option<P>) ->
     }
 }
 
+/// Returns the API keys of the RPC server, with the read-only and wallet roles.
+fn new_rpc_api_keys() -> Vec<RpcApiKey> {
+    vec![
+        RpcApiKey {
+            name: "reader".to_string(),
+            key: "read-secret".to_string().into(),
+            role: RpcRole::ReadOnly,
+        },
+        RpcApiKey {
+            name: "wallet".to_string(),
+            key: "wallet-secret".to_string().into(),
+            role: RpcRole::Wallet,
+        },
+    ]
+}
+
 async fn new_rpc_context<N: Network, E: Environment, S: Storage, P: AsRef<Path>>(path: P) -> RpcContext<N, E> {
     let username = "root".to_string();
     let password = "pass".to_string();
@@ -70,10 +134,10 @@ async fn new_rpc_context<N: Network, E: Environment, S: Storage, P: AsRef<Path>>
     let (ledger_path, prover_path, operator_storage_path) = (path.as_ref().to_path_buf(), temp_dir(), temp_dir());
 
     // Initialize a new instance for managing peers.
-    let peers = Peers::new(node_addr, None).await;
+    let peers = Peers::new(node_addr, None, Default::default()).await;
 
     // Initialize a new instance for managing the ledger.
-    let ledger = Ledger::<N, E>::open::<S, _>(&ledger_path, peers.router())
+    let ledger = Ledger::<N, E>::open::<S, _>(&ledger_path, true, peers.router())
         .await
         .expect("Failed to initialize ledger");
 
@@ -83,6 +147,7 @@ async fn new_rpc_context<N: Network, E: Environment, S: Storage, P: AsRef<Path>>
         None,
         node_addr,
         Some(node_addr),
+        Default::default(),
         peers.router(),
         ledger.reader(),
         ledger.router(),
@@ -94,7 +159,14 @@ async fn new_rpc_context<N: Network, E: Environment, S: Storage, P: AsRef<Path>>
     let operator = Operator::open::<RocksDB, _>(
         &operator_storage_path,
         None,
+        Default::default(),
+        None,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
         node_addr,
+        None,
         prover.memory_pool(),
         peers.router(),
         ledger.reader(),
@@ -104,15 +176,24 @@ async fn new_rpc_context<N: Network, E: Environment, S: Storage, P: AsRef<Path>>
     .await
     .expect("Failed to initialize operator");
 
-    RpcContext::new(
+    let credentials = RpcCredentials {
         username,
-        password,
+        password: password.into(),
+    };
+    let auth = RpcAuth::new(credentials, new_rpc_api_keys()).expect("Failed to initialize the RPC authorization");
+
+    RpcContext::new(
+        auth,
+        None,
+        None,
+        RpcMethodPolicy::default(),
         None,
         peers,
         ledger.reader(),
         operator,
         prover.router(),
         prover.memory_pool(),
+        BlockTemplateSchema::Native,
     )
 }
 
@@ -125,7 +206,8 @@ async fn new_rpc_server<N: Network, E: Environment, S: Storage>(existing_rpc_con
     };
 
     // Initialize the RPC server.
-    let (rpc_server_addr, rpc_server_handle) = initialize_rpc_server("127.0.0.1:0".parse().unwrap(), rpc_context).await;
+    let (rpc_server_addr, rpc_server_handle) =
+        initialize_rpc_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, None, None, None, None).await;
 
     E::resources().register_task(None, rpc_server_handle);
 
@@ -183,6 +265,21 @@ async fn test_latest_block_height() {
     assert_eq!(response, CurrentNetwork::genesis_block().height());
 }
 
+#[tokio::test]
+async fn test_batch_request() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send a batch with more calls than can be executed at once to the server.
+    let batch = (0..32).map(|_| ("getblockheader", rpc_params![0u32])).collect();
+    let response: Vec<BlockHeader<CurrentNetwork>> = rpc_client.batch_request(batch).await.expect("Invalid response");
+
+    // Check that every call of the batch was answered.
+    assert_eq!(response.len(), 32);
+    assert!(response.iter().all(|header| header == CurrentNetwork::genesis_block().header()));
+}
+
 #[tokio::test]
 async fn test_latest_block_hash() {
     // Initialize a new RPC server and create an associated client.
@@ -209,6 +306,50 @@ async fn test_latest_block_header() {
     assert_eq!(response, *CurrentNetwork::genesis_block().header());
 }
 
+#[tokio::test]
+async fn test_get_canonical_block() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let params = rpc_params![0u32, 1u32];
+    let response: serde_json::Value = rpc_client.request("getcanonicalblock", params).await.expect("Invalid response");
+
+    // Check the canonical block.
+    let genesis_block = CurrentNetwork::genesis_block();
+    assert_eq!(response["schema_version"], 1);
+    assert_eq!(response["block_hash"], genesis_block.hash().to_string());
+    assert_eq!(response["header"]["schema_version"], 1);
+    assert_eq!(response["header"]["height"], 0);
+    assert_eq!(response["header"]["cumulative_weight"], genesis_block.header().cumulative_weight().to_string());
+    assert_eq!(response["transactions"][0]["transaction_id"], genesis_block.transactions()[0].transaction_id().to_string());
+
+    // Check that an unsupported schema version is rejected.
+    let params = rpc_params![0u32, 2u32];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getcanonicalblock", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_get_canonical_block_header() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let params = rpc_params![0u32, 1u32];
+    let response: serde_json::Value = rpc_client.request("getcanonicalblockheader", params).await.expect("Invalid response");
+
+    // Check the canonical block header.
+    let genesis_header = CurrentNetwork::genesis_block().header();
+    assert_eq!(response["schema_version"], 1);
+    assert_eq!(response["height"], genesis_header.height());
+    assert_eq!(response["timestamp"], genesis_header.timestamp());
+    assert_eq!(response["difficulty_target"], genesis_header.difficulty_target().to_string());
+    assert_eq!(response["nonce"], genesis_header.nonce().to_string());
+}
+
 #[tokio::test]
 async fn test_latest_block_transactions() {
     // Initialize a new RPC server and create an associated client.
@@ -253,6 +394,47 @@ async fn test_get_block() {
     assert_eq!(response, *CurrentNetwork::genesis_block());
 }
 
+#[tokio::test]
+async fn test_get_block_verbosity() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+    let genesis_block = CurrentNetwork::genesis_block();
+
+    // Check the block hex.
+    let response: String = rpc_client.request("getblock", rpc_params![0u32, 0u8]).await.expect("Invalid response");
+    assert_eq!(response, hex::encode(genesis_block.to_bytes_le().unwrap()));
+
+    // Check the block header and transaction IDs.
+    let response: serde_json::Value = rpc_client.request("getblock", rpc_params![0u32, 1u8]).await.expect("Invalid response");
+    assert_eq!(response["block_hash"], genesis_block.hash().to_string());
+    assert_eq!(response["header"], serde_json::json!(genesis_block.header()));
+    assert_eq!(response["transaction_ids"], serde_json::json!(genesis_block.transactions().transaction_ids().collect::<Vec<_>>()));
+    assert!(response.get("transactions").is_none());
+
+    // Check the fully expanded block.
+    let response: Block<CurrentNetwork> = rpc_client.request("getblock", rpc_params![0u32, 2u8]).await.expect("Invalid response");
+    assert_eq!(response, *genesis_block);
+
+    // Check an unknown verbosity is rejected.
+    let response: Result<serde_json::Value, _> = rpc_client.request("getblock", rpc_params![0u32, 3u8]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_get_block_by_hash() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let params = rpc_params![CurrentNetwork::genesis_block().hash()];
+    let response: Block<CurrentNetwork> = rpc_client.request("getblockbyhash", params).await.expect("Invalid response");
+
+    // Check the block.
+    assert_eq!(response, *CurrentNetwork::genesis_block());
+}
+
 #[tokio::test]
 async fn test_get_blocks() {
     // Initialize a new temporary directory.
@@ -399,6 +581,763 @@ async fn test_get_block_template() {
     assert_eq!(response["ledger_root"].as_str().unwrap(), expected_ledger_root);
     assert_eq!(response["transactions"].as_array().unwrap(), &expected_transactions);
     assert_eq!(response["coinbase_reward"].as_i64().unwrap(), expected_block_reward);
+    assert_eq!(response["coinbase_reward_credits"], format_credits(AleoAmount(expected_block_reward)));
+}
+
+#[tokio::test]
+async fn test_get_block_template_with_hints() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a transaction, which is not in the memory pool.
+    let address = Account::<CurrentNetwork>::new(&mut rng).address();
+    let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(address, AleoAmount(1234), true, &mut rng)
+        .expect("Failed to create a coinbase transaction");
+    let transaction_id = transaction.transaction_id();
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+
+    // Check the transaction hints require credentials.
+    let params = rpc_params![Option::<RpcCredentials>::None, vec![transaction_id]];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getblocktemplate", params).await;
+    assert!(response.is_err());
+
+    // Check an excluded transaction is accepted, even if it is not in the memory pool.
+    let params = rpc_params![credentials.clone(), vec![transaction_id]];
+    let response: serde_json::Value = rpc_client.request("getblocktemplate", params).await.expect("Invalid response");
+    assert_eq!(response["transactions"].as_array().map(|transactions| transactions.len()), Some(0));
+
+    // Check an included transaction must be in the memory pool.
+    let params = rpc_params![credentials, Vec::<<CurrentNetwork as Network>::TransactionID>::new(), vec![transaction_id]];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getblocktemplate", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_get_block_template_long_poll() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the template ID is repeated while the block template is unchanged.
+    let response: serde_json::Value = rpc_client.request("getblocktemplate", None).await.expect("Invalid response");
+    let template_id = response["template_id"].as_u64().expect("Missing template ID");
+    let response: serde_json::Value = rpc_client.request("getblocktemplate", None).await.expect("Invalid response");
+    assert_eq!(response["template_id"].as_u64(), Some(template_id));
+
+    // Check a stale template ID is answered without waiting for the long-poll.
+    let no_hints = Vec::<<CurrentNetwork as Network>::TransactionID>::new();
+    let params = rpc_params![Option::<RpcCredentials>::None, no_hints.clone(), no_hints, template_id - 1];
+    let start = std::time::Instant::now();
+    let response: serde_json::Value = rpc_client.request("getblocktemplate", params).await.expect("Invalid response");
+    assert_eq!(response["template_id"].as_u64(), Some(template_id));
+    assert!(start.elapsed() < std::time::Duration::from_secs(BLOCK_TEMPLATE_LONG_POLL_IN_SECS));
+}
+
+#[test]
+fn test_generic_block_template() {
+    let template = serde_json::json!({
+        "template_id": 26,
+        "previous_block_hash": "ab1...",
+        "block_height": 7,
+        "time": 1650000000,
+        "difficulty_target": 255,
+        "cumulative_weight": 1234,
+        "ledger_root": "al1...",
+        "transactions": ["tx"],
+        "coinbase_reward": 1000,
+        "coinbase_reward_credits": "0.001000",
+    });
+    let generic = to_generic_block_template(&template, true);
+    assert_eq!(generic["job_id"], "1a");
+    assert_eq!(generic["longpollid"], 26);
+    assert_eq!(generic["clean_jobs"], true);
+    assert_eq!(generic["previousblockhash"], "ab1...");
+    assert_eq!(generic["height"], 7);
+    assert_eq!(generic["curtime"], 1650000000);
+    assert_eq!(generic["target"], "00000000000000ff");
+    assert_eq!(generic["coinbasevalue"], 1000);
+    assert_eq!(generic["transactions"], serde_json::json!(["tx"]));
+
+    // The schema is parsed from the flag of the node.
+    assert_eq!("generic".parse::<BlockTemplateSchema>(), Ok(BlockTemplateSchema::Generic));
+    assert_eq!(BlockTemplateSchema::default().to_string(), "native");
+    assert!("bip22".parse::<BlockTemplateSchema>().is_err());
+}
+
+#[test]
+fn test_rpc_rate_limiter() {
+    let limit = RateLimit {
+        calls_per_second: 0.001,
+        burst: 3,
+    };
+    let expensive_limit = RateLimit {
+        calls_per_second: 0.001,
+        burst: 1,
+    };
+    let rate_limiter = RpcRateLimiter::new(limit, expensive_limit).unwrap();
+    let call = |method: &str| serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method }).to_string();
+    let ip = "203.0.113.7".parse().unwrap();
+
+    // Check the expensive methods are limited separately from the other methods.
+    assert!(rate_limiter.check_request(ip, call("getblocks").as_bytes()));
+    assert!(!rate_limiter.check_request(ip, call("getblocks").as_bytes()));
+    assert!(rate_limiter.check_request(ip, call("latestblockheight").as_bytes()));
+
+    // Check each call of a batch counts, and a batch beyond the remaining calls is rejected as a whole.
+    let batch = format!("[{},{},{}]", call("latestblock"), call("latestblock"), call("latestblock"));
+    assert!(!rate_limiter.check_request(ip, batch.as_bytes()));
+    assert!(rate_limiter.check_request(ip, b"not a call"));
+    assert!(rate_limiter.check_request(ip, call("latestblock").as_bytes()));
+    assert!(!rate_limiter.check_connection(ip));
+
+    // Check the clients are limited independently, and an IPv6 client by its /64 prefix.
+    assert!(rate_limiter.check_request("203.0.113.8".parse().unwrap(), call("getblocks").as_bytes()));
+    assert!(rate_limiter.check_request("2001:db8::1".parse().unwrap(), call("getblocks").as_bytes()));
+    assert!(!rate_limiter.check_request("2001:db8::2".parse().unwrap(), call("getblocks").as_bytes()));
+
+    // Check a request to the REST gateway is limited by the RPC method of its route.
+    let ip = "203.0.113.9".parse().unwrap();
+    assert!(rate_limiter.check_method(ip, rest_method("/api/v1/search/1")));
+    assert!(!rate_limiter.check_method(ip, rest_method("/api/v1/mempool/fee/1")));
+    assert!(rate_limiter.check_method(ip, rest_method("/api/v1/blocks/latest")));
+    assert!(rate_limiter.check_method(ip, rest_method("/pool")));
+
+    // Check the limits must be positive.
+    let unlimited = RateLimit {
+        calls_per_second: 0.0,
+        burst: 1,
+    };
+    assert!(RpcRateLimiter::new(unlimited, expensive_limit).is_err());
+}
+
+#[test]
+fn test_rpc_usage_tracker() {
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+    let usage = RpcUsageTracker::new(RpcAuth::new(credentials, new_rpc_api_keys()).unwrap());
+    let call = |method: &str, params: serde_json::Value| {
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+    };
+    let result = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": 0 });
+    let error = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "Method not found" } });
+    let ip = "203.0.113.7".parse().unwrap();
+
+    // Record a call, and a batch with a failed call, from the same IP.
+    let request = call("latestblockheight", serde_json::json!([])).to_string();
+    usage.record_request(ip, request.as_bytes(), true, result.to_string().as_bytes());
+    let batch = serde_json::json!([call("getblock", serde_json::json!([0])), call("unknown", serde_json::json!([]))]).to_string();
+    let response = serde_json::json!([result.clone(), error]).to_string();
+    usage.record_request(ip, batch.as_bytes(), true, response.as_bytes());
+
+    // Record a call with the credentials of an API key, and a rejected call with invalid credentials.
+    let reader = serde_json::json!([{ "username": "reader", "password": "read-secret" }]);
+    usage.record_request(ip, call("replaypayouts", reader).to_string().as_bytes(), true, result.to_string().as_bytes());
+    let rejected = call("replaypayouts", serde_json::json!([{ "username": "reader", "password": "wrong" }])).to_string();
+    usage.record_request(ip, rejected.as_bytes(), false, b"");
+
+    // Check the usage of the IP, which holds the calls without valid credentials.
+    let clients = usage.usage(10);
+    assert_eq!(clients.len(), 2);
+    assert_eq!(clients[0].client, RpcClient::Ip(ip));
+    let last_hour = &clients[0].last_hour;
+    assert_eq!(last_hour.calls, 4);
+    assert_eq!(last_hour.errors, 2);
+    assert_eq!(last_hour.error_rate, 0.5);
+    assert_eq!(last_hour.methods["replaypayouts"], 1);
+    assert_eq!(last_hour.bytes_received, (request.len() + batch.len() + rejected.len()) as u64);
+    assert_eq!(clients[0].last_minute, *last_hour);
+
+    // Check the usage of the API key.
+    assert_eq!(clients[1].client, RpcClient::ApiKey("reader".to_string()));
+    assert_eq!(clients[1].last_hour.calls, 1);
+    assert_eq!(clients[1].last_hour.errors, 0);
+
+    // Check the clients are limited, starting with the clients with the most calls.
+    assert_eq!(usage.usage(1).len(), 1);
+    assert_eq!(usage.usage(1)[0].client, RpcClient::Ip(ip));
+}
+
+#[test]
+fn test_rpc_audit_log() {
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+    let path = temp_dir().join("rpc-audit.log");
+    let audit_log = RpcAuditLog::open(path.to_str().unwrap(), RpcAuth::new(credentials, new_rpc_api_keys()).unwrap()).unwrap();
+    let call = |method: &str, params: serde_json::Value| {
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string()
+    };
+    let result = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": true }).to_string();
+    let error = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32001, "message": "Unauthorized" } }).to_string();
+    let ip = "203.0.113.7".parse().unwrap();
+
+    // Record a call to a public method, which is not audited.
+    audit_log.record_request(ip, call("latestblockheight", serde_json::json!([])).as_bytes(), 200, b"{}", Duration::from_millis(1));
+
+    // Record a call to a guarded method with valid credentials, and one with invalid credentials.
+    let params = serde_json::json!([{ "username": "root", "password": "pass" }, "203.0.113.8:4132"]);
+    audit_log.record_request(ip, call("banpeer", params).as_bytes(), 200, result.as_bytes(), Duration::from_millis(3));
+    let params = serde_json::json!([{ "username": "root", "password": "wrong" }, "203.0.113.8:4132"]);
+    audit_log.record_request(ip, call("banpeer", params).as_bytes(), 200, error.as_bytes(), Duration::from_millis(2));
+
+    // Check the guarded calls are audited, without their passwords.
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(!log.contains("pass"));
+    let records: Vec<serde_json::Value> = log.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["method"], "banpeer");
+    assert_eq!(records[0]["ip"], "203.0.113.7");
+    assert_eq!(records[0]["identity"], "root");
+    assert_eq!(records[0]["latency_in_ms"], 3);
+    assert_eq!(records[0]["status"], 200);
+    assert_eq!(records[0]["result_code"], 0);
+    assert_eq!(records[1]["identity"], serde_json::Value::Null);
+    assert_eq!(records[1]["result_code"], -32001);
+
+    // Check the parameters of both calls have the same digest once their credentials are stripped.
+    assert_eq!(records[0]["params_digest"], records[1]["params_digest"]);
+}
+
+#[test]
+fn test_rpc_method_policy() {
+    let methods = |methods: &[&str]| methods.iter().map(|method| method.to_string()).collect::<Vec<_>>();
+
+    // Check every method is enabled by default.
+    let policy = RpcMethodPolicy::default();
+    assert!(policy.is_enabled("getblock"));
+    assert!(policy.is_enabled("banpeer"));
+
+    // Check the denied groups and methods are disabled.
+    let policy = RpcMethodPolicy::new(&[], &methods(&["admin", "wallet", "getblocks"])).unwrap();
+    assert!(policy.is_enabled("getblock"));
+    assert!(policy.is_enabled("getpoolstats"));
+    assert!(!policy.is_enabled("banpeer"));
+    assert!(!policy.is_enabled("sendtransaction"));
+    assert!(!policy.is_enabled("getblocks"));
+
+    // Check only the allowed methods are enabled, unless they are denied.
+    let policy = RpcMethodPolicy::new(&methods(&["getblock", " Operator "]), &methods(&["submitshare"])).unwrap();
+    assert!(policy.is_enabled("getblock"));
+    assert!(policy.is_enabled("getpoolstats"));
+    assert!(!policy.is_enabled("getblocks"));
+    assert!(!policy.is_enabled("submitshare"));
+
+    // Check empty entries are rejected.
+    assert!(RpcMethodPolicy::new(&methods(&[""]), &[]).is_err());
+}
+
+#[tokio::test]
+async fn test_rpc_rate_limit() {
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let limit = RateLimit {
+        calls_per_second: 0.001,
+        burst: 2,
+    };
+    let rate_limiter = Arc::new(RpcRateLimiter::new(limit, limit).unwrap());
+
+    // Initialize a new RPC server behind the rate limiter, and create an associated client.
+    let (rpc_server_addr, rpc_server_handle) =
+        initialize_rpc_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, Some(rate_limiter), None, None, None).await;
+    Client::<CurrentNetwork>::resources().register_task(None, rpc_server_handle);
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the calls are forwarded to the RPC server, until the burst of the client is exhausted.
+    for _ in 0..2 {
+        let response: u32 = rpc_client.request("latestblockheight", None).await.expect("Invalid response");
+        assert_eq!(response, 0);
+    }
+    let response: Result<u32, _> = rpc_client.request("latestblockheight", None).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_rpc_ws_rate_limit() {
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let limit = RateLimit {
+        calls_per_second: 0.001,
+        burst: 3,
+    };
+    let rate_limiter = Arc::new(RpcRateLimiter::new(limit, limit).unwrap());
+
+    // Initialize a new RPC WebSocket server behind the rate limiter, and create an associated client.
+    let (rpc_ws_server_addr, rpc_ws_server_handle) =
+        initialize_rpc_ws_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, Some(rate_limiter)).await;
+    Client::<CurrentNetwork>::resources().register_task(None, rpc_ws_server_handle);
+    let rpc_ws_client = WsClientBuilder::default()
+        .build(format!("ws://{}", rpc_ws_server_addr))
+        .await
+        .expect("Couldn't build a JSON-RPC WebSocket client");
+
+    // Check each message counts as a call, after the connection itself, until the burst of the client is exhausted.
+    for _ in 0..2 {
+        let response: u32 = rpc_ws_client.request("latestblockheight", None).await.expect("Invalid response");
+        assert_eq!(response, 0);
+    }
+    let response: Result<u32, _> = rpc_ws_client.request("latestblockheight", None).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_rpc_cors() {
+    use hyper::{header, Body, Method, Request, StatusCode};
+
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let origin = "https://dashboard.example.com";
+    let origins = vec![origin.to_string()];
+    let cors = RpcCors::new(&origins, &["post".to_string()], &["Content-Type".to_string()]).unwrap();
+
+    // Initialize a new RPC server with the CORS policy.
+    let (rpc_server_addr, rpc_server_handle) =
+        initialize_rpc_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, None, Some(Arc::new(cors)), None, None).await;
+    Client::<CurrentNetwork>::resources().register_task(None, rpc_server_handle);
+    let client = hyper::Client::new();
+    let url = format!("http://{}", rpc_server_addr);
+    let call = r#"{"jsonrpc":"2.0","method":"latestblockheight","id":1}"#;
+
+    // Check the preflight request of an allowed origin is answered with the policy.
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri(&url)
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+        .body(Body::empty())
+        .unwrap();
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], origin);
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_METHODS], "POST");
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+
+    // Check the preflight request of a disallowed method is rejected.
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri(&url)
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+        .body(Body::empty())
+        .unwrap();
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Check the calls of an allowed origin are forwarded, and readable by the origin.
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(&url)
+        .header(header::ORIGIN, origin)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(call))
+        .unwrap();
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], origin);
+
+    // Check the calls of a disallowed origin are rejected.
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(&url)
+        .header(header::ORIGIN, "https://attacker.example.com")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(call))
+        .unwrap();
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Check the calls without an origin are forwarded, as they are not made from a browser.
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(&url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(call))
+        .unwrap();
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    // Check the policy requires an origin, and valid methods.
+    assert!(RpcCors::new(&[], &["POST".to_string()], &[]).is_err());
+    assert!(RpcCors::new(&origins, &["NOT A METHOD".to_string()], &[]).is_err());
+}
+
+#[tokio::test]
+async fn test_rpc_compression() {
+    use hyper::{body, header, Body, Method, Request, StatusCode};
+    use std::io::Read;
+
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let compression = RpcCompression::new(6).unwrap();
+
+    // Initialize a new RPC server with compression.
+    let (rpc_server_addr, rpc_server_handle) =
+        initialize_rpc_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, None, None, Some(compression), None).await;
+    Client::<CurrentNetwork>::resources().register_task(None, rpc_server_handle);
+    let client = hyper::Client::new();
+    let url = format!("http://{}", rpc_server_addr);
+    let request = |call: &'static str, accept_encoding: Option<&'static str>| {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(&url)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+        request.body(Body::from(call)).unwrap()
+    };
+    let get_block = r#"{"jsonrpc":"2.0","method":"getblock","params":[0],"id":1}"#;
+
+    // Check a large response is compressed with gzip, which decompresses to the block.
+    let response = client.request(request(get_block, Some("deflate;q=0.5, gzip"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    assert_eq!(response.headers()[header::VARY], "accept-encoding");
+    let compressed = body::to_bytes(response.into_body()).await.unwrap();
+    let mut decompressed = String::new();
+    flate2::read::GzDecoder::new(&compressed[..]).read_to_string(&mut decompressed).unwrap();
+    let decompressed: serde_json::Value = serde_json::from_str(&decompressed).unwrap();
+    assert_eq!(decompressed["result"], serde_json::json!(CurrentNetwork::genesis_block()));
+    assert!(compressed.len() < decompressed.to_string().len());
+
+    // Check a response is compressed with deflate, if the client prefers it.
+    let response = client.request(request(get_block, Some("gzip;q=0.1, deflate"))).await.unwrap();
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "deflate");
+
+    // Check a response is not compressed for a client which does not accept it.
+    for accept_encoding in [None, Some("br"), Some("gzip;q=0")] {
+        let response = client.request(request(get_block, accept_encoding)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+    }
+
+    // Check a small response is not compressed.
+    let latest_block_height = r#"{"jsonrpc":"2.0","method":"latestblockheight","id":1}"#;
+    let response = client.request(request(latest_block_height, Some("gzip"))).await.unwrap();
+    assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+
+    // Check the level must be between 1 and 9.
+    assert!(RpcCompression::new(0).is_err());
+    assert!(RpcCompression::new(10).is_err());
+}
+
+#[tokio::test]
+async fn test_rpc_http2() {
+    use hyper::{header, Body, Method, Request, StatusCode, Version};
+    use tokio::io::AsyncReadExt;
+
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let http_config = RpcHttpConfig {
+        http2: true,
+        idle_timeout: Some(Duration::from_secs(1)),
+    };
+
+    // Initialize a new RPC server with HTTP/2 and an idle timeout.
+    let (rpc_server_addr, rpc_server_handle) =
+        initialize_rpc_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, None, None, None, Some(http_config)).await;
+    Client::<CurrentNetwork>::resources().register_task(None, rpc_server_handle);
+    let url = format!("http://{}", rpc_server_addr);
+    let call = r#"{"jsonrpc":"2.0","method":"latestblockheight","id":1}"#;
+    let request = || {
+        Request::builder()
+            .method(Method::POST)
+            .uri(&url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(call))
+            .unwrap()
+    };
+
+    // Check the calls of a client with prior knowledge of HTTP/2 are served over HTTP/2.
+    let client = hyper::Client::builder().http2_only(true).build_http::<Body>();
+    let response = client.request(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.version(), Version::HTTP_2);
+
+    // Check the calls of an HTTP/1.1 client are still served over HTTP/1.1.
+    let client = hyper::Client::new();
+    let response = client.request(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.version(), Version::HTTP_11);
+
+    // Check an idle connection is closed after the idle timeout.
+    let mut stream = tokio::net::TcpStream::connect(rpc_server_addr).await.unwrap();
+    let mut buffer = [0u8; 1];
+    let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buffer)).await;
+    assert!(matches!(read, Ok(Ok(0)) | Ok(Err(_))));
+}
+
+#[tokio::test]
+async fn test_rest_gateway() {
+    use hyper::{body, Body, Method, Request, StatusCode};
+
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+
+    // Initialize a new REST gateway over the RPC context, with the built-in explorer and the landing page of the pool.
+    let pages = RestPages {
+        explorer: Some("/explorer".to_string()),
+        pool: Some("/pool".to_string()),
+    };
+    let (rest_server_addr, rest_server_handle) =
+        initialize_rest_server("127.0.0.1:0".parse().unwrap(), rpc_context, pages, None, None, None, None, None).await;
+    Client::<CurrentNetwork>::resources().register_task(None, rest_server_handle);
+    let client = hyper::Client::new();
+    let get = |path: &str| {
+        let uri = format!("http://{}{}", rest_server_addr, path).parse().unwrap();
+        let client = client.clone();
+        async move {
+            let response = client.get(uri).await.unwrap();
+            let status = response.status();
+            let body = body::to_bytes(response.into_body()).await.unwrap();
+            (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+        }
+    };
+    let genesis_block = CurrentNetwork::genesis_block();
+
+    // Check the routes map onto the RPC endpoints.
+    let (status, response) = get("/api/v1/blocks/latest/height").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, serde_json::json!(0));
+
+    let (status, response) = get("/api/v1/blocks/0").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, serde_json::to_value(genesis_block).unwrap());
+
+    let (status, response) = get(&format!("/api/v1/blocks/hash/{}", genesis_block.hash())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, serde_json::to_value(genesis_block).unwrap());
+
+    let (status, response) = get("/api/v1/blocks?start=0&end=0").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, serde_json::json!([genesis_block]));
+
+    let transaction_id = genesis_block.transactions().transaction_ids().next().unwrap();
+    let (status, response) = get(&format!("/api/v1/transactions/{}", transaction_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["transaction"]["transaction_id"], serde_json::json!(transaction_id));
+
+    let (status, response) = get("/api/v1/mempool").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, serde_json::json!([]));
+
+    let (status, response) = get("/api/v1/mempool/info").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["number_of_transactions"], 0);
+    assert!(response["median_value_balance"].is_null());
+
+    let (status, response) = get("/api/v1/mempool/fee/6").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["confirmation_target"], 6);
+
+    let (status, response) = get("/api/v1/search/0").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["type"], "block");
+
+    let (status, response) = get("/api/v1/pool/info").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["payout_scheme"], "proportional");
+    assert_eq!(response["endpoints"][0]["protocol"], "tcp");
+    assert_eq!(response["stats"]["current_round_shares"], 0);
+
+    let (status, response) = get("/api/v1/pool/leaderboard/weekly?timestamp=0").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["started_at"], -259_200);
+
+    // Check the pages are served at their paths, with or without a trailing slash.
+    for (path, title) in [("/explorer", "snarkOS Explorer"), ("/explorer/", "snarkOS Explorer"), ("/pool", "snarkOS Pool")] {
+        let response = client.get(format!("http://{}{}", rest_server_addr, path).parse().unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[hyper::header::CONTENT_TYPE], "text/html; charset=utf-8");
+        let page = body::to_bytes(response.into_body()).await.unwrap();
+        assert!(std::str::from_utf8(&page).unwrap().contains(title));
+    }
+
+    // Check the requests which do not match a route, or have malformed parameters, are rejected.
+    let (status, _) = get("/api/v1/unknown").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, response) = get("/api/v1/blocks/latest-block").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response["error"].is_string());
+    let (status, _) = get("/api/v1/blocks?start=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Check only GET requests are served.
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("http://{}/api/v1/mempool", rest_server_addr))
+        .body(Body::empty())
+        .unwrap();
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn test_rest_block_export() {
+    use hyper::{body, header, Request, StatusCode};
+
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let compression = RpcCompression::new(6).unwrap();
+
+    // Initialize a new REST gateway with compression, which does not apply to the streamed exports.
+    let pages = RestPages::default();
+    let (rest_server_addr, rest_server_handle) =
+        initialize_rest_server("127.0.0.1:0".parse().unwrap(), rpc_context, pages, None, None, None, Some(compression), None).await;
+    Client::<CurrentNetwork>::resources().register_task(None, rest_server_handle);
+    let client = hyper::Client::new();
+    let get = |path: &str| {
+        let request = Request::get(format!("http://{}{}", rest_server_addr, path))
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(hyper::Body::empty())
+            .unwrap();
+        client.request(request)
+    };
+    let genesis_block = CurrentNetwork::genesis_block();
+
+    // Check the blocks are streamed as newline-delimited JSON, from the genesis block to the latest block by default.
+    let response = get("/api/v1/export/blocks").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+    assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+    let body = body::to_bytes(response.into_body()).await.unwrap();
+    let blocks: Vec<serde_json::Value> = body
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).unwrap())
+        .collect();
+    assert_eq!(blocks, vec![serde_json::to_value(genesis_block).unwrap()]);
+
+    // Check the blocks are streamed at the given verbosity.
+    let response = get("/api/v1/export/blocks?start=0&end=0&verbosity=1").await.unwrap();
+    let body = body::to_bytes(response.into_body()).await.unwrap();
+    let block: serde_json::Value = serde_json::from_slice(body.strip_suffix(b"\n").unwrap()).unwrap();
+    assert_eq!(block["block_hash"], serde_json::json!(genesis_block.hash()));
+
+    // Check a range beyond the latest block, or an invalid verbosity, is rejected before streaming.
+    for path in ["/api/v1/export/blocks?end=1", "/api/v1/export/blocks?verbosity=3"] {
+        let response = get(path).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[cfg(feature = "grpc")]
+#[tokio::test]
+async fn test_grpc_server() {
+    use crate::grpc::{
+        initialize_grpc_server,
+        proto::{self, node_client::NodeClient},
+    };
+    use futures::TryStreamExt;
+
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+
+    // Initialize a new gRPC server over the RPC context.
+    let (grpc_server_addr, grpc_server_handle) = initialize_grpc_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, None).await;
+    Client::<CurrentNetwork>::resources().register_task(None, grpc_server_handle);
+    let mut client = NodeClient::connect(format!("http://{}", grpc_server_addr)).await.unwrap();
+    let genesis_block = CurrentNetwork::genesis_block();
+
+    // Check the blocks are served with their identifiers, and in full as JSON.
+    let block = client
+        .get_block(proto::GetBlockRequest { block_height: 0 })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(block.height, 0);
+    assert_eq!(block.hash, genesis_block.hash().to_string());
+    assert_eq!(block.transaction_ids.len(), genesis_block.transactions().transaction_ids().count());
+    let json: serde_json::Value = serde_json::from_str(&block.json).unwrap();
+    assert_eq!(json, serde_json::to_value(genesis_block).unwrap());
+
+    let request = proto::GetBlockByHashRequest {
+        block_hash: block.hash.clone(),
+    };
+    assert_eq!(client.get_block_by_hash(request).await.unwrap().into_inner(), block);
+
+    // Check the blocks in a range are streamed.
+    let request = proto::GetBlocksRequest {
+        start_block_height: 0,
+        end_block_height: 0,
+    };
+    let blocks: Vec<_> = client.get_blocks(request).await.unwrap().into_inner().try_collect().await.unwrap();
+    assert_eq!(blocks, vec![block]);
+
+    // Check the memory pool is streamed, and the operator has no shares.
+    let request = proto::GetMemoryPoolRequest {};
+    let transactions: Vec<_> = client
+        .get_memory_pool(request)
+        .await
+        .unwrap()
+        .into_inner()
+        .try_collect()
+        .await
+        .unwrap();
+    assert!(transactions.is_empty());
+    assert_eq!(client.get_shares(proto::GetSharesRequest {}).await.unwrap().into_inner().shares, 0);
+
+    // Check malformed and unknown identifiers are rejected.
+    let request = proto::GetBlockByHashRequest {
+        block_hash: "hash".to_string(),
+    };
+    assert_eq!(
+        client.get_block_by_hash(request).await.unwrap_err().code(),
+        tonic::Code::InvalidArgument
+    );
+    assert!(client.get_block(proto::GetBlockRequest { block_height: 1 }).await.is_err());
+}
+
+#[cfg(feature = "grpc")]
+#[tokio::test]
+async fn test_grpc_rate_limit() {
+    use crate::grpc::{
+        initialize_grpc_server,
+        proto::{self, node_client::NodeClient},
+    };
+
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let limit = RateLimit {
+        calls_per_second: 0.001,
+        burst: 1,
+    };
+    let rate_limiter = Arc::new(RpcRateLimiter::new(limit, limit).unwrap());
+
+    // Initialize a new gRPC server behind the rate limiter.
+    let (grpc_server_addr, grpc_server_handle) =
+        initialize_grpc_server("127.0.0.1:0".parse().unwrap(), rpc_context, None, Some(rate_limiter)).await;
+    Client::<CurrentNetwork>::resources().register_task(None, grpc_server_handle);
+    let mut client = NodeClient::connect(format!("http://{}", grpc_server_addr)).await.unwrap();
+
+    // Check the calls are served, until the burst of the client is exhausted.
+    assert!(client.get_shares(proto::GetSharesRequest {}).await.is_ok());
+    let error = client.get_shares(proto::GetSharesRequest {}).await.unwrap_err();
+    assert_eq!(error.code(), tonic::Code::ResourceExhausted);
+}
+
+#[test]
+fn test_format_and_parse_credits() {
+    // Check the formatting of amounts.
+    assert_eq!(format_credits(AleoAmount(0)), "0.000000");
+    assert_eq!(format_credits(AleoAmount(1_500_000)), "1.500000");
+    assert_eq!(format_credits(AleoAmount(-1)), "-0.000001");
+    assert_eq!(format_credits(AleoAmount(i64::MIN)), "-9223372036854.775808");
+
+    // Check the parsing of amounts, including the round trip of formatted amounts.
+    assert_eq!(parse_credits("1.5").unwrap(), AleoAmount(1_500_000));
+    assert_eq!(parse_credits("-0.000001").unwrap(), AleoAmount(-1));
+    assert_eq!(parse_credits("42").unwrap(), AleoAmount(42_000_000));
+    for gates in [0, 1, -1, 123_456_789, i64::MAX] {
+        assert_eq!(parse_credits(&format_credits(AleoAmount(gates))).unwrap(), AleoAmount(gates));
+    }
+
+    // Check that invalid amounts are rejected.
+    for credits in ["", "-", ".5", "1.", "1.0000001", "1,5", "+1", "1e6", "9223372036855"] {
+        assert!(parse_credits(credits).is_err(), "'{}' should be rejected", credits);
+    }
 }
 
 #[tokio::test]
@@ -444,6 +1383,34 @@ async fn test_get_ciphertext() {
     );
 }
 
+#[tokio::test]
+async fn test_get_ciphertexts() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Get the commitments from the genesis coinbase transaction, and a commitment which does not exist.
+    let coinbase_transaction = CurrentNetwork::genesis_block().to_coinbase_transaction().unwrap();
+    let mut commitments: Vec<String> = coinbase_transaction.commitments().map(|commitment| commitment.to_string()).collect();
+    let unknown_commitment = <CurrentNetwork as Network>::Commitment::default().to_string();
+    commitments.push(unknown_commitment);
+
+    // Send the request to the server.
+    let params = rpc_params![commitments.clone()];
+    let response: Vec<Option<<CurrentNetwork as Network>::RecordCiphertext>> =
+        rpc_client.request("getciphertexts", params).await.expect("Invalid response");
+
+    // Check the ciphertexts are returned in the order of the commitments.
+    let expected: Vec<_> = coinbase_transaction.ciphertexts().cloned().map(Some).chain([None]).collect();
+    assert_eq!(response, expected);
+
+    // Check a batch beyond the limit is refused.
+    let params = rpc_params![vec![commitments[0].clone(); 501]];
+    let response: Result<Vec<Option<<CurrentNetwork as Network>::RecordCiphertext>>, _> =
+        rpc_client.request("getciphertexts", params).await;
+    assert!(response.is_err());
+}
+
 #[tokio::test]
 async fn test_get_ledger_proof() {
     let mut rng = ChaChaRng::seed_from_u64(thread_rng().gen());
@@ -496,6 +1463,73 @@ async fn test_get_ledger_proof() {
     assert_eq!(response, expected);
 }
 
+#[tokio::test]
+async fn test_get_ledger_proofs() {
+    let mut rng = ChaChaRng::seed_from_u64(thread_rng().gen());
+
+    // Initialize a new temporary directory.
+    let directory = temp_dir();
+
+    // Initialize a new ledger state at the temporary directory.
+    let ledger_state = new_ledger_state::<CurrentNetwork, RocksDB, PathBuf>(Some(directory.clone()));
+
+    // Mine the next block.
+    let address = Account::<CurrentNetwork>::new(&mut rng).address();
+    let (block_1, _) = ledger_state
+        .mine_next_block(address, true, &[], &Default::default(), &mut rng)
+        .expect("Failed to mine");
+    ledger_state.add_next_block(&block_1).expect("Failed to add next block to ledger");
+
+    // Get the ledger proofs of the commitments of the genesis block and the new block.
+    let record_commitments: Vec<_> = [CurrentNetwork::genesis_block(), &block_1]
+        .iter()
+        .flat_map(|block| block.transactions().iter().flat_map(|transaction| transaction.commitments().copied()))
+        .collect();
+    let expected: Vec<String> = record_commitments
+        .iter()
+        .map(|commitment| {
+            let ledger_proof = ledger_state.get_ledger_inclusion_proof(*commitment).unwrap();
+            hex::encode(ledger_proof.to_bytes_le().expect("Failed to serialize ledger proof"))
+        })
+        .collect();
+
+    // Drop the handle to ledger_state. Note this does not remove the blocks in the temporary directory.
+    drop(ledger_state);
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(directory).await;
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(Some(rpc_server_context)).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the ledger proofs are returned in the order of the commitments.
+    let params = rpc_params![record_commitments.clone()];
+    let response: Vec<String> = rpc_client.request("getledgerproofs", params).await.expect("Invalid response");
+    assert_eq!(response, expected);
+
+    // Check a batch with a commitment which does not exist is refused.
+    let mut record_commitments = record_commitments;
+    record_commitments.push(Default::default());
+    let response: Result<Vec<String>, _> = rpc_client.request("getledgerproofs", rpc_params![record_commitments]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_get_sync_status() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let response: serde_json::Value = rpc_client.request("getsyncstatus", None).await.expect("Invalid response");
+
+    // Check a node without peers has caught up, and has no throughput measured on its first call.
+    assert_eq!(response["latest_block_height"], 0);
+    assert_eq!(response["best_known_block_height"], 0);
+    assert_eq!(response["blocks_remaining"], 0);
+    assert!(response["blocks_per_second"].is_null());
+    assert!(response["eta_in_secs"].is_null());
+}
+
 #[tokio::test]
 async fn test_get_node_state() {
     // Initialize a new RPC server and create an associated client.
@@ -510,6 +1544,15 @@ async fn test_get_node_state() {
         "address": Option::<Address<CurrentNetwork>>::None,
         "candidate_peers": Vec::<SocketAddr>::new(),
         "connected_peers": Vec::<SocketAddr>::new(),
+        "handshake_failures": {
+            "bad_genesis": 0,
+            "banned": 0,
+            "node_type_not_permitted": 0,
+            "other": 0,
+            "outdated_version": 0,
+            "wrong_network": 0,
+        },
+        "outdated_peer_versions": {},
         "latest_block_hash": CurrentNetwork::genesis_block().hash(),
         "latest_block_height": 0u32,
         "latest_cumulative_weight": 0u128,
@@ -571,6 +1614,48 @@ async fn test_get_transaction() {
     assert_eq!(response.decrypted_records, expected_decrypted_records)
 }
 
+#[tokio::test]
+async fn test_get_transactions_for_address() {
+    let mut rng = ChaChaRng::seed_from_u64(thread_rng().gen());
+
+    // Initialize a new temporary directory.
+    let directory = temp_dir();
+
+    // Initialize a new ledger state at the temporary directory.
+    let ledger_state = new_ledger_state::<CurrentNetwork, RocksDB, PathBuf>(Some(directory.clone()));
+
+    // Mine the next block, with a public coinbase record for a new account.
+    let address = Account::<CurrentNetwork>::new(&mut rng).address();
+    let (block_1, _) = ledger_state
+        .mine_next_block(address, true, &[], &Default::default(), &mut rng)
+        .expect("Failed to mine");
+    ledger_state.add_next_block(&block_1).expect("Failed to add next block to ledger");
+
+    // Drop the handle to ledger_state. Note this does not remove the blocks in the temporary directory.
+    drop(ledger_state);
+
+    // Initialize a new RPC server, which indexes the existing blocks, and create an associated client.
+    let rpc_server_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(directory).await;
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(Some(rpc_server_context)).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let params = rpc_params![address, 0u32, 1u32];
+    let response: serde_json::Value = rpc_client.request("gettransactionsforaddress", params).await.expect("Invalid response");
+
+    // Check the transactions of the address.
+    let expected = serde_json::json!([{
+        "block_height": 1,
+        "transaction_id": block_1.transactions()[0].transaction_id(),
+    }]);
+    assert_eq!(response, expected);
+
+    // Check that a range beyond the maximum is rejected.
+    let params = rpc_params![address, 0u32, 10_000u32];
+    let response: Result<serde_json::Value, _> = rpc_client.request("gettransactionsforaddress", params).await;
+    assert!(response.is_err());
+}
+
 #[tokio::test]
 async fn test_get_transition() {
     // Initialize a new RPC server and create an associated client.
@@ -612,12 +1697,46 @@ async fn test_get_connected_peers() {
 }
 
 #[tokio::test]
-async fn test_send_transaction() {
-    let mut rng = ChaChaRng::seed_from_u64(123456789);
+async fn test_get_peer_info() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
 
-    // Initialize a new account.
-    let account = Account::<CurrentNetwork>::new(&mut rng);
-    let address = account.address();
+    // Send the request to the server.
+    let response: Vec<serde_json::Value> = rpc_client.request("getpeerinfo", None).await.expect("Invalid response");
+
+    // Check the node has no peers.
+    assert!(response.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_network_time() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let response: serde_json::Value = rpc_client.request("getnetworktime", None).await.expect("Invalid response");
+
+    // Check the node has no peer time, and the median time past is the genesis timestamp.
+    assert!(response["median_peer_time"].is_null());
+    assert_eq!(response["number_of_peers"], 0);
+    assert_eq!(response["median_time_past"], CurrentNetwork::genesis_block().timestamp());
+    assert_eq!(response["timestamp_policy"]["median_time_past_blocks"], 1);
+
+    // Check no prover has submitted a timestamped share.
+    assert!(response["median_prover_clock_offset_in_secs"].is_null());
+    assert_eq!(response["number_of_provers"], 0);
+    assert_eq!(response["skewed_provers"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_send_transaction() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a new account.
+    let account = Account::<CurrentNetwork>::new(&mut rng);
+    let address = account.address();
 
     // Initialize a new transaction.
     let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(address, AleoAmount(1234), true, &mut rng)
@@ -636,6 +1755,238 @@ async fn test_send_transaction() {
     assert_eq!(response, transaction.transaction_id());
 }
 
+#[tokio::test]
+async fn test_send_transactions() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a new account.
+    let account = Account::<CurrentNetwork>::new(&mut rng);
+    let address = account.address();
+
+    // Initialize a new transaction.
+    let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(address, AleoAmount(1234), true, &mut rng)
+        .expect("Failed to create a coinbase transaction");
+    let transaction_hex = hex::encode(transaction.to_bytes_le().unwrap());
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server, with an invalid hex and a duplicate transaction.
+    let params = rpc_params![vec![transaction_hex.clone(), "abcd".to_string(), transaction_hex]];
+    let response: Vec<serde_json::Value> = rpc_client.request("sendtransactions", params).await.expect("Invalid response");
+
+    // Check the results.
+    assert_eq!(response.len(), 3);
+    assert_eq!(response[0]["accepted"], true);
+    assert_eq!(response[0]["transaction_id"], serde_json::json!(transaction.transaction_id()));
+    assert_eq!(response[1]["accepted"], false);
+    assert!(response[1]["error"].is_string());
+    assert_eq!(response[2]["accepted"], false);
+    assert!(response[2]["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_validate_transaction() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a coinbase transaction, which mints credits.
+    let address = Account::<CurrentNetwork>::new(&mut rng).address();
+    let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(address, AleoAmount(1234), true, &mut rng)
+        .expect("Failed to create a coinbase transaction");
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let params = rpc_params![hex::encode(transaction.to_bytes_le().unwrap())];
+    let response: serde_json::Value = rpc_client.request("validatetransaction", params).await.expect("Invalid response");
+
+    // Check the transaction is refused for its negative fee, and its proof is verified.
+    assert_eq!(response["transaction_id"], serde_json::json!(transaction.transaction_id()));
+    assert_eq!(response["valid"], false);
+    assert_eq!(response["fee"], -1234);
+    let check = |name: &str| {
+        let checks = response["checks"].as_array().unwrap();
+        checks.iter().find(|check| check["check"] == name).expect("Missing check").clone()
+    };
+    assert_eq!(check("proof")["passed"], true);
+    assert_eq!(check("double_spend")["passed"], true);
+    assert_eq!(check("fee")["passed"], false);
+    assert!(check("fee")["reason"].is_string());
+
+    // Check the transaction was neither added to the memory pool nor propagated.
+    assert_eq!(response["in_memory_pool"], false);
+    let response: Vec<serde_json::Value> = rpc_client.request("getmemorypool", None).await.expect("Invalid response");
+    assert!(response.is_empty());
+}
+
+#[tokio::test]
+async fn test_decode_transaction() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a coinbase transaction, which mints credits.
+    let address = Account::<CurrentNetwork>::new(&mut rng).address();
+    let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(address, AleoAmount(1234), true, &mut rng)
+        .expect("Failed to create a coinbase transaction");
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let params = rpc_params![hex::encode(transaction.to_bytes_le().unwrap())];
+    let response: serde_json::Value = rpc_client.request("decodetransaction", params).await.expect("Invalid response");
+
+    // Check the transaction is decoded as by `gettransaction`.
+    assert_eq!(response["transaction"], serde_json::json!(transaction));
+    assert_eq!(response["value_balance"], -1234);
+    let decrypted_records: Vec<Record<CurrentNetwork>> = transaction.to_records().collect();
+    assert_eq!(response["decrypted_records"], serde_json::json!(decrypted_records));
+
+    // Check the transaction was not added to the memory pool.
+    let response: Vec<serde_json::Value> = rpc_client.request("getmemorypool", None).await.expect("Invalid response");
+    assert!(response.is_empty());
+
+    // Check an invalid transaction hex is refused.
+    let response: Result<serde_json::Value, _> = rpc_client.request("decodetransaction", rpc_params!["00"]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_decrypt_record() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a private coinbase record for a new account.
+    let account = Account::<CurrentNetwork>::new(&mut rng);
+    let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(account.address(), AleoAmount(1234), false, &mut rng)
+        .expect("Failed to create a coinbase transaction");
+    let ciphertext = transaction.transitions()[0].ciphertexts().next().unwrap().clone();
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let wallet = RpcCredentials {
+        username: "wallet".to_string(),
+        password: "wallet-secret".to_string().into(),
+    };
+
+    // Check the record is decrypted with the view key of its owner.
+    let params = rpc_params![wallet.clone(), ciphertext.to_string(), account.view_key().to_string()];
+    let response: Option<Record<CurrentNetwork>> = rpc_client.request("decryptrecord", params).await.expect("Invalid response");
+    let record = response.expect("Failed to decrypt the record");
+    assert_eq!(record.owner(), account.address());
+    assert_eq!(record.value(), AleoAmount(1234));
+
+    // Check the record is not decrypted with the view key of another account.
+    let other_account = Account::<CurrentNetwork>::new(&mut rng);
+    let params = rpc_params![wallet.clone(), ciphertext.to_string(), other_account.view_key().to_string()];
+    let response: Option<Record<CurrentNetwork>> = rpc_client.request("decryptrecord", params).await.expect("Invalid response");
+    assert!(response.is_none());
+
+    // Check a commitment in the ledger is resolved to its ciphertext.
+    let commitment = *CurrentNetwork::genesis_block().to_coinbase_transaction().unwrap().commitments().next().unwrap();
+    let params = rpc_params![wallet.clone(), commitment.to_string(), account.view_key().to_string()];
+    let response: Option<Record<CurrentNetwork>> = rpc_client.request("decryptrecord", params).await.expect("Invalid response");
+    assert!(response.is_none());
+
+    // Check an invalid view key is refused.
+    let params = rpc_params![wallet, ciphertext.to_string(), "invalid"];
+    let response: Result<Option<Record<CurrentNetwork>>, _> = rpc_client.request("decryptrecord", params).await;
+    assert!(response.is_err());
+
+    // Check the read-only role may not decrypt records.
+    let reader = RpcCredentials {
+        username: "reader".to_string(),
+        password: "read-secret".to_string().into(),
+    };
+    let params = rpc_params![reader, ciphertext.to_string(), account.view_key().to_string()];
+    let response: Result<Option<Record<CurrentNetwork>>, _> = rpc_client.request("decryptrecord", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_get_unspent_records() {
+    let mut rng = ChaChaRng::seed_from_u64(thread_rng().gen());
+
+    // Initialize a new temporary directory.
+    let directory = temp_dir();
+
+    // Initialize a new ledger state at the temporary directory.
+    let ledger_state = new_ledger_state::<CurrentNetwork, RocksDB, PathBuf>(Some(directory.clone()));
+
+    // Mine the next block to a new account.
+    let account = Account::<CurrentNetwork>::new(&mut rng);
+    let (block_1, _) = ledger_state
+        .mine_next_block(account.address(), true, &[], &Default::default(), &mut rng)
+        .expect("Failed to mine");
+    ledger_state.add_next_block(&block_1).expect("Failed to add next block to ledger");
+
+    // Drop the handle to ledger_state. Note this does not remove the blocks in the temporary directory.
+    drop(ledger_state);
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(directory).await;
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(Some(rpc_server_context)).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let wallet = RpcCredentials {
+        username: "wallet".to_string(),
+        password: "wallet-secret".to_string().into(),
+    };
+    let view_key = account.view_key().to_string();
+    let compute_key = hex::encode(account.private_key().to_compute_key().to_bytes_le().unwrap());
+
+    // Check the coinbase record of the account is unspent.
+    let params = rpc_params![wallet.clone(), view_key.clone(), compute_key.clone(), 0, 1];
+    let response: Vec<serde_json::Value> = rpc_client.request("getunspentrecords", params).await.expect("Invalid response");
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0]["block_height"], 1);
+    assert_eq!(response[0]["record"]["value"], Block::<CurrentNetwork>::block_reward(1).0);
+
+    // Check the compute key of another account is refused.
+    let other_account = Account::<CurrentNetwork>::new(&mut rng);
+    let other_compute_key = hex::encode(other_account.private_key().to_compute_key().to_bytes_le().unwrap());
+    let params = rpc_params![wallet.clone(), view_key.clone(), other_compute_key, 0, 1];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getunspentrecords", params).await;
+    assert!(response.is_err());
+
+    // Check a range beyond the latest block is refused.
+    let params = rpc_params![wallet, view_key.clone(), compute_key.clone(), 0, 2];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getunspentrecords", params).await;
+    assert!(response.is_err());
+
+    // Check the read-only role may not scan for records.
+    let reader = RpcCredentials {
+        username: "reader".to_string(),
+        password: "read-secret".to_string().into(),
+    };
+    let params = rpc_params![reader, view_key, compute_key, 0, 1];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getunspentrecords", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_submit_block() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check a block the ledger already contains is reported as a duplicate.
+    let genesis_block = CurrentNetwork::genesis_block();
+    let params = rpc_params![hex::encode(genesis_block.to_bytes_le().unwrap())];
+    let response: serde_json::Value = rpc_client.request("submitblock", params).await.expect("Invalid response");
+    assert_eq!(response["status"], "duplicate");
+    assert_eq!(response["block_height"], 0);
+    assert_eq!(response["block_hash"], serde_json::json!(genesis_block.hash()));
+
+    // Check an invalid block hex is refused.
+    let response: Result<serde_json::Value, _> = rpc_client.request("submitblock", rpc_params!["abcd"]).await;
+    assert!(response.is_err());
+}
+
 #[tokio::test]
 async fn test_send_transaction_large() {
     // Initialize a new RPC server and create an associated client.
@@ -682,3 +2033,812 @@ async fn test_get_memory_pool() {
     // Check the transactions.
     assert_eq!(response, vec![transaction]);
 }
+
+#[tokio::test]
+async fn test_get_mempool_info() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the summary of the empty memory pool.
+    let response: serde_json::Value = rpc_client.request("getmempoolinfo", None).await.expect("Invalid response");
+    assert_eq!(response["number_of_transactions"], 0);
+    assert!(response["median_value_balance"].is_null());
+
+    // Initialize a new transaction.
+    let address = Account::<CurrentNetwork>::new(&mut rng).address();
+    let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(address, AleoAmount(0), true, &mut rng)
+        .expect("Failed to create a coinbase transaction");
+
+    // Send the transaction to the server.
+    let params = rpc_params![hex::encode(transaction.to_bytes_le().unwrap())];
+    let _: <CurrentNetwork as Network>::TransactionID = rpc_client.request("sendtransaction", params).await.expect("Invalid response");
+
+    // Check the summary of the memory pool, in which the coinbase transaction pays no fee.
+    let response: serde_json::Value = rpc_client.request("getmempoolinfo", None).await.expect("Invalid response");
+    assert_eq!(response["number_of_transactions"], 1);
+    assert_eq!(response["size_in_bytes"], transaction.to_bytes_le().unwrap().len());
+    assert_eq!(response["total_fees"], 0);
+    assert_eq!(response["median_value_balance"], transaction.value_balance().0);
+}
+
+#[tokio::test]
+async fn test_subscribe_pending_transactions() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a new RPC context, served over both HTTP and WebSocket.
+    let rpc_context = new_rpc_context::<CurrentNetwork, Client<CurrentNetwork>, RocksDB, PathBuf>(temp_dir()).await;
+    let (rpc_ws_server_addr, rpc_ws_server_handle) =
+        initialize_rpc_ws_server("127.0.0.1:0".parse().unwrap(), rpc_context.clone(), None, None).await;
+    <Client<CurrentNetwork>>::resources().register_task(None, rpc_ws_server_handle);
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(Some(rpc_context)).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Subscribe to the IDs of the pending transactions.
+    let rpc_ws_client = WsClientBuilder::default()
+        .build(format!("ws://{}", rpc_ws_server_addr))
+        .await
+        .expect("Couldn't build a JSON-RPC WebSocket client");
+    let mut subscription = rpc_ws_client
+        .subscribe::<<CurrentNetwork as Network>::TransactionID>("subscribe", rpc_params!["pendingTransactions"], "unsubscribe")
+        .await
+        .expect("Invalid subscription");
+
+    // Initialize a new transaction.
+    let account = Account::<CurrentNetwork>::new(&mut rng);
+    let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(account.address(), AleoAmount(0), true, &mut rng)
+        .expect("Failed to create a coinbase transaction");
+
+    // Send the transaction to the server.
+    let params = rpc_params![hex::encode(transaction.to_bytes_le().unwrap())];
+    let _: <CurrentNetwork as Network>::TransactionID = rpc_client.request("sendtransaction", params).await.expect("Invalid response");
+
+    // Check the transaction ID is pushed to the subscriber once the transaction is accepted into the memory pool.
+    let transaction_id = subscription
+        .next()
+        .await
+        .expect("The subscription was closed")
+        .expect("Invalid notification");
+    assert_eq!(transaction_id, transaction.transaction_id());
+}
+
+#[tokio::test]
+async fn test_search() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let genesis_block = CurrentNetwork::genesis_block();
+    let transaction = genesis_block.to_coinbase_transaction().unwrap();
+    let commitment = transaction.commitments().next().unwrap();
+
+    // Search for the genesis block, by height and by hash.
+    for query in [0.to_string(), genesis_block.hash().to_string()] {
+        let response: serde_json::Value = rpc_client.request("search", rpc_params![query]).await.expect("Invalid response");
+        assert_eq!(response["type"], "block");
+        assert_eq!(response["result"]["block_hash"], genesis_block.hash().to_string());
+    }
+
+    // Search for the coinbase transaction, its transition, and one of its commitments.
+    let params = rpc_params![transaction.transaction_id().to_string()];
+    let response: serde_json::Value = rpc_client.request("search", params).await.expect("Invalid response");
+    assert_eq!(response["type"], "transaction");
+    assert_eq!(response["result"]["transaction"]["transaction_id"], transaction.transaction_id().to_string());
+
+    let params = rpc_params![transaction.transitions()[0].transition_id().to_string()];
+    let response: serde_json::Value = rpc_client.request("search", params).await.expect("Invalid response");
+    assert_eq!(response["type"], "transition");
+
+    let params = rpc_params![format!(" {} ", commitment)];
+    let response: serde_json::Value = rpc_client.request("search", params).await.expect("Invalid response");
+    assert_eq!(response["type"], "commitment");
+    assert_eq!(response["result"]["commitment"], commitment.to_string());
+
+    // Search for an address.
+    let address = Account::<CurrentNetwork>::new(&mut rng).address();
+    let response: serde_json::Value = rpc_client.request("search", rpc_params![address.to_string()]).await.expect("Invalid response");
+    assert_eq!(response["type"], "address");
+    assert_eq!(response["result"]["address"], address.to_string());
+
+    // Search for a block height that does not exist, and for an unrecognized query.
+    for query in ["1000", "hello"] {
+        let response: serde_json::Value = rpc_client.request("search", rpc_params![query]).await.expect("Invalid response");
+        assert!(response.is_null());
+    }
+}
+
+#[tokio::test]
+async fn test_validate_address() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a new account.
+    let account = Account::<CurrentNetwork>::new(&mut rng);
+    let address = account.address();
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server, using the uppercase form of the address.
+    let params = rpc_params![address.to_string().to_uppercase()];
+    let response: serde_json::Value = rpc_client.request("validateaddress", params).await.expect("Invalid response");
+
+    // Check the address is valid and canonicalized.
+    assert_eq!(response["valid"], true);
+    assert_eq!(response["address"], address.to_string());
+    assert_eq!(response["network_id"], CurrentNetwork::NETWORK_ID);
+
+    // Send a request with an address of the wrong prefix.
+    let params = rpc_params![address.to_string().replacen("aleo1", "btc1", 1)];
+    let response: serde_json::Value = rpc_client.request("validateaddress", params).await.expect("Invalid response");
+
+    // Check the address is invalid.
+    assert_eq!(response["valid"], false);
+}
+
+#[tokio::test]
+async fn test_profile_cpu_requires_credentials() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server, using the wrong password.
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "wrong".to_string().into(),
+    };
+    let params = rpc_params![credentials, 1];
+    let response: Result<serde_json::Value, _> = rpc_client.request("profilecpu", params).await;
+
+    // Check the request was rejected.
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_maintenance_requires_operator() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the request fails on a client node, with or without a backup pool.
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+    let response: Result<bool, _> = rpc_client.request("maintenance", rpc_params![credentials.clone()]).await;
+    assert!(response.unwrap_err().to_string().contains("Only an operating node"));
+    let response: Result<bool, _> = rpc_client.request("maintenance", rpc_params![credentials, "203.0.113.20:4132"]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_set_log_level() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the request is rejected with the wrong password.
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "wrong".to_string().into(),
+    };
+    let response: Result<serde_json::Value, _> = rpc_client.request("setloglevel", rpc_params![credentials, "debug"]).await;
+    assert!(response.is_err());
+
+    // Check the request fails with valid credentials, as the logger of the test is not reloadable.
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+    let response: Result<serde_json::Value, _> = rpc_client.request("setloglevel", rpc_params![credentials, "snarkos_network=trace"]).await;
+    assert!(response.unwrap_err().to_string().contains("cannot be reloaded"));
+}
+
+#[tokio::test]
+async fn test_get_sub_pools() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let response: serde_json::Value = rpc_client.request("getsubpools", None).await.expect("Invalid response");
+
+    // Check the default sub-pool is served, and has no shares.
+    assert_eq!(response.as_array().map(|sub_pools| sub_pools.len()), Some(1));
+    assert_eq!(response[0]["name"], snarkos_network::helpers::DEFAULT_SUB_POOL);
+    assert_eq!(response[0]["public"], true);
+    assert_eq!(response[0]["shares"], 0);
+}
+
+#[tokio::test]
+async fn test_get_audit_events() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let response: serde_json::Value = rpc_client.request("getauditevents", None).await.expect("Invalid response");
+
+    // Check a new operator has no incidents.
+    assert_eq!(response, serde_json::json!([]));
+
+    // Check the events can be polled after a given ID.
+    let response: serde_json::Value = rpc_client.request("getauditevents", rpc_params![0]).await.expect("Invalid response");
+    assert_eq!(response, serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_create_and_cancel_lease() {
+    let renter = Account::<CurrentNetwork>::new(&mut ChaChaRng::seed_from_u64(123456789)).address();
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+
+    // Lease a quarter of the pool to the renter.
+    let params = rpc_params![credentials.clone(), renter, 2_500, 60];
+    let lease: serde_json::Value = rpc_client.request("createlease", params).await.expect("Invalid response");
+    assert_eq!(lease["renter"], renter.to_string());
+
+    // Check the lease is active.
+    let leases: serde_json::Value = rpc_client.request("getleases", None).await.expect("Invalid response");
+    assert_eq!(leases[0]["id"], lease["id"]);
+    assert_eq!(leases[0]["active"], true);
+
+    // Cancel the lease, and check it is no longer active.
+    let params = rpc_params![credentials, lease["id"].clone()];
+    let cancelled: bool = rpc_client.request("cancellease", params).await.expect("Invalid response");
+    assert!(cancelled);
+    let leases: serde_json::Value = rpc_client.request("getleases", None).await.expect("Invalid response");
+    assert_eq!(leases[0]["active"], false);
+}
+
+#[tokio::test]
+async fn test_get_pool_stats() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let response: serde_json::Value = rpc_client.request("getpoolstats", None).await.expect("Invalid response");
+
+    // Check a pool without shares has no statistics.
+    assert_eq!(response["current_round_shares"], 0);
+    assert_eq!(response["luck_30d"], serde_json::Value::Null);
+    assert_eq!(response["rounds"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_get_leaderboard() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the epoch containing the timestamp is returned, without provers as the pool has no shares.
+    let params = rpc_params!["daily", 100_000];
+    let response: serde_json::Value = rpc_client.request("getleaderboard", params).await.expect("Invalid response");
+    assert_eq!(response["period"], "daily");
+    assert_eq!(response["started_at"], 86_400);
+    assert_eq!(response["ended_at"], 172_800);
+    assert_eq!(response["rolled_up_to"], serde_json::Value::Null);
+    assert_eq!(response["number_of_provers"], 0);
+    assert_eq!(response["provers"], serde_json::json!([]));
+
+    // Check the weekly epochs start on Mondays.
+    let params = rpc_params!["weekly", 0];
+    let response: serde_json::Value = rpc_client.request("getleaderboard", params).await.expect("Invalid response");
+    assert_eq!(response["started_at"], -259_200);
+
+    // Check the current epoch is returned without a timestamp, and an unknown period is rejected.
+    let response: serde_json::Value = rpc_client.request("getleaderboard", rpc_params!["weekly"]).await.expect("Invalid response");
+    assert_eq!(response["period"], "weekly");
+    let response: Result<serde_json::Value, _> = rpc_client.request("getleaderboard", rpc_params!["monthly"]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_estimate_earnings() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Send the request to the server.
+    let params = rpc_params![10.0, 86400];
+    let response: serde_json::Value = rpc_client.request("estimateearnings", params).await.expect("Invalid response");
+
+    // Check the default sub-pool charges no fee, and the pool without history has neutral luck.
+    assert_eq!(response["luck"], 1.0);
+    assert_eq!(response["fee"], 0);
+    assert_eq!(response["estimated_reward"], response["gross_reward"]);
+    assert_eq!(response["block_reward"], Block::<CurrentNetwork>::block_reward(1).0);
+
+    // Check an invalid window is rejected.
+    let response: Result<serde_json::Value, _> = rpc_client.request("estimateearnings", rpc_params![10.0, 0]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_get_pool_job() {
+    let account = Account::<CurrentNetwork>::new(&mut ChaChaRng::seed_from_u64(123456789));
+    let prover = account.address();
+    let sign = |account: &Account<CurrentNetwork>, timestamp: i64| {
+        let message_bits = pool_job_message(&prover, timestamp).as_bytes().to_bits_le();
+        account.private_key().sign(&message_bits, &mut thread_rng()).unwrap()
+    };
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check an unsigned request is rejected, as is a request signed by another account, or signed long ago.
+    let params = rpc_params![prover, Option::<u32>::None];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getpooljob", params).await;
+    assert!(response.is_err());
+    let other_account = Account::<CurrentNetwork>::new(&mut thread_rng());
+    let params = rpc_params![prover, Option::<u32>::None, now, sign(&other_account, now)];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getpooljob", params).await;
+    assert!(response.is_err());
+    let params = rpc_params![prover, Option::<u32>::None, now - 3600, sign(&account, now - 3600)];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getpooljob", params).await;
+    assert!(response.is_err());
+
+    // Check an operator without a block template has no job.
+    let params = rpc_params![prover, Option::<u32>::None, now, sign(&account, now)];
+    let response: serde_json::Value = rpc_client.request("getpooljob", params).await.expect("Invalid response");
+    assert_eq!(response, serde_json::Value::Null);
+
+    // Check a malformed share is rejected, as is a share without a timestamp.
+    let params = rpc_params![prover, "00", "00", 1_650_000_000];
+    let response: Result<bool, _> = rpc_client.request("submitshare", params).await;
+    assert!(response.is_err());
+    let params = rpc_params![prover, "00", "00"];
+    let response: Result<bool, _> = rpc_client.request("submitshare", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_get_network_difficulty_estimate() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let response: DifficultyEstimate = rpc_client.request("getnetworkdifficultyestimate", None).await.expect("Invalid response");
+
+    // Check the estimate of a ledger at genesis is the difficulty of the genesis block.
+    let genesis = CurrentNetwork::genesis_block();
+    assert_eq!(response.block_height, genesis.height());
+    assert!((response.difficulty - u64::MAX as f64 / genesis.difficulty_target() as f64).abs() < 1e-6);
+    assert!(response.proof_rate > 0.0);
+}
+
+#[tokio::test]
+async fn test_get_network_stats() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the statistics of a ledger at genesis are based on the genesis block, with or without a window.
+    let genesis = CurrentNetwork::genesis_block();
+    for params in [None, rpc_params![10]] {
+        let response: NetworkStats = rpc_client.request("getnetworkstats", params).await.expect("Invalid response");
+        assert_eq!(response.block_height, genesis.height());
+        assert_eq!(response.difficulty_target, genesis.difficulty_target());
+        assert_eq!(response.average_block_time_in_secs, CurrentNetwork::ALEO_BLOCK_TIME_IN_SECS as f64);
+        assert!(response.proof_rate > 0.0);
+    }
+
+    // Check an empty window is rejected.
+    let response: Result<NetworkStats, _> = rpc_client.request("getnetworkstats", rpc_params![0]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_estimate_fee() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the estimate of a ledger at genesis has no blocks to estimate from, and falls back to the minimum fee.
+    let response: FeeEstimate = rpc_client.request("estimatefee", rpc_params![6]).await.expect("Invalid response");
+    assert_eq!(response.block_height, CurrentNetwork::genesis_block().height());
+    assert_eq!(response.number_of_blocks, 0);
+    assert_eq!(response.confirmation_target, 6);
+    assert_eq!(response.fee_per_byte, response.minimum_fee_per_byte);
+    assert_eq!(response.typical_fee, None);
+
+    // Check the confirmation target must be between 1 and the maximum.
+    for confirmation_target in [0, MAXIMUM_CONFIRMATION_TARGET + 1] {
+        let response: Result<FeeEstimate, _> = rpc_client.request("estimatefee", rpc_params![confirmation_target]).await;
+        assert!(response.is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_get_coinbase_reward_schedule() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check the reward of a ledger at genesis defaults to the genesis block.
+    let response: serde_json::Value = rpc_client.request("getcoinbaserewardschedule", None).await.expect("Invalid response");
+    assert_eq!(response["block_height"], 0);
+    assert_eq!(response["block_reward"], Block::<CurrentNetwork>::block_reward(0).0);
+    assert_eq!(response["total_issued"], Block::<CurrentNetwork>::block_reward(0).0);
+
+    // Check the reward and the total issued at a given height match the block rewards of the network.
+    let block_height = 1000u32;
+    let params = rpc_params![block_height];
+    let response: serde_json::Value = rpc_client.request("getcoinbaserewardschedule", params).await.expect("Invalid response");
+    assert_eq!(response["block_reward"], Block::<CurrentNetwork>::block_reward(block_height).0);
+    let total_issued: i64 = (0..=block_height).map(|height| Block::<CurrentNetwork>::block_reward(height).0).sum();
+    assert_eq!(response["total_issued"], total_issued);
+
+    // Check each epoch of the schedule follows the previous one, and the reward at each halving point.
+    let epochs = response["epochs"].as_array().expect("Invalid epochs");
+    assert_eq!(epochs[0]["start_block_height"], 0);
+    assert!(epochs.last().unwrap()["end_block_height"].is_null());
+    for (previous, epoch) in epochs.iter().zip(epochs.iter().skip(1)) {
+        let start_block_height = epoch["start_block_height"].as_u64().unwrap() as u32;
+        assert_eq!(previous["end_block_height"].as_u64().unwrap() as u32 + 1, start_block_height);
+        assert_eq!(epoch["block_reward"], Block::<CurrentNetwork>::block_reward(start_block_height).0);
+        assert_ne!(epoch["block_reward"], previous["block_reward"]);
+    }
+}
+
+#[tokio::test]
+async fn test_get_blocks_by_tag() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check an operator without found blocks has none for any tag.
+    let response: serde_json::Value = rpc_client.request("getblocksbytag", rpc_params!["hashpool"]).await.expect("Invalid response");
+    assert_eq!(response, serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_get_found_block() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    // Check an operator without found blocks has no provenance for any height.
+    let response: serde_json::Value = rpc_client.request("getfoundblock", rpc_params![1]).await.expect("Invalid response");
+    assert_eq!(response, serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_get_my_shares() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+    let prover = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+
+    // Check a prover without shares has an empty share history.
+    let response: Vec<serde_json::Value> = rpc_client
+        .request("getmyshares", rpc_params![prover, 0, i64::MAX])
+        .await
+        .expect("Invalid response");
+    assert!(response.is_empty());
+
+    // Check the range must not end before it starts.
+    let response: Result<Vec<serde_json::Value>, _> = rpc_client.request("getmyshares", rpc_params![prover, 100, 0]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_update_payout_address() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let rng = &mut thread_rng();
+    let prover = Account::<CurrentNetwork>::new(rng);
+    let payout_address = Account::<CurrentNetwork>::new(rng).address();
+    let sign = |account: &Account<CurrentNetwork>, effective_from: u32| {
+        let message = payout_address_message(&prover.address(), &payout_address, effective_from);
+        let message_bits = message.as_bytes().to_bits_le();
+        account.private_key().sign(&message_bits, &mut thread_rng()).unwrap()
+    };
+
+    // Check an update signed by another account is rejected.
+    let other_account = Account::<CurrentNetwork>::new(rng);
+    let params = rpc_params![prover.address(), payout_address, 1, sign(&other_account, 1)];
+    let response: Result<serde_json::Value, _> = rpc_client.request("updatepayoutaddress", params).await;
+    assert!(response.is_err());
+
+    // Check an update effective from a round which was already paid is rejected.
+    let params = rpc_params![prover.address(), payout_address, 0, sign(&prover, 0)];
+    let response: Result<serde_json::Value, _> = rpc_client.request("updatepayoutaddress", params).await;
+    assert!(response.is_err());
+
+    // Check an update signed by the prover is recorded from the current round.
+    let signature = sign(&prover, 1);
+    let params = rpc_params![prover.address(), payout_address, 1, signature.clone()];
+    let response: serde_json::Value = rpc_client.request("updatepayoutaddress", params).await.expect("Invalid response");
+    assert_eq!(response["payout_address"], serde_json::json!(payout_address.to_string()));
+    assert_eq!(response["effective_from"], serde_json::json!(1));
+
+    // Check the same update cannot be replayed.
+    let params = rpc_params![prover.address(), payout_address, 1, signature];
+    let response: Result<serde_json::Value, _> = rpc_client.request("updatepayoutaddress", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_update_payout_schedule() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let rng = &mut thread_rng();
+    let prover = Account::<CurrentNetwork>::new(rng);
+    let sign = |minimum_payout: u64, payout_interval: u32, effective_from: u32| {
+        let schedule = PayoutSchedule {
+            minimum_payout,
+            payout_interval,
+        };
+        let message = payout_schedule_message(&prover.address(), &schedule, effective_from);
+        let message_bits = message.as_bytes().to_bits_le();
+        prover.private_key().sign(&message_bits, &mut thread_rng()).unwrap()
+    };
+
+    // Check a prover without a payout schedule of its own is paid on the default payout schedule.
+    let response: serde_json::Value = rpc_client
+        .request("getpayoutschedule", rpc_params![prover.address()])
+        .await
+        .expect("Invalid response");
+    assert_eq!(response["minimum_payout"], serde_json::json!(DEFAULT_MINIMUM_PAYOUT));
+    assert_eq!(response["payout_interval"], serde_json::json!(DEFAULT_PAYOUT_INTERVAL));
+    assert_eq!(response["is_default"], serde_json::json!(true));
+
+    // Check a payout schedule beyond the bounds of the operator is rejected.
+    let params = rpc_params![prover.address(), 5_000_000u64, 0u32, 1u32, sign(5_000_000, 0, 1)];
+    let response: Result<serde_json::Value, _> = rpc_client.request("updatepayoutschedule", params).await;
+    assert!(response.is_err());
+
+    // Check an update whose signature does not cover the payout schedule is rejected.
+    let params = rpc_params![prover.address(), 5_000_000u64, 720u32, 1u32, sign(1, 720, 1)];
+    let response: Result<serde_json::Value, _> = rpc_client.request("updatepayoutschedule", params).await;
+    assert!(response.is_err());
+
+    // Check an update signed by the prover is recorded from the next block.
+    let signature = sign(5_000_000, 720, 1);
+    let params = rpc_params![prover.address(), 5_000_000u64, 720u32, 1u32, signature.clone()];
+    let response: serde_json::Value = rpc_client.request("updatepayoutschedule", params).await.expect("Invalid response");
+    assert_eq!(response["schedule"]["minimum_payout"], serde_json::json!(5_000_000));
+    assert_eq!(response["effective_from"], serde_json::json!(1));
+
+    let response: serde_json::Value = rpc_client
+        .request("getpayoutschedule", rpc_params![prover.address()])
+        .await
+        .expect("Invalid response");
+    assert_eq!(response["minimum_payout"], serde_json::json!(5_000_000));
+    assert_eq!(response["payout_interval"], serde_json::json!(720));
+    assert_eq!(response["is_default"], serde_json::json!(false));
+
+    // Check the same update cannot be replayed.
+    let params = rpc_params![prover.address(), 5_000_000u64, 720u32, 1u32, signature];
+    let response: Result<serde_json::Value, _> = rpc_client.request("updatepayoutschedule", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_replay_payouts() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+
+    // Check a round without found blocks has no payouts to replay, under the current or corrected sub-pools.
+    let params = rpc_params![credentials.clone(), 1];
+    let response: serde_json::Value = rpc_client.request("replaypayouts", params).await.expect("Invalid response");
+    assert_eq!(response, serde_json::json!([]));
+
+    let sub_pools = serde_json::json!([{ "name": "default", "fee_basis_points": 100 }]);
+    let params = rpc_params![credentials.clone(), 1, sub_pools];
+    let response: serde_json::Value = rpc_client.request("replaypayouts", params).await.expect("Invalid response");
+    assert_eq!(response, serde_json::json!([]));
+
+    // Check invalid sub-pools are rejected.
+    let params = rpc_params![credentials, 1, serde_json::json!([])];
+    let response: Result<serde_json::Value, _> = rpc_client.request("replaypayouts", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_pending_payouts() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+
+    // Check an operator without found blocks has no payouts to pay.
+    let response: serde_json::Value = rpc_client
+        .request("getpendingpayouts", rpc_params![credentials.clone()])
+        .await
+        .expect("Invalid response");
+    assert_eq!(response, serde_json::json!([]));
+
+    // Check a batch which does not exist cannot be recorded as paid.
+    let genesis_block = CurrentNetwork::genesis_block();
+    let transaction_id = genesis_block.to_coinbase_transaction().unwrap().transaction_id();
+    let params = rpc_params![credentials, 0, genesis_block.hash(), 0, transaction_id];
+    let response: Result<bool, _> = rpc_client.request("completepayout", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_select_payout_records() {
+    let mut rng = ChaChaRng::seed_from_u64(123456789);
+
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+
+    // Initialize the records of the pool wallet, created at two block heights.
+    let account = Account::<CurrentNetwork>::new(&mut rng);
+    let mut records = Vec::new();
+    for (block_height, value) in [(1u32, 1000), (2u32, 3000)] {
+        let (transaction, _) = Transaction::<CurrentNetwork>::new_coinbase(account.address(), AleoAmount(value), true, &mut rng)
+            .expect("Failed to create a coinbase transaction");
+        let record = transaction.to_records().find(|record| !record.is_dummy()).unwrap();
+        records.push((block_height, record));
+    }
+
+    // Check the largest record is selected first, under the default record selection.
+    let params = rpc_params![credentials.clone(), 2500u64, records.clone()];
+    let response: Vec<Record<CurrentNetwork>> = rpc_client.request("selectpayoutrecords", params).await.expect("Invalid response");
+    assert_eq!(response, vec![records[1].1.clone()]);
+
+    // Check a payout beyond the value of the records is rejected.
+    let params = rpc_params![credentials, 5000u64, records];
+    let response: Result<Vec<Record<CurrentNetwork>>, _> = rpc_client.request("selectpayoutrecords", params).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_rpc_roles() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let api_key = |username: &str, password: &str| RpcCredentials {
+        username: username.to_string(),
+        password: password.to_string().into(),
+    };
+    let admin = api_key("root", "pass");
+    let reader = api_key("reader", "read-secret");
+    let wallet = api_key("wallet", "wallet-secret");
+
+    // Check the read-only key may not call the wallet or admin endpoints, which include the replay of the payouts.
+    let response: Result<serde_json::Value, _> = rpc_client.request("replaypayouts", rpc_params![reader.clone(), 1]).await;
+    assert!(response.is_err());
+    let transaction_id = CurrentNetwork::genesis_block().to_coinbase_transaction().unwrap().transaction_id();
+    let params = rpc_params![reader.clone(), vec![transaction_id]];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getblocktemplate", params).await;
+    assert!(response.is_err());
+    let params = rpc_params![reader.clone(), vec!["127.0.0.1:4132".to_string()]];
+    let response: Result<bool, _> = rpc_client.request("connect", params).await;
+    assert!(response.is_err());
+    let response: Result<bool, _> = rpc_client.request("unbanpeer", rpc_params![reader, "203.0.113.7"]).await;
+    assert!(response.is_err());
+
+    // Check the wallet key may give transaction hints, but not call the admin endpoints.
+    let params = rpc_params![wallet.clone(), vec![transaction_id]];
+    let response: Result<serde_json::Value, _> = rpc_client.request("getblocktemplate", params).await;
+    assert!(response.is_ok());
+    let response: Result<bool, _> = rpc_client.request("unbanpeer", rpc_params![wallet, "203.0.113.7"]).await;
+    assert!(response.is_err());
+
+    // Check the credentials of the node hold the admin role, and an unknown key holds no role.
+    let response: bool = rpc_client
+        .request("unbanpeer", rpc_params![admin, "203.0.113.7"])
+        .await
+        .expect("Invalid response");
+    assert!(!response);
+    let response: Result<bool, _> = rpc_client
+        .request("unbanpeer", rpc_params![api_key("reader", "pass"), "203.0.113.7"])
+        .await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_ban_and_unban_peer() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+
+    // Ban an IP indefinitely, and a peer for a minute, given by its socket address.
+    let banned: bool = rpc_client
+        .request("banpeer", rpc_params![credentials.clone(), "203.0.113.7"])
+        .await
+        .expect("Invalid response");
+    assert!(banned);
+    let banned: bool = rpc_client
+        .request("banpeer", rpc_params![credentials.clone(), "203.0.113.8:4132", 60])
+        .await
+        .expect("Invalid response");
+    assert!(banned);
+
+    // Check the bans are lifted once, and an IP that is not banned is reported as such.
+    for (ip, was_banned) in [("203.0.113.7", true), ("203.0.113.8", true), ("203.0.113.7", false)] {
+        let response: bool = rpc_client
+            .request("unbanpeer", rpc_params![credentials.clone(), ip])
+            .await
+            .expect("Invalid response");
+        assert_eq!(response, was_banned);
+    }
+
+    // Check an invalid IP and invalid credentials are rejected.
+    let response: Result<bool, _> = rpc_client.request("banpeer", rpc_params![credentials, "not an ip"]).await;
+    assert!(response.is_err());
+    let invalid_credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "wrong".to_string().into(),
+    };
+    let response: Result<bool, _> = rpc_client.request("banpeer", rpc_params![invalid_credentials, "203.0.113.7"]).await;
+    assert!(response.is_err());
+}
+
+#[tokio::test]
+async fn test_disconnect() {
+    // Initialize a new RPC server and create an associated client.
+    let rpc_server_addr = new_rpc_server::<CurrentNetwork, Client<CurrentNetwork>, RocksDB>(None).await;
+    let rpc_client = new_rpc_client(rpc_server_addr);
+
+    let credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "pass".to_string().into(),
+    };
+
+    // Check a peer the node is not connected to is reported as such, with or without the restriction.
+    let response: bool = rpc_client
+        .request("disconnect", rpc_params![credentials.clone(), "203.0.113.7:4132"])
+        .await
+        .expect("Invalid response");
+    assert!(!response);
+    let response: bool = rpc_client
+        .request("disconnect", rpc_params![credentials, "203.0.113.7:4132", true])
+        .await
+        .expect("Invalid response");
+    assert!(!response);
+
+    // Check invalid credentials are rejected.
+    let invalid_credentials = RpcCredentials {
+        username: "root".to_string(),
+        password: "wrong".to_string().into(),
+    };
+    let response: Result<bool, _> = rpc_client
+        .request("disconnect", rpc_params![invalid_credentials, "203.0.113.7:4132"])
+        .await;
+    assert!(response.is_err());
+}
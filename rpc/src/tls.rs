@@ -0,0 +1,90 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! TLS termination for the RPC servers.
+
+use snarkos_network::helpers::{load_certificates, load_private_key};
+
+use anyhow::Result;
+use std::{fmt, net::SocketAddr, path::Path, sync::Arc, time::Duration};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_rustls::{rustls::ServerConfig, server::TlsStream, TlsAcceptor};
+
+/// The maximum duration of the TLS handshake of an RPC client.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+///
+/// The TLS configuration of the RPC servers, which terminates TLS in front of the plaintext RPC server.
+///
+#[derive(Clone)]
+pub struct RpcTlsAcceptor {
+    config: Arc<ServerConfig>,
+    acceptor: TlsAcceptor,
+}
+
+impl RpcTlsAcceptor {
+    ///
+    /// Initializes the TLS configuration from the PEM-encoded certificate chain and private key at the given paths.
+    ///
+    pub fn new<P: AsRef<Path>>(certificate_path: P, private_key_path: P) -> Result<Self> {
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(load_certificates(certificate_path)?, load_private_key(private_key_path)?)?;
+
+        let config = Arc::new(config);
+        Ok(Self {
+            acceptor: TlsAcceptor::from(config.clone()),
+            config,
+        })
+    }
+
+    ///
+    /// Returns the TLS configuration which negotiates HTTP/2 with the RPC clients via ALPN, falling back to HTTP/1.1.
+    ///
+    pub(crate) fn with_http2(&self) -> Self {
+        let mut config = (*self.config).clone();
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        let config = Arc::new(config);
+        Self {
+            acceptor: TlsAcceptor::from(config.clone()),
+            config,
+        }
+    }
+
+    ///
+    /// Performs the TLS handshake of the given RPC client, returning its stream if the handshake succeeds in time.
+    ///
+    pub(crate) async fn accept(&self, stream: TcpStream, peer_addr: SocketAddr) -> Option<TlsStream<TcpStream>> {
+        match timeout(TLS_HANDSHAKE_TIMEOUT, self.acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => Some(stream),
+            Ok(Err(error)) => {
+                debug!("The TLS handshake with RPC client {} failed: {}", peer_addr, error);
+                None
+            }
+            Err(_) => {
+                debug!("The TLS handshake with RPC client {} timed out", peer_addr);
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RpcTlsAcceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcTlsAcceptor").finish()
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Per-client usage statistics of the RPC servers, so heavy clients may be identified and contacted.
+
+use crate::{rate_limit::client_key, RpcAuth, RpcCredentials};
+
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    net::IpAddr,
+    sync::Mutex,
+    time::Instant,
+};
+
+/// The number of clients above which the clients without calls in the latest hour are no longer tracked.
+const MAXIMUM_TRACKED_CLIENTS: usize = 65_536;
+/// The number of methods tracked for a client in a minute, above which the calls to other methods are counted under `other`.
+const MAXIMUM_TRACKED_METHODS: usize = 64;
+/// The number of minutes of usage retained for each client.
+const RETAINED_MINUTES: u64 = 60;
+
+///
+/// A client of the RPC servers, which is the API key of its credentials, if it presents valid credentials, or its IP.
+/// An IPv6 client is identified by its /64 prefix, as for rate limiting.
+///
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcClient {
+    /// A client presenting the credentials of the API key with the given name.
+    ApiKey(String),
+    /// A client without valid credentials, with the given IP.
+    Ip(IpAddr),
+}
+
+///
+/// The usage of the RPC servers by a client over a window of time.
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct RpcUsage {
+    /// The number of calls, where each call of a batch request counts separately.
+    pub calls: u64,
+    /// The number of calls which failed, or were rejected by the listener.
+    pub errors: u64,
+    /// The ratio of the failed calls to the calls.
+    pub error_rate: f64,
+    /// The number of bytes of the request bodies.
+    pub bytes_received: u64,
+    /// The number of bytes of the response bodies, before compression.
+    pub bytes_sent: u64,
+    /// The number of calls to each method.
+    pub methods: BTreeMap<String, u64>,
+}
+
+impl RpcUsage {
+    /// Adds the given usage to this usage.
+    fn add(&mut self, other: &RpcUsage) {
+        self.calls += other.calls;
+        self.errors += other.errors;
+        self.bytes_received += other.bytes_received;
+        self.bytes_sent += other.bytes_sent;
+        for (method, calls) in &other.methods {
+            *self.methods.entry(method.clone()).or_default() += calls;
+        }
+        self.error_rate = match self.calls {
+            0 => 0.0,
+            calls => self.errors as f64 / calls as f64,
+        };
+    }
+
+    /// Counts the given calls to the given method, under `other` if too many methods are tracked already.
+    fn add_calls(&mut self, method: &str, calls: u64) {
+        let method = match self.methods.contains_key(method) || self.methods.len() < MAXIMUM_TRACKED_METHODS {
+            true => method,
+            false => "other",
+        };
+        *self.methods.entry(method.to_string()).or_default() += calls;
+    }
+}
+
+///
+/// The usage of the RPC servers by a client over the latest minute, 15 minutes, and hour.
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RpcClientUsage {
+    /// The client.
+    pub client: RpcClient,
+    /// The usage in the latest minute.
+    pub last_minute: RpcUsage,
+    /// The usage in the latest 15 minutes.
+    pub last_15_minutes: RpcUsage,
+    /// The usage in the latest hour.
+    pub last_hour: RpcUsage,
+}
+
+///
+/// The usage statistics of the RPC servers, recorded for each client by the minute over the latest hour.
+///
+#[derive(Debug)]
+pub struct RpcUsageTracker {
+    /// The credentials of the RPC server, to attribute the calls presenting them to their API key.
+    auth: RpcAuth,
+    /// The time at which the tracker was initialized, from which the minutes of the usage are counted.
+    start: Instant,
+    /// The usage of each client, by minute.
+    clients: Mutex<HashMap<RpcClient, VecDeque<(u64, RpcUsage)>>>,
+}
+
+impl RpcUsageTracker {
+    ///
+    /// Initializes a new tracker, attributing the calls with valid credentials of the given authorization to their API key.
+    ///
+    pub fn new(auth: RpcAuth) -> Self {
+        Self {
+            auth,
+            start: Instant::now(),
+            clients: Default::default(),
+        }
+    }
+
+    ///
+    /// Records a request of the given client IP with the given body, answered with the given body, and returns its client.
+    /// If `is_success` is `false`, the request was rejected as a whole, so each of its calls counts as failed.
+    /// A request which holds no JSON-RPC calls, such as a request to the REST gateway, counts as a single call to `rest`.
+    ///
+    pub fn record_request(&self, ip: IpAddr, request: &[u8], is_success: bool, response: &[u8]) -> RpcClient {
+        let calls = match serde_json::from_slice::<Value>(request) {
+            Ok(Value::Array(batch)) => batch,
+            Ok(call) => vec![call],
+            Err(_) => vec![],
+        };
+
+        let mut usage = RpcUsage {
+            bytes_received: request.len() as u64,
+            bytes_sent: response.len() as u64,
+            ..Default::default()
+        };
+        for call in &calls {
+            usage.add_calls(call.get("method").and_then(Value::as_str).unwrap_or("invalid"), 1);
+        }
+        if calls.is_empty() {
+            usage.add_calls("rest", 1);
+        }
+        usage.calls = usage.methods.values().sum();
+        usage.errors = match (is_success, serde_json::from_slice::<Value>(response)) {
+            (true, Ok(Value::Array(batch))) => batch.iter().filter(|response| response.get("error").is_some()).count() as u64,
+            (true, Ok(response)) => response.get("error").is_some() as u64,
+            (true, Err(_)) => 0,
+            (false, _) => usage.calls,
+        };
+
+        // Attribute the request to the API key of the first valid credentials among its calls, or to the client IP.
+        let client = calls
+            .iter()
+            .filter_map(|call| serde_json::from_value::<RpcCredentials>(call.get("params")?.get(0)?.clone()).ok())
+            .find(|credentials| self.auth.role(credentials).is_some())
+            .map_or_else(|| RpcClient::Ip(client_key(ip)), |credentials| RpcClient::ApiKey(credentials.username));
+
+        self.record(client.clone(), usage, self.minute());
+        client
+    }
+
+    ///
+    /// Records the given number of bytes sent to the given client, as a streamed response is sent.
+    ///
+    pub fn record_bytes_sent(&self, client: &RpcClient, bytes: usize) {
+        let usage = RpcUsage {
+            bytes_sent: bytes as u64,
+            ..Default::default()
+        };
+        self.record(client.clone(), usage, self.minute());
+    }
+
+    ///
+    /// Returns the usage of the clients with calls in the latest hour, up to the given number of clients,
+    /// starting with the clients with the most calls.
+    ///
+    pub fn usage(&self, limit: usize) -> Vec<RpcClientUsage> {
+        let minute = self.minute();
+        let clients = self.clients.lock().expect("The RPC usage lock is never poisoned");
+
+        let mut usage: Vec<RpcClientUsage> = clients
+            .iter()
+            .map(|(client, minutes)| {
+                let window = |length: u64| {
+                    let mut usage = RpcUsage::default();
+                    minutes.iter().filter(|(start, _)| start + length > minute).for_each(|(_, other)| usage.add(other));
+                    usage
+                };
+                RpcClientUsage {
+                    client: client.clone(),
+                    last_minute: window(1),
+                    last_15_minutes: window(15),
+                    last_hour: window(RETAINED_MINUTES),
+                }
+            })
+            .filter(|usage| usage.last_hour.calls > 0)
+            .collect();
+        usage.sort_by(|a, b| b.last_hour.calls.cmp(&a.last_hour.calls).then_with(|| a.client.cmp(&b.client)));
+        usage.truncate(limit);
+        usage
+    }
+
+    /// Adds the given usage of the given client to the given minute.
+    fn record(&self, client: RpcClient, usage: RpcUsage, minute: u64) {
+        let is_retained = |start: u64| start + RETAINED_MINUTES > minute;
+        let mut clients = self.clients.lock().expect("The RPC usage lock is never poisoned");
+
+        // Stop tracking the clients without calls in the latest hour once too many clients are tracked.
+        if clients.len() >= MAXIMUM_TRACKED_CLIENTS {
+            clients.retain(|_, minutes| minutes.back().map_or(false, |(start, _)| is_retained(*start)));
+        }
+
+        let minutes = clients.entry(client).or_default();
+        while minutes.front().map_or(false, |(start, _)| !is_retained(*start)) {
+            minutes.pop_front();
+        }
+        match minutes.back_mut() {
+            Some((start, current)) if *start == minute => current.add(&usage),
+            _ => {
+                let mut current = RpcUsage::default();
+                current.add(&usage);
+                minutes.push_back((minute, current));
+            }
+        }
+    }
+
+    /// Returns the number of minutes since the tracker was initialized.
+    fn minute(&self) -> u64 {
+        self.start.elapsed().as_secs() / 60
+    }
+}
@@ -79,7 +79,7 @@ impl<'a, N: Network, E: Environment> Display<'a, N, E> {
         // Initialize the log channel.
         let (log_sender, log_receiver) = mpsc::channel(1024);
 
-        initialize_logger(verbosity, Some(log_sender));
+        initialize_logger(verbosity, Some(log_sender), None);
 
         enable_raw_mode()?;
         let mut stdout = io::stdout();
@@ -30,9 +30,18 @@ pub(crate) use display::*;
 pub mod node;
 pub use node::*;
 
+pub mod parameters;
+pub use parameters::*;
+
+pub mod preflight;
+pub use preflight::*;
+
 pub mod server;
 pub use server::*;
 
+pub mod service;
+pub use service::*;
+
 pub mod updater;
 pub use updater::*;
 
@@ -18,19 +18,38 @@ use snarkos::{initialize_logger, Node};
 
 use anyhow::Result;
 use clap::Parser;
+use std::fs::OpenOptions;
 use tokio::runtime;
 
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
 fn main() -> Result<()> {
+    // Parse the provided arguments.
+    let node = Node::parse();
+
+    // Hand control to the Windows service control manager, if the node was started as a service.
+    #[cfg(windows)]
+    if node.windows_service {
+        return windows::run();
+    }
+
+    run(node)
+}
+
+fn run(node: Node) -> Result<()> {
     if num_cpus::get() < 16 {
         eprintln!("\nWARNING - Your machine must have at least 16-cores to run a node.\n");
     }
 
-    // Parse the provided arguments.
-    let node = Node::parse();
-
     // Start logging, if enabled.
     if !node.display {
-        initialize_logger(node.verbosity, None);
+        let log_file = match &node.log_file {
+            Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+            None => None,
+        };
+        initialize_logger(node.verbosity, None, log_file);
     }
 
     let (num_tokio_worker_threads, max_tokio_blocking_threads) = (num_cpus::get(), 512); // 512 is tokio's current default
@@ -58,3 +77,76 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Runs the node as a Windows service, reporting its status to the service control manager.
+#[cfg(windows)]
+mod windows {
+    use clap::Parser;
+    use once_cell::sync::OnceCell;
+    use snarkos::{exit_hook, shutdown_notifier, Node, SERVICE_NAME};
+    use std::{ffi::OsString, time::Duration};
+    use windows_service::{
+        define_windows_service,
+        service::{ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType},
+        service_control_handler::{self, ServiceControlHandlerResult, ServiceStatusHandle},
+        service_dispatcher,
+    };
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    /// The status handle of the service, which reports the exit of the node to the service control manager.
+    static STATUS_HANDLE: OnceCell<ServiceStatusHandle> = OnceCell::new();
+
+    pub fn run() -> anyhow::Result<()> {
+        service_dispatcher::start(SERVICE_NAME, ffi_service_main)?;
+        Ok(())
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        // Request a clean shutdown of the node when the service is stopped.
+        let status_handle = match service_control_handler::register(SERVICE_NAME, |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                shutdown_notifier().notify_one();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        }) {
+            Ok(status_handle) => status_handle,
+            Err(_) => return,
+        };
+
+        // The node exits the process once it has shut down, so the service is reported as stopped just before.
+        let _ = STATUS_HANDLE.set(status_handle);
+        let _ = exit_hook().set(report_exit);
+
+        set_status(&status_handle, ServiceState::Running, ServiceExitCode::Win32(0));
+        let exit_code = match super::run(Node::parse()) {
+            Ok(()) => 0,
+            Err(_) => 1,
+        };
+        report_exit(exit_code);
+    }
+
+    /// Reports the service as stopped with the given exit code.
+    fn report_exit(exit_code: i32) {
+        if let Some(status_handle) = STATUS_HANDLE.get() {
+            set_status(status_handle, ServiceState::Stopped, ServiceExitCode::Win32(exit_code as u32));
+        }
+    }
+
+    fn set_status(status_handle: &ServiceStatusHandle, current_state: ServiceState, exit_code: ServiceExitCode) {
+        let _ = status_handle.set_service_status(ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state,
+            controls_accepted: match current_state {
+                ServiceState::Running => ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+                _ => ServiceControlAccept::empty(),
+            },
+            exit_code,
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        });
+    }
+}
@@ -14,13 +14,14 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Display, Server, Updater};
+use crate::{Display, InstallService, Server, Updater};
 use snarkos_environment::{
-    helpers::NodeType,
+    helpers::{NodeType, SecretString, ShutdownKind},
     Client,
     ClientTrial,
     CurrentNetwork,
     Environment,
+    Explorer,
     Miner,
     MinerTrial,
     Operator,
@@ -29,66 +30,323 @@ use snarkos_environment::{
     ProverTrial,
     SyncNode,
 };
-use snarkos_storage::storage::rocksdb::RocksDB;
-use snarkvm::dpc::prelude::*;
+use snarkos_network::{
+    helpers::{payout_address_message, payout_schedule_message, read_session, PropagationPolicy, RecordSelection},
+    Message,
+};
+#[cfg(feature = "rpc")]
+use snarkos_rpc::BlockTemplateSchema;
+use snarkos_storage::{storage::rocksdb::RocksDB, PayoutSchedule};
+use snarkvm::{dpc::prelude::*, utilities::ToBits};
 
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use colored::*;
 use crossterm::tty::IsTty;
-use std::{io, net::SocketAddr, path::PathBuf, str::FromStr};
-use tokio::sync::mpsc;
-use tracing_subscriber::EnvFilter;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc,
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use once_cell::sync::OnceCell;
+use tokio::sync::{mpsc, Notify};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
+use zeroize::Zeroize;
 
 #[derive(Debug, Parser)]
 #[clap(name = "snarkos", author = "The Aleo Team <hello@aleo.org>")]
 pub struct Node {
     /// Specify the IP address and port of a peer to connect to.
-    #[clap(long = "connect")]
+    #[clap(long = "connect", env = "SNARKOS_CONNECT")]
     pub connect: Option<String>,
     /// Specify this as a mining node, with the given miner address.
-    #[clap(long = "miner")]
+    #[clap(long = "miner", env = "SNARKOS_MINER")]
     pub miner: Option<String>,
     /// Specify this as an operating node, with the given operator address.
-    #[clap(long = "operator")]
+    #[clap(long = "operator", env = "SNARKOS_OPERATOR")]
     pub operator: Option<String>,
+    /// Specify this as an explorer node, serving RPC from the ledger at the given path as it is synced by another node.
+    #[clap(parse(from_os_str), long = "explorer", env = "SNARKOS_EXPLORER")]
+    pub explorer: Option<PathBuf>,
+    /// Specify a JSON file of the sub-pools served by an operating node, each with its own fee, payout address, and provers.
+    #[clap(parse(from_os_str), long = "sub-pools", env = "SNARKOS_SUB_POOLS")]
+    pub sub_pools: Option<PathBuf>,
+    /// Specify a tag of up to 32 characters, embedded in the coinbase of the blocks found by an operating node.
+    #[clap(long = "block-tag", env = "SNARKOS_BLOCK_TAG")]
+    pub block_tag: Option<String>,
+    /// Specify the private key of the operator address, to sign the block tag over the hash of each block found.
+    #[clap(long = "block-tag-key", env = "SNARKOS_BLOCK_TAG_KEY", hide_env_values = true)]
+    pub block_tag_key: Option<SecretString>,
+    /// Specify the number of days for which an operating node retains the raw shares of each round (forever if 0).
+    #[clap(default_value = "0", long = "share-retention-days", env = "SNARKOS_SHARE_RETENTION_DAYS")]
+    pub share_retention_days: u64,
+    /// Specify the number of days for which an operating node retains the statistics of each round (forever if 0).
+    #[clap(default_value = "0", long = "round-retention-days", env = "SNARKOS_ROUND_RETENTION_DAYS")]
+    pub round_retention_days: u64,
+    /// Specify the number of days for which an operating node retains the contributions of each epoch (forever if 0).
+    #[clap(default_value = "0", long = "metrics-retention-days", env = "SNARKOS_METRICS_RETENTION_DAYS")]
+    pub metrics_retention_days: u64,
+    /// Specify the fraction of shares fully verified by an operating node under extreme share load (always verified if 1).
+    #[clap(default_value = "1", long = "share-sampling-rate", env = "SNARKOS_SHARE_SAMPLING_RATE")]
+    pub share_sampling_rate: f64,
+    /// Specify the number of shares per second above which an operating node samples the shares it verifies.
+    #[clap(default_value = "1000", long = "share-sampling-load", env = "SNARKOS_SHARE_SAMPLING_LOAD")]
+    pub share_sampling_load: u64,
+    /// Specify the number of seconds by which the timestamp of a share may be off the clock of an operating node.
+    #[clap(default_value = "60", long = "share-timestamp-tolerance", env = "SNARKOS_SHARE_TIMESTAMP_TOLERANCE")]
+    pub share_timestamp_tolerance: u64,
+    /// Specify the minimum payout of the provers of an operating node, in gates, unless they choose their own.
+    #[clap(default_value = "1000000", long = "min-payout", env = "SNARKOS_MIN_PAYOUT")]
+    pub min_payout: u64,
+    /// Specify the lowest and highest minimum payouts that provers may choose, in gates.
+    #[clap(
+        default_value = "100000,1000000000000",
+        long = "min-payout-bounds",
+        use_value_delimiter = true,
+        env = "SNARKOS_MIN_PAYOUT_BOUNDS"
+    )]
+    pub min_payout_bounds: Vec<u64>,
+    /// Specify the number of blocks between two payouts to the provers of an operating node, unless they choose their own.
+    #[clap(default_value = "180", long = "payout-interval", env = "SNARKOS_PAYOUT_INTERVAL")]
+    pub payout_interval: u32,
+    /// Specify the shortest and longest payout intervals that provers may choose, in blocks.
+    #[clap(
+        default_value = "1,4320",
+        long = "payout-interval-bounds",
+        use_value_delimiter = true,
+        env = "SNARKOS_PAYOUT_INTERVAL_BOUNDS"
+    )]
+    pub payout_interval_bounds: Vec<u32>,
+    /// Specify the selection of the pool wallet records that fund the payouts [options: largest-first, oldest-first, branch-and-bound].
+    #[clap(default_value = "largest-first", long = "payout-record-selection", env = "SNARKOS_PAYOUT_RECORD_SELECTION")]
+    pub payout_record_selection: RecordSelection,
+    /// Specify the URLs of external RPC servers, e.g. other pools and explorers, to compare the chain of an operating node with.
+    #[clap(long = "chain-split-endpoints", use_value_delimiter = true, env = "SNARKOS_CHAIN_SPLIT_ENDPOINTS")]
+    pub chain_split_endpoints: Vec<String>,
+    /// Specify the number of blocks the chain may diverge from the external RPC servers before a chain split is raised.
+    #[clap(default_value = "6", long = "chain-split-threshold", env = "SNARKOS_CHAIN_SPLIT_THRESHOLD")]
+    pub chain_split_threshold: u32,
+    /// Specify the interval in seconds at which to compare the chain with the external RPC servers.
+    #[clap(default_value = "60", long = "chain-split-interval", env = "SNARKOS_CHAIN_SPLIT_INTERVAL")]
+    pub chain_split_interval: u64,
+    /// If the flag is set, an operating node halts its payouts while its chain is split from the external RPC servers.
+    #[clap(long = "chain-split-halt-payouts", env = "SNARKOS_CHAIN_SPLIT_HALT_PAYOUTS")]
+    pub chain_split_halt_payouts: bool,
+    /// Specify the IP address and port of the TLS listener for the provers of an operating node.
+    #[clap(parse(try_from_str), long = "pool-tls-listener", env = "SNARKOS_POOL_TLS_LISTENER")]
+    pub pool_tls_listener: Option<SocketAddr>,
+    /// Specify the PEM-encoded certificate chain of the TLS listener.
+    #[clap(parse(from_os_str), long = "pool-tls-cert", env = "SNARKOS_POOL_TLS_CERT")]
+    pub pool_tls_cert: Option<PathBuf>,
+    /// Specify the PEM-encoded private key of the TLS listener.
+    #[clap(parse(from_os_str), long = "pool-tls-key", env = "SNARKOS_POOL_TLS_KEY")]
+    pub pool_tls_key: Option<PathBuf>,
+    /// Specify the PEM-encoded CA issuing the client certificates of provers, for sub-pools requiring them.
+    #[clap(parse(from_os_str), long = "pool-tls-client-ca", env = "SNARKOS_POOL_TLS_CLIENT_CA")]
+    pub pool_tls_client_ca: Option<PathBuf>,
     /// Specify this as a prover node, with the given prover address.
-    #[clap(long = "prover")]
+    #[clap(long = "prover", env = "SNARKOS_PROVER")]
     pub prover: Option<String>,
     /// Specify the pool that a prover node is contributing to.
-    #[clap(long = "pool")]
+    #[clap(long = "pool", env = "SNARKOS_POOL")]
     pub pool: Option<SocketAddr>,
+    /// Specify the URL of the pool's RPC server, to fetch jobs and submit shares over HTTP(S) if the pool is unreachable over TCP.
+    #[clap(long = "pool-http", env = "SNARKOS_POOL_HTTP")]
+    pub pool_http: Option<String>,
+    /// Specify the private key of the prover address, to sign the job requests of a prover node to the pool's RPC server.
+    #[clap(long = "pool-http-key", env = "SNARKOS_POOL_HTTP_KEY", hide_env_values = true)]
+    pub pool_http_key: Option<SecretString>,
+    /// Specify the TLS server name of the pool, to connect a prover node to the pool over TLS.
+    #[clap(long = "pool-tls-name", env = "SNARKOS_POOL_TLS_NAME")]
+    pub pool_tls_name: Option<String>,
+    /// Specify the PEM-encoded CA to verify the pool certificate against, instead of the web PKI roots.
+    #[clap(parse(from_os_str), long = "pool-tls-ca", env = "SNARKOS_POOL_TLS_CA")]
+    pub pool_tls_ca: Option<PathBuf>,
+    /// Specify the PEM-encoded client certificate chain and private key of a prover node, for pools requiring them.
+    #[clap(parse(from_os_str), long = "pool-tls-identity", env = "SNARKOS_POOL_TLS_IDENTITY")]
+    pub pool_tls_identity: Option<PathBuf>,
     /// Specify the network of this node.
-    #[clap(default_value = "2", long = "network")]
+    #[clap(default_value = "2", long = "network", env = "SNARKOS_NETWORK")]
     pub network: u16,
     /// Specify the IP address and port for the node server.
-    #[clap(parse(try_from_str), default_value = "0.0.0.0:4132", long = "node")]
+    #[clap(parse(try_from_str), default_value = "0.0.0.0:4132", long = "node", env = "SNARKOS_NODE")]
     pub node: SocketAddr,
     /// Specify the IP address and port for the RPC server.
-    #[clap(parse(try_from_str), default_value = "0.0.0.0:3032", long = "rpc")]
+    #[clap(parse(try_from_str), default_value = "0.0.0.0:3032", long = "rpc", env = "SNARKOS_RPC")]
     pub rpc: SocketAddr,
+    /// Specify the IP address and port for the RPC WebSocket server, which also serves subscriptions (disabled if unset).
+    #[clap(parse(try_from_str), long = "rpc-ws", env = "SNARKOS_RPC_WS")]
+    pub rpc_ws: Option<SocketAddr>,
+    /// Specify the IP address and port for the REST gateway over the public RPC endpoints (disabled if unset).
+    #[clap(parse(try_from_str), long = "rest", env = "SNARKOS_REST")]
+    pub rest: Option<SocketAddr>,
+    /// Specify the path at which the REST gateway serves the built-in explorer, such as `/explorer` (disabled if unset).
+    #[clap(long = "rest-explorer", env = "SNARKOS_REST_EXPLORER")]
+    pub rest_explorer: Option<String>,
+    /// Specify the path at which the REST gateway of an operating node serves the landing page of the pool (disabled if unset).
+    #[clap(long = "rest-pool-page", env = "SNARKOS_REST_POOL_PAGE")]
+    pub rest_pool_page: Option<String>,
+    /// Specify the IP address and port for the gRPC server over the public RPC endpoints (disabled if unset).
+    #[cfg(feature = "grpc")]
+    #[clap(parse(try_from_str), long = "grpc", env = "SNARKOS_GRPC")]
+    pub grpc: Option<SocketAddr>,
+    /// Specify the IP address and port for the Prometheus metrics, served at `/metrics`.
+    #[cfg(feature = "prometheus")]
+    #[clap(parse(try_from_str), default_value = "0.0.0.0:9000", long = "metrics", env = "SNARKOS_METRICS")]
+    pub metrics: SocketAddr,
+    /// Specify the PEM-encoded certificate chain, to serve the RPC servers over TLS.
+    #[clap(parse(from_os_str), long = "rpc-tls-cert", env = "SNARKOS_RPC_TLS_CERT")]
+    pub rpc_tls_cert: Option<PathBuf>,
+    /// Specify the PEM-encoded private key, to serve the RPC servers over TLS.
+    #[clap(parse(from_os_str), long = "rpc-tls-key", env = "SNARKOS_RPC_TLS_KEY")]
+    pub rpc_tls_key: Option<PathBuf>,
+    /// Specify the origins allowed to call the RPC server from a browser, or `*` for any origin (CORS is disabled if unset).
+    #[clap(long = "rpc-cors-origins", use_value_delimiter = true, env = "SNARKOS_RPC_CORS_ORIGINS")]
+    pub rpc_cors_origins: Vec<String>,
+    /// Specify the HTTP methods allowed in cross-origin RPC requests.
+    #[clap(
+        default_value = "POST",
+        long = "rpc-cors-methods",
+        use_value_delimiter = true,
+        env = "SNARKOS_RPC_CORS_METHODS"
+    )]
+    pub rpc_cors_methods: Vec<String>,
+    /// Specify the request headers allowed in cross-origin RPC requests.
+    #[clap(
+        default_value = "content-type",
+        long = "rpc-cors-headers",
+        use_value_delimiter = true,
+        env = "SNARKOS_RPC_CORS_HEADERS"
+    )]
+    pub rpc_cors_headers: Vec<String>,
+    /// Specify the level to compress the RPC HTTP responses at, from 1 (fastest) to 9 (smallest) (disabled if unset).
+    #[clap(long = "rpc-compression", env = "SNARKOS_RPC_COMPRESSION")]
+    pub rpc_compression: Option<u32>,
+    /// If the flag is set, the RPC server will serve HTTP/2 to clients with prior knowledge, and negotiate it over TLS.
+    #[clap(long = "rpc-http2", env = "SNARKOS_RPC_HTTP2")]
+    pub rpc_http2: bool,
+    /// Specify the number of seconds after which an idle connection to the RPC server is closed (kept open if unset).
+    #[clap(long = "rpc-idle-timeout", env = "SNARKOS_RPC_IDLE_TIMEOUT")]
+    pub rpc_idle_timeout: Option<u64>,
+    /// Specify the RPC calls per second each client IP may sustain (unlimited if unset).
+    #[clap(long = "rpc-rate", env = "SNARKOS_RPC_RATE")]
+    pub rpc_rate: Option<f64>,
+    /// Specify the RPC calls each client IP may make at once, if the RPC calls are rate limited.
+    #[clap(default_value = "50", long = "rpc-burst", env = "SNARKOS_RPC_BURST")]
+    pub rpc_burst: u32,
+    /// Specify the calls per second each client IP may sustain to the expensive RPC methods, such as `getblocks`.
+    #[clap(default_value = "1", long = "rpc-heavy-rate", env = "SNARKOS_RPC_HEAVY_RATE")]
+    pub rpc_heavy_rate: f64,
+    /// Specify the calls each client IP may make at once to the expensive RPC methods.
+    #[clap(default_value = "5", long = "rpc-heavy-burst", env = "SNARKOS_RPC_HEAVY_BURST")]
+    pub rpc_heavy_burst: u32,
+    /// If the flag is set, the RPC server will record the usage of each client, which is served by `getapiusage`.
+    #[clap(long = "rpc-usage", env = "SNARKOS_RPC_USAGE")]
+    pub rpc_usage: bool,
+    /// Specify the file to append the audit log of the calls to the guarded RPC methods to, or `syslog`.
+    #[clap(long = "rpc-audit-log", env = "SNARKOS_RPC_AUDIT_LOG")]
+    pub rpc_audit_log: Option<String>,
     /// Specify the username for the RPC server.
-    #[clap(default_value = "root", long = "username")]
+    #[clap(default_value = "root", long = "username", env = "SNARKOS_USERNAME")]
     pub rpc_username: String,
     /// Specify the password for the RPC server.
-    #[clap(default_value = "pass", long = "password")]
-    pub rpc_password: String,
+    #[clap(default_value = "pass", long = "password", env = "SNARKOS_PASSWORD", hide_env_values = true)]
+    pub rpc_password: SecretString,
+    /// Specify the schema of the block templates served over RPC [options: native, generic].
+    #[cfg(feature = "rpc")]
+    #[clap(default_value = "native", long = "rpc-block-template-schema", env = "SNARKOS_RPC_BLOCK_TEMPLATE_SCHEMA")]
+    pub rpc_block_template_schema: BlockTemplateSchema,
+    /// Specify a JSON file of API keys for the private RPC endpoints, each with a role of "read-only", "wallet", or "admin".
+    #[clap(parse(from_os_str), long = "rpc-api-keys", env = "SNARKOS_RPC_API_KEYS")]
+    pub rpc_api_keys: Option<PathBuf>,
+    /// Specify the only RPC methods or groups of methods ("admin", "operator", "wallet") to serve (serves all if unset).
+    #[clap(long = "rpc-allowed-methods", use_value_delimiter = true, env = "SNARKOS_RPC_ALLOWED_METHODS")]
+    pub rpc_allowed_methods: Vec<String>,
+    /// Specify the RPC methods or groups of methods ("admin", "operator", "wallet") to disable.
+    #[clap(long = "rpc-denied-methods", use_value_delimiter = true, env = "SNARKOS_RPC_DENIED_METHODS")]
+    pub rpc_denied_methods: Vec<String>,
+    /// Specify the node types permitted to connect inbound, e.g. "prover,sync" (permits all node types if unset).
+    #[clap(long = "allow-inbound", use_value_delimiter = true, env = "SNARKOS_ALLOW_INBOUND")]
+    pub allow_inbound: Vec<NodeType>,
+    /// Specify the number of connection slots reserved for outbound connections to sync and beacon nodes (none if 0).
+    #[clap(default_value = "0", long = "reserved-sync-slots", env = "SNARKOS_RESERVED_SYNC_SLOTS")]
+    pub reserved_sync_slots: usize,
+    /// Specify the interval in seconds at which to rotate a fraction of the outbound peers (disabled if 0).
+    #[clap(default_value = "0", long = "peer-rotation-interval", env = "SNARKOS_PEER_ROTATION_INTERVAL")]
+    pub peer_rotation_interval: u64,
+    /// If the flag is set, the node will serve block headers to its peers, but decline to serve full blocks.
+    #[clap(long = "headers-only", env = "SNARKOS_HEADERS_ONLY")]
+    pub headers_only: bool,
+    /// Specify a directory to record the inbound message stream of each peer session in, to replay it against another node.
+    #[clap(parse(from_os_str), long = "record-sessions", env = "SNARKOS_RECORD_SESSIONS")]
+    pub record_sessions: Option<PathBuf>,
+    /// If the flag is set, the node will index the transactions of each address, for the `gettransactionsforaddress` RPC endpoint.
+    #[clap(long = "address-index", env = "SNARKOS_ADDRESS_INDEX")]
+    pub address_index: bool,
+    /// Specify the interval in seconds at which to cross-check the storage indexes against the blocks (disabled if 0).
+    #[clap(default_value = "600", long = "index-check-interval", env = "SNARKOS_INDEX_CHECK_INTERVAL")]
+    pub index_check_interval: u64,
+    /// Specify the number of blocks sampled on each cross-check of the storage indexes.
+    #[clap(default_value = "16", long = "index-check-sample-size", env = "SNARKOS_INDEX_CHECK_SAMPLE_SIZE")]
+    pub index_check_sample_size: u32,
+    /// Specify the propagation of the transactions submitted over RPC [options: immediate, trickle, private].
+    #[clap(default_value = "immediate", long = "tx-propagation", env = "SNARKOS_TX_PROPAGATION")]
+    pub tx_propagation: PropagationPolicy,
+    /// Specify the minimum fee per byte of the transactions admitted into the memory pool, in gates.
+    #[clap(default_value = "0", long = "mempool-min-fee-per-byte", env = "SNARKOS_MEMPOOL_MIN_FEE_PER_BYTE")]
+    pub mempool_min_fee_per_byte: u64,
+    /// Specify the maximum number of transitions of the transactions admitted into the memory pool (unlimited if unset).
+    #[clap(long = "mempool-max-transitions", env = "SNARKOS_MEMPOOL_MAX_TRANSITIONS")]
+    pub mempool_max_transitions: Option<usize>,
+    /// If the flag is set, the memory pool rejects transactions that pay no fee and transfer no value.
+    #[clap(long = "mempool-reject-zero-value", env = "SNARKOS_MEMPOOL_REJECT_ZERO_VALUE")]
+    pub mempool_reject_zero_value: bool,
     /// Specify the verbosity of the node [options: 0, 1, 2, 3]
-    #[clap(default_value = "2", long = "verbosity")]
+    #[clap(default_value = "2", long = "verbosity", env = "SNARKOS_VERBOSITY")]
     pub verbosity: u8,
     /// Enables development mode, specify a unique ID for the local node.
-    #[clap(long)]
+    #[clap(long, env = "SNARKOS_DEV")]
     pub dev: Option<u16>,
+    /// Specify the directory to store the node data in, e.g. a mounted volume (defaults to the Aleo directory).
+    #[clap(parse(from_os_str), long = "data-dir", env = "SNARKOS_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
     /// If the flag is set, the node will render a read-only display.
-    #[clap(long)]
+    #[clap(long, env = "SNARKOS_DISPLAY")]
     pub display: bool,
     /// If the flag is set, the node will not initialize the RPC server.
-    #[clap(long)]
+    #[clap(long, env = "SNARKOS_NORPC")]
     pub norpc: bool,
-    #[clap(hide = true, long)]
+    /// Specify a file to append the logs to, instead of printing them.
+    #[clap(parse(from_os_str), long = "log-file", env = "SNARKOS_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
+    /// If the flag is set, the node will load its SNARK parameters in parallel before starting, instead of upon first use.
+    #[clap(long = "preload-params", env = "SNARKOS_PRELOAD_PARAMS")]
+    pub preload_params: bool,
+    /// Specify a manifest of parameter files, whose checksums are verified before starting.
+    #[clap(parse(from_os_str), long = "params-manifest", env = "SNARKOS_PARAMS_MANIFEST")]
+    pub params_manifest: Option<PathBuf>,
+    /// If the flag is set, missing or corrupted parameter files in the manifest are downloaded again.
+    #[clap(long = "repair-params", env = "SNARKOS_REPAIR_PARAMS")]
+    pub repair_params: bool,
+    #[clap(hide = true, long, env = "SNARKOS_TRIAL")]
     pub trial: bool,
-    #[clap(hide = true, long)]
+    #[clap(hide = true, long, env = "SNARKOS_SYNC")]
     pub sync: bool,
+    /// If the flag is set, the node runs under the Windows service control manager.
+    #[cfg(windows)]
+    #[clap(hide = true, long = "windows-service")]
+    pub windows_service: bool,
     /// Specify an optional subcommand.
     #[clap(subcommand)]
     commands: Option<Command>,
@@ -100,7 +358,11 @@ impl Node {
         // Parse optional subcommands first.
         match self.commands {
             Some(command) => {
-                println!("{}", command.parse()?);
+                // The output may contain private key material, so zeroize it once printed. Only the output is zeroized,
+                // as the accounts and private keys of snarkVM that the output is formatted from are not zeroized on drop.
+                let mut output = command.parse()?;
+                println!("{}", output);
+                output.zeroize();
                 Ok(())
             }
             None => match &self.get_node_type() {
@@ -116,6 +378,7 @@ impl Node {
                 }
                 (NodeType::Prover, true) => self.start_server::<CurrentNetwork, ProverTrial<CurrentNetwork>>(&self.prover).await,
                 (NodeType::Sync, _) => self.start_server::<CurrentNetwork, SyncNode<CurrentNetwork>>(&None).await,
+                (NodeType::Explorer, false) => self.start_server::<CurrentNetwork, Explorer<CurrentNetwork>>(&None).await,
                 _ => panic!("Unsupported node configuration"),
             },
         }
@@ -124,6 +387,8 @@ impl Node {
     fn get_node_type(&self) -> (NodeType, bool) {
         (
             match (self.network, &self.miner, &self.operator, &self.prover, self.sync) {
+                (2, None, None, None, false) if self.explorer.is_some() => NodeType::Explorer,
+                _ if self.explorer.is_some() => panic!("Unsupported node configuration"),
                 (2, None, None, None, false) => NodeType::Client,
                 (2, Some(_), None, None, false) => NodeType::Miner,
                 (2, None, Some(_), None, false) => NodeType::Operator,
@@ -135,12 +400,41 @@ impl Node {
         )
     }
 
+    /// Returns the storage path of the given kind in the data directory, if one is specified.
+    fn data_storage_path(&self, kind: &str) -> Option<PathBuf> {
+        self.data_dir.as_ref().map(|data_dir| match self.dev {
+            Some(id) => data_dir.join(format!("{}-{}-{}", kind, self.network, id)),
+            None => data_dir.join(format!("{}-{}", kind, self.network)),
+        })
+    }
+
+    /// Ensures the data directory, if one is specified, exists and is writable by this process.
+    fn check_data_dir(&self) -> Result<()> {
+        if let Some(data_dir) = &self.data_dir {
+            fs::create_dir_all(data_dir)
+                .map_err(|error| anyhow!("Failed to create the data directory {}: {}", data_dir.display(), error))?;
+
+            // Probe the directory, as a read-only volume or a mismatched owner only surfaces upon the first write.
+            let probe = data_dir.join(".snarkos-write-check");
+            fs::write(&probe, []).and_then(|_| fs::remove_file(&probe)).map_err(|error| {
+                anyhow!(
+                    "The data directory {} is not writable ({}). Ensure the volume is mounted read-write and is owned by the user running snarkOS",
+                    data_dir.display(),
+                    error
+                )
+            })?;
+        }
+        Ok(())
+    }
+
     /// Returns the storage path of the ledger.
     pub(crate) fn ledger_storage_path(&self, _local_ip: SocketAddr) -> PathBuf {
         if cfg!(feature = "test") {
             // Tests may use any available ports, and removes the storage artifacts afterwards,
             // so that there is no need to adhere to a specific number assignment logic.
             PathBuf::from(format!("/tmp/snarkos-test-ledger-{}", _local_ip.port()))
+        } else if let Some(path) = self.data_storage_path("ledger") {
+            path
         } else {
             aleo_std::aleo_ledger_dir(self.network, self.dev)
         }
@@ -152,6 +446,8 @@ impl Node {
             // Tests may use any available ports, and removes the storage artifacts afterwards,
             // so that there is no need to adhere to a specific number assignment logic.
             PathBuf::from(format!("/tmp/snarkos-test-operator-{}", _local_ip.port()))
+        } else if let Some(path) = self.data_storage_path("operator") {
+            path
         } else {
             aleo_std::aleo_operator_dir(self.network, self.dev)
         }
@@ -163,14 +459,24 @@ impl Node {
             // Tests may use any available ports, and removes the storage artifacts afterwards,
             // so that there is no need to adhere to a specific number assignment logic.
             PathBuf::from(format!("/tmp/snarkos-test-prover-{}", _local_ip.port()))
+        } else if let Some(path) = self.data_storage_path("prover") {
+            path
         } else {
             aleo_std::aleo_prover_dir(self.network, self.dev)
         }
     }
 
     async fn start_server<N: Network, E: Environment>(&self, address: &Option<String>) -> Result<()> {
+        #[cfg(not(feature = "prover"))]
+        if matches!(E::NODE_TYPE, NodeType::Miner | NodeType::Prover) {
+            return Err(anyhow!("This node was built without prover support. Please rebuild it with '--features prover'"));
+        }
+
         println!("{}", crate::display::welcome_message());
 
+        // Check the configuration and the host before starting, to surface misconfigurations upfront.
+        crate::run_preflight_checks::<N, E>(self)?;
+
         let address = match (E::NODE_TYPE, address) {
             (NodeType::Miner, Some(address)) | (NodeType::Operator, Some(address)) | (NodeType::Prover, Some(address)) => {
                 let address = Address::<N>::from_str(address)?;
@@ -183,6 +489,20 @@ impl Node {
         println!("Starting {} on {}.", E::NODE_TYPE.description(), N::NETWORK_NAME);
         println!("{}", crate::display::notification_message::<N>(address));
 
+        // Ensure the data directory is usable before opening any storage.
+        self.check_data_dir()?;
+
+        // Verify the parameter files, if a manifest is given.
+        if let Some(manifest_path) = self.params_manifest.clone() {
+            let repair = self.repair_params;
+            tokio::task::spawn_blocking(move || crate::check_parameters(manifest_path, repair)).await??;
+        }
+
+        // Load the SNARK parameters upfront, if requested.
+        if self.preload_params {
+            tokio::task::spawn_blocking(|| crate::preload_parameters::<N>(E::NODE_TYPE)).await?;
+        }
+
         // Initialize the node's server.
         let server = Server::<N, E>::initialize(self, address, self.pool).await?;
 
@@ -209,7 +529,7 @@ impl Node {
     }
 }
 
-pub fn initialize_logger(verbosity: u8, log_sender: Option<mpsc::Sender<Vec<u8>>>) {
+pub fn initialize_logger(verbosity: u8, log_sender: Option<mpsc::Sender<Vec<u8>>>, log_file: Option<File>) {
     match verbosity {
         0 => std::env::set_var("RUST_LOG", "info"),
         1 => std::env::set_var("RUST_LOG", "debug"),
@@ -217,23 +537,74 @@ pub fn initialize_logger(verbosity: u8, log_sender: Option<mpsc::Sender<Vec<u8>>
         _ => std::env::set_var("RUST_LOG", "info"),
     };
 
-    // Filter out undesirable logs.
-    let filter = EnvFilter::from_default_env()
+    // Filter out undesirable logs, behind a handle which reloads the filter while the node runs.
+    let directives = std::env::var("RUST_LOG").unwrap_or_default();
+    let (filter, filter_handle) = reload::Layer::new(log_filter(&directives).expect("The verbosity is a valid log filter"));
+
+    // Initialize the log output.
+    let is_stdout = log_sender.is_none() && log_file.is_none();
+    let log_file = log_file.map(|file| Arc::new(Mutex::new(file)));
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_ansi(is_stdout && io::stdout().is_tty())
+        .with_writer(move || LogWriter::new(&log_sender, &log_file))
+        .with_target(verbosity == 3);
+
+    let subscriber = tracing_subscriber::registry().with(filter).with(fmt_layer);
+
+    // Export the spans to an OTLP collector, if one is specified.
+    #[cfg(feature = "otlp")]
+    let subscriber = subscriber.with(initialize_otlp_layer());
+
+    // Initialize tracing, and let the filter be changed at runtime, such as by the `setloglevel` RPC method.
+    // Note: The logger is process-wide, so the filter is shared by every environment.
+    if subscriber.try_init().is_ok() {
+        Client::<CurrentNetwork>::log_filter().initialize(
+            directives,
+            Box::new(move |directives| {
+                let filter = log_filter(directives)?;
+                filter_handle.reload(filter).map_err(|error| error.to_string())
+            }),
+        );
+    }
+}
+
+/// Returns the log filter of the given directives, which filters out undesirable logs.
+fn log_filter(directives: &str) -> Result<EnvFilter, String> {
+    let filter = EnvFilter::try_new(directives).map_err(|error| format!("Invalid log filter '{}': {}", directives, error))?;
+    Ok(filter
         .add_directive("mio=off".parse().unwrap())
         .add_directive("tokio_util=off".parse().unwrap())
         .add_directive("hyper::proto::h1::conn=off".parse().unwrap())
         .add_directive("hyper::proto::h1::decode=off".parse().unwrap())
         .add_directive("hyper::proto::h1::io=off".parse().unwrap())
         .add_directive("hyper::proto::h1::role=off".parse().unwrap())
-        .add_directive("jsonrpsee=off".parse().unwrap());
+        .add_directive("jsonrpsee=off".parse().unwrap()))
+}
+
+/// Returns a layer exporting spans to the OTLP collector at `OTEL_EXPORTER_OTLP_ENDPOINT`, if the variable is set.
+#[cfg(feature = "otlp")]
+fn initialize_otlp_layer<S>() -> Option<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry::sdk::trace::Tracer>>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::{sdk::Resource, KeyValue};
+    use opentelemetry_otlp::WithExportConfig;
+
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok()?;
 
-    // Initialize tracing.
-    let _ = tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_ansi(log_sender.is_none() && io::stdout().is_tty())
-        .with_writer(move || LogWriter::new(&log_sender))
-        .with_target(verbosity == 3)
-        .try_init();
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().http().with_endpoint(&endpoint))
+        .with_trace_config(opentelemetry::sdk::trace::config().with_resource(Resource::new(vec![KeyValue::new("service.name", "snarkos")])))
+        .install_simple();
+
+    match tracer {
+        Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+        Err(error) => {
+            eprintln!("Failed to initialize the OTLP exporter at {}: {}", endpoint, error);
+            None
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -246,6 +617,12 @@ pub enum Command {
     Experimental(Experimental),
     #[clap(name = "miner", about = "Miner commands and settings")]
     Miner(MinerSubcommand),
+    #[clap(name = "account", about = "Account commands and tools")]
+    Account(AccountSubcommand),
+    #[clap(name = "install-service", about = "Installs snarkOS as a system service")]
+    InstallService(InstallService),
+    #[clap(name = "replay", about = "Replays a recorded peer session against a node")]
+    Replay(ReplaySession),
 }
 
 impl Command {
@@ -255,6 +632,9 @@ impl Command {
             Self::Update(command) => command.parse(),
             Self::Experimental(command) => command.parse(),
             Self::Miner(command) => command.parse(),
+            Self::Account(command) => command.parse(),
+            Self::InstallService(command) => command.parse(),
+            Self::Replay(command) => command.parse(),
         }
     }
 }
@@ -262,12 +642,15 @@ impl Command {
 pub enum LogWriter {
     Stdout(io::Stdout),
     Sender(mpsc::Sender<Vec<u8>>),
+    File(Arc<Mutex<File>>),
 }
 
 impl LogWriter {
-    pub fn new(log_sender: &Option<mpsc::Sender<Vec<u8>>>) -> Self {
+    pub fn new(log_sender: &Option<mpsc::Sender<Vec<u8>>>, log_file: &Option<Arc<Mutex<File>>>) -> Self {
         if let Some(sender) = log_sender {
             Self::Sender(sender.clone())
+        } else if let Some(file) = log_file {
+            Self::File(file.clone())
         } else {
             Self::Stdout(io::stdout())
         }
@@ -283,6 +666,10 @@ impl io::Write for LogWriter {
                 let _ = sender.try_send(log);
                 Ok(buf.len())
             }
+            Self::File(file) => match file.lock() {
+                Ok(mut file) => file.write(buf),
+                Err(_) => Ok(buf.len()),
+            },
         }
     }
 
@@ -371,6 +758,89 @@ impl Update {
     }
 }
 
+#[derive(Debug, Parser)]
+pub struct ReplaySession {
+    /// Specify the recorded session file to replay.
+    #[clap(parse(from_os_str))]
+    session: PathBuf,
+    /// Specify the IP address and port of the node to replay the session against.
+    #[clap(default_value = "127.0.0.1:4132", long = "node")]
+    node: SocketAddr,
+    /// If the flag is set, the messages are replayed at the times they were recorded, instead of back-to-back.
+    #[clap(long = "realtime")]
+    realtime: bool,
+}
+
+impl ReplaySession {
+    pub fn parse(self) -> Result<String> {
+        let frames = read_session(&self.session)?;
+
+        // Listen on a local port, for the node to verify the listener port of the replayed challenge request.
+        let listener = TcpListener::bind(("0.0.0.0", 0))?;
+        let listener_port = listener.local_addr()?.port();
+        std::thread::spawn(move || listener.incoming().for_each(drop));
+
+        let mut stream = TcpStream::connect(self.node)?;
+        // Drain the messages of the node, until it disconnects or falls silent.
+        let mut reader = stream.try_clone()?;
+        reader.set_read_timeout(Some(Duration::from_secs(Client::<CurrentNetwork>::RADIO_SILENCE_IN_SECS)))?;
+        let drain = std::thread::spawn(move || io::copy(&mut reader, &mut io::sink()));
+
+        let started_at = Instant::now();
+        let number_of_frames = frames.len();
+        for (index, (elapsed, frame)) in frames.into_iter().enumerate() {
+            if self.realtime {
+                if let Some(delay) = elapsed.checked_sub(started_at.elapsed()) {
+                    std::thread::sleep(delay);
+                }
+            }
+            // The first frame of a session is the challenge request of the peer.
+            let frame = match index {
+                0 => Self::rewrite_listener_port(&frame, listener_port)?,
+                _ => frame,
+            };
+            stream.write_all(&frame)?;
+        }
+        stream.shutdown(Shutdown::Write)?;
+        let _ = drain.join();
+
+        Ok(format!(
+            "Replayed {} messages of {} against {}",
+            number_of_frames,
+            self.session.display(),
+            self.node
+        ))
+    }
+
+    /// Returns the given challenge request frame, with its listener port replaced by the given port.
+    fn rewrite_listener_port(frame: &[u8], listener_port: u16) -> Result<Vec<u8>> {
+        let payload = frame.get(4..).ok_or_else(|| anyhow!("The session does not start with a complete message"))?;
+        let message = match Message::<CurrentNetwork, Client<CurrentNetwork>>::deserialize(&mut io::Cursor::new(payload))? {
+            Message::ChallengeRequest(version, fork_depth, node_type, status, _, nonce, cumulative_weight, serving_mode, timestamp) => {
+                Message::<CurrentNetwork, Client<CurrentNetwork>>::ChallengeRequest(
+                    version,
+                    fork_depth,
+                    node_type,
+                    status,
+                    listener_port,
+                    nonce,
+                    cumulative_weight,
+                    serving_mode,
+                    timestamp,
+                )
+            }
+            message => return Err(anyhow!("The session starts with a {} message, not a challenge request", message.name())),
+        };
+
+        // Re-encode the message as a length-prefixed frame.
+        let mut frame = 0u32.to_le_bytes().to_vec();
+        message.serialize_into(&mut frame)?;
+        let length = (frame.len() - 4) as u32;
+        frame[..4].copy_from_slice(&length.to_le_bytes());
+        Ok(frame)
+    }
+}
+
 #[derive(Debug, Parser)]
 pub struct Experimental {
     #[clap(subcommand)]
@@ -412,6 +882,183 @@ impl NewAccount {
     }
 }
 
+#[derive(Debug, Parser)]
+pub struct AccountSubcommand {
+    #[clap(subcommand)]
+    commands: AccountCommands,
+}
+
+impl AccountSubcommand {
+    pub fn parse(self) -> Result<String> {
+        match self.commands {
+            AccountCommands::Vanity(command) => command.parse(),
+            AccountCommands::Vectors(command) => command.parse(),
+            AccountCommands::SignPayoutAddress(command) => command.parse(),
+            AccountCommands::SignPayoutSchedule(command) => command.parse(),
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub enum AccountCommands {
+    #[clap(name = "vanity", about = "Generate a new Aleo account with an address of the given prefix.")]
+    Vanity(Vanity),
+    #[clap(name = "vectors", about = "Generate deterministic account test vectors from the given seed.")]
+    Vectors(Vectors),
+    #[clap(name = "sign-payout-address", about = "Sign an update of the payout address of a prover.")]
+    SignPayoutAddress(SignPayoutAddress),
+    #[clap(name = "sign-payout-schedule", about = "Sign an update of the payout schedule of a prover.")]
+    SignPayoutSchedule(SignPayoutSchedule),
+}
+
+#[derive(Debug, Parser)]
+pub struct Vanity {
+    /// Specify the desired address prefix (e.g. aleo1pool).
+    #[clap(long = "prefix")]
+    prefix: String,
+    /// Specify the number of threads to search with (defaults to the number of CPUs).
+    #[clap(long = "threads")]
+    threads: Option<usize>,
+}
+
+impl Vanity {
+    /// The human-readable prefix shared by all Aleo addresses.
+    const ADDRESS_PREFIX: &'static str = "aleo1";
+    /// The bech32 character set, from which all address characters are drawn.
+    const BECH32_CHARSET: &'static str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+    pub fn parse(self) -> Result<String> {
+        // Normalize the prefix, and prepend the address prefix if it is missing.
+        let prefix = self.prefix.to_lowercase();
+        let prefix = match prefix.starts_with(Self::ADDRESS_PREFIX) {
+            true => prefix,
+            false => format!("{}{}", Self::ADDRESS_PREFIX, prefix),
+        };
+
+        // Ensure the vanity characters are all valid bech32 characters.
+        let vanity = &prefix[Self::ADDRESS_PREFIX.len()..];
+        if let Some(character) = vanity.chars().find(|c| !Self::BECH32_CHARSET.contains(*c)) {
+            return Err(anyhow!("The character '{}' can never appear in an Aleo address", character));
+        }
+
+        // Compute the expected number of attempts required to find a match.
+        let expected_attempts = 32f64.powi(vanity.len() as i32);
+        let num_threads = self.threads.unwrap_or_else(num_cpus::get).max(1);
+
+        eprintln!(
+            "\nSearching for an address with prefix '{}' using {} threads (~{} attempts expected)...\n",
+            prefix, num_threads, expected_attempts as u64
+        );
+
+        let found = Arc::new(AtomicBool::new(false));
+        let attempts = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        // Spawn the search threads.
+        for _ in 0..num_threads {
+            let (prefix, found, attempts, sender) = (prefix.clone(), found.clone(), attempts.clone(), sender.clone());
+            std::thread::spawn(move || {
+                let rng = &mut rand::thread_rng();
+                while !found.load(Ordering::Relaxed) {
+                    let account = Account::<CurrentNetwork>::new(rng);
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    if account.address().to_string().starts_with(&prefix) && !found.swap(true, Ordering::SeqCst) {
+                        let _ = sender.send(account);
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // Report the progress periodically, until a matching account is found.
+        let start = Instant::now();
+        let account = loop {
+            match receiver.recv_timeout(Duration::from_secs(5)) {
+                Ok(account) => break account,
+                Err(RecvTimeoutError::Timeout) => {
+                    let attempts = attempts.load(Ordering::Relaxed);
+                    let rate = attempts as f64 / start.elapsed().as_secs_f64();
+                    let remaining = (expected_attempts - attempts as f64).max(0.0);
+                    eprintln!(
+                        "Tried {} addresses ({:.0}/s), estimated time remaining: {}s",
+                        attempts,
+                        rate,
+                        (remaining / rate.max(1.0)) as u64
+                    );
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(anyhow!("The vanity address search has stopped unexpectedly")),
+            }
+        };
+
+        // Print the new Aleo account.
+        let mut output = format!(
+            "\n Found a matching address after {} attempts in {}s.\n",
+            attempts.load(Ordering::Relaxed),
+            start.elapsed().as_secs()
+        );
+        output += &format!(
+            "\n {:>12}\n",
+            "Attention - Remember to store this account private key and view key.".red().bold()
+        );
+        output += &format!("\n {:>12}  {}\n", "Private Key".cyan().bold(), account.private_key());
+        output += &format!(" {:>12}  {}\n", "View Key".cyan().bold(), account.view_key());
+        output += &format!(" {:>12}  {}\n", "Address".cyan().bold(), account.address());
+
+        Ok(output)
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct Vectors {
+    /// Specify the seed of the ChaCha RNG used to derive the accounts.
+    #[clap(long = "seed")]
+    seed: u64,
+    /// Specify the number of accounts to derive.
+    #[clap(default_value = "1", long = "count")]
+    count: usize,
+}
+
+impl Vectors {
+    /// The messages signed by each account in the test vectors.
+    const MESSAGES: [&'static str; 2] = ["", "snarkOS"];
+
+    pub fn parse(self) -> Result<String> {
+        let rng = &mut ChaChaRng::seed_from_u64(self.seed);
+
+        let mut accounts = Vec::with_capacity(self.count);
+        for _ in 0..self.count {
+            let account = Account::<CurrentNetwork>::new(rng);
+
+            // Sign each of the sample messages, and ensure the signature verifies.
+            let mut signatures = Vec::with_capacity(Self::MESSAGES.len());
+            for message in Self::MESSAGES {
+                let message_bits = message.as_bytes().to_bits_le();
+                let signature = account.private_key().sign(&message_bits, rng)?;
+                if !account.address().verify_signature(&message_bits, &signature)? {
+                    return Err(anyhow!("Failed to verify the signature of '{}'", message));
+                }
+                signatures.push(serde_json::json!({ "message": message, "signature": signature.to_string() }));
+            }
+
+            accounts.push(serde_json::json!({
+                "private_key": account.private_key().to_string(),
+                "view_key": account.view_key().to_string(),
+                "address": account.address().to_string(),
+                "signatures": signatures,
+            }));
+        }
+
+        let vectors = serde_json::json!({
+            "network": CurrentNetwork::NETWORK_NAME,
+            "rng": "ChaChaRng",
+            "seed": self.seed,
+            "accounts": accounts,
+        });
+
+        Ok(serde_json::to_string_pretty(&vectors)?)
+    }
+}
+
 #[derive(Debug, Parser)]
 pub struct MinerSubcommand {
     #[clap(subcommand)]
@@ -490,20 +1137,175 @@ impl MinerStats {
     }
 }
 
+/// Returns the notifier used to request a clean shutdown of the node, e.g. by a service manager.
+pub fn shutdown_notifier() -> &'static Notify {
+    static NOTIFIER: OnceCell<Notify> = OnceCell::new();
+    NOTIFIER.get_or_init(Notify::new)
+}
+
+/// Returns the hook called with the exit code of the node once it has shut down, before the process exits,
+/// e.g. to report the exit to a service manager.
+pub fn exit_hook() -> &'static OnceCell<fn(i32)> {
+    static HOOK: OnceCell<fn(i32)> = OnceCell::new();
+    &HOOK
+}
+
 // This function is responsible for handling OS signals in order
 // for the node to be able to intercept them and perform a clean shutdown.
-// Note: Only Ctrl-C is supported; it should work on both Unix-family systems and Windows.
+// Note: Ctrl-C is supported on both Unix-family systems and Windows; SIGTERM (e.g. `docker stop`) on Unix-family systems.
+// A shutdown may also be requested through the `shutdown_notifier`, or of a given kind through `E::shutdown()`,
+// such as by the `stop` and `restart` RPC methods; the node then calls the `exit_hook` and exits with the code of that kind.
 pub fn handle_signals<N: Network, E: Environment>(server: Server<N, E>) {
     E::resources().register_task(
         None, // No need to provide an id, as the task will run indefinitely.
         tokio::task::spawn(async move {
-            match tokio::signal::ctrl_c().await {
-                Ok(()) => {
-                    server.shut_down().await;
-                    std::process::exit(0);
+            // As PID 1 in a container, the node receives no default signal handling, so SIGTERM must be handled explicitly.
+            #[cfg(unix)]
+            let terminate = async {
+                match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                    Ok(mut signal) => {
+                        signal.recv().await;
+                    }
+                    Err(error) => {
+                        error!("Failed to listen for SIGTERM: {}", error);
+                        std::future::pending::<()>().await
+                    }
                 }
-                Err(error) => error!("tokio::signal::ctrl_c encountered an error: {}", error),
+            };
+            #[cfg(not(unix))]
+            let terminate = std::future::pending::<()>();
+
+            let kind = tokio::select! {
+                result = tokio::signal::ctrl_c() => {
+                    if let Err(error) = result {
+                        error!("tokio::signal::ctrl_c encountered an error: {}", error);
+                        return;
+                    }
+                    ShutdownKind::Stop
+                }
+                _ = terminate => ShutdownKind::Stop,
+                _ = shutdown_notifier().notified() => ShutdownKind::Stop,
+                kind = E::shutdown().requested() => kind,
+            };
+            server.shut_down().await;
+            if let Some(hook) = exit_hook().get() {
+                hook(kind.exit_code());
             }
+            std::process::exit(kind.exit_code());
         }),
     );
 }
+
+#[derive(Debug, Parser)]
+pub struct SignPayoutAddress {
+    /// Specify the private key of the prover.
+    #[clap(long = "private-key", env = "SNARKOS_PRIVATE_KEY", hide_env_values = true)]
+    private_key: SecretString,
+    /// Specify the address to receive the payouts of the prover.
+    #[clap(long = "payout-address")]
+    payout_address: String,
+    /// Specify the block height of the first round paid to the payout address.
+    #[clap(long = "effective-from")]
+    effective_from: u32,
+}
+
+impl SignPayoutAddress {
+    pub fn parse(self) -> Result<String> {
+        let private_key = PrivateKey::<CurrentNetwork>::from_str(self.private_key.expose_secret())?;
+        let prover = Address::from_private_key(&private_key);
+        let payout_address = Address::<CurrentNetwork>::from_str(&self.payout_address)?;
+
+        // Sign the update, as verified by the operator.
+        let message = payout_address_message(&prover, &payout_address, self.effective_from);
+        let signature = private_key.sign(&message.as_bytes().to_bits_le(), &mut rand::thread_rng())?;
+
+        // Print the parameters of the `updatepayoutaddress` request.
+        let params = serde_json::json!([
+            prover.to_string(),
+            payout_address.to_string(),
+            self.effective_from,
+            signature.to_string()
+        ]);
+        Ok(serde_json::to_string_pretty(&params)?)
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct SignPayoutSchedule {
+    /// Specify the private key of the prover.
+    #[clap(long = "private-key", env = "SNARKOS_PRIVATE_KEY", hide_env_values = true)]
+    private_key: SecretString,
+    /// Specify the minimum balance paid out to the prover, in gates.
+    #[clap(long = "min-payout")]
+    min_payout: u64,
+    /// Specify the number of blocks between two payouts to the prover.
+    #[clap(long = "payout-interval")]
+    payout_interval: u32,
+    /// Specify the block height from which the prover is paid on the payout schedule.
+    #[clap(long = "effective-from")]
+    effective_from: u32,
+}
+
+impl SignPayoutSchedule {
+    pub fn parse(self) -> Result<String> {
+        let private_key = PrivateKey::<CurrentNetwork>::from_str(self.private_key.expose_secret())?;
+        let prover = Address::from_private_key(&private_key);
+        let schedule = PayoutSchedule {
+            minimum_payout: self.min_payout,
+            payout_interval: self.payout_interval,
+        };
+
+        // Sign the update, as verified by the operator.
+        let message = payout_schedule_message(&prover, &schedule, self.effective_from);
+        let signature = private_key.sign(&message.as_bytes().to_bits_le(), &mut rand::thread_rng())?;
+
+        // Print the parameters of the `updatepayoutschedule` request.
+        let params = serde_json::json!([
+            prover.to_string(),
+            self.min_payout,
+            self.payout_interval,
+            self.effective_from,
+            signature.to_string()
+        ]);
+        Ok(serde_json::to_string_pretty(&params)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use clap::CommandFactory;
+    use std::collections::BTreeSet;
+
+    /// Inserts the long names of the options of the given command and its subcommands which end with `-key`.
+    fn key_options(command: &clap::Command, options: &mut BTreeSet<String>) {
+        for argument in command.get_arguments() {
+            if let Some(long) = argument.get_long().filter(|long| long.ends_with("-key")) {
+                options.insert(long.to_string());
+            }
+        }
+        for subcommand in command.get_subcommands() {
+            key_options(subcommand, options);
+        }
+    }
+
+    #[test]
+    fn test_private_key_options_are_secret() {
+        // The options taking a private key are held as a `SecretString`, so they are redacted and zeroized.
+        let _ = |node: &Node, address: &SignPayoutAddress, schedule: &SignPayoutSchedule| -> [Option<&SecretString>; 4] {
+            [
+                node.block_tag_key.as_ref(),
+                node.pool_http_key.as_ref(),
+                Some(&address.private_key),
+                Some(&schedule.private_key),
+            ]
+        };
+
+        // Check every option ending in `-key` is either one of the private keys above, or the path of a TLS key.
+        let mut options = BTreeSet::new();
+        key_options(&Node::command(), &mut options);
+        let expected = ["block-tag-key", "pool-http-key", "private-key", "pool-tls-key", "rpc-tls-key"];
+        assert_eq!(options, expected.iter().map(|option| option.to_string()).collect());
+    }
+}
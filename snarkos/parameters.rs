@@ -0,0 +1,335 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_environment::helpers::NodeType;
+use snarkvm::dpc::Network;
+
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use reqwest::{blocking::Client, header::RANGE, StatusCode};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The maximum duration to establish a connection to a mirror.
+const MIRROR_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// The minimum transfer rate of a download, in bytes per second, below which the download times out.
+const MINIMUM_DOWNLOAD_RATE: u64 = 256 * 1024;
+
+/// A named loader for a set of SNARK parameters.
+type ParameterLoader = (&'static str, fn());
+
+///
+/// Loads the SNARK parameters required by the given node type in parallel, logging the progress.
+/// Otherwise, the parameters are loaded lazily upon first use.
+///
+pub fn preload_parameters<N: Network>(node_type: NodeType) {
+    let mut loaders: Vec<ParameterLoader> = vec![
+        ("inner verifying key", || {
+            let _ = N::inner_verifying_key();
+        }),
+        ("PoSW verifying key", || {
+            let _ = N::posw_verifying_key();
+        }),
+    ];
+
+    // Only the node types that produce PoSW proofs require the PoSW proving key, which an operator never does,
+    // as it only verifies the shares of its provers. A node built without the prover never produces them.
+    #[cfg(feature = "prover")]
+    if matches!(node_type, NodeType::Miner | NodeType::Prover) {
+        loaders.push(("PoSW proving key", || {
+            let _ = N::posw_proving_key();
+        }));
+    }
+
+    // Only the node types that produce coinbase transactions require the inner proving key.
+    if matches!(node_type, NodeType::Miner | NodeType::Operator) {
+        loaders.push(("inner proving key", || {
+            let _ = N::inner_proving_key();
+        }));
+    }
+
+    let start = Instant::now();
+    let number_of_loaders = loaders.len();
+    let number_of_loaded = AtomicUsize::new(0);
+    info!("Loading {} sets of parameters", number_of_loaders);
+
+    loaders.par_iter().for_each(|(name, load)| {
+        let timer = Instant::now();
+        load();
+        let number_of_loaded = number_of_loaded.fetch_add(1, Ordering::SeqCst) + 1;
+        info!("Loaded the {} in {:.2?} ({}/{})", name, timer.elapsed(), number_of_loaded, number_of_loaders);
+    });
+
+    info!("Loaded all parameters in {:.2?}", start.elapsed());
+}
+
+/// A parameter file, as listed in the parameters manifest.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ParameterFile {
+    /// The file name of the parameters.
+    pub name: String,
+    /// The SHA-256 checksum of the parameters, in hex.
+    pub checksum: String,
+    /// The size of the parameters in bytes.
+    pub size: u64,
+}
+
+/// A list of parameter files, and the mirrors they may be downloaded from.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ParametersManifest {
+    /// The base URLs of the mirrors, in order of preference.
+    pub mirrors: Vec<String>,
+    /// The parameter files.
+    pub files: Vec<ParameterFile>,
+}
+
+impl ParameterFile {
+    /// Ensures the file name is a plain file name, which cannot escape the parameters directory,
+    /// and the checksum is a SHA-256 checksum in hex.
+    pub fn validate(&self) -> Result<()> {
+        let mut components = Path::new(&self.name).components();
+        let is_plain_file_name = matches!((components.next(), components.next()), (Some(Component::Normal(_)), None));
+        if !is_plain_file_name || self.name.contains(&['/', '\\'][..]) {
+            return Err(anyhow!("Parameter file '{}' must be a plain file name", self.name));
+        }
+        if self.checksum.len() != 64 || !self.checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Parameter file '{}' has an invalid SHA-256 checksum '{}'", self.name, self.checksum));
+        }
+        Ok(())
+    }
+}
+
+impl ParametersManifest {
+    /// Reads the manifest at the given path, and ensures each of its files is valid.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let manifest = fs::read_to_string(path.as_ref())
+            .map_err(|error| anyhow!("Failed to read the parameters manifest at {}: {}", path.as_ref().display(), error))?;
+        let manifest: Self = serde_json::from_str(&manifest)?;
+        for file in &manifest.files {
+            file.validate()?;
+        }
+        Ok(manifest)
+    }
+}
+
+///
+/// Verifies the parameter files in the given manifest, downloading any missing or corrupted files if `repair` is set.
+///
+pub fn check_parameters<P: AsRef<Path>>(manifest_path: P, repair: bool) -> Result<()> {
+    let manifest = ParametersManifest::open(manifest_path)?;
+    let directory = parameters_directory();
+
+    for file in &manifest.files {
+        match verify_parameter_file(&directory, file) {
+            Ok(()) => debug!("Verified the parameter file '{}'", file.name),
+            Err(error) if repair => {
+                warn!("{}, downloading it again", error);
+                download_parameter_file(&directory, file, &manifest.mirrors)?;
+            }
+            Err(error) => return Err(anyhow!("{}. Restart the node with '--repair-params' to download it again", error)),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the directory of the parameter files.
+fn parameters_directory() -> PathBuf {
+    aleo_std::aleo_dir().join("resources")
+}
+
+/// Ensures the given parameter file exists in the directory, and matches the expected size and checksum.
+fn verify_parameter_file(directory: &Path, file: &ParameterFile) -> Result<()> {
+    let path = directory.join(&file.name);
+    let size = match fs::metadata(&path) {
+        Ok(metadata) => metadata.len(),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Err(anyhow!("Parameter file '{}' is missing", file.name)),
+        Err(error) => return Err(error.into()),
+    };
+    if size != file.size {
+        return Err(anyhow!("Parameter file '{}' has {} bytes, expected {}", file.name, size, file.size));
+    }
+    if !sha256_checksum(&path)?.eq_ignore_ascii_case(&file.checksum) {
+        return Err(anyhow!("Parameter file '{}' is corrupted (checksum mismatch)", file.name));
+    }
+    Ok(())
+}
+
+/// Returns the SHA-256 checksum of the file at the given path, in hex.
+fn sha256_checksum(path: &Path) -> Result<String> {
+    let mut reader = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Downloads the given parameter file into the directory, trying each mirror in turn.
+fn download_parameter_file(directory: &Path, file: &ParameterFile, mirrors: &[String]) -> Result<()> {
+    fs::create_dir_all(directory)?;
+    let path = directory.join(&file.name);
+    let partial_path = directory.join(format!("{}.partial", file.name));
+
+    for mirror in mirrors {
+        if let Err(error) = download_from_mirror(mirror, file, &partial_path) {
+            warn!("Failed to download '{}' from {}: {}", file.name, mirror, error);
+            continue;
+        }
+        // Ensure the downloaded file is intact before replacing the existing one.
+        match sha256_checksum(&partial_path)?.eq_ignore_ascii_case(&file.checksum) {
+            true => {
+                fs::rename(&partial_path, &path)?;
+                info!("Downloaded the parameter file '{}'", file.name);
+                return Ok(());
+            }
+            false => {
+                warn!("Downloaded '{}' from {} with a checksum mismatch", file.name, mirror);
+                fs::remove_file(&partial_path)?;
+            }
+        }
+    }
+    Err(anyhow!("Failed to download the parameter file '{}' from any mirror", file.name))
+}
+
+/// Downloads the given parameter file from the mirror, resuming from any partially downloaded file.
+fn download_from_mirror(mirror: &str, file: &ParameterFile, partial_path: &Path) -> Result<()> {
+    let mut offset = fs::metadata(partial_path).map(|metadata| metadata.len()).unwrap_or(0);
+    if offset > file.size {
+        fs::remove_file(partial_path)?;
+        offset = 0;
+    }
+    if offset == file.size {
+        return Ok(());
+    }
+
+    let url = format!("{}/{}", mirror.trim_end_matches('/'), file.name);
+    info!("Downloading '{}' from {} ({} of {} bytes present)", file.name, url, offset, file.size);
+
+    // Parameter files are large, so the transfer is given time in proportion to the remaining bytes.
+    // A download that times out is resumed from the partially downloaded file.
+    let client = Client::builder().connect_timeout(MIRROR_CONNECT_TIMEOUT).build()?;
+    let mut response = client
+        .get(&url)
+        .header(RANGE, format!("bytes={}-", offset))
+        .timeout(download_timeout(file.size - offset))
+        .send()?
+        .error_for_status()?;
+
+    // Resume the transfer if the mirror honored the range request, and restart it otherwise.
+    let (mut output, remaining_bytes) = match response.status() == StatusCode::PARTIAL_CONTENT {
+        true => (OpenOptions::new().append(true).open(partial_path)?, file.size - offset),
+        false => (File::create(partial_path)?, file.size),
+    };
+    if let Err(error) = copy_bounded(&mut response, &mut output, remaining_bytes) {
+        fs::remove_file(partial_path)?;
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Copies at most `limit` bytes from the reader into the writer, failing if the reader holds any further bytes.
+fn copy_bounded<R: Read, W: Write>(reader: &mut R, writer: &mut W, limit: u64) -> Result<u64> {
+    let bytes_copied = io::copy(&mut reader.by_ref().take(limit), writer)?;
+    if reader.read(&mut [0u8; 1])? > 0 {
+        return Err(anyhow!("The mirror sent more than the expected {} bytes", limit));
+    }
+    Ok(bytes_copied)
+}
+
+/// Returns the timeout of a download of the given number of bytes.
+fn download_timeout(bytes: u64) -> Duration {
+    MIRROR_CONNECT_TIMEOUT + Duration::from_secs(bytes / MINIMUM_DOWNLOAD_RATE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter_file(name: &str, checksum: &str, size: u64) -> ParameterFile {
+        ParameterFile {
+            name: name.to_string(),
+            checksum: checksum.to_string(),
+            size,
+        }
+    }
+
+    #[test]
+    fn test_parameter_file_names() {
+        let checksum = "ab".repeat(32);
+        assert!(parameter_file("posw.proving.1b6bf7a", &checksum, 0).validate().is_ok());
+
+        // Check the file names which could escape the parameters directory are rejected.
+        for name in ["", ".", "..", "../posw.proving", "resources/posw.proving", "/etc/passwd", "..\\posw.proving"] {
+            assert!(parameter_file(name, &checksum, 0).validate().is_err(), "{}", name);
+        }
+
+        // Check the checksums which are not SHA-256 checksums in hex are rejected.
+        assert!(parameter_file("posw.proving", "ab", 0).validate().is_err());
+        assert!(parameter_file("posw.proving", &"zz".repeat(32), 0).validate().is_err());
+    }
+
+    #[test]
+    fn test_verify_parameter_file() {
+        let directory = std::env::temp_dir().join(format!("snarkos-test-parameters-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let contents = b"parameters";
+        fs::write(directory.join("posw.proving"), contents).unwrap();
+        let checksum = format!("{:x}", Sha256::digest(contents));
+
+        // Check the file is verified against its size and checksum.
+        assert!(verify_parameter_file(&directory, &parameter_file("posw.proving", &checksum, 10)).is_ok());
+        assert!(verify_parameter_file(&directory, &parameter_file("posw.proving", &checksum.to_uppercase(), 10)).is_ok());
+        assert!(verify_parameter_file(&directory, &parameter_file("posw.proving", &checksum, 11)).is_err());
+        assert!(verify_parameter_file(&directory, &parameter_file("posw.proving", &"00".repeat(32), 10)).is_err());
+        assert!(verify_parameter_file(&directory, &parameter_file("inner.proving", &checksum, 10)).is_err());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_copy_bounded() {
+        let mut output = Vec::new();
+        assert_eq!(copy_bounded(&mut &b"parameters"[..], &mut output, 10).unwrap(), 10);
+        assert_eq!(output, b"parameters");
+
+        // Check a truncated transfer is left for the checksum to reject, or to be resumed.
+        let mut output = Vec::new();
+        assert_eq!(copy_bounded(&mut &b"param"[..], &mut output, 10).unwrap(), 5);
+
+        // Check a transfer beyond the expected size is rejected.
+        let mut output = Vec::new();
+        assert!(copy_bounded(&mut &b"parameters and more"[..], &mut output, 10).is_err());
+        assert_eq!(output.len(), 10);
+    }
+
+    #[test]
+    fn test_download_timeout() {
+        assert_eq!(download_timeout(0), MIRROR_CONNECT_TIMEOUT);
+        assert_eq!(download_timeout(MINIMUM_DOWNLOAD_RATE * 60), MIRROR_CONNECT_TIMEOUT + Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::Node;
+use snarkos_environment::{helpers::NodeType, Environment};
+use snarkvm::dpc::{Address, Network, PrivateKey};
+
+use anyhow::{anyhow, Result};
+use colored::*;
+use std::{
+    fmt,
+    fs,
+    net::SocketAddr,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The free disk space below which the node refuses to start, as the ledger would soon fail to write.
+const MINIMUM_FREE_DISK_SPACE: u64 = 1024 * 1024 * 1024;
+/// The free disk space below which the node warns that it will soon run out of space.
+const RECOMMENDED_FREE_DISK_SPACE: u64 = 32 * 1024 * 1024 * 1024;
+/// The duration by which the last write to the ledger may be ahead of the system clock, before the clock is considered skewed.
+const MAXIMUM_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+/// The default RPC credentials, which are as good as no credentials on a public bind.
+const DEFAULT_RPC_CREDENTIALS: (&str, &str) = ("root", "pass");
+
+/// The severity of a finding of the preflight checks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The configuration is risky, but the node can start.
+    Warning,
+    /// The configuration is incoherent, and the node cannot start.
+    Error,
+}
+
+/// A misconfiguration found by the preflight checks, with a message on how to resolve it.
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Finding {
+    fn warning(message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+
+    fn error(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "{} {}", "WARNING".yellow().bold(), self.message),
+            Severity::Error => write!(f, "{} {}", "ERROR".red().bold(), self.message),
+        }
+    }
+}
+
+///
+/// Checks the configuration of the given node for coherence and the host for its prerequisites,
+/// printing each finding, and returns an error if the node cannot start as configured.
+///
+pub fn run_preflight_checks<N: Network, E: Environment>(node: &Node) -> Result<()> {
+    let findings = preflight_checks::<N, E>(node);
+    for finding in &findings {
+        println!("{}", finding);
+    }
+
+    let number_of_errors = findings.iter().filter(|finding| finding.severity == Severity::Error).count();
+    match number_of_errors {
+        0 => Ok(()),
+        _ => Err(anyhow!(
+            "The node failed {} preflight check(s), resolve the errors above and restart it",
+            number_of_errors
+        )),
+    }
+}
+
+/// Returns the findings of the preflight checks for the given node.
+pub fn preflight_checks<N: Network, E: Environment>(node: &Node) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check_address::<N, E>(node, &mut findings);
+    check_pool::<N, E>(node, &mut findings);
+    check_rpc(node, &mut findings);
+    check_clock::<N>(node, &mut findings);
+    check_disk_space(node, &mut findings);
+    findings
+}
+
+/// Ensures the address of a node type that requires one is well-formed.
+fn check_address<N: Network, E: Environment>(node: &Node, findings: &mut Vec<Finding>) {
+    let (flag, address) = match E::NODE_TYPE {
+        NodeType::Miner => ("--miner", &node.miner),
+        NodeType::Operator => ("--operator", &node.operator),
+        NodeType::Prover => ("--prover", &node.prover),
+        _ => return,
+    };
+    match address.as_deref().map(str::trim) {
+        None | Some("") => findings.push(Finding::error(format!(
+            "Running {} requires an Aleo address, specify it with '{} <address>'",
+            E::NODE_TYPE.description(),
+            flag
+        ))),
+        Some(address) => {
+            if Address::<N>::from_str(address).is_err() {
+                findings.push(Finding::error(format!(
+                    "'{}' is not a valid Aleo address, generate one with 'snarkos experimental new_account'",
+                    address
+                )));
+            }
+        }
+    }
+}
+
+/// Ensures the private key given with the given flag is well-formed, and is the private key of the given address, if any.
+fn check_private_key<N: Network>(flag: &str, private_key: &str, address_flag: &str, address: &Option<String>, findings: &mut Vec<Finding>) {
+    let private_key = match PrivateKey::<N>::from_str(private_key.trim()) {
+        Ok(private_key) => private_key,
+        Err(_) => return findings.push(Finding::error(format!("'{}' is not a valid private key", flag))),
+    };
+    // A malformed address is reported by the address check.
+    if let Some(Ok(address)) = address.as_deref().map(|address| Address::<N>::from_str(address.trim())) {
+        if address != Address::from_private_key(&private_key) {
+            findings.push(Finding::error(format!(
+                "'{}' is not the private key of the address given with '{}'",
+                flag, address_flag
+            )));
+        }
+    }
+}
+
+/// Ensures a prover has a pool to contribute to, and the pool options are given to the node types that use them.
+fn check_pool<N: Network, E: Environment>(node: &Node, findings: &mut Vec<Finding>) {
+    match E::NODE_TYPE {
+        NodeType::Prover => {
+            if node.pool.is_none() {
+                findings.push(Finding::error(match node.pool_http {
+                    Some(_) => "A proving node requires '--pool <ip:port>', as '--pool-http' is only a fallback for it".to_string(),
+                    None => "A proving node requires a pool to contribute to, specify it with '--pool <ip:port>'".to_string(),
+                }));
+            }
+            // The job requests over HTTP are signed, so the pool only assigns jobs to the owner of the prover address.
+            if node.pool_http.is_some() {
+                match &node.pool_http_key {
+                    Some(private_key) => {
+                        check_private_key::<N>("--pool-http-key", private_key.expose_secret(), "--prover", &node.prover, findings)
+                    }
+                    None => findings.push(Finding::error(
+                        "'--pool-http' requires the private key of the prover address, specify it with '--pool-http-key'".to_string(),
+                    )),
+                }
+            }
+        }
+        NodeType::Operator => {
+            if node.pool_tls_listener.is_some() && (node.pool_tls_cert.is_none() || node.pool_tls_key.is_none()) {
+                findings.push(Finding::error(
+                    "The TLS listener requires a certificate and a private key, specify them with '--pool-tls-cert' and '--pool-tls-key'"
+                        .to_string(),
+                ));
+            }
+            // The block tag is signed, so it is only attributed to the operator receiving the coinbase.
+            if node.block_tag.is_some() {
+                match &node.block_tag_key {
+                    Some(private_key) => {
+                        check_private_key::<N>("--block-tag-key", private_key.expose_secret(), "--operator", &node.operator, findings)
+                    }
+                    None => findings.push(Finding::error(
+                        "'--block-tag' requires the private key of the operator address, specify it with '--block-tag-key'".to_string(),
+                    )),
+                }
+            }
+        }
+        _ => (),
+    }
+
+    if E::NODE_TYPE != NodeType::Prover && (node.pool.is_some() || node.pool_http.is_some()) {
+        findings.push(Finding::warning(format!(
+            "'--pool' and '--pool-http' only apply to a proving node, and are ignored by {}",
+            E::NODE_TYPE.description()
+        )));
+    }
+    if E::NODE_TYPE != NodeType::Operator && (node.pool_tls_listener.is_some() || node.sub_pools.is_some()) {
+        findings.push(Finding::warning(format!(
+            "'--pool-tls-listener' and '--sub-pools' only apply to an operating node, and are ignored by {}",
+            E::NODE_TYPE.description()
+        )));
+    }
+}
+
+/// Ensures TLS for the RPC server is fully configured, and warns if it is reachable beyond this host with the default credentials.
+fn check_rpc(node: &Node, findings: &mut Vec<Finding>) {
+    if node.norpc {
+        return;
+    }
+    if node.rpc_tls_cert.is_some() != node.rpc_tls_key.is_some() {
+        findings.push(Finding::error(
+            "TLS for the RPC server requires a certificate and a private key, specify them with '--rpc-tls-cert' and '--rpc-tls-key'"
+                .to_string(),
+        ));
+    }
+
+    let has_default_credentials = (node.rpc_username.as_str(), node.rpc_password.expose_secret()) == DEFAULT_RPC_CREDENTIALS;
+    if !has_default_credentials {
+        return;
+    }
+
+    let is_public = |ip: &SocketAddr| !ip.ip().is_loopback();
+    for rpc_ip in std::iter::once(&node.rpc).chain(node.rpc_ws.as_ref()).filter(|ip| is_public(ip)) {
+        findings.push(Finding::warning(format!(
+            "The RPC server at {} is reachable beyond this host with the default credentials, \
+             set '--username' and '--password', or bind it to 127.0.0.1",
+            rpc_ip
+        )));
+    }
+}
+
+/// Ensures the system clock is not behind the genesis block or the last write to the ledger, which skews the block timestamps.
+fn check_clock<N: Network>(node: &Node, findings: &mut Vec<Finding>) {
+    let now = SystemTime::now();
+    let unix_now = now.duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs() as i64).unwrap_or_default();
+    if unix_now < N::genesis_block().timestamp() {
+        findings.push(Finding::error(
+            "The system clock is behind the genesis block, synchronize it with an NTP server and restart the node".to_string(),
+        ));
+        return;
+    }
+
+    // A ledger written to in the future indicates the system clock has been set back since.
+    let last_write = fs::metadata(node.ledger_storage_path(node.node)).and_then(|metadata| metadata.modified());
+    if let Ok(skew) = last_write.map(|last_write| last_write.duration_since(now).unwrap_or_default()) {
+        if skew > MAXIMUM_CLOCK_SKEW {
+            findings.push(Finding::warning(format!(
+                "The system clock is {} seconds behind the last write to the ledger, synchronize it with an NTP server",
+                skew.as_secs()
+            )));
+        }
+    }
+}
+
+/// Ensures the volume of the data directory has enough free space for the ledger to grow.
+fn check_disk_space(node: &Node, findings: &mut Vec<Finding>) {
+    let ledger_path = node.ledger_storage_path(node.node);
+    // The ledger may not exist yet, so measure the volume of its nearest existing ancestor.
+    let existing_path = match ledger_path.ancestors().find(|path| path.exists()) {
+        Some(path) => path,
+        None => return,
+    };
+
+    match fs2::available_space(existing_path) {
+        Ok(available) if available < MINIMUM_FREE_DISK_SPACE => findings.push(Finding::error(format!(
+            "Only {} MiB of disk space are free at {}, free up space or specify another volume with '--data-dir'",
+            available / (1024 * 1024),
+            existing_path.display()
+        ))),
+        Ok(available) if available < RECOMMENDED_FREE_DISK_SPACE => findings.push(Finding::warning(format!(
+            "Only {} GiB of disk space are free at {}, the ledger may soon run out of space",
+            available / (1024 * 1024 * 1024),
+            existing_path.display()
+        ))),
+        Ok(_) => (),
+        Err(error) => findings.push(Finding::warning(format!(
+            "Failed to measure the free disk space at {}: {}",
+            existing_path.display(),
+            error
+        ))),
+    }
+}
@@ -20,26 +20,58 @@ use snarkos_environment::{
     Environment,
 };
 use snarkos_network::{
+    helpers::{
+        ChainSplitDetector,
+        MemoryPoolPolicy,
+        PayoutPolicy,
+        PeerStream,
+        PeersConfig,
+        PoolTlsAcceptor,
+        PoolTlsConnector,
+        RetentionPolicy,
+        ShareSampling,
+        ShareTimestamps,
+        SubPools,
+    },
     ledger::{Ledger, LedgerReader, LedgerRequest, LedgerRouter},
+    message::ServingMode,
     operator::{Operator, OperatorRouter},
     peers::{Peers, PeersRequest, PeersRouter},
     prover::{Prover, ProverRouter},
 };
-use snarkos_storage::storage::rocksdb::RocksDB;
+use snarkos_storage::{storage::rocksdb::RocksDB, PayoutSchedule};
 use snarkvm::prelude::*;
 
 #[cfg(feature = "rpc")]
-use snarkos_rpc::{initialize_rpc_server, RpcContext};
+use snarkos_rpc::{
+    initialize_rest_server,
+    initialize_rpc_server,
+    initialize_rpc_ws_server,
+    RateLimit,
+    RestPages,
+    RpcAuditLog,
+    RpcAuth,
+    RpcCompression,
+    RpcContext,
+    RpcCors,
+    RpcCredentials,
+    RpcHttpConfig,
+    RpcMethodPolicy,
+    RpcRateLimiter,
+    RpcTlsAcceptor,
+    RpcUsageTracker,
+    REST_API_PREFIX,
+};
 
 #[cfg(any(feature = "test", feature = "prometheus"))]
 use snarkos_metrics as metrics;
 
 #[cfg(feature = "rpc")]
-use tokio::sync::RwLock;
+use snarkos_network::helpers::SharedMemoryPool;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::{net::SocketAddr, sync::Arc, time::Duration};
-use tokio::{net::TcpListener, sync::oneshot, task};
+use tokio::{net::TcpListener, sync::oneshot, task, time::timeout};
 
 ///
 /// A set of operations to initialize the node server for a specific network.
@@ -64,10 +96,13 @@ impl<N: Network, E: Environment> Server<N, E> {
     ///
     #[inline]
     pub async fn initialize(node: &Node, address: Option<Address<N>>, pool_ip: Option<SocketAddr>) -> Result<Self> {
-        // Initialize a new TCP listener at the given IP.
-        let (local_ip, listener) = match TcpListener::bind(node.node).await {
-            Ok(listener) => (listener.local_addr().expect("Failed to fetch the local IP"), listener),
-            Err(error) => panic!("Failed to bind listener: {:?}. Check if another Aleo node is running", error),
+        // Initialize a new TCP listener at the given IP, unless this is an explorer, which never connects to the network.
+        let (local_ip, listener) = match E::NODE_TYPE {
+            NodeType::Explorer => (node.node, None),
+            _ => match TcpListener::bind(node.node).await {
+                Ok(listener) => (listener.local_addr().expect("Failed to fetch the local IP"), Some(listener)),
+                Err(error) => panic!("Failed to bind listener: {:?}. Check if another Aleo node is running", error),
+            },
         };
 
         // Initialize the ledger storage path.
@@ -77,26 +112,116 @@ impl<N: Network, E: Environment> Server<N, E> {
         // Initialize the prover storage path.
         let prover_storage_path = node.prover_storage_path(local_ip);
 
+        // Initialize the configuration of the peers.
+        let peers_config = PeersConfig {
+            allowed_inbound_node_types: node.allow_inbound.iter().copied().collect(),
+            reserved_outbound_slots: node.reserved_sync_slots,
+            peer_rotation_interval: match node.peer_rotation_interval {
+                0 => None,
+                interval => Some(Duration::from_secs(interval)),
+            },
+            pool_tls: match (&node.pool_tls_name, pool_ip) {
+                (Some(server_name), Some(pool_ip)) => Some(PoolTlsConnector::new(
+                    pool_ip,
+                    server_name,
+                    node.pool_tls_ca.as_ref(),
+                    node.pool_tls_identity.as_ref(),
+                )?),
+                _ => None,
+            },
+            serving_mode: match node.headers_only {
+                true => ServingMode::HeadersOnly,
+                false => ServingMode::Full,
+            },
+            session_directory: node.record_sessions.clone(),
+        };
+
+        // Initialize the sub-pools of the operator.
+        let sub_pools = match &node.sub_pools {
+            Some(path) => SubPools::open(path)?,
+            None => SubPools::default(),
+        };
+
+        // Initialize the retention policy of the operator.
+        let retention_in_days = |days: u64| match days {
+            0 => None,
+            days => Some(Duration::from_secs(days * 24 * 60 * 60)),
+        };
+        let retention_policy = RetentionPolicy::new(
+            retention_in_days(node.share_retention_days),
+            retention_in_days(node.round_retention_days),
+            retention_in_days(node.metrics_retention_days),
+        )?;
+
+        // Initialize the sampling policy of share verification under load.
+        let share_sampling = ShareSampling::new(node.share_sampling_rate, node.share_sampling_load)?;
+        // Initialize the replay window of the share timestamps.
+        let share_timestamps = ShareTimestamps::new(node.share_timestamp_tolerance)?;
+
+        // Initialize the payout schedules that provers may choose, and the default payout schedule.
+        let default_payout_schedule = PayoutSchedule {
+            minimum_payout: node.min_payout,
+            payout_interval: node.payout_interval,
+        };
+        let mut payout_policy = match (&node.min_payout_bounds[..], &node.payout_interval_bounds[..]) {
+            ([lowest_payout, highest_payout], [shortest_interval, longest_interval]) => PayoutPolicy::new(
+                default_payout_schedule,
+                *lowest_payout..=*highest_payout,
+                *shortest_interval..=*longest_interval,
+            )?,
+            _ => return Err(anyhow!("The payout bounds must each be given as '<lowest>,<highest>'")),
+        };
+        payout_policy.record_selection = node.payout_record_selection;
+
+        // Initialize the block tag, which is signed with the private key of the operator.
+        let block_tag = match (&node.block_tag, &node.block_tag_key) {
+            (Some(block_tag), Some(private_key)) => {
+                Some((block_tag.clone(), private_key.expose_secret().trim().parse::<PrivateKey<N>>()?))
+            }
+            (Some(_), None) => return Err(anyhow!("The block tag requires the private key of the operator, specify '--block-tag-key'")),
+            (None, _) => None,
+        };
+
         // Initialize a new instance for managing peers.
-        let peers = Peers::new(local_ip, None).await;
+        let peers = Peers::new(local_ip, None, peers_config).await;
         // Initialize a new instance for managing the ledger.
-        let ledger = Ledger::<N, E>::open::<RocksDB, _>(&ledger_storage_path, peers.router()).await?;
+        let ledger = match (E::NODE_TYPE, &node.explorer) {
+            (NodeType::Explorer, Some(path)) => {
+                // Keep the state of the secondary instance apart from the ledger it follows.
+                let secondary_path = ledger_storage_path.join("explorer");
+                Ledger::<N, E>::open_explorer::<RocksDB, _>(path.as_path(), secondary_path.as_path(), peers.router()).await?
+            }
+            _ => Ledger::<N, E>::open::<RocksDB, _>(&ledger_storage_path, node.address_index, peers.router()).await?,
+        };
         // Initialize a new instance for managing the prover.
         let prover = Prover::open::<RocksDB, _>(
             &prover_storage_path,
             address,
             local_ip,
             pool_ip,
+            node.tx_propagation,
             peers.router(),
             ledger.reader(),
             ledger.router(),
         )
         .await?;
+        prover.memory_pool().set_policy(MemoryPoolPolicy {
+            minimum_fee_per_byte: node.mempool_min_fee_per_byte,
+            maximum_transitions: node.mempool_max_transitions,
+            reject_zero_value: node.mempool_reject_zero_value,
+        });
         // Initialize a new instance for managing the operator.
         let operator = Operator::open::<RocksDB, _>(
             &operator_storage_path,
             address,
+            sub_pools,
+            block_tag,
+            retention_policy,
+            share_sampling,
+            share_timestamps,
+            payout_policy,
             local_ip,
+            node.pool_tls_listener,
             prover.memory_pool(),
             peers.router(),
             ledger.reader(),
@@ -105,9 +230,26 @@ impl<N: Network, E: Environment> Server<N, E> {
         )
         .await?;
 
+        // Compare the chain with the external endpoints, if any are given to an operating node.
+        if E::NODE_TYPE == NodeType::Operator && !node.chain_split_endpoints.is_empty() {
+            let detector = ChainSplitDetector::new(
+                node.chain_split_endpoints.clone(),
+                node.chain_split_threshold,
+                node.chain_split_halt_payouts,
+                Duration::from_secs(node.chain_split_interval),
+            )?;
+            operator.start_chain_split_detector(detector);
+        }
+
+        // Cross-check the storage indexes against the blocks, if enabled.
+        if node.index_check_interval > 0 {
+            operator.start_index_divergence_detector(Duration::from_secs(node.index_check_interval), node.index_check_sample_size);
+        }
+
         // TODO (howardwu): This is a hack for the prover.
         //  Check that the prover is connected to the pool before sending a PoolRegister message.
-        if let Some(pool_ip) = pool_ip {
+        if pool_ip.is_some() {
+            let prover_clone = prover.clone();
             let peers_router = peers.router();
             let ledger_reader = ledger.reader();
             let ledger_router = ledger.router();
@@ -121,6 +263,8 @@ impl<N: Network, E: Environment> Server<N, E> {
                     // Notify the outer function that the task is ready.
                     let _ = router.send(());
                     loop {
+                        // Connect to the current pool of the prover, which is replaced by its backup pool during maintenance.
+                        let pool_ip = prover_clone.pool().expect("The pool of a prover is never removed");
                         // Initialize the connection process.
                         let (router, handler) = oneshot::channel();
                         // Route a `Connect` request to the pool.
@@ -140,30 +284,71 @@ impl<N: Network, E: Environment> Server<N, E> {
                         // Wait until the connection task is initialized.
                         let _ = handler.await;
 
-                        // Sleep for `30` seconds.
-                        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                        // Sleep for `30` seconds, or until the prover migrates to a backup pool.
+                        tokio::select! {
+                            _ = tokio::time::sleep(std::time::Duration::from_secs(30)) => (),
+                            _ = prover_clone.pool_changed() => (),
+                        }
                     }
                 }),
             );
 
             // Wait until the prover handler is ready.
             let _ = handler.await;
+
+            // Fall back to the RPC server of the pool, if the pool is unreachable over TCP.
+            if let Some(pool_url) = &node.pool_http {
+                let private_key = match &node.pool_http_key {
+                    Some(private_key) => private_key.expose_secret().trim().parse::<PrivateKey<N>>()?,
+                    None => return Err(anyhow!("The HTTP fallback requires the private key of the prover, specify '--pool-http-key'")),
+                };
+                prover.start_http_fallback(pool_url.clone(), private_key, peers.clone())?;
+            }
         }
 
         // Initialize the connection listener for new peers.
-        Self::initialize_listener(
-            local_ip,
-            listener,
-            peers.clone(),
-            ledger.reader(),
-            ledger.router(),
-            operator.router(),
-            prover.router(),
-        )
-        .await;
+        if let Some(listener) = listener {
+            Self::initialize_listener(
+                local_ip,
+                listener,
+                peers.clone(),
+                ledger.reader(),
+                ledger.router(),
+                operator.router(),
+                prover.router(),
+            )
+            .await;
+        }
+
+        // Initialize the TLS listener for provers, if it is enabled.
+        if let Some(tls_ip) = node.pool_tls_listener {
+            let acceptor = match (&node.pool_tls_cert, &node.pool_tls_key) {
+                (Some(certificate_path), Some(private_key_path)) => {
+                    PoolTlsAcceptor::new(certificate_path, private_key_path, node.pool_tls_client_ca.as_ref())?
+                }
+                _ => return Err(anyhow!("The TLS listener requires --pool-tls-cert and --pool-tls-key")),
+            };
+            let tls_listener = match TcpListener::bind(tls_ip).await {
+                Ok(tls_listener) => tls_listener,
+                Err(error) => return Err(anyhow!("Failed to bind the TLS listener at {}: {}", tls_ip, error)),
+            };
+            Self::initialize_tls_listener(
+                tls_ip,
+                tls_listener,
+                acceptor,
+                peers.clone(),
+                operator.clone(),
+                ledger.reader(),
+                ledger.router(),
+                prover.router(),
+            )
+            .await;
+        }
 
-        // Initialize a new instance of the heartbeat.
-        Self::initialize_heartbeat(peers.router(), ledger.reader(), ledger.router(), operator.router(), prover.router()).await;
+        // Initialize a new instance of the heartbeat, unless this is an explorer, whose ledger is synced by another node.
+        if E::NODE_TYPE != NodeType::Explorer {
+            Self::initialize_heartbeat(peers.router(), ledger.reader(), ledger.router(), operator.router(), prover.router()).await;
+        }
 
         #[cfg(feature = "rpc")]
         // Initialize a new instance of the RPC server.
@@ -178,14 +363,14 @@ impl<N: Network, E: Environment> Server<N, E> {
             prover.router(),
             prover.memory_pool(),
         )
-        .await;
+        .await?;
 
         // Initialize a new instance of the notification.
         Self::initialize_notification(ledger.reader(), prover.clone(), address).await;
 
         // Initialise the metrics exporter.
         #[cfg(any(feature = "test", feature = "prometheus"))]
-        Self::initialize_metrics(ledger.reader());
+        Self::initialize_metrics(node, ledger.reader());
 
         Ok(Self {
             local_ip,
@@ -249,6 +434,10 @@ impl<N: Network, E: Environment> Server<N, E> {
         trace!("Proceeding to shut down the ledger...");
         self.ledger.shut_down().await;
 
+        // Persist the pending shares of the operator.
+        trace!("Proceeding to shut down the operator...");
+        self.operator.shut_down();
+
         // Flush the tasks.
         E::resources().shut_down();
         trace!("Node has shut down.");
@@ -284,7 +473,7 @@ impl<N: Network, E: Environment> Server<N, E> {
                             // Process the inbound connection request.
                             Ok((stream, peer_ip)) => {
                                 let request = PeersRequest::PeerConnecting(
-                                    stream,
+                                    PeerStream::from(stream),
                                     peer_ip,
                                     ledger_reader.clone(),
                                     ledger_router.clone(),
@@ -311,6 +500,91 @@ impl<N: Network, E: Environment> Server<N, E> {
         let _ = handler.await;
     }
 
+    ///
+    /// Initialize a new instance of the TLS listener for provers, which routes each connection
+    /// to a sub-pool by the server name (SNI) requested by the prover.
+    ///
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    async fn initialize_tls_listener(
+        tls_ip: SocketAddr,
+        listener: TcpListener,
+        acceptor: PoolTlsAcceptor,
+        peers: Arc<Peers<N, E>>,
+        operator: Arc<Operator<N, E>>,
+        ledger_reader: LedgerReader<N>,
+        ledger_router: LedgerRouter<N>,
+        prover_router: ProverRouter<N>,
+    ) {
+        // Initialize the TLS listener process.
+        let (router, handler) = oneshot::channel();
+
+        E::resources().register_task(
+            None, // No need to provide an id, as the task will run indefinitely.
+            task::spawn(async move {
+                // Notify the outer function that the task is ready.
+                let _ = router.send(());
+                info!("Listening for provers over TLS at {}", tls_ip);
+                loop {
+                    // Asynchronously wait for an inbound TcpStream.
+                    let (stream, peer_ip) = match listener.accept().await {
+                        Ok((stream, peer_ip)) => (stream, peer_ip),
+                        Err(error) => {
+                            error!("Failed to accept a TLS connection: {}", error);
+                            continue;
+                        }
+                    };
+
+                    let acceptor = acceptor.clone();
+                    let peers_router = peers.router();
+                    let operator = operator.clone();
+                    let ledger_reader = ledger_reader.clone();
+                    let ledger_router = ledger_router.clone();
+                    let prover_router = prover_router.clone();
+
+                    // Perform the TLS handshake asynchronously, so a slow prover does not block the listener.
+                    let resource_id = E::resources().procure_id();
+                    E::resources().register_task(
+                        Some(resource_id),
+                        task::spawn(async move {
+                            match timeout(Duration::from_millis(E::CONNECTION_TIMEOUT_IN_MILLIS), acceptor.accept(stream)).await {
+                                Ok(Ok((stream, server_name, is_authenticated))) => {
+                                    // Route the connection to the sub-pool serving the requested server name.
+                                    match operator.route_connection(peer_ip, server_name.as_deref(), is_authenticated).await {
+                                        Ok(()) => {
+                                            let request = PeersRequest::PeerConnecting(
+                                                stream,
+                                                peer_ip,
+                                                ledger_reader,
+                                                ledger_router,
+                                                operator.router(),
+                                                prover_router,
+                                            );
+                                            if let Err(error) = peers_router.send(request).await {
+                                                error!("Failed to send request to peers: {}", error)
+                                            }
+                                        }
+                                        Err(error) => debug!("Dropping TLS connection from {} ({})", peer_ip, error),
+                                    }
+                                }
+                                Ok(Err(error)) => debug!("Failed the TLS handshake with {}: {}", peer_ip, error),
+                                Err(_) => debug!("Timed out on the TLS handshake with {}", peer_ip),
+                            }
+
+                            E::resources().deregister(resource_id);
+                        }),
+                    );
+
+                    // Add a small delay to prevent overloading the network from handshakes.
+                    tokio::time::sleep(Duration::from_millis(150)).await;
+                }
+            }),
+        );
+
+        // Wait until the TLS listener task is ready.
+        let _ = handler.await;
+    }
+
     ///
     /// Initialize a new instance of the heartbeat.
     ///
@@ -368,13 +642,81 @@ impl<N: Network, E: Environment> Server<N, E> {
         operator: Arc<Operator<N, E>>,
         operator_router: OperatorRouter<N>,
         prover_router: ProverRouter<N>,
-        memory_pool: Arc<RwLock<MemoryPool<N>>>,
-    ) {
+        memory_pool: Arc<SharedMemoryPool<N>>,
+    ) -> Result<()> {
         if !node.norpc {
+            // Initialize the TLS configuration of the RPC servers, if it is enabled.
+            let tls_acceptor = match (&node.rpc_tls_cert, &node.rpc_tls_key) {
+                (Some(certificate_path), Some(private_key_path)) => Some(RpcTlsAcceptor::new(certificate_path, private_key_path)?),
+                (None, None) => None,
+                _ => return Err(anyhow!("TLS for the RPC server requires both --rpc-tls-cert and --rpc-tls-key")),
+            };
+
+            // Initialize the rate limiter of the RPC servers, shared by their clients, if it is enabled.
+            let rate_limiter = match node.rpc_rate {
+                Some(calls_per_second) => {
+                    let limit = RateLimit {
+                        calls_per_second,
+                        burst: node.rpc_burst,
+                    };
+                    let heavy_limit = RateLimit {
+                        calls_per_second: node.rpc_heavy_rate,
+                        burst: node.rpc_heavy_burst,
+                    };
+                    Some(Arc::new(RpcRateLimiter::new(limit, heavy_limit)?))
+                }
+                None => None,
+            };
+
+            // Initialize the CORS policy of the RPC HTTP server, if any origins are allowed.
+            let cors = match node.rpc_cors_origins.is_empty() {
+                true => None,
+                false => {
+                    let cors = RpcCors::new(&node.rpc_cors_origins, &node.rpc_cors_methods, &node.rpc_cors_headers)?;
+                    Some(Arc::new(cors))
+                }
+            };
+
+            // Initialize the compression of the RPC HTTP responses, if it is enabled.
+            let compression = node.rpc_compression.map(RpcCompression::new).transpose()?;
+
+            // Initialize the HTTP settings of the RPC HTTP server, if HTTP/2 or an idle timeout is enabled.
+            let http_config = match (node.rpc_http2, node.rpc_idle_timeout) {
+                (false, None) => None,
+                (http2, idle_timeout) => Some(RpcHttpConfig {
+                    http2,
+                    idle_timeout: idle_timeout.map(Duration::from_secs),
+                }),
+            };
+
+            // Initialize the credentials of the RPC server, and its API keys, if any are given.
+            let credentials = RpcCredentials {
+                username: node.rpc_username.clone(),
+                password: node.rpc_password.clone(),
+            };
+            let auth = match &node.rpc_api_keys {
+                Some(path) => RpcAuth::open(credentials, path)?,
+                None => RpcAuth::new(credentials, vec![])?,
+            };
+
+            // Initialize the usage statistics of the clients of the RPC servers, if they are recorded.
+            let usage = node.rpc_usage.then(|| Arc::new(RpcUsageTracker::new(auth.clone())));
+
+            // Initialize the audit log of the calls to the guarded RPC methods, if they are audited.
+            let audit_log = node.rpc_audit_log.as_deref().map(|sink| RpcAuditLog::open(sink, auth.clone())).transpose()?.map(Arc::new);
+            if audit_log.is_some() && node.rpc_ws.is_some() {
+                warn!("The calls made over the RPC WebSocket server are not recorded in the audit log");
+            }
+
+            // Initialize the policy which enables and disables the RPC methods.
+            let method_policy = RpcMethodPolicy::new(&node.rpc_allowed_methods, &node.rpc_denied_methods)?;
+
             // Initialize a new instance of the RPC server.
             let rpc_context = RpcContext::new(
-                node.rpc_username.clone(),
-                node.rpc_password.clone(),
+                auth,
+                usage,
+                audit_log,
+                method_policy,
                 address,
                 peers,
                 ledger_reader,
@@ -383,14 +725,74 @@ impl<N: Network, E: Environment> Server<N, E> {
                 operator_router,
                 prover_router,
                 memory_pool,
+                node.rpc_block_template_schema,
             );
-            let (rpc_server_addr, rpc_server_handle) = initialize_rpc_server::<N, E>(node.rpc, rpc_context).await;
+            // Initialize a new instance of the RPC WebSocket server, if it is enabled.
+            if let Some(rpc_ws) = node.rpc_ws {
+                let (rpc_ws_server_addr, rpc_ws_server_handle) =
+                    initialize_rpc_ws_server::<N, E>(rpc_ws, rpc_context.clone(), tls_acceptor.clone(), rate_limiter.clone()).await;
+
+                debug!("JSON-RPC WebSocket server listening on {}", rpc_ws_server_addr);
+
+                // Register the task; no need to provide an id, as it will run indefinitely.
+                E::resources().register_task(None, rpc_ws_server_handle);
+            }
+            // Initialize a new instance of the REST gateway, if it is enabled.
+            if let Some(rest) = node.rest {
+                // The built-in explorer and the landing page of the pool are served outside of the routes of the gateway.
+                let pages = RestPages {
+                    explorer: node.rest_explorer.clone(),
+                    pool: node.rest_pool_page.clone(),
+                };
+                for path in pages.explorer.iter().chain(&pages.pool) {
+                    if !path.starts_with('/') || path.starts_with(REST_API_PREFIX) {
+                        return Err(anyhow!("The path of a page must start with '/', outside of {}", REST_API_PREFIX));
+                    }
+                }
+                if pages.pool.is_some() && E::NODE_TYPE != NodeType::Operator {
+                    return Err(anyhow!("The landing page of the pool is only served by an operating node"));
+                }
+                let (rest_server_addr, rest_server_handle) =
+                    initialize_rest_server::<N, E>(
+                        rest,
+                        rpc_context.clone(),
+                        pages,
+                        tls_acceptor.clone(),
+                        rate_limiter.clone(),
+                        cors.clone(),
+                        compression,
+                        http_config,
+                    )
+                    .await;
+
+                debug!("REST gateway listening on {}", rest_server_addr);
+
+                // Register the task; no need to provide an id, as it will run indefinitely.
+                E::resources().register_task(None, rest_server_handle);
+            }
+
+            // Initialize a new instance of the gRPC server, if it is enabled.
+            #[cfg(feature = "grpc")]
+            if let Some(grpc) = node.grpc {
+                let (tls_acceptor, rate_limiter) = (tls_acceptor.clone(), rate_limiter.clone());
+                let (grpc_server_addr, grpc_server_handle) =
+                    snarkos_rpc::initialize_grpc_server::<N, E>(grpc, rpc_context.clone(), tls_acceptor, rate_limiter).await;
+
+                debug!("gRPC server listening on {}", grpc_server_addr);
+
+                // Register the task; no need to provide an id, as it will run indefinitely.
+                E::resources().register_task(None, grpc_server_handle);
+            }
+
+            let (rpc_server_addr, rpc_server_handle) =
+                initialize_rpc_server::<N, E>(node.rpc, rpc_context, tls_acceptor, rate_limiter, cors, compression, http_config).await;
 
             debug!("JSON-RPC server listening on {}", rpc_server_addr);
 
             // Register the task; no need to provide an id, as it will run indefinitely.
             E::resources().register_task(None, rpc_server_handle);
         }
+        Ok(())
     }
 
     ///
@@ -452,14 +854,15 @@ impl<N: Network, E: Environment> Server<N, E> {
     }
 
     #[cfg(any(feature = "test", feature = "prometheus"))]
-    fn initialize_metrics(ledger: LedgerReader<N>) {
+    fn initialize_metrics(_node: &Node, ledger: LedgerReader<N>) {
         #[cfg(not(feature = "test"))]
-        if let Some(handler) = snarkos_metrics::initialize() {
+        if let Some(handler) = snarkos_metrics::initialize(_node.metrics) {
             // No need to provide an id, as the task will run indefinitely.
             E::resources().register_task(None, handler);
         }
 
-        // Set the block height as it could already be non-zero.
+        // Set the block height and cumulative weight as they could already be non-zero.
         metrics::gauge!(metrics::blocks::HEIGHT, ledger.latest_block_height() as f64);
+        metrics::gauge!(metrics::blocks::CUMULATIVE_WEIGHT, ledger.latest_cumulative_weight() as f64);
     }
 }
@@ -0,0 +1,234 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use clap::Parser;
+use std::path::{Path, PathBuf};
+
+/// The name of the snarkOS service.
+pub const SERVICE_NAME: &str = "snarkos";
+
+#[derive(Debug, Parser)]
+pub struct InstallService {
+    /// Specify the user to run the service as.
+    #[clap(long = "user")]
+    pub user: Option<String>,
+    /// Specify the directory to write the service logs to.
+    #[clap(parse(from_os_str), long = "log-dir")]
+    pub log_dir: Option<PathBuf>,
+    /// Specify the Aleo directory of the service user, which the service may write to.
+    #[clap(parse(from_os_str), long = "aleo-dir")]
+    pub aleo_dir: Option<PathBuf>,
+    /// Specify the directory to write the systemd unit to.
+    #[clap(parse(from_os_str), default_value = "/etc/systemd/system", long = "unit-dir")]
+    pub unit_dir: PathBuf,
+    /// If the flag is set, the service definition is printed instead of installed.
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+    /// The arguments to start the node with, e.g. `-- --operator aleo1...`.
+    #[clap(last = true)]
+    pub args: Vec<String>,
+}
+
+impl InstallService {
+    pub fn parse(self) -> Result<String> {
+        let executable = std::env::current_exe()?;
+        self.install(&executable)
+    }
+
+    /// Returns the arguments to start the node with, including the log file, if any.
+    fn node_arguments(&self) -> Vec<String> {
+        let mut args = self.args.clone();
+        if let Some(log_dir) = &self.log_dir {
+            args.push("--log-file".to_string());
+            args.push(log_dir.join("snarkos.log").display().to_string());
+        }
+        args
+    }
+
+    /// Returns a systemd unit for the node, which is sandboxed to write only to the Aleo and log directories.
+    pub fn systemd_unit(&self, executable: &Path) -> String {
+        let aleo_dir = self.aleo_dir.clone().unwrap_or_else(aleo_std::aleo_dir);
+        let mut writable_paths = vec![aleo_dir.display().to_string()];
+        if let Some(log_dir) = &self.log_dir {
+            writable_paths.push(log_dir.display().to_string());
+        }
+        let user = match &self.user {
+            Some(user) => format!("User={}\n", user),
+            None => String::new(),
+        };
+        let command = std::iter::once(executable.display().to_string())
+            .chain(self.node_arguments().iter().map(|arg| quote_argument(arg)))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        format!(
+            r#"[Unit]
+Description=snarkOS node
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+{user}ExecStart={command}
+Restart=on-failure
+RestartSec=10
+# snarkOS shuts down cleanly upon receiving SIGINT.
+KillSignal=SIGINT
+TimeoutStopSec=120
+LimitNOFILE=65536
+NoNewPrivileges=true
+PrivateTmp=true
+PrivateDevices=true
+ProtectSystem=strict
+ProtectHome=read-only
+ReadWritePaths={writable_paths}
+ProtectKernelTunables=true
+ProtectKernelModules=true
+ProtectControlGroups=true
+RestrictSUIDSGID=true
+LockPersonality=true
+
+[Install]
+WantedBy=multi-user.target
+"#,
+            user = user,
+            command = command,
+            writable_paths = writable_paths.join(" "),
+        )
+    }
+
+    /// Writes a systemd unit for the node into the unit directory.
+    #[cfg(unix)]
+    fn install(&self, executable: &Path) -> Result<String> {
+        let unit = self.systemd_unit(executable);
+        if self.dry_run {
+            return Ok(unit);
+        }
+
+        if let Some(log_dir) = &self.log_dir {
+            std::fs::create_dir_all(log_dir)?;
+        }
+        let path = self.unit_dir.join(format!("{}.service", SERVICE_NAME));
+        std::fs::write(&path, unit).map_err(|error| anyhow::anyhow!("Failed to write the systemd unit to {}: {}", path.display(), error))?;
+
+        Ok(format!(
+            "Installed the systemd unit at {}. Start the service with:\n\n    systemctl daemon-reload && systemctl enable --now {}\n",
+            path.display(),
+            SERVICE_NAME
+        ))
+    }
+
+    /// Registers the node with the Windows service control manager.
+    #[cfg(windows)]
+    fn install(&self, executable: &Path) -> Result<String> {
+        use std::ffi::OsString;
+        use windows_service::{
+            service::{ServiceAccess, ServiceErrorControl, ServiceInfo, ServiceStartType, ServiceType},
+            service_manager::{ServiceManager, ServiceManagerAccess},
+        };
+
+        let arguments: Vec<String> = std::iter::once("--windows-service".to_string()).chain(self.node_arguments()).collect();
+        if self.dry_run {
+            return Ok(format!("{} {}", executable.display(), arguments.join(" ")));
+        }
+
+        if let Some(log_dir) = &self.log_dir {
+            std::fs::create_dir_all(log_dir)?;
+        }
+        let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE)?;
+        let service_info = ServiceInfo {
+            name: OsString::from(SERVICE_NAME),
+            display_name: OsString::from("snarkOS node"),
+            service_type: ServiceType::OWN_PROCESS,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: executable.to_path_buf(),
+            launch_arguments: arguments.into_iter().map(OsString::from).collect(),
+            dependencies: vec![],
+            account_name: self.user.as_ref().map(OsString::from),
+            account_password: None,
+        };
+        manager.create_service(&service_info, ServiceAccess::QUERY_STATUS)?;
+
+        Ok(format!("Registered the '{}' service. Start it with:\n\n    sc.exe start {}\n", SERVICE_NAME, SERVICE_NAME))
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn install(&self, _executable: &Path) -> Result<String> {
+        Err(anyhow::anyhow!("Installing a service is not supported on this platform"))
+    }
+}
+
+/// Quotes the given argument for a systemd `ExecStart` line, if it contains whitespace or quotes.
+fn quote_argument(argument: &str) -> String {
+    match argument.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        true => format!("\"{}\"", argument.replace('\\', "\\\\").replace('"', "\\\"")),
+        false => argument.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install_service(args: &[&str]) -> InstallService {
+        InstallService {
+            user: Some("aleo".to_string()),
+            log_dir: Some(PathBuf::from("/var/log/snarkos")),
+            aleo_dir: Some(PathBuf::from("/home/aleo/.aleo")),
+            unit_dir: PathBuf::from("/etc/systemd/system"),
+            dry_run: true,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_quote_argument() {
+        assert_eq!(quote_argument("--operator"), "--operator");
+        assert_eq!(quote_argument("/var/lib/snarkos"), "/var/lib/snarkos");
+        assert_eq!(quote_argument("/var/lib/snark os"), "\"/var/lib/snark os\"");
+        assert_eq!(quote_argument("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_argument("C:\\snarkos"), "\"C:\\\\snarkos\"");
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let unit = install_service(&["--operator", "aleo1operator", "--node", "0.0.0.0:4132"]).systemd_unit(Path::new("/usr/bin/snarkos"));
+
+        // Check the node is started with its arguments and the log file.
+        assert!(unit.contains(
+            "\nExecStart=/usr/bin/snarkos --operator aleo1operator --node 0.0.0.0:4132 --log-file /var/log/snarkos/snarkos.log\n"
+        ));
+        assert!(unit.contains("\nUser=aleo\n"));
+        // Check the service may only write to the Aleo and log directories.
+        assert!(unit.contains("\nReadWritePaths=/home/aleo/.aleo /var/log/snarkos\n"));
+        assert!(unit.contains("\nProtectSystem=strict\n"));
+        assert!(unit.contains("\nKillSignal=SIGINT\n"));
+    }
+
+    #[test]
+    fn test_systemd_unit_quotes_arguments() {
+        let mut install_service = install_service(&["--log-filter", "snarkos=debug warn"]);
+        install_service.user = None;
+        install_service.log_dir = None;
+        let unit = install_service.systemd_unit(Path::new("/usr/bin/snarkos"));
+
+        assert!(unit.contains("\nExecStart=/usr/bin/snarkos --log-filter \"snarkos=debug warn\"\n"));
+        assert!(!unit.contains("User="));
+        assert!(unit.contains("\nReadWritePaths=/home/aleo/.aleo\n"));
+    }
+}
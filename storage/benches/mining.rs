@@ -0,0 +1,90 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_environment::CurrentNetwork;
+use snarkos_storage::{storage::rocksdb::RocksDB, LedgerState};
+use snarkvm::{
+    dpc::{posw::PoSWScheme, prelude::*},
+    utilities::{FromBytes, ToBytes},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::thread_rng;
+
+fn block_template(c: &mut Criterion) {
+    let temp_dir = tempfile::tempdir().expect("Failed to open temporary directory").into_path();
+    // Create a ledger containing only the genesis block.
+    let ledger: LedgerState<CurrentNetwork> =
+        LedgerState::open_writer_with_increment::<RocksDB, _>(&temp_dir, 1).expect("Failed to initialize ledger");
+    let recipient = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+
+    c.bench_function("block_template", |b| {
+        b.iter(|| {
+            ledger
+                .get_block_template(recipient, true, &[], &mut thread_rng())
+                .expect("Failed to construct a block template");
+        })
+    });
+}
+
+fn share_verification(c: &mut Criterion) {
+    let block_header = CurrentNetwork::genesis_block().header();
+
+    c.bench_function("share_verification", |b| {
+        b.iter(|| {
+            assert!(CurrentNetwork::posw().verify_from_block_header(block_header));
+        })
+    });
+}
+
+fn block_serialization(c: &mut Criterion) {
+    let block = CurrentNetwork::genesis_block();
+    let block_bytes = block.to_bytes_le().expect("Failed to serialize the genesis block");
+    let block_json = serde_json::to_string(block).expect("Failed to serialize the genesis block");
+
+    c.bench_function("block_to_bytes", |b| {
+        b.iter(|| {
+            block.to_bytes_le().expect("Failed to serialize a block");
+        })
+    });
+
+    c.bench_function("block_from_bytes", |b| {
+        b.iter(|| {
+            Block::<CurrentNetwork>::from_bytes_le(&block_bytes).expect("Failed to deserialize a block");
+        })
+    });
+
+    c.bench_function("block_to_json", |b| {
+        b.iter(|| {
+            serde_json::to_string(block).expect("Failed to serialize a block");
+        })
+    });
+
+    c.bench_function("block_from_json", |b| {
+        b.iter(|| {
+            serde_json::from_str::<Block<CurrentNetwork>>(&block_json).expect("Failed to deserialize a block");
+        })
+    });
+}
+
+criterion_group!(
+    name = templates;
+    // Constructing a block template produces a coinbase proof, so fewer samples are taken.
+    config = Criterion::default().sample_size(10);
+    targets = block_template
+);
+criterion_group!(benches, share_verification, block_serialization);
+criterion_main!(templates, benches);
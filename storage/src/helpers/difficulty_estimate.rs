@@ -0,0 +1,124 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// The number of blocks spanned by the smoothing of the difficulty estimate.
+pub const DIFFICULTY_ESTIMATE_PERIOD: u32 = 20;
+
+///
+/// A smoothed estimate of the effective network difficulty and proof rate, as an exponential moving average over recent blocks.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyEstimate {
+    /// The height of the latest block included in the estimate.
+    pub block_height: u32,
+    /// The smoothed difficulty, as the expected number of proofs to find a block.
+    pub difficulty: f64,
+    /// The difficulty target corresponding to the smoothed difficulty.
+    pub difficulty_target: u64,
+    /// The smoothed time between blocks, in seconds.
+    pub block_interval_in_secs: f64,
+    /// The estimated proof rate of the network, in proofs per second.
+    pub proof_rate: f64,
+}
+
+impl DifficultyEstimate {
+    ///
+    /// Estimates the network difficulty from the given blocks := \[(block_height, block_timestamp, difficulty_target)\],
+    /// in ascending order of height. The smoothed block interval starts from the given target block interval.
+    ///
+    pub fn new(blocks: &[(u32, i64, u64)], target_block_interval_in_secs: i64) -> Result<Self> {
+        let (first, remaining) = match blocks.split_first() {
+            Some((first, remaining)) => (first, remaining),
+            None => return Err(anyhow!("At least one block is required to estimate the network difficulty")),
+        };
+
+        // Weight the most recent block as in an exponential moving average over the estimate period.
+        let alpha = 2.0 / (DIFFICULTY_ESTIMATE_PERIOD as f64 + 1.0);
+
+        let (mut block_height, mut block_timestamp, difficulty_target) = *first;
+        let mut difficulty = Self::to_difficulty(difficulty_target);
+        let mut block_interval_in_secs = target_block_interval_in_secs.max(1) as f64;
+
+        for (next_height, next_timestamp, next_difficulty_target) in remaining {
+            // Block timestamps are not strictly increasing, so each interval is at least one second.
+            let interval = next_timestamp.saturating_sub(block_timestamp).max(1) as f64;
+            block_interval_in_secs += alpha * (interval - block_interval_in_secs);
+            difficulty += alpha * (Self::to_difficulty(*next_difficulty_target) - difficulty);
+
+            block_height = *next_height;
+            block_timestamp = *next_timestamp;
+        }
+
+        Ok(Self {
+            block_height,
+            difficulty,
+            difficulty_target: (u64::MAX as f64 / difficulty) as u64,
+            block_interval_in_secs,
+            proof_rate: difficulty / block_interval_in_secs,
+        })
+    }
+
+    ///
+    /// Returns the expected number of proofs to meet the given difficulty target.
+    ///
+    fn to_difficulty(difficulty_target: u64) -> f64 {
+        u64::MAX as f64 / difficulty_target.max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_difficulty() {
+        let blocks: Vec<_> = (1..=10).map(|height| (height, height as i64 * 20, u64::MAX / 1000)).collect();
+        let estimate = DifficultyEstimate::new(&blocks, 20).unwrap();
+
+        assert_eq!(estimate.block_height, 10);
+        assert!((estimate.difficulty - 1000.0).abs() < 1e-6);
+        assert!((estimate.block_interval_in_secs - 20.0).abs() < 1e-6);
+        assert!((estimate.proof_rate - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_smoothing() {
+        // A single block at double the difficulty moves the estimate only by the smoothing factor.
+        let blocks = [(1, 20, u64::MAX / 1000), (2, 40, u64::MAX / 2000)];
+        let estimate = DifficultyEstimate::new(&blocks, 20).unwrap();
+
+        let alpha = 2.0 / (DIFFICULTY_ESTIMATE_PERIOD as f64 + 1.0);
+        assert!((estimate.difficulty - (1000.0 + alpha * 1000.0)).abs() < 1e-6);
+        assert!(estimate.difficulty_target < u64::MAX / 1000);
+        assert!(estimate.difficulty_target > u64::MAX / 2000);
+    }
+
+    #[test]
+    fn test_non_increasing_timestamps() {
+        let blocks = [(1, 100, u64::MAX / 1000), (2, 90, u64::MAX / 1000)];
+        let estimate = DifficultyEstimate::new(&blocks, 20).unwrap();
+        assert!(estimate.block_interval_in_secs > 0.0);
+        assert!(estimate.proof_rate.is_finite());
+    }
+
+    #[test]
+    fn test_no_blocks() {
+        assert!(DifficultyEstimate::new(&[], 20).is_err());
+    }
+}
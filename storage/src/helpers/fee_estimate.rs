@@ -0,0 +1,262 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// The number of latest blocks from which fees are estimated.
+pub const FEE_ESTIMATE_WINDOW: u32 = 100;
+/// The maximum confirmation target, in blocks, for which a fee is estimated.
+pub const MAXIMUM_CONFIRMATION_TARGET: u32 = 100;
+/// The size of the transactions in a block, excluding the coinbase, in bytes, from which the block is considered full.
+pub const FULL_BLOCK_SIZE_IN_BYTES: usize = 256 * 1024;
+/// The probability with which a transaction paying the estimated fee is expected to be included within the confirmation target.
+const FEE_ESTIMATE_CONFIDENCE: f64 = 0.95;
+
+///
+/// An estimate of the fee for a transaction to be included within a confirmation target,
+/// from the fullness and fee levels of the latest blocks.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    /// The height of the latest block included in the estimate.
+    pub block_height: u32,
+    /// The number of blocks included in the estimate.
+    pub number_of_blocks: u32,
+    /// The number of blocks included in the estimate with no transaction besides the coinbase.
+    pub number_of_empty_blocks: u32,
+    /// The number of blocks included in the estimate whose transactions reach the size of a full block.
+    pub number_of_full_blocks: u32,
+    /// The average number of transactions per block, excluding the coinbase.
+    pub average_transactions_per_block: f64,
+    /// The average size of the transactions in a block, excluding the coinbase, in bytes.
+    pub average_block_size_in_bytes: f64,
+    /// The number of blocks within which the transaction is to be included.
+    pub confirmation_target: u32,
+    /// The minimum fee per byte admitted into the memory pool, in gates.
+    pub minimum_fee_per_byte: u64,
+    /// The estimated fee per byte, in gates.
+    pub fee_per_byte: u64,
+    /// The median size of the transactions in the blocks, in bytes, if any.
+    pub typical_transaction_size_in_bytes: Option<usize>,
+    /// The estimated fee for a transaction of the typical size, in gates, if any.
+    pub typical_fee: Option<u64>,
+}
+
+impl FeeEstimate {
+    ///
+    /// Estimates the fee from the given blocks := \[(block_height, \[(transaction_size_in_bytes, transaction_fee)\])\],
+    /// in ascending order of height and excluding the coinbase transactions, up to the given latest block height.
+    ///
+    /// The clearing fee rate of a full block is the lowest fee per byte among its transactions, while a block that is
+    /// not full clears at the minimum fee per byte, as it had room for any transaction. The estimate is the lowest fee
+    /// per byte that at least one of the blocks within the confirmation target is expected to clear, and no less than
+    /// the given minimum fee per byte.
+    ///
+    pub fn new(
+        block_height: u32,
+        blocks: &[(u32, Vec<(usize, i64)>)],
+        confirmation_target: u32,
+        minimum_fee_per_byte: u64,
+    ) -> Result<Self> {
+        if confirmation_target == 0 || confirmation_target > MAXIMUM_CONFIRMATION_TARGET {
+            return Err(anyhow!(
+                "The confirmation target must be between 1 and {} blocks, found {}",
+                MAXIMUM_CONFIRMATION_TARGET,
+                confirmation_target
+            ));
+        }
+
+        let mut clearing_fee_rates = Vec::with_capacity(blocks.len());
+        let mut transaction_sizes = Vec::new();
+        let mut number_of_empty_blocks = 0u32;
+        let mut number_of_full_blocks = 0u32;
+        let mut total_size_in_bytes = 0usize;
+
+        for (_, transactions) in blocks {
+            // A block that is not full would have cleared any fee, which is raised to the minimum fee below.
+            let block_size_in_bytes: usize = transactions.iter().map(|(size_in_bytes, _)| size_in_bytes).sum();
+            let clearing_fee_rate = match block_size_in_bytes >= FULL_BLOCK_SIZE_IN_BYTES {
+                true => {
+                    number_of_full_blocks += 1;
+                    transactions
+                        .iter()
+                        .map(|(size_in_bytes, fee)| (*fee).max(0) as f64 / (*size_in_bytes).max(1) as f64)
+                        .reduce(f64::min)
+                        .unwrap_or(0.0)
+                }
+                false => 0.0,
+            };
+            clearing_fee_rates.push(clearing_fee_rate);
+
+            if transactions.is_empty() {
+                number_of_empty_blocks += 1;
+            }
+            for (size_in_bytes, _) in transactions {
+                total_size_in_bytes += size_in_bytes;
+                transaction_sizes.push(*size_in_bytes);
+            }
+        }
+
+        // A fee clearing a fraction `p` of the blocks is included within `n` blocks with probability `1 - (1 - p)^n`,
+        // so the estimate is the clearing fee rate at the fraction reaching the confidence within the target.
+        let estimated_fee_per_byte = match clearing_fee_rates.is_empty() {
+            true => 0,
+            false => {
+                clearing_fee_rates.sort_by(|a, b| a.total_cmp(b));
+                let fraction = 1.0 - (1.0 - FEE_ESTIMATE_CONFIDENCE).powf(1.0 / confirmation_target as f64);
+                let index = ((fraction * clearing_fee_rates.len() as f64).ceil() as usize).clamp(1, clearing_fee_rates.len()) - 1;
+                clearing_fee_rates[index].ceil() as u64
+            }
+        };
+        let fee_per_byte = estimated_fee_per_byte.max(minimum_fee_per_byte);
+
+        transaction_sizes.sort_unstable();
+        let typical_transaction_size_in_bytes = transaction_sizes.get(transaction_sizes.len() / 2).copied();
+        let number_of_blocks = blocks.len() as u32;
+        let average = |total: usize| match number_of_blocks {
+            0 => 0.0,
+            _ => total as f64 / number_of_blocks as f64,
+        };
+
+        Ok(Self {
+            block_height,
+            number_of_blocks,
+            number_of_empty_blocks,
+            number_of_full_blocks,
+            average_transactions_per_block: average(transaction_sizes.len()),
+            average_block_size_in_bytes: average(total_size_in_bytes),
+            confirmation_target,
+            minimum_fee_per_byte,
+            fee_per_byte,
+            typical_transaction_size_in_bytes,
+            typical_fee: typical_transaction_size_in_bytes.map(|size_in_bytes| fee_per_byte.saturating_mul(size_in_bytes as u64)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_blocks() {
+        let estimate = FeeEstimate::new(0, &[], 1, 0).unwrap();
+        assert_eq!(estimate.number_of_blocks, 0);
+        assert_eq!(estimate.fee_per_byte, 0);
+        assert_eq!(estimate.average_transactions_per_block, 0.0);
+        assert_eq!(estimate.typical_transaction_size_in_bytes, None);
+        assert_eq!(estimate.typical_fee, None);
+
+        // The estimate is no less than the minimum fee per byte.
+        let estimate = FeeEstimate::new(0, &[], 1, 3).unwrap();
+        assert_eq!(estimate.fee_per_byte, 3);
+    }
+
+    #[test]
+    fn test_invalid_confirmation_target() {
+        assert!(FeeEstimate::new(0, &[], 0, 0).is_err());
+        assert!(FeeEstimate::new(0, &[], MAXIMUM_CONFIRMATION_TARGET + 1, 0).is_err());
+        assert!(FeeEstimate::new(0, &[], MAXIMUM_CONFIRMATION_TARGET, 0).is_ok());
+    }
+
+    /// The size of a transaction filling half of a block, in bytes.
+    const HALF_BLOCK_SIZE: usize = FULL_BLOCK_SIZE_IN_BYTES / 2;
+
+    /// Returns the transactions of a block filled by a single transaction paying the given fee per byte.
+    fn full_block(fee_per_byte: i64) -> Vec<(usize, i64)> {
+        vec![(FULL_BLOCK_SIZE_IN_BYTES, FULL_BLOCK_SIZE_IN_BYTES as i64 * fee_per_byte)]
+    }
+
+    #[test]
+    fn test_full_blocks() {
+        // Every block is full, and clears at 10 gates per byte.
+        let transactions = vec![
+            (HALF_BLOCK_SIZE, HALF_BLOCK_SIZE as i64 * 10),
+            (HALF_BLOCK_SIZE, HALF_BLOCK_SIZE as i64 * 20),
+        ];
+        let blocks: Vec<_> = (1..=20).map(|height| (height, transactions.clone())).collect();
+
+        let estimate = FeeEstimate::new(20, &blocks, 1, 0).unwrap();
+        assert_eq!(estimate.block_height, 20);
+        assert_eq!(estimate.number_of_blocks, 20);
+        assert_eq!(estimate.number_of_empty_blocks, 0);
+        assert_eq!(estimate.number_of_full_blocks, 20);
+        assert_eq!(estimate.average_transactions_per_block, 2.0);
+        assert_eq!(estimate.average_block_size_in_bytes, FULL_BLOCK_SIZE_IN_BYTES as f64);
+        assert_eq!(estimate.fee_per_byte, 10);
+        assert_eq!(estimate.typical_transaction_size_in_bytes, Some(HALF_BLOCK_SIZE));
+        assert_eq!(estimate.typical_fee, Some(HALF_BLOCK_SIZE as u64 * 10));
+
+        // A longer confirmation target does not lower the fee when every block clears at the same rate.
+        assert_eq!(FeeEstimate::new(20, &blocks, 10, 0).unwrap().fee_per_byte, 10);
+    }
+
+    #[test]
+    fn test_non_full_blocks_clear_at_the_minimum_fee() {
+        // Every block holds a single transaction paying 50 gates per byte, with room to spare.
+        let blocks: Vec<_> = (1..=20)
+            .map(|height| (height, vec![(HALF_BLOCK_SIZE, HALF_BLOCK_SIZE as i64 * 50)]))
+            .collect();
+
+        let estimate = FeeEstimate::new(20, &blocks, 1, 2).unwrap();
+        assert_eq!(estimate.number_of_empty_blocks, 0);
+        assert_eq!(estimate.number_of_full_blocks, 0);
+        assert_eq!(estimate.fee_per_byte, 2);
+    }
+
+    #[test]
+    fn test_empty_blocks_lower_the_estimate() {
+        // Half of the blocks are full and clear at 10 gates per byte, and the other half are empty.
+        let blocks: Vec<_> = (1..=20)
+            .map(|height| match height % 2 {
+                0 => (height, full_block(10)),
+                _ => (height, vec![]),
+            })
+            .collect();
+
+        let estimate = FeeEstimate::new(20, &blocks, 1, 0).unwrap();
+        assert_eq!(estimate.number_of_empty_blocks, 10);
+        assert_eq!(estimate.number_of_full_blocks, 10);
+        assert_eq!(estimate.average_transactions_per_block, 0.5);
+        assert_eq!(estimate.fee_per_byte, 10);
+
+        // Within 10 blocks, an empty block is all but certain to be found.
+        let estimate = FeeEstimate::new(20, &blocks, 10, 2).unwrap();
+        assert_eq!(estimate.fee_per_byte, 2);
+    }
+
+    #[test]
+    fn test_estimate_decreases_with_confirmation_target() {
+        // The clearing fee rates of the full blocks range from 1 to 100 gates per byte.
+        let blocks: Vec<_> = (1..=100).map(|height| (height, full_block(height as i64))).collect();
+
+        let fees: Vec<_> = [1, 2, 6, 25, 100]
+            .iter()
+            .map(|target| FeeEstimate::new(100, &blocks, *target, 0).unwrap().fee_per_byte)
+            .collect();
+        assert_eq!(fees[0], 95);
+        assert!(fees.windows(2).all(|pair| pair[0] >= pair[1]), "{:?}", fees);
+        assert!(fees[4] < fees[0]);
+    }
+
+    #[test]
+    fn test_coinbase_fee_is_ignored() {
+        // A negative fee is treated as no fee.
+        let blocks = vec![(1, vec![(FULL_BLOCK_SIZE_IN_BYTES, -1000)])];
+        assert_eq!(FeeEstimate::new(1, &blocks, 1, 0).unwrap().fee_per_byte, 0);
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::prelude::*;
+
+use rand::{seq::index, Rng};
+use std::fmt;
+
+///
+/// A divergence of a derived index of the storage from the canonical block store,
+/// which would cause the index to serve wrong answers until it is repaired.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IndexDivergence<N: Network> {
+    /// A transaction of the block is missing from the transaction index.
+    MissingTransaction { block_height: u32, transaction_id: N::TransactionID },
+    /// The metadata of a transaction of the block points to another block, or another position in the block.
+    TransactionMetadata { block_height: u32, transaction_id: N::TransactionID },
+    /// A serial number of the transition is missing from the serial number index, or points to another transition.
+    SerialNumber { transition_id: N::TransitionID, serial_number: N::SerialNumber },
+    /// A commitment of the transition is missing from the commitment index, or points to another transition.
+    Commitment { transition_id: N::TransitionID, commitment: N::Commitment },
+    /// A transaction with a public record owned by the address is missing from the address index of the block.
+    AddressIndex { block_height: u32, address: Address<N>, transaction_id: N::TransactionID },
+    /// The shares of a round won by the operator are not attributed to the coinbase record of its canonical block.
+    ShareRound { block_height: u32, commitment: N::Commitment },
+}
+
+impl<N: Network> IndexDivergence<N> {
+    /// Returns the name of the diverged index.
+    pub fn index(&self) -> &'static str {
+        match self {
+            Self::MissingTransaction { .. } | Self::TransactionMetadata { .. } => "transactions",
+            Self::SerialNumber { .. } => "serial_numbers",
+            Self::Commitment { .. } => "commitments",
+            Self::AddressIndex { .. } => "address_transactions",
+            Self::ShareRound { .. } => "shares",
+        }
+    }
+}
+
+impl<N: Network> fmt::Display for IndexDivergence<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingTransaction {
+                block_height,
+                transaction_id,
+            } => write!(f, "Transaction {} of block {} is missing from the index", transaction_id, block_height),
+            Self::TransactionMetadata {
+                block_height,
+                transaction_id,
+            } => write!(f, "The metadata of transaction {} does not point to block {}", transaction_id, block_height),
+            Self::SerialNumber {
+                transition_id,
+                serial_number,
+            } => write!(f, "Serial number {} does not point to transition {}", serial_number, transition_id),
+            Self::Commitment { transition_id, commitment } => {
+                write!(f, "Commitment {} does not point to transition {}", commitment, transition_id)
+            }
+            Self::AddressIndex {
+                block_height,
+                address,
+                transaction_id,
+            } => write!(
+                f,
+                "Transaction {} of block {} is missing from the address index of {}",
+                transaction_id, block_height, address
+            ),
+            Self::ShareRound { block_height, commitment } => write!(
+                f,
+                "The shares of block {} are not attributed to its coinbase commitment {}",
+                block_height, commitment
+            ),
+        }
+    }
+}
+
+///
+/// Returns up to the given number of distinct block heights to check, in ascending order,
+/// sampled uniformly from the chain up to the given latest block height, which is always included.
+///
+pub fn sample_block_heights<R: Rng>(latest_block_height: u32, sample_size: u32, rng: &mut R) -> Vec<u32> {
+    if sample_size == 0 {
+        return vec![];
+    }
+    let number_of_blocks = latest_block_height as usize;
+    let amount = (sample_size as usize - 1).min(number_of_blocks);
+
+    let mut block_heights: Vec<u32> = index::sample(rng, number_of_blocks, amount).into_iter().map(|height| height as u32).collect();
+    block_heights.push(latest_block_height);
+    block_heights.sort_unstable();
+    block_heights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_sample_block_heights() {
+        let rng = &mut thread_rng();
+
+        // Check the sample includes the latest block, and distinct block heights in the chain.
+        let block_heights = sample_block_heights(1000, 10, rng);
+        assert_eq!(block_heights.len(), 10);
+        assert_eq!(block_heights.last(), Some(&1000));
+        assert!(block_heights.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // Check a sample larger than the chain includes every block.
+        assert_eq!(sample_block_heights(3, 10, rng), vec![0, 1, 2, 3]);
+        assert_eq!(sample_block_heights(0, 10, rng), vec![0]);
+        assert!(sample_block_heights(1000, 0, rng).is_empty());
+    }
+}
@@ -16,3 +16,21 @@
 
 pub mod block_locators;
 pub use block_locators::*;
+
+pub mod difficulty_estimate;
+pub use difficulty_estimate::*;
+
+pub mod fee_estimate;
+pub use fee_estimate::*;
+
+pub mod index_divergence;
+pub use index_divergence::*;
+
+pub mod network_stats;
+pub use network_stats::*;
+
+pub mod reward_schedule;
+pub use reward_schedule::*;
+
+pub mod timestamp_policy;
+pub use timestamp_policy::*;
@@ -0,0 +1,149 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// The number of blocks in the window of the network statistics, if none is given.
+pub const DEFAULT_NETWORK_STATS_WINDOW: u32 = 100;
+/// The maximum number of blocks in the window of the network statistics.
+pub const MAXIMUM_NETWORK_STATS_WINDOW: u32 = 10_000;
+
+///
+/// The statistics of the network over a window of recent blocks, such as its average block time and estimated proof rate.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NetworkStats {
+    /// The height of the latest block in the window.
+    pub block_height: u32,
+    /// The number of blocks in the window.
+    pub window: u32,
+    /// The difficulty target of the latest block.
+    pub difficulty_target: u64,
+    /// The difficulty of the latest block, as the expected number of proofs to find a block.
+    pub difficulty: f64,
+    /// The average difficulty of the blocks in the window.
+    pub average_difficulty: f64,
+    /// The relative change of the difficulty over the window, e.g. `0.1` for an increase of 10%.
+    pub difficulty_change: f64,
+    /// The average time between the blocks in the window, in seconds.
+    pub average_block_time_in_secs: f64,
+    /// The estimated proof rate of the network over the window, in proofs per second.
+    pub proof_rate: f64,
+}
+
+impl NetworkStats {
+    ///
+    /// Computes the network statistics from the given blocks := \[(block_height, block_timestamp, difficulty_target)\],
+    /// in ascending order of height. The first block only marks the start of the window, unless it is the only block,
+    /// in which case the block time is assumed to be the given target block interval.
+    ///
+    pub fn new(blocks: &[(u32, i64, u64)], target_block_interval_in_secs: i64) -> Result<Self> {
+        let ((_, first_timestamp, first_difficulty_target), (block_height, last_timestamp, difficulty_target)) =
+            match (blocks.first(), blocks.last()) {
+                (Some(first), Some(last)) => (*first, *last),
+                _ => return Err(anyhow!("At least one block is required to compute the network statistics")),
+            };
+
+        let window = match blocks.len() {
+            1 => blocks,
+            _ => &blocks[1..],
+        };
+        let difficulties = window
+            .iter()
+            .map(|(_, _, difficulty_target)| Self::to_difficulty(*difficulty_target));
+        let total_difficulty: f64 = difficulties.sum();
+
+        // Block timestamps are not strictly increasing, so the window spans at least one second per block.
+        let elapsed_in_secs = match blocks.len() {
+            1 => target_block_interval_in_secs.max(1),
+            _ => last_timestamp.saturating_sub(first_timestamp).max(window.len() as i64),
+        } as f64;
+
+        let difficulty = Self::to_difficulty(difficulty_target);
+        Ok(Self {
+            block_height,
+            window: window.len() as u32,
+            difficulty_target,
+            difficulty,
+            average_difficulty: total_difficulty / window.len() as f64,
+            difficulty_change: difficulty / Self::to_difficulty(first_difficulty_target) - 1.0,
+            average_block_time_in_secs: elapsed_in_secs / window.len() as f64,
+            proof_rate: total_difficulty / elapsed_in_secs,
+        })
+    }
+
+    ///
+    /// Returns the expected number of proofs to meet the given difficulty target.
+    ///
+    fn to_difficulty(difficulty_target: u64) -> f64 {
+        u64::MAX as f64 / difficulty_target.max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_difficulty() {
+        let blocks: Vec<_> = (0..=10).map(|height| (height, height as i64 * 20, u64::MAX / 1000)).collect();
+        let stats = NetworkStats::new(&blocks, 20).unwrap();
+
+        assert_eq!(stats.block_height, 10);
+        assert_eq!(stats.window, 10);
+        assert!((stats.difficulty - 1000.0).abs() < 1e-6);
+        assert!((stats.average_difficulty - 1000.0).abs() < 1e-6);
+        assert!(stats.difficulty_change.abs() < 1e-6);
+        assert!((stats.average_block_time_in_secs - 20.0).abs() < 1e-6);
+        assert!((stats.proof_rate - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rising_difficulty() {
+        // The difficulty doubles over the window, while the blocks are found twice as fast.
+        let blocks = [(1, 0, u64::MAX / 1000), (2, 10, u64::MAX / 1000), (3, 20, u64::MAX / 2000)];
+        let stats = NetworkStats::new(&blocks, 20).unwrap();
+
+        assert_eq!(stats.window, 2);
+        assert!((stats.average_difficulty - 1500.0).abs() < 1e-6);
+        assert!((stats.difficulty_change - 1.0).abs() < 1e-6);
+        assert!((stats.average_block_time_in_secs - 10.0).abs() < 1e-6);
+        assert!((stats.proof_rate - 150.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_single_block() {
+        let stats = NetworkStats::new(&[(0, 0, u64::MAX / 1000)], 20).unwrap();
+
+        assert_eq!(stats.window, 1);
+        assert!((stats.average_block_time_in_secs - 20.0).abs() < 1e-6);
+        assert!((stats.proof_rate - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_non_increasing_timestamps() {
+        let blocks = [(1, 100, u64::MAX / 1000), (2, 90, u64::MAX / 1000)];
+        let stats = NetworkStats::new(&blocks, 20).unwrap();
+        assert!(stats.average_block_time_in_secs > 0.0);
+        assert!(stats.proof_rate.is_finite());
+    }
+
+    #[test]
+    fn test_no_blocks() {
+        assert!(NetworkStats::new(&[], 20).is_err());
+    }
+}
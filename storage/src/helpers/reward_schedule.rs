@@ -0,0 +1,179 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::{Block, Network};
+
+use serde::{Deserialize, Serialize};
+
+///
+/// A range of block heights over which the coinbase reward of each block is constant.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardEpoch {
+    /// The first block height of the epoch.
+    pub start_block_height: u32,
+    /// The last block height of the epoch, or `None` if the reward never changes after its start.
+    pub end_block_height: Option<u32>,
+    /// The coinbase reward of each block in the epoch, in gates.
+    pub block_reward: i64,
+}
+
+///
+/// The emission schedule of the coinbase rewards, as the epochs of constant reward from the genesis block onwards.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardSchedule {
+    epochs: Vec<RewardEpoch>,
+}
+
+impl RewardSchedule {
+    ///
+    /// Returns the reward schedule of the given network, derived from `Block::<N>::block_reward`.
+    ///
+    pub fn for_network<N: Network>() -> Self {
+        Self::new(|block_height| Block::<N>::block_reward(block_height).0)
+    }
+
+    ///
+    /// Derives the reward schedule from the given block reward, which must be non-increasing in the block height,
+    /// by searching for the height at which each epoch ends.
+    ///
+    pub fn new(block_reward: impl Fn(u32) -> i64) -> Self {
+        let mut epochs = Vec::new();
+        let mut start_block_height = 0u32;
+        loop {
+            let reward = block_reward(start_block_height);
+            if block_reward(u32::MAX) == reward {
+                epochs.push(RewardEpoch {
+                    start_block_height,
+                    end_block_height: None,
+                    block_reward: reward,
+                });
+                return Self { epochs };
+            }
+
+            // Find the last height with the same reward, as the reward only changes once per epoch.
+            let (mut last, mut next) = (start_block_height, u32::MAX);
+            while next - last > 1 {
+                let middle = last + (next - last) / 2;
+                match block_reward(middle) == reward {
+                    true => last = middle,
+                    false => next = middle,
+                }
+            }
+
+            epochs.push(RewardEpoch {
+                start_block_height,
+                end_block_height: Some(last),
+                block_reward: reward,
+            });
+            start_block_height = next;
+        }
+    }
+
+    /// Returns the epochs of the schedule, in ascending order of height.
+    pub fn epochs(&self) -> &[RewardEpoch] {
+        &self.epochs
+    }
+
+    /// Returns the epoch of the given block height.
+    pub fn epoch(&self, block_height: u32) -> &RewardEpoch {
+        self.epochs
+            .iter()
+            .rev()
+            .find(|epoch| epoch.start_block_height <= block_height)
+            .expect("The reward schedule starts at the genesis block")
+    }
+
+    /// Returns the coinbase reward of the block at the given height, in gates.
+    pub fn block_reward(&self, block_height: u32) -> i64 {
+        self.epoch(block_height).block_reward
+    }
+
+    /// Returns the sum of the coinbase rewards of the blocks up to and including the given height, in gates.
+    pub fn total_issued(&self, block_height: u32) -> i64 {
+        self.epochs
+            .iter()
+            .take_while(|epoch| epoch.start_block_height <= block_height)
+            .map(|epoch| {
+                let end_block_height = epoch.end_block_height.map_or(block_height, |end| end.min(block_height));
+                let number_of_blocks = (end_block_height - epoch.start_block_height) as i64 + 1;
+                number_of_blocks.saturating_mul(epoch.block_reward)
+            })
+            .fold(0i64, i64::saturating_add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reward of 100 gates which halves every 1000 blocks, twice.
+    fn halving_reward(block_height: u32) -> i64 {
+        100 >> (block_height / 1000).min(2)
+    }
+
+    #[test]
+    fn test_epochs() {
+        let schedule = RewardSchedule::new(halving_reward);
+
+        let expected = [
+            RewardEpoch {
+                start_block_height: 0,
+                end_block_height: Some(999),
+                block_reward: 100,
+            },
+            RewardEpoch {
+                start_block_height: 1000,
+                end_block_height: Some(1999),
+                block_reward: 50,
+            },
+            RewardEpoch {
+                start_block_height: 2000,
+                end_block_height: None,
+                block_reward: 25,
+            },
+        ];
+        assert_eq!(schedule.epochs(), &expected);
+    }
+
+    #[test]
+    fn test_block_reward() {
+        let schedule = RewardSchedule::new(halving_reward);
+        for block_height in [0, 1, 999, 1000, 1999, 2000, u32::MAX] {
+            assert_eq!(schedule.block_reward(block_height), halving_reward(block_height));
+        }
+    }
+
+    #[test]
+    fn test_total_issued() {
+        let schedule = RewardSchedule::new(halving_reward);
+        assert_eq!(schedule.total_issued(0), 100);
+        assert_eq!(schedule.total_issued(999), 100_000);
+        assert_eq!(schedule.total_issued(1000), 100_050);
+        assert_eq!(schedule.total_issued(2500), 100_000 + 50_000 + 501 * 25);
+
+        let expected: i64 = (0..=2500).map(halving_reward).sum();
+        assert_eq!(schedule.total_issued(2500), expected);
+    }
+
+    #[test]
+    fn test_constant_reward() {
+        let schedule = RewardSchedule::new(|_| 7);
+        assert_eq!(schedule.epochs().len(), 1);
+        assert_eq!(schedule.total_issued(9), 70);
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::dpc::Network;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+///
+/// The rules on the timestamps of new blocks, which may differ from one network to another.
+///
+/// A new block timestamp must be after the median timestamp of the latest `median_time_past_blocks` blocks,
+/// and at most `future_time_limit_in_secs` ahead of the local time.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimestampPolicy {
+    /// The number of latest blocks whose median timestamp a new block timestamp must exceed.
+    pub median_time_past_blocks: u32,
+    /// The number of seconds a new block timestamp may be ahead of the local time.
+    pub future_time_limit_in_secs: i64,
+}
+
+impl TimestampPolicy {
+    ///
+    /// Returns the timestamp policy of the given network.
+    ///
+    pub fn for_network<N: Network>() -> Self {
+        let median_time_past_blocks = match N::NETWORK_ID {
+            // Testnet2 requires every block timestamp to be after the timestamp of the previous block.
+            2 => 1,
+            // Note: the median of the latest 11 blocks accepts a block timestamp earlier than that of the previous block,
+            // which the nodes requiring increasing timestamps reject. Enabling this rule on a network splits consensus
+            // between the nodes that apply it and those that do not, so it must be activated by every node at once.
+            _ => 11,
+        };
+
+        Self {
+            median_time_past_blocks,
+            future_time_limit_in_secs: N::ALEO_FUTURE_TIME_LIMIT_IN_SECS,
+        }
+    }
+
+    ///
+    /// Returns the median of the given block timestamps, or `None` if none are given.
+    /// For an even number of timestamps, the later of the two middle timestamps is returned.
+    ///
+    pub fn median_time_past(timestamps: &[i64]) -> Option<i64> {
+        let mut timestamps = timestamps.to_vec();
+        timestamps.sort_unstable();
+        timestamps.get(timestamps.len() / 2).copied()
+    }
+
+    ///
+    /// Ensures the given block timestamp is after the given median time past, and not too far ahead of the given local time.
+    ///
+    pub fn check_timestamp(&self, block_timestamp: i64, median_time_past: i64, now: i64) -> Result<()> {
+        if block_timestamp > now.saturating_add(self.future_time_limit_in_secs) {
+            return Err(anyhow!("The block timestamp {} exceeds the time limit", block_timestamp));
+        }
+        if block_timestamp <= median_time_past {
+            return Err(anyhow!(
+                "The block timestamp {} is not after the median time past {}",
+                block_timestamp,
+                median_time_past
+            ));
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the earliest valid timestamp of a new block at the given local time.
+    ///
+    pub fn next_timestamp(&self, median_time_past: i64, now: i64) -> i64 {
+        std::cmp::max(now, median_time_past.saturating_add(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_time_past() {
+        assert_eq!(TimestampPolicy::median_time_past(&[]), None);
+        assert_eq!(TimestampPolicy::median_time_past(&[5]), Some(5));
+        assert_eq!(TimestampPolicy::median_time_past(&[30, 10, 20]), Some(20));
+        assert_eq!(TimestampPolicy::median_time_past(&[10, 40, 20, 30]), Some(30));
+    }
+
+    #[test]
+    fn test_check_timestamp() {
+        let policy = TimestampPolicy {
+            median_time_past_blocks: 11,
+            future_time_limit_in_secs: 120,
+        };
+
+        assert!(policy.check_timestamp(101, 100, 1000).is_ok());
+        assert!(policy.check_timestamp(1120, 100, 1000).is_ok());
+        assert!(policy.check_timestamp(100, 100, 1000).is_err());
+        assert!(policy.check_timestamp(1121, 100, 1000).is_err());
+    }
+
+    #[test]
+    fn test_next_timestamp() {
+        let policy = TimestampPolicy {
+            median_time_past_blocks: 11,
+            future_time_limit_in_secs: 120,
+        };
+
+        assert_eq!(policy.next_timestamp(100, 1000), 1000);
+        assert_eq!(policy.next_timestamp(1000, 1000), 1001);
+        assert!(policy.check_timestamp(policy.next_timestamp(1000, 1000), 1000, 1000).is_ok());
+    }
+}
@@ -21,14 +21,40 @@
 extern crate tracing;
 
 mod helpers;
-pub use helpers::BlockLocators;
+pub use helpers::{
+    BlockLocators,
+    sample_block_heights,
+    DifficultyEstimate,
+    FeeEstimate,
+    IndexDivergence,
+    NetworkStats,
+    RewardEpoch,
+    RewardSchedule,
+    TimestampPolicy,
+    DEFAULT_NETWORK_STATS_WINDOW,
+    DIFFICULTY_ESTIMATE_PERIOD,
+    FEE_ESTIMATE_WINDOW,
+    MAXIMUM_CONFIRMATION_TARGET,
+    MAXIMUM_NETWORK_STATS_WINDOW,
+};
 
 pub(crate) mod state;
 pub use state::{
+    Contribution,
+    ContributionEpoch,
+    ContributionPeriod,
+    FoundBlock,
+    Lease,
     LedgerState,
     Metadata,
     OperatorState,
+    PayoutAddressUpdate,
+    PayoutBatch,
+    PayoutSchedule,
+    PayoutScheduleUpdate,
     ProverState,
+    Round,
+    ShareRecord,
     MAXIMUM_BLOCK_LOCATORS,
     MAXIMUM_LINEAR_BLOCK_LOCATORS,
     MAXIMUM_QUADRATIC_BLOCK_LOCATORS,
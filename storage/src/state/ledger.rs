@@ -17,7 +17,7 @@
 #[cfg(any(test, feature = "test"))]
 use crate::storage::rocksdb::RocksDB;
 use crate::{
-    helpers::BlockLocators,
+    helpers::{BlockLocators, DifficultyEstimate, FeeEstimate, IndexDivergence, NetworkStats, TimestampPolicy, FEE_ESTIMATE_WINDOW},
     storage::{DataMap, Map, MapId, Storage},
 };
 use snarkos_environment::helpers::Resource;
@@ -31,11 +31,14 @@ use rand::{CryptoRng, Rng};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    ops::{Bound, RangeBounds, RangeInclusive},
     path::Path,
-    sync::{atomic::AtomicBool, Arc},
+    sync::Arc,
     thread,
 };
+#[cfg(feature = "prover")]
+use std::sync::atomic::AtomicBool;
 use time::OffsetDateTime;
 use tokio::sync::oneshot::{self, error::TryRecvError};
 
@@ -82,6 +85,9 @@ pub struct LedgerState<N: Network> {
     latest_block_hashes_and_headers: RwLock<CircularQueue<(N::BlockHash, BlockHeader<N>)>>,
     /// The block locators from the latest block of the ledger.
     latest_block_locators: RwLock<BlockLocators<N>>,
+    /// The transaction sizes and fees of the latest blocks in the fee estimate window, excluding the genesis block
+    /// := \[(block_height, block_hash, \[(transaction_size_in_bytes, transaction_fee)\])\]
+    latest_block_fees: RwLock<VecDeque<(u32, N::BlockHash, Vec<(usize, i64)>)>>,
     /// The ledger root corresponding to each block height.
     ledger_roots: DataMap<N::LedgerRoot, u32>,
     /// The blocks of the ledger in storage.
@@ -91,6 +97,8 @@ pub struct LedgerState<N: Network> {
     map_lock: Arc<RwLock<()>>,
     /// Coinbase cache.
     coinbase_cache: RwLock<(Option<Transaction<N>>, Option<Record<N>>)>,
+    /// The rules on the timestamps of new blocks, which are fixed for each network.
+    timestamp_policy: TimestampPolicy,
     read_only: (bool, RwLock<Block<N>>),
 }
 
@@ -122,10 +130,12 @@ impl<N: Network> LedgerState<N> {
             latest_block: RwLock::new(N::genesis_block().clone()),
             latest_block_hashes_and_headers: RwLock::new(CircularQueue::with_capacity(MAXIMUM_LINEAR_BLOCK_LOCATORS as usize)),
             latest_block_locators: Default::default(),
+            latest_block_fees: Default::default(),
             ledger_roots: storage.open_map(MapId::LedgerRoots)?,
             blocks: BlockState::open(storage)?,
             map_lock: Default::default(),
             coinbase_cache: RwLock::new((None, None)),
+            timestamp_policy: TimestampPolicy::for_network::<N>(),
             read_only: (is_read_only, RwLock::new(N::genesis_block().clone())),
         };
 
@@ -256,16 +266,34 @@ impl<N: Network> LedgerState<N> {
         let is_read_only = true;
         let storage = S::open(path, context, is_read_only)?;
 
+        Self::open_reader_with_storage(storage)
+    }
+
+    ///
+    /// Opens a read-only instance of `LedgerState` from the given storage path, as a secondary instance
+    /// which keeps its own state at the given secondary path, e.g. for a node serving a ledger synced by another node.
+    ///
+    pub fn open_secondary_reader<S: Storage, P: AsRef<Path>>(path: P, secondary_path: P) -> Result<(Arc<Self>, Resource)> {
+        let storage = S::open_secondary(path, secondary_path, N::NETWORK_ID)?;
+        Self::open_reader_with_storage(storage)
+    }
+
+    /// Opens a read-only instance of `LedgerState` from the given read-only storage.
+    fn open_reader_with_storage<S: Storage>(storage: S) -> Result<(Arc<Self>, Resource)> {
+        let is_read_only = true;
+
         // Initialize the ledger.
         let ledger = Arc::new(Self {
             ledger_tree: RwLock::new(LedgerTree::<N>::new()?),
             latest_block: RwLock::new(N::genesis_block().clone()),
             latest_block_hashes_and_headers: RwLock::new(CircularQueue::with_capacity(MAXIMUM_LINEAR_BLOCK_LOCATORS as usize)),
             latest_block_locators: Default::default(),
+            latest_block_fees: Default::default(),
             ledger_roots: storage.open_map(MapId::LedgerRoots)?,
             blocks: BlockState::open(storage)?,
             map_lock: Default::default(),
             coinbase_cache: RwLock::new((None, None)),
+            timestamp_policy: TimestampPolicy::for_network::<N>(),
             read_only: (is_read_only, RwLock::new(N::genesis_block().clone())),
         });
 
@@ -356,6 +384,29 @@ impl<N: Network> LedgerState<N> {
         self.latest_block.read().transactions().clone().into()
     }
 
+    /// Returns the rules on the timestamps of new blocks.
+    pub fn timestamp_policy(&self) -> TimestampPolicy {
+        self.timestamp_policy
+    }
+
+    /// Returns the median timestamp of the latest blocks, which the timestamp of the next block must exceed.
+    pub fn latest_median_time_past(&self) -> Result<i64> {
+        self.median_time_past_of(self.timestamp_policy.median_time_past_blocks)
+    }
+
+    /// Returns the median timestamp of the given number of latest blocks.
+    pub(crate) fn median_time_past_of(&self, number_of_blocks: u32) -> Result<i64> {
+        let latest_block_height = self.latest_block_height();
+        let number_of_blocks = number_of_blocks.max(1);
+        let start_block_height = latest_block_height.saturating_sub(number_of_blocks - 1);
+        let timestamps: Vec<_> = self
+            .get_block_headers(start_block_height, latest_block_height)?
+            .iter()
+            .map(|header| header.timestamp())
+            .collect();
+        TimestampPolicy::median_time_past(&timestamps).ok_or_else(|| anyhow!("Missing the latest block headers"))
+    }
+
     /// Returns the latest block locators.
     pub fn latest_block_locators(&self) -> BlockLocators<N> {
         self.latest_block_locators.read().clone()
@@ -387,26 +438,31 @@ impl<N: Network> LedgerState<N> {
     }
 
     /// Returns `true` if the given serial number exists in storage.
+    #[tracing::instrument(level = "trace", skip_all)]
     pub fn contains_serial_number(&self, serial_number: &N::SerialNumber) -> Result<bool> {
         self.blocks.contains_serial_number(serial_number)
     }
 
     /// Returns `true` if the given commitment exists in storage.
+    #[tracing::instrument(level = "trace", skip_all)]
     pub fn contains_commitment(&self, commitment: &N::Commitment) -> Result<bool> {
         self.blocks.contains_commitment(commitment)
     }
 
     /// Returns the record ciphertext for a given commitment.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn get_ciphertext(&self, commitment: &N::Commitment) -> Result<N::RecordCiphertext> {
         self.blocks.get_ciphertext(commitment)
     }
 
     /// Returns the transition for a given transition ID.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn get_transition(&self, transition_id: &N::TransitionID) -> Result<Transition<N>> {
         self.blocks.get_transition(transition_id)
     }
 
     /// Returns the transaction for a given transaction ID.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn get_transaction(&self, transaction_id: &N::TransactionID) -> Result<Transaction<N>> {
         self.blocks.get_transaction(transaction_id)
     }
@@ -442,35 +498,175 @@ impl<N: Network> LedgerState<N> {
     }
 
     /// Returns the block header for the given block height.
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn get_block_header(&self, block_height: u32) -> Result<BlockHeader<N>> {
         self.blocks.get_block_header(block_height)
     }
 
+    /// Returns the expected difficulty target of a block at the given height and timestamp, given the preceding blocks.
+    pub fn get_difficulty_target(&self, block_timestamp: i64, block_height: u32) -> Result<u64> {
+        if N::NETWORK_ID == 2 && block_height <= snarkvm::dpc::testnet2::V12_UPGRADE_BLOCK_HEIGHT {
+            let previous_block_header = self.get_block_header(block_height.saturating_sub(1))?;
+            Ok(Blocks::<N>::compute_difficulty_target(&previous_block_header, block_timestamp, block_height))
+        } else if N::NETWORK_ID == 2 {
+            let anchor_block_header = self.get_block_header(snarkvm::dpc::testnet2::V12_UPGRADE_BLOCK_HEIGHT)?;
+            Ok(Blocks::<N>::compute_difficulty_target(&anchor_block_header, block_timestamp, block_height))
+        } else {
+            Ok(Blocks::<N>::compute_difficulty_target(N::genesis_block().header(), block_timestamp, block_height))
+        }
+    }
+
+    /// Returns a smoothed estimate of the network difficulty over the latest blocks.
+    pub fn get_network_difficulty_estimate(&self) -> Result<DifficultyEstimate> {
+        // Skip the genesis block, as its timestamp does not reflect the block interval.
+        let mut blocks: Vec<_> = self
+            .latest_block_hashes_and_headers
+            .read()
+            .asc_iter()
+            .filter(|(_, header)| header.height() != 0)
+            .map(|(_, header)| (header.height(), header.timestamp(), header.difficulty_target()))
+            .collect();
+        if blocks.is_empty() {
+            let header = self.latest_block_header();
+            blocks.push((header.height(), header.timestamp(), header.difficulty_target()));
+        }
+        DifficultyEstimate::new(&blocks, N::ALEO_BLOCK_TIME_IN_SECS)
+    }
+
+    /// Returns the network statistics over the given number of latest blocks, excluding the genesis block.
+    pub fn get_network_stats(&self, window: u32) -> Result<NetworkStats> {
+        let latest_block_height = self.latest_block_height();
+        // The block preceding the window marks its start. The genesis block is skipped unless it is the only block,
+        // as its timestamp does not reflect the block interval.
+        let start_block_height = latest_block_height.saturating_sub(window.max(1)).max(1).min(latest_block_height);
+        let blocks: Vec<_> = self
+            .get_block_headers(start_block_height, latest_block_height)?
+            .iter()
+            .map(|header| (header.height(), header.timestamp(), header.difficulty_target()))
+            .collect();
+        NetworkStats::new(&blocks, N::ALEO_BLOCK_TIME_IN_SECS)
+    }
+
+    /// Returns the fee estimate for the given confirmation target over the latest blocks, excluding the genesis block,
+    /// and no less than the given minimum fee per byte.
+    pub fn get_fee_estimate(&self, confirmation_target: u32, minimum_fee_per_byte: u64) -> Result<FeeEstimate> {
+        let blocks: Vec<_> = self
+            .latest_block_fees
+            .read()
+            .iter()
+            .map(|(block_height, _, transaction_fees)| (*block_height, transaction_fees.clone()))
+            .collect();
+        FeeEstimate::new(self.latest_block_height(), &blocks, confirmation_target, minimum_fee_per_byte)
+    }
+
+    /// Returns the sizes and fees of the given transactions of a block := \[(transaction_size_in_bytes, transaction_fee)\],
+    /// excluding the coinbase transaction.
+    fn to_transaction_fees(transactions: &Transactions<N>) -> Result<Vec<(usize, i64)>> {
+        // The coinbase transaction pays no fee, and is the only transaction with a negative value balance.
+        transactions
+            .iter()
+            .filter(|transaction| !transaction.value_balance().is_negative())
+            .map(|transaction| Ok((transaction.to_bytes_le()?.len(), transaction.value_balance().0)))
+            .collect()
+    }
+
     /// Returns the block headers from the given `start_block_height` to `end_block_height` (inclusive).
     pub fn get_block_headers(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<BlockHeader<N>>> {
         self.blocks.get_block_headers(start_block_height, end_block_height)
     }
 
     /// Returns the transactions from the block of the given block height.
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn get_block_transactions(&self, block_height: u32) -> Result<Transactions<N>> {
         self.blocks.get_block_transactions(block_height)
     }
 
     /// Returns the block for a given block height.
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn get_block(&self, block_height: u32) -> Result<Block<N>> {
         self.blocks.get_block(block_height)
     }
 
     /// Returns the blocks from the given `start_block_height` to `end_block_height` (inclusive).
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn get_blocks(&self, start_block_height: u32, end_block_height: u32) -> Result<Vec<Block<N>>> {
         self.blocks.get_blocks(start_block_height, end_block_height)
     }
 
+    /// Returns an iterator over the blocks in the given range of block heights, up to the latest block height.
+    /// Each block is retrieved from storage as the iterator is advanced, so the range is never held in memory.
+    pub fn iter_blocks<R: RangeBounds<u32>>(&self, range: R) -> impl Iterator<Item = Result<Block<N>>> + '_ {
+        self.to_block_heights(range).map(move |block_height| self.get_block(block_height))
+    }
+
+    /// Returns an iterator over the block headers in the given range of block heights, up to the latest block height.
+    /// Each block header is retrieved from storage as the iterator is advanced, so the range is never held in memory.
+    pub fn iter_headers<R: RangeBounds<u32>>(&self, range: R) -> impl Iterator<Item = Result<BlockHeader<N>>> + '_ {
+        self.to_block_heights(range)
+            .map(move |block_height| self.get_block_header(block_height))
+    }
+
+    /// Returns `true` if the address index is enabled.
+    pub fn is_address_index_enabled(&self) -> Result<bool> {
+        self.blocks.addresses.is_enabled()
+    }
+
+    /// Returns the IDs of the transactions with public records owned by the given address,
+    /// with their block heights, in the given range of block heights (inclusive).
+    pub fn get_transactions_for_address(
+        &self,
+        address: &Address<N>,
+        start_block_height: u32,
+        end_block_height: u32,
+    ) -> Result<Vec<(u32, N::TransactionID)>> {
+        if !self.is_address_index_enabled()? {
+            return Err(anyhow!("The address index is not enabled"));
+        }
+
+        let mut transactions = Vec::new();
+        for block_height in self.to_block_heights(start_block_height..=end_block_height) {
+            for transaction_id in self.blocks.addresses.get_transaction_ids(address, block_height)? {
+                // Skip the stale entries of reverted blocks, which may remain if the blocks were cleared directly.
+                match self.get_transaction_metadata(&transaction_id) {
+                    Ok(metadata) if metadata.block_height == block_height => transactions.push((block_height, transaction_id)),
+                    _ => continue,
+                }
+            }
+        }
+        Ok(transactions)
+    }
+
+    ///
+    /// Returns the divergences of the derived indexes of the given block from the canonical block store,
+    /// which are the transaction metadata, the serial numbers, the commitments, and the address index, if it is enabled.
+    ///
+    pub fn check_indexes(&self, block_height: u32) -> Result<Vec<IndexDivergence<N>>> {
+        self.blocks.check_indexes(block_height)
+    }
+
     /// Returns the ledger root in the block header of the given block height.
     pub fn get_previous_ledger_root(&self, block_height: u32) -> Result<N::LedgerRoot> {
         self.blocks.get_previous_ledger_root(block_height)
     }
 
+    /// Returns the block heights in the given range, bounded by the latest block height.
+    fn to_block_heights<R: RangeBounds<u32>>(&self, range: R) -> RangeInclusive<u32> {
+        let start_block_height = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let latest_block_height = self.latest_block_height();
+        let end_block_height = match range.end_bound() {
+            Bound::Included(end) => (*end).min(latest_block_height),
+            // An empty range ends before the genesis block.
+            Bound::Excluded(0) => return 1..=0,
+            Bound::Excluded(end) => (end - 1).min(latest_block_height),
+            Bound::Unbounded => latest_block_height,
+        };
+        start_block_height..=end_block_height
+    }
+
     /// Returns the block locators of the current ledger, from the given block height.
     pub fn get_block_locators(&self, block_height: u32) -> Result<BlockLocators<N>> {
         // Initialize the current block height that a block locator is obtained from.
@@ -603,6 +799,7 @@ impl<N: Network> LedgerState<N> {
     }
 
     /// Returns a block template based on the latest state of the ledger.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn get_block_template<R: Rng + CryptoRng>(
         &self,
         recipient: Address<N>,
@@ -617,21 +814,14 @@ impl<N: Network> LedgerState<N> {
         // Prepare the new block.
         let previous_block_hash = latest_block.hash();
         let block_height = latest_block.height().saturating_add(1);
-        // Ensure that the new timestamp is ahead of the previous timestamp.
-        let block_timestamp = std::cmp::max(
-            OffsetDateTime::now_utc().unix_timestamp(),
-            latest_block.timestamp().saturating_add(1),
-        );
+        // Ensure that the new timestamp is ahead of the median time past.
+        let median_time_past = self.latest_median_time_past()?;
+        let block_timestamp = self
+            .timestamp_policy()
+            .next_timestamp(median_time_past, OffsetDateTime::now_utc().unix_timestamp());
 
         // Compute the block difficulty target.
-        let difficulty_target = if N::NETWORK_ID == 2 && block_height <= snarkvm::dpc::testnet2::V12_UPGRADE_BLOCK_HEIGHT {
-            Blocks::<N>::compute_difficulty_target(latest_block.header(), block_timestamp, block_height)
-        } else if N::NETWORK_ID == 2 {
-            let anchor_block_header = self.get_block_header(snarkvm::dpc::testnet2::V12_UPGRADE_BLOCK_HEIGHT)?;
-            Blocks::<N>::compute_difficulty_target(&anchor_block_header, block_timestamp, block_height)
-        } else {
-            Blocks::<N>::compute_difficulty_target(N::genesis_block().header(), block_timestamp, block_height)
-        };
+        let difficulty_target = self.get_difficulty_target(block_timestamp, block_height)?;
 
         // Compute the cumulative weight.
         let cumulative_weight = latest_block
@@ -713,6 +903,7 @@ impl<N: Network> LedgerState<N> {
     }
 
     /// Mines a new block using the latest state of the given ledger.
+    #[cfg(feature = "prover")]
     pub fn mine_next_block<R: Rng + CryptoRng>(
         &self,
         recipient: Address<N>,
@@ -731,13 +922,8 @@ impl<N: Network> LedgerState<N> {
         }
     }
 
-    /// Adds the given block as the next block in the ledger to storage.
-    pub fn add_next_block(&self, block: &Block<N>) -> Result<()> {
-        // If the storage is in read-only mode, this method cannot be called.
-        if self.is_read_only() {
-            return Err(anyhow!("Ledger is in read-only mode"));
-        }
-
+    /// Checks that the given block is valid as the next block in the ledger, without adding it.
+    pub fn check_next_block(&self, block: &Block<N>) -> Result<()> {
         // Ensure the block itself is valid.
         if !block.is_valid() {
             return Err(anyhow!("Block {} is invalid", block.height()));
@@ -764,26 +950,13 @@ impl<N: Network> LedgerState<N> {
             ));
         }
 
-        // Ensure the next block timestamp is within the declared time limit.
+        // Ensure the next block timestamp is after the median time past, and within the declared time limit.
         let now = OffsetDateTime::now_utc().unix_timestamp();
-        if block.timestamp() > (now + N::ALEO_FUTURE_TIME_LIMIT_IN_SECS) {
-            return Err(anyhow!("The given block timestamp exceeds the time limit"));
-        }
-
-        // Ensure the next block timestamp is after the current block timestamp.
-        if block.timestamp() <= current_block.timestamp() {
-            return Err(anyhow!("The given block timestamp is before the current timestamp"));
-        }
+        self.timestamp_policy()
+            .check_timestamp(block.timestamp(), self.latest_median_time_past()?, now)?;
 
         // Compute the expected difficulty target.
-        let expected_difficulty_target = if N::NETWORK_ID == 2 && block_height <= snarkvm::dpc::testnet2::V12_UPGRADE_BLOCK_HEIGHT {
-            Blocks::<N>::compute_difficulty_target(current_block.header(), block.timestamp(), block.height())
-        } else if N::NETWORK_ID == 2 {
-            let anchor_block_header = self.get_block_header(snarkvm::dpc::testnet2::V12_UPGRADE_BLOCK_HEIGHT)?;
-            Blocks::<N>::compute_difficulty_target(&anchor_block_header, block.timestamp(), block.height())
-        } else {
-            Blocks::<N>::compute_difficulty_target(N::genesis_block().header(), block.timestamp(), block.height())
-        };
+        let expected_difficulty_target = self.get_difficulty_target(block.timestamp(), block_height)?;
 
         // Ensure the expected difficulty target is met.
         if block.difficulty_target() != expected_difficulty_target {
@@ -858,6 +1031,21 @@ impl<N: Network> LedgerState<N> {
             }
         }
 
+        Ok(())
+    }
+
+    /// Adds the given block as the next block in the ledger to storage.
+    #[tracing::instrument(level = "debug", skip_all, fields(block_height = block.height()))]
+    pub fn add_next_block(&self, block: &Block<N>) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only() {
+            return Err(anyhow!("Ledger is in read-only mode"));
+        }
+
+        // Ensure the block is valid as the next block in the ledger.
+        self.check_next_block(block)?;
+        let transaction_fees = Self::to_transaction_fees(block.transactions())?;
+
         // Perform all the associated storage operations as an atomic batch.
         let batch = self.ledger_roots.prepare_batch();
 
@@ -874,6 +1062,13 @@ impl<N: Network> LedgerState<N> {
             .write()
             .push((block.hash(), block.header().clone()));
         *self.latest_block_locators.write() = self.get_block_locators(block.height())?;
+        {
+            let mut latest_block_fees = self.latest_block_fees.write();
+            latest_block_fees.push_back((block.height(), block.hash(), transaction_fees));
+            if latest_block_fees.len() > FEE_ESTIMATE_WINDOW as usize {
+                latest_block_fees.pop_front();
+            }
+        }
         *self.latest_block.write() = block.clone();
 
         Ok(())
@@ -947,9 +1142,36 @@ impl<N: Network> LedgerState<N> {
         Ok(blocks.values().skip(1).cloned().collect())
     }
 
+    ///
+    /// Enables or disables the address index, which maps the owners of public records to their transactions.
+    /// Enabling the index indexes every block in the ledger, and disabling the index clears it.
+    /// This method should be called before the ledger begins to add blocks.
+    ///
+    pub fn set_address_index(&self, is_enabled: bool) -> Result<()> {
+        // If the storage is in read-only mode, this method cannot be called.
+        if self.is_read_only() {
+            return Err(anyhow!("Ledger is in read-only mode"));
+        }
+
+        if is_enabled == self.is_address_index_enabled()? {
+            return Ok(());
+        }
+
+        // The index is only marked as enabled once every block is indexed,
+        // so an interrupted build is restarted the next time the index is enabled.
+        if is_enabled {
+            info!("Building the address index up to block {}", self.latest_block_height());
+            for block in self.iter_blocks(..) {
+                self.blocks.addresses.add_block(&block?, None)?;
+            }
+        }
+        self.blocks.addresses.set_enabled(is_enabled)
+    }
+
     ///
     /// Returns a ledger proof for the given commitment.
     ///
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn get_ledger_inclusion_proof(&self, commitment: N::Commitment) -> Result<LedgerProof<N>> {
         // TODO (raychu86): Add getter functions.
         let commitment_transition_id = match self.blocks.transactions.commitments.get(&commitment)? {
@@ -1037,6 +1259,37 @@ impl<N: Network> LedgerState<N> {
         }
 
         *self.latest_block_locators.write() = self.get_block_locators(end_block_height)?;
+        self.update_latest_block_fees()?;
+
+        Ok(())
+    }
+
+    /// Updates the transaction sizes and fees of the latest blocks up to the latest block height,
+    /// only reading the blocks that are new to the fee estimate window.
+    fn update_latest_block_fees(&self) -> Result<()> {
+        // Compute the start block height and end block height (inclusive), excluding the genesis block.
+        let end_block_height = self.latest_block_height();
+        let start_block_height = end_block_height.saturating_sub(FEE_ESTIMATE_WINDOW - 1).max(1);
+
+        let mut latest_block_fees = self.latest_block_fees.write();
+
+        // Remove the reverted blocks, which are the latest ones, and the blocks that fell out of the window.
+        while let Some((block_height, block_hash, _)) = latest_block_fees.back() {
+            if *block_height <= end_block_height && self.get_block_hash(*block_height)? == *block_hash {
+                break;
+            }
+            latest_block_fees.pop_back();
+        }
+        while latest_block_fees.front().map_or(false, |(block_height, ..)| *block_height < start_block_height) {
+            latest_block_fees.pop_front();
+        }
+
+        // Add the blocks that are new to the window.
+        let next_block_height = latest_block_fees.back().map_or(start_block_height, |(block_height, ..)| block_height + 1);
+        for block_height in next_block_height..=end_block_height {
+            let transaction_fees = Self::to_transaction_fees(&self.get_block_transactions(block_height)?)?;
+            latest_block_fees.push_back((block_height, self.get_block_hash(block_height)?, transaction_fees));
+        }
 
         Ok(())
     }
@@ -1345,6 +1598,7 @@ struct BlockState<N: Network> {
     block_headers: DataMap<N::BlockHash, BlockHeader<N>>,
     block_transactions: DataMap<N::BlockHash, Vec<N::TransactionID>>,
     transactions: TransactionState<N>,
+    addresses: AddressState<N>,
 }
 
 impl<N: Network> BlockState<N> {
@@ -1354,6 +1608,7 @@ impl<N: Network> BlockState<N> {
             block_heights: storage.open_map(MapId::BlockHeights)?,
             block_headers: storage.open_map(MapId::BlockHeaders)?,
             block_transactions: storage.open_map(MapId::BlockTransactions)?,
+            addresses: AddressState::open(&storage)?,
             transactions: TransactionState::open(storage)?,
         })
     }
@@ -1532,6 +1787,52 @@ impl<N: Network> BlockState<N> {
         Ok(block_header.previous_ledger_root())
     }
 
+    /// Returns the divergences of the derived indexes of the given block from its transactions.
+    fn check_indexes(&self, block_height: u32) -> Result<Vec<IndexDivergence<N>>> {
+        // Retrieve the block hash.
+        let block_hash = self.get_block_hash(block_height)?;
+
+        // Retrieve the block transaction IDs.
+        let transaction_ids = match self.block_transactions.get(&block_hash)? {
+            Some(transaction_ids) => transaction_ids,
+            None => return Err(anyhow!("Block {} missing from block transactions map", block_hash)),
+        };
+
+        let mut divergences = Vec::new();
+        let mut transactions = Vec::with_capacity(transaction_ids.len());
+        for (index, transaction_id) in transaction_ids.into_iter().enumerate() {
+            match self.transactions.transactions.get(&transaction_id)? {
+                Some((_, _, metadata))
+                    if metadata.block_height == block_height
+                        && metadata.block_hash == block_hash
+                        && metadata.transaction_index as usize == index => {}
+                Some(_) => divergences.push(IndexDivergence::TransactionMetadata {
+                    block_height,
+                    transaction_id,
+                }),
+                None => {
+                    divergences.push(IndexDivergence::MissingTransaction {
+                        block_height,
+                        transaction_id,
+                    });
+                    continue;
+                }
+            }
+            let transaction = self.transactions.get_transaction(&transaction_id)?;
+            divergences.extend(self.transactions.check_indexes(&transaction)?);
+            transactions.push(transaction);
+        }
+        if self.addresses.is_enabled()? {
+            divergences.extend(self.addresses.check_indexes(block_height, &transactions)?);
+        }
+
+        // Discard the divergences if the block was reverted during the check.
+        match self.get_block_hash(block_height) {
+            Ok(latest_block_hash) if latest_block_hash == block_hash => Ok(divergences),
+            _ => Ok(vec![]),
+        }
+    }
+
     /// Adds the given block to storage.
     fn add_block(&self, block: &Block<N>, batch: Option<usize>) -> Result<()> {
         // Ensure the block does not exist.
@@ -1555,6 +1856,10 @@ impl<N: Network> BlockState<N> {
                 let metadata = Metadata::<N>::new(block_height, block_hash, block.timestamp(), index as u16);
                 self.transactions.add_transaction(transaction, metadata, batch)?;
             }
+            // Insert the addresses, if the address index is enabled.
+            if self.addresses.is_enabled()? {
+                self.addresses.add_block(block, batch)?;
+            }
 
             Ok(())
         }
@@ -1594,6 +1899,14 @@ impl<N: Network> BlockState<N> {
             self.block_headers.remove(&block_hash, batch)?;
             // Remove the block transactions.
             self.block_transactions.remove(&block_hash, batch)?;
+            // Remove the addresses, if the address index is enabled.
+            if self.addresses.is_enabled()? {
+                let transactions = transaction_ids
+                    .iter()
+                    .map(|transaction_id| self.transactions.get_transaction(transaction_id))
+                    .collect::<Result<Vec<_>>>()?;
+                self.addresses.remove_block(block_height, &transactions, batch)?;
+            }
             // Remove the transactions.
             for transaction_ids in transaction_ids.iter() {
                 self.transactions.remove_transaction(transaction_ids, batch)?;
@@ -1700,6 +2013,31 @@ impl<N: Network> TransactionState<N> {
         }
     }
 
+    /// Returns the divergences of the serial numbers and commitments of the given transaction from its transitions.
+    fn check_indexes(&self, transaction: &Transaction<N>) -> Result<Vec<IndexDivergence<N>>> {
+        let mut divergences = Vec::new();
+        for transition in transaction.transitions() {
+            let transition_id = transition.transition_id();
+            for serial_number in transition.serial_numbers() {
+                if self.serial_numbers.get(serial_number)? != Some(transition_id) {
+                    divergences.push(IndexDivergence::SerialNumber {
+                        transition_id,
+                        serial_number: *serial_number,
+                    });
+                }
+            }
+            for commitment in transition.commitments() {
+                if self.commitments.get(commitment)? != Some(transition_id) {
+                    divergences.push(IndexDivergence::Commitment {
+                        transition_id,
+                        commitment: *commitment,
+                    });
+                }
+            }
+        }
+        Ok(divergences)
+    }
+
     /// Adds the given transaction to storage.
     fn add_transaction(&self, transaction: &Transaction<N>, metadata: Metadata<N>, batch: Option<usize>) -> Result<()> {
         // Ensure the transaction does not exist.
@@ -1768,3 +2106,95 @@ impl<N: Network> TransactionState<N> {
         Ok(())
     }
 }
+
+#[derive(Clone, Debug)]
+#[allow(clippy::type_complexity)]
+struct AddressState<N: Network> {
+    /// The transactions with public records owned by each address in each block := ((address, block_height), transaction_ids)
+    address_transactions: DataMap<(Address<N>, u32), Vec<N::TransactionID>>,
+    /// The indicator that the address index is enabled, and complete up to the latest block.
+    address_index: DataMap<(), bool>,
+}
+
+impl<N: Network> AddressState<N> {
+    /// Initializes a new instance of `AddressState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            address_transactions: storage.open_map(MapId::AddressTransactions)?,
+            address_index: storage.open_map(MapId::AddressIndex)?,
+        })
+    }
+
+    /// Returns `true` if the address index is enabled.
+    fn is_enabled(&self) -> Result<bool> {
+        Ok(self.address_index.get(&())?.unwrap_or(false))
+    }
+
+    /// Returns the transaction IDs of the given address in the given block, which may include stale entries of reverted blocks.
+    fn get_transaction_ids(&self, address: &Address<N>, block_height: u32) -> Result<Vec<N::TransactionID>> {
+        Ok(self.address_transactions.get(&(*address, block_height))?.unwrap_or_default())
+    }
+
+    /// Adds the addresses of the given block to storage.
+    fn add_block(&self, block: &Block<N>, batch: Option<usize>) -> Result<()> {
+        for (address, transaction_ids) in Self::to_address_transactions(block.transactions().iter()) {
+            self.address_transactions.insert(&(address, block.height()), &transaction_ids, batch)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the addresses of the given transactions in the given block from storage.
+    fn remove_block(&self, block_height: u32, transactions: &[Transaction<N>], batch: Option<usize>) -> Result<()> {
+        for address in Self::to_address_transactions(transactions.iter()).keys() {
+            self.address_transactions.remove(&(*address, block_height), batch)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the divergences of the address index of the given block from its transactions.
+    fn check_indexes(&self, block_height: u32, transactions: &[Transaction<N>]) -> Result<Vec<IndexDivergence<N>>> {
+        let mut divergences = Vec::new();
+        for (address, transaction_ids) in Self::to_address_transactions(transactions.iter()) {
+            let indexed_transaction_ids = self.get_transaction_ids(&address, block_height)?;
+            for transaction_id in transaction_ids {
+                if !indexed_transaction_ids.contains(&transaction_id) {
+                    divergences.push(IndexDivergence::AddressIndex {
+                        block_height,
+                        address,
+                        transaction_id,
+                    });
+                }
+            }
+        }
+        Ok(divergences)
+    }
+
+    /// Marks the address index as enabled, or as disabled and clears it.
+    fn set_enabled(&self, is_enabled: bool) -> Result<()> {
+        self.address_index.insert(&(), &is_enabled, None)?;
+        if !is_enabled {
+            for key in self.address_transactions.keys() {
+                self.address_transactions.remove(&key, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the owners of the public records of nonzero value in the given transactions, with the IDs of their transactions.
+    /// Records that are not public, such as the coinbase records of private blocks, cannot be decrypted, and are not included.
+    fn to_address_transactions<'a>(
+        transactions: impl Iterator<Item = &'a Transaction<N>>,
+    ) -> HashMap<Address<N>, Vec<N::TransactionID>> {
+        let mut address_transactions = HashMap::<_, Vec<_>>::new();
+        for transaction in transactions {
+            let transaction_id = transaction.transaction_id();
+            for record in transaction.to_records().filter(|record| record.value() != AleoAmount::ZERO) {
+                let transaction_ids = address_transactions.entry(record.owner()).or_default();
+                if !transaction_ids.contains(&transaction_id) {
+                    transaction_ids.push(transaction_id);
+                }
+            }
+        }
+        address_transactions
+    }
+}
@@ -18,7 +18,20 @@ pub(crate) mod ledger;
 pub use ledger::{LedgerState, Metadata, MAXIMUM_BLOCK_LOCATORS, MAXIMUM_LINEAR_BLOCK_LOCATORS, MAXIMUM_QUADRATIC_BLOCK_LOCATORS};
 
 pub(crate) mod operator;
-pub use operator::OperatorState;
+pub use operator::{
+    Contribution,
+    ContributionEpoch,
+    ContributionPeriod,
+    FoundBlock,
+    Lease,
+    OperatorState,
+    PayoutAddressUpdate,
+    PayoutBatch,
+    PayoutSchedule,
+    PayoutScheduleUpdate,
+    Round,
+    ShareRecord,
+};
 
 pub(crate) mod prover;
 pub use prover::ProverState;
@@ -14,19 +14,255 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::storage::{DataMap, Map, MapId, Storage};
+use crate::{
+    helpers::IndexDivergence,
+    storage::{DataMap, Map, MapId, Storage},
+    LedgerState,
+};
 use snarkvm::dpc::prelude::*;
 
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
     iter::FromIterator,
     path::Path,
 };
 
+///
+/// The statistics of the operator for a single block height.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Round<N: Network> {
+    /// The UNIX timestamp of the first share for this block height.
+    pub started_at: i64,
+    /// The number of valid shares submitted for this block height.
+    pub shares: u64,
+    /// The number of blocks the shares were expected to find, given the network difficulty.
+    pub expected_blocks: f64,
+    /// The block found by the operator for this block height := (block_hash, found_at)
+    pub block: Option<(N::BlockHash, i64)>,
+}
+
+///
+/// The provenance of a block found by the operator, linking it to the share that satisfied the network target.
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FoundBlock<N: Network> {
+    /// The hash of the found block.
+    pub block_hash: N::BlockHash,
+    /// The prover which submitted the winning share, or `None` if it was submitted as a block.
+    pub prover: Option<Address<N>>,
+    /// The worker which submitted the winning share, as its peer IP, or `http` if it was submitted over RPC.
+    pub worker: String,
+    /// The header root of the block template, which identifies the job the share was computed for.
+    pub job_id: N::BlockHeaderRoot,
+    /// The lease the job was built for, or `None` for the jobs of the operator.
+    pub lease_id: Option<u64>,
+    /// The share difficulty of the prover when the share was submitted, or `None` if it was submitted as a block.
+    pub share_difficulty: Option<u64>,
+    /// The nonce of the winning share.
+    pub nonce: N::PoSWNonce,
+    /// The block template the share was computed for.
+    pub block_template: BlockTemplate<N>,
+    /// The UNIX timestamp at which the share was submitted.
+    pub submitted_at: i64,
+}
+
+///
+/// A share submitted by a prover, as recorded in the share history of the operator.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareRecord {
+    /// The UNIX timestamp at which the share was submitted.
+    pub submitted_at: i64,
+    /// The worker which submitted the share, as its peer IP, or `http` if it was submitted over RPC.
+    pub worker: String,
+    /// The block height of the job the share was computed for, or `None` if the share was rejected before it was known.
+    pub block_height: Option<u32>,
+    /// The share difficulty of the prover, or `None` if the share was rejected before it was known.
+    pub share_difficulty: Option<u64>,
+    /// `true` if the share was accepted, and attributed to the prover.
+    pub is_accepted: bool,
+    /// The reason the share was rejected, if it was rejected.
+    pub rejection_reason: Option<String>,
+}
+
+///
+/// An update of the payout address of a prover, signed by the account of the prover.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PayoutAddressUpdate<N: Network> {
+    /// The address receiving the payouts of the prover.
+    pub payout_address: Address<N>,
+    /// The block height of the first round whose payouts are redirected to the payout address.
+    pub effective_from: u32,
+    /// The signature of the prover over the update.
+    pub signature: N::AccountSignature,
+    /// The UNIX timestamp at which the update was recorded.
+    pub updated_at: i64,
+}
+
+///
+/// The schedule on which a prover is paid, once its balance reaches the minimum payout.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutSchedule {
+    /// The minimum balance paid out to the prover, in gates.
+    pub minimum_payout: u64,
+    /// The minimum number of blocks between two payouts to the prover.
+    pub payout_interval: u32,
+}
+
+///
+/// An update of the payout schedule of a prover, signed by the account of the prover.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PayoutScheduleUpdate<N: Network> {
+    /// The payout schedule chosen by the prover.
+    pub schedule: PayoutSchedule,
+    /// The block height from which the prover is paid on the payout schedule.
+    pub effective_from: u32,
+    /// The signature of the prover over the update.
+    pub signature: N::AccountSignature,
+    /// The UNIX timestamp at which the update was recorded.
+    pub updated_at: i64,
+}
+
+///
+/// A batch of the payouts for a block found by the operator, which is paid in a single transaction.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PayoutBatch<N: Network> {
+    /// The addresses paid in the batch, and the amount paid to each, in gates.
+    pub payouts: Vec<(Address<N>, u64)>,
+    /// The ID of the transaction paying the batch, once it was paid.
+    pub transaction_id: Option<N::TransactionID>,
+}
+
+///
+/// The period of the epochs over which the contributions of the provers are aggregated.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContributionPeriod {
+    /// Epochs of a day, starting at midnight UTC.
+    Daily,
+    /// Epochs of a week, starting on Monday at midnight UTC.
+    Weekly,
+}
+
+impl ContributionPeriod {
+    /// The periods over which the contributions of the provers are aggregated.
+    pub const ALL: [Self; 2] = [Self::Daily, Self::Weekly];
+
+    /// The UNIX epoch fell on a Thursday, so the weekly epochs are offset by four days to start on a Monday.
+    const WEEKLY_OFFSET_IN_SECS: i64 = 4 * 24 * 60 * 60;
+
+    /// Returns the duration of an epoch of the period, in seconds.
+    pub const fn duration_in_secs(&self) -> i64 {
+        match self {
+            Self::Daily => 24 * 60 * 60,
+            Self::Weekly => 7 * 24 * 60 * 60,
+        }
+    }
+
+    /// Returns the UNIX timestamp at which the epoch of the period containing the given timestamp starts.
+    pub fn epoch_start(&self, timestamp: i64) -> i64 {
+        let offset = match self {
+            Self::Daily => 0,
+            Self::Weekly => Self::WEEKLY_OFFSET_IN_SECS,
+        };
+        let duration = self.duration_in_secs();
+        timestamp.saturating_sub(offset).div_euclid(duration).saturating_mul(duration).saturating_add(offset)
+    }
+}
+
+impl fmt::Display for ContributionPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "daily"),
+            Self::Weekly => write!(f, "weekly"),
+        }
+    }
+}
+
+///
+/// The contribution of a prover to the pool over an epoch.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contribution {
+    /// The number of shares of the prover accepted in the epoch.
+    pub shares: u64,
+    /// The number of blocks found by the shares of the prover in the epoch, whether or not they were orphaned since.
+    pub blocks_found: u64,
+}
+
+///
+/// The contributions of the provers over an epoch, rolled up from the share history and the found blocks of the operator.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ContributionEpoch<N: Network> {
+    /// The UNIX timestamp up to which, exclusive, the shares and found blocks of the epoch are rolled up.
+    pub rolled_up_to: i64,
+    /// The contribution of each prover to the epoch.
+    pub contributions: HashMap<Address<N>, Contribution>,
+}
+
+///
+/// A lease of a slice of the aggregate hashrate of the pool, rented by an external party.
+///
+/// While a lease is active, the given share of jobs is built on behalf of the coinbase address of the renter.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Lease<N: Network> {
+    /// The unique ID of the lease.
+    pub id: u64,
+    /// The coinbase address of the renter.
+    pub renter: Address<N>,
+    /// The share of jobs routed to the renter, in basis points.
+    pub basis_points: u16,
+    /// The UNIX timestamp at which the lease was created.
+    pub created_at: i64,
+    /// The UNIX timestamp at which the lease expires.
+    pub expires_at: i64,
+    /// The number of jobs built on behalf of the renter.
+    pub jobs: u64,
+    /// The number of valid shares submitted on jobs of the renter.
+    pub shares: u64,
+    /// The blocks found on jobs of the renter := (block_height, block_hash)
+    pub blocks: Vec<(u32, N::BlockHash)>,
+}
+
+impl<N: Network> Lease<N> {
+    ///
+    /// Returns `true` if the lease has not expired at the given UNIX timestamp.
+    ///
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+}
+
 #[derive(Debug)]
 pub struct OperatorState<N: Network> {
     shares: SharesState<N>,
+    share_history: ShareHistoryState<N>,
+    rounds: RoundsState<N>,
+    tagged_blocks: TaggedBlocksState<N>,
+    found_blocks: FoundBlocksState<N>,
+    payouts: PayoutsState<N>,
+    payout_addresses: PayoutAddressesState<N>,
+    payout_schedules: PayoutSchedulesState<N>,
+    contributions: ContributionsState<N>,
+    leases: LeasesState<N>,
 }
 
 impl<N: Network> OperatorState<N> {
@@ -41,6 +277,15 @@ impl<N: Network> OperatorState<N> {
 
         // Initialize the operator.
         let operator = Self {
+            rounds: RoundsState::open(&storage)?,
+            tagged_blocks: TaggedBlocksState::open(&storage)?,
+            found_blocks: FoundBlocksState::open(&storage)?,
+            payouts: PayoutsState::open(&storage)?,
+            payout_addresses: PayoutAddressesState::open(&storage)?,
+            payout_schedules: PayoutSchedulesState::open(&storage)?,
+            contributions: ContributionsState::open(&storage)?,
+            leases: LeasesState::open(&storage)?,
+            share_history: ShareHistoryState::open(&storage)?,
             shares: SharesState::open(storage)?,
         };
 
@@ -58,6 +303,41 @@ impl<N: Network> OperatorState<N> {
         self.shares.to_records()
     }
 
+    ///
+    /// Returns the divergences of the shares of the rounds won by the operator from the canonical chain of the given ledger,
+    /// which are the rounds whose block is canonical, and whose shares are attributed to other coinbase records only.
+    /// Rounds whose shares were pruned are skipped.
+    ///
+    pub fn check_share_rounds(&self, ledger_state: &LedgerState<N>) -> Result<Vec<IndexDivergence<N>>> {
+        let mut commitments_by_height: HashMap<u32, HashSet<N::Commitment>> = HashMap::new();
+        for (block_height, coinbase_record) in self.to_coinbase_records() {
+            commitments_by_height.entry(block_height).or_default().insert(coinbase_record.commitment());
+        }
+
+        let mut divergences = Vec::new();
+        for (block_height, round) in self.to_rounds() {
+            let (block_hash, commitments) = match (round.block, commitments_by_height.get(&block_height)) {
+                (Some((block_hash, _)), Some(commitments)) => (block_hash, commitments),
+                _ => continue,
+            };
+            // Skip the rounds whose block is not in the canonical chain.
+            if block_height > ledger_state.latest_block_height() || ledger_state.get_block_hash(block_height)? != block_hash {
+                continue;
+            }
+            let coinbase_transaction = ledger_state.get_block(block_height)?.to_coinbase_transaction()?;
+            let coinbase_commitments: Vec<N::Commitment> = coinbase_transaction.commitments().copied().collect();
+            if !coinbase_commitments.iter().any(|commitment| commitments.contains(commitment)) {
+                if let Some(commitment) = coinbase_commitments.first() {
+                    divergences.push(IndexDivergence::ShareRound {
+                        block_height,
+                        commitment: *commitment,
+                    });
+                }
+            }
+        }
+        Ok(divergences)
+    }
+
     /// Returns the shares for a specific block, given the block height and coinbase record.
     pub fn get_shares_for_block(&self, block_height: u32, coinbase_record: Record<N>) -> Result<HashMap<Address<N>, u64>> {
         self.shares.get_shares_for_block(block_height, coinbase_record)
@@ -73,6 +353,28 @@ impl<N: Network> OperatorState<N> {
         self.shares.increment_share(block_height, coinbase_record, prover)
     }
 
+    /// Increments the share counts by the given amounts for a given block height and coinbase record.
+    pub fn increment_shares(&self, block_height: u32, coinbase_record: Record<N>, increments: &HashMap<Address<N>, u64>) -> Result<()> {
+        self.shares.increment_shares(block_height, coinbase_record, increments)
+    }
+
+    /// Revokes up to the given number of shares from the given prover for a given block height and coinbase record,
+    /// returning the number of shares revoked.
+    pub fn revoke_shares(&self, block_height: u32, coinbase_record: Record<N>, prover: &Address<N>, penalty: u64) -> Result<u64> {
+        self.shares.revoke_shares(block_height, coinbase_record, prover, penalty)
+    }
+
+    /// Returns the sub-pool that each prover of the round with the given block height was routed to, if it was routed.
+    pub fn get_round_routes(&self, block_height: u32) -> Result<HashMap<Address<N>, String>> {
+        self.shares.get_routes(block_height)
+    }
+
+    /// Records the sub-pool that each given prover is routed to in the round with the given block height,
+    /// removing the route of the provers given without a sub-pool.
+    pub fn set_round_routes(&self, block_height: u32, routes: &HashMap<Address<N>, Option<String>>) -> Result<()> {
+        self.shares.set_routes(block_height, routes)
+    }
+
     /// Removes the shares for a given block height and coinbase record in storage.
     pub fn remove_shares(&self, block_height: u32, coinbase_record: Record<N>) -> Result<()> {
         self.shares.remove_shares(block_height, coinbase_record)
@@ -82,6 +384,192 @@ impl<N: Network> OperatorState<N> {
     pub fn get_provers(&self) -> Vec<Address<N>> {
         self.shares.get_provers()
     }
+
+    /// Returns the shares submitted by the given prover between the given UNIX timestamps, inclusive, in the order they were submitted.
+    pub fn get_share_history(&self, prover: &Address<N>, from: i64, to: i64) -> Vec<ShareRecord> {
+        self.share_history.get_share_history(prover, from, to)
+    }
+
+    /// Appends the given shares to the share history of their provers := \[(prover, share_record)\]
+    pub fn add_share_records(&self, share_records: Vec<(Address<N>, ShareRecord)>) -> Result<()> {
+        self.share_history.add_share_records(share_records)
+    }
+
+    /// Removes the share history submitted before the given timestamp, returning the number of entries removed.
+    pub fn prune_share_history(&self, timestamp: i64) -> Result<usize> {
+        self.share_history.remove_share_records_before(timestamp)
+    }
+
+    /// Returns the statistics of every block height the operator has received shares for, in ascending order.
+    pub fn to_rounds(&self) -> Vec<(u32, Round<N>)> {
+        self.rounds.to_rounds()
+    }
+
+    /// Adds the given shares and expected blocks to the statistics for a given block height.
+    pub fn increment_round(&self, block_height: u32, shares: u64, expected_blocks: f64, timestamp: i64) -> Result<()> {
+        self.rounds.increment_round(block_height, shares, expected_blocks, timestamp)
+    }
+
+    /// Records the block found by the operator for a given block height.
+    pub fn set_round_block(&self, block_height: u32, block_hash: N::BlockHash, timestamp: i64) -> Result<()> {
+        self.rounds.set_round_block(block_height, block_hash, timestamp)
+    }
+
+    ///
+    /// Removes the shares of every block height up to and including the given block height, returning the number of entries removed.
+    /// The shares are dated by the block height they were submitted for, so the shares of rounds without statistics are removed too.
+    ///
+    pub fn prune_shares(&self, block_height: u32) -> Result<usize> {
+        self.shares.remove_shares_up_to(block_height)
+    }
+
+    /// Removes the statistics of the rounds started before the given timestamp, returning the number of rounds removed.
+    pub fn prune_rounds(&self, timestamp: i64) -> Result<usize> {
+        self.rounds.remove_rounds_before(timestamp)
+    }
+
+    /// Returns the blocks found by the operator with the given tag := \[(block_height, block_hash)\], in ascending order.
+    pub fn get_tagged_blocks(&self, tag: &str) -> Vec<(u32, N::BlockHash)> {
+        self.tagged_blocks.get_tagged_blocks(tag)
+    }
+
+    /// Records the block found by the operator with the given tag.
+    pub fn add_tagged_block(&self, tag: &str, block_height: u32, block_hash: N::BlockHash) -> Result<()> {
+        self.tagged_blocks.add_tagged_block(tag, block_height, block_hash)
+    }
+
+    /// Returns the provenance of the blocks found by the operator at the given block height, in the order they were found.
+    pub fn get_found_blocks(&self, block_height: u32) -> Result<Vec<FoundBlock<N>>> {
+        self.found_blocks.get_found_blocks(block_height)
+    }
+
+    /// Records the provenance of a block found by the operator at the given block height.
+    pub fn add_found_block(&self, block_height: u32, found_block: FoundBlock<N>) -> Result<()> {
+        self.found_blocks.add_found_block(block_height, found_block)
+    }
+
+    /// Returns the payouts for the block found by the operator with the given block height and block hash, if it was paid.
+    pub fn get_payouts(&self, block_height: u32, block_hash: N::BlockHash) -> Result<Option<HashMap<Address<N>, u64>>> {
+        self.payouts.get_payouts(block_height, block_hash)
+    }
+
+    /// Records the payouts for the block found by the operator with the given block height and block hash.
+    pub fn set_payouts(&self, block_height: u32, block_hash: N::BlockHash, payouts: &HashMap<Address<N>, u64>) -> Result<()> {
+        self.payouts.set_payouts(block_height, block_hash, payouts)
+    }
+
+    /// Returns the payout batches for the block found by the operator with the given block height and block hash.
+    pub fn get_payout_batches(&self, block_height: u32, block_hash: N::BlockHash) -> Result<Vec<PayoutBatch<N>>> {
+        self.payouts.get_payout_batches(block_height, block_hash)
+    }
+
+    /// Returns the payout batches which are yet to be paid, with the block height, block hash, and index of each batch.
+    pub fn get_pending_payout_batches(&self) -> Vec<(u32, N::BlockHash, usize, PayoutBatch<N>)> {
+        self.payouts.get_pending_payout_batches()
+    }
+
+    /// Records the payout batches for the block found by the operator with the given block height and block hash,
+    /// unless a batch for the block was already paid.
+    pub fn set_payout_batches(&self, block_height: u32, block_hash: N::BlockHash, batches: &[PayoutBatch<N>]) -> Result<()> {
+        self.payouts.set_payout_batches(block_height, block_hash, batches)
+    }
+
+    /// Records the given payout batch as paid by the transaction with the given transaction ID.
+    pub fn complete_payout_batch(
+        &self,
+        block_height: u32,
+        block_hash: N::BlockHash,
+        index: usize,
+        transaction_id: N::TransactionID,
+    ) -> Result<()> {
+        self.payouts.complete_payout_batch(block_height, block_hash, index, transaction_id)
+    }
+
+    /// Returns the payout address of the given prover for the round with the given block height, if it was updated.
+    pub fn get_payout_address(&self, prover: &Address<N>, block_height: u32) -> Result<Option<Address<N>>> {
+        self.payout_addresses.get_payout_address(prover, block_height)
+    }
+
+    /// Returns the updates of the payout address of the given prover, in ascending order of the rounds they are effective from.
+    pub fn get_payout_address_updates(&self, prover: &Address<N>) -> Result<Vec<PayoutAddressUpdate<N>>> {
+        self.payout_addresses.get_payout_address_updates(prover)
+    }
+
+    /// Records an update of the payout address of the given prover, which must be effective from a later round than the last update.
+    pub fn add_payout_address_update(&self, prover: &Address<N>, update: PayoutAddressUpdate<N>) -> Result<()> {
+        self.payout_addresses.add_payout_address_update(prover, update)
+    }
+
+    /// Returns the payout schedule of the given prover at the given block height, if the prover chose one.
+    pub fn get_payout_schedule(&self, prover: &Address<N>, block_height: u32) -> Result<Option<PayoutSchedule>> {
+        self.payout_schedules.get_payout_schedule(prover, block_height)
+    }
+
+    /// Returns the updates of the payout schedule of the given prover, in ascending order of the block heights they are effective from.
+    pub fn get_payout_schedule_updates(&self, prover: &Address<N>) -> Result<Vec<PayoutScheduleUpdate<N>>> {
+        self.payout_schedules.get_payout_schedule_updates(prover)
+    }
+
+    /// Records an update of the payout schedule of the given prover, which must be effective from a later block than the last update.
+    pub fn add_payout_schedule_update(&self, prover: &Address<N>, update: PayoutScheduleUpdate<N>) -> Result<()> {
+        self.payout_schedules.add_payout_schedule_update(prover, update)
+    }
+
+    /// Returns the contributions of the provers over the epoch of the given period starting at the given timestamp, if it was rolled up.
+    pub fn get_contribution_epoch(&self, period: ContributionPeriod, epoch_start: i64) -> Result<Option<ContributionEpoch<N>>> {
+        self.contributions.get_contribution_epoch(period, epoch_start)
+    }
+
+    ///
+    /// Rolls up the accepted shares and found blocks submitted since the last rollup of the given period, and before the
+    /// given timestamp, into the contributions of the provers to the epochs of the period, returning the number of epochs updated.
+    /// The first rollup of a period starts from the earliest share history retained.
+    ///
+    pub fn roll_up_contributions(&self, period: ContributionPeriod, to: i64) -> Result<usize> {
+        let rolled_up_to = self.contributions.get_rolled_up_to(period);
+        let from = rolled_up_to.unwrap_or(i64::MIN);
+        if from >= to {
+            return Ok(0);
+        }
+
+        // Aggregate the contributions submitted in the range by epoch := (epoch_start, (prover, contribution))
+        let mut increments: BTreeMap<i64, HashMap<Address<N>, Contribution>> = BTreeMap::new();
+        for (prover, submitted_at, shares) in self.share_history.count_accepted_shares(from, to) {
+            let contribution = increments.entry(period.epoch_start(submitted_at)).or_default().entry(prover).or_default();
+            contribution.shares = contribution.shares.saturating_add(shares);
+        }
+        for found_block in self.found_blocks.to_found_blocks() {
+            if let (Some(prover), true) = (found_block.prover, (from..to).contains(&found_block.submitted_at)) {
+                let contribution = increments.entry(period.epoch_start(found_block.submitted_at)).or_default().entry(prover).or_default();
+                contribution.blocks_found = contribution.blocks_found.saturating_add(1);
+            }
+        }
+
+        // The first rollup starts from the epoch of the earliest contribution, if there is any.
+        let from = match rolled_up_to {
+            Some(rolled_up_to) => rolled_up_to,
+            None => increments.keys().next().copied().unwrap_or(to),
+        };
+        self.contributions.add_contributions(period, from, to, increments)
+    }
+
+    ///
+    /// Removes the contributions of the epochs ended before the given timestamp, returning the number of epochs removed.
+    /// The latest epoch of each period is retained, as it records how far the period is rolled up.
+    ///
+    pub fn prune_contributions(&self, timestamp: i64) -> Result<usize> {
+        self.contributions.remove_epochs_before(timestamp)
+    }
+
+    /// Returns all leases in storage, including the expired ones, in ascending order of their IDs.
+    pub fn to_leases(&self) -> Vec<Lease<N>> {
+        self.leases.to_leases()
+    }
+
+    /// Stores the given leases, replacing their previous state.
+    pub fn set_leases(&self, leases: &[Lease<N>]) -> Result<()> {
+        self.leases.set_leases(leases)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -89,6 +577,8 @@ impl<N: Network> OperatorState<N> {
 struct SharesState<N: Network> {
     /// The miner shares for each block.
     shares: DataMap<(u32, Record<N>), HashMap<Address<N>, u64>>,
+    /// The sub-pool routes of the provers in each round := (block_height, (prover, sub_pool_name))
+    routes: DataMap<u32, HashMap<Address<N>, String>>,
 }
 
 impl<N: Network> SharesState<N> {
@@ -96,6 +586,7 @@ impl<N: Network> SharesState<N> {
     fn open<S: Storage>(storage: S) -> Result<Self> {
         Ok(Self {
             shares: storage.open_map(MapId::Shares)?,
+            routes: storage.open_map(MapId::RoundRoutes)?,
         })
     }
 
@@ -124,25 +615,88 @@ impl<N: Network> SharesState<N> {
 
     /// Increments the share count by one for a given block height, coinbase record, and prover address.
     fn increment_share(&self, block_height: u32, coinbase_record: Record<N>, prover: &Address<N>) -> Result<()> {
+        self.increment_shares(block_height, coinbase_record, &[(*prover, 1)].into_iter().collect())
+    }
+
+    /// Increments the share counts by the given amounts for a given block height and coinbase record.
+    fn increment_shares(&self, block_height: u32, coinbase_record: Record<N>, increments: &HashMap<Address<N>, u64>) -> Result<()> {
         // Retrieve the current shares for a given block height.
         let mut shares = match self.shares.get(&(block_height, coinbase_record.clone()))? {
             Some(shares) => shares,
             None => HashMap::new(),
         };
 
-        // Increment the share count for the given address.
-        let entry = shares.entry(*prover).or_insert(0);
-        *entry = entry.saturating_add(1);
+        // Increment the share count for each given address.
+        for (prover, increment) in increments {
+            let entry = shares.entry(*prover).or_insert(0);
+            *entry = entry.saturating_add(*increment);
+        }
 
         // Insert the updated shares for the given block height.
         self.shares.insert(&(block_height, coinbase_record), &shares, None)
     }
 
+    /// Revokes up to the given number of shares from the given prover for a given block height and coinbase record.
+    fn revoke_shares(&self, block_height: u32, coinbase_record: Record<N>, prover: &Address<N>, penalty: u64) -> Result<u64> {
+        let key = (block_height, coinbase_record);
+        let mut shares = match self.shares.get(&key)? {
+            Some(shares) => shares,
+            None => return Ok(0),
+        };
+
+        // Remove the prover once all of its shares are revoked.
+        let revoked = match shares.get_mut(prover) {
+            Some(count) if *count > penalty => {
+                *count -= penalty;
+                penalty
+            }
+            Some(_) => shares.remove(prover).unwrap_or_default(),
+            None => return Ok(0),
+        };
+        self.shares.insert(&key, &shares, None)?;
+
+        Ok(revoked)
+    }
+
     /// Removes all of the shares for a given block height and coinbase record.
     fn remove_shares(&self, block_height: u32, coinbase_record: Record<N>) -> Result<()> {
         self.shares.remove(&(block_height, coinbase_record), None)
     }
 
+    /// Returns the sub-pool routes of the provers in the given round.
+    fn get_routes(&self, block_height: u32) -> Result<HashMap<Address<N>, String>> {
+        Ok(self.routes.get(&block_height)?.unwrap_or_default())
+    }
+
+    /// Updates the sub-pool routes of the given provers in the given round, removing the routes given as `None`.
+    fn set_routes(&self, block_height: u32, updates: &HashMap<Address<N>, Option<String>>) -> Result<()> {
+        let mut routes = self.get_routes(block_height)?;
+        for (prover, route) in updates {
+            match route {
+                Some(sub_pool_name) => routes.insert(*prover, sub_pool_name.clone()),
+                None => routes.remove(prover),
+            };
+        }
+        self.routes.insert(&block_height, &routes, None)
+    }
+
+    /// Removes all of the shares and routes up to and including the given block height, returning the number of entries removed.
+    fn remove_shares_up_to(&self, block_height: u32) -> Result<usize> {
+        let keys: Vec<_> = self.shares.keys().filter(|(height, _)| *height <= block_height).collect();
+        let route_keys: Vec<_> = self.routes.keys().filter(|height| *height <= block_height).collect();
+
+        let batch = self.shares.prepare_batch();
+        for key in &keys {
+            self.shares.remove(key, Some(batch))?;
+        }
+        for key in &route_keys {
+            self.routes.remove(key, Some(batch))?;
+        }
+        self.shares.execute_batch(batch)?;
+
+        Ok(keys.len() + route_keys.len())
+    }
+
     fn get_provers(&self) -> Vec<Address<N>> {
         let set: HashSet<Address<N>> = self
             .shares
@@ -152,3 +706,519 @@ impl<N: Network> SharesState<N> {
         Vec::from_iter(set)
     }
 }
+
+#[derive(Clone, Debug)]
+struct ShareHistoryState<N: Network> {
+    /// The shares submitted by each prover, grouped by the second they were submitted := ((prover, submitted_at), share_records)
+    share_history: DataMap<(Address<N>, i64), Vec<ShareRecord>>,
+}
+
+impl<N: Network> ShareHistoryState<N> {
+    /// Initializes a new instance of `ShareHistoryState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            share_history: storage.open_map(MapId::ShareHistory)?,
+        })
+    }
+
+    /// Returns the shares submitted by the given prover between the given timestamps, inclusive, in the order they were submitted.
+    fn get_share_history(&self, prover: &Address<N>, from: i64, to: i64) -> Vec<ShareRecord> {
+        let mut share_history: Vec<_> = self
+            .share_history
+            .iter()
+            .filter(|((address, submitted_at), _)| address == prover && (from..=to).contains(submitted_at))
+            .collect();
+        share_history.sort_unstable_by_key(|((_, submitted_at), _)| *submitted_at);
+        share_history.into_iter().flat_map(|(_, share_records)| share_records).collect()
+    }
+
+    /// Appends the given shares to the share history of their provers, in a single batch.
+    fn add_share_records(&self, share_records: Vec<(Address<N>, ShareRecord)>) -> Result<()> {
+        // Group the shares by their key, so each key is written once.
+        let mut entries: HashMap<(Address<N>, i64), Vec<ShareRecord>> = HashMap::new();
+        for (prover, share_record) in share_records {
+            entries.entry((prover, share_record.submitted_at)).or_default().push(share_record);
+        }
+
+        let batch = self.share_history.prepare_batch();
+        for (key, share_records) in entries {
+            let mut history = self.share_history.get(&key)?.unwrap_or_default();
+            history.extend(share_records);
+            self.share_history.insert(&key, &history, Some(batch))?;
+        }
+        self.share_history.execute_batch(batch)
+    }
+
+    /// Returns the number of accepted shares of each prover in each second between the given timestamps, exclusive of the end,
+    /// := \[(prover, submitted_at, shares)\]
+    fn count_accepted_shares(&self, from: i64, to: i64) -> Vec<(Address<N>, i64, u64)> {
+        self.share_history
+            .iter()
+            .filter(|((_, submitted_at), _)| (from..to).contains(submitted_at))
+            .map(|((prover, submitted_at), share_records)| {
+                let shares = share_records.iter().filter(|share_record| share_record.is_accepted).count();
+                (prover, submitted_at, shares as u64)
+            })
+            .filter(|(_, _, shares)| *shares > 0)
+            .collect()
+    }
+
+    /// Removes the shares submitted before the given timestamp, returning the number of entries removed.
+    fn remove_share_records_before(&self, timestamp: i64) -> Result<usize> {
+        let keys: Vec<_> = self
+            .share_history
+            .keys()
+            .filter(|(_, submitted_at)| *submitted_at < timestamp)
+            .collect();
+
+        let batch = self.share_history.prepare_batch();
+        for key in &keys {
+            self.share_history.remove(key, Some(batch))?;
+        }
+        self.share_history.execute_batch(batch)?;
+
+        Ok(keys.len())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RoundsState<N: Network> {
+    /// The statistics of the operator for each block height.
+    rounds: DataMap<u32, Round<N>>,
+}
+
+impl<N: Network> RoundsState<N> {
+    /// Initializes a new instance of `RoundsState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            rounds: storage.open_map(MapId::Rounds)?,
+        })
+    }
+
+    /// Returns the statistics of every block height in storage, in ascending order.
+    fn to_rounds(&self) -> Vec<(u32, Round<N>)> {
+        let mut rounds: Vec<_> = self.rounds.iter().collect();
+        rounds.sort_unstable_by_key(|(block_height, _)| *block_height);
+        rounds
+    }
+
+    /// Returns the statistics for a given block height, initializing them at the given timestamp if they do not exist.
+    fn get_or_init(&self, block_height: u32, timestamp: i64) -> Result<Round<N>> {
+        Ok(self.rounds.get(&block_height)?.unwrap_or(Round {
+            started_at: timestamp,
+            shares: 0,
+            expected_blocks: 0.0,
+            block: None,
+        }))
+    }
+
+    /// Adds the given shares and expected blocks to the statistics for a given block height.
+    fn increment_round(&self, block_height: u32, shares: u64, expected_blocks: f64, timestamp: i64) -> Result<()> {
+        let mut round = self.get_or_init(block_height, timestamp)?;
+        round.shares = round.shares.saturating_add(shares);
+        round.expected_blocks += expected_blocks;
+        self.rounds.insert(&block_height, &round, None)
+    }
+
+    /// Records the block found for a given block height.
+    fn set_round_block(&self, block_height: u32, block_hash: N::BlockHash, timestamp: i64) -> Result<()> {
+        let mut round = self.get_or_init(block_height, timestamp)?;
+        round.block = Some((block_hash, timestamp));
+        self.rounds.insert(&block_height, &round, None)
+    }
+
+    /// Removes the statistics of the rounds started before the given timestamp, returning the number of rounds removed.
+    fn remove_rounds_before(&self, timestamp: i64) -> Result<usize> {
+        let block_heights: Vec<_> = self
+            .rounds
+            .iter()
+            .filter(|(_, round)| round.started_at < timestamp)
+            .map(|(block_height, _)| block_height)
+            .collect();
+
+        let batch = self.rounds.prepare_batch();
+        for block_height in &block_heights {
+            self.rounds.remove(block_height, Some(batch))?;
+        }
+        self.rounds.execute_batch(batch)?;
+
+        Ok(block_heights.len())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TaggedBlocksState<N: Network> {
+    /// The blocks found by the operator for each tag := ((tag, block_height), block_hash)
+    tagged_blocks: DataMap<(String, u32), N::BlockHash>,
+}
+
+impl<N: Network> TaggedBlocksState<N> {
+    /// Initializes a new instance of `TaggedBlocksState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            tagged_blocks: storage.open_map(MapId::TaggedBlocks)?,
+        })
+    }
+
+    /// Returns the blocks found with the given tag, in ascending order.
+    fn get_tagged_blocks(&self, tag: &str) -> Vec<(u32, N::BlockHash)> {
+        let mut blocks: Vec<_> = self
+            .tagged_blocks
+            .iter()
+            .filter(|((block_tag, _), _)| block_tag == tag)
+            .map(|((_, block_height), block_hash)| (block_height, block_hash))
+            .collect();
+        blocks.sort_unstable_by_key(|(block_height, _)| *block_height);
+        blocks
+    }
+
+    /// Records the block found with the given tag.
+    fn add_tagged_block(&self, tag: &str, block_height: u32, block_hash: N::BlockHash) -> Result<()> {
+        self.tagged_blocks.insert(&(tag.to_string(), block_height), &block_hash, None)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FoundBlocksState<N: Network> {
+    /// The provenance of the blocks found by the operator at each block height.
+    found_blocks: DataMap<u32, Vec<FoundBlock<N>>>,
+}
+
+impl<N: Network> FoundBlocksState<N> {
+    /// Initializes a new instance of `FoundBlocksState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            found_blocks: storage.open_map(MapId::FoundBlocks)?,
+        })
+    }
+
+    /// Returns the provenance of the blocks found at the given block height.
+    fn get_found_blocks(&self, block_height: u32) -> Result<Vec<FoundBlock<N>>> {
+        Ok(self.found_blocks.get(&block_height)?.unwrap_or_default())
+    }
+
+    /// Returns the provenance of every block found in storage.
+    fn to_found_blocks(&self) -> Vec<FoundBlock<N>> {
+        self.found_blocks.values().flatten().collect()
+    }
+
+    /// Records the provenance of a block found at the given block height.
+    fn add_found_block(&self, block_height: u32, found_block: FoundBlock<N>) -> Result<()> {
+        let mut found_blocks = self.get_found_blocks(block_height)?;
+        found_blocks.push(found_block);
+        self.found_blocks.insert(&block_height, &found_blocks, None)
+    }
+}
+
+#[derive(Clone, Debug)]
+#[allow(clippy::type_complexity)]
+struct PayoutsState<N: Network> {
+    /// The payouts for each block found by the operator := ((block_height, block_hash), payouts)
+    payouts: DataMap<(u32, N::BlockHash), HashMap<Address<N>, u64>>,
+    /// The payout batches for each block found by the operator := ((block_height, block_hash), batches)
+    payout_batches: DataMap<(u32, N::BlockHash), Vec<PayoutBatch<N>>>,
+}
+
+impl<N: Network> PayoutsState<N> {
+    /// Initializes a new instance of `PayoutsState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            payouts: storage.open_map(MapId::Payouts)?,
+            payout_batches: storage.open_map(MapId::PayoutBatches)?,
+        })
+    }
+
+    /// Returns the payouts for the given block, if it was paid.
+    fn get_payouts(&self, block_height: u32, block_hash: N::BlockHash) -> Result<Option<HashMap<Address<N>, u64>>> {
+        self.payouts.get(&(block_height, block_hash))
+    }
+
+    /// Records the payouts for the given block.
+    fn set_payouts(&self, block_height: u32, block_hash: N::BlockHash, payouts: &HashMap<Address<N>, u64>) -> Result<()> {
+        self.payouts.insert(&(block_height, block_hash), payouts, None)
+    }
+
+    /// Returns the payout batches for the given block.
+    fn get_payout_batches(&self, block_height: u32, block_hash: N::BlockHash) -> Result<Vec<PayoutBatch<N>>> {
+        Ok(self.payout_batches.get(&(block_height, block_hash))?.unwrap_or_default())
+    }
+
+    /// Returns the payout batches which are yet to be paid, in ascending order of block height.
+    fn get_pending_payout_batches(&self) -> Vec<(u32, N::BlockHash, usize, PayoutBatch<N>)> {
+        let mut pending: Vec<_> = self
+            .payout_batches
+            .iter()
+            .flat_map(|((block_height, block_hash), batches)| {
+                batches
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, batch)| batch.transaction_id.is_none())
+                    .map(move |(index, batch)| (block_height, block_hash, index, batch))
+            })
+            .collect();
+        pending.sort_by_key(|(block_height, _, index, _)| (*block_height, *index));
+        pending
+    }
+
+    /// Records the payout batches for the given block, unless a batch for the block was already paid.
+    fn set_payout_batches(&self, block_height: u32, block_hash: N::BlockHash, batches: &[PayoutBatch<N>]) -> Result<()> {
+        if self
+            .get_payout_batches(block_height, block_hash)?
+            .iter()
+            .any(|batch| batch.transaction_id.is_some())
+        {
+            return Err(anyhow!("The payouts for block {} were already partially paid", block_height));
+        }
+        self.payout_batches.insert(&(block_height, block_hash), &batches.to_vec(), None)
+    }
+
+    /// Records the given payout batch as paid by the given transaction.
+    fn complete_payout_batch(
+        &self,
+        block_height: u32,
+        block_hash: N::BlockHash,
+        index: usize,
+        transaction_id: N::TransactionID,
+    ) -> Result<()> {
+        let mut batches = self.get_payout_batches(block_height, block_hash)?;
+        match batches.get_mut(index) {
+            Some(batch) => match batch.transaction_id {
+                Some(paid_by) if paid_by != transaction_id => {
+                    return Err(anyhow!(
+                        "Payout batch {} for block {} was already paid by {}",
+                        index,
+                        block_height,
+                        paid_by
+                    ));
+                }
+                _ => batch.transaction_id = Some(transaction_id),
+            },
+            None => return Err(anyhow!("Payout batch {} for block {} does not exist", index, block_height)),
+        }
+        self.payout_batches.insert(&(block_height, block_hash), &batches, None)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PayoutAddressesState<N: Network> {
+    /// The updates of the payout address of each prover, in ascending order of the rounds they are effective from.
+    payout_addresses: DataMap<Address<N>, Vec<PayoutAddressUpdate<N>>>,
+}
+
+impl<N: Network> PayoutAddressesState<N> {
+    /// Initializes a new instance of `PayoutAddressesState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            payout_addresses: storage.open_map(MapId::PayoutAddresses)?,
+        })
+    }
+
+    /// Returns the payout address of the given prover for the given block height, from the last update effective by then.
+    fn get_payout_address(&self, prover: &Address<N>, block_height: u32) -> Result<Option<Address<N>>> {
+        let updates = self.get_payout_address_updates(prover)?;
+        Ok(updates
+            .iter()
+            .rev()
+            .find(|update| update.effective_from <= block_height)
+            .map(|update| update.payout_address))
+    }
+
+    /// Returns the updates of the payout address of the given prover.
+    fn get_payout_address_updates(&self, prover: &Address<N>) -> Result<Vec<PayoutAddressUpdate<N>>> {
+        Ok(self.payout_addresses.get(prover)?.unwrap_or_default())
+    }
+
+    /// Records an update of the payout address of the given prover.
+    fn add_payout_address_update(&self, prover: &Address<N>, update: PayoutAddressUpdate<N>) -> Result<()> {
+        let mut updates = self.get_payout_address_updates(prover)?;
+        // Ensure the update is effective from a later round, so a signed update cannot be replayed.
+        if let Some(last_update) = updates.last() {
+            if update.effective_from <= last_update.effective_from {
+                return Err(anyhow!(
+                    "The payout address of {} was already updated from block {}",
+                    prover,
+                    last_update.effective_from
+                ));
+            }
+        }
+        updates.push(update);
+        self.payout_addresses.insert(prover, &updates, None)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PayoutSchedulesState<N: Network> {
+    /// The updates of the payout schedule of each prover, in ascending order of the block heights they are effective from.
+    payout_schedules: DataMap<Address<N>, Vec<PayoutScheduleUpdate<N>>>,
+}
+
+impl<N: Network> PayoutSchedulesState<N> {
+    /// Initializes a new instance of `PayoutSchedulesState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            payout_schedules: storage.open_map(MapId::PayoutSchedules)?,
+        })
+    }
+
+    /// Returns the payout schedule of the given prover at the given block height, from the last update effective by then.
+    fn get_payout_schedule(&self, prover: &Address<N>, block_height: u32) -> Result<Option<PayoutSchedule>> {
+        let updates = self.get_payout_schedule_updates(prover)?;
+        Ok(updates
+            .iter()
+            .rev()
+            .find(|update| update.effective_from <= block_height)
+            .map(|update| update.schedule))
+    }
+
+    /// Returns the updates of the payout schedule of the given prover.
+    fn get_payout_schedule_updates(&self, prover: &Address<N>) -> Result<Vec<PayoutScheduleUpdate<N>>> {
+        Ok(self.payout_schedules.get(prover)?.unwrap_or_default())
+    }
+
+    /// Records an update of the payout schedule of the given prover.
+    fn add_payout_schedule_update(&self, prover: &Address<N>, update: PayoutScheduleUpdate<N>) -> Result<()> {
+        let mut updates = self.get_payout_schedule_updates(prover)?;
+        // Ensure the update is effective from a later block, so a signed update cannot be replayed.
+        if let Some(last_update) = updates.last() {
+            if update.effective_from <= last_update.effective_from {
+                return Err(anyhow!(
+                    "The payout schedule of {} was already updated from block {}",
+                    prover,
+                    last_update.effective_from
+                ));
+            }
+        }
+        updates.push(update);
+        self.payout_schedules.insert(prover, &updates, None)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ContributionsState<N: Network> {
+    /// The contributions of the provers over each epoch := ((period, epoch_start), contribution_epoch)
+    contributions: DataMap<(ContributionPeriod, i64), ContributionEpoch<N>>,
+}
+
+impl<N: Network> ContributionsState<N> {
+    /// Initializes a new instance of `ContributionsState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            contributions: storage.open_map(MapId::Contributions)?,
+        })
+    }
+
+    /// Returns the contributions of the provers over the epoch of the given period starting at the given timestamp.
+    fn get_contribution_epoch(&self, period: ContributionPeriod, epoch_start: i64) -> Result<Option<ContributionEpoch<N>>> {
+        self.contributions.get(&(period, epoch_start))
+    }
+
+    /// Returns the timestamp up to which the given period is rolled up, or `None` if it was never rolled up.
+    fn get_rolled_up_to(&self, period: ContributionPeriod) -> Option<i64> {
+        self.contributions
+            .iter()
+            .filter(|((epoch_period, _), _)| *epoch_period == period)
+            .map(|(_, epoch)| epoch.rolled_up_to)
+            .max()
+    }
+
+    ///
+    /// Adds the given contributions to the epochs of the given period, and marks every epoch overlapping the given range
+    /// as rolled up to the end of the range, in a single batch, returning the number of epochs updated.
+    ///
+    fn add_contributions(
+        &self,
+        period: ContributionPeriod,
+        from: i64,
+        to: i64,
+        mut increments: BTreeMap<i64, HashMap<Address<N>, Contribution>>,
+    ) -> Result<usize> {
+        if from >= to {
+            return Ok(0);
+        }
+
+        let duration = period.duration_in_secs();
+        let first_epoch = period.epoch_start(from);
+        let last_epoch = period.epoch_start(to - 1);
+
+        let batch = self.contributions.prepare_batch();
+        let mut number_of_epochs = 0;
+        let mut epoch_start = first_epoch;
+        while epoch_start <= last_epoch {
+            let key = (period, epoch_start);
+            let mut epoch = self.contributions.get(&key)?.unwrap_or_else(|| ContributionEpoch {
+                rolled_up_to: epoch_start,
+                contributions: HashMap::new(),
+            });
+            for (prover, increment) in increments.remove(&epoch_start).unwrap_or_default() {
+                let contribution = epoch.contributions.entry(prover).or_default();
+                contribution.shares = contribution.shares.saturating_add(increment.shares);
+                contribution.blocks_found = contribution.blocks_found.saturating_add(increment.blocks_found);
+            }
+            epoch.rolled_up_to = to.min(epoch_start.saturating_add(duration));
+            self.contributions.insert(&key, &epoch, Some(batch))?;
+
+            number_of_epochs += 1;
+            epoch_start = epoch_start.saturating_add(duration);
+        }
+        self.contributions.execute_batch(batch)?;
+
+        Ok(number_of_epochs)
+    }
+
+    ///
+    /// Removes the epochs ended before the given timestamp, except for the latest epoch of each period,
+    /// in a single batch, returning the number of epochs removed.
+    ///
+    fn remove_epochs_before(&self, timestamp: i64) -> Result<usize> {
+        // Retain the latest epoch of each period := (period, epoch_start)
+        let mut latest_epochs: HashMap<ContributionPeriod, i64> = HashMap::new();
+        for ((period, epoch_start), _) in self.contributions.iter() {
+            let latest = latest_epochs.entry(period).or_insert(epoch_start);
+            *latest = (*latest).max(epoch_start);
+        }
+
+        let batch = self.contributions.prepare_batch();
+        let mut number_of_epochs = 0;
+        for (period, epoch_start) in self.contributions.keys() {
+            let epoch_end = epoch_start.saturating_add(period.duration_in_secs());
+            if epoch_end <= timestamp && latest_epochs.get(&period) != Some(&epoch_start) {
+                self.contributions.remove(&(period, epoch_start), Some(batch))?;
+                number_of_epochs += 1;
+            }
+        }
+        self.contributions.execute_batch(batch)?;
+
+        Ok(number_of_epochs)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct LeasesState<N: Network> {
+    /// The hashrate leases of the operator := (lease_id, lease)
+    leases: DataMap<u64, Lease<N>>,
+}
+
+impl<N: Network> LeasesState<N> {
+    /// Initializes a new instance of `LeasesState`.
+    fn open<S: Storage>(storage: &S) -> Result<Self> {
+        Ok(Self {
+            leases: storage.open_map(MapId::Leases)?,
+        })
+    }
+
+    /// Returns all leases, in ascending order of their IDs.
+    fn to_leases(&self) -> Vec<Lease<N>> {
+        let mut leases: Vec<_> = self.leases.values().collect();
+        leases.sort_unstable_by_key(|lease| lease.id);
+        leases
+    }
+
+    /// Stores the given leases, in a single batch.
+    fn set_leases(&self, leases: &[Lease<N>]) -> Result<()> {
+        let batch = self.leases.prepare_batch();
+        for lease in leases {
+            self.leases.insert(&lease.id, lease, Some(batch))?;
+        }
+        self.leases.execute_batch(batch)
+    }
+}
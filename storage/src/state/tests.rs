@@ -15,14 +15,26 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-    storage::{rocksdb::RocksDB, Storage},
+    storage::{rocksdb::RocksDB, Map, MapId, Storage},
+    Contribution,
+    ContributionPeriod,
+    FoundBlock,
+    IndexDivergence,
+    Lease,
     LedgerState,
+    OperatorState,
+    PayoutAddressUpdate,
+    PayoutBatch,
+    PayoutSchedule,
+    PayoutScheduleUpdate,
+    ShareRecord,
+    TimestampPolicy,
 };
 use snarkos_environment::CurrentNetwork;
 use snarkvm::dpc::prelude::*;
 
 use rand::{thread_rng, Rng};
-use std::sync::atomic::AtomicBool;
+use std::{collections::HashMap, sync::atomic::AtomicBool};
 
 fn temp_dir() -> std::path::PathBuf {
     tempfile::tempdir().expect("Failed to open temporary directory").into_path()
@@ -203,6 +215,40 @@ fn test_remove_last_2_blocks() {
     assert_eq!(ledger_tree.root(), ledger.latest_ledger_root());
 }
 
+#[test]
+fn test_iter_blocks() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let address = Account::<CurrentNetwork>::new(rng).address();
+
+    // Mine the next two blocks.
+    for _ in 0..2 {
+        let (block, _) = ledger
+            .mine_next_block(address, true, &[], &terminator, rng)
+            .expect("Failed to mine");
+        ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+    }
+    assert_eq!(2, ledger.latest_block_height());
+
+    // Check the iterators yield the same items as the materialized lists.
+    let blocks: Vec<_> = ledger.iter_blocks(..).collect::<Result<_, _>>().unwrap();
+    assert_eq!(blocks, ledger.get_blocks(0, 2).unwrap());
+    let headers: Vec<_> = ledger.iter_headers(1..=2).collect::<Result<_, _>>().unwrap();
+    assert_eq!(headers, ledger.get_block_headers(1, 2).unwrap());
+
+    // Check the ranges are bounded by the latest block height.
+    let heights: Vec<_> = ledger.iter_headers(1..).map(|header| header.unwrap().height()).collect();
+    assert_eq!(heights, vec![1, 2]);
+    let heights: Vec<_> = ledger.iter_headers(..2).map(|header| header.unwrap().height()).collect();
+    assert_eq!(heights, vec![0, 1]);
+    assert_eq!(ledger.iter_blocks(2..100).count(), 1);
+    assert_eq!(ledger.iter_blocks(3..).count(), 0);
+    assert_eq!(ledger.iter_blocks(..0).count(), 0);
+}
+
 #[test]
 fn test_get_block_locators() {
     let rng = &mut thread_rng();
@@ -347,3 +393,732 @@ fn test_transaction_fees() {
     assert_eq!(new_coinbase_record.value(), expected_block_reward);
     assert_eq!(output_record.value(), amount);
 }
+
+#[test]
+fn test_address_index() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger, and enable the address index.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    assert!(ledger.get_transactions_for_address(&Account::<CurrentNetwork>::new(rng).address(), 0, 0).is_err());
+    ledger.set_address_index(true).expect("Failed to enable the address index");
+    assert!(ledger.is_address_index_enabled().unwrap());
+
+    // Mine the next block, with a public coinbase record for a new account.
+    let address = Account::<CurrentNetwork>::new(rng).address();
+    let (block, _record) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+
+    // Ensure the coinbase transaction is indexed for the address.
+    let coinbase_id = block.transactions()[0].transaction_id();
+    assert_eq!(ledger.get_transactions_for_address(&address, 0, 1).unwrap(), vec![(1, coinbase_id)]);
+    assert!(ledger.get_transactions_for_address(&address, 0, 0).unwrap().is_empty());
+
+    // Ensure the index is updated when the block is reverted.
+    ledger.revert_to_block_height(0).expect("Failed to revert the ledger");
+    assert!(ledger.get_transactions_for_address(&address, 0, 1).unwrap().is_empty());
+
+    // Ensure a disabled index is cleared.
+    ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+    ledger.set_address_index(false).expect("Failed to disable the address index");
+    assert!(ledger.get_transactions_for_address(&address, 0, 1).is_err());
+
+    // Ensure the index is rebuilt from the existing blocks when it is enabled again.
+    ledger.set_address_index(true).expect("Failed to enable the address index");
+    assert_eq!(ledger.get_transactions_for_address(&address, 0, 1).unwrap(), vec![(1, coinbase_id)]);
+}
+
+#[test]
+fn test_open_secondary_reader() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger, and mine the next block.
+    let path = temp_dir();
+    let ledger = LedgerState::<CurrentNetwork>::open_writer_with_increment::<RocksDB, _>(&path, 1).expect("Failed to initialize ledger");
+    let address = Account::<CurrentNetwork>::new(rng).address();
+    let (block, _record) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+
+    // Ensure a secondary reader with its own state directory serves the ledger of the writer.
+    let secondary_path = temp_dir();
+    let (reader, _resource) =
+        LedgerState::<CurrentNetwork>::open_secondary_reader::<RocksDB, _>(&path, &secondary_path).expect("Failed to open the reader");
+    assert!(reader.is_read_only());
+    assert_eq!(reader.latest_block_height(), 1);
+    assert_eq!(reader.latest_block_hash(), block.hash());
+}
+
+#[test]
+fn test_check_indexes() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger with the address index, and mine the next block.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    ledger.set_address_index(true).expect("Failed to enable the address index");
+    let address = Account::<CurrentNetwork>::new(rng).address();
+    let (block, _record) = ledger
+        .mine_next_block(address, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+
+    // Ensure the indexes of a consistent ledger do not diverge.
+    assert!(ledger.check_indexes(0).unwrap().is_empty());
+    assert!(ledger.check_indexes(1).unwrap().is_empty());
+    assert!(ledger.check_indexes(2).is_err());
+
+    // Remove a commitment of the block from the commitment index.
+    let transition = &block.transactions()[0].transitions()[0];
+    let commitment = *transition.commitments().next().unwrap();
+    ledger
+        .storage()
+        .open_map::<<CurrentNetwork as Network>::Commitment, <CurrentNetwork as Network>::TransitionID>(MapId::Commitments)
+        .unwrap()
+        .remove(&commitment, None)
+        .unwrap();
+
+    // Ensure the divergence is detected.
+    assert_eq!(ledger.check_indexes(1).unwrap(), vec![IndexDivergence::Commitment {
+        transition_id: transition.transition_id(),
+        commitment,
+    }]);
+    assert!(ledger.check_indexes(0).unwrap().is_empty());
+}
+
+#[test]
+fn test_operator_check_share_rounds() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Mine the next block, as found by the operator.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let prover = Account::<CurrentNetwork>::new(rng).address();
+    let (block, coinbase_record) = ledger
+        .mine_next_block(prover, true, &[], &terminator, rng)
+        .expect("Failed to mine");
+    ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+
+    // Ensure the shares attributed to the coinbase record of the block do not diverge.
+    operator.increment_share(1, coinbase_record, &prover).unwrap();
+    operator.set_round_block(1, block.hash(), 100).unwrap();
+    assert!(operator.check_share_rounds(&ledger).unwrap().is_empty());
+
+    // Ensure the shares attributed to another coinbase record diverge.
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let block_template = ledger.get_block_template(prover, true, &[], rng).expect("Failed to get a block template");
+    operator.increment_share(1, block_template.coinbase_record().clone(), &prover).unwrap();
+    operator.set_round_block(1, block.hash(), 100).unwrap();
+    let divergences = operator.check_share_rounds(&ledger).unwrap();
+    assert_eq!(divergences.len(), 1);
+    assert!(matches!(divergences[0], IndexDivergence::ShareRound { block_height: 1, .. }));
+
+    // Ensure the rounds of orphaned blocks are skipped.
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    operator.increment_share(1, block_template.coinbase_record().clone(), &prover).unwrap();
+    operator.set_round_block(1, CurrentNetwork::genesis_block().hash(), 100).unwrap();
+    assert!(operator.check_share_rounds(&ledger).unwrap().is_empty());
+}
+
+#[test]
+fn test_operator_revoke_shares() {
+    let rng = &mut thread_rng();
+
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let (prover, other_prover) = (Account::<CurrentNetwork>::new(rng).address(), Account::<CurrentNetwork>::new(rng).address());
+    let block_template = ledger.get_block_template(prover, true, &[], rng).expect("Failed to get a block template");
+    let coinbase_record = block_template.coinbase_record().clone();
+
+    let increments = [(prover, 10), (other_prover, 5)].into_iter().collect();
+    operator.increment_shares(1, coinbase_record.clone(), &increments).unwrap();
+
+    // Check the penalty is revoked from the given prover only.
+    assert_eq!(operator.revoke_shares(1, coinbase_record.clone(), &prover, 4).unwrap(), 4);
+    let shares = operator.get_shares_for_block(1, coinbase_record.clone()).unwrap();
+    assert_eq!(shares[&prover], 6);
+    assert_eq!(shares[&other_prover], 5);
+
+    // Check the prover is removed once all of its shares are revoked, and nothing is revoked from an unknown round.
+    assert_eq!(operator.revoke_shares(1, coinbase_record.clone(), &prover, 20).unwrap(), 6);
+    assert!(!operator.get_shares_for_block(1, coinbase_record.clone()).unwrap().contains_key(&prover));
+    assert_eq!(operator.revoke_shares(1, coinbase_record.clone(), &prover, 1).unwrap(), 0);
+    assert_eq!(operator.revoke_shares(2, coinbase_record, &other_prover, 1).unwrap(), 0);
+}
+
+#[test]
+fn test_operator_rounds() {
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let block_hash = CurrentNetwork::genesis_block().hash();
+
+    // Accumulate the statistics for two block heights, out of order.
+    operator.increment_round(2, 3, 0.25, 200).unwrap();
+    operator.increment_round(1, 5, 0.5, 100).unwrap();
+    operator.increment_round(1, 1, 0.125, 150).unwrap();
+    operator.set_round_block(1, block_hash, 160).unwrap();
+
+    let rounds = operator.to_rounds();
+    assert_eq!(rounds.len(), 2);
+
+    // Check the rounds are in ascending order, and retain the timestamp of the first share.
+    let (block_height, round) = &rounds[0];
+    assert_eq!(*block_height, 1);
+    assert_eq!(round.started_at, 100);
+    assert_eq!(round.shares, 6);
+    assert_eq!(round.expected_blocks, 0.625);
+    assert_eq!(round.block, Some((block_hash, 160)));
+
+    let (block_height, round) = &rounds[1];
+    assert_eq!(*block_height, 2);
+    assert_eq!(round.block, None);
+}
+
+#[test]
+fn test_operator_pruning() {
+    let rng = &mut thread_rng();
+
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let prover = Account::<CurrentNetwork>::new(rng).address();
+
+    let block_template = ledger.get_block_template(prover, true, &[], rng).expect("Failed to get a block template");
+    let coinbase_record = block_template.coinbase_record().clone();
+
+    // Record the shares of three block heights, and the rounds of the last two, leaving the shares of the first without a round.
+    for block_height in [1, 2, 3] {
+        operator.increment_share(block_height, coinbase_record.clone(), &prover).unwrap();
+    }
+    for (block_height, started_at) in [(2, 200), (3, 300)] {
+        operator.increment_round(block_height, 1, 0.1, started_at).unwrap();
+    }
+
+    // Check the shares up to the cutoff height are removed, including the shares without a round, and the rounds are retained.
+    assert_eq!(operator.prune_shares(2).unwrap(), 2);
+    let block_heights: Vec<_> = operator
+        .to_coinbase_records()
+        .into_iter()
+        .map(|(block_height, _)| block_height)
+        .collect();
+    assert_eq!(block_heights, vec![3]);
+    assert_eq!(operator.to_rounds().len(), 2);
+    assert_eq!(operator.prune_shares(2).unwrap(), 0);
+
+    // Check the rounds started before the cutoff are removed.
+    assert_eq!(operator.prune_rounds(250).unwrap(), 1);
+    let block_heights: Vec<_> = operator.to_rounds().into_iter().map(|(block_height, _)| block_height).collect();
+    assert_eq!(block_heights, vec![3]);
+
+    // Check the shares above the cutoff height are retained.
+    assert_eq!(operator.prune_shares(0).unwrap(), 0);
+    assert_eq!(operator.get_shares_for_prover(&prover), 1);
+}
+
+#[test]
+fn test_operator_share_history() {
+    let rng = &mut thread_rng();
+
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let prover = Account::<CurrentNetwork>::new(rng).address();
+    let other_prover = Account::<CurrentNetwork>::new(rng).address();
+
+    let share_record = |submitted_at: i64, is_accepted: bool| ShareRecord {
+        submitted_at,
+        worker: "127.0.0.1:4132".to_string(),
+        block_height: Some(1),
+        share_difficulty: Some(u64::MAX / 5),
+        is_accepted,
+        rejection_reason: (!is_accepted).then(|| "Duplicate share".to_string()),
+    };
+
+    // Record the shares of two provers, including two shares of the same prover in the same second, across two batches.
+    operator
+        .add_share_records(vec![(prover, share_record(200, true)), (other_prover, share_record(150, true))])
+        .unwrap();
+    operator
+        .add_share_records(vec![(prover, share_record(100, true)), (prover, share_record(200, false))])
+        .unwrap();
+
+    // Check the shares of the prover are returned in the order they were submitted, within the inclusive range.
+    assert_eq!(
+        operator.get_share_history(&prover, 0, 300),
+        vec![share_record(100, true), share_record(200, true), share_record(200, false)]
+    );
+    assert_eq!(operator.get_share_history(&prover, 100, 100), vec![share_record(100, true)]);
+    assert!(operator.get_share_history(&prover, 300, 400).is_empty());
+    assert_eq!(operator.get_share_history(&other_prover, 0, 300), vec![share_record(150, true)]);
+
+    // Check the shares submitted before the cutoff are removed.
+    assert_eq!(operator.prune_share_history(175).unwrap(), 2);
+    assert_eq!(operator.get_share_history(&prover, 0, 300).len(), 2);
+    assert!(operator.get_share_history(&other_prover, 0, 300).is_empty());
+}
+
+#[test]
+fn test_operator_payout_addresses() {
+    let rng = &mut thread_rng();
+
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let account = Account::<CurrentNetwork>::new(rng);
+    let prover = account.address();
+    let first_address = Account::<CurrentNetwork>::new(rng).address();
+    let second_address = Account::<CurrentNetwork>::new(rng).address();
+
+    let signature = account.private_key().sign(&[true], rng).unwrap();
+    let update = |payout_address: Address<CurrentNetwork>, effective_from: u32| PayoutAddressUpdate {
+        payout_address,
+        effective_from,
+        signature: signature.clone(),
+        updated_at: 100,
+    };
+
+    // Check a prover without updates is paid to its own address.
+    assert_eq!(operator.get_payout_address(&prover, 10).unwrap(), None);
+
+    operator.add_payout_address_update(&prover, update(first_address, 10)).unwrap();
+    operator.add_payout_address_update(&prover, update(second_address, 20)).unwrap();
+
+    // Check each round is paid to the payout address effective from it.
+    assert_eq!(operator.get_payout_address(&prover, 9).unwrap(), None);
+    assert_eq!(operator.get_payout_address(&prover, 10).unwrap(), Some(first_address));
+    assert_eq!(operator.get_payout_address(&prover, 19).unwrap(), Some(first_address));
+    assert_eq!(operator.get_payout_address(&prover, 25).unwrap(), Some(second_address));
+
+    // Check an update which is not effective from a later round is rejected, so it cannot be replayed.
+    assert!(operator.add_payout_address_update(&prover, update(first_address, 10)).is_err());
+    assert!(operator.add_payout_address_update(&prover, update(first_address, 20)).is_err());
+    assert_eq!(operator.get_payout_address_updates(&prover).unwrap().len(), 2);
+}
+
+#[test]
+fn test_operator_payout_schedules() {
+    let rng = &mut thread_rng();
+
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let account = Account::<CurrentNetwork>::new(rng);
+    let prover = account.address();
+    let first_schedule = PayoutSchedule {
+        minimum_payout: 5_000_000,
+        payout_interval: 100,
+    };
+    let second_schedule = PayoutSchedule {
+        minimum_payout: 1_000_000,
+        payout_interval: 10,
+    };
+
+    let signature = account.private_key().sign(&[true], rng).unwrap();
+    let update = |schedule: PayoutSchedule, effective_from: u32| PayoutScheduleUpdate {
+        schedule,
+        effective_from,
+        signature: signature.clone(),
+        updated_at: 100,
+    };
+
+    // Check a prover without updates has no payout schedule of its own.
+    assert_eq!(operator.get_payout_schedule(&prover, 10).unwrap(), None);
+
+    operator.add_payout_schedule_update(&prover, update(first_schedule, 10)).unwrap();
+    operator.add_payout_schedule_update(&prover, update(second_schedule, 20)).unwrap();
+
+    // Check each block height has the payout schedule effective from it.
+    assert_eq!(operator.get_payout_schedule(&prover, 9).unwrap(), None);
+    assert_eq!(operator.get_payout_schedule(&prover, 10).unwrap(), Some(first_schedule));
+    assert_eq!(operator.get_payout_schedule(&prover, 19).unwrap(), Some(first_schedule));
+    assert_eq!(operator.get_payout_schedule(&prover, 25).unwrap(), Some(second_schedule));
+
+    // Check an update which is not effective from a later block is rejected, so it cannot be replayed.
+    assert!(operator.add_payout_schedule_update(&prover, update(first_schedule, 20)).is_err());
+    assert_eq!(operator.get_payout_schedule_updates(&prover).unwrap().len(), 2);
+}
+
+#[test]
+fn test_operator_tagged_blocks() {
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let block_hash = CurrentNetwork::genesis_block().hash();
+
+    operator.add_tagged_block("pool-a", 7, block_hash).unwrap();
+    operator.add_tagged_block("pool-b", 5, block_hash).unwrap();
+    operator.add_tagged_block("pool-a", 3, block_hash).unwrap();
+
+    // Check the blocks are indexed by tag, in ascending order.
+    assert_eq!(operator.get_tagged_blocks("pool-a"), vec![(3, block_hash), (7, block_hash)]);
+    assert_eq!(operator.get_tagged_blocks("pool-b"), vec![(5, block_hash)]);
+    assert!(operator.get_tagged_blocks("pool-c").is_empty());
+}
+
+#[test]
+fn test_operator_found_blocks() {
+    let rng = &mut thread_rng();
+
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let prover = Account::<CurrentNetwork>::new(rng).address();
+
+    let block_template = ledger.get_block_template(prover, true, &[], rng).expect("Failed to get a block template");
+    let found_block = FoundBlock {
+        block_hash: CurrentNetwork::genesis_block().hash(),
+        prover: Some(prover),
+        worker: "127.0.0.1:4132".to_string(),
+        job_id: block_template.to_header_root().unwrap(),
+        lease_id: None,
+        share_difficulty: Some(u64::MAX / 5),
+        nonce: CurrentNetwork::genesis_block().header().nonce(),
+        block_template: block_template.clone(),
+        submitted_at: 100,
+    };
+
+    // Check the provenance of each block found at a height is retained, in the order they were found.
+    assert!(operator.get_found_blocks(1).unwrap().is_empty());
+    operator.add_found_block(1, found_block.clone()).unwrap();
+    let mut orphaned_block = found_block.clone();
+    orphaned_block.lease_id = Some(3);
+    operator.add_found_block(1, orphaned_block).unwrap();
+
+    let found_blocks = operator.get_found_blocks(1).unwrap();
+    assert_eq!(found_blocks.len(), 2);
+    assert_eq!(found_blocks[0].prover, Some(prover));
+    assert_eq!(found_blocks[0].job_id, found_block.job_id);
+    assert_eq!(found_blocks[0].block_template.block_height(), block_template.block_height());
+    assert_eq!(found_blocks[0].lease_id, None);
+    assert_eq!(found_blocks[1].lease_id, Some(3));
+    assert!(operator.get_found_blocks(2).unwrap().is_empty());
+}
+
+#[test]
+fn test_operator_contributions() {
+    const DAY: i64 = 24 * 60 * 60;
+
+    let rng = &mut thread_rng();
+
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let prover = Account::<CurrentNetwork>::new(rng).address();
+    let other_prover = Account::<CurrentNetwork>::new(rng).address();
+
+    let share_record = |submitted_at: i64, is_accepted: bool| ShareRecord {
+        submitted_at,
+        worker: "127.0.0.1:4132".to_string(),
+        block_height: Some(1),
+        share_difficulty: Some(u64::MAX / 5),
+        is_accepted,
+        rejection_reason: (!is_accepted).then(|| "Duplicate share".to_string()),
+    };
+    let contribution = |shares: u64, blocks_found: u64| Contribution { shares, blocks_found };
+
+    // Check the epochs are aligned to midnight UTC, and the weekly epochs to Mondays.
+    assert_eq!(ContributionPeriod::Daily.epoch_start(DAY + 10), DAY);
+    assert_eq!(ContributionPeriod::Daily.epoch_start(-1), -DAY);
+    assert_eq!(ContributionPeriod::Weekly.epoch_start(0), -3 * DAY);
+    assert_eq!(ContributionPeriod::Weekly.epoch_start(4 * DAY), 4 * DAY);
+
+    // Record the shares of two provers over two days, and a block found by the first prover.
+    operator
+        .add_share_records(vec![
+            (prover, share_record(DAY + 10, true)),
+            (prover, share_record(DAY + 10, true)),
+            (prover, share_record(DAY + 10, false)),
+            (other_prover, share_record(DAY + 20, true)),
+            (prover, share_record(2 * DAY + 5, true)),
+        ])
+        .unwrap();
+    let block_template = ledger.get_block_template(prover, true, &[], rng).expect("Failed to get a block template");
+    let found_block = FoundBlock {
+        block_hash: CurrentNetwork::genesis_block().hash(),
+        prover: Some(prover),
+        worker: "127.0.0.1:4132".to_string(),
+        job_id: block_template.to_header_root().unwrap(),
+        lease_id: None,
+        share_difficulty: Some(u64::MAX / 5),
+        nonce: CurrentNetwork::genesis_block().header().nonce(),
+        block_template,
+        submitted_at: DAY + 10,
+    };
+    operator.add_found_block(1, found_block).unwrap();
+
+    // Check the first rollup starts from the epoch of the earliest share, and only counts the accepted shares.
+    assert_eq!(operator.roll_up_contributions(ContributionPeriod::Daily, 2 * DAY + 100).unwrap(), 2);
+    let first_day = operator.get_contribution_epoch(ContributionPeriod::Daily, DAY).unwrap().unwrap();
+    assert_eq!(first_day.rolled_up_to, 2 * DAY);
+    assert_eq!(first_day.contributions.len(), 2);
+    assert_eq!(first_day.contributions[&prover], contribution(2, 1));
+    assert_eq!(first_day.contributions[&other_prover], contribution(1, 0));
+    let second_day = operator.get_contribution_epoch(ContributionPeriod::Daily, 2 * DAY).unwrap().unwrap();
+    assert_eq!(second_day.rolled_up_to, 2 * DAY + 100);
+    assert_eq!(second_day.contributions[&prover], contribution(1, 0));
+    assert!(operator.get_contribution_epoch(ContributionPeriod::Daily, 0).unwrap().is_none());
+
+    // Check the next rollup resumes from the last one, and marks the epochs without shares as rolled up.
+    operator.add_share_records(vec![(prover, share_record(2 * DAY + 200, true))]).unwrap();
+    assert_eq!(operator.roll_up_contributions(ContributionPeriod::Daily, 3 * DAY + 1).unwrap(), 2);
+    assert_eq!(operator.roll_up_contributions(ContributionPeriod::Daily, 3 * DAY + 1).unwrap(), 0);
+    let second_day = operator.get_contribution_epoch(ContributionPeriod::Daily, 2 * DAY).unwrap().unwrap();
+    assert_eq!(second_day.rolled_up_to, 3 * DAY);
+    assert_eq!(second_day.contributions[&prover], contribution(2, 0));
+    let third_day = operator.get_contribution_epoch(ContributionPeriod::Daily, 3 * DAY).unwrap().unwrap();
+    assert_eq!(third_day.rolled_up_to, 3 * DAY + 1);
+    assert!(third_day.contributions.is_empty());
+
+    // Check the periods are rolled up independently.
+    assert_eq!(operator.roll_up_contributions(ContributionPeriod::Weekly, 3 * DAY + 1).unwrap(), 1);
+    let week = operator.get_contribution_epoch(ContributionPeriod::Weekly, -3 * DAY).unwrap().unwrap();
+    assert_eq!(week.contributions[&prover], contribution(4, 1));
+    assert_eq!(week.contributions[&other_prover], contribution(1, 0));
+
+    // Check the contributions outlive the share history they were rolled up from.
+    operator.prune_share_history(3 * DAY).unwrap();
+    let first_day = operator.get_contribution_epoch(ContributionPeriod::Daily, DAY).unwrap().unwrap();
+    assert_eq!(first_day.contributions[&prover], contribution(2, 1));
+
+    // Check the expired epochs are pruned, and the latest epoch of each period is retained to resume the rollups from.
+    assert_eq!(operator.prune_contributions(3 * DAY).unwrap(), 2);
+    assert!(operator.get_contribution_epoch(ContributionPeriod::Daily, DAY).unwrap().is_none());
+    assert!(operator.get_contribution_epoch(ContributionPeriod::Daily, 2 * DAY).unwrap().is_none());
+    assert!(operator.get_contribution_epoch(ContributionPeriod::Daily, 3 * DAY).unwrap().is_some());
+    assert!(operator.get_contribution_epoch(ContributionPeriod::Weekly, -3 * DAY).unwrap().is_some());
+    assert_eq!(operator.prune_contributions(10 * DAY).unwrap(), 0);
+    assert_eq!(operator.roll_up_contributions(ContributionPeriod::Daily, 3 * DAY + 1).unwrap(), 0);
+}
+
+#[test]
+fn test_operator_payouts() {
+    let rng = &mut thread_rng();
+
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let block_hash = CurrentNetwork::genesis_block().hash();
+    let prover = Account::<CurrentNetwork>::new(rng).address();
+
+    // Check a block without payouts is distinguished from a block with empty payouts.
+    assert_eq!(operator.get_payouts(1, block_hash).unwrap(), None);
+    operator.set_payouts(2, block_hash, &HashMap::new()).unwrap();
+    assert_eq!(operator.get_payouts(2, block_hash).unwrap(), Some(HashMap::new()));
+
+    // Check the payouts are retained for the given block.
+    let payouts: HashMap<_, _> = [(prover, 1_000)].into_iter().collect();
+    operator.set_payouts(1, block_hash, &payouts).unwrap();
+    assert_eq!(operator.get_payouts(1, block_hash).unwrap(), Some(payouts));
+}
+
+#[test]
+fn test_operator_payout_batches() {
+    let rng = &mut thread_rng();
+
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(temp_dir()).expect("Failed to initialize operator");
+    let genesis_block = CurrentNetwork::genesis_block();
+    let (block_hash, transaction_id) = (genesis_block.hash(), genesis_block.transactions()[0].transaction_id());
+    let mut batch = |amount| PayoutBatch {
+        payouts: vec![(Account::<CurrentNetwork>::new(rng).address(), amount)],
+        transaction_id: None,
+    };
+    let batches = vec![batch(1_000), batch(2_000)];
+
+    // Check the batches of a block are pending until paid.
+    assert!(operator.get_payout_batches(1, block_hash).unwrap().is_empty());
+    operator.set_payout_batches(1, block_hash, &batches).unwrap();
+    assert_eq!(operator.get_payout_batches(1, block_hash).unwrap(), batches);
+    assert_eq!(operator.get_pending_payout_batches().len(), 2);
+
+    // Check a paid batch is no longer pending, and a batch which does not exist cannot be paid.
+    operator.complete_payout_batch(1, block_hash, 0, transaction_id).unwrap();
+    let pending = operator.get_pending_payout_batches();
+    assert_eq!(pending, vec![(1, block_hash, 1, batches[1].clone())]);
+    assert!(operator.complete_payout_batch(1, block_hash, 0, transaction_id).is_ok());
+    assert!(operator.complete_payout_batch(1, block_hash, 2, transaction_id).is_err());
+
+    // Check the batches of a partially paid block cannot be replaced.
+    assert!(operator.set_payout_batches(1, block_hash, &batches).is_err());
+}
+
+#[test]
+fn test_operator_leases() {
+    let path = temp_dir();
+    let renter = Account::<CurrentNetwork>::new(&mut thread_rng()).address();
+    let lease = |id: u64| Lease {
+        id,
+        renter,
+        basis_points: 1_000,
+        created_at: 100,
+        expires_at: 200,
+        jobs: 0,
+        shares: 0,
+        blocks: vec![],
+    };
+
+    {
+        let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(&path).expect("Failed to initialize operator");
+        assert!(operator.to_leases().is_empty());
+
+        // Check the leases are stored in ascending order of their IDs, and updated in place.
+        operator.set_leases(&[lease(2), lease(1)]).unwrap();
+        let mut updated = lease(1);
+        updated.shares = 10;
+        updated.blocks.push((5, CurrentNetwork::genesis_block().hash()));
+        operator.set_leases(&[updated.clone()]).unwrap();
+        assert_eq!(operator.to_leases(), vec![updated.clone(), lease(2)]);
+    }
+
+    // Check the leases survive a restart of the operator.
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(&path).expect("Failed to reopen operator");
+    assert_eq!(operator.to_leases().len(), 2);
+    assert_eq!(operator.to_leases()[0].shares, 10);
+}
+
+#[test]
+fn test_operator_round_routes() {
+    let path = temp_dir();
+    let rng = &mut thread_rng();
+    let (prover_1, prover_2) = (Account::<CurrentNetwork>::new(rng).address(), Account::<CurrentNetwork>::new(rng).address());
+
+    let operator = OperatorState::<CurrentNetwork>::open_writer::<RocksDB, _>(&path).expect("Failed to initialize operator");
+    assert!(operator.get_round_routes(1).unwrap().is_empty());
+
+    // Check the routes of a round are updated in place, and a route given as `None` is removed.
+    let routes = [(prover_1, Some("private".to_string())), (prover_2, Some("farm".to_string()))];
+    operator.set_round_routes(1, &routes.into_iter().collect()).unwrap();
+    operator.set_round_routes(1, &[(prover_2, None)].into_iter().collect()).unwrap();
+    let routes = operator.get_round_routes(1).unwrap();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes.get(&prover_1).map(String::as_str), Some("private"));
+
+    // Check the routes of each round are kept apart.
+    assert!(operator.get_round_routes(2).unwrap().is_empty());
+}
+
+#[test]
+fn test_get_difficulty_target() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let address = Account::<CurrentNetwork>::new(rng).address();
+
+    // Check the difficulty target of a block template matches the expected difficulty target.
+    let block_template = ledger.get_block_template(address, true, &[], rng).expect("Failed to get a block template");
+    let expected_difficulty_target = ledger
+        .get_difficulty_target(block_template.block_timestamp(), block_template.block_height())
+        .unwrap();
+    assert_eq!(block_template.difficulty_target(), expected_difficulty_target);
+
+    // Check the difficulty target of a mined block matches the expected difficulty target.
+    let (block, _) = ledger.mine_next_block(address, true, &[], &terminator, rng).expect("Failed to mine");
+    assert_eq!(
+        block.difficulty_target(),
+        ledger.get_difficulty_target(block.timestamp(), block.height()).unwrap()
+    );
+}
+
+#[test]
+fn test_get_network_difficulty_estimate() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let address = Account::<CurrentNetwork>::new(rng).address();
+
+    // Check the estimate at genesis is based on the genesis block.
+    let estimate = ledger.get_network_difficulty_estimate().unwrap();
+    assert_eq!(estimate.block_height, 0);
+
+    // Check the estimate follows the latest block, excluding the genesis block.
+    let (block, _) = ledger.mine_next_block(address, true, &[], &terminator, rng).expect("Failed to mine");
+    ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+    let estimate = ledger.get_network_difficulty_estimate().unwrap();
+    assert_eq!(estimate.block_height, 1);
+    assert!((estimate.difficulty - u64::MAX as f64 / block.difficulty_target() as f64).abs() < 1e-6);
+    assert_eq!(estimate.block_interval_in_secs, CurrentNetwork::ALEO_BLOCK_TIME_IN_SECS as f64);
+}
+
+#[test]
+fn test_get_fee_estimate() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let address = Account::<CurrentNetwork>::new(rng).address();
+
+    // Check the estimate at genesis has no blocks to estimate from.
+    let estimate = ledger.get_fee_estimate(1, 1).unwrap();
+    assert_eq!(estimate.block_height, 0);
+    assert_eq!(estimate.number_of_blocks, 0);
+
+    // Check the window follows the added blocks, excluding their coinbase transactions.
+    for _ in 0..2 {
+        let (block, _) = ledger.mine_next_block(address, true, &[], &terminator, rng).expect("Failed to mine");
+        ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+    }
+    let estimate = ledger.get_fee_estimate(1, 1).unwrap();
+    assert_eq!(estimate.block_height, 2);
+    assert_eq!(estimate.number_of_blocks, 2);
+    assert_eq!(estimate.number_of_empty_blocks, 2);
+    assert_eq!(estimate.fee_per_byte, 1);
+
+    // Check the window drops the reverted blocks.
+    ledger.revert_to_block_height(1).expect("Failed to revert the ledger");
+    let estimate = ledger.get_fee_estimate(1, 1).unwrap();
+    assert_eq!(estimate.block_height, 1);
+    assert_eq!(estimate.number_of_blocks, 1);
+}
+
+#[test]
+fn test_get_network_stats() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let address = Account::<CurrentNetwork>::new(rng).address();
+
+    // Check the statistics at genesis are based on the genesis block.
+    let stats = ledger.get_network_stats(100).unwrap();
+    assert_eq!(stats.block_height, 0);
+    assert_eq!(stats.window, 1);
+
+    // Check the statistics follow the latest blocks, excluding the genesis block.
+    for _ in 0..2 {
+        let (block, _) = ledger.mine_next_block(address, true, &[], &terminator, rng).expect("Failed to mine");
+        ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+    }
+    let stats = ledger.get_network_stats(100).unwrap();
+    assert_eq!(stats.block_height, 2);
+    assert_eq!(stats.window, 1);
+    assert_eq!(stats.difficulty_target, ledger.latest_block_header().difficulty_target());
+    assert!(stats.proof_rate > 0.0);
+}
+
+#[test]
+fn test_latest_median_time_past() {
+    let rng = &mut thread_rng();
+    let terminator = AtomicBool::new(false);
+
+    // Initialize a new ledger.
+    let ledger = create_new_ledger::<CurrentNetwork, RocksDB>();
+    let address = Account::<CurrentNetwork>::new(rng).address();
+
+    // Check the median time past at genesis is the genesis timestamp.
+    assert_eq!(ledger.timestamp_policy(), TimestampPolicy::for_network::<CurrentNetwork>());
+    assert_eq!(ledger.latest_median_time_past().unwrap(), ledger.latest_block_timestamp());
+
+    // Check the median time past follows the latest blocks.
+    for _ in 0..2 {
+        let (block, _) = ledger.mine_next_block(address, true, &[], &terminator, rng).expect("Failed to mine");
+        ledger.add_next_block(&block).expect("Failed to add next block to ledger");
+    }
+    let mut timestamps: Vec<_> = ledger.get_block_headers(0, 2).unwrap().iter().map(|header| header.timestamp()).collect();
+    timestamps.sort_unstable();
+    assert_eq!(ledger.median_time_past_of(3).unwrap(), timestamps[1]);
+
+    // Check the median time past of the network, as testnet2 only considers the latest block.
+    let median_time_past = ledger.latest_median_time_past().unwrap();
+    assert_eq!(median_time_past, ledger.latest_block_timestamp());
+
+    // Check a block template is timestamped after the median time past.
+    let block_template = ledger.get_block_template(address, true, &[], rng).expect("Failed to get a block template");
+    assert!(block_template.block_timestamp() > median_time_past);
+}
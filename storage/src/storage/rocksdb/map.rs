@@ -35,6 +35,19 @@ pub enum MapId {
     Transactions,
     Transitions,
     Shares,
+    Rounds,
+    TaggedBlocks,
+    FoundBlocks,
+    Payouts,
+    AddressTransactions,
+    AddressIndex,
+    ShareHistory,
+    PayoutAddresses,
+    PayoutSchedules,
+    PayoutBatches,
+    Contributions,
+    Leases,
+    RoundRoutes,
     #[cfg(test)]
     Test,
 }
@@ -38,7 +38,7 @@ use std::{
     borrow::Borrow,
     collections::HashMap,
     convert::TryInto,
-    fs::File,
+    fs::{self, File},
     io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
     marker::PhantomData,
     path::Path,
@@ -56,45 +56,59 @@ pub struct RocksDB {
     is_read_only: bool,
 }
 
-impl Storage for RocksDB {
-    ///
-    /// Opens storage at the given `path` and `context`.
-    ///
-    fn open<P: AsRef<Path>>(path: P, context: u16, is_read_only: bool) -> Result<Self> {
-        let context = context.to_le_bytes().to_vec();
-
-        // Customize database options.
+impl RocksDB {
+    /// Returns the options of the database, shared by its primary and secondary instances.
+    fn options() -> rocksdb::Options {
         let mut options = rocksdb::Options::default();
         options.set_compression_type(rocksdb::DBCompressionType::Lz4);
 
         // Register the prefix length.
         let prefix_extractor = rocksdb::SliceTransform::create_fixed_prefix(PREFIX_LEN);
         options.set_prefix_extractor(prefix_extractor);
+        options
+    }
+}
 
-        let primary = path.as_ref().to_path_buf();
-        let rocksdb = match is_read_only {
-            true => {
-                // Construct the directory paths.
-                let reader = path.as_ref().join("reader");
-                // Open a secondary reader for the primary rocksdb.
-                let rocksdb = rocksdb::DB::open_as_secondary(&options, &primary, &reader)?;
-                Arc::new(rocksdb)
-            }
-            false => {
-                options.increase_parallelism(2);
-                options.create_if_missing(true);
-                Arc::new(rocksdb::DB::open(&options, &primary)?)
-            }
-        };
+impl Storage for RocksDB {
+    ///
+    /// Opens storage at the given `path` and `context`.
+    ///
+    fn open<P: AsRef<Path>>(path: P, context: u16, is_read_only: bool) -> Result<Self> {
+        if is_read_only {
+            // Open a secondary reader for the primary rocksdb.
+            let reader = path.as_ref().join("reader");
+            return Self::open_secondary(path.as_ref(), &reader, context);
+        }
+
+        let mut options = Self::options();
+        options.increase_parallelism(2);
+        options.create_if_missing(true);
+        let rocksdb = Arc::new(rocksdb::DB::open(&options, path.as_ref())?);
 
         Ok(RocksDB {
             rocksdb,
-            context,
+            context: context.to_le_bytes().to_vec(),
             batches: Default::default(),
             is_read_only,
         })
     }
 
+    ///
+    /// Opens a read-only secondary instance of the storage at the given `path` and `context`,
+    /// which keeps its own state at the given `secondary_path`.
+    ///
+    fn open_secondary<P: AsRef<Path>>(path: P, secondary_path: P, context: u16) -> Result<Self> {
+        fs::create_dir_all(secondary_path.as_ref())?;
+        let rocksdb = rocksdb::DB::open_as_secondary(&Self::options(), path.as_ref(), secondary_path.as_ref())?;
+
+        Ok(RocksDB {
+            rocksdb: Arc::new(rocksdb),
+            context: context.to_le_bytes().to_vec(),
+            batches: Default::default(),
+            is_read_only: true,
+        })
+    }
+
     ///
     /// Opens a map with the given `context` from storage.
     ///
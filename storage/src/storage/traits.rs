@@ -28,6 +28,14 @@ pub trait Storage {
     where
         Self: Sized;
 
+    ///
+    /// Opens a read-only secondary instance of the storage at the given `path` and `context`,
+    /// which keeps its own state at the given `secondary_path`.
+    ///
+    fn open_secondary<P: AsRef<Path>>(path: P, secondary_path: P, context: u16) -> Result<Self>
+    where
+        Self: Sized;
+
     ///
     /// Opens a map with the given `context` from storage.
     ///